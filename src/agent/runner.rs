@@ -5,14 +5,52 @@
 
 use std::path::PathBuf;
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 
 use tokio::sync::{broadcast, mpsc};
 
 use crate::config::NotificationConfig;
 use crate::hooks::HookEvent;
+use crate::notification::coalesce::{BulkOperations, NoticeKind, Notifier};
 
 use super::{analyzer_host, consumers, guard, hot_brain, memory, scheduler, Action, ProgressEntry, System, World};
 
+/// Where the agent pipeline keeps one profile's files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileDirs {
+    /// `~/.agent-hand/profiles/{profile}/progress/`
+    pub progress_dir: PathBuf,
+    /// `~/.agent-hand/profiles/{profile}/agent-runtime/`
+    pub runtime_dir: PathBuf,
+}
+
+impl ProfileDirs {
+    pub fn for_profile(profile: &str) -> Self {
+        let base = crate::session::Storage::get_agent_hand_dir()
+            .unwrap_or_else(|_| PathBuf::from(".agent-hand"))
+            .join("profiles")
+            .join(profile);
+        Self {
+            progress_dir: base.join("progress"),
+            runtime_dir: base.join("agent-runtime"),
+        }
+    }
+}
+
+/// Profile dirs shared with the TUI, which swaps them on a profile switch.
+pub type SharedProfileDirs = Arc<RwLock<ProfileDirs>>;
+
+/// Tmux server of the profile whose progress files live in `progress_dir`
+/// (`…/profiles/{profile}/progress/`).
+fn server_name_for_progress_dir(progress_dir: &std::path::Path) -> String {
+    progress_dir
+        .parent() // …/profiles/{profile}
+        .and_then(|p| p.file_name())
+        .and_then(|n| n.to_str())
+        .map(crate::tmux::server_name_for_profile)
+        .unwrap_or_else(|| crate::tmux::server_name_for_profile("default"))
+}
+
 /// Runs all registered Systems in a single tokio task.
 /// Replaces multiple independent background tasks (e.g. sound_task).
 pub struct SystemRunner {
@@ -77,12 +115,18 @@ impl SystemRunner {
 /// Each Action variant maps to a concrete side effect.
 pub struct ActionExecutor {
     notification_manager: crate::notification::NotificationManager,
+    /// Coalesces per-session sounds so a burst of changes plays once.
+    notifier: Notifier,
+    /// Set while the TUI runs a bulk operation; sounds wait until it ends.
+    bulk_operations: Option<BulkOperations>,
     /// Shared config for hot-reload (settings dialog writes, executor reads).
     shared_config: Arc<RwLock<NotificationConfig>>,
     /// Directory for progress files: `~/.agent-hand/profiles/default/progress/`
     progress_dir: PathBuf,
     /// Directory for audit JSONL files: `~/.agent-hand/profiles/default/agent-runtime/`
     runtime_dir: PathBuf,
+    /// The TUI's current profile dirs; followed before each action.
+    profile_dirs: Option<SharedProfileDirs>,
     /// Context delivery transport (file-based by default, swappable for ACPX).
     delivery: Box<dyn super::delivery::ContextDelivery>,
     /// Tmux server name (e.g. "agenthand_default") for capture-pane commands.
@@ -114,19 +158,19 @@ impl ActionExecutor {
             progress_dir.clone(),
             runtime_dir.clone(),
         ));
-        // Extract profile name from progress_dir (…/profiles/{profile}/progress/)
-        let tmux_server_name = progress_dir
-            .parent() // …/profiles/{profile}
-            .and_then(|p| p.file_name())
-            .and_then(|n| n.to_str())
-            .map(|profile| crate::tmux::server_name_for_profile(profile))
-            .unwrap_or_else(|| crate::tmux::server_name_for_profile("default"));
+        let tmux_server_name = server_name_for_progress_dir(&progress_dir);
 
         Self {
             notification_manager: crate::notification::NotificationManager::new(&initial_config),
+            notifier: Notifier::new(
+                Duration::from_millis(initial_config.coalesce_window_ms),
+                initial_config.max_per_minute,
+            ),
+            bulk_operations: None,
             shared_config,
             progress_dir,
             runtime_dir,
+            profile_dirs: None,
             delivery,
             tmux_server_name,
             chat_response_tx: None,
@@ -156,14 +200,78 @@ impl ActionExecutor {
         self.chat_response_tx = Some(tx);
     }
 
+    /// Share the TUI's bulk-operation flag.
+    pub fn set_bulk_operations(&mut self, bulk: BulkOperations) {
+        self.bulk_operations = Some(bulk);
+    }
+
+    /// Follow the TUI's profile: progress, audit and context files go to
+    /// whichever profile it shows.
+    pub fn set_profile_dirs(&mut self, dirs: SharedProfileDirs) {
+        self.profile_dirs = Some(dirs);
+    }
+
+    /// Move to the shared profile dirs if the TUI switched profile since the
+    /// last action.
+    fn follow_profile_dirs(&mut self) {
+        let Some(shared) = &self.profile_dirs else {
+            return;
+        };
+        let dirs = shared.read().unwrap_or_else(|e| e.into_inner()).clone();
+        if dirs.progress_dir == self.progress_dir && dirs.runtime_dir == self.runtime_dir {
+            return;
+        }
+        self.delivery = Box::new(super::delivery::FileContextDelivery::new(
+            dirs.progress_dir.clone(),
+            dirs.runtime_dir.clone(),
+        ));
+        self.tmux_server_name = server_name_for_progress_dir(&dirs.progress_dir);
+        self.progress_dir = dirs.progress_dir;
+        self.runtime_dir = dirs.runtime_dir;
+        // The canvas plugin is loaded from the runtime dir.
+        #[cfg(feature = "wasm")]
+        {
+            self.wasm_canvas_host = None;
+            self.wasm_plugin_mtime = None;
+        }
+    }
+
     /// Run the action execution loop.
     pub async fn run(mut self, mut rx: mpsc::UnboundedReceiver<Action>) {
-        while let Some(action) = rx.recv().await {
-            // Hot-reload notification pack on each action
-            if let Ok(cfg) = self.shared_config.read() {
-                self.notification_manager.reload_pack(&cfg);
+        let mut flush = tokio::time::interval(Duration::from_millis(250));
+        flush.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                action = rx.recv() => {
+                    let Some(action) = action else { break };
+                    // Hot-reload notification pack on each action
+                    if let Ok(cfg) = self.shared_config.read() {
+                        self.notification_manager.reload_pack(&cfg);
+                    }
+                    self.follow_profile_dirs();
+                    self.execute(action).await;
+                    self.flush_sounds();
+                }
+                _ = flush.tick() => self.flush_sounds(),
             }
-            self.execute(action).await;
+        }
+    }
+
+    /// Play whatever the notifier has released: one sound per summary.
+    fn flush_sounds(&mut self) {
+        let now = Instant::now();
+        let bulk = self
+            .bulk_operations
+            .as_ref()
+            .is_some_and(|b| b.is_active(now));
+        for summary in self.notifier.poll(now, bulk) {
+            let key = match summary.sessions.as_slice() {
+                [only] => only.as_str(),
+                _ => "coalesced",
+            };
+            tracing::debug!("[sound] {}", summary.message());
+            self.notification_manager
+                .play_category(key, summary.kind.category());
         }
     }
 
@@ -172,10 +280,12 @@ impl ActionExecutor {
             Action::PlaySound {
                 category,
                 session_key,
-            } => {
-                self.notification_manager
-                    .play_category(&session_key, &category);
-            }
+            } => match NoticeKind::from_category(&category) {
+                Some(kind) => self.notifier.push(Instant::now(), &session_key, kind),
+                None => self
+                    .notification_manager
+                    .play_category(&session_key, &category),
+            },
             Action::WriteProgress {
                 session_key,
                 entry,
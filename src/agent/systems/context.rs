@@ -16,6 +16,7 @@ use super::super::guard::{
     InjectionScope, Proposal, ProposalKind, ResponseLevel, RiskLevel,
     SidecarFeedback,
 };
+use super::super::runner::SharedProfileDirs;
 use super::super::{Action, System, World};
 
 /// Read agent-written sidecar feedback for a session.
@@ -38,6 +39,8 @@ pub struct ContextGuardSystem {
     injected_this_event: HashSet<String>,
     /// Runtime directory for sidecar and audit files.
     runtime_dir: PathBuf,
+    /// The TUI's current profile dirs; overrides `runtime_dir` when set.
+    profile_dirs: Option<SharedProfileDirs>,
 }
 
 impl ContextGuardSystem {
@@ -47,6 +50,23 @@ impl ContextGuardSystem {
             last_injection_ts: HashMap::new(),
             injected_this_event: HashSet::new(),
             runtime_dir,
+            profile_dirs: None,
+        }
+    }
+
+    /// Read sidecars from whichever profile the TUI shows.
+    pub fn set_profile_dirs(&mut self, dirs: SharedProfileDirs) {
+        self.profile_dirs = Some(dirs);
+    }
+
+    fn runtime_dir(&self) -> PathBuf {
+        match &self.profile_dirs {
+            Some(dirs) => dirs
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .runtime_dir
+                .clone(),
+            None => self.runtime_dir.clone(),
         }
     }
 
@@ -215,7 +235,7 @@ impl System for ContextGuardSystem {
 
                 // Read sidecar feedback written by the agent (if any).
                 // Missing file → SidecarFeedback::default() → same as pre-sidecar behavior.
-                let sidecar = read_sidecar(&self.runtime_dir, &event.tmux_session);
+                let sidecar = read_sidecar(&self.runtime_dir(), &event.tmux_session);
                 let urgency_level = guard::parse_urgency(&sidecar.urgency);
                 let recommended_response_level = match &urgency_level {
                     RiskLevel::Critical | RiskLevel::High => ResponseLevel::L3CrossSessionInject,
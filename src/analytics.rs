@@ -2,7 +2,7 @@
 //!
 //! Records events like:
 //! - Session enter (attach)
-//! - Session exit (detach via Ctrl+Q)
+//! - Session exit (back in the TUI after attaching)
 //! - Switcher usage
 //!
 //! Enable in config.json: { "analytics": { "enabled": true } }
@@ -22,7 +22,7 @@ use crate::session::Storage;
 pub enum EventType {
     /// User entered/attached to a session
     Enter,
-    /// User exited/detached from a session (Ctrl+Q)
+    /// User returned to the TUI from a session
     Exit,
     /// User used the switcher popup
     Switch,
@@ -89,13 +89,8 @@ impl ActivityTracker {
 
     /// Get the log file path for today (JSONL format)
     fn log_path(&self) -> Result<PathBuf> {
-        let base = Storage::get_agent_hand_dir()?;
         let date = Utc::now().format("%Y-%m-%d").to_string();
-        Ok(base
-            .join("profiles")
-            .join(&self.profile)
-            .join("analytics")
-            .join(format!("{}.jsonl", date)))
+        log_path_for(&self.profile, &date)
     }
 
     /// Record a session enter event
@@ -217,17 +212,10 @@ impl ActivityTracker {
             });
         }
 
-        // Read JSONL format (one event per line)
         let content = fs::read_to_string(&path).await?;
-        let events: Vec<ActivityEvent> = content
-            .lines()
-            .filter(|line| !line.trim().is_empty())
-            .filter_map(|line| serde_json::from_str(line).ok())
-            .collect();
-
         Ok(DailyLog {
             date: Utc::now().format("%Y-%m-%d").to_string(),
-            events,
+            events: parse_events(&content),
         })
     }
 
@@ -238,6 +226,82 @@ impl ActivityTracker {
     }
 }
 
+/// Daily log file for `profile` on `date` (YYYY-MM-DD).
+fn log_path_for(profile: &str, date: &str) -> Result<PathBuf> {
+    let base = Storage::get_agent_hand_dir()?;
+    Ok(base
+        .join("profiles")
+        .join(profile)
+        .join("analytics")
+        .join(format!("{}.jsonl", date)))
+}
+
+/// Read JSONL format (one event per line); unparseable lines are skipped.
+fn parse_events(content: &str) -> Vec<ActivityEvent> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Logs for the last `days` days (today included), oldest first. Days
+/// without a log are skipped. Reads whatever exists, whether or not
+/// analytics is currently enabled.
+pub async fn load_recent_logs(profile: &str, days: u32) -> Result<Vec<DailyLog>> {
+    let today = Utc::now().date_naive();
+    let mut logs = Vec::new();
+    for back in (0..days.max(1)).rev() {
+        let date = (today - chrono::Duration::days(back as i64))
+            .format("%Y-%m-%d")
+            .to_string();
+        let path = log_path_for(profile, &date)?;
+        let Ok(content) = fs::read_to_string(&path).await else {
+            continue;
+        };
+        logs.push(DailyLog {
+            date,
+            events: parse_events(&content),
+        });
+    }
+    Ok(logs)
+}
+
+/// Activity of one session over a set of logs.
+#[derive(Debug, Clone)]
+pub struct SessionActivity {
+    pub session_id: String,
+    /// Title at the session's most recent event.
+    pub session_name: String,
+    pub summary: ActivitySummary,
+}
+
+/// Per-session summaries across `logs`, most attached time first.
+pub fn summarize_by_session(logs: &[DailyLog]) -> Vec<SessionActivity> {
+    let mut by_id: std::collections::HashMap<String, SessionActivity> =
+        std::collections::HashMap::new();
+    for event in logs.iter().flat_map(|log| &log.events) {
+        let entry = by_id
+            .entry(event.session_id.clone())
+            .or_insert_with(|| SessionActivity {
+                session_id: event.session_id.clone(),
+                session_name: event.session_name.clone(),
+                summary: ActivitySummary::default(),
+            });
+        entry.session_name = event.session_name.clone();
+        entry.summary.add_event(event);
+    }
+    let mut sessions: Vec<SessionActivity> = by_id.into_values().collect();
+    sessions.sort_by(|a, b| {
+        b.summary
+            .total_duration_secs
+            .cmp(&a.summary.total_duration_secs)
+            .then(b.summary.total_enters.cmp(&a.summary.total_enters))
+            .then(a.session_name.cmp(&b.session_name))
+    });
+    sessions
+}
+
 /// Summary of activity for a time period
 #[derive(Debug, Clone, Default)]
 pub struct ActivitySummary {
@@ -251,31 +315,33 @@ pub struct ActivitySummary {
 impl ActivitySummary {
     pub fn from_log(log: &DailyLog) -> Self {
         let mut summary = Self::default();
-        let mut sessions = std::collections::HashSet::new();
-
         for event in &log.events {
-            sessions.insert(event.session_name.clone());
-            match event.event_type {
-                EventType::Enter => summary.total_enters += 1,
-                EventType::Exit => {
-                    summary.total_exits += 1;
-                    if let Some(d) = event.duration_secs {
-                        summary.total_duration_secs += d;
-                    }
+            summary.add_event(event);
+        }
+        summary
+    }
+
+    /// Count one event into the totals.
+    pub fn add_event(&mut self, event: &ActivityEvent) {
+        if let Err(i) = self.sessions_touched.binary_search(&event.session_name) {
+            self.sessions_touched.insert(i, event.session_name.clone());
+        }
+        match event.event_type {
+            EventType::Enter => self.total_enters += 1,
+            EventType::Exit => {
+                self.total_exits += 1;
+                if let Some(d) = event.duration_secs {
+                    self.total_duration_secs += d;
                 }
-                EventType::Switch => summary.total_switches += 1,
-                // Premium events don't affect basic summary counters
-                EventType::ShareStart
-                | EventType::ShareStop
-                | EventType::RelationshipCreate
-                | EventType::RelationshipDelete
-                | EventType::ContextCapture => {}
             }
+            EventType::Switch => self.total_switches += 1,
+            // Premium events don't affect basic summary counters
+            EventType::ShareStart
+            | EventType::ShareStop
+            | EventType::RelationshipCreate
+            | EventType::RelationshipDelete
+            | EventType::ContextCapture => {}
         }
-
-        summary.sessions_touched = sessions.into_iter().collect();
-        summary.sessions_touched.sort();
-        summary
     }
 
     /// Format duration as human-readable string
@@ -289,3 +355,65 @@ impl ActivitySummary {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: EventType, id: &str, name: &str, duration: Option<u64>) -> ActivityEvent {
+        ActivityEvent {
+            timestamp: Utc::now(),
+            event_type: kind,
+            session_id: id.to_string(),
+            session_name: name.to_string(),
+            duration_secs: duration,
+        }
+    }
+
+    #[test]
+    fn summarizes_per_session_across_days() {
+        let day1 = DailyLog {
+            date: "2026-01-01".into(),
+            events: vec![
+                event(EventType::Enter, "a", "api", None),
+                event(EventType::Exit, "a", "api", Some(600)),
+                event(EventType::Switch, "b", "web", None),
+            ],
+        };
+        let day2 = DailyLog {
+            date: "2026-01-02".into(),
+            events: vec![
+                event(EventType::Enter, "a", "api v2", None),
+                event(EventType::Exit, "a", "api v2", Some(1200)),
+                event(EventType::Enter, "b", "web", None),
+                event(EventType::Exit, "b", "web", Some(60)),
+            ],
+        };
+
+        let sessions = summarize_by_session(&[day1, day2]);
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].session_name, "api v2");
+        assert_eq!(sessions[0].summary.total_duration_secs, 1800);
+        assert_eq!(sessions[0].summary.total_enters, 2);
+        assert_eq!(sessions[0].summary.format_duration(), "30m");
+        assert_eq!(sessions[1].session_id, "b");
+        assert_eq!(sessions[1].summary.total_switches, 1);
+        assert_eq!(sessions[1].summary.total_enters, 1);
+    }
+
+    #[test]
+    fn from_log_lists_touched_sessions_sorted_once() {
+        let log = DailyLog {
+            date: "2026-01-01".into(),
+            events: vec![
+                event(EventType::Enter, "b", "web", None),
+                event(EventType::Enter, "a", "api", None),
+                event(EventType::Exit, "b", "web", Some(5)),
+            ],
+        };
+        let summary = ActivitySummary::from_log(&log);
+        assert_eq!(summary.sessions_touched, vec!["api", "web"]);
+        assert_eq!(summary.total_enters, 2);
+        assert_eq!(summary.total_exits, 1);
+    }
+}
@@ -95,6 +95,7 @@ impl AuthToken {
     /// Query the server for current features and update the local token.
     /// Returns Ok(true) if features changed, Ok(false) if unchanged.
     pub async fn refresh(&mut self) -> Result<bool> {
+        crate::network::ensure_online()?;
         let client = reqwest::Client::new();
         let resp = client
             .get(format!("{AUTH_SERVER}/auth/status"))
@@ -135,6 +136,7 @@ impl AuthToken {
 
     /// Send a heartbeat to register/refresh this device. Returns slot status.
     pub async fn heartbeat(&self) -> Result<HeartbeatResponse> {
+        crate::network::ensure_online()?;
         let device = crate::device::DeviceInfo::generate();
         let client = reqwest::Client::new();
         let resp = client
@@ -177,6 +179,7 @@ impl AuthToken {
 
     /// Fetch the user's registered devices from the server.
     pub async fn list_devices(&self) -> Result<DevicesListResponse> {
+        crate::network::ensure_online()?;
         let client = reqwest::Client::new();
         let resp = client
             .get(format!("{AUTH_SERVER}/api/devices"))
@@ -197,6 +200,7 @@ impl AuthToken {
 
     /// Remove (unbind) a device by its full device_id.
     pub async fn remove_device(&self, device_id: &str) -> Result<()> {
+        crate::network::ensure_online()?;
         let client = reqwest::Client::new();
         let resp = client
             .delete(format!("{AUTH_SERVER}/api/devices/{device_id}"))
@@ -0,0 +1,293 @@
+//! Carry a Claude conversation over into a forked session.
+//!
+//! Two mechanisms, preferred in this order:
+//! - `claude --resume <id> --fork-session`, when the installed CLI has it;
+//! - copying the conversation JSONL to a new file under a fresh id and
+//!   resuming that copy.
+//!
+//! The parent's file is only ever read.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::error::{Error, Result};
+use crate::session::Instance;
+use crate::tmux::Tool;
+
+/// What the installed `claude` CLI supports, from its `--help` output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ClaudeCapabilities {
+    pub fork_session: bool,
+}
+
+pub fn parse_help(help: &str) -> ClaudeCapabilities {
+    ClaudeCapabilities {
+        fork_session: help.contains("--fork-session"),
+    }
+}
+
+/// How long `claude --help` may take before the CLI counts as unusable.
+const HELP_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Capabilities of the `claude` on PATH, probed once per binary (a
+/// `claude` that later resolves elsewhere is probed again). `None` when it
+/// cannot be run or does not answer within [`HELP_TIMEOUT`].
+pub async fn detect_capabilities() -> Option<ClaudeCapabilities> {
+    static CAPS: OnceLock<Mutex<HashMap<PathBuf, Option<ClaudeCapabilities>>>> = OnceLock::new();
+    let cache = CAPS.get_or_init(Default::default);
+
+    let binary = crate::claude::mcp::find_command("claude", std::env::var_os("PATH").as_deref())?;
+    if let Some(caps) = cache.lock().unwrap_or_else(|e| e.into_inner()).get(&binary) {
+        return *caps;
+    }
+
+    let probe = tokio::process::Command::new(&binary)
+        .arg("--help")
+        .stdin(std::process::Stdio::null())
+        .kill_on_drop(true)
+        .output();
+    let caps = match tokio::time::timeout(HELP_TIMEOUT, probe).await {
+        Ok(Ok(output)) => {
+            let mut help = String::from_utf8_lossy(&output.stdout).into_owned();
+            help.push_str(&String::from_utf8_lossy(&output.stderr));
+            Some(parse_help(&help))
+        }
+        _ => None,
+    };
+    cache
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(binary, caps);
+    caps
+}
+
+/// How the fork will get the parent's context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContextCopy {
+    /// Launch with `--resume <parent id> --fork-session`.
+    ForkFlag { parent_id: String },
+    /// Copy this conversation file and resume the copy.
+    FileCopy { parent_id: String, source: PathBuf },
+}
+
+/// Decide whether (and how) a fork of `parent` can copy its conversation.
+/// The error is the reason to show next to the disabled toggle.
+pub fn plan_context_copy(
+    parent: &Instance,
+    caps: Option<ClaudeCapabilities>,
+    find: impl Fn(&Path, &str) -> Option<PathBuf>,
+) -> std::result::Result<ContextCopy, String> {
+    if parent.tool != Tool::Claude {
+        return Err(format!("unsupported for {}", parent.tool));
+    }
+    let Some(parent_id) = parent.claude_session_id.clone() else {
+        return Err("no Claude conversation detected yet".to_string());
+    };
+
+    if caps.is_some_and(|c| c.fork_session) {
+        return Ok(ContextCopy::ForkFlag { parent_id });
+    }
+    match find(&parent.project_path, &parent_id) {
+        Some(source) => Ok(ContextCopy::FileCopy { parent_id, source }),
+        None if caps.is_none() => Err("claude CLI not found".to_string()),
+        None => Err("conversation file not found".to_string()),
+    }
+}
+
+/// Locate `<session_id>.jsonl`: first in the project's own directory, then
+/// in any project directory (the parent may have moved).
+pub fn find_conversation(projects_dir: &Path, project_path: &Path, session_id: &str) -> Option<PathBuf> {
+    let file_name = format!("{session_id}.jsonl");
+
+    if let Some(dir) = crate::tmux::session_id_scanner::derive_claude_project_dir(project_path) {
+        let candidate = projects_dir.join(dir.file_name()?).join(&file_name);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+    }
+
+    std::fs::read_dir(projects_dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path().join(&file_name))
+        .find(|p| p.is_file())
+}
+
+pub fn claude_projects_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".claude").join("projects"))
+}
+
+/// Rewrite `sessionId` fields equal to `old_id`. Lines that are not JSON
+/// objects pass through unchanged.
+pub fn rewrite_session_id(line: &str, old_id: &str, new_id: &str) -> String {
+    let Ok(mut value) = serde_json::from_str::<serde_json::Value>(line) else {
+        return line.to_string();
+    };
+    match value.get_mut("sessionId") {
+        Some(id) if id.as_str() == Some(old_id) => {
+            *id = serde_json::Value::String(new_id.to_string());
+            value.to_string()
+        }
+        _ => line.to_string(),
+    }
+}
+
+/// Copy `source` into `target_dir` as `<new_id>.jsonl`, rewriting session
+/// ids. `target_dir` is the Claude project directory of the fork's working
+/// directory, since Claude looks conversations up by cwd. Refuses a file
+/// whose first line is not JSON, and never overwrites.
+pub fn copy_conversation(
+    source: &Path,
+    target_dir: &Path,
+    old_id: &str,
+    new_id: &str,
+) -> Result<PathBuf> {
+    let content = std::fs::read_to_string(source)?;
+    let first = content.lines().next().unwrap_or("");
+    if serde_json::from_str::<serde_json::Value>(first).is_err() {
+        return Err(Error::InvalidInput(format!(
+            "{} does not look like a Claude conversation",
+            source.display()
+        )));
+    }
+
+    let target = target_dir.join(format!("{new_id}.jsonl"));
+    if target.exists() {
+        return Err(Error::InvalidInput(format!("{} already exists", target.display())));
+    }
+
+    let mut out = String::with_capacity(content.len());
+    for line in content.lines() {
+        out.push_str(&rewrite_session_id(line, old_id, new_id));
+        out.push('\n');
+    }
+
+    std::fs::create_dir_all(target_dir)?;
+    let tmp = target.with_extension("jsonl.tmp");
+    std::fs::write(&tmp, out)?;
+    std::fs::rename(&tmp, &target)?;
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARENT: &str = "11111111-2222-3333-4444-555555555555";
+    const CHILD: &str = "66666666-7777-8888-9999-000000000000";
+
+    fn claude_parent() -> Instance {
+        let mut inst = Instance::with_tool(
+            "p".into(),
+            PathBuf::from("/work/app"),
+            Tool::Claude,
+        );
+        inst.claude_session_id = Some(PARENT.to_string());
+        inst
+    }
+
+    #[test]
+    fn help_parsing_detects_fork_flag() {
+        let new = "Options:\n  -r, --resume [sessionId]  Resume\n  --fork-session  When resuming, create a new session ID\n";
+        let old = "Options:\n  -r, --resume [sessionId]  Resume\n";
+        assert!(parse_help(new).fork_session);
+        assert!(!parse_help(old).fork_session);
+    }
+
+    #[test]
+    fn plan_prefers_fork_flag_then_file_copy() {
+        let parent = claude_parent();
+        let found = |_: &Path, _: &str| Some(PathBuf::from("/x.jsonl"));
+        let missing = |_: &Path, _: &str| None;
+
+        let with_flag = Some(ClaudeCapabilities { fork_session: true });
+        assert!(matches!(
+            plan_context_copy(&parent, with_flag, missing),
+            Ok(ContextCopy::ForkFlag { .. })
+        ));
+
+        let old = Some(ClaudeCapabilities::default());
+        assert!(matches!(
+            plan_context_copy(&parent, old, found),
+            Ok(ContextCopy::FileCopy { .. })
+        ));
+        assert!(plan_context_copy(&parent, old, missing).is_err());
+    }
+
+    #[test]
+    fn plan_rejects_other_tools_and_undetected_sessions() {
+        let mut parent = claude_parent();
+        parent.claude_session_id = None;
+        let err = plan_context_copy(&parent, None, |_: &Path, _: &str| None).unwrap_err();
+        assert!(err.contains("no Claude conversation"));
+
+        let codex = Instance::with_tool("c".into(), PathBuf::from("/w"), Tool::Codex);
+        let err = plan_context_copy(&codex, None, |_: &Path, _: &str| None).unwrap_err();
+        assert!(err.starts_with("unsupported for"));
+    }
+
+    #[test]
+    fn rewrite_touches_only_matching_ids() {
+        let line = format!(r#"{{"type":"user","sessionId":"{PARENT}"}}"#);
+        let out = rewrite_session_id(&line, PARENT, CHILD);
+        assert!(out.contains(CHILD) && !out.contains(PARENT));
+
+        let other = r#"{"type":"user","sessionId":"someone-else"}"#;
+        assert_eq!(rewrite_session_id(other, PARENT, CHILD), other);
+        assert_eq!(rewrite_session_id("not json", PARENT, CHILD), "not json");
+    }
+
+    #[test]
+    fn copy_writes_new_file_and_leaves_parent_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join(format!("{PARENT}.jsonl"));
+        let original = format!(
+            "{{\"sessionId\":\"{PARENT}\",\"n\":1}}\n{{\"sessionId\":\"{PARENT}\",\"n\":2}}\n"
+        );
+        std::fs::write(&source, &original).unwrap();
+
+        let target = copy_conversation(&source, dir.path(), PARENT, CHILD).unwrap();
+        assert_eq!(target.file_name().unwrap().to_str().unwrap(), format!("{CHILD}.jsonl"));
+        assert_eq!(std::fs::read_to_string(&source).unwrap(), original);
+        let copied = std::fs::read_to_string(&target).unwrap();
+        assert_eq!(copied.matches(CHILD).count(), 2);
+
+        // A second copy to the same id must not overwrite.
+        assert!(copy_conversation(&source, dir.path(), PARENT, CHILD).is_err());
+    }
+
+    #[test]
+    fn copy_lands_in_the_forks_project_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("-work-app").join(format!("{PARENT}.jsonl"));
+        std::fs::create_dir_all(source.parent().unwrap()).unwrap();
+        std::fs::write(&source, format!("{{\"sessionId\":\"{PARENT}\"}}\n")).unwrap();
+
+        let worktree = dir.path().join("-work-app-fix");
+        let target = copy_conversation(&source, &worktree, PARENT, CHILD).unwrap();
+        assert_eq!(target, worktree.join(format!("{CHILD}.jsonl")));
+        assert!(!source.with_file_name(format!("{CHILD}.jsonl")).exists());
+    }
+
+    #[test]
+    fn copy_rejects_non_conversation_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("bad.jsonl");
+        std::fs::write(&source, "garbage\n").unwrap();
+        assert!(copy_conversation(&source, dir.path(), PARENT, CHILD).is_err());
+    }
+
+    #[test]
+    fn find_falls_back_to_other_project_dirs() {
+        let dir = tempfile::tempdir().unwrap();
+        let moved = dir.path().join("-old-location");
+        std::fs::create_dir_all(&moved).unwrap();
+        std::fs::write(moved.join(format!("{PARENT}.jsonl")), "{}\n").unwrap();
+
+        let found = find_conversation(dir.path(), Path::new("/work/app"), PARENT).unwrap();
+        assert_eq!(found, moved.join(format!("{PARENT}.jsonl")));
+        assert!(find_conversation(dir.path(), Path::new("/work/app"), CHILD).is_none());
+    }
+}
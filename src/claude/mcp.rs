@@ -0,0 +1,587 @@
+//! MCP server discovery across config sources, and per-session configs.
+//!
+//! Server definitions are kept as raw JSON so a server found in one file can
+//! be copied into another exactly as written.
+//!
+//! A project's `.mcp.json` is shared by every session in that directory, so
+//! a session's own set is written to a file of its own instead and passed
+//! to tools that take one on the command line (see [`McpScope`]).
+
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+
+use crate::error::{Error, Result};
+use crate::session::Storage;
+use crate::tmux::Tool;
+
+pub type McpServerMap = BTreeMap<String, Value>;
+
+/// Where an available MCP server definition came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum McpSource {
+    /// agent-hand's global pool file.
+    Pool,
+    /// Claude's user-level config (`~/.claude.json`).
+    Claude,
+    /// Another session's project `.mcp.json`, labelled by directory name.
+    Project(String),
+}
+
+impl McpSource {
+    /// Short tag shown next to the server name, e.g. `[proj:api]`.
+    pub fn tag(&self) -> String {
+        match self {
+            McpSource::Pool => "[pool]".to_string(),
+            McpSource::Claude => "[claude]".to_string(),
+            McpSource::Project(label) => format!("[proj:{label}]"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct McpCandidate {
+    pub name: String,
+    pub source: McpSource,
+    pub config: Value,
+}
+
+/// Extract the `mcpServers` object from a config document.
+///
+/// Both `.mcp.json` and `~/.claude.json` keep servers under this key. A
+/// missing key yields an empty map; a key of the wrong shape is an error so
+/// format drift is noticed instead of silently hiding servers.
+pub fn parse_server_map(json: &str) -> Result<McpServerMap> {
+    server_map(&serde_json::from_str(json)?)
+}
+
+/// [`parse_server_map`] for a document that is already parsed.
+pub fn server_map(doc: &Value) -> Result<McpServerMap> {
+    let Some(servers) = doc.get("mcpServers") else {
+        return Ok(McpServerMap::new());
+    };
+    let Some(obj) = servers.as_object() else {
+        return Err(Error::config("mcpServers is not an object"));
+    };
+
+    let mut out = McpServerMap::new();
+    for (name, cfg) in obj {
+        if !cfg.is_object() {
+            return Err(Error::config(format!("mcpServers.{name} is not an object")));
+        }
+        out.insert(name.clone(), cfg.clone());
+    }
+    Ok(out)
+}
+
+/// Parse the user-level servers from Claude's `~/.claude.json`.
+///
+/// Only the top-level `mcpServers` are user-scoped; the per-project entries
+/// under `projects` belong to those projects and are ignored here.
+pub fn parse_claude_user_config(json: &str) -> Result<McpServerMap> {
+    parse_server_map(json)
+}
+
+pub fn claude_user_config_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".claude.json"))
+}
+
+/// Best-effort read of Claude's user-level servers; missing or unreadable
+/// config yields an empty map.
+pub fn load_claude_user_servers() -> McpServerMap {
+    claude_user_config_path()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|s| parse_claude_user_config(&s).ok())
+        .unwrap_or_default()
+}
+
+/// Best-effort read of a project's `.mcp.json`.
+pub fn load_project_servers(project_path: &Path) -> McpServerMap {
+    std::fs::read_to_string(project_path.join(".mcp.json"))
+        .ok()
+        .and_then(|s| parse_server_map(&s).ok())
+        .unwrap_or_default()
+}
+
+/// Label for a project source: its directory name.
+pub fn project_label(project_path: &Path) -> String {
+    project_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| project_path.to_string_lossy().to_string())
+}
+
+/// Merge candidate servers from all sources, de-duplicated by name.
+///
+/// Precedence on name clashes: pool, then Claude user config, then projects
+/// in the order given. Output is sorted by name.
+pub fn merge_available(
+    pool: &McpServerMap,
+    claude: &McpServerMap,
+    projects: &[(String, McpServerMap)],
+) -> Vec<McpCandidate> {
+    let mut merged: BTreeMap<String, McpCandidate> = BTreeMap::new();
+
+    let mut add = |map: &McpServerMap, source: McpSource| {
+        for (name, config) in map {
+            merged.entry(name.clone()).or_insert_with(|| McpCandidate {
+                name: name.clone(),
+                source: source.clone(),
+                config: config.clone(),
+            });
+        }
+    };
+
+    add(pool, McpSource::Pool);
+    add(claude, McpSource::Claude);
+    for (label, map) in projects {
+        add(map, McpSource::Project(label.clone()));
+    }
+
+    merged.into_values().collect()
+}
+
+/// Transports a server definition may declare in `type`.
+pub const TRANSPORTS: &[&str] = &["stdio", "sse", "http"];
+
+/// A server definition in the shape `mcpServers` entries are written in.
+/// Empty `args` and `env` are left out, as Claude does.
+pub fn server_definition(
+    transport: &str,
+    command: &str,
+    args: &[String],
+    env: &[(String, String)],
+) -> Value {
+    let mut def = json!({ "type": transport, "command": command });
+    if !args.is_empty() {
+        def["args"] = json!(args);
+    }
+    if !env.is_empty() {
+        let env: Map<String, Value> = env
+            .iter()
+            .map(|(k, v)| (k.clone(), Value::String(v.clone())))
+            .collect();
+        def["env"] = Value::Object(env);
+    }
+    def
+}
+
+/// Split a `KEY=VALUE` argument, rejecting keys a shell could not export.
+pub fn parse_env_pair(s: &str) -> Result<(String, String)> {
+    let Some((key, value)) = s.split_once('=') else {
+        return Err(Error::config(format!("env entry {s:?} is not KEY=VALUE")));
+    };
+    if !is_env_key(key) {
+        return Err(Error::config(format!("env key {key:?} is not a valid variable name")));
+    }
+    Ok((key.to_string(), value.to_string()))
+}
+
+fn is_env_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Check a definition before it is saved: a known transport, a command
+/// that resolves on `PATH` (or a `url` for remote transports), string
+/// args, and well-formed env keys.
+pub fn validate_server(name: &str, config: &Value) -> Result<()> {
+    validate_server_in(name, config, std::env::var_os("PATH").as_deref())
+}
+
+fn validate_server_in(name: &str, config: &Value, path_var: Option<&OsStr>) -> Result<()> {
+    let invalid = |what: String| Err(Error::config(format!("mcpServers.{name}: {what}")));
+    if name.trim().is_empty() || name.chars().any(char::is_whitespace) {
+        return Err(Error::config(format!("invalid server name {name:?}")));
+    }
+    let Some(obj) = config.as_object() else {
+        return invalid("not an object".to_string());
+    };
+
+    let transport = match obj.get("type") {
+        None => "stdio",
+        Some(Value::String(t)) if TRANSPORTS.contains(&t.as_str()) => t.as_str(),
+        Some(t) => {
+            return invalid(format!("type {t} is not one of {}", TRANSPORTS.join(", ")));
+        }
+    };
+    if transport != "stdio" {
+        return match obj.get("url") {
+            Some(Value::String(url)) if !url.is_empty() => Ok(()),
+            _ => invalid(format!("{transport} servers need a url")),
+        };
+    }
+
+    let Some(command) = obj.get("command").and_then(Value::as_str) else {
+        return invalid("command is missing".to_string());
+    };
+    if find_command(command, path_var).is_none() {
+        return invalid(format!("command {command:?} not found on PATH"));
+    }
+    if let Some(args) = obj.get("args") {
+        if !args.as_array().is_some_and(|a| a.iter().all(Value::is_string)) {
+            return invalid("args must be a list of strings".to_string());
+        }
+    }
+    if let Some(env) = obj.get("env") {
+        let Some(env) = env.as_object() else {
+            return invalid("env must be an object".to_string());
+        };
+        for (key, value) in env {
+            if !is_env_key(key) {
+                return invalid(format!("env key {key:?} is not a valid variable name"));
+            }
+            if !value.is_string() {
+                return invalid(format!("env.{key} must be a string"));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Resolve `command` the way a shell would: as given when it has a path
+/// separator, otherwise through each `PATH` entry.
+pub(crate) fn find_command(command: &str, path_var: Option<&OsStr>) -> Option<PathBuf> {
+    if command.is_empty() {
+        return None;
+    }
+    if command.contains(std::path::MAIN_SEPARATOR) {
+        let path = PathBuf::from(command);
+        return path.is_file().then_some(path);
+    }
+    std::env::split_paths(path_var?)
+        .map(|dir| dir.join(command))
+        .find(|path| path.is_file())
+}
+
+/// agent-hand's global pool of server definitions, `~/.agent-hand/mcp.json`,
+/// kept in the same `mcpServers` shape as `.mcp.json`. It is only a file:
+/// nothing runs the servers in it, and `agent-hand mcp` and `doctor` are
+/// its only readers.
+pub fn pool_path() -> Result<PathBuf> {
+    Ok(Storage::get_agent_hand_dir()?.join("mcp.json"))
+}
+
+/// Read a config document to edit it. A missing file is an empty document;
+/// one that does not parse, or whose `mcpServers` is malformed, is an error
+/// so it is never overwritten.
+pub fn read_config_doc(path: &Path) -> Result<Value> {
+    let json = match std::fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(json!({})),
+        Err(e) => return Err(e.into()),
+    };
+    let doc: Value = serde_json::from_str(&json)
+        .map_err(|e| Error::config(format!("{}: {e}", path.display())))?;
+    server_map(&doc).map_err(|e| Error::config(format!("{}: {e}", path.display())))?;
+    Ok(doc)
+}
+
+/// Write a config document through a temp file, so a failed write leaves
+/// the old one in place.
+pub fn write_config_doc(path: &Path, doc: &Value) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(doc)? + "\n")?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Add or replace `name` under a config document's `mcpServers`, leaving
+/// every other key as it was.
+pub fn upsert_server(doc: &mut Value, name: &str, config: Value) -> Result<()> {
+    let Some(root) = doc.as_object_mut() else {
+        return Err(Error::config("config document is not an object"));
+    };
+    let servers = root
+        .entry("mcpServers")
+        .or_insert_with(|| Value::Object(Map::new()));
+    let Some(servers) = servers.as_object_mut() else {
+        return Err(Error::config("mcpServers is not an object"));
+    };
+    servers.insert(name.to_string(), config);
+    Ok(())
+}
+
+/// Remove `name` from a config document's `mcpServers`; returns the
+/// definition it had, if any.
+pub fn remove_server(doc: &mut Value, name: &str) -> Option<Value> {
+    doc.get_mut("mcpServers")?.as_object_mut()?.remove(name)
+}
+
+/// Where a session's MCP servers come from when its tool starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum McpScope {
+    /// The session's own config, passed with `--mcp-config`.
+    Session,
+    /// The project's `.mcp.json`, shared with every session in the
+    /// directory; for tools with no per-launch override.
+    Project,
+}
+
+impl McpScope {
+    pub fn of(tool: Tool) -> Self {
+        match tool {
+            Tool::Claude => Self::Session,
+            Tool::Gemini | Tool::OpenCode | Tool::Codex | Tool::Shell => Self::Project,
+        }
+    }
+}
+
+/// Where a session's own config is written:
+/// `~/.agent-hand/profiles/<profile>/mcp/<session-id>.json`.
+pub fn session_config_path(profile: &str, session_id: &str) -> Result<PathBuf> {
+    Ok(Storage::profile_dir_for(profile)?
+        .join("mcp")
+        .join(format!("{session_id}.json")))
+}
+
+/// Write `servers` as a session's own config; returns the file's path.
+pub fn write_session_config(
+    profile: &str,
+    session_id: &str,
+    servers: &McpServerMap,
+) -> Result<PathBuf> {
+    let path = session_config_path(profile, session_id)?;
+    write_config_doc(&path, &json!({ "mcpServers": servers }))?;
+    Ok(path)
+}
+
+/// A session's own config, for editing. Until the session has one, it
+/// starts from the servers in `project_dir`'s `.mcp.json`: the session
+/// loads only its own file once that exists, so they would be lost.
+pub fn read_session_config(path: &Path, project_dir: &Path) -> Result<Value> {
+    if path.exists() {
+        return read_config_doc(path);
+    }
+    let project = read_config_doc(&project_dir.join(".mcp.json"))?;
+    Ok(json!({ "mcpServers": server_map(&project)? }))
+}
+
+/// `command` made to load only the servers in `config`, for tools of
+/// [`McpScope::Session`]. Left alone otherwise, or when the file is gone.
+pub fn with_session_config(tool: Tool, command: &str, config: Option<&Path>) -> String {
+    match config {
+        Some(path) if McpScope::of(tool) == McpScope::Session && path.is_file() => format!(
+            "{command} --mcp-config {} --strict-mcp-config",
+            crate::tmux::shell_quote(&path.to_string_lossy())
+        ),
+        _ => command.to_string(),
+    }
+}
+
+/// Delete a session's own config, returning what it held so it can be
+/// written back on undo. None when there was no file.
+pub fn take_session_config(path: &Path) -> Option<String> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    if let Err(e) = std::fs::remove_file(path) {
+        tracing::warn!("could not remove {}: {e}", path.display());
+    }
+    Some(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Trimmed-down `~/.claude.json` in the shape Claude Code writes it.
+    const CLAUDE_USER_FIXTURE: &str = r#"{
+        "numStartups": 42,
+        "mcpServers": {
+            "github": {
+                "type": "stdio",
+                "command": "npx",
+                "args": ["-y", "@modelcontextprotocol/server-github"],
+                "env": { "GITHUB_TOKEN": "x" }
+            },
+            "fetch": { "command": "uvx", "args": ["mcp-server-fetch"] }
+        },
+        "projects": {
+            "/home/me/api": {
+                "mcpServers": { "db": { "command": "db-mcp" } }
+            }
+        }
+    }"#;
+
+    fn map(entries: &[(&str, Value)]) -> McpServerMap {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn parses_claude_user_servers_only() {
+        let servers = parse_claude_user_config(CLAUDE_USER_FIXTURE).unwrap();
+        assert_eq!(servers.keys().collect::<Vec<_>>(), vec!["fetch", "github"]);
+        assert_eq!(servers["github"]["command"], "npx");
+        assert_eq!(servers["github"]["env"]["GITHUB_TOKEN"], "x");
+        assert!(!servers.contains_key("db"));
+    }
+
+    #[test]
+    fn missing_servers_key_is_empty() {
+        assert!(parse_server_map(r#"{"numStartups": 1}"#).unwrap().is_empty());
+    }
+
+    #[test]
+    fn malformed_servers_are_rejected() {
+        assert!(parse_server_map(r#"{"mcpServers": []}"#).is_err());
+        assert!(parse_server_map(r#"{"mcpServers": {"x": "npx"}}"#).is_err());
+        assert!(parse_server_map("not json").is_err());
+    }
+
+    #[test]
+    fn pool_wins_name_clashes() {
+        let pool = map(&[("github", json!({"command": "pooled"}))]);
+        let claude = map(&[
+            ("github", json!({"command": "npx"})),
+            ("fetch", json!({"command": "uvx"})),
+        ]);
+        let projects = vec![
+            ("api".to_string(), map(&[("fetch", json!({"command": "proj"})), ("db", json!({"command": "db"}))])),
+            ("web".to_string(), map(&[("db", json!({"command": "other"}))])),
+        ];
+
+        let merged = merge_available(&pool, &claude, &projects);
+        let summary: Vec<(String, String)> = merged
+            .iter()
+            .map(|c| (c.name.clone(), c.source.tag()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("db".to_string(), "[proj:api]".to_string()),
+                ("fetch".to_string(), "[claude]".to_string()),
+                ("github".to_string(), "[pool]".to_string()),
+            ]
+        );
+        assert_eq!(merged[2].config["command"], "pooled");
+        assert_eq!(merged[0].config["command"], "db");
+    }
+
+    #[test]
+    fn definitions_validate_against_path() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("mcp-fetch"), "").unwrap();
+        let path = Some(dir.path().as_os_str());
+
+        let env = vec![parse_env_pair("API_KEY=a=b").unwrap()];
+        let def = server_definition("stdio", "mcp-fetch", &["--port".to_string()], &env);
+        assert_eq!(def["env"]["API_KEY"], "a=b");
+        assert!(validate_server_in("fetch", &def, path).is_ok());
+
+        let missing = server_definition("stdio", "nope", &[], &[]);
+        assert!(validate_server_in("fetch", &missing, path).is_err());
+        assert!(validate_server_in("two words", &def, path).is_err());
+        assert!(validate_server_in("fetch", &json!({"type": "grpc"}), path).is_err());
+        let remote = json!({"type": "http", "url": "https://x"});
+        assert!(validate_server_in("web", &remote, path).is_ok());
+        let bad_env = json!({"command": "mcp-fetch", "env": {"1KEY": "x"}});
+        assert!(validate_server_in("fetch", &bad_env, path).is_err());
+    }
+
+    #[test]
+    fn env_pairs_need_a_variable_name() {
+        assert_eq!(parse_env_pair("K=").unwrap(), ("K".to_string(), String::new()));
+        assert!(parse_env_pair("K").is_err());
+        assert!(parse_env_pair("MY-KEY=1").is_err());
+        assert!(parse_env_pair("=1").is_err());
+    }
+
+    #[test]
+    fn editing_servers_keeps_other_keys() {
+        let mut doc: Value = serde_json::from_str(CLAUDE_USER_FIXTURE).unwrap();
+        upsert_server(&mut doc, "db", json!({"command": "db-mcp"})).unwrap();
+        assert_eq!(remove_server(&mut doc, "fetch").unwrap()["command"], "uvx");
+        assert!(remove_server(&mut doc, "fetch").is_none());
+
+        let servers = parse_server_map(&doc.to_string()).unwrap();
+        assert_eq!(servers.keys().collect::<Vec<_>>(), vec!["db", "github"]);
+        assert_eq!(doc["numStartups"], 42);
+
+        let mut empty = json!({});
+        upsert_server(&mut empty, "db", json!({"command": "db-mcp"})).unwrap();
+        assert_eq!(empty["mcpServers"]["db"]["command"], "db-mcp");
+    }
+
+    #[test]
+    fn config_docs_round_trip_and_refuse_corrupt_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested").join("mcp.json");
+        assert_eq!(read_config_doc(&path).unwrap(), json!({}));
+
+        let mut doc = json!({ "keep": true });
+        upsert_server(&mut doc, "db", json!({"command": "db-mcp"})).unwrap();
+        write_config_doc(&path, &doc).unwrap();
+        assert_eq!(read_config_doc(&path).unwrap(), doc);
+
+        std::fs::write(&path, "{ not json").unwrap();
+        assert!(read_config_doc(&path).is_err());
+        std::fs::write(&path, r#"{"mcpServers": []}"#).unwrap();
+        assert!(read_config_doc(&path).is_err());
+    }
+
+    #[test]
+    fn first_session_config_starts_from_the_project() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().join("api");
+        let session = dir.path().join("mcp").join("abc.json");
+        write_config_doc(
+            &project.join(".mcp.json"),
+            &json!({ "mcpServers": { "db": { "command": "db-mcp" } }, "other": 1 }),
+        )
+        .unwrap();
+
+        let mut doc = read_session_config(&session, &project).unwrap();
+        upsert_server(&mut doc, "fs", json!({"command": "fs-mcp"})).unwrap();
+        write_config_doc(&session, &doc).unwrap();
+        let servers = server_map(&read_session_config(&session, &project).unwrap()).unwrap();
+        assert_eq!(servers.keys().collect::<Vec<_>>(), ["db", "fs"]);
+
+        // Once the session has its own file, the project no longer counts.
+        write_config_doc(&project.join(".mcp.json"), &json!({})).unwrap();
+        let servers = server_map(&read_session_config(&session, &project).unwrap()).unwrap();
+        assert_eq!(servers.len(), 2);
+        assert_eq!(
+            read_session_config(&session.with_file_name("new.json"), &dir.path().join("none"))
+                .unwrap(),
+            json!({ "mcpServers": {} })
+        );
+    }
+
+    #[test]
+    fn only_session_scoped_tools_get_their_own_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("it's.json");
+        std::fs::write(&path, r#"{"mcpServers": {}}"#).unwrap();
+
+        assert_eq!(
+            with_session_config(Tool::Claude, "claude --resume x", Some(&path)),
+            format!(
+                "claude --resume x --mcp-config '{}/it'\\''s.json' --strict-mcp-config",
+                dir.path().display()
+            )
+        );
+        assert_eq!(with_session_config(Tool::Codex, "codex", Some(&path)), "codex");
+        assert_eq!(with_session_config(Tool::Claude, "claude", None), "claude");
+
+        assert_eq!(take_session_config(&path).as_deref(), Some(r#"{"mcpServers": {}}"#));
+        assert!(!path.exists());
+        assert_eq!(with_session_config(Tool::Claude, "claude", Some(&path)), "claude");
+        assert_eq!(take_session_config(&path), None);
+    }
+
+    #[test]
+    fn project_label_uses_dir_name() {
+        assert_eq!(project_label(Path::new("/home/me/api")), "api");
+    }
+}
@@ -1,3 +1,5 @@
+pub mod fork;
 mod hooks;
+pub mod mcp;
 
 pub use hooks::{bridge_script_path, ensure_event_bridge_hooks};
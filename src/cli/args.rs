@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Parser, Debug)]
 #[command(name = "agent-hand")]
@@ -30,6 +30,45 @@ pub enum Command {
         /// Command to run
         #[arg(short, long)]
         cmd: Option<String>,
+
+        /// Shell command run in the pane before the command; the command
+        /// only starts if it succeeds
+        #[arg(long)]
+        pre: Option<String>,
+
+        /// Command typed into the pane the first time it is attached after
+        /// a start
+        #[arg(long)]
+        post_attach: Option<String>,
+
+        /// Extra tmux window running a command, e.g. dev:"npm run dev";
+        /// repeat for more windows
+        #[arg(long = "window", value_name = "NAME:CMD")]
+        windows: Vec<crate::session::WindowSpec>,
+
+        /// Window the agent runs in, probed for status and selected on
+        /// attach (default: the first one running the session's tool)
+        #[arg(long, value_name = "NAME")]
+        primary_window: Option<String>,
+
+        /// Fill title, tool, command and group from a saved template;
+        /// the other flags override it
+        #[arg(long)]
+        template: Option<String>,
+
+        /// Start the session's tmux session right away
+        #[arg(long)]
+        start: bool,
+
+        /// Start the session if needed and attach to it; exits with the
+        /// attach's status
+        #[arg(long)]
+        attach: bool,
+
+        /// When a session for this path exists: use it (start/attach it),
+        /// fail, or add another one
+        #[arg(long, value_enum, default_value_t = Existing::Attach)]
+        existing: Existing,
     },
 
     /// List all sessions
@@ -41,12 +80,43 @@ pub enum Command {
         /// List sessions from all profiles
         #[arg(long)]
         all: bool,
+
+        /// Only sessions with this label (case-insensitive)
+        #[arg(long)]
+        label: Option<String>,
+
+        /// Only sessions whose project directory no longer exists
+        #[arg(long)]
+        broken: bool,
+    },
+
+    /// Print the group/session tree in the session list's order
+    Tree {
+        /// Output format: markdown, json or text
+        #[arg(short, long, default_value = "markdown")]
+        format: String,
+
+        /// Only this group and its subgroups
+        #[arg(short, long)]
+        group: Option<String>,
+
+        /// Show each session's status
+        #[arg(long)]
+        include_status: bool,
+
+        /// Show each session's project path
+        #[arg(long)]
+        include_paths: bool,
     },
 
     /// Remove a session
     Remove {
         /// Session ID or title
         identifier: String,
+
+        /// Remove even while a TUI has the profile open
+        #[arg(long)]
+        force: bool,
     },
 
     /// Show session status
@@ -62,10 +132,45 @@ pub enum Command {
         /// JSON output
         #[arg(long)]
         json: bool,
+
+        /// Refresh every N seconds (default 2) until Ctrl-C
+        #[arg(long, value_name = "SECS", num_args = 0..=1, default_missing_value = "2")]
+        watch: Option<u64>,
     },
 
     /// Print a compact one-line status for tmux status-left
-    Statusline,
+    Statusline {
+        /// Output format: text, json or template
+        #[arg(long, default_value = "text")]
+        format: String,
+
+        /// Template for `--format template`, e.g. "{waiting_count}⏸ {oldest_waiting_title}"
+        #[arg(long)]
+        template: Option<String>,
+    },
+
+    /// Print session status changes as NDJSON, one event per line
+    Events {
+        /// Keep probing and printing changes until Ctrl-C; without it, only
+        /// the current statuses are printed
+        #[arg(short, long)]
+        follow: bool,
+
+        /// Seconds between probes with --follow (at least 0.25)
+        #[arg(long, value_name = "SECS", default_value_t = 2.0)]
+        interval: f64,
+
+        /// Event types to print, comma-separated (default: all)
+        #[arg(long = "type", value_name = "TYPE", value_enum, value_delimiter = ',')]
+        types: Vec<super::events::EventType>,
+    },
+
+    /// Time spent in each session, from the analytics logs
+    Stats {
+        /// Days to cover, today included
+        #[arg(long, default_value_t = 7)]
+        days: u32,
+    },
 
     /// Session management commands
     Session {
@@ -79,6 +184,122 @@ pub enum Command {
         action: ProfileAction,
     },
 
+    /// Manage session templates
+    Template {
+        #[command(subcommand)]
+        action: TemplateAction,
+    },
+
+    /// Manage groups
+    Group {
+        #[command(subcommand)]
+        action: GroupAction,
+    },
+
+    /// Snapshot and restore the profile's saved state
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotAction,
+    },
+
+    /// Export the profile's sessions and groups to a portable file
+    Export {
+        /// Output format: json or toml
+        #[arg(short, long, default_value = "json")]
+        format: String,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        out: Option<String>,
+    },
+
+    /// Import sessions and groups from an exported file
+    Import {
+        /// File written by `agent-hand export`
+        file: String,
+
+        /// Add to the existing sessions, skipping duplicate project paths
+        #[arg(long)]
+        merge: bool,
+
+        /// Replace all existing sessions and groups
+        #[arg(long)]
+        replace: bool,
+
+        /// Import even while a TUI has the profile open
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Create sessions for existing sessions on another tmux server
+    Adopt {
+        /// tmux server (`-L` socket name) to adopt from
+        #[arg(long, default_value = "default")]
+        server: String,
+
+        /// Adopt every session on the server
+        #[arg(long, conflicts_with = "names")]
+        all: bool,
+
+        /// Sessions to adopt; lists the server's sessions when none are given
+        names: Vec<String>,
+
+        /// Kill each adopted session and recreate it on agent-hand's server
+        /// with the same directory and command
+        #[arg(long)]
+        migrate: bool,
+    },
+
+    /// Ask working agents to wrap up, wait for them, then stop sessions
+    Wrapup {
+        /// Only sessions in this group (and its subgroups)
+        #[arg(short, long)]
+        group: Option<String>,
+
+        /// Seconds to wait for agents to go quiet before stopping anyway
+        #[arg(long, default_value_t = crate::session::wrapup::DEFAULT_GRACE_SECS)]
+        grace_secs: u64,
+
+        /// Message sent to each working agent
+        #[arg(short, long)]
+        message: Option<String>,
+
+        /// Report outcomes but leave the tmux sessions running
+        #[arg(long)]
+        no_kill: bool,
+
+        /// Show what would happen without sending anything
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Write the ids of stopped sessions to this file (see `resume --from`)
+        #[arg(long, value_name = "FILE")]
+        resume_list: Option<String>,
+    },
+
+    /// Start sessions listed in a file, one id per line
+    Resume {
+        /// File written by `wrapup --resume-list`
+        #[arg(long, value_name = "FILE")]
+        from: String,
+    },
+
+    /// Stop sessions idle for longer than `[reaper] idle_hours` (default 24);
+    /// the sessions themselves are kept
+    Reap {
+        /// List what would be stopped without stopping anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Move sessions that are ungrouped or in the group guessed from their
+    /// path to where the `[grouping]` rules put them
+    Regroup {
+        /// List the moves without making them
+        #[arg(long)]
+        dry_run: bool,
+    },
+
     /// Upgrade agent-hand from GitHub Releases
     Upgrade {
         /// Install directory (default: /usr/local/bin if writable, else ~/.local/bin)
@@ -99,6 +320,11 @@ pub enum Command {
     /// Show version
     Version,
 
+    /// Print a shell completion script, e.g. `eval "$(agent-hand completions zsh)"`
+    Completions {
+        shell: super::completions::CompletionShell,
+    },
+
     /// Authenticate to unlock premium features
     Login,
 
@@ -170,6 +396,37 @@ pub enum Command {
         action: ConfigAction,
     },
 
+    /// Add, remove, show and edit MCP server definitions
+    Mcp {
+        #[command(subcommand)]
+        action: McpAction,
+    },
+
+    /// Check tmux, the tmux server and saved sessions for problems
+    Doctor {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Classify a pane dump as waiting/running/idle and show the pattern
+    /// that decided it, for testing `status_detection` settings
+    Detect {
+        /// Tool whose patterns apply: claude, gemini, opencode, codex, shell
+        #[arg(long, default_value = "shell")]
+        tool: String,
+
+        /// Pane dump to read (stdin when omitted or "-")
+        #[arg(long)]
+        file: Option<String>,
+    },
+
+    /// Inspect agent-hand's tmux server state
+    Tmux {
+        #[command(subcommand)]
+        action: TmuxAction,
+    },
+
     /// Internal: PTY viewer bridge (runs inside tmux pane, bridges WebSocket ↔ stdio)
     #[command(name = "pty-viewer")]
     PtyViewer {
@@ -205,6 +462,24 @@ pub enum Command {
         #[arg(long)]
         room_id: String,
     },
+
+    /// Internal: live candidates for the completion scripts
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        #[command(subcommand)]
+        target: CompleteTarget,
+    },
+}
+
+/// `add --existing`: what to do when the path already has a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Existing {
+    /// Use that session: start or attach it as asked.
+    Attach,
+    /// Fail with the session's title and ID.
+    Error,
+    /// Add another session for the same path.
+    New,
 }
 
 #[derive(Subcommand, Debug)]
@@ -218,11 +493,212 @@ pub enum SessionAction {
     /// Restart a session
     Restart { id: String },
 
+    /// Restart a session and resume its AI CLI conversation
+    /// (e.g. `claude --resume <session id>`); a plain restart when no
+    /// session ID was detected
+    Resume { id: String },
+
     /// Attach to a session
     Attach { id: String },
 
+    /// Show two sessions side by side; detaching closes only the view
+    Compare {
+        /// Left session: ID, ID prefix or title
+        left: String,
+        /// Right session: ID, ID prefix or title
+        right: String,
+    },
+
+    /// Print a session's scrollback, or write it to a file
+    Logs {
+        /// Session ID, ID prefix or title
+        id: String,
+
+        /// Only the last N lines
+        #[arg(long, conflicts_with = "full")]
+        lines: Option<usize>,
+
+        /// The whole history (the default)
+        #[arg(long)]
+        full: bool,
+
+        /// Write to this file instead of stdout
+        #[arg(short, long)]
+        out: Option<String>,
+
+        /// Keep colors and other escape sequences
+        #[arg(long)]
+        raw: bool,
+    },
+
     /// Show session details
-    Show { id: Option<String> },
+    Show {
+        id: Option<String>,
+
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Type a prompt into a session and press Enter
+    Send {
+        /// Session ID, ID prefix or title
+        id: String,
+
+        /// Text to send; newlines are pasted, not pressed as Enter
+        text: String,
+
+        /// Start the session first if it is not running
+        #[arg(long)]
+        start: bool,
+
+        /// Type the text but don't submit it
+        #[arg(long)]
+        no_enter: bool,
+    },
+
+    /// Type the last lines of one session's output into another session
+    Forward {
+        /// Source session: ID, ID prefix or title
+        src: String,
+
+        /// Target session: ID, ID prefix or title
+        dst: String,
+
+        /// Lines to forward (default: forward_lines from the config, 50)
+        #[arg(long)]
+        lines: Option<usize>,
+
+        /// Start the target first if it is not running
+        #[arg(long)]
+        start: bool,
+    },
+
+    /// Start a session whenever the TUI launches and it is not running
+    Autostart {
+        /// Session ID, ID prefix or title
+        id: String,
+
+        #[arg(
+            value_name = "on|off",
+            action = clap::ArgAction::Set,
+            value_parser = clap::builder::BoolishValueParser::new()
+        )]
+        enabled: bool,
+    },
+
+    /// Set a session's label badge
+    Set {
+        id: String,
+
+        /// Label text; "" clears it
+        #[arg(long)]
+        label: Option<String>,
+
+        /// Badge color: gray, magenta, cyan, green, yellow, red, blue
+        #[arg(long)]
+        label_color: Option<String>,
+
+        /// Desktop notifications when it starts waiting: on or off
+        #[arg(long, value_name = "on|off", value_parser = clap::builder::BoolishValueParser::new())]
+        notify: Option<bool>,
+
+        /// Exempt it from the idle reaper: on or off
+        #[arg(long, value_name = "on|off", value_parser = clap::builder::BoolishValueParser::new())]
+        keep_alive: Option<bool>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TemplateAction {
+    /// Save a session's title, tool, command and group as a template
+    Save {
+        name: String,
+
+        /// Session ID, ID prefix or title to copy
+        #[arg(long)]
+        from: String,
+
+        /// Title pattern; `{dirname}` becomes the project directory name
+        #[arg(short, long)]
+        title: Option<String>,
+    },
+
+    /// List templates
+    List {
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Delete a template
+    Delete { name: String },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum GroupAction {
+    /// List groups as a tree with their session counts
+    List {
+        /// Print JSON instead of a tree
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Create a group and any missing parents
+    Create {
+        /// Group path, e.g. work/frontend
+        path: String,
+    },
+
+    /// Rename a group; its subgroups and sessions follow
+    Rename {
+        /// Current group path
+        old: String,
+
+        /// New group path
+        new: String,
+    },
+
+    /// Delete a group and its subgroups
+    Delete {
+        /// Group path, e.g. work/frontend
+        path: String,
+
+        /// Also delete the sessions in it and stop their tmux sessions
+        #[arg(long, conflicts_with = "keep_sessions")]
+        with_sessions: bool,
+
+        /// Keep the sessions, ungrouped (the default)
+        #[arg(long)]
+        keep_sessions: bool,
+
+        /// Delete sessions even while a TUI has the profile open
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Move a group, its subgroups and their sessions under another parent
+    Move {
+        /// Group path, e.g. work/frontend
+        path: String,
+
+        /// New parent group; "" or "/" for the top level
+        new_parent: String,
+    },
+
+    /// Show or set a group's notes, shown in the TUI preview
+    Notes {
+        /// Group path, e.g. work/frontend
+        path: String,
+
+        /// New notes; `\n` is a line break and "" clears them
+        #[arg(long, conflicts_with = "show")]
+        set: Option<String>,
+
+        /// Print the notes (the default)
+        #[arg(long)]
+        show: bool,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -234,7 +710,57 @@ pub enum ProfileAction {
     Create { name: String },
 
     /// Delete a profile
-    Delete { name: String },
+    Delete {
+        name: String,
+
+        /// Delete even while a TUI has the profile open
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Restore a profile's sessions.json from one of its rolling backups
+    Restore {
+        name: String,
+
+        /// Backup to restore; 1 is the newest
+        #[arg(short, long, default_value_t = 1)]
+        generation: usize,
+
+        /// List the backups instead of restoring
+        #[arg(long)]
+        list: bool,
+
+        /// Restore even while a TUI has the profile open
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SnapshotAction {
+    /// Snapshot the current profile state
+    Create {
+        /// Label; labeled snapshots are never pruned
+        #[arg(short, long)]
+        label: Option<String>,
+    },
+
+    /// List snapshots, newest first
+    List,
+
+    /// Restore a snapshot (the current state is snapshotted first)
+    Restore {
+        /// Snapshot id or unique id prefix
+        id: String,
+
+        /// Show what would change without touching any files
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Restore even while a TUI has the profile open
+        #[arg(long)]
+        force: bool,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -340,6 +866,8 @@ pub enum ConfigAction {
     },
     /// Show configuration file path
     Path,
+    /// Check keybindings for conflicts
+    Check,
     /// Reset all settings to defaults
     Reset {
         /// Skip confirmation prompt
@@ -348,6 +876,85 @@ pub enum ConfigAction {
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum McpAction {
+    /// Add or replace a server definition
+    Add {
+        /// Server name, the key under `mcpServers`
+        name: String,
+
+        /// Program that runs the server; must resolve on PATH
+        #[arg(long)]
+        command: String,
+
+        /// Argument passed to the command (repeatable)
+        #[arg(long = "arg", value_name = "ARG", allow_hyphen_values = true)]
+        args: Vec<String>,
+
+        /// Environment variable as KEY=VALUE (repeatable)
+        #[arg(long, value_name = "KEY=VALUE")]
+        env: Vec<String>,
+
+        /// Transport: stdio, sse or http
+        #[arg(long, default_value = "stdio", value_parser = ["stdio", "sse", "http"])]
+        transport: String,
+
+        /// Server URL, for the sse and http transports
+        #[arg(long)]
+        url: Option<String>,
+
+        /// Edit this session's servers instead of the global pool
+        #[arg(long, value_name = "SESSION")]
+        session: Option<String>,
+    },
+
+    /// Remove a server definition
+    Remove {
+        name: String,
+
+        /// Edit this session's servers instead of the global pool
+        #[arg(long, value_name = "SESSION")]
+        session: Option<String>,
+    },
+
+    /// Print a server definition, or list all servers when no name is given
+    Show {
+        name: Option<String>,
+
+        /// Show this session's servers instead of the global pool
+        #[arg(long, value_name = "SESSION")]
+        session: Option<String>,
+    },
+
+    /// Open a server definition in $EDITOR, then validate and save it
+    Edit {
+        name: String,
+
+        /// Edit this session's servers instead of the global pool
+        #[arg(long, value_name = "SESSION")]
+        session: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TmuxAction {
+    /// Show the global environment markers agent-hand sets on its server
+    Env {
+        /// Unset all of them (bindings are restored on next TUI start)
+        #[arg(long)]
+        clear: bool,
+    },
+
+    /// Show the installed tmux version and which features it supports
+    Version,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CompleteTarget {
+    /// `id<TAB>title` for each session of the profile
+    Sessions,
+}
+
 #[derive(Subcommand, Debug)]
 pub enum SkillsAction {
     /// Initialize skills repository on GitHub
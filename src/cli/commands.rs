@@ -3,14 +3,19 @@ use std::sync::Arc;
 
 use tokio::process::Command as TokioCommand;
 
-use crate::cli::{Args, CanvasAction, Command, ConfigAction, ProfileAction, SessionAction};
+use crate::cli::{
+    Args, CanvasAction, Command, CompleteTarget, ConfigAction, Existing, GroupAction, McpAction,
+    ProfileAction, SessionAction, SnapshotAction, TemplateAction, TmuxAction,
+};
 #[cfg(feature = "pro")]
 use crate::cli::SkillsAction;
 use crate::error::Result;
 use crate::i18n::Language;
+use crate::session::waiting::{waiting_queue, waiting_since};
 use crate::session::{Instance, Storage, DEFAULT_PROFILE};
 use crate::t;
 use crate::tmux::TmuxManager;
+use crate::ui::text::{fit, format_age, truncate};
 use tracing::warn;
 
 pub async fn run_cli(args: Args) -> Result<()> {
@@ -27,6 +32,21 @@ pub async fn run_cli(args: Args) -> Result<()> {
     // Ensure tmux popups inherit the active profile.
     std::env::set_var("AGENTHAND_PROFILE", profile);
 
+    // Completion scripts call back on every Tab press; skip the setup below.
+    match &args.command {
+        Some(Command::Completions { shell }) => {
+            return super::completions::write_script(*shell, &mut std::io::stdout());
+        }
+        Some(Command::Complete {
+            target: CompleteTarget::Sessions,
+        }) => {
+            let (instances, _, _) = Storage::new(profile).await?.load().await?;
+            print!("{}", super::completions::session_candidates(&instances));
+            return Ok(());
+        }
+        _ => {}
+    }
+
     let cfg = crate::config::ConfigFile::load().await.ok().flatten();
     // Install event bridge hooks for event-driven status detection.
     // When auto_register is enabled (default), this also registers hooks
@@ -46,9 +66,24 @@ pub async fn run_cli(args: Args) -> Result<()> {
         }
     }
     if let Some(cfg) = cfg.as_ref() {
-        if let Err(err) = crate::tmux::set_status_detection_config(cfg.status_detection()) {
-            warn!("failed to set status detection config: {err}");
+        let invalid = crate::tmux::set_status_detection_config(cfg.status_detection());
+        if !invalid.is_empty() {
+            warn!("ignoring invalid status_detection patterns: {}", invalid.join("; "));
+        }
+        crate::session::grouping::set_rules(cfg.group_rules());
+        if let Some(server) = cfg.tmux_server() {
+            if !crate::tmux::set_server_template(server) {
+                warn!("ignoring invalid [tmux] server name: {server:?}");
+            }
         }
+        if cfg.offline {
+            crate::network::set_offline(true);
+        }
+    }
+
+    // Read-only commands (list, export, status, ...) work without tmux.
+    if needs_tmux(&args.command) {
+        require_tmux(lang).await?;
     }
 
     match args.command {
@@ -57,24 +92,137 @@ pub async fn run_cli(args: Args) -> Result<()> {
             title,
             group,
             cmd,
-        }) => handle_add(lang, profile, path, title, group, cmd).await,
+            pre,
+            post_attach,
+            windows,
+            primary_window,
+            template,
+            start,
+            attach,
+            existing,
+        }) => {
+            let launch = AddLaunch {
+                start,
+                attach,
+                existing,
+            };
+            let windows = AddWindows {
+                windows,
+                primary: primary_window,
+            };
+            handle_add(
+                lang, profile, path, title, group, cmd, pre, post_attach, windows, template, launch,
+            )
+            .await
+        }
 
-        Some(Command::List { json, all }) => handle_list(lang, profile, json, all).await,
+        Some(Command::List {
+            json,
+            all,
+            label,
+            broken,
+        }) => handle_list(lang, profile, json, all, label.as_deref(), broken).await,
+
+        Some(Command::Tree {
+            format,
+            group,
+            include_status,
+            include_paths,
+        }) => {
+            let opts = crate::cli::tree::TreeExportOptions {
+                include_status,
+                include_paths,
+            };
+            handle_tree(profile, &format, group.as_deref(), opts).await
+        }
 
-        Some(Command::Remove { identifier }) => handle_remove(lang, profile, &identifier).await,
+        Some(Command::Remove { identifier, force }) => {
+            handle_remove(lang, profile, &identifier, force).await
+        }
 
         Some(Command::Status {
             verbose,
             quiet,
             json,
-        }) => handle_status(lang, profile, verbose, quiet, json).await,
+            watch,
+        }) => handle_status(lang, profile, verbose, quiet, json, watch).await,
+
+        Some(Command::Statusline { format, template }) => {
+            handle_statusline(profile, &format, template.as_deref()).await
+        }
+
+        Some(Command::Events {
+            follow,
+            interval,
+            types,
+        }) => handle_events(profile, follow, interval, &types).await,
 
-        Some(Command::Statusline) => handle_statusline(profile).await,
+        Some(Command::Stats { days }) => handle_stats(lang, profile, days).await,
 
         Some(Command::Session { action }) => handle_session(lang, profile, action).await,
 
         Some(Command::Profile { action }) => handle_profile(lang, action).await,
 
+        Some(Command::Template { action }) => handle_template(lang, profile, action).await,
+
+        Some(Command::Group { action }) => handle_group(lang, profile, action).await,
+
+        Some(Command::Snapshot { action }) => {
+            let keep = cfg
+                .as_ref()
+                .map(|c| c.snapshot_keep())
+                .unwrap_or(crate::session::snapshot::DEFAULT_SNAPSHOT_KEEP);
+            handle_snapshot(lang, profile, keep, action)
+        }
+
+        Some(Command::Export { format, out }) => handle_export(lang, profile, &format, out).await,
+
+        Some(Command::Import {
+            file,
+            merge,
+            replace,
+            force,
+        }) => handle_import(lang, profile, &file, merge, replace, force).await,
+
+        Some(Command::Adopt {
+            server,
+            all,
+            names,
+            migrate,
+        }) => handle_adopt(lang, profile, &server, all, &names, migrate).await,
+
+        Some(Command::Wrapup {
+            group,
+            grace_secs,
+            message,
+            no_kill,
+            dry_run,
+            resume_list,
+        }) => {
+            let opts = crate::session::wrapup::WrapupOptions {
+                message: message
+                    .unwrap_or_else(|| crate::session::wrapup::DEFAULT_WRAPUP_MESSAGE.to_string()),
+                grace: std::time::Duration::from_secs(grace_secs),
+                kill: !no_kill,
+            };
+            handle_wrapup(lang, profile, group.as_deref(), opts, dry_run, resume_list.as_deref())
+                .await
+        }
+
+        Some(Command::Resume { from }) => handle_resume(lang, profile, &from).await,
+
+        Some(Command::Reap { dry_run }) => {
+            let idle_after = cfg
+                .as_ref()
+                .map(|c| c.reaper_idle_after())
+                .unwrap_or(std::time::Duration::from_secs(crate::session::reaper::DEFAULT_IDLE_HOURS * 3600));
+            handle_reap(lang, profile, idle_after, dry_run).await
+        }
+        Some(Command::Regroup { dry_run }) => {
+            let rules = cfg.as_ref().map(|c| c.group_rules()).unwrap_or_default();
+            handle_regroup(lang, profile, rules, dry_run).await
+        }
+
         Some(Command::Upgrade { prefix, version }) => {
             handle_upgrade(lang, prefix, version).await
         }
@@ -88,6 +236,9 @@ pub async fn run_cli(args: Args) -> Result<()> {
             Ok(())
         }
 
+        // Returned early, before the hook and config setup.
+        Some(Command::Completions { .. } | Command::Complete { .. }) => unreachable!(),
+
         Some(Command::Login) => handle_login(lang).await,
 
         Some(Command::Logout) => handle_logout(lang),
@@ -288,6 +439,14 @@ pub async fn run_cli(args: Args) -> Result<()> {
 
         Some(Command::Config { action }) => handle_config(lang, action).await,
 
+        Some(Command::Mcp { action }) => handle_mcp(lang, profile, action).await,
+
+        Some(Command::Doctor { json }) => handle_doctor(lang, json).await,
+
+        Some(Command::Detect { tool, file }) => handle_detect(lang, &tool, file).await,
+
+        Some(Command::Tmux { action }) => handle_tmux(lang, profile, action).await,
+
         Some(Command::PtyViewer {
             relay_url,
             room_id,
@@ -332,31 +491,6 @@ pub async fn run_cli(args: Args) -> Result<()> {
         }
 
         None => {
-            // Check tmux availability before launching TUI
-            if !crate::tmux::TmuxManager::is_available()
-                .await
-                .unwrap_or(false)
-            {
-                eprintln!("{}", t!(lang, "Error: tmux is not installed or not in PATH", "错误: 未安装 tmux 或不在 PATH 中"));
-                eprintln!();
-                eprintln!("{}", t!(lang,
-                    "agent-hand requires tmux to manage terminal sessions.",
-                    "agent-hand 需要 tmux 来管理终端会话。"
-                ));
-                eprintln!();
-                eprintln!("{}", t!(lang, "Install tmux:", "安装 tmux:"));
-                eprintln!("  macOS:        brew install tmux");
-                eprintln!("  Ubuntu/Debian: sudo apt install tmux");
-                eprintln!("  Fedora:       sudo dnf install tmux");
-                eprintln!("  Arch:         sudo pacman -S tmux");
-                eprintln!();
-                eprintln!("{}", t!(lang,
-                    "Or visit: https://github.com/tmux/tmux/wiki/Installing",
-                    "或访问: https://github.com/tmux/tmux/wiki/Installing"
-                ));
-                return Err(crate::Error::tmux("tmux is not installed"));
-            }
-
             // Launch TUI
             let mut app = crate::ui::App::new(profile).await?;
             app.run().await
@@ -364,6 +498,73 @@ pub async fn run_cli(args: Args) -> Result<()> {
     }
 }
 
+/// Whether `command` shells out to tmux (the TUI included).
+fn needs_tmux(command: &Option<Command>) -> bool {
+    match command {
+        None | Some(Command::Switch | Command::Jump | Command::Resume { .. }) => true,
+        Some(Command::Adopt { .. } | Command::Reap { .. }) => true,
+        Some(Command::Add { start, attach, .. }) => *start || *attach,
+        Some(Command::Wrapup { dry_run, .. }) => !dry_run,
+        Some(Command::Session { action }) => matches!(
+            action,
+            SessionAction::Start { .. }
+                | SessionAction::Stop { .. }
+                | SessionAction::Restart { .. }
+                | SessionAction::Resume { .. }
+                | SessionAction::Attach { .. }
+                | SessionAction::Compare { .. }
+                | SessionAction::Logs { .. }
+                | SessionAction::Send { .. }
+                | SessionAction::Forward { .. }
+        ),
+        Some(Command::Tmux { action }) => matches!(action, TmuxAction::Env { .. }),
+        _ => false,
+    }
+}
+
+/// Fail with install hints when tmux is not on PATH.
+async fn require_tmux(lang: Language) -> Result<()> {
+    use crate::ui::guard::{Capabilities, Restriction};
+
+    let mut caps = Capabilities::default();
+    caps.set_tmux_available(TmuxManager::is_available().await.unwrap_or(false));
+    if !caps.is_restricted(Restriction::NoTmux) {
+        return Ok(());
+    }
+    eprintln!("{}", t!(lang, "Error: tmux is not installed or not in PATH", "错误: 未安装 tmux 或不在 PATH 中"));
+    eprintln!();
+    eprintln!("{}", t!(lang,
+        "agent-hand requires tmux to manage terminal sessions.",
+        "agent-hand 需要 tmux 来管理终端会话。"
+    ));
+    eprintln!();
+    eprintln!("{}", t!(lang, "Install tmux:", "安装 tmux:"));
+    eprintln!("  macOS:        brew install tmux");
+    eprintln!("  Ubuntu/Debian: sudo apt install tmux");
+    eprintln!("  Fedora:       sudo dnf install tmux");
+    eprintln!("  Arch:         sudo pacman -S tmux");
+    eprintln!();
+    eprintln!("{}", t!(lang,
+        "Or visit: https://github.com/tmux/tmux/wiki/Installing",
+        "或访问: https://github.com/tmux/tmux/wiki/Installing"
+    ));
+    Err(crate::Error::tmux("tmux is not installed"))
+}
+
+/// What `add` does with the session once it is saved.
+struct AddLaunch {
+    start: bool,
+    attach: bool,
+    existing: Existing,
+}
+
+/// `add --window` and `--primary-window`.
+struct AddWindows {
+    windows: Vec<crate::session::WindowSpec>,
+    primary: Option<String>,
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn handle_add(
     lang: Language,
     profile: &str,
@@ -371,7 +572,34 @@ async fn handle_add(
     title: Option<String>,
     group: Option<String>,
     cmd: Option<String>,
+    pre: Option<String>,
+    post_attach: Option<String>,
+    windows: AddWindows,
+    template: Option<String>,
+    launch: AddLaunch,
 ) -> Result<()> {
+    let primary_window = match &windows.primary {
+        Some(name) => Some(
+            windows
+                .windows
+                .iter()
+                .position(|w| &w.name == name)
+                .ok_or_else(|| {
+                    crate::Error::InvalidInput(format!(
+                        "{} {}",
+                        t!(lang, "No --window named", "没有这个名字的 --window:"),
+                        name
+                    ))
+                })?,
+        ),
+        None => None,
+    };
+
+    let template = match template {
+        Some(name) => Some(find_template(lang, &name).await?),
+        None => None,
+    };
+
     let project_path = if let Some(p) = path {
         PathBuf::from(p)
     } else {
@@ -394,12 +622,23 @@ async fn handle_add(
         )));
     }
 
-    let title = title.unwrap_or_else(|| {
-        project_path
+    let title = match (title, &template) {
+        (Some(title), _) => crate::session::templates::expand_title(&title, &project_path),
+        (None, Some(t)) => t.title_for(&project_path),
+        (None, None) => project_path
             .file_name()
             .and_then(|n| n.to_str())
             .unwrap_or("Untitled")
-            .to_string()
+            .to_string(),
+    };
+    let title = crate::session::titles::check(&title)
+        .map_err(|e| crate::Error::InvalidInput(e.message(lang.is_zh())))?
+        .to_string();
+    let group = group.or_else(|| {
+        template
+            .as_ref()
+            .map(|t| t.group_path.clone())
+            .filter(|g| !g.is_empty())
     });
 
     // Load existing sessions
@@ -407,14 +646,22 @@ async fn handle_add(
     let (mut instances, tree, relationships) = storage.load().await?;
 
     // Check for duplicates
-    for inst in &instances {
-        if inst.project_path == project_path {
-            println!("{} {} ({})",
-                t!(lang, "✓ Session already exists:", "✓ 会话已存在:"),
-                inst.title, inst.id
-            );
-            return Ok(());
+    let duplicate = instances.iter().position(|inst| inst.project_path == project_path);
+    if let Some(index) = duplicate.filter(|_| launch.existing != Existing::New) {
+        let inst = &instances[index];
+        if launch.existing == Existing::Error {
+            return Err(crate::Error::InvalidInput(format!(
+                "{} {} ({})",
+                t!(lang, "Session already exists:", "会话已存在:"),
+                inst.title,
+                inst.id
+            )));
         }
+        println!("{} {} ({})",
+            t!(lang, "✓ Session already exists:", "✓ 会话已存在:"),
+            inst.title, inst.id
+        );
+        return launch_added(lang, profile, &storage, instances.swap_remove(index), &launch).await;
     }
 
     // Create new instance
@@ -424,49 +671,152 @@ async fn handle_add(
         Instance::new(title.clone(), project_path.clone())
     };
 
+    if let Some(t) = &template {
+        instance.command = t.command.clone();
+        instance.tool = t.tool;
+    }
     if let Some(command) = cmd {
         instance.command = command.clone();
-        // NOTE: `tool` is legacy metadata; we no longer infer it from the command string.
-        // UI/status should rely on tags/labels and prompt detection instead.
+        // Picks the prompt detector's heuristics; the TUI dialog does the same.
+        instance.tool = crate::tmux::Tool::from_command(&command);
     }
+    instance.pre_command = pre.unwrap_or_default();
+    instance.post_attach_command = post_attach.unwrap_or_default();
+    if instance.tool == crate::tmux::Tool::Shell {
+        // No agent in the first window: take the tool from the primary
+        // window, or the first window that runs one.
+        let detected = match primary_window {
+            Some(i) => crate::tmux::Tool::from_command(&windows.windows[i].command),
+            None => windows
+                .windows
+                .iter()
+                .map(|w| crate::tmux::Tool::from_command(&w.command))
+                .find(|&tool| tool != crate::tmux::Tool::Shell)
+                .unwrap_or(crate::tmux::Tool::Shell),
+        };
+        instance.tool = detected;
+    }
+    instance.windows = windows.windows;
+    instance.primary_window = primary_window;
+    // Titles stay unique within a group, so one names a session.
+    instance.title = crate::session::titles::unique(&instances, &instance.group_path, &title);
 
     instances.push(instance.clone());
 
     // Save
     storage.save(&instances, &tree, &relationships).await?;
 
-    println!("{} {}", t!(lang, "✓ Added session:", "✓ 已添加会话:"), title);
+    println!("{} {}", t!(lang, "✓ Added session:", "✓ 已添加会话:"), instance.title);
     println!("  {}: {}", t!(lang, "Profile", "配置"), profile);
     println!("  {}: {}", t!(lang, "Path", "路径"), project_path.display());
     println!("  {}: {}", t!(lang, "Group", "分组"), instance.group_path);
     println!("  ID:      {}", instance.id);
 
+    launch_added(lang, profile, &storage, instance, &launch).await
+}
+
+/// `add --start/--attach`: start `inst` unless it is running, then attach
+/// to it. The start goes through `create_session`, which also records the
+/// session in the tmux cache. A failed attach exits with tmux's status.
+async fn launch_added(
+    lang: Language,
+    profile: &str,
+    storage: &Storage,
+    mut inst: Instance,
+    launch: &AddLaunch,
+) -> Result<()> {
+    if !launch.start && !launch.attach {
+        return Ok(());
+    }
+    let manager = Arc::new(TmuxManager::new(profile));
+    manager.refresh_cache().await?;
+
+    inst.init_tmux(manager.clone());
+    if !manager.session_exists(&inst.tmux_name()).unwrap_or(false) {
+        inst.start().await?;
+        save_launched(storage, &inst).await?;
+        println!("{} {}", t!(lang, "✓ Started session:", "✓ 已启动会话:"), inst.title);
+    }
+
+    if launch.attach {
+        crate::session::launch::send_post_attach(&manager, &inst).await;
+        inst.mark_accessed();
+        let Some(tmux) = inst.tmux() else {
+            return Ok(());
+        };
+        let status = tmux.attach_status().await?;
+        save_launched(storage, &inst).await?;
+        if !status.success() {
+            std::process::exit(status.code().unwrap_or(1));
+        }
+    }
     Ok(())
 }
 
-async fn handle_list(lang: Language, profile: &str, json: bool, all: bool) -> Result<()> {
+/// Write what starting and attaching changed on `inst` (its status and
+/// last access) over a fresh load, so edits made meanwhile, e.g. from the
+/// dashboard while attached, are kept.
+async fn save_launched(storage: &Storage, inst: &Instance) -> Result<()> {
+    let (mut instances, tree, relationships) = storage.load().await?;
+    if let Some(saved) = instances.iter_mut().find(|i| i.id == inst.id) {
+        saved.status = inst.status;
+        saved.status_since = inst.status_since;
+        saved.last_accessed_at = inst.last_accessed_at;
+    }
+    storage.save(&instances, &tree, &relationships).await
+}
+
+async fn handle_list(
+    lang: Language,
+    profile: &str,
+    json: bool,
+    all: bool,
+    label: Option<&str>,
+    broken: bool,
+) -> Result<()> {
     if all {
         let profiles = Storage::list_profiles().await?;
         for prof in profiles {
             println!("\n=== {}: {} ===", t!(lang, "Profile", "配置"), prof);
-            list_profile(lang, &prof, json).await?;
+            list_profile(lang, &prof, json, label, broken).await?;
         }
         return Ok(());
     }
 
-    list_profile(lang, profile, json).await
+    list_profile(lang, profile, json, label, broken).await
 }
 
-async fn list_profile(lang: Language, profile: &str, json: bool) -> Result<()> {
+async fn list_profile(
+    lang: Language,
+    profile: &str,
+    json: bool,
+    label: Option<&str>,
+    broken: bool,
+) -> Result<()> {
     let storage = Storage::new(profile).await?;
-    let (instances, _, _) = storage.load().await?;
+    let (mut instances, _, _) = storage.load().await?;
+    if let Some(label) = label {
+        instances.retain(|inst| inst.has_label(label));
+    }
+    if broken {
+        instances.retain(|inst| inst.path_missing());
+    }
 
     if instances.is_empty() {
         if !json {
-            if lang.is_zh() {
-                println!("在配置 '{}' 中未找到会话。", profile);
-            } else {
-                println!("No sessions found in profile '{}'.", profile);
+            match label {
+                _ if broken && lang.is_zh() => {
+                    println!("在配置 '{}' 中没有路径缺失的会话。", profile)
+                }
+                _ if broken => println!("No sessions with missing paths in profile '{}'.", profile),
+                Some(label) if lang.is_zh() => {
+                    println!("在配置 '{}' 中没有标签为 '{}' 的会话。", profile, label.trim())
+                }
+                Some(label) => {
+                    println!("No sessions labeled '{}' in profile '{}'.", label.trim(), profile)
+                }
+                None if lang.is_zh() => println!("在配置 '{}' 中未找到会话。", profile),
+                None => println!("No sessions found in profile '{}'.", profile),
             }
         }
         return Ok(());
@@ -487,14 +837,16 @@ async fn list_profile(lang: Language, profile: &str, json: bool) -> Result<()> {
 
         for inst in &instances {
             let path_str = inst.project_path.to_string_lossy();
-            let path_display = truncate(&path_str, 40);
-            let title_display = truncate(&inst.title, 20);
-            let group_display = truncate(&inst.group_path, 15);
+            let path_display = fit(&path_str, 40);
+            let title_display = fit(&inst.title, 20);
+            let group_display = fit(&inst.group_path, 15);
             let id_display = &inst.id[..inst.id.len().min(12)];
 
+            let badge = inst.label_badge().map(|b| format!(" {b}")).unwrap_or_default();
+
             println!(
-                "{:<20} {:<15} {:<40} {}",
-                title_display, group_display, path_display, id_display
+                "{} {} {} {}{}",
+                title_display, group_display, path_display, id_display, badge
             );
         }
 
@@ -508,19 +860,80 @@ async fn list_profile(lang: Language, profile: &str, json: bool) -> Result<()> {
     Ok(())
 }
 
-async fn handle_remove(lang: Language, profile: &str, identifier: &str) -> Result<()> {
+async fn handle_tree(
+    profile: &str,
+    format: &str,
+    group: Option<&str>,
+    opts: crate::cli::tree::TreeExportOptions,
+) -> Result<()> {
+    use crate::ui::tree::{build, nest_forks, TreeScope};
+
+    let format = crate::cli::tree::TreeFormat::parse(format).ok_or_else(|| {
+        crate::Error::InvalidInput(format!(
+            "unknown tree format '{format}' (expected markdown, json or text)"
+        ))
+    })?;
+
     let storage = Storage::new(profile).await?;
-    let (instances, tree, relationships) = storage.load().await?;
+    let (instances, groups, _) = storage.load().await?;
+    let group = group.map(|g| g.trim().trim_matches('/'));
+    if let Some(g) = group {
+        if groups.get_group(g).is_none() {
+            return Err(crate::Error::InvalidInput(format!("group not found: {g}")));
+        }
+    }
 
-    let (to_remove, to_keep): (Vec<_>, Vec<_>) = instances.into_iter().partition(|inst| {
-        inst.id == identifier || inst.id.starts_with(identifier) || inst.title == identifier
-    });
+    let sort = crate::config::ConfigFile::load()
+        .await
+        .ok()
+        .flatten()
+        .map(|c| c.tree_sort())
+        .unwrap_or_default();
+    let nodes = nest_forks(build(&instances, &groups, sort, TreeScope { group }), &instances);
+    print!("{}", crate::cli::tree::render(format, &nodes, &instances, opts)?);
+    Ok(())
+}
 
-    if to_remove.is_empty() {
-        return Err(crate::Error::SessionNotFound(identifier.to_string()));
+/// Refuse to rewrite a profile's storage while a TUI has it open, since
+/// the TUI's next save would put back what the command removed. With
+/// `force` the command goes ahead and gets the TUI's heartbeat back.
+fn guard_open_profile(
+    lang: Language,
+    profile: &str,
+    force: bool,
+) -> Result<Option<crate::session::heartbeat::Heartbeat>> {
+    let dir = Storage::profile_dir_for(profile)?;
+    let Some(tui) = crate::session::heartbeat::live_tui(&dir, chrono::Utc::now()) else {
+        return Ok(None);
+    };
+    if lang.is_zh() {
+        eprintln!("⚠  配置 '{}' 正在 agent-hand TUI 中打开 (pid {})", profile, tui.pid);
+        eprintln!("   TUI 下次保存时可能会撤销这次修改。");
+    } else {
+        eprintln!("⚠  Profile '{}' is open in an agent-hand TUI (pid {})", profile, tui.pid);
+        eprintln!("   Its next save can undo this change.");
+    }
+    if !force {
+        return Err(crate::Error::InvalidInput(
+            t!(
+                lang,
+                "close the TUI first, or re-run with --force",
+                "请先关闭 TUI，或加 --force 重新运行"
+            )
+            .to_string(),
+        ));
     }
+    Ok(Some(tui))
+}
+
+async fn handle_remove(lang: Language, profile: &str, identifier: &str, force: bool) -> Result<()> {
+    let storage = Storage::new(profile).await?;
+    let (mut instances, tree, relationships) = storage.load().await?;
 
-    let removed = &to_remove[0];
+    let index = crate::session::titles::find(&instances, identifier)?;
+    let open_tui = guard_open_profile(lang, profile, force)?;
+
+    let removed = instances.remove(index);
     let title = removed.title.clone();
 
     // Kill tmux session if exists
@@ -531,9 +944,24 @@ async fn handle_remove(lang: Language, profile: &str, identifier: &str) -> Resul
             eprintln!("{} {}", t!(lang, "Warning: failed to kill tmux session:", "警告: 无法终止 tmux 会话:"), e);
         }
     }
+    let _ = crate::tmux::env::clear_markers_for(&manager, &tmux_name).await;
 
     // Save
-    storage.save(&to_keep, &tree, &relationships).await?;
+    storage.save(&instances, &tree, &relationships).await?;
+
+    // Only once the session is gone from storage: a failed save keeps both.
+    if let Some(config) = &removed.mcp_config {
+        crate::claude::mcp::take_session_config(config);
+    }
+
+    // The open TUI drops it on its next tick, before it saves again.
+    if open_tui.is_some() {
+        crate::session::heartbeat::append_tombstones(
+            &Storage::profile_dir_for(profile)?,
+            std::slice::from_ref(&removed.id),
+            chrono::Utc::now(),
+        )?;
+    }
 
     println!("{} {} ({} '{}')",
         t!(lang, "✓ Removed session:", "✓ 已移除会话:"),
@@ -544,30 +972,106 @@ async fn handle_remove(lang: Language, profile: &str, identifier: &str) -> Resul
     Ok(())
 }
 
-async fn handle_status(lang: Language, profile: &str, verbose: bool, quiet: bool, json: bool) -> Result<()> {
+async fn handle_status(
+    lang: Language,
+    profile: &str,
+    verbose: bool,
+    quiet: bool,
+    json: bool,
+    watch: Option<u64>,
+) -> Result<()> {
+    use std::io::{IsTerminal, Write};
+
+    let manager = Arc::new(TmuxManager::new(profile));
+    let Some(secs) = watch else {
+        print!("{}", status_report(lang, profile, &manager, verbose, quiet, json).await?);
+        return Ok(());
+    };
+
+    // JSON streams one object per line; text redraws in place on a terminal.
+    let redraw = !json && std::io::stdout().is_terminal();
+    let interval = std::time::Duration::from_secs(secs.max(1));
+    if redraw {
+        print!("\x1b[?25l");
+    }
+
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    let mut drawn = 0usize;
+    let result = loop {
+        let frame = tokio::select! {
+            _ = &mut ctrl_c => break Ok(()),
+            frame = status_report(lang, profile, &manager, verbose, quiet, json) => frame,
+        };
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(e) => break Err(e),
+        };
+
+        let mut out = std::io::stdout().lock();
+        if redraw && drawn > 0 {
+            // Back to the first line of the previous frame, then clear below.
+            let _ = write!(out, "\x1b[{drawn}F\x1b[J");
+        }
+        let _ = write!(out, "{frame}");
+        let _ = out.flush();
+        drop(out);
+        drawn = frame.lines().count();
+
+        tokio::select! {
+            _ = &mut ctrl_c => break Ok(()),
+            _ = tokio::time::sleep(interval) => {}
+        }
+    };
+
+    if redraw {
+        print!("\x1b[?25h");
+        let _ = std::io::stdout().flush();
+    }
+    result
+}
+
+/// One `status` report, newline-terminated.
+async fn status_report(
+    lang: Language,
+    profile: &str,
+    manager: &Arc<TmuxManager>,
+    verbose: bool,
+    quiet: bool,
+    json: bool,
+) -> Result<String> {
     let storage = Storage::new(profile).await?;
     let (mut instances, _, _) = storage.load().await?;
 
     if instances.is_empty() {
-        if json {
-            println!(r#"{{"waiting": 0, "running": 0, "idle": 0, "error": 0, "total": 0}}"#);
-        } else if !quiet {
-            if lang.is_zh() {
-                println!("配置 '{}' 中没有会话。", profile);
-            } else {
-                println!("No sessions in profile '{}'.", profile);
-            }
-        }
-        return Ok(());
+        return Ok(if json {
+            concat!(
+                r#"{"waiting": 0, "running": 0, "idle": 0, "stopped": 0, "error": 0, "total": 0, "waiting_queue": []}"#,
+                "\n"
+            )
+            .to_string()
+        } else if quiet {
+            String::new()
+        } else if lang.is_zh() {
+            format!("配置 '{}' 中没有会话。\n", profile)
+        } else {
+            format!("No sessions in profile '{}'.\n", profile)
+        });
     }
 
     // Update statuses
-    let manager = Arc::new(TmuxManager::new(profile));
     manager.refresh_cache().await?;
 
+    let now = chrono::Utc::now();
     for inst in &mut instances {
         inst.init_tmux(manager.clone());
+        let prev = inst.status;
         let _ = inst.update_status().await;
+        // A wait that started just now; not saved, only used for the queue.
+        let waiting = crate::session::Status::Waiting;
+        if inst.status == waiting && prev != waiting {
+            inst.last_waiting_at = Some(now);
+        }
     }
 
     // Count by status
@@ -580,76 +1084,264 @@ async fn handle_status(lang: Language, profile: &str, verbose: bool, quiet: bool
             crate::session::Status::Idle => counts.idle += 1,
             crate::session::Status::Error => counts.error += 1,
             crate::session::Status::Starting => counts.idle += 1,
+            crate::session::Status::Stopped => counts.stopped += 1,
         }
     }
 
-    if json {
-        println!(
-            r#"{{"waiting": {}, "running": {}, "idle": {}, "error": {}, "total": {}}}"#,
-            counts.waiting, counts.running, counts.idle, counts.error, counts.total
-        );
+    let report = if json {
+        let queue: Vec<serde_json::Value> = waiting_queue(&instances)
+            .into_iter()
+            .map(|s| {
+                serde_json::json!({
+                    "id": s.id,
+                    "title": s.title,
+                    "waiting_since": waiting_since(s),
+                    "reason": s.waiting_reason,
+                })
+            })
+            .collect();
+        let value = serde_json::json!({
+            "waiting": counts.waiting,
+            "running": counts.running,
+            "idle": counts.idle,
+            "stopped": counts.stopped,
+            "error": counts.error,
+            "total": counts.total,
+            "waiting_queue": queue,
+        });
+        format!("{value}\n")
     } else if quiet {
-        println!("{}", counts.waiting);
+        format!("{}\n", counts.waiting)
     } else if verbose {
-        print_status_verbose(lang, &instances);
+        format_status_verbose(lang, &instances)
     } else {
-        println!(
-            "{} {} • {} {} • {} {}",
+        format!(
+            "{} {} • {} {} • {} {}\n",
             counts.waiting, t!(lang, "waiting", "等待中"),
             counts.running, t!(lang, "running", "运行中"),
             counts.idle, t!(lang, "idle", "空闲")
+        )
+    };
+
+    Ok(report)
+}
+
+async fn handle_stats(lang: Language, profile: &str, days: u32) -> Result<()> {
+    use crate::analytics::{summarize_by_session, ActivitySummary};
+
+    let days = days.max(1);
+    let logs = crate::analytics::load_recent_logs(profile, days).await?;
+    let sessions = summarize_by_session(&logs);
+    if sessions.is_empty() {
+        println!(
+            "{}",
+            t!(
+                lang,
+                "No activity recorded in this window",
+                "该时间段内没有活动记录"
+            )
         );
+        let enabled = crate::config::ConfigFile::load()
+            .await
+            .ok()
+            .flatten()
+            .is_some_and(|c| c.analytics_enabled());
+        if !enabled {
+            println!(
+                "{}",
+                t!(
+                    lang,
+                    "Analytics is off; turn it on with `agent-hand config set analytics.enabled true`",
+                    "分析功能未开启; 可用 `agent-hand config set analytics.enabled true` 开启"
+                )
+            );
+        }
+        return Ok(());
     }
 
+    if lang.is_zh() {
+        println!("最近 {days} 天的活动:");
+    } else {
+        println!("Activity over the last {days} day(s):");
+    }
+    println!(
+        "{:<32} {:>9} {:>7} {:>9}",
+        t!(lang, "SESSION", "会话"),
+        t!(lang, "TIME", "时长"),
+        t!(lang, "ENTERS", "进入"),
+        t!(lang, "SWITCHES", "切换")
+    );
+    let mut total = ActivitySummary::default();
+    for s in &sessions {
+        println!(
+            "{:<32} {:>9} {:>7} {:>9}",
+            truncate(&s.session_name, 32),
+            s.summary.format_duration(),
+            s.summary.total_enters,
+            s.summary.total_switches
+        );
+        total.total_duration_secs += s.summary.total_duration_secs;
+        total.total_enters += s.summary.total_enters;
+        total.total_switches += s.summary.total_switches;
+    }
+    println!(
+        "{:<32} {:>9} {:>7} {:>9}",
+        t!(lang, "TOTAL", "合计"),
+        total.format_duration(),
+        total.total_enters,
+        total.total_switches
+    );
     Ok(())
 }
 
-async fn handle_statusline(profile: &str) -> Result<()> {
-    use crate::session::Status;
+/// Probe like the dashboard does and print what changed as NDJSON.
+async fn handle_events(
+    profile: &str,
+    follow: bool,
+    interval: f64,
+    types: &[super::events::EventType],
+) -> Result<()> {
+    use super::events::{status_events, Snapshot};
+    use crate::session::status_engine::{probe_statuses, ProbeCache};
+    use std::io::Write;
 
-    // tmux status-left may spawn this command again before the previous run finishes.
-    // Prevent piling up shells/PTYs by allowing only one in-flight statusline instance.
-    {
-        use fs2::FileExt;
+    let cfg = crate::config::ConfigFile::load().await.ok().flatten();
+    let timing = cfg.as_ref().map(|c| c.status_timing()).unwrap_or_default();
+    let interval = crate::config::clamped_secs(
+        interval,
+        std::time::Duration::from_millis(250),
+        std::time::Duration::from_secs(2),
+    );
 
-        if let Ok(lock_path) = Storage::get_agent_hand_dir().map(|d| d.join("statusline.lock")) {
-            if let Ok(f) = std::fs::OpenOptions::new()
-                .create(true)
-                .write(true)
-                .open(lock_path)
-            {
-                if f.try_lock_exclusive().is_err() {
-                    println!("AH");
-                    return Ok(());
+    let storage = Storage::new(profile).await?;
+    let manager = TmuxManager::new(profile);
+    let mut cache = ProbeCache::default();
+    let mut seen: Option<Snapshot> = None;
+
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    loop {
+        let loaded = async {
+            let (instances, _, _) = storage.load().await?;
+            manager.refresh_cache().await?;
+            Ok::<_, crate::Error>(instances)
+        }
+        .await;
+        // With --follow a failed read (a save mid-rename, tmux restarting)
+        // costs one round rather than the stream.
+        let mut instances = match loaded {
+            Ok(instances) => instances,
+            Err(e) if follow => {
+                eprintln!("agent-hand events: {e}; retrying");
+                tokio::select! {
+                    _ = &mut ctrl_c => return Ok(()),
+                    _ = tokio::time::sleep(interval) => continue,
                 }
-                // keep file handle alive until function returns
-                let _statusline_lock = f;
             }
-        }
-    }
+            Err(e) => return Err(e),
+        };
+        probe_statuses(&mut instances, &manager, &mut cache, &timing).await;
 
-    let cfg = crate::config::ConfigFile::load().await.ok().flatten();
-    let ready_ttl_secs: i64 = cfg.as_ref().map(|c| c.ready_ttl_minutes()).unwrap_or(40) as i64 * 60;
+        let now = chrono::Utc::now();
+        let events = match &seen {
+            Some(prev) => prev.diff(&instances, now),
+            None => status_events(&instances, now),
+        };
+        seen = Some(Snapshot::of(&instances));
 
-    let storage = Storage::new(profile).await?;
-    let (mut instances, tree, relationships) = storage.load().await?;
+        let mut out = std::io::stdout().lock();
+        for event in &events {
+            if types.is_empty() || types.contains(&event.kind.event_type()) {
+                writeln!(out, "{}", serde_json::to_string(event)?)?;
+            }
+        }
+        out.flush()?;
+        drop(out);
 
-    if instances.is_empty() {
-        println!("AH");
-        return Ok(());
+        if !follow {
+            return Ok(());
+        }
+        tokio::select! {
+            _ = &mut ctrl_c => return Ok(()),
+            _ = tokio::time::sleep(interval) => {}
+        }
     }
+}
 
-    let manager = Arc::new(TmuxManager::new(profile));
-    manager.refresh_cache().await?;
+async fn handle_statusline(profile: &str, format: &str, template: Option<&str>) -> Result<()> {
+    use super::statusline::{probe_cache_path, StatuslineData, StatuslineFormat, PROBE_BUDGET};
+    use crate::session::status_engine::{probe_statuses, ProbeCache};
+    use crate::session::Status;
 
-    let now = chrono::Utc::now();
-    let mut dirty = false;
+    let format = StatuslineFormat::parse(format).ok_or_else(|| {
+        crate::Error::InvalidInput(format!(
+            "unknown format '{format}' (expected text, json or template)"
+        ))
+    })?;
+    if format == StatuslineFormat::Template && template.is_none() {
+        return Err(crate::Error::InvalidInput(
+            "--format template needs --template \"...\"".to_string(),
+        ));
+    }
+    let busy_line = if format == StatuslineFormat::Text { "AH" } else { "" };
 
-    for inst in &mut instances {
-        inst.init_tmux(manager.clone());
-        let prev = inst.status;
-        let _ = inst.update_status().await;
+    // tmux status-left may spawn this command again before the previous run finishes.
+    // Prevent piling up shells/PTYs by allowing only one in-flight statusline instance
+    // per format; the handle is held until the function returns.
+    let _statusline_lock = {
+        use fs2::FileExt;
+
+        let lock = Storage::get_agent_hand_dir()
+            .ok()
+            .map(|d| d.join(format.lock_name()))
+            .and_then(|path| {
+                std::fs::OpenOptions::new()
+                    .create(true)
+                    .truncate(false)
+                    .write(true)
+                    .open(path)
+                    .ok()
+            });
+        if let Some(f) = &lock {
+            if f.try_lock_exclusive().is_err() {
+                println!("{busy_line}");
+                return Ok(());
+            }
+        }
+        lock
+    };
+
+    let cfg = crate::config::ConfigFile::load().await.ok().flatten();
+    let ready_ttl_secs: i64 = cfg.as_ref().map(|c| c.ready_ttl_minutes()).unwrap_or(40) as i64 * 60;
+    let timing = cfg.as_ref().map(|c| c.status_timing()).unwrap_or_default();
+
+    let storage = Storage::new(profile).await?;
+    let (mut instances, tree, relationships) = storage.load().await?;
+
+    if instances.is_empty() && format == StatuslineFormat::Text {
+        println!("AH");
+        return Ok(());
+    }
+
+    let manager = Arc::new(TmuxManager::new(profile));
+    manager.refresh_cache().await?;
+
+    let before: Vec<Status> = instances.iter().map(|i| i.status).collect();
+    let cache_path = probe_cache_path(profile).ok();
+    let mut cache = cache_path.as_deref().map(ProbeCache::load).unwrap_or_default();
+    let _ = tokio::time::timeout(
+        PROBE_BUDGET,
+        probe_statuses(&mut instances, &manager, &mut cache, &timing),
+    )
+    .await;
+    if let Some(path) = &cache_path {
+        cache.save(path);
+    }
 
+    let now = chrono::Utc::now();
+    let mut dirty = false;
+
+    for (inst, prev) in instances.iter_mut().zip(before) {
         if inst.status == Status::Waiting && prev != Status::Waiting {
             inst.last_waiting_at = Some(now);
             dirty = true;
@@ -674,16 +1366,31 @@ async fn handle_statusline(profile: &str) -> Result<()> {
         inst.last_running_at
             .is_some_and(|t| now.signed_duration_since(t).num_seconds() < ready_ttl_secs)
     };
+    let stalled_for = |inst: &Instance| cache.stalled_for(inst, &timing, now);
+
+    if format != StatuslineFormat::Text {
+        let update = crate::update::available_update().await;
+        let data = StatuslineData::collect(&instances, now, is_ready, stalled_for, update);
+        match template {
+            Some(template) if format == StatuslineFormat::Template => {
+                println!("{}", data.render(template))
+            }
+            _ => println!("{}", serde_json::to_string(&data)?),
+        }
+        return Ok(());
+    }
 
     let mut waiting = 0usize;
     let mut ready = 0usize;
     let mut running = 0usize;
+    let mut stalled = 0usize;
     let mut idle = 0usize;
     let mut error = 0usize;
 
     for inst in &instances {
         match inst.status {
             Status::Waiting => waiting += 1,
+            Status::Running if stalled_for(inst).is_some() => stalled += 1,
             Status::Running => running += 1,
             Status::Idle => {
                 if is_ready(inst) {
@@ -694,6 +1401,7 @@ async fn handle_statusline(profile: &str) -> Result<()> {
             }
             Status::Error => error += 1,
             Status::Starting => idle += 1,
+            Status::Stopped => {}
         }
     }
 
@@ -707,10 +1415,8 @@ async fn handle_statusline(profile: &str) -> Result<()> {
     };
 
     let (target, priority_tmux) = if waiting > 0 {
-        instances
-            .iter()
-            .filter(|s| s.status == Status::Waiting)
-            .max_by_key(|s| s.last_waiting_at.unwrap_or(s.created_at))
+        waiting_queue(&instances)
+            .first()
             .map(|s| (format!("! {}", truncate(&s.title, 24)), Some(s.tmux_name())))
             .unwrap_or_else(|| (String::new(), None))
     } else {
@@ -724,7 +1430,7 @@ async fn handle_statusline(profile: &str) -> Result<()> {
 
     let _ = manager
         .set_environment_global(
-            "AGENTHAND_PRIORITY_SESSION",
+            crate::tmux::env::PRIORITY_SESSION,
             priority_tmux.as_deref().unwrap_or(""),
         )
         .await;
@@ -741,6 +1447,9 @@ async fn handle_statusline(profile: &str) -> Result<()> {
         running,
         idle
     );
+    if stalled > 0 {
+        line.push_str(&format!(" ⊘{}", stalled));
+    }
     if error > 0 {
         line.push_str(&format!(" ✕{}", error));
     }
@@ -820,11 +1529,10 @@ async fn handle_jump(profile: &str) -> Result<()> {
             .is_some_and(|t| now.signed_duration_since(t).num_seconds() < ready_ttl_secs)
     };
 
-    // Priority 1: Waiting sessions (jump to newest - highest urgency)
-    let waiting_target = instances
-        .iter()
-        .filter(|s| s.status == Status::Waiting && s.tmux_name() != current_session)
-        .max_by_key(|s| s.last_waiting_at.unwrap_or(s.created_at));
+    // Priority 1: Waiting sessions (longest wait first, as in the TUI)
+    let waiting_target = waiting_queue(&instances)
+        .into_iter()
+        .find(|s| s.tmux_name() != current_session);
 
     // Priority 2: Ready sessions (round-robin rotation)
     let ready_target = if waiting_target.is_none() {
@@ -907,149 +1615,1679 @@ async fn handle_jump(profile: &str) -> Result<()> {
     Ok(())
 }
 
-async fn handle_session(lang: Language, profile: &str, action: SessionAction) -> Result<()> {
+async fn handle_session(lang: Language, profile: &str, action: SessionAction) -> Result<()> {
+    let storage = Storage::new(profile).await?;
+    let (mut instances, tree, relationships) = storage.load().await?;
+    let manager = Arc::new(TmuxManager::new(profile));
+
+    match action {
+        SessionAction::Start { id } => {
+            let inst = find_session(&mut instances, &id)?;
+            let title = inst.title.clone();
+            inst.init_tmux(manager.clone());
+            inst.start().await?;
+            storage.save(&instances, &tree, &relationships).await?;
+            println!("{} {}", t!(lang, "✓ Started session:", "✓ 已启动会话:"), title);
+        }
+
+        SessionAction::Stop { id } => {
+            let inst = find_session(&mut instances, &id)?;
+            let title = inst.title.clone();
+            inst.init_tmux(manager.clone());
+            inst.stop().await?;
+            storage.save(&instances, &tree, &relationships).await?;
+            println!("{} {}", t!(lang, "✓ Stopped session:", "✓ 已停止会话:"), title);
+        }
+
+        SessionAction::Restart { id } => {
+            let inst = find_session(&mut instances, &id)?;
+            let title = inst.title.clone();
+            inst.init_tmux(manager.clone());
+            inst.stop().await?;
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+            inst.start().await?;
+            storage.save(&instances, &tree, &relationships).await?;
+            println!("{} {}", t!(lang, "✓ Restarted session:", "✓ 已重启会话:"), title);
+        }
+
+        SessionAction::Resume { id } => {
+            let cfg = crate::config::ConfigFile::load()
+                .await
+                .ok()
+                .flatten()
+                .unwrap_or_default();
+            let inst = find_session(&mut instances, &id)?;
+            let title = inst.title.clone();
+            let resume = inst
+                .cli_session_id()
+                .and_then(|sid| inst.resume_command(sid, &cfg));
+            let launch = resume.as_deref().and_then(|cmd| inst.launch_command(Some(cmd)));
+            inst.init_tmux(manager.clone());
+            manager.refresh_cache().await?;
+            if manager.session_exists(&inst.tmux_name()).unwrap_or(false) {
+                inst.stop().await?;
+                tokio::time::sleep(tokio::time::Duration::from_millis(300)).await;
+            }
+            match resume {
+                Some(cmd) => {
+                    manager
+                        .create_session(
+                            &inst.tmux_name(),
+                            &inst.project_path.to_string_lossy(),
+                            launch.as_deref(),
+                            Some(&title),
+                        )
+                        .await?;
+                    crate::session::launch::open_windows(&manager, inst).await;
+                    inst.status = crate::session::Status::Idle;
+                    println!("{} {} ({})", t!(lang, "✓ Resumed session:", "✓ 已恢复会话:"), title, cmd);
+                }
+                None => {
+                    inst.start().await?;
+                    println!("{} {}", t!(lang, "✓ Restarted session:", "✓ 已重启会话:"), title);
+                    println!(
+                        "{}",
+                        t!(
+                            lang,
+                            "  No CLI session ID was detected, so the conversation was not resumed.",
+                            "  未检测到 CLI 会话 ID，未恢复之前的对话。"
+                        )
+                    );
+                }
+            }
+            storage.save(&instances, &tree, &relationships).await?;
+        }
+
+        SessionAction::Attach { id } => {
+            let inst = find_session(&mut instances, &id)?;
+            inst.init_tmux(manager.clone());
+            crate::session::launch::send_post_attach(&manager, inst).await;
+            inst.attach().await?;
+            storage.save(&instances, &tree, &relationships).await?;
+        }
+
+        SessionAction::Compare { left, right } => {
+            let names = [&left, &right]
+                .into_iter()
+                .map(|id| find_session(&mut instances, id).map(|inst| inst.tmux_name()))
+                .collect::<Result<Vec<_>>>()?;
+            if names[0] == names[1] {
+                return Err(crate::Error::InvalidInput(
+                    t!(lang, "Compare needs two different sessions", "对比需要两个不同的会话")
+                        .to_string(),
+                ));
+            }
+            manager.refresh_cache().await?;
+            for id in [&left, &right] {
+                let inst = find_session(&mut instances, id)?;
+                if !manager.session_exists(&inst.tmux_name()).unwrap_or(false) {
+                    inst.init_tmux(manager.clone());
+                    inst.start().await?;
+                }
+                inst.mark_accessed();
+            }
+            storage.save(&instances, &tree, &relationships).await?;
+
+            let view = manager.create_compare_session(&names[0], &names[1]).await?;
+            let attached = manager.attach_session(&view).await;
+            let _ = manager.kill_session(&view).await;
+            attached?;
+        }
+
+        SessionAction::Logs {
+            id,
+            lines,
+            full: _,
+            out,
+            raw,
+        } => {
+            let inst = find_session(&mut instances, &id)?;
+            let name = inst.tmux_name();
+            manager.refresh_cache().await?;
+            if !manager.session_exists(&name).unwrap_or(false) {
+                return Err(crate::Error::InvalidInput(format!(
+                    "{} {}",
+                    t!(lang, "Session is not running:", "会话未运行:"),
+                    inst.title
+                )));
+            }
+            let mut text = manager.capture_history(&name, lines, raw).await?;
+            if !raw {
+                text = crate::tmux::strip_ansi(&text);
+            }
+            match out {
+                Some(path) => {
+                    std::fs::write(&path, &text)?;
+                    eprintln!(
+                        "{} {} {} → {}",
+                        t!(lang, "✓ Saved", "✓ 已保存"),
+                        text.lines().count(),
+                        t!(lang, "lines", "行"),
+                        path
+                    );
+                }
+                None => print!("{text}"),
+            }
+        }
+
+        SessionAction::Show { id, json } => {
+            let inst = if let Some(id_str) = &id {
+                find_session(&mut instances, id_str)?
+            } else {
+                return Err(crate::Error::InvalidInput(
+                    t!(lang, "Auto-detection not yet implemented", "自动检测尚未实现").to_string(),
+                ));
+            };
+
+            // tmux's last activity in the session, which the reaper goes by.
+            let last_activity = match manager.refresh_cache().await {
+                Ok(()) => manager.session_activity(&inst.tmux_name()),
+                Err(_) => None,
+            };
+            // PTY FDs held by the session's processes; the TUI only scans with `[ptmx] enabled`.
+            let ptmx_count = match last_activity {
+                Some(_) => {
+                    crate::tmux::ptmx::scan_session_ptmx(manager.server_name(), &inst.tmux_name())
+                        .await
+                }
+                None => 0,
+            };
+
+            if json {
+                let mut value = serde_json::to_value(&*inst)?;
+                value["tmux_version"] = manager
+                    .version()
+                    .map_or(serde_json::Value::Null, |v| v.raw.clone().into());
+                value["last_activity"] = last_activity.map_or(serde_json::Value::Null, Into::into);
+                value["ptmx_count"] = ptmx_count.into();
+                println!("{}", serde_json::to_string_pretty(&value)?);
+                return Ok(());
+            }
+
+            println!("{}: {}", t!(lang, "Session", "会话"), inst.title);
+            println!("  ID:      {}", inst.id);
+            println!("  {}: {}", t!(lang, "Path", "路径"), inst.project_path.display());
+            println!("  {}: {}", t!(lang, "Group", "分组"), inst.group_path);
+            println!("  {}: {:?}", t!(lang, "Status", "状态"), inst.status);
+            if let Some(sid) = inst.cli_session_id() {
+                println!("  {}: {sid} ({})", t!(lang, "CLI session", "CLI 会话"), inst.tool);
+            }
+            if let Some(activity) = last_activity.filter(|&at| at > 0) {
+                let idle = crate::session::reaper::idle_since(activity, chrono::Utc::now().timestamp());
+                println!(
+                    "  {}: {} {}",
+                    t!(lang, "Last activity", "最后活动"),
+                    format_age(chrono::Duration::seconds(idle.as_secs() as i64)),
+                    t!(lang, "ago", "前")
+                );
+            }
+            if ptmx_count > 0 {
+                println!("  {}: {ptmx_count}", t!(lang, "PTY FDs", "PTY 句柄"));
+            }
+            if !inst.label.trim().is_empty() {
+                println!(
+                    "  {}: {} ({})",
+                    t!(lang, "Label", "标签"),
+                    inst.label,
+                    inst.label_color.name()
+                );
+            }
+            if let Some(origin) = &inst.adopted_from {
+                println!("  {}: tmux {origin}", t!(lang, "Adopted from", "采用自"));
+            }
+            if inst.autostart {
+                println!("  {}: on", t!(lang, "Autostart", "自动启动"));
+            }
+            if inst.keep_alive {
+                println!("  {}: on", t!(lang, "Keep alive", "保持运行"));
+            }
+            println!("  {}: {}", t!(lang, "Created", "创建时间"), inst.created_at);
+        }
+
+        SessionAction::Send {
+            id,
+            text,
+            start,
+            no_enter,
+        } => {
+            use crate::session::wrapup::probe_status;
+            // Wait after sending before reading the agent's status.
+            const SETTLE: tokio::time::Duration = tokio::time::Duration::from_millis(1500);
+            // Longest wait for a freshly started agent to show its prompt.
+            const STARTUP: tokio::time::Duration = tokio::time::Duration::from_secs(15);
+
+            let inst = find_session(&mut instances, &id)?;
+            let name = inst.tmux_name();
+            manager.refresh_cache().await?;
+            let running = manager.session_exists(&name).unwrap_or(false);
+            if !running && !start {
+                return Err(crate::Error::InvalidInput(format!(
+                    "{} {}",
+                    t!(
+                        lang,
+                        "Session is not running (pass --start to start it):",
+                        "会话未运行（传入 --start 以启动）:"
+                    ),
+                    inst.title
+                )));
+            }
+            if !running {
+                inst.init_tmux(manager.clone());
+                inst.start().await?;
+            }
+            let inst = inst.clone();
+            if !running {
+                storage.save(&instances, &tree, &relationships).await?;
+                let started = tokio::time::Instant::now();
+                while started.elapsed() < STARTUP
+                    && probe_status(&manager, &inst).await != crate::session::Status::Waiting
+                {
+                    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+                }
+            }
+
+            manager.type_text(&name, text.trim_end_matches('\n')).await?;
+            if !no_enter {
+                manager.send_raw_keys(&name, "Enter").await?;
+            }
+            tokio::time::sleep(SETTLE).await;
+            let status = format!("{:?}", probe_status(&manager, &inst).await).to_lowercase();
+            println!(
+                "{} {} ({}: {status})",
+                t!(lang, "✓ Sent to", "✓ 已发送到"),
+                inst.title,
+                t!(lang, "status", "状态"),
+            );
+        }
+
+        SessionAction::Forward {
+            src,
+            dst,
+            lines,
+            start,
+        } => {
+            use crate::session::forward::{forward_payload, DEFAULT_FORWARD_LINES};
+            use crate::session::wrapup::probe_status;
+            // Longest wait for a freshly started agent to show its prompt.
+            const STARTUP: tokio::time::Duration = tokio::time::Duration::from_secs(15);
+
+            let lines = match lines {
+                Some(n) => n.max(1),
+                None => crate::config::ConfigFile::load()
+                    .await
+                    .ok()
+                    .flatten()
+                    .map_or(DEFAULT_FORWARD_LINES, |c| c.forward_lines()),
+            };
+            let source = find_session(&mut instances, &src)?.clone();
+            manager.refresh_cache().await?;
+            if !manager.session_exists(&source.tmux_name()).unwrap_or(false) {
+                return Err(crate::Error::InvalidInput(format!(
+                    "{} {}",
+                    t!(lang, "Source session is not running:", "源会话未运行:"),
+                    source.title
+                )));
+            }
+            let content = manager.capture_pane(&source.tmux_target(), lines).await?;
+            let payload = forward_payload(&source.title, &content, lines).ok_or_else(|| {
+                crate::Error::InvalidInput(format!(
+                    "{} {}",
+                    t!(lang, "No output to forward from", "没有可转发的输出:"),
+                    source.title
+                ))
+            })?;
+
+            let target = find_session(&mut instances, &dst)?;
+            if target.id == source.id {
+                return Err(crate::Error::InvalidInput(
+                    t!(lang, "Source and target are the same session", "源会话和目标会话相同")
+                        .to_string(),
+                ));
+            }
+            let name = target.tmux_name();
+            let running = manager.session_exists(&name).unwrap_or(false);
+            if !running && !start {
+                return Err(crate::Error::InvalidInput(format!(
+                    "{} {}",
+                    t!(
+                        lang,
+                        "Target session is not running (pass --start to start it):",
+                        "目标会话未运行（传入 --start 以启动）:"
+                    ),
+                    target.title
+                )));
+            }
+            if !running {
+                target.init_tmux(manager.clone());
+                target.start().await?;
+            }
+            let target = target.clone();
+            if !running {
+                storage.save(&instances, &tree, &relationships).await?;
+                let started = tokio::time::Instant::now();
+                while started.elapsed() < STARTUP
+                    && probe_status(&manager, &target).await != crate::session::Status::Waiting
+                {
+                    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+                }
+            }
+
+            manager.type_text(&name, &payload).await?;
+            println!(
+                "{} {} → {} ({} {})",
+                t!(lang, "✓ Forwarded", "✓ 已转发"),
+                source.title,
+                target.title,
+                payload.lines().count() - 2,
+                t!(lang, "lines", "行"),
+            );
+        }
+
+        SessionAction::Autostart { id, enabled } => {
+            let inst = find_session(&mut instances, &id)?;
+            inst.autostart = enabled;
+            let title = inst.title.clone();
+            storage.save(&instances, &tree, &relationships).await?;
+            if enabled {
+                println!("{} {}", t!(lang, "✓ Autostart on:", "✓ 已开启自动启动:"), title);
+            } else {
+                println!("{} {}", t!(lang, "✓ Autostart off:", "✓ 已关闭自动启动:"), title);
+            }
+        }
+
+        SessionAction::Set {
+            id,
+            label,
+            label_color,
+            notify,
+            keep_alive,
+        } => {
+            if label.is_none() && label_color.is_none() && notify.is_none() && keep_alive.is_none() {
+                return Err(crate::Error::InvalidInput(
+                    t!(
+                        lang,
+                        "Nothing to set; pass --label, --label-color, --notify and/or --keep-alive",
+                        "没有要设置的内容; 请传入 --label、--label-color、--notify 和/或 --keep-alive"
+                    )
+                    .to_string(),
+                ));
+            }
+            let labeled = label.is_some() || label_color.is_some();
+            let color = match label_color.as_deref() {
+                Some(name) => Some(crate::session::LabelColor::parse(name).ok_or_else(|| {
+                    crate::Error::InvalidInput(format!(
+                        "{} {name} (gray, magenta, cyan, green, yellow, red, blue)",
+                        t!(lang, "Unknown label color:", "未知标签颜色:")
+                    ))
+                })?),
+                None => None,
+            };
+
+            let inst = find_session(&mut instances, &id)?;
+            if let Some(label) = label {
+                inst.label = label.trim().to_string();
+                if inst.label.is_empty() {
+                    inst.label_color = crate::session::LabelColor::default();
+                }
+            }
+            if let Some(color) = color {
+                inst.label_color = color;
+            }
+            if let Some(notify) = notify {
+                inst.mute_notifications = !notify;
+            }
+            if let Some(keep_alive) = keep_alive {
+                inst.keep_alive = keep_alive;
+            }
+            let title = inst.title.clone();
+            let badge = inst.label_badge();
+            storage.save(&instances, &tree, &relationships).await?;
+            if labeled {
+                match badge {
+                    Some(badge) => {
+                        println!("{} {title} {badge}", t!(lang, "✓ Labeled session:", "✓ 已设置会话标签:"))
+                    }
+                    None => {
+                        println!("{} {title}", t!(lang, "✓ Cleared label:", "✓ 已清除标签:"))
+                    }
+                }
+            }
+            match notify {
+                Some(true) => println!(
+                    "{} {title}",
+                    t!(lang, "✓ Desktop notifications on:", "✓ 已开启桌面通知:")
+                ),
+                Some(false) => println!(
+                    "{} {title}",
+                    t!(lang, "✓ Desktop notifications off:", "✓ 已关闭桌面通知:")
+                ),
+                None => {}
+            }
+            match keep_alive {
+                Some(true) => println!(
+                    "{} {title}",
+                    t!(lang, "✓ Exempt from the idle reaper:", "✓ 已免于空闲回收:")
+                ),
+                Some(false) => println!(
+                    "{} {title}",
+                    t!(lang, "✓ No longer exempt from the idle reaper:", "✓ 已取消空闲回收豁免:")
+                ),
+                None => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn find_template(
+    lang: Language,
+    name: &str,
+) -> Result<crate::session::templates::SessionTemplate> {
+    let store = crate::session::templates::TemplateStore::load().await?;
+    store.get(name).cloned().ok_or_else(|| {
+        crate::Error::InvalidInput(format!(
+            "{} {}",
+            t!(lang, "Template not found:", "未找到模板:"),
+            name
+        ))
+    })
+}
+
+async fn handle_template(lang: Language, profile: &str, action: TemplateAction) -> Result<()> {
+    use crate::session::templates::{SessionTemplate, TemplateStore};
+
+    let mut store = TemplateStore::load().await?;
+    match action {
+        TemplateAction::Save { name, from, title } => {
+            let storage = Storage::new(profile).await?;
+            let (mut instances, _, _) = storage.load().await?;
+            let inst = find_session(&mut instances, &from)?;
+            let mut template = SessionTemplate::from_instance(&name, inst);
+            if let Some(title) = title {
+                template.title = title;
+            }
+            let replaced = store.upsert(template);
+            store.save().await?;
+            let msg = if replaced {
+                t!(lang, "✓ Updated template:", "✓ 已更新模板:")
+            } else {
+                t!(lang, "✓ Saved template:", "✓ 已保存模板:")
+            };
+            println!("{} {}", msg, name);
+        }
+
+        TemplateAction::List { json } => {
+            if json {
+                println!("{}", serde_json::to_string_pretty(&store.templates)?);
+            } else if store.templates.is_empty() {
+                println!("{}", t!(lang, "No templates.", "没有模板。"));
+            } else {
+                println!(
+                    "{:<16} {:<20} {:<24} {}",
+                    t!(lang, "NAME", "名称"),
+                    t!(lang, "TITLE", "标题"),
+                    t!(lang, "COMMAND", "命令"),
+                    t!(lang, "GROUP", "分组"),
+                );
+                for t in &store.templates {
+                    let command = if t.command.is_empty() {
+                        t.tool.to_string()
+                    } else {
+                        t.command.clone()
+                    };
+                    println!(
+                        "{:<16} {:<20} {:<24} {}",
+                        truncate(&t.name, 16),
+                        truncate(&t.title, 20),
+                        truncate(&command, 24),
+                        t.group_path
+                    );
+                }
+            }
+        }
+
+        TemplateAction::Delete { name } => {
+            if !store.remove(&name) {
+                return Err(crate::Error::InvalidInput(format!(
+                    "{} {}",
+                    t!(lang, "Template not found:", "未找到模板:"),
+                    name
+                )));
+            }
+            store.save().await?;
+            println!("{} {}", t!(lang, "✓ Deleted template:", "✓ 已删除模板:"), name);
+        }
+    }
+    Ok(())
+}
+
+async fn handle_group(lang: Language, profile: &str, action: GroupAction) -> Result<()> {
+    use crate::session::groups_ops;
+
+    match action {
+        GroupAction::List { json } => {
+            let storage = Storage::new(profile).await?;
+            let (instances, tree, _) = storage.load().await?;
+            let rows = groups_ops::group_rows(&tree, &instances);
+            if json {
+                println!("{}", serde_json::to_string_pretty(&rows)?);
+                return Ok(());
+            }
+            if rows.is_empty() {
+                println!("{}", t!(lang, "No groups", "暂无分组"));
+            }
+            for row in &rows {
+                println!("{}{} ({})", "  ".repeat(row.depth), row.name, row.total);
+            }
+            let ungrouped = instances.iter().filter(|s| s.group_path.is_empty()).count();
+            if ungrouped > 0 {
+                println!("{} ({})", t!(lang, "(ungrouped)", "（未分组）"), ungrouped);
+            }
+        }
+        GroupAction::Create { path } => {
+            let path = path.trim();
+            let storage = Storage::new(profile).await?;
+            let (instances, mut tree, relationships) = storage.load().await?;
+            if tree.get_group(path).is_some() {
+                println!("{} {}", t!(lang, "Group already exists:", "分组已存在:"), path);
+                return Ok(());
+            }
+            if !groups_ops::create_group(&mut tree, path) {
+                return Err(crate::Error::InvalidInput(
+                    t!(lang, "Group path is empty", "分组路径为空").to_string(),
+                ));
+            }
+            storage.save(&instances, &tree, &relationships).await?;
+            println!("{} {}", t!(lang, "✓ Created group:", "✓ 已创建分组:"), path);
+        }
+        GroupAction::Rename { old, new } => {
+            let (old, new) = (old.trim(), new.trim());
+            let storage = Storage::new(profile).await?;
+            let (mut instances, mut tree, relationships) = storage.load().await?;
+            if tree.get_group(old).is_none() {
+                return Err(crate::Error::InvalidInput(format!(
+                    "{} {}",
+                    t!(lang, "Group not found:", "未找到分组:"),
+                    old
+                )));
+            }
+            if tree.get_group(new).is_some() {
+                return Err(crate::Error::InvalidInput(format!(
+                    "{} {}",
+                    t!(lang, "Group already exists:", "分组已存在:"),
+                    new
+                )));
+            }
+            if groups_ops::rename_group(&mut instances, &mut tree, old, new) {
+                storage.save(&instances, &tree, &relationships).await?;
+            }
+            println!("{} {} → {}", t!(lang, "✓ Renamed group:", "✓ 已重命名分组:"), old, new);
+        }
+        GroupAction::Delete {
+            path,
+            with_sessions,
+            keep_sessions: _,
+            force,
+        } => {
+            let path = path.trim();
+            let storage = Storage::new(profile).await?;
+            let (mut instances, mut tree, relationships) = storage.load().await?;
+            let has_sessions = instances
+                .iter()
+                .any(|s| groups_ops::in_group(&s.group_path, path));
+            if path.is_empty() || (tree.get_group(path).is_none() && !has_sessions) {
+                return Err(crate::Error::InvalidInput(format!(
+                    "{} {}",
+                    t!(lang, "Group not found:", "未找到分组:"),
+                    path
+                )));
+            }
+
+            if !with_sessions {
+                let deleted = groups_ops::delete_group_keep_sessions(&mut instances, &mut tree, path);
+                storage.save(&instances, &tree, &relationships).await?;
+                println!(
+                    "{} {} ({} {})",
+                    t!(lang, "✓ Deleted group:", "✓ 已删除分组:"),
+                    path,
+                    deleted.regrouped.len(),
+                    t!(lang, "sessions ungrouped", "个会话移出分组")
+                );
+                return Ok(());
+            }
+
+            let open_tui = if has_sessions {
+                guard_open_profile(lang, profile, force)?
+            } else {
+                None
+            };
+            let deleted = groups_ops::delete_group_with_sessions(&mut instances, &mut tree, path);
+            let manager = TmuxManager::new(profile);
+            for inst in &deleted.sessions {
+                let tmux_name = inst.tmux_name();
+                if manager.session_exists(&tmux_name).unwrap_or(false) {
+                    if let Err(e) = manager.kill_session(&tmux_name).await {
+                        eprintln!("{} {}", t!(lang, "Warning: failed to kill tmux session:", "警告: 无法终止 tmux 会话:"), e);
+                    }
+                }
+                let _ = crate::tmux::env::clear_markers_for(&manager, &tmux_name).await;
+            }
+            storage.save(&instances, &tree, &relationships).await?;
+            for config in deleted.sessions.iter().filter_map(|s| s.mcp_config.as_ref()) {
+                crate::claude::mcp::take_session_config(config);
+            }
+
+            // The open TUI drops these on its next tick, before it saves again.
+            if open_tui.is_some() {
+                let ids: Vec<String> = deleted.sessions.iter().map(|s| s.id.clone()).collect();
+                crate::session::heartbeat::append_tombstones(
+                    &Storage::profile_dir_for(profile)?,
+                    &ids,
+                    chrono::Utc::now(),
+                )?;
+            }
+            println!(
+                "{} {} ({} {})",
+                t!(lang, "✓ Deleted group:", "✓ 已删除分组:"),
+                path,
+                deleted.sessions.len(),
+                t!(lang, "sessions removed", "个会话已删除")
+            );
+        }
+        GroupAction::Move { path, new_parent } => {
+            let storage = Storage::new(profile).await?;
+            let (mut instances, mut tree, relationships) = storage.load().await?;
+            let new_path = tree.move_group(&path, &new_parent)?;
+            if new_path == path.trim() {
+                println!("{} {}", t!(lang, "Group is already there:", "分组已在该位置:"), new_path);
+                return Ok(());
+            }
+            crate::session::rename_sessions_group(&mut instances, path.trim(), &new_path);
+            storage.save(&instances, &tree, &relationships).await?;
+            println!(
+                "{} {} → {}",
+                t!(lang, "✓ Moved group:", "✓ 已移动分组:"),
+                path.trim(),
+                new_path
+            );
+        }
+        GroupAction::Notes { path, set, show: _ } => {
+            let path = path.trim();
+            let storage = Storage::new(profile).await?;
+            let (instances, mut tree, relationships) = storage.load().await?;
+            let Some(notes) = tree.get_group(path).map(|g| g.notes.clone()) else {
+                return Err(crate::Error::InvalidInput(format!(
+                    "{} {}",
+                    t!(lang, "Group not found:", "未找到分组:"),
+                    path
+                )));
+            };
+            match set {
+                None if notes.is_empty() => {
+                    println!("{}", t!(lang, "(no notes)", "（无备注）"));
+                }
+                None => println!("{notes}"),
+                Some(text) => {
+                    tree.set_notes(path, crate::session::notes_from_input(&text));
+                    storage.save(&instances, &tree, &relationships).await?;
+                    println!("{} {}", t!(lang, "✓ Updated notes for group:", "✓ 已更新分组备注:"), path);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn handle_profile(lang: Language, action: ProfileAction) -> Result<()> {
+    match action {
+        ProfileAction::List => {
+            let profiles = Storage::list_profiles().await?;
+            println!("{}:", t!(lang, "Profiles", "配置列表"));
+            for prof in profiles {
+                println!("  {}", prof);
+            }
+        }
+
+        ProfileAction::Create { name } => {
+            Storage::create_profile(&name).await?;
+            println!("{} {}", t!(lang, "✓ Created profile:", "✓ 已创建配置:"), name);
+        }
+
+        ProfileAction::Delete { name, force } => {
+            guard_open_profile(lang, &name, force)?;
+            Storage::delete_profile(&name).await?;
+            println!("{} {}", t!(lang, "✓ Deleted profile:", "✓ 已删除配置:"), name);
+        }
+
+        ProfileAction::Restore {
+            name,
+            generation,
+            list,
+            force,
+        } => {
+            if !Storage::profile_dir_for(&name)?.exists() {
+                return Err(crate::Error::profile(format!("Profile '{}' not found", name)));
+            }
+            let storage = Storage::new(&name).await?;
+
+            if list {
+                let backups = storage.list_backups().await;
+                if backups.is_empty() {
+                    println!("{}", t!(lang, "No backups", "暂无备份"));
+                    return Ok(());
+                }
+                println!(
+                    "{:<4} {:<20} {:>8} {:>9}",
+                    t!(lang, "GEN", "代"),
+                    t!(lang, "UPDATED", "更新时间"),
+                    t!(lang, "AGE", "时间"),
+                    t!(lang, "SESSIONS", "会话")
+                );
+                let now = chrono::Utc::now();
+                for b in backups {
+                    match b.contents {
+                        Ok((updated_at, count)) => println!(
+                            "{:<4} {:<20} {:>8} {:>9}",
+                            b.generation,
+                            updated_at
+                                .with_timezone(&chrono::Local)
+                                .format("%Y-%m-%d %H:%M:%S"),
+                            format_age(now - updated_at),
+                            count
+                        ),
+                        Err(e) => println!(
+                            "{:<4} {} {}",
+                            b.generation,
+                            t!(lang, "unreadable:", "无法解析:"),
+                            e
+                        ),
+                    }
+                }
+                return Ok(());
+            }
+
+            guard_open_profile(lang, &name, force)?;
+            let data = storage.restore_backup(generation).await?;
+            println!(
+                "{} {} ({} {}, {} {})",
+                t!(lang, "✓ Restored profile from backup:", "✓ 已从备份恢复配置:"),
+                name,
+                data.instances.len(),
+                t!(lang, "sessions", "个会话"),
+                t!(lang, "saved", "保存于"),
+                data.updated_at
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M:%S")
+            );
+            println!(
+                "{}",
+                t!(
+                    lang,
+                    "  The previous sessions.json is now backup 1.",
+                    "  原 sessions.json 已保存为备份 1。"
+                )
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_snapshot(lang: Language, profile: &str, keep: usize, action: SnapshotAction) -> Result<()> {
+    use crate::session::snapshot;
+
+    let profile_dir = Storage::profile_dir_for(profile)?;
+
+    match action {
+        SnapshotAction::Create { label } => {
+            let snap = snapshot::create_snapshot(&profile_dir, label.as_deref(), false)?;
+            snapshot::prune_snapshots(&profile_dir, keep)?;
+            println!(
+                "{} {} ({} {})",
+                t!(lang, "✓ Created snapshot:", "✓ 已创建快照:"),
+                snap.id,
+                snap.session_count,
+                t!(lang, "sessions", "个会话")
+            );
+        }
+
+        SnapshotAction::List => {
+            let snaps = snapshot::list_snapshots(&profile_dir)?;
+            if snaps.is_empty() {
+                println!("{}", t!(lang, "No snapshots", "暂无快照"));
+                return Ok(());
+            }
+            println!(
+                "{:<40} {:<24} {:>8} {:>10} {:>9}",
+                "ID",
+                t!(lang, "LABEL", "标签"),
+                t!(lang, "AGE", "时间"),
+                t!(lang, "SIZE", "大小"),
+                t!(lang, "SESSIONS", "会话")
+            );
+            let now = chrono::Utc::now();
+            for s in snaps {
+                let label = match (&s.label, s.automatic) {
+                    (Some(l), _) => l.clone(),
+                    (None, true) => t!(lang, "(auto)", "(自动)").to_string(),
+                    (None, false) => "-".to_string(),
+                };
+                println!(
+                    "{:<40} {:<24} {:>8} {:>10} {:>9}",
+                    truncate(&s.id, 40),
+                    truncate(&label, 24),
+                    format_age(now - s.created_at),
+                    format_size(s.total_size()),
+                    s.session_count
+                );
+            }
+        }
+
+        SnapshotAction::Restore { id, dry_run, force } => {
+            if !dry_run {
+                guard_open_profile(lang, profile, force)?;
+            }
+            let report = snapshot::restore_snapshot(&profile_dir, &id, dry_run)?;
+            if dry_run {
+                println!(
+                    "{} {}",
+                    t!(lang, "Dry run — would restore:", "试运行 — 将恢复:"),
+                    report.restored.id
+                );
+            } else {
+                println!(
+                    "{} {}",
+                    t!(lang, "✓ Restored snapshot:", "✓ 已恢复快照:"),
+                    report.restored.id
+                );
+            }
+            println!("  {}", report.diff.summary());
+            for e in &report.diff.added {
+                println!("  + {} ({})", e.title, e.id);
+            }
+            for e in &report.diff.removed {
+                println!("  - {} ({})", e.title, e.id);
+            }
+            for e in &report.diff.changed {
+                println!("  ~ {} ({})", e.title, e.id);
+            }
+            if let Some(safety) = report.safety {
+                println!(
+                    "{} {}",
+                    t!(lang, "  Previous state saved as snapshot:", "  原状态已保存为快照:"),
+                    safety.id
+                );
+                snapshot::prune_snapshots(&profile_dir, keep)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_export(lang: Language, profile: &str, format: &str, out: Option<String>) -> Result<()> {
+    use crate::session::transfer::{Export, Format};
+
+    let format = Format::parse(format).ok_or_else(|| {
+        crate::Error::InvalidInput(format!(
+            "{}: {}",
+            t!(lang, "unknown format (use json or toml)", "未知格式（请使用 json 或 toml）"),
+            format
+        ))
+    })?;
+    let storage = Storage::new(profile).await?;
+    let (instances, tree, _) = storage.load().await?;
+    let text = Export::new(profile, &instances, &tree).render(format)?;
+
+    match out {
+        Some(path) => {
+            std::fs::write(&path, text)?;
+            eprintln!(
+                "{} {} {} → {}",
+                t!(lang, "✓ Exported", "✓ 已导出"),
+                instances.len(),
+                t!(lang, "sessions", "个会话"),
+                path
+            );
+        }
+        None => print!("{text}"),
+    }
+    Ok(())
+}
+
+async fn handle_import(
+    lang: Language,
+    profile: &str,
+    file: &str,
+    merge: bool,
+    replace: bool,
+    force: bool,
+) -> Result<()> {
+    use crate::session::transfer::{self, Export, Format, ImportMode};
+
+    let mode = match (merge, replace) {
+        (true, false) => ImportMode::Merge,
+        (false, true) => ImportMode::Replace,
+        _ => {
+            return Err(crate::Error::InvalidInput(
+                t!(
+                    lang,
+                    "pass exactly one of --merge or --replace",
+                    "请指定 --merge 或 --replace 之一"
+                )
+                .to_string(),
+            ))
+        }
+    };
+    let content = std::fs::read_to_string(file)?;
+    let export = Export::parse(&content, Format::from_path(std::path::Path::new(file)))?;
+
+    let storage = Storage::new(profile).await?;
+    let (mut instances, mut tree, mut relationships) = storage.load().await?;
+    guard_open_profile(lang, profile, force)?;
+
+    if mode == ImportMode::Replace && !instances.is_empty() {
+        let safety = crate::session::snapshot::create_snapshot(
+            &Storage::profile_dir_for(profile)?,
+            None,
+            true,
+        )?;
+        println!(
+            "{} {}",
+            t!(lang, "Snapshotted current state:", "已快照当前状态:"),
+            safety.id
+        );
+    }
+
+    let report = transfer::import(&mut instances, &mut tree, export, mode);
+    if mode == ImportMode::Replace {
+        let ids: std::collections::HashSet<&str> =
+            instances.iter().map(|s| s.id.as_str()).collect();
+        relationships.retain(|r| {
+            ids.contains(r.session_a_id.as_str()) && ids.contains(r.session_b_id.as_str())
+        });
+    }
+    storage.save(&instances, &tree, &relationships).await?;
+
+    println!(
+        "{} {} {} ({} '{}')",
+        t!(lang, "✓ Imported", "✓ 已导入"),
+        report.imported,
+        t!(lang, "sessions", "个会话"),
+        t!(lang, "into profile", "到配置"),
+        profile
+    );
+    if report.renamed > 0 {
+        println!(
+            "  {} {}",
+            report.renamed,
+            t!(lang, "given new ids (theirs were taken)", "个会话因 ID 冲突而换用新 ID")
+        );
+    }
+    if !report.skipped.is_empty() {
+        println!(
+            "  {} {}:",
+            report.skipped.len(),
+            t!(lang, "skipped (project path already used)", "个会话被跳过（项目路径已存在）")
+        );
+        for title in &report.skipped {
+            println!("    - {title}");
+        }
+    }
+    Ok(())
+}
+
+async fn handle_wrapup(
+    lang: Language,
+    profile: &str,
+    group: Option<&str>,
+    opts: crate::session::wrapup::WrapupOptions,
+    dry_run: bool,
+    resume_list: Option<&str>,
+) -> Result<()> {
+    use crate::session::wrapup::{self, WrapupOutcome};
+    use crate::session::journal;
+
+    let storage = Storage::new(profile).await?;
+    let (instances, _, _) = storage.load().await?;
+    let manager = Arc::new(TmuxManager::new(profile));
+    manager.refresh_cache().await?;
+
+    let targets: Vec<Instance> = wrapup::select_targets(&instances, group)
+        .into_iter()
+        .filter(|inst| manager.session_exists(&inst.tmux_name()).unwrap_or(false))
+        .cloned()
+        .collect();
+
+    if targets.is_empty() {
+        println!("{}", t!(lang, "No running sessions to wrap up.", "没有需要收尾的运行中会话。"));
+        return Ok(());
+    }
+
+    if dry_run {
+        println!("{}", t!(lang, "Dry run — nothing will be sent or stopped:", "试运行 — 不会发送或停止任何会话:"));
+        for inst in &targets {
+            let status = wrapup::probe_status(&manager, inst).await;
+            let action = match wrapup::initial_outcome(status) {
+                None => t!(lang, "send message, wait, stop", "发送消息、等待、停止"),
+                Some(_) if opts.kill => t!(lang, "already idle, stop", "已空闲，停止"),
+                Some(_) => t!(lang, "already idle", "已空闲"),
+            };
+            println!("  {:<30} {:<10} {}", truncate(&inst.title, 30), format!("{:?}", status), action);
+        }
+        return Ok(());
+    }
+
+    println!(
+        "{} {} ({}s)",
+        t!(lang, "Wrapping up", "正在收尾"),
+        targets.len(),
+        opts.grace.as_secs()
+    );
+    let results = wrapup::run_wrapup(manager.clone(), targets, &opts).await;
+
+    println!("{:<30} {:<10} {}", t!(lang, "TITLE", "标题"), "ID", t!(lang, "OUTCOME", "结果"));
+    for r in &results {
+        let outcome = match (r.outcome, r.killed) {
+            (WrapupOutcome::AlreadyIdle, _) => t!(lang, "was already idle", "原本已空闲"),
+            (WrapupOutcome::Finished, _) => t!(lang, "finished cleanly", "已正常完成"),
+            (WrapupOutcome::TimedOut, true) => t!(lang, "timed out and killed", "超时并已终止"),
+            (WrapupOutcome::TimedOut, false) => t!(lang, "timed out", "超时"),
+            (WrapupOutcome::Vanished, _) => t!(lang, "exited while waiting", "等待期间已退出"),
+            (WrapupOutcome::SendFailed, _) => t!(lang, "could not send message", "消息发送失败"),
+        };
+        let mut line = format!("{:<30} {:<10} {}", truncate(&r.title, 30), truncate(&r.id, 10), outcome);
+        if let Some(err) = &r.error {
+            line.push_str(&format!(" ({err})"));
+        }
+        println!("{line}");
+    }
+
+    let profile_dir = Storage::profile_dir_for(profile)?;
+    let entries: Vec<_> = results.iter().flat_map(|r| r.journal_entries()).collect();
+    if let Err(e) = journal::append(&profile_dir, &entries) {
+        warn!("failed to write wrapup journal: {e}");
+    }
+
+    if let Some(path) = resume_list {
+        let ids: String = results
+            .iter()
+            .filter(|r| r.killed)
+            .map(|r| format!("{}\n", r.id))
+            .collect();
+        std::fs::write(path, ids)?;
+        println!("{} {}", t!(lang, "Resume list written to", "恢复列表已写入"), path);
+    }
+
+    Ok(())
+}
+
+async fn handle_adopt(
+    lang: Language,
+    profile: &str,
+    server: &str,
+    all: bool,
+    names: &[String],
+    migrate: bool,
+) -> Result<()> {
+    use crate::tmux::discovery::{kill_foreign_session, list_foreign_sessions};
+
+    if server == crate::tmux::server_name_for_profile(profile) {
+        return Err(crate::Error::InvalidInput(
+            t!(lang, "That is agent-hand's own tmux server", "这是 agent-hand 自己的 tmux server")
+                .to_string(),
+        ));
+    }
+    let found = list_foreign_sessions(server).await?;
+    if found.is_empty() {
+        println!("{} {}", t!(lang, "No tmux sessions on server", "该 tmux server 上没有会话:"), server);
+        return Ok(());
+    }
+
+    if !all && names.is_empty() {
+        let width = found.iter().map(|s| s.name.len()).max().unwrap_or(0);
+        for s in &found {
+            println!("{:<width$}  {:<10}  {}", s.name, s.command, s.path.display());
+        }
+        println!();
+        println!("{}", t!(lang,
+            "Adopt with: agent-hand adopt <names...> (or --all); add --migrate to move them onto agent-hand's server",
+            "采用: agent-hand adopt <names...>（或 --all）；加 --migrate 将其迁移到 agent-hand 的 server"
+        ));
+        return Ok(());
+    }
+
+    for name in names {
+        if !found.iter().any(|s| &s.name == name) {
+            println!("  {} {}", t!(lang, "✗ Not found:", "✗ 未找到:"), name);
+        }
+    }
+    let picked = found.iter().filter(|s| all || names.contains(&s.name));
+
+    let storage = Storage::new(profile).await?;
+    let (mut instances, tree, relationships) = storage.load().await?;
+    let manager = Arc::new(TmuxManager::new(profile));
+    let mut adopted = 0;
+    // Sessions to start here, by index, with the original to kill after.
+    let mut to_migrate: Vec<(usize, String)> = Vec::new();
+    manager.refresh_cache().await?;
+    for foreign in picked {
+        // Left on another server (e.g. the legacy shared one) by a session
+        // we still track: restart that session here rather than add a copy.
+        if let Some(owner) = instances.iter().position(|i| i.tmux_name() == foreign.name) {
+            if !migrate || manager.session_exists(&foreign.name).unwrap_or(false) {
+                println!(
+                    "  {} {} ({})",
+                    t!(lang, "• Already tracked:", "• 已在管理中:"),
+                    foreign.name,
+                    instances[owner].title
+                );
+                continue;
+            }
+            to_migrate.push((owner, foreign.name.clone()));
+            continue;
+        }
+        if let Some(existing) = instances.iter().find(|i| i.project_path == foreign.path) {
+            println!(
+                "  {} {} ({} {})",
+                t!(lang, "• Skipped:", "• 已跳过:"),
+                foreign.name,
+                t!(lang, "path already used by", "路径已被使用:"),
+                existing.title
+            );
+            continue;
+        }
+
+        let mut inst = Instance::new(foreign.name.clone(), foreign.path.clone());
+        (inst.tool, inst.command) = foreign.adopted_command();
+        // Until a migration succeeds the original keeps running there.
+        inst.adopted_from = Some(format!("{server}:{}", foreign.name));
+        if migrate {
+            to_migrate.push((instances.len(), foreign.name.clone()));
+        } else {
+            println!("  {} {}", t!(lang, "✓ Adopted:", "✓ 已采用:"), foreign.name);
+            adopted += 1;
+        }
+        instances.push(inst);
+    }
+
+    // Saved before anything starts, so a failed start leaves the session
+    // tracked and the original running rather than neither.
+    storage.save(&instances, &tree, &relationships).await?;
+    if adopted > 0 {
+        println!("{}", t!(lang,
+            "The original sessions keep running; starting an adopted session opens a new one on agent-hand's server.",
+            "原会话继续运行；启动已采用的会话会在 agent-hand 的 server 上新开一个会话。"
+        ));
+    }
+    if to_migrate.is_empty() {
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for (index, name) in to_migrate {
+        let inst = &mut instances[index];
+        inst.init_tmux(manager.clone());
+        if let Err(e) = inst.start().await {
+            println!("  {} {name} ({e})", t!(lang, "✗ Failed:", "✗ 失败:"));
+            println!("    {}", t!(lang, "original left running", "原会话仍在运行"));
+            failed += 1;
+            continue;
+        }
+        inst.adopted_from = None;
+        println!("  {} {name}", t!(lang, "✓ Migrated:", "✓ 已迁移:"));
+        if let Err(e) = kill_foreign_session(server, &name).await {
+            println!("    {} {e}", t!(lang, "original left running:", "原会话仍在运行:"));
+        }
+    }
+    storage.save(&instances, &tree, &relationships).await?;
+
+    if failed > 0 {
+        return Err(crate::Error::CommandFailed(if lang.is_zh() {
+            format!("{failed} 个会话迁移失败")
+        } else {
+            format!("{failed} session(s) could not be migrated")
+        }));
+    }
+    Ok(())
+}
+
+async fn handle_resume(lang: Language, profile: &str, from: &str) -> Result<()> {
+    use crate::session::journal::{self, JournalEntry};
+
+    let content = std::fs::read_to_string(from)?;
+    let ids: Vec<&str> = content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .collect();
+
     let storage = Storage::new(profile).await?;
     let (mut instances, tree, relationships) = storage.load().await?;
     let manager = Arc::new(TmuxManager::new(profile));
+    manager.refresh_cache().await?;
 
-    match action {
-        SessionAction::Start { id } => {
-            let inst = find_session(&mut instances, &id)?;
-            let title = inst.title.clone();
-            inst.init_tmux(manager.clone());
-            inst.start().await?;
-            storage.save(&instances, &tree, &relationships).await?;
-            println!("{} {}", t!(lang, "✓ Started session:", "✓ 已启动会话:"), title);
+    let mut entries = Vec::new();
+    for id in ids {
+        let Ok(inst) = find_session(&mut instances, id) else {
+            println!("  {} {}", t!(lang, "✗ Not found:", "✗ 未找到:"), id);
+            continue;
+        };
+        if manager.session_exists(&inst.tmux_name()).unwrap_or(false) {
+            println!("  {} {}", t!(lang, "• Already running:", "• 已在运行:"), inst.title);
+            continue;
+        }
+        inst.init_tmux(manager.clone());
+        match inst.start().await {
+            Ok(()) => {
+                println!("  {} {}", t!(lang, "✓ Started:", "✓ 已启动:"), inst.title);
+                entries.push(JournalEntry::new("resume.started", &inst.id, &inst.title, ""));
+            }
+            Err(e) => {
+                println!("  {} {} ({e})", t!(lang, "✗ Failed:", "✗ 失败:"), inst.title);
+                entries.push(JournalEntry::new("resume.failed", &inst.id, &inst.title, e.to_string()));
+            }
         }
+    }
 
-        SessionAction::Stop { id } => {
-            let inst = find_session(&mut instances, &id)?;
-            let title = inst.title.clone();
-            inst.init_tmux(manager.clone());
-            inst.stop().await?;
-            storage.save(&instances, &tree, &relationships).await?;
-            println!("{} {}", t!(lang, "✓ Stopped session:", "✓ 已停止会话:"), title);
+    storage.save(&instances, &tree, &relationships).await?;
+    if let Err(e) = journal::append(&Storage::profile_dir_for(profile)?, &entries) {
+        warn!("failed to write resume journal: {e}");
+    }
+    Ok(())
+}
+
+async fn handle_reap(lang: Language, profile: &str, idle_after: std::time::Duration, dry_run: bool) -> Result<()> {
+    use crate::session::journal;
+    use crate::session::reaper;
+    use crate::session::wrapup::probe_status;
+
+    let storage = Storage::new(profile).await?;
+    let (instances, _, _) = storage.load().await?;
+    let manager = TmuxManager::new(profile);
+    manager.refresh_cache().await?;
+
+    let stale = reaper::stale_sessions(
+        &instances,
+        |name| manager.session_activity(name),
+        chrono::Utc::now().timestamp(),
+        idle_after,
+    );
+    // Stored statuses can be out of date, so probe each pane; sessions
+    // waiting on a prompt or still working are left alone.
+    let mut candidates = Vec::new();
+    for candidate in stale {
+        let Some(inst) = instances.iter().find(|i| i.id == candidate.id) else {
+            continue;
+        };
+        if probe_status(&manager, inst).await == crate::session::Status::Idle {
+            candidates.push(candidate);
         }
+    }
 
-        SessionAction::Restart { id } => {
-            let inst = find_session(&mut instances, &id)?;
-            let title = inst.title.clone();
-            inst.init_tmux(manager.clone());
-            inst.stop().await?;
-            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-            inst.start().await?;
-            storage.save(&instances, &tree, &relationships).await?;
-            println!("{} {}", t!(lang, "✓ Restarted session:", "✓ 已重启会话:"), title);
+    if candidates.is_empty() {
+        println!(
+            "{} {}h.",
+            t!(lang, "No idle sessions without activity for", "没有空闲且无活动超过"),
+            idle_after.as_secs() / 3600
+        );
+        return Ok(());
+    }
+
+    let idle_label = |c: &reaper::ReapCandidate| format_age(chrono::Duration::seconds(c.idle.as_secs() as i64));
+    if dry_run {
+        println!("{}", t!(lang, "Dry run — these sessions would be stopped:", "试运行 — 将停止以下会话:"));
+        for c in &candidates {
+            println!(
+                "  {:<30} {:<10} {} {}",
+                truncate(&c.title, 30),
+                truncate(&c.id, 10),
+                t!(lang, "idle", "空闲"),
+                idle_label(c)
+            );
         }
+        return Ok(());
+    }
 
-        SessionAction::Attach { id } => {
-            let inst = find_session(&mut instances, &id)?;
-            inst.init_tmux(manager.clone());
-            inst.attach().await?;
-            storage.save(&instances, &tree, &relationships).await?;
+    let results = reaper::reap(&manager, candidates).await;
+    for (c, error) in &results {
+        match error {
+            None => println!(
+                "  {} {} ({} {})",
+                t!(lang, "✓ Stopped:", "✓ 已停止:"),
+                c.title,
+                t!(lang, "idle", "空闲"),
+                idle_label(c)
+            ),
+            Some(e) => println!("  {} {} ({e})", t!(lang, "✗ Failed:", "✗ 失败:"), c.title),
         }
+    }
 
-        SessionAction::Show { id } => {
-            let inst = if let Some(id_str) = &id {
-                find_session(&mut instances, id_str)?
-            } else {
-                return Err(crate::Error::InvalidInput(
-                    t!(lang, "Auto-detection not yet implemented", "自动检测尚未实现").to_string(),
-                ));
-            };
+    let entries: Vec<_> = results.iter().map(|(c, error)| c.journal_entry(error.as_deref())).collect();
+    if let Err(e) = journal::append(&Storage::profile_dir_for(profile)?, &entries) {
+        warn!("failed to write reap journal: {e}");
+    }
+    Ok(())
+}
 
-            println!("{}: {}", t!(lang, "Session", "会话"), inst.title);
-            println!("  ID:      {}", inst.id);
-            println!("  {}: {}", t!(lang, "Path", "路径"), inst.project_path.display());
-            println!("  {}: {}", t!(lang, "Group", "分组"), inst.group_path);
-            println!("  {}: {:?}", t!(lang, "Status", "状态"), inst.status);
-            println!("  {}: {}", t!(lang, "Created", "创建时间"), inst.created_at);
-        }
+async fn handle_regroup(
+    lang: Language,
+    profile: &str,
+    rules: &[crate::config::GroupRule],
+    dry_run: bool,
+) -> Result<()> {
+    use crate::session::grouping;
+
+    let storage = Storage::new(profile).await?;
+    let (mut instances, mut tree, relationships) = storage.load().await?;
+    let moves = grouping::proposed_moves(&instances, rules);
+    if moves.is_empty() {
+        println!("{}", t!(lang, "Nothing to regroup.", "没有需要重新分组的会话。"));
+        return Ok(());
+    }
+
+    let ungrouped = t!(lang, "(ungrouped)", "（未分组）");
+    let show = |group: &str| {
+        if group.is_empty() { ungrouped.to_string() } else { group.to_string() }
+    };
+    if dry_run {
+        println!("{}", t!(lang, "Dry run — these sessions would move:", "试运行 — 将移动以下会话:"));
+    }
+    for m in &moves {
+        println!(
+            "  {:<30} {:<10} {} → {}",
+            truncate(&m.title, 30),
+            truncate(&m.id, 10),
+            show(&m.from),
+            show(&m.to)
+        );
+    }
+    if dry_run {
+        return Ok(());
     }
 
+    for m in &moves {
+        if let Some(inst) = instances.iter_mut().find(|i| i.id == m.id) {
+            inst.group_path = m.to.clone();
+        }
+        if !m.to.is_empty() {
+            tree.create_group(m.to.clone());
+        }
+    }
+    storage.save(&instances, &tree, &relationships).await?;
+    println!("{} {}", t!(lang, "✓ Regrouped sessions:", "✓ 已重新分组会话:"), moves.len());
     Ok(())
 }
 
-async fn handle_profile(lang: Language, action: ProfileAction) -> Result<()> {
-    match action {
-        ProfileAction::List => {
-            let profiles = Storage::list_profiles().await?;
-            println!("{}:", t!(lang, "Profiles", "配置列表"));
-            for prof in profiles {
-                println!("  {}", prof);
+async fn handle_doctor(lang: Language, json: bool) -> Result<()> {
+    use crate::cli::doctor::{self, Check, Generation, Outcome, Server};
+
+    let mut checks = vec![doctor::tmux_binary(crate::tmux::version::detected())];
+    let tmux_found = !checks[0].failed();
+
+    let profiles = Storage::list_profiles().await?;
+    let mut all_instances = Vec::new();
+    for profile in &profiles {
+        let profile = profile.as_str();
+        let generations = doctor::storage_generations(&Storage::profile_dir_for(profile)?)
+            .into_iter()
+            .map(|(file, path)| (file, Generation::read(&path)))
+            .collect();
+        let (storage, instances) = doctor::storage_check(profile, generations);
+
+        let server = if tmux_found {
+            let output = TmuxManager::new(profile)
+                .tmux_cmd()
+                .args(["list-sessions", "-F", "#{session_name}"])
+                .output()
+                .await?;
+            Some(Server::from_output(
+                output.status.success(),
+                &String::from_utf8_lossy(&output.stdout),
+                &String::from_utf8_lossy(&output.stderr),
+            ))
+        } else {
+            None
+        };
+
+        match &server {
+            Some(server) => {
+                checks.push(doctor::server_check(profile, server));
+                checks.push(storage);
+                checks.push(doctor::orphan_check(profile, server, instances.as_deref()));
             }
+            None => checks.push(storage),
         }
+        all_instances.extend(instances.unwrap_or_default());
+    }
 
-        ProfileAction::Create { name } => {
-            Storage::create_profile(&name).await?;
-            println!("{} {}", t!(lang, "✓ Created profile:", "✓ 已创建配置:"), name);
+    if tmux_found {
+        let legacy = crate::tmux::LEGACY_SERVER;
+        let in_use = profiles
+            .iter()
+            .any(|p| crate::tmux::server_name_for_profile(p) == legacy);
+        let sessions: Vec<String> = crate::tmux::discovery::list_foreign_sessions(legacy)
+            .await?
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        checks.push(doctor::legacy_check(in_use, &sessions, &all_instances));
+    }
+
+    let pool = crate::claude::mcp::pool_path().and_then(|path| {
+        let doc = crate::claude::mcp::read_config_doc(&path)?;
+        Ok((path, crate::claude::mcp::server_map(&doc)?.len()))
+    });
+    checks.push(match pool {
+        Ok((path, count)) => Check {
+            name: "mcp pool".to_string(),
+            status: Outcome::Pass,
+            detail: format!("{count} {} {}", t!(lang, "server(s) in", "个服务器，位于"), path.display()),
+        },
+        Err(e) => Check {
+            name: "mcp pool".to_string(),
+            status: Outcome::Fail,
+            detail: e.to_string(),
+        },
+    });
+
+    let failed = checks.iter().filter(|c| c.failed()).count();
+    let warned = checks.iter().filter(|c| c.status == Outcome::Warn).count();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&checks)?);
+    } else {
+        print!("{}", doctor::table(&checks));
+        println!();
+        if failed == 0 && warned == 0 {
+            println!("{}", t!(lang, "All checks passed", "所有检查均通过"));
+        } else if failed == 0 && lang.is_zh() {
+            println!("无失败项，{warned} 项警告");
+        } else if failed == 0 {
+            println!("No failures, {warned} warning(s)");
+        } else if lang.is_zh() {
+            println!("{failed} 项检查未通过");
+        } else {
+            println!("{failed} check(s) failed");
         }
+    }
+    if failed > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
 
-        ProfileAction::Delete { name } => {
-            Storage::delete_profile(&name).await?;
-            println!("{} {}", t!(lang, "✓ Deleted profile:", "✓ 已删除配置:"), name);
+async fn handle_detect(lang: Language, tool: &str, file: Option<String>) -> Result<()> {
+    let tool = crate::tmux::Tool::from_name(tool).ok_or_else(|| {
+        crate::Error::InvalidInput(format!(
+            "unknown tool: {tool} (expected claude, gemini, opencode, codex or shell)"
+        ))
+    })?;
+    let content = match file.as_deref() {
+        None | Some("-") => {
+            let mut buf = String::new();
+            std::io::Read::read_to_string(&mut std::io::stdin(), &mut buf)?;
+            buf
         }
+        Some(path) => tokio::fs::read_to_string(path).await?,
+    };
+
+    // Applied again so bad patterns are shown here rather than only logged.
+    let cfg = crate::config::ConfigFile::load()
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    for problem in crate::tmux::set_status_detection_config(cfg.status_detection()) {
+        eprintln!("{} {problem}", t!(lang, "warning: ignored", "警告: 已忽略"));
     }
 
+    let detector = crate::tmux::PromptDetector::new(tool);
+    let (status, matched) = if let Some(m) = detector.prompt_match(&content) {
+        ("waiting", Some(m))
+    } else if let Some(m) = detector.busy_match(&content) {
+        ("running", Some(m))
+    } else {
+        ("idle", None)
+    };
+    println!("{} {status}", t!(lang, "Status:", "状态:"));
+    match matched {
+        Some(m) => println!("{} {} {:?}", t!(lang, "Matched:", "匹配:"), m.source, m.pattern),
+        None => println!(
+            "{}",
+            t!(lang, "Matched: no prompt or busy pattern", "匹配: 没有命中提示或忙碌规则")
+        ),
+    }
     Ok(())
 }
 
-// Helper functions
+async fn handle_tmux(lang: Language, profile: &str, action: TmuxAction) -> Result<()> {
+    use crate::tmux::env;
 
-fn find_session<'a>(instances: &'a mut [Instance], id: &str) -> Result<&'a mut Instance> {
-    instances
-        .iter_mut()
-        .find(|inst| inst.id == id || inst.id.starts_with(id) || inst.title == id)
-        .ok_or_else(|| crate::Error::SessionNotFound(id.to_string()))
+    let manager = TmuxManager::new(profile);
+    match action {
+        TmuxAction::Env { clear } => {
+            if clear {
+                env::clear_all(&manager).await?;
+                println!("{}", t!(lang, "✓ Cleared agent-hand tmux environment", "✓ 已清除 agent-hand 的 tmux 环境变量"));
+                return Ok(());
+            }
+
+            manager.refresh_cache().await?;
+            let values = env::read_all(&manager).await;
+            for key in env::OWNED_KEYS {
+                let value = values.get(key.name);
+                let stale = key.names_session
+                    && value.is_some_and(|v| {
+                        !v.is_empty() && manager.session_exists(v) == Some(false)
+                    });
+                println!(
+                    "{:<28} {:<32} {}{}",
+                    key.name,
+                    value.map_or_else(|| t!(lang, "(unset)", "(未设置)").to_string(), |v| truncate(v, 32)),
+                    key.description,
+                    if stale { t!(lang, "  [stale]", "  [已失效]") } else { "" }
+                );
+            }
+        }
+        TmuxAction::Version => {
+            use crate::tmux::version;
+
+            let detected = manager.version();
+            match detected {
+                Some(d) => {
+                    let parsed = d
+                        .version
+                        .map_or_else(|| t!(lang, "unrecognized", "无法识别").to_string(), |v| v.to_string());
+                    println!("tmux {} ({}: {})", d.raw, t!(lang, "parsed", "解析为"), parsed);
+                }
+                None => println!("{}", t!(lang, "tmux not found", "未找到 tmux")),
+            }
+            let missing: Vec<_> = [
+                version::popup(detected),
+                version::new_session_env(detected),
+                version::copy_mode_commands(detected),
+            ]
+            .into_iter()
+            .filter_map(|check| check.err())
+            .collect();
+            if missing.is_empty() {
+                println!("  ✓ {}", t!(lang, "All features supported", "所有功能均可用"));
+            }
+            for e in missing {
+                println!("  ✗ {e}");
+            }
+        }
+    }
+    Ok(())
 }
 
-fn truncate(s: &str, max: usize) -> String {
-    if s.len() <= max {
-        s.to_string()
-    } else if max <= 3 {
-        let end = s.floor_char_boundary(max);
-        s[..end].to_string()
+// Helper functions
+
+fn format_size(bytes: u64) -> String {
+    if bytes < 1024 {
+        format!("{bytes} B")
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
     } else {
-        let end = s.floor_char_boundary(max - 3);
-        format!("{}...", &s[..end])
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
     }
 }
 
+fn find_session<'a>(instances: &'a mut [Instance], id: &str) -> Result<&'a mut Instance> {
+    let index = crate::session::titles::find(instances, id)?;
+    Ok(&mut instances[index])
+}
+
 #[derive(Default)]
 struct StatusCounts {
     waiting: usize,
     running: usize,
     idle: usize,
+    stopped: usize,
     error: usize,
     total: usize,
 }
 
-fn print_status_verbose(lang: Language, instances: &[Instance]) {
+fn format_status_verbose(lang: Language, instances: &[Instance]) -> String {
+    use std::fmt::Write;
+
     let symbols = [
         (crate::session::Status::Waiting, "◐", t!(lang, "WAITING", "等待中")),
         (crate::session::Status::Running, "●", t!(lang, "RUNNING", "运行中")),
         (crate::session::Status::Idle, "○", t!(lang, "IDLE", "空闲")),
+        (crate::session::Status::Stopped, "■", t!(lang, "STOPPED", "已停止")),
         (crate::session::Status::Error, "✕", t!(lang, "ERROR", "错误")),
     ];
 
+    let now = chrono::Utc::now();
+    let mut out = String::new();
     for (status, symbol, label) in &symbols {
         let matching: Vec<_> = instances.iter().filter(|i| &i.status == status).collect();
         if matching.is_empty() {
             continue;
         }
 
-        println!("{} ({}):", label, matching.len());
+        let _ = writeln!(out, "{} ({}):", label, matching.len());
         for inst in matching {
             let path = inst.project_path.to_string_lossy();
-            println!("  {} {:<16} {:?}", symbol, inst.title, path);
+            let timer = crate::ui::text::status_timer(inst, now)
+                .map(|timer| format!("{timer:<7} "))
+                .unwrap_or_default();
+            let _ = writeln!(out, "  {} {}{:<16} {:?}", symbol, timer, inst.title, path);
+            if let Some(reason) = &inst.waiting_reason {
+                let _ = writeln!(out, "      ↳ {}", reason);
+            }
         }
-        println!();
+        let _ = writeln!(out);
     }
+    out
 }
 
 async fn handle_login(lang: Language) -> Result<()> {
     use crate::auth::{AuthToken, DeviceCodeResponse, DeviceTokenResponse, AUTH_SERVER};
 
+    crate::network::ensure_online()?;
     let dev_info = crate::device::DeviceInfo::generate();
     let client = reqwest::Client::new();
 
@@ -1964,6 +4202,34 @@ async fn handle_config(lang: Language, action: ConfigAction) -> Result<()> {
             let path = dir.join("config.toml");
             println!("{}", path.display());
         }
+        ConfigAction::Check => {
+            let cfg = crate::config::ConfigFile::load()
+                .await?
+                .unwrap_or_default();
+            let kb = crate::config::KeyBindings::from_config(&cfg);
+            let conflicts = kb.conflicts();
+            if conflicts.is_empty() {
+                println!("{}", t!(lang, "✓ No keybinding conflicts", "✓ 没有快捷键冲突"));
+                return Ok(());
+            }
+            println!(
+                "{} ({}):",
+                t!(lang, "Keybinding conflicts", "快捷键冲突"),
+                conflicts.len()
+            );
+            for c in conflicts {
+                println!("  {}", c.describe());
+            }
+            println!(
+                "{}",
+                t!(
+                    lang,
+                    "The first action keeps the key; the shadowed action no longer responds to it.",
+                    "前一个动作保留该键；被覆盖的动作不再响应此键。"
+                )
+            );
+            std::process::exit(1);
+        }
         ConfigAction::Reset { force } => {
             if !force {
                 eprint!(
@@ -1999,6 +4265,238 @@ async fn handle_config(lang: Language, action: ConfigAction) -> Result<()> {
     Ok(())
 }
 
+/// The file an `agent-hand mcp` command edits.
+enum McpFile {
+    /// The global pool.
+    Pool(PathBuf),
+    /// A session's own config, passed to its tool with `--mcp-config`.
+    Session {
+        storage: Box<Storage>,
+        id: String,
+        title: String,
+        project_path: PathBuf,
+    },
+    /// The project `.mcp.json` a session's tool reads, for tools with no
+    /// per-launch override; shared with every session in the directory.
+    Project(PathBuf),
+}
+
+impl McpFile {
+    async fn open(profile: &str, session: Option<&str>) -> Result<Self> {
+        use crate::claude::mcp::{self, McpScope};
+
+        let Some(session) = session else {
+            return Ok(Self::Pool(mcp::pool_path()?));
+        };
+        let storage = Storage::new(profile).await?;
+        let (instances, _, _) = storage.load().await?;
+        let inst = &instances[crate::session::titles::find(&instances, session)?];
+        Ok(match McpScope::of(inst.tool) {
+            McpScope::Session => Self::Session {
+                id: inst.id.clone(),
+                title: inst.title.clone(),
+                project_path: inst.project_path.clone(),
+                storage: Box::new(storage),
+            },
+            McpScope::Project => Self::Project(inst.project_path.join(".mcp.json")),
+        })
+    }
+
+    fn path(&self, profile: &str) -> Result<PathBuf> {
+        match self {
+            Self::Pool(path) | Self::Project(path) => Ok(path.clone()),
+            Self::Session { id, .. } => crate::claude::mcp::session_config_path(profile, id),
+        }
+    }
+
+    /// The document at `path`; see [`crate::claude::mcp::read_session_config`]
+    /// for a session that has no file yet.
+    fn read(&self, path: &std::path::Path) -> Result<serde_json::Value> {
+        match self {
+            Self::Session { project_path, .. } => {
+                crate::claude::mcp::read_session_config(path, project_path)
+            }
+            _ => crate::claude::mcp::read_config_doc(path),
+        }
+    }
+
+    /// Which servers this file decides, printed before every change.
+    fn describe(&self, lang: Language, path: &std::path::Path) -> String {
+        let path = path.display();
+        match self {
+            Self::Pool(_) => format!("{} {path}", t!(lang, "Global MCP pool:", "全局 MCP 池:")),
+            Self::Session { title, .. } => format!(
+                "{} {path} ({} '{title}', {})",
+                t!(lang, "Session MCP config:", "会话 MCP 配置:"),
+                t!(lang, "only", "仅"),
+                t!(lang, "applies on its next start", "下次启动时生效")
+            ),
+            Self::Project(_) => format!(
+                "{} {path} ({})",
+                t!(lang, "Project MCP config:", "项目 MCP 配置:"),
+                t!(
+                    lang,
+                    "no per-session config for this tool; shared by the directory's sessions",
+                    "该工具不支持会话级配置；目录下所有会话共用"
+                )
+            ),
+        }
+    }
+
+    async fn write(&self, profile: &str, doc: &serde_json::Value) -> Result<()> {
+        use crate::claude::mcp;
+
+        let Self::Session { storage, id, .. } = self else {
+            return mcp::write_config_doc(&self.path(profile)?, doc);
+        };
+        let path = mcp::write_session_config(profile, id, &mcp::server_map(doc)?)?;
+        let (mut instances, tree, relationships) = storage.load().await?;
+        let Some(inst) = instances.iter_mut().find(|i| i.id == *id) else {
+            return Err(crate::Error::SessionNotFound(id.clone()));
+        };
+        if inst.mcp_config.as_deref() != Some(path.as_path()) {
+            inst.mcp_config = Some(path);
+            storage.save(&instances, &tree, &relationships).await?;
+        }
+        Ok(())
+    }
+}
+
+async fn handle_mcp(lang: Language, profile: &str, action: McpAction) -> Result<()> {
+    use crate::claude::mcp;
+
+    let not_found = |name: &str| {
+        crate::Error::InvalidInput(format!(
+            "{} {name}",
+            t!(lang, "MCP server not found:", "未找到 MCP 服务器:")
+        ))
+    };
+
+    match action {
+        McpAction::Add {
+            name,
+            command,
+            args,
+            env,
+            transport,
+            url,
+            session,
+        } => {
+            let env = env
+                .iter()
+                .map(|pair| mcp::parse_env_pair(pair))
+                .collect::<Result<Vec<_>>>()?;
+            let mut def = mcp::server_definition(&transport, &command, &args, &env);
+            if let Some(url) = url {
+                def["url"] = url.into();
+            }
+            mcp::validate_server(&name, &def)?;
+
+            let file = McpFile::open(profile, session.as_deref()).await?;
+            let path = file.path(profile)?;
+            let mut doc = file.read(&path)?;
+            mcp::upsert_server(&mut doc, &name, def)?;
+            file.write(profile, &doc).await?;
+            println!("{}", file.describe(lang, &path));
+            println!("{} {name}", t!(lang, "✓ Saved MCP server:", "✓ 已保存 MCP 服务器:"));
+        }
+
+        McpAction::Remove { name, session } => {
+            let file = McpFile::open(profile, session.as_deref()).await?;
+            let path = file.path(profile)?;
+            let mut doc = file.read(&path)?;
+            if mcp::remove_server(&mut doc, &name).is_none() {
+                return Err(not_found(&name));
+            }
+            file.write(profile, &doc).await?;
+            println!("{}", file.describe(lang, &path));
+            println!("{} {name}", t!(lang, "✓ Removed MCP server:", "✓ 已移除 MCP 服务器:"));
+        }
+
+        McpAction::Show { name, session } => {
+            let file = McpFile::open(profile, session.as_deref()).await?;
+            let path = file.path(profile)?;
+            let servers = mcp::server_map(&file.read(&path)?)?;
+            match name {
+                Some(name) => {
+                    let def = servers.get(&name).ok_or_else(|| not_found(&name))?;
+                    println!("{}", serde_json::to_string_pretty(def)?);
+                }
+                None => {
+                    println!("{}", file.describe(lang, &path));
+                    if servers.is_empty() {
+                        println!("{}", t!(lang, "No MCP servers.", "没有 MCP 服务器。"));
+                    }
+                    for (name, def) in &servers {
+                        let target = def
+                            .get("command")
+                            .or_else(|| def.get("url"))
+                            .and_then(|v| v.as_str())
+                            .unwrap_or("");
+                        println!("{:<20} {}", truncate(name, 20), target);
+                    }
+                }
+            }
+        }
+
+        McpAction::Edit { name, session } => {
+            let file = McpFile::open(profile, session.as_deref()).await?;
+            let path = file.path(profile)?;
+            let mut doc = file.read(&path)?;
+            let servers = mcp::server_map(&doc)?;
+            let def = servers.get(&name).ok_or_else(|| not_found(&name))?;
+            let Some(edited) = edit_in_editor(&name, def).await? else {
+                println!("{}", t!(lang, "No changes.", "没有修改。"));
+                return Ok(());
+            };
+            mcp::validate_server(&name, &edited)?;
+            mcp::upsert_server(&mut doc, &name, edited)?;
+            file.write(profile, &doc).await?;
+            println!("{}", file.describe(lang, &path));
+            println!("{} {name}", t!(lang, "✓ Saved MCP server:", "✓ 已保存 MCP 服务器:"));
+        }
+    }
+    Ok(())
+}
+
+/// Open `def` in `$VISUAL`/`$EDITOR` (vi when neither is set) and parse
+/// what was saved; `None` when it was left as it was.
+async fn edit_in_editor(
+    name: &str,
+    def: &serde_json::Value,
+) -> Result<Option<serde_json::Value>> {
+    let before = serde_json::to_string_pretty(def)? + "\n";
+    let file = format!("agent-hand-mcp-{}-{name}.json", std::process::id());
+    let path = std::env::temp_dir().join(file);
+    std::fs::write(&path, &before)?;
+
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    // Through sh, so an editor given with arguments (`code -w`) works.
+    let status = TokioCommand::new("sh")
+        .arg("-c")
+        .arg(format!("{editor} \"$1\""))
+        .arg("sh")
+        .arg(&path)
+        .status()
+        .await;
+    let after = std::fs::read_to_string(&path);
+    let _ = std::fs::remove_file(&path);
+
+    let status = status?;
+    if !status.success() {
+        return Err(crate::Error::CommandFailed(format!("{editor} exited with {status}")));
+    }
+    let after = after?;
+    if after == before {
+        return Ok(None);
+    }
+    serde_json::from_str(&after)
+        .map(Some)
+        .map_err(|e| crate::Error::config(format!("mcpServers.{name}: {e}")))
+}
+
 fn navigate_toml<'a>(value: &'a toml::Value, path: &str) -> Option<&'a toml::Value> {
     let mut current = value;
     for part in path.split('.') {
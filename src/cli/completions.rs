@@ -0,0 +1,290 @@
+//! `agent-hand completions <shell>`: clap's completion script plus a hook
+//! that completes session arguments with live data.
+//!
+//! The static part comes from clap_complete. Arguments that name a session
+//! (see [`SESSION_ARGS`]) are completed by calling back into the binary:
+//! `agent-hand __complete sessions` prints `id<TAB>title` lines for the
+//! active profile, and the hook offers both the ids (described by their
+//! titles) and the titles, quoted the way each shell needs.
+
+use std::io::Write;
+
+use clap::{CommandFactory, ValueEnum};
+
+use super::args::Args;
+use crate::error::Result;
+use crate::session::Instance;
+
+const BIN: &str = "agent-hand";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Subcommands whose leading positionals are sessions (ID, ID prefix or
+/// title), with how many there are.
+pub const SESSION_ARGS: &[(&str, usize)] = &[
+    ("remove", 1),
+    ("share", 1),
+    ("unshare", 1),
+    ("session start", 1),
+    ("session stop", 1),
+    ("session restart", 1),
+    ("session resume", 1),
+    ("session attach", 1),
+    ("session logs", 1),
+    ("session show", 1),
+    ("session send", 1),
+    ("session autostart", 1),
+    ("session set", 1),
+    ("session compare", 2),
+    ("session forward", 2),
+];
+
+/// Write the completion script for `shell` to `out`.
+pub fn write_script(shell: CompletionShell, out: &mut dyn Write) -> Result<()> {
+    let generator = match shell {
+        CompletionShell::Bash => clap_complete::Shell::Bash,
+        CompletionShell::Zsh => clap_complete::Shell::Zsh,
+        CompletionShell::Fish => clap_complete::Shell::Fish,
+    };
+    let mut args = Args::command();
+    if shell == CompletionShell::Fish {
+        let mut script = Vec::new();
+        clap_complete::generate(generator, &mut args, BIN, &mut script);
+        out.write_all(drop_hidden_fish(&args, &String::from_utf8_lossy(&script)).as_bytes())?;
+    } else {
+        clap_complete::generate(generator, &mut args, BIN, out);
+    }
+    let hook = match shell {
+        CompletionShell::Bash => format!("{}{BASH_HOOK}", sh_id_slot()),
+        CompletionShell::Zsh => format!("{}{ZSH_HOOK}", sh_id_slot()),
+        CompletionShell::Fish => format!("{}{FISH_HOOK}", fish_id_slot()),
+    };
+    writeln!(out, "\n{hook}")?;
+    Ok(())
+}
+
+/// clap_complete's fish script offers hidden subcommands too; drop the
+/// lines that offer or complete them.
+fn drop_hidden_fish(args: &clap::Command, script: &str) -> String {
+    let hidden: Vec<&str> = args
+        .get_subcommands()
+        .filter(|cmd| cmd.is_hide_set())
+        .map(|cmd| cmd.get_name())
+        .collect();
+    script
+        .lines()
+        .filter(|line| {
+            !hidden.iter().any(|name| {
+                line.contains(&format!("-a \"{name}\""))
+                    || line.contains(&format!("__fish_agent_hand_using_subcommand {name};"))
+            })
+        })
+        .flat_map(|line| [line, "\n"])
+        .collect()
+}
+
+/// One `id<TAB>title` line per session. Tabs and line breaks in titles
+/// become spaces so each session stays on one line.
+pub fn session_candidates(instances: &[Instance]) -> String {
+    instances
+        .iter()
+        .map(|inst| {
+            let title = inst.title.replace(['\t', '\n', '\r'], " ");
+            format!("{}\t{title}\n", inst.id)
+        })
+        .collect()
+}
+
+/// [`SESSION_ARGS`] paths of `depth` words taking `slots` sessions.
+fn session_paths(depth: usize, slots: usize) -> impl Iterator<Item = &'static str> {
+    SESSION_ARGS
+        .iter()
+        .filter(move |(path, n)| path.split(' ').count() == depth && *n == slots)
+        .map(|(path, _)| *path)
+}
+
+/// `_agent_hand_id_slot WORD...`, shared by bash and zsh: succeeds when
+/// the next positional after the non-option WORDs names a session.
+fn sh_id_slot() -> String {
+    let mut out = String::from("_agent_hand_id_slot() {\n    local slots=0 depth=1\n");
+    for (depth, subject) in [(1, "\"$1\""), (2, "\"$1 $2\"")] {
+        out.push_str(&format!("    case {subject} in\n"));
+        for slots in [1, 2] {
+            let paths: Vec<String> = session_paths(depth, slots)
+                .map(|p| format!("\"{p}\""))
+                .collect();
+            if !paths.is_empty() {
+                out.push_str(&format!(
+                    "        {}) slots={slots} depth={depth} ;;\n",
+                    paths.join("|")
+                ));
+            }
+        }
+        out.push_str("    esac\n");
+    }
+    out.push_str("    (( $# - depth < slots ))\n}\n");
+    out
+}
+
+/// The fish version of [`sh_id_slot`], reading the command line itself.
+fn fish_id_slot() -> String {
+    let mut out = String::from(FISH_ARGS);
+    out.push_str("function __agent_hand_id_slot\n");
+    out.push_str("    set -l args (__agent_hand_args)\n    set -l slots 0\n    set -l depth 1\n");
+    for (depth, subject) in [(1, "\"$args[1]\""), (2, "\"$args[1] $args[2]\"")] {
+        out.push_str(&format!("    switch {subject}\n"));
+        for slots in [1, 2] {
+            let paths: Vec<String> = session_paths(depth, slots)
+                .map(|p| format!("'{p}'"))
+                .collect();
+            if !paths.is_empty() {
+                out.push_str(&format!(
+                    "        case {}\n            set slots {slots}\n            set depth {depth}\n",
+                    paths.join(" ")
+                ));
+            }
+        }
+        out.push_str("    end\n");
+    }
+    out.push_str("    test (math (count $args) - $depth) -lt $slots\nend\n");
+    out
+}
+
+const BASH_HOOK: &str = r#"
+_agent_hand_complete() {
+    local cur="${COMP_WORDS[COMP_CWORD]}" args=() profile=() i id title
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${COMP_WORDS[i]}" in
+            -p|--profile) profile=(--profile "${COMP_WORDS[i+1]}"); ((i++)) ;;
+            -*) ;;
+            *) args+=("${COMP_WORDS[i]}") ;;
+        esac
+    done
+    if [[ $cur != -* ]] && _agent_hand_id_slot "${args[@]}"; then
+        COMPREPLY=()
+        while IFS=$'\t' read -r id title; do
+            [[ $id == "$cur"* ]] && COMPREPLY+=("$id")
+            [[ -n $title && $title == "$cur"* ]] && COMPREPLY+=("$(printf '%q' "$title")")
+        done < <(agent-hand "${profile[@]}" __complete sessions 2>/dev/null)
+        return 0
+    fi
+    _agent-hand "$@"
+}
+complete -F _agent_hand_complete -o bashdefault -o default agent-hand
+"#;
+
+const ZSH_HOOK: &str = r#"
+_agent_hand_complete() {
+    local -a args profile ids titles
+    local i id title
+    for ((i = 2; i < CURRENT; i++)); do
+        case ${words[i]} in
+            -p|--profile) profile=(--profile "${words[i+1]}"); ((i++)) ;;
+            -*) ;;
+            *) args+=("${words[i]}") ;;
+        esac
+    done
+    if [[ ${words[CURRENT]} != -* ]] && _agent_hand_id_slot "${args[@]}"; then
+        while IFS=$'\t' read -r id title; do
+            ids+=("${id//:/\\:}:$title")
+            [[ -n $title ]] && titles+=("${title//:/\\:}:$id")
+        done < <(agent-hand "${profile[@]}" __complete sessions 2>/dev/null)
+        _describe -t session-ids 'session id' ids
+        _describe -t session-titles 'session title' titles
+        return 0
+    fi
+    _agent-hand "$@"
+}
+compdef _agent_hand_complete agent-hand
+"#;
+
+const FISH_ARGS: &str = r#"
+function __agent_hand_args
+    set -l tokens (commandline -opc)
+    set -e tokens[1]
+    while set -q tokens[1]
+        switch $tokens[1]
+            case -p --profile
+                set -e tokens[1]
+            case '-*'
+            case '*'
+                echo $tokens[1]
+        end
+        set -e tokens[1]
+    end
+end
+"#;
+
+const FISH_HOOK: &str = r#"
+function __agent_hand_sessions
+    set -l tokens (commandline -opc)
+    set -l profile
+    set -l i (contains -i -- --profile $tokens; or contains -i -- -p $tokens)
+    if test -n "$i"
+        set profile --profile $tokens[(math $i + 1)]
+    end
+    for line in (agent-hand $profile __complete sessions 2>/dev/null)
+        echo $line
+        string replace -rf '^([^\t]*)\t(.+)$' '$2'\t'$1' -- $line
+    end
+end
+
+complete -c agent-hand -n __agent_hand_id_slot -f -a '(__agent_hand_sessions)'
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script(shell: CompletionShell) -> String {
+        let mut out = Vec::new();
+        write_script(shell, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn session_args_name_real_positionals() {
+        let args = Args::command();
+        for (path, slots) in SESSION_ARGS {
+            let cmd = path.split(' ').fold(&args, |cmd, name| {
+                cmd.find_subcommand(name)
+                    .unwrap_or_else(|| panic!("no subcommand {path}"))
+            });
+            assert!(
+                cmd.get_positionals().count() >= *slots,
+                "{path} has fewer than {slots} positionals"
+            );
+        }
+    }
+
+    #[test]
+    fn scripts_hook_session_arguments() {
+        let bash = script(CompletionShell::Bash);
+        assert!(bash.contains("\"session compare\"|\"session forward\") slots=2 depth=2"));
+        assert!(bash.contains("complete -F _agent_hand_complete"));
+
+        let zsh = script(CompletionShell::Zsh);
+        assert!(zsh.contains("\"remove\"|\"share\"|\"unshare\") slots=1 depth=1"));
+        assert!(zsh.contains("compdef _agent_hand_complete agent-hand"));
+
+        let fish = script(CompletionShell::Fish);
+        assert!(fish.contains("case 'session start' 'session stop'"));
+        assert!(fish.contains("__complete sessions"));
+        assert!(
+            !fish.contains("-a \"__complete\""),
+            "the hidden command stays hidden"
+        );
+    }
+
+    #[test]
+    fn candidates_keep_one_session_per_line() {
+        let mut inst = Instance::new("fix\tlogin\nflow".into(), "/tmp".into());
+        inst.id = "abc123".into();
+        assert_eq!(session_candidates(&[inst]), "abc123\tfix login flow\n");
+    }
+}
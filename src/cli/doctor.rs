@@ -0,0 +1,478 @@
+//! `agent-hand doctor`: environment checks, printed as a ✓/!/✗ table or JSON.
+//!
+//! The probes (running tmux, reading files) live in the command handler;
+//! everything here turns their results into [`Check`]s.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::session::{Instance, Storage, StorageData, MAX_BACKUP_GENERATIONS};
+use crate::tmux::version::{self, DetectedTmux, TmuxVersion};
+
+/// Oldest tmux agent-hand runs on. Newer features are checked one by one
+/// in [`crate::tmux::version`].
+pub const MIN_TMUX: TmuxVersion = TmuxVersion::new(2, 4);
+
+/// Sessions created on the dedicated server that never belong to an
+/// Instance (shared-session viewers, side-by-side comparisons).
+const UNTRACKED_PREFIXES: &[&str] = &["viewer-", crate::tmux::COMPARE_PREFIX];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Pass,
+    /// Worth fixing, but nothing is broken; does not fail the run.
+    Warn,
+    Fail,
+    /// Not applicable, or blocked by an earlier failure.
+    Skip,
+}
+
+impl Outcome {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Self::Pass => "✓",
+            Self::Warn => "!",
+            Self::Fail => "✗",
+            Self::Skip => "-",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Check {
+    pub name: String,
+    pub status: Outcome,
+    pub detail: String,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, status: Outcome, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        }
+    }
+
+    pub fn failed(&self) -> bool {
+        self.status == Outcome::Fail
+    }
+}
+
+/// The tmux binary: present, and at least [`MIN_TMUX`]. Features the
+/// version is too old for are listed but do not fail the check.
+pub fn tmux_binary(detected: Option<&DetectedTmux>) -> Check {
+    const NAME: &str = "tmux";
+    let Some(d) = detected else {
+        return Check::new(NAME, Outcome::Fail, "tmux not found on PATH");
+    };
+    let Some(v) = d.version else {
+        return Check::new(
+            NAME,
+            Outcome::Pass,
+            format!("{} (unrecognized version, assuming supported)", d.raw),
+        );
+    };
+    if !v.at_least(MIN_TMUX.major, MIN_TMUX.minor) {
+        return Check::new(
+            NAME,
+            Outcome::Fail,
+            format!("{} is older than the minimum {MIN_TMUX}", d.raw),
+        );
+    }
+    let missing: Vec<_> = [version::popup(detected), version::new_session_env(detected)]
+        .into_iter()
+        .filter_map(|check| check.err().map(|e| e.feature))
+        .collect();
+    let detail = if missing.is_empty() {
+        d.raw.clone()
+    } else {
+        format!("{} (unavailable: {})", d.raw, missing.join(", "))
+    };
+    Check::new(NAME, Outcome::Pass, detail)
+}
+
+/// What `list-sessions` on a profile's dedicated server said.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Server {
+    Running(Vec<String>),
+    /// No server and no socket; it starts with the first session.
+    NotRunning,
+    /// A socket nobody answers on, or some other tmux error.
+    Unreachable(String),
+}
+
+impl Server {
+    pub fn from_output(success: bool, stdout: &str, stderr: &str) -> Self {
+        if success {
+            return Self::Running(
+                stdout
+                    .lines()
+                    .filter(|l| !l.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            );
+        }
+        let stderr = stderr.trim();
+        if stderr.contains("no server running") || stderr.contains("No such file or directory") {
+            Self::NotRunning
+        } else {
+            Self::Unreachable(stderr.to_string())
+        }
+    }
+}
+
+pub fn server_check(profile: &str, server: &Server) -> Check {
+    let name = format!("server ({profile})");
+    match server {
+        Server::Running(sessions) => Check::new(
+            name,
+            Outcome::Pass,
+            format!("running, {} sessions", sessions.len()),
+        ),
+        Server::NotRunning => Check::new(name, Outcome::Pass, "not running"),
+        Server::Unreachable(err) => Check::new(
+            name,
+            Outcome::Fail,
+            format!(
+                "{err}; if the socket is stale, run `tmux -L {} kill-server`",
+                crate::tmux::server_name_for_profile(profile)
+            ),
+        ),
+    }
+}
+
+/// One saved copy of `sessions.json`.
+#[derive(Debug, Clone)]
+pub enum Generation {
+    Missing,
+    Valid {
+        instances: Vec<Instance>,
+        saved_at: DateTime<Utc>,
+    },
+    Invalid(String),
+}
+
+impl Generation {
+    pub fn read(path: &Path) -> Self {
+        let content = match std::fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Self::Missing,
+            Err(e) => return Self::Invalid(e.to_string()),
+        };
+        match serde_json::from_str::<StorageData>(&content) {
+            Ok(data) => Self::Valid {
+                instances: data.instances,
+                saved_at: data.updated_at,
+            },
+            Err(e) => Self::Invalid(e.to_string()),
+        }
+    }
+}
+
+/// `sessions.json` and its backups, newest first, as (file name, path).
+pub fn storage_generations(profile_dir: &Path) -> Vec<(String, PathBuf)> {
+    let current = profile_dir.join("sessions.json");
+    let backups = (1..=MAX_BACKUP_GENERATIONS).map(|g| Storage::backup_path_for(&current, g));
+    std::iter::once(current.clone())
+        .chain(backups)
+        .map(|path| {
+            let file = path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            (file, path)
+        })
+        .collect()
+}
+
+/// Check a profile's `sessions.json`, pointing at the newest valid backup
+/// when it is broken. Returns the saved sessions when the file is usable.
+pub fn storage_check(
+    profile: &str,
+    generations: Vec<(String, Generation)>,
+) -> (Check, Option<Vec<Instance>>) {
+    let name = format!("sessions.json ({profile})");
+    let mut generations = generations.into_iter();
+    let Some((_, current)) = generations.next() else {
+        return (Check::new(name, Outcome::Skip, "nothing to read"), None);
+    };
+    match current {
+        Generation::Missing => (
+            Check::new(name, Outcome::Pass, "no sessions saved yet"),
+            Some(Vec::new()),
+        ),
+        Generation::Valid { instances, .. } => (
+            Check::new(name, Outcome::Pass, format!("{} sessions", instances.len())),
+            Some(instances),
+        ),
+        Generation::Invalid(err) => {
+            let backup = generations.find_map(|(file, g)| match g {
+                Generation::Valid {
+                    instances,
+                    saved_at,
+                } => Some(format!(
+                    "newest valid backup: {file}, saved {} ({} sessions)",
+                    saved_at.format("%Y-%m-%d %H:%M UTC"),
+                    instances.len()
+                )),
+                _ => None,
+            });
+            let detail = format!(
+                "{err}; {}",
+                backup.unwrap_or_else(|| "no valid backup".to_string())
+            );
+            (Check::new(name, Outcome::Fail, detail), None)
+        }
+    }
+}
+
+/// Sessions on the dedicated server that no saved Instance owns.
+pub fn orphans<'a>(server_sessions: &'a [String], instances: &[Instance]) -> Vec<&'a str> {
+    let owned: HashSet<String> = instances.iter().map(Instance::tmux_name).collect();
+    server_sessions
+        .iter()
+        .map(String::as_str)
+        .filter(|s| !owned.contains(*s) && !UNTRACKED_PREFIXES.iter().any(|p| s.starts_with(p)))
+        .collect()
+}
+
+pub fn orphan_check(profile: &str, server: &Server, instances: Option<&[Instance]>) -> Check {
+    let name = format!("orphaned sessions ({profile})");
+    let (sessions, instances) = match (server, instances) {
+        (Server::Running(sessions), Some(instances)) => (sessions, instances),
+        (Server::NotRunning, _) => return Check::new(name, Outcome::Pass, "server not running"),
+        (Server::Unreachable(_), _) => {
+            return Check::new(name, Outcome::Skip, "server unreachable")
+        }
+        (_, None) => return Check::new(name, Outcome::Skip, "sessions.json unreadable"),
+    };
+    let found = orphans(sessions, instances);
+    if found.is_empty() {
+        return Check::new(name, Outcome::Pass, "none");
+    }
+    Check::new(
+        name,
+        Outcome::Fail,
+        format!(
+            "{}; remove with `tmux -L {} kill-session -t <name>`",
+            found.join(", "),
+            crate::tmux::server_name_for_profile(profile)
+        ),
+    )
+}
+
+/// Sessions of ours left on [`LEGACY_SERVER`](crate::tmux::LEGACY_SERVER):
+/// named with our prefix, or by a saved Instance of any profile. `in_use`
+/// is set when `[tmux] server` points a profile back at that server.
+/// Leftovers are a warning: they still run, just out of this profile's reach.
+pub fn legacy_check(in_use: bool, server_sessions: &[String], instances: &[Instance]) -> Check {
+    let server = crate::tmux::LEGACY_SERVER;
+    let name = format!("legacy server ({server})");
+    if in_use {
+        return Check::new(name, Outcome::Skip, "configured as the server");
+    }
+    let owned: HashSet<String> = instances.iter().map(Instance::tmux_name).collect();
+    let found: Vec<&str> = server_sessions
+        .iter()
+        .map(String::as_str)
+        .filter(|s| s.starts_with(crate::tmux::SESSION_PREFIX) || owned.contains(*s))
+        .collect();
+    if found.is_empty() {
+        return Check::new(name, Outcome::Pass, "none");
+    }
+    Check::new(
+        name,
+        Outcome::Warn,
+        format!(
+            "{}; move them with `agent-hand adopt --server {server} --migrate`, \
+             or set `server = \"{server}\"` under [tmux] to keep using it",
+            found.join(", ")
+        ),
+    )
+}
+
+/// The checks as an aligned table, one row per check.
+pub fn table(checks: &[Check]) -> String {
+    let width = checks
+        .iter()
+        .map(|c| c.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let mut out = String::new();
+    for c in checks {
+        out.push_str(&format!(
+            "{} {:<width$}  {}\n",
+            c.status.symbol(),
+            c.name,
+            c.detail
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detected(raw: &str) -> DetectedTmux {
+        DetectedTmux {
+            raw: raw.to_string(),
+            version: TmuxVersion::parse(raw),
+        }
+    }
+
+    fn instance(id: &str, tmux_name: Option<&str>) -> Instance {
+        let mut inst = Instance::new(id.into(), PathBuf::from("/src"));
+        inst.id = id.into();
+        inst.tmux_session_name = tmux_name.map(str::to_string);
+        inst
+    }
+
+    #[test]
+    fn tmux_presence_and_minimum() {
+        assert!(tmux_binary(None).failed());
+        assert!(tmux_binary(Some(&detected("2.3"))).failed());
+        assert_eq!(tmux_binary(Some(&detected("3.4"))).detail, "3.4");
+
+        let old = tmux_binary(Some(&detected("2.9a")));
+        assert_eq!(old.status, Outcome::Pass);
+        assert!(old.detail.contains("switcher popup"), "{}", old.detail);
+
+        let odd = tmux_binary(Some(&detected("master")));
+        assert_eq!(odd.status, Outcome::Pass);
+    }
+
+    #[test]
+    fn server_states() {
+        let running = Server::from_output(true, "a\nb\n", "");
+        assert_eq!(running, Server::Running(vec!["a".into(), "b".into()]));
+        assert_eq!(
+            Server::from_output(
+                false,
+                "",
+                "no server running on /tmp/tmux-1000/agenthand_default\n"
+            ),
+            Server::NotRunning
+        );
+        let stale = Server::from_output(
+            false,
+            "",
+            "error connecting to /tmp/tmux-1000/agenthand_default (Connection refused)",
+        );
+        assert!(matches!(stale, Server::Unreachable(_)));
+        assert!(server_check("default", &stale).failed());
+        assert!(!server_check("default", &Server::NotRunning).failed());
+    }
+
+    #[test]
+    fn broken_sessions_json_names_newest_valid_backup() {
+        let gens = vec![
+            (
+                "sessions.json".to_string(),
+                Generation::Invalid("EOF at line 3".into()),
+            ),
+            (
+                "sessions.bak".to_string(),
+                Generation::Invalid("EOF at line 1".into()),
+            ),
+            (
+                "sessions.bak.2".to_string(),
+                Generation::Valid {
+                    instances: vec![instance("a", None)],
+                    saved_at: "2026-03-01T09:30:00Z".parse().unwrap(),
+                },
+            ),
+            ("sessions.bak.3".to_string(), Generation::Missing),
+        ];
+        let (check, instances) = storage_check("work", gens);
+        assert!(check.failed());
+        assert!(instances.is_none());
+        assert_eq!(
+            check.detail,
+            "EOF at line 3; newest valid backup: sessions.bak.2, \
+             saved 2026-03-01 09:30 UTC (1 sessions)"
+        );
+
+        let gens = vec![("sessions.json".to_string(), Generation::Missing)];
+        let (check, instances) = storage_check("work", gens);
+        assert_eq!(check.status, Outcome::Pass);
+        assert_eq!(instances.unwrap().len(), 0);
+    }
+
+    #[test]
+    fn reads_generations_from_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let gens = storage_generations(dir.path());
+        std::fs::write(&gens[0].1, "{ not json").unwrap();
+        let data = StorageData {
+            instances: vec![instance("a", None)],
+            groups: Vec::new(),
+            relationships: Vec::new(),
+            updated_at: chrono::Utc::now(),
+            revision: 0,
+        };
+        std::fs::write(&gens[1].1, serde_json::to_string(&data).unwrap()).unwrap();
+
+        let read: Vec<_> = gens.iter().map(|(_, p)| Generation::read(p)).collect();
+        assert!(matches!(read[0], Generation::Invalid(_)));
+        assert!(matches!(&read[1], Generation::Valid { instances, .. } if instances.len() == 1));
+        assert!(matches!(read[2], Generation::Missing));
+    }
+
+    #[test]
+    fn orphans_skip_owned_and_viewer_sessions() {
+        let instances = vec![instance("abc", Some("api_abc12345")), instance("def", None)];
+        let server: Vec<String> = [
+            "api_abc12345",
+            "agentdeck_rs_def",
+            "agentdeck_rs_gone",
+            "viewer-1234",
+            "compare~api_abc12345~agentdeck_rs_def",
+        ]
+        .map(String::from)
+        .to_vec();
+        assert_eq!(orphans(&server, &instances), ["agentdeck_rs_gone"]);
+
+        let running = Server::Running(server);
+        assert!(orphan_check("default", &running, Some(&instances)).failed());
+        let blocked = orphan_check("default", &running, None);
+        assert_eq!(blocked.status, Outcome::Skip);
+    }
+
+    #[test]
+    fn legacy_sessions_by_prefix_or_owner() {
+        let instances = vec![instance("abc", Some("api_abc12345"))];
+        let server: Vec<String> = ["agentdeck_rs_def", "api_abc12345", "scratch"]
+            .map(String::from)
+            .to_vec();
+        let check = legacy_check(false, &server, &instances);
+        assert_eq!(check.status, Outcome::Warn);
+        assert!(!check.failed());
+        assert!(check.detail.starts_with("agentdeck_rs_def, api_abc12345;"));
+
+        let clean = legacy_check(false, &["scratch".to_string()], &instances);
+        assert_eq!(clean.status, Outcome::Pass);
+        assert_eq!(
+            legacy_check(true, &server, &instances).status,
+            Outcome::Skip
+        );
+    }
+
+    #[test]
+    fn table_aligns_names() {
+        let checks = vec![
+            Check::new("tmux", Outcome::Pass, "3.4"),
+            Check::new("server (default)", Outcome::Fail, "refused"),
+        ];
+        assert_eq!(
+            table(&checks),
+            "✓ tmux              3.4\n✗ server (default)  refused\n"
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! `agent-hand events`: session changes as NDJSON, one object per line.
+//!
+//! Each pass reloads the profile's sessions and probes them with the same
+//! [`status_engine`](crate::session::status_engine) cycle the dashboard
+//! runs. The first pass reports every session's status; later ones report
+//! only what changed since the pass before.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::session::{Instance, Status};
+
+/// What `--type` selects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "snake_case")]
+pub enum EventType {
+    /// Every session's status, from the first pass.
+    Status,
+    StatusChanged,
+    SessionCreated,
+    SessionDeleted,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventKind {
+    Status {
+        session_id: String,
+        title: String,
+        status: Status,
+    },
+    StatusChanged {
+        session_id: String,
+        title: String,
+        from: Status,
+        to: Status,
+    },
+    SessionCreated {
+        session_id: String,
+        title: String,
+        status: Status,
+    },
+    SessionDeleted {
+        session_id: String,
+        title: String,
+    },
+}
+
+impl EventKind {
+    pub fn event_type(&self) -> EventType {
+        match self {
+            Self::Status { .. } => EventType::Status,
+            Self::StatusChanged { .. } => EventType::StatusChanged,
+            Self::SessionCreated { .. } => EventType::SessionCreated,
+            Self::SessionDeleted { .. } => EventType::SessionDeleted,
+        }
+    }
+}
+
+/// One line of output.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Event {
+    pub ts: DateTime<Utc>,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+/// One `status` event per session, in list order.
+pub fn status_events(instances: &[Instance], ts: DateTime<Utc>) -> Vec<Event> {
+    instances
+        .iter()
+        .map(|inst| Event {
+            ts,
+            kind: EventKind::Status {
+                session_id: inst.id.clone(),
+                title: inst.title.clone(),
+                status: inst.status,
+            },
+        })
+        .collect()
+}
+
+/// Title and status of each session as of one pass, by id.
+#[derive(Debug, Default)]
+pub struct Snapshot(BTreeMap<String, (String, Status)>);
+
+impl Snapshot {
+    pub fn of(instances: &[Instance]) -> Self {
+        Self(
+            instances
+                .iter()
+                .map(|inst| (inst.id.clone(), (inst.title.clone(), inst.status)))
+                .collect(),
+        )
+    }
+
+    /// What changed between this pass and `instances`: new sessions and
+    /// status changes in list order, then deleted sessions by id.
+    pub fn diff(&self, instances: &[Instance], ts: DateTime<Utc>) -> Vec<Event> {
+        let mut events = Vec::new();
+        for inst in instances {
+            let kind = match self.0.get(&inst.id) {
+                None => EventKind::SessionCreated {
+                    session_id: inst.id.clone(),
+                    title: inst.title.clone(),
+                    status: inst.status,
+                },
+                Some(&(_, from)) if from != inst.status => EventKind::StatusChanged {
+                    session_id: inst.id.clone(),
+                    title: inst.title.clone(),
+                    from,
+                    to: inst.status,
+                },
+                Some(_) => continue,
+            };
+            events.push(Event { ts, kind });
+        }
+        for (id, (title, _)) in &self.0 {
+            if !instances.iter().any(|inst| &inst.id == id) {
+                events.push(Event {
+                    ts,
+                    kind: EventKind::SessionDeleted {
+                        session_id: id.clone(),
+                        title: title.clone(),
+                    },
+                });
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn session(id: &str, status: Status) -> Instance {
+        let mut inst = Instance::new(format!("{id} title"), PathBuf::from("/tmp"));
+        inst.id = id.into();
+        inst.status = status;
+        inst
+    }
+
+    #[test]
+    fn diff_reports_changes_only() {
+        let before = [
+            session("api", Status::Running),
+            session("web", Status::Idle),
+            session("old", Status::Idle),
+        ];
+        let after = [
+            session("api", Status::Waiting),
+            session("web", Status::Idle),
+            session("new", Status::Starting),
+        ];
+        let events = Snapshot::of(&before).diff(&after, Utc::now());
+        let kinds: Vec<EventKind> = events.into_iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                EventKind::StatusChanged {
+                    session_id: "api".into(),
+                    title: "api title".into(),
+                    from: Status::Running,
+                    to: Status::Waiting,
+                },
+                EventKind::SessionCreated {
+                    session_id: "new".into(),
+                    title: "new title".into(),
+                    status: Status::Starting,
+                },
+                EventKind::SessionDeleted {
+                    session_id: "old".into(),
+                    title: "old title".into(),
+                },
+            ]
+        );
+        assert!(Snapshot::of(&after).diff(&after, Utc::now()).is_empty());
+    }
+
+    #[test]
+    fn events_serialize_flat_with_a_type() {
+        let event = Event {
+            ts: Utc::now(),
+            kind: EventKind::StatusChanged {
+                session_id: "api".into(),
+                title: "API".into(),
+                from: Status::Running,
+                to: Status::Waiting,
+            },
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "status_changed");
+        assert_eq!(json["session_id"], "api");
+        assert_eq!(json["from"], "running");
+        assert_eq!(json["to"], "waiting");
+        assert!(json["ts"].is_string());
+
+        let first = status_events(&[session("api", Status::Idle)], Utc::now());
+        assert_eq!(first[0].kind.event_type(), EventType::Status);
+        assert_eq!(
+            EventType::from_str("session_created", false),
+            Ok(EventType::SessionCreated)
+        );
+    }
+}
@@ -1,5 +1,13 @@
 mod args;
 mod commands;
+mod completions;
+mod doctor;
+mod events;
+mod statusline;
+mod tree;
 
-pub use args::{Args, CanvasAction, Command, ConfigAction, ProfileAction, SessionAction, SkillsAction};
+pub use args::{
+    Args, CanvasAction, Command, CompleteTarget, ConfigAction, Existing, GroupAction, McpAction,
+    ProfileAction, SessionAction, SkillsAction, SnapshotAction, TemplateAction, TmuxAction,
+};
 pub use commands::run_cli;
@@ -0,0 +1,287 @@
+//! `agent-hand statusline`: the status bar tmux refreshes every few seconds.
+//!
+//! Every run is a fresh process, so the probe history the TUI keeps in
+//! memory is kept in `statusline_probe.json` instead. Each run lists tmux
+//! sessions once, and captures a pane only when
+//! [`plan_probe`](crate::session::status_engine::plan_probe) asks for it,
+//! reading at most `[status] capture_lines` lines. All the probing shares
+//! [`PROBE_BUDGET`]. Sessions it does not reach keep their last status.
+//! The same captures feed stall detection (see [`crate::session::stall`]).
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::session::waiting::{waiting_queue, waiting_since};
+use crate::session::{Instance, Status, Storage};
+
+/// Upper bound on the time one run spends probing sessions.
+pub const PROBE_BUDGET: Duration = Duration::from_millis(1500);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatuslineFormat {
+    /// The compact line for tmux status-left.
+    Text,
+    Json,
+    /// A user template with `{placeholder}`s; see [`StatuslineData::render`].
+    Template,
+}
+
+impl StatuslineFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "text" => Some(Self::Text),
+            "json" => Some(Self::Json),
+            "template" => Some(Self::Template),
+            _ => None,
+        }
+    }
+
+    /// Lock file that keeps one run of this format in flight at a time.
+    pub fn lock_name(self) -> &'static str {
+        match self {
+            Self::Text => "statusline.lock",
+            Self::Json => "statusline-json.lock",
+            Self::Template => "statusline-template.lock",
+        }
+    }
+}
+
+/// Where the probe history is kept between runs.
+pub fn probe_cache_path(profile: &str) -> Result<PathBuf> {
+    Ok(Storage::profile_dir_for(profile)?.join("statusline_probe.json"))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WaitingEntry {
+    pub title: String,
+    pub id: String,
+    pub waited_secs: i64,
+    /// What the prompt asks, when known.
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StalledEntry {
+    pub title: String,
+    pub id: String,
+    /// How long the pane has not changed.
+    pub stalled_secs: u64,
+}
+
+/// Everything the statusline formats show.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatuslineData {
+    /// Longest wait first.
+    pub waiting: Vec<WaitingEntry>,
+    /// Running, not counting stalled sessions.
+    pub running: usize,
+    /// Running with an unchanged pane; longest first.
+    pub stalled: Vec<StalledEntry>,
+    /// Idle, but ran within the ready window.
+    pub ready: usize,
+    /// Idle and not ready.
+    pub idle: usize,
+    pub stopped: usize,
+    pub error: usize,
+    /// Version of an available update.
+    pub update: Option<String>,
+}
+
+impl StatuslineData {
+    pub fn collect(
+        instances: &[Instance],
+        now: DateTime<Utc>,
+        is_ready: impl Fn(&Instance) -> bool,
+        stalled_for: impl Fn(&Instance) -> Option<Duration>,
+        update: Option<String>,
+    ) -> Self {
+        let waiting = waiting_queue(instances)
+            .into_iter()
+            .map(|s| WaitingEntry {
+                title: s.title.clone(),
+                id: s.id.clone(),
+                waited_secs: (now - waiting_since(s)).num_seconds().max(0),
+                reason: s.waiting_reason.clone(),
+            })
+            .collect();
+        let mut data = Self {
+            waiting,
+            running: 0,
+            stalled: Vec::new(),
+            ready: 0,
+            idle: 0,
+            stopped: 0,
+            error: 0,
+            update,
+        };
+        for inst in instances {
+            match inst.status {
+                Status::Waiting => {}
+                Status::Running => match stalled_for(inst) {
+                    Some(d) => data.stalled.push(StalledEntry {
+                        title: inst.title.clone(),
+                        id: inst.id.clone(),
+                        stalled_secs: d.as_secs(),
+                    }),
+                    None => data.running += 1,
+                },
+                Status::Idle if is_ready(inst) => data.ready += 1,
+                Status::Idle | Status::Starting => data.idle += 1,
+                Status::Stopped => data.stopped += 1,
+                Status::Error => data.error += 1,
+            }
+        }
+        data.stalled
+            .sort_by_key(|s| std::cmp::Reverse(s.stalled_secs));
+        data
+    }
+
+    /// Fill `template`'s placeholders:
+    /// `{waiting_count}`, `{running_count}`, `{stalled_count}`, `{ready_count}`,
+    /// `{idle_count}`, `{stopped_count}`, `{error_count}`,
+    /// `{oldest_waiting_title}`, `{oldest_waiting_id}`, `{oldest_waiting_secs}`,
+    /// `{oldest_waiting_for}` (e.g. "3m") and `{update}`. The waiting and
+    /// update ones are empty when there is nothing to show; unknown
+    /// placeholders are left as they are.
+    pub fn render(&self, template: &str) -> String {
+        let oldest = self.waiting.first();
+        let values = [
+            ("waiting_count", self.waiting.len().to_string()),
+            ("running_count", self.running.to_string()),
+            ("stalled_count", self.stalled.len().to_string()),
+            ("ready_count", self.ready.to_string()),
+            ("idle_count", self.idle.to_string()),
+            ("stopped_count", self.stopped.to_string()),
+            ("error_count", self.error.to_string()),
+            (
+                "oldest_waiting_title",
+                oldest.map(|w| w.title.clone()).unwrap_or_default(),
+            ),
+            (
+                "oldest_waiting_id",
+                oldest.map(|w| w.id.clone()).unwrap_or_default(),
+            ),
+            (
+                "oldest_waiting_secs",
+                oldest
+                    .map(|w| w.waited_secs.to_string())
+                    .unwrap_or_default(),
+            ),
+            (
+                "oldest_waiting_for",
+                oldest
+                    .map(|w| short_duration(w.waited_secs))
+                    .unwrap_or_default(),
+            ),
+            ("update", self.update.clone().unwrap_or_default()),
+        ];
+        values
+            .iter()
+            .fold(template.to_string(), |out, (name, value)| {
+                out.replace(&format!("{{{name}}}"), value)
+            })
+    }
+}
+
+/// "45s", "3m", "2h", "1d".
+fn short_duration(secs: i64) -> String {
+    match secs {
+        s if s < 60 => format!("{s}s"),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86_400 => format!("{}h", s / 3600),
+        s => format!("{}d", s / 86_400),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn session(title: &str, status: Status, waiting_secs: i64) -> Instance {
+        let mut inst = Instance::new(title.into(), PathBuf::from("/tmp"));
+        inst.id = title.into();
+        inst.status = status;
+        inst.last_waiting_at = Some(Utc::now() - chrono::Duration::seconds(waiting_secs));
+        inst
+    }
+
+    fn fixture() -> StatuslineData {
+        let mut web = session("web", Status::Waiting, 200);
+        web.waiting_reason = Some("Do you want to create main.rs?".into());
+        let sessions = [
+            session("api", Status::Waiting, 30),
+            web,
+            session("db", Status::Running, 0),
+            session("hung", Status::Running, 0),
+            session("docs", Status::Idle, 0),
+            session("old", Status::Idle, 0),
+            session("off", Status::Stopped, 0),
+        ];
+        StatuslineData::collect(
+            &sessions,
+            Utc::now(),
+            |s| s.title == "docs",
+            |s| (s.title == "hung").then_some(Duration::from_secs(420)),
+            Some("0.3.0".into()),
+        )
+    }
+
+    #[test]
+    fn collects_waiting_longest_first_and_counts() {
+        let data = fixture();
+        let waiting: Vec<&str> = data.waiting.iter().map(|w| w.title.as_str()).collect();
+        assert_eq!(waiting, ["web", "api"]);
+        assert!((199..=201).contains(&data.waiting[0].waited_secs));
+        assert_eq!(
+            (
+                data.running,
+                data.ready,
+                data.idle,
+                data.stopped,
+                data.error
+            ),
+            (1, 1, 1, 1, 0)
+        );
+
+        let json = serde_json::to_value(&data).unwrap();
+        assert_eq!(json["waiting"][0]["id"], "web");
+        assert_eq!(
+            json["waiting"][0]["reason"],
+            "Do you want to create main.rs?"
+        );
+        assert!(json["waiting"][1]["reason"].is_null());
+        assert_eq!(json["stalled"][0]["id"], "hung");
+        assert_eq!(json["stalled"][0]["stalled_secs"], 420);
+        assert_eq!(json["update"], "0.3.0");
+    }
+
+    #[test]
+    fn template_fills_known_placeholders_only() {
+        let data = fixture();
+        assert_eq!(
+            data.render("{waiting_count}! {oldest_waiting_title} ({oldest_waiting_for}) {nope}"),
+            "2! web (3m) {nope}"
+        );
+
+        assert_eq!(data.render("{running_count}/{stalled_count}"), "1/1");
+
+        let empty = StatuslineData::collect(&[], Utc::now(), |_| false, |_| None, None);
+        assert_eq!(empty.render("[{oldest_waiting_title}|{update}]"), "[|]");
+    }
+
+    #[test]
+    fn formats_parse_case_insensitively() {
+        assert_eq!(
+            StatuslineFormat::parse("JSON"),
+            Some(StatuslineFormat::Json)
+        );
+        assert_eq!(StatuslineFormat::parse("yaml"), None);
+        assert_eq!(short_duration(59), "59s");
+        assert_eq!(short_duration(7200), "2h");
+    }
+}
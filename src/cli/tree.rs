@@ -0,0 +1,345 @@
+//! `agent-hand tree`: the session list's hierarchy as Markdown, JSON or text.
+
+use serde::Serialize;
+
+use crate::session::{Instance, Status};
+use crate::ui::tree::TreeNode;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TreeFormat {
+    Markdown,
+    Json,
+    Text,
+}
+
+impl TreeFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Some(Self::Markdown),
+            "json" => Some(Self::Json),
+            "text" | "txt" => Some(Self::Text),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TreeExportOptions {
+    pub include_status: bool,
+    pub include_paths: bool,
+}
+
+pub fn render(
+    format: TreeFormat,
+    nodes: &[TreeNode],
+    sessions: &[Instance],
+    opts: TreeExportOptions,
+) -> crate::Result<String> {
+    let mut out = String::new();
+    match format {
+        TreeFormat::Markdown => {
+            for node in nodes {
+                markdown(node, sessions, opts, 0, &mut out);
+            }
+        }
+        TreeFormat::Text => {
+            for node in nodes {
+                text(node, sessions, opts, 0, &mut out);
+            }
+        }
+        TreeFormat::Json => {
+            out = serde_json::to_string_pretty(&json_level(nodes, sessions, opts))?;
+            out.push('\n');
+        }
+    }
+    Ok(out)
+}
+
+fn status_emoji(status: Status) -> &'static str {
+    match status {
+        Status::Waiting => "🔵",
+        Status::Running => "🟡",
+        Status::Idle => "⚪",
+        Status::Error => "🔴",
+        Status::Starting => "⏳",
+        Status::Stopped => "⚫",
+    }
+}
+
+/// The session list's status glyphs, without the animation.
+fn status_glyph(status: Status) -> &'static str {
+    match status {
+        Status::Waiting => "!",
+        Status::Running => "●",
+        Status::Idle => "○",
+        Status::Error => "✕",
+        Status::Starting => "⋯",
+        Status::Stopped => "■",
+    }
+}
+
+fn markdown(
+    node: &TreeNode,
+    sessions: &[Instance],
+    opts: TreeExportOptions,
+    depth: usize,
+    out: &mut String,
+) {
+    let indent = "  ".repeat(depth);
+    match node {
+        TreeNode::Group { name, children, .. } => {
+            out.push_str(&format!("{indent}- **{name}**\n"));
+            for child in children {
+                markdown(child, sessions, opts, depth + 1, out);
+            }
+        }
+        TreeNode::Forked { session, forks, .. } => {
+            markdown(&TreeNode::Session(*session), sessions, opts, depth, out);
+            for fork in forks {
+                markdown(fork, sessions, opts, depth + 1, out);
+            }
+        }
+        TreeNode::Session(i) => {
+            let s = &sessions[*i];
+            out.push_str(&format!("{indent}- "));
+            if opts.include_status {
+                out.push_str(status_emoji(s.status));
+                out.push(' ');
+            }
+            out.push_str(&s.title);
+            if opts.include_paths {
+                out.push_str(&format!(" — `{}`", s.project_path.display()));
+            }
+            out.push('\n');
+        }
+    }
+}
+
+fn text(node: &TreeNode, sessions: &[Instance], opts: TreeExportOptions, depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+    match node {
+        TreeNode::Group {
+            path,
+            name,
+            children,
+            ..
+        } => {
+            let has_subgroups = children.iter().any(|c| matches!(c, TreeNode::Group { .. }));
+            let icon = if has_subgroups { "▾" } else { " " };
+            out.push_str(&format!("{indent}{icon} {name} ({path})\n"));
+            for child in children {
+                text(child, sessions, opts, depth + 1, out);
+            }
+        }
+        TreeNode::Forked { session, forks, .. } => {
+            text(&TreeNode::Session(*session), sessions, opts, depth, out);
+            for fork in forks {
+                text(fork, sessions, opts, depth + 1, out);
+            }
+        }
+        TreeNode::Session(i) => {
+            let s = &sessions[*i];
+            out.push_str(&indent);
+            if opts.include_status {
+                out.push_str(status_glyph(s.status));
+                out.push(' ');
+            }
+            out.push_str(&s.title);
+            if opts.include_paths {
+                out.push_str(&format!("  {}", s.project_path.display()));
+            }
+            out.push('\n');
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonLevel<'a> {
+    groups: Vec<JsonGroup<'a>>,
+    sessions: Vec<JsonSession<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonGroup<'a> {
+    name: &'a str,
+    path: &'a str,
+    #[serde(flatten)]
+    children: JsonLevel<'a>,
+}
+
+#[derive(Serialize)]
+struct JsonSession<'a> {
+    id: &'a str,
+    title: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<Status>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    project_path: Option<String>,
+    /// Sessions forked from this one.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    forks: Vec<JsonSession<'a>>,
+}
+
+fn json_session<'a>(
+    i: usize,
+    forks: &'a [TreeNode],
+    sessions: &'a [Instance],
+    opts: TreeExportOptions,
+) -> JsonSession<'a> {
+    let s = &sessions[i];
+    JsonSession {
+        id: &s.id,
+        title: &s.title,
+        status: opts.include_status.then_some(s.status),
+        project_path: opts
+            .include_paths
+            .then(|| s.project_path.display().to_string()),
+        forks: json_level(forks, sessions, opts).sessions,
+    }
+}
+
+fn json_level<'a>(nodes: &'a [TreeNode], sessions: &'a [Instance], opts: TreeExportOptions) -> JsonLevel<'a> {
+    let mut level = JsonLevel {
+        groups: Vec::new(),
+        sessions: Vec::new(),
+    };
+    for node in nodes {
+        match node {
+            TreeNode::Group {
+                path,
+                name,
+                children,
+                ..
+            } => level.groups.push(JsonGroup {
+                name,
+                path,
+                children: json_level(children, sessions, opts),
+            }),
+            TreeNode::Session(i) => level.sessions.push(json_session(*i, &[], sessions, opts)),
+            TreeNode::Forked { session, forks, .. } => {
+                level.sessions.push(json_session(*session, forks, sessions, opts))
+            }
+        }
+    }
+    level
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TreeSort;
+    use crate::session::GroupTree;
+    use crate::ui::tree::{build, nest_forks, TreeScope};
+    use std::path::PathBuf;
+
+    fn fixture() -> (Vec<Instance>, GroupTree) {
+        let session = |id: &str, title: &str, group: &str, status: Status| {
+            let mut inst = Instance::new(title.into(), PathBuf::from(format!("/src/{title}")));
+            inst.id = id.into();
+            inst.group_path = group.into();
+            inst.status = status;
+            inst
+        };
+        let sessions = vec![
+            session("s1", "scratch", "", Status::Idle),
+            session("s2", "api", "work/backend", Status::Running),
+            session("s3", "docs", "work", Status::Waiting),
+        ];
+        let mut groups = GroupTree::new();
+        groups.create_group("work".into());
+        groups.create_group("work/backend".into());
+        (sessions, groups)
+    }
+
+    fn export(format: TreeFormat, include_status: bool, include_paths: bool) -> String {
+        let (sessions, groups) = fixture();
+        let nodes = build(&sessions, &groups, TreeSort::Title, TreeScope::default());
+        let opts = TreeExportOptions {
+            include_status,
+            include_paths,
+        };
+        render(format, &nodes, &sessions, opts).unwrap()
+    }
+
+    #[test]
+    fn markdown_bullets() {
+        assert_eq!(
+            export(TreeFormat::Markdown, false, false),
+            "- scratch\n- **work**\n  - **backend**\n    - api\n  - docs\n"
+        );
+        assert_eq!(
+            export(TreeFormat::Markdown, true, true),
+            "- ⚪ scratch — `/src/scratch`\n\
+             - **work**\n  \
+               - **backend**\n    \
+                 - 🟡 api — `/src/api`\n  \
+               - 🔵 docs — `/src/docs`\n"
+        );
+    }
+
+    #[test]
+    fn text_mirrors_session_list_glyphs() {
+        assert_eq!(
+            export(TreeFormat::Text, true, false),
+            "○ scratch\n▾ work (work)\n    backend (work/backend)\n    ● api\n  ! docs\n"
+        );
+        assert_eq!(
+            export(TreeFormat::Text, false, true),
+            "scratch  /src/scratch\n▾ work (work)\n    backend (work/backend)\n    api  /src/api\n  docs  /src/docs\n"
+        );
+    }
+
+    #[test]
+    fn json_nests_groups() {
+        let value: serde_json::Value =
+            serde_json::from_str(&export(TreeFormat::Json, true, false)).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "groups": [{
+                    "name": "work",
+                    "path": "work",
+                    "groups": [{
+                        "name": "backend",
+                        "path": "work/backend",
+                        "groups": [],
+                        "sessions": [{ "id": "s2", "title": "api", "status": "running" }],
+                    }],
+                    "sessions": [{ "id": "s3", "title": "docs", "status": "waiting" }],
+                }],
+                "sessions": [{ "id": "s1", "title": "scratch", "status": "idle" }],
+            })
+        );
+    }
+
+    #[test]
+    fn forks_nest_under_their_parent() {
+        let (mut sessions, groups) = fixture();
+        let mut fork = Instance::new("docs-v2".into(), PathBuf::from("/src/docs-v2"));
+        fork.id = "s4".into();
+        fork.group_path = "work".into();
+        fork.parent_session_id = Some("s3".into());
+        sessions.push(fork);
+        let nodes = nest_forks(
+            build(&sessions, &groups, TreeSort::Title, TreeScope::default()),
+            &sessions,
+        );
+        let opts = TreeExportOptions::default();
+
+        assert_eq!(
+            render(TreeFormat::Markdown, &nodes, &sessions, opts).unwrap(),
+            "- scratch\n- **work**\n  - **backend**\n    - api\n  - docs\n    - docs-v2\n"
+        );
+        let json = render(TreeFormat::Json, &nodes, &sessions, opts).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["groups"][0]["sessions"][0]["forks"][0]["id"], "s4");
+    }
+
+    #[test]
+    fn parses_format_names() {
+        assert_eq!(TreeFormat::parse("md"), Some(TreeFormat::Markdown));
+        assert_eq!(TreeFormat::parse("JSON"), Some(TreeFormat::Json));
+        assert_eq!(TreeFormat::parse("text"), Some(TreeFormat::Text));
+        assert_eq!(TreeFormat::parse("yaml"), None);
+    }
+}
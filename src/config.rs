@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use crossterm::event::{KeyCode, KeyModifiers};
 use serde::{Deserialize, Serialize};
@@ -35,10 +35,277 @@ pub enum MouseCaptureMode {
     Off,
 }
 
+/// Order of sessions within each group of the tree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TreeSort {
+    /// As arranged with the move keys; title breaks ties (default).
+    #[default]
+    Manual,
+    /// Alphabetical by title.
+    Title,
+    /// Waiting first, then running, then the rest; title breaks ties.
+    Status,
+    /// Most recently attached first.
+    Recency,
+}
+
+/// How much of the TUI is exposed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UiMode {
+    /// Navigate, attach, create, delete, search and help only.
+    Simple,
+    /// Every action (default).
+    #[default]
+    Full,
+}
+
+/// A right-aligned column on session rows in the tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionColumn {
+    /// The AI CLI the session runs.
+    Tool,
+    /// Time since the tmux session last had output or input.
+    Age,
+}
+
+impl SessionColumn {
+    fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "tool" => Some(Self::Tool),
+            "age" | "activity" => Some(Self::Age),
+            _ => None,
+        }
+    }
+}
+
+/// `[ui]` table.
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct UiConfig {
+    /// `"simple"` or `"full"` (default). F9 switches at runtime; the switch
+    /// is remembered in the UI-state file and wins over this value.
+    #[serde(default)]
+    pub mode: Option<String>,
+    /// Terminal width (columns) below which session rows drop their
+    /// `[label]` badge. Default: 80.
+    #[serde(default)]
+    pub label_min_width: Option<u16>,
+    /// p95 draw and key-handling time, in milliseconds, above which a slow
+    /// frame warning is logged (at most once a minute). Default: 50.
+    #[serde(default)]
+    pub perf_draw_warn_ms: Option<u64>,
+    /// The same for status, cache and preview refreshes. Default: 200.
+    #[serde(default)]
+    pub perf_probe_warn_ms: Option<u64>,
+    /// Right-aligned columns on session rows, in order: `"tool"` and
+    /// `"age"` (since the last tmux activity). `[]` hides them. Default:
+    /// `["tool", "age"]`.
+    #[serde(default)]
+    pub session_columns: Option<Vec<String>>,
+    /// Width of the session list below which those columns are hidden.
+    /// Default: 60.
+    #[serde(default)]
+    pub columns_min_width: Option<u16>,
+}
+
+/// `[theme]` table: a preset plus per-slot colors, e.g.
+///
+/// ```toml
+/// [theme]
+/// preset = "light"
+/// accent = "#0087af"
+///
+/// [theme.profiles.work]
+/// selection_bg = "magenta"
+/// ```
+///
+/// Slots: selection_bg, selection_fg, group, waiting, running, idle, error,
+/// accent, dim, highlight. Colors are ratatui names, `#rrggbb` or 0-255 indexes.
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct ThemeConfig {
+    /// `"default"` or `"light"`.
+    #[serde(default)]
+    pub preset: Option<String>,
+    /// Overrides for one profile, applied on top of the rest of the table.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub profiles: HashMap<String, ThemeTable>,
+    #[serde(flatten)]
+    pub colors: BTreeMap<String, String>,
+}
+
+/// `[theme.profiles.<name>]` table.
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct ThemeTable {
+    #[serde(default)]
+    pub preset: Option<String>,
+    #[serde(flatten)]
+    pub colors: BTreeMap<String, String>,
+}
+
+/// `[preview]` table.
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct PreviewConfig {
+    /// Seconds between captures of the selected session while it is
+    /// running or waiting, so the preview follows it live. 0 leaves the
+    /// preview to `p`. Default: 2.
+    #[serde(default)]
+    pub auto_refresh_secs: Option<u64>,
+    /// Scrollback lines captured when the preview is focused with Tab. 0
+    /// captures the whole scrollback. Default: 2000.
+    #[serde(default)]
+    pub focus_lines: Option<usize>,
+}
+
+/// `[reaper]` table: stopping sessions that have sat idle for a long time.
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct ReaperConfig {
+    /// Let the TUI stop idle sessions, checking at most once an hour.
+    /// `agent-hand reap` works either way. Default: false.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Hours without tmux activity before an idle session is stopped.
+    /// Default: 24.
+    #[serde(default)]
+    pub idle_hours: Option<u64>,
+}
+
+/// `[grouping]` table: which group a new session goes to, by its path.
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct GroupingConfig {
+    /// Tried in order; the first whose pattern matches the project path
+    /// wins. Without a match the group is guessed from the path.
+    #[serde(default)]
+    pub rules: Vec<GroupRule>,
+}
+
+/// One `[grouping]` rule, e.g. `{ pattern = "~/work/*/services/*",
+/// group = "work/{1}" }`. See [`crate::session::grouping`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GroupRule {
+    pub pattern: String,
+    pub group: String,
+}
+
+/// `[ptmx]` table: scanning PTY usage in the background. Each scan runs
+/// `lsof`, which is slow on busy machines, so it is off by default.
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct PtmxConfig {
+    /// Scan PTY usage from the TUI and show it per session. Default: false.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Minutes between scans. Default: 30.
+    #[serde(default)]
+    pub interval_minutes: Option<u64>,
+    /// Percentage of the system PTY limit in use above which the status bar
+    /// names the sessions holding the most. Default: 80.
+    #[serde(default)]
+    pub warn_percent: Option<u32>,
+}
+
+/// `[status]` table: how often sessions are probed for their status.
+/// Durations are in seconds and may be fractional.
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct StatusConfig {
+    /// Seconds between status passes over the sessions. Default: 1.
+    #[serde(default)]
+    pub refresh_secs: Option<f64>,
+    /// Seconds between refreshes of the tmux session list. Default: 2.
+    #[serde(default)]
+    pub cache_refresh_secs: Option<f64>,
+    /// Seconds a pane must be quiet before the selected session is
+    /// probed again. Default: 2.
+    #[serde(default)]
+    pub cooldown_secs: Option<f64>,
+    /// Seconds after which every session is probed regardless of
+    /// activity. Default: 10.
+    #[serde(default)]
+    pub fallback_secs: Option<f64>,
+    /// Pane lines captured per probe. Default: 35.
+    #[serde(default)]
+    pub capture_lines: Option<usize>,
+    /// Seconds a Running session's pane may stay unchanged before it is
+    /// shown as stalled; 0 turns stall detection off. Default: 300.
+    #[serde(default)]
+    pub stall_secs: Option<f64>,
+}
+
+/// Resolved `[status]` settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusTiming {
+    pub refresh: std::time::Duration,
+    pub cache_refresh: std::time::Duration,
+    pub cooldown: std::time::Duration,
+    pub fallback: std::time::Duration,
+    pub capture_lines: usize,
+    /// None when stall detection is off.
+    pub stall: Option<std::time::Duration>,
+}
+
+impl StatusTiming {
+    /// Shortest interval accepted for any of the durations.
+    pub const MIN_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+    pub const MIN_CAPTURE_LINES: usize = 5;
+}
+
+impl Default for StatusTiming {
+    fn default() -> Self {
+        StatusConfig::default().timing()
+    }
+}
+
+/// `secs` seconds, at least `min`. Negative values give `min`; NaN,
+/// infinite and too-large values give `fallback`, as
+/// `Duration::from_secs_f64` would panic on them.
+pub fn clamped_secs(
+    secs: f64,
+    min: std::time::Duration,
+    fallback: std::time::Duration,
+) -> std::time::Duration {
+    if secs.is_nan() {
+        return fallback.max(min);
+    }
+    std::time::Duration::try_from_secs_f64(secs.max(0.0))
+        .unwrap_or(fallback)
+        .max(min)
+}
+
+impl StatusConfig {
+    pub fn timing(&self) -> StatusTiming {
+        let secs = |v: Option<f64>, default: f64| {
+            let default = std::time::Duration::from_secs_f64(default);
+            v.map_or(default, |v| clamped_secs(v, StatusTiming::MIN_INTERVAL, default))
+        };
+        StatusTiming {
+            refresh: secs(self.refresh_secs, 1.0),
+            cache_refresh: secs(self.cache_refresh_secs, 2.0),
+            cooldown: secs(self.cooldown_secs, 2.0),
+            fallback: secs(self.fallback_secs, 10.0),
+            capture_lines: self
+                .capture_lines
+                .unwrap_or(35)
+                .max(StatusTiming::MIN_CAPTURE_LINES),
+            stall: match self.stall_secs.filter(|v| v.is_finite()).unwrap_or(300.0) {
+                v if v > 0.0 => Some(secs(Some(v), 300.0)),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// `[keybindings]` table: normal-mode actions at the top level, dialog-scoped
+/// actions under `dialog.<name>` (e.g. `dialog.delete_confirm.confirm`).
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+struct KeybindingsConfig {
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    dialog: HashMap<String, HashMap<String, OneOrMany>>,
+    #[serde(flatten)]
+    actions: HashMap<String, OneOrMany>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct ConfigFile {
     #[serde(default)]
-    keybindings: HashMap<String, OneOrMany>,
+    keybindings: KeybindingsConfig,
 
     #[serde(default)]
     tmux: TmuxKeys,
@@ -63,13 +330,38 @@ pub struct ConfigFile {
     pub sharing: SharingConfig,
 
     /// Sound notification configuration (Pro)
-    #[serde(default)]
+    #[serde(default, alias = "notifications")]
     pub notification: NotificationConfig,
 
     /// Hook integration configuration
     #[serde(default)]
     pub hooks: HooksConfig,
 
+    /// Background discovery of agent processes outside agent-hand
+    #[serde(default)]
+    pub discovery: DiscoveryConfig,
+
+    #[serde(default)]
+    pub ui: UiConfig,
+
+    #[serde(default)]
+    pub theme: ThemeConfig,
+
+    #[serde(default)]
+    pub preview: PreviewConfig,
+
+    #[serde(default)]
+    pub status: StatusConfig,
+
+    #[serde(default)]
+    pub reaper: ReaperConfig,
+
+    #[serde(default)]
+    pub grouping: GroupingConfig,
+
+    #[serde(default)]
+    pub ptmx: PtmxConfig,
+
     /// How long a session stays in "Ready (✓)" after leaving Running.
     /// Unit: minutes. Default: 40.
     #[serde(default)]
@@ -87,6 +379,47 @@ pub struct ConfigFile {
     #[serde(default)]
     pub mouse_capture: Option<String>,
 
+    /// Skip all network access (update check, heartbeat, pack registry).
+    /// Also switched on for the rest of a run when a request cannot connect.
+    #[serde(default)]
+    pub offline: bool,
+
+    /// Unlabeled profile snapshots to keep. Default: 10.
+    #[serde(default)]
+    pub snapshot_keep: Option<usize>,
+
+    /// Pane captures kept per session for browsing with [ and ] in the
+    /// preview. Default: 5.
+    #[serde(default)]
+    pub preview_history: Option<usize>,
+
+    /// Session order in the tree: "manual" (default), "title", "status",
+    /// "recency". Manual order is set with Shift+Up/Down and starts out
+    /// alphabetical; status and recency orders re-sort automatically as
+    /// sessions change.
+    #[serde(default)]
+    pub tree_sort: Option<String>,
+
+    /// Most results shown at once in the search popup and switcher;
+    /// PageUp/PageDown move through the rest. Default: 50.
+    #[serde(default)]
+    pub search_result_cap: Option<usize>,
+
+    /// Text the "notify siblings" action (P) sends to the other sessions
+    /// working on the selected session's repository.
+    #[serde(default)]
+    pub repo_sibling_message: Option<String>,
+
+    /// Lines of output the "forward output" action (F) and `agent-hand
+    /// session forward` send to the other session. Default: 50.
+    #[serde(default)]
+    pub forward_lines: Option<usize>,
+
+    /// Directory git worktrees for forks are created in ("~" expands).
+    /// Default: next to the parent session's project directory.
+    #[serde(default)]
+    pub worktree_root: Option<String>,
+
     /// Skills configuration (Pro tier)
     #[cfg(feature = "pro")]
     #[serde(default)]
@@ -220,6 +553,10 @@ struct TmuxKeys {
     jump: Option<String>,
     #[serde(default)]
     copy_mode: Option<String>,
+    /// Server (`-L` socket) name; `{profile}` becomes the profile name.
+    /// Default: `agenthand_{profile}`.
+    #[serde(default)]
+    server: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -253,6 +590,7 @@ pub struct GeminiConfig {
     pub yolo: bool,
 }
 
+/// Extra WAITING/RUNNING patterns, checked before the built-in ones.
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct StatusDetectionConfig {
     #[serde(default)]
@@ -263,6 +601,11 @@ pub struct StatusDetectionConfig {
     pub busy_contains: Vec<String>,
     #[serde(default)]
     pub busy_regex: Vec<String>,
+    /// Patterns for one tool only ("claude", "gemini", "opencode", "codex",
+    /// "shell"), checked before the global ones. A `tools` table nested
+    /// inside one of these is ignored.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tools: HashMap<String, StatusDetectionConfig>,
 }
 
 /// Configuration for remote session sharing (Premium)
@@ -356,6 +699,28 @@ pub struct NotificationConfig {
     /// Suppress sound when the session is currently attached (focused)
     #[serde(default = "default_true")]
     pub quiet_when_focused: bool,
+    /// Merge task-complete / input / error notifications that arrive in a
+    /// burst into one. A burst ends after half a second without a new one,
+    /// or this many milliseconds after it began. 0 plays each immediately.
+    #[serde(default = "default_coalesce_window_ms")]
+    pub coalesce_window_ms: u64,
+    /// Most per-session notifications per minute; the rest are summarized.
+    #[serde(default = "default_max_per_minute")]
+    pub max_per_minute: usize,
+    /// Alert when a session starts waiting for input: "bell", "system",
+    /// a sound file path, or "off". Unset plays the sound pack as before.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sound: Option<String>,
+    /// Local-time window with no sounds at all, e.g. "22:00-08:00".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quiet_hours: Option<String>,
+    /// Desktop notification (notify-send / osascript) when a session goes
+    /// from Running to Waiting. Independent of `enabled`, which is sound.
+    #[serde(default)]
+    pub desktop: bool,
+    /// At most one desktop notification per session per this many seconds.
+    #[serde(default = "default_desktop_interval_secs")]
+    pub desktop_interval_secs: u64,
 }
 
 fn default_true() -> bool {
@@ -367,6 +732,15 @@ fn default_volume() -> f32 {
 fn default_pack() -> String {
     "peon".to_string()
 }
+fn default_coalesce_window_ms() -> u64 {
+    2000
+}
+fn default_max_per_minute() -> usize {
+    6
+}
+fn default_desktop_interval_secs() -> u64 {
+    crate::notification::desktop::DEFAULT_INTERVAL_SECS
+}
 
 impl Default for NotificationConfig {
     fn default() -> Self {
@@ -382,6 +756,12 @@ impl Default for NotificationConfig {
             on_resource_limit: true,
             on_user_spam: true,
             quiet_when_focused: true,
+            coalesce_window_ms: default_coalesce_window_ms(),
+            max_per_minute: default_max_per_minute(),
+            sound: None,
+            quiet_hours: None,
+            desktop: false,
+            desktop_interval_secs: default_desktop_interval_secs(),
         }
     }
 }
@@ -403,6 +783,30 @@ impl Default for HooksConfig {
     }
 }
 
+fn default_discovery_interval() -> u64 {
+    10
+}
+
+/// Discovery of claude/codex/gemini processes running outside agent-hand.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DiscoveryConfig {
+    /// Periodically scan for unmanaged agent processes (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Minutes between scans (default: 10)
+    #[serde(default = "default_discovery_interval")]
+    pub interval_minutes: u64,
+}
+
+impl Default for DiscoveryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_minutes: default_discovery_interval(),
+        }
+    }
+}
+
 /// Skills library configuration (Pro tier)
 #[cfg(feature = "pro")]
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -486,20 +890,7 @@ impl ConfigFile {
         // 2. ~/.agent-hand/config.toml
         // 3. ~/.config/agent-hand/config.toml (XDG standard)
         // 4. ~/.config/agent-hand/config.json
-        let agent_hand_dir = Storage::get_agent_hand_dir()?;
-        let xdg_dir = dirs::home_dir().map(|h| h.join(".config").join("agent-hand"));
-
-        let candidates: Vec<std::path::PathBuf> = [
-            Some(agent_hand_dir.join("config.toml")),
-            xdg_dir.as_ref().map(|d| d.join("config.toml")),
-            Some(agent_hand_dir.join("config.json")),
-            xdg_dir.as_ref().map(|d| d.join("config.json")),
-        ]
-        .into_iter()
-        .flatten()
-        .collect();
-
-        for path in candidates {
+        for path in Self::candidate_paths()? {
             let content = match fs::read_to_string(&path).await {
                 Ok(c) => c,
                 Err(_) => continue,
@@ -516,6 +907,56 @@ impl ConfigFile {
         Ok(None)
     }
 
+    fn candidate_paths() -> Result<Vec<std::path::PathBuf>> {
+        let agent_hand_dir = Storage::get_agent_hand_dir()?;
+        let xdg_dir = dirs::home_dir().map(|h| h.join(".config").join("agent-hand"));
+
+        Ok([
+            Some(agent_hand_dir.join("config.toml")),
+            xdg_dir.as_ref().map(|d| d.join("config.toml")),
+            Some(agent_hand_dir.join("config.json")),
+            xdg_dir.as_ref().map(|d| d.join("config.json")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect())
+    }
+
+    /// The file [`load`](Self::load) reads, or `~/.agent-hand/config.toml`
+    /// if there is none yet.
+    pub fn active_path() -> Result<std::path::PathBuf> {
+        let candidates = Self::candidate_paths()?;
+        Ok(candidates
+            .iter()
+            .find(|p| p.is_file())
+            .cloned()
+            .unwrap_or_else(|| candidates[0].clone()))
+    }
+
+    /// Write one binding into the active config file, leaving everything
+    /// else in it as it was, and return the reloaded config.
+    pub async fn write_binding(target: RebindTarget, key: &KeySpec) -> Result<Self> {
+        let path = Self::active_path()?;
+        if path.extension().and_then(|e| e.to_str()) != Some("toml") {
+            return Err(crate::Error::config(format!(
+                "{} is JSON; move it to config.toml to rebind keys from the TUI",
+                path.display()
+            )));
+        }
+        let source = match fs::read_to_string(&path).await {
+            Ok(s) => s,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+        let edited = edit_binding(&source, target, key)?;
+        let cfg: Self = toml::from_str(&edited)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).await?;
+        }
+        fs::write(&path, edited).await?;
+        Ok(cfg)
+    }
+
     pub fn tmux_switcher_key(&self) -> Option<&str> {
         self.tmux.switcher.as_deref()
     }
@@ -532,6 +973,10 @@ impl ConfigFile {
         self.tmux.copy_mode.as_deref()
     }
 
+    pub fn tmux_server(&self) -> Option<&str> {
+        self.tmux.server.as_deref()
+    }
+
     pub fn analytics_enabled(&self) -> bool {
         self.analytics.enabled
     }
@@ -560,6 +1005,143 @@ impl ConfigFile {
         self.scroll_padding.unwrap_or(5)
     }
 
+    pub fn snapshot_keep(&self) -> usize {
+        self.snapshot_keep
+            .unwrap_or(crate::session::snapshot::DEFAULT_SNAPSHOT_KEEP)
+    }
+
+    pub fn preview_history(&self) -> usize {
+        self.preview_history
+            .unwrap_or(crate::ui::preview_history::DEFAULT_PREVIEW_HISTORY)
+            .max(1)
+    }
+
+    pub fn status_timing(&self) -> StatusTiming {
+        self.status.timing()
+    }
+
+    /// Interval of the live preview; None when turned off.
+    pub fn preview_auto_refresh(&self) -> Option<std::time::Duration> {
+        match self.preview.auto_refresh_secs.unwrap_or(2) {
+            0 => None,
+            secs => Some(std::time::Duration::from_secs(secs)),
+        }
+    }
+
+    /// Lines the focused preview captures; None for the whole scrollback.
+    pub fn preview_focus_lines(&self) -> Option<usize> {
+        match self.preview.focus_lines.unwrap_or(2000) {
+            0 => None,
+            n => Some(n),
+        }
+    }
+
+    /// How long a session must sit without activity before the reaper
+    /// stops it.
+    pub fn reaper_idle_after(&self) -> std::time::Duration {
+        let hours = self
+            .reaper
+            .idle_hours
+            .unwrap_or(crate::session::reaper::DEFAULT_IDLE_HOURS)
+            .max(1);
+        std::time::Duration::from_secs(hours * 3600)
+    }
+
+    /// `[grouping] rules`, in the order they are tried.
+    pub fn group_rules(&self) -> &[GroupRule] {
+        &self.grouping.rules
+    }
+
+    /// Time between background PTY scans.
+    pub fn ptmx_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.ptmx.interval_minutes.unwrap_or(30).max(1) * 60)
+    }
+
+    /// Percentage of the system PTY limit in use that raises the warning.
+    pub fn ptmx_warn_percent(&self) -> u32 {
+        self.ptmx.warn_percent.unwrap_or(80).clamp(1, 100)
+    }
+
+    pub fn search_result_cap(&self) -> usize {
+        self.search_result_cap.unwrap_or(50).max(1)
+    }
+
+    pub fn forward_lines(&self) -> usize {
+        self.forward_lines
+            .unwrap_or(crate::session::forward::DEFAULT_FORWARD_LINES)
+            .max(1)
+    }
+
+    pub fn repo_sibling_message(&self) -> &str {
+        self.repo_sibling_message
+            .as_deref()
+            .filter(|m| !m.trim().is_empty())
+            .unwrap_or(crate::session::repo::DEFAULT_SIBLING_MESSAGE)
+    }
+
+    pub fn worktree_root(&self) -> Option<std::path::PathBuf> {
+        let root = self.worktree_root.as_deref()?.trim();
+        if root.is_empty() {
+            return None;
+        }
+        match root.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().map(|home| home.join(rest)),
+            None if root == "~" => dirs::home_dir(),
+            None => Some(std::path::PathBuf::from(root)),
+        }
+    }
+
+    pub fn tree_sort(&self) -> TreeSort {
+        match self.tree_sort.as_deref() {
+            Some("title") => TreeSort::Title,
+            Some("status") => TreeSort::Status,
+            Some("recency" | "recent") => TreeSort::Recency,
+            _ => TreeSort::Manual,
+        }
+    }
+
+    pub fn ui_mode(&self) -> UiMode {
+        match self.ui.mode.as_deref() {
+            Some("simple") => UiMode::Simple,
+            _ => UiMode::Full,
+        }
+    }
+
+    pub fn label_min_width(&self) -> u16 {
+        self.ui.label_min_width.unwrap_or(80)
+    }
+
+    /// Session row columns in the configured order; unknown names and
+    /// repeats are dropped.
+    pub fn session_columns(&self) -> Vec<SessionColumn> {
+        let Some(names) = &self.ui.session_columns else {
+            return vec![SessionColumn::Tool, SessionColumn::Age];
+        };
+        let mut columns = Vec::new();
+        for column in names.iter().filter_map(|n| SessionColumn::from_name(n)) {
+            if !columns.contains(&column) {
+                columns.push(column);
+            }
+        }
+        columns
+    }
+
+    pub fn columns_min_width(&self) -> u16 {
+        self.ui.columns_min_width.unwrap_or(60)
+    }
+
+    pub fn theme(&self, profile: &str) -> crate::ui::theme::Theme {
+        crate::ui::theme::Theme::from_config(&self.theme, profile)
+    }
+
+    pub fn perf_thresholds(&self) -> crate::ui::perf::Thresholds {
+        let defaults = crate::ui::perf::Thresholds::default();
+        crate::ui::perf::Thresholds {
+            draw: self.ui.perf_draw_warn_ms.map_or(defaults.draw, std::time::Duration::from_millis),
+            probe: self.ui.perf_probe_warn_ms.map_or(defaults.probe, std::time::Duration::from_millis),
+        }
+    }
+
     pub fn mouse_capture(&self) -> MouseCaptureMode {
         match self.mouse_capture.as_deref() {
             Some("on") => MouseCaptureMode::On,
@@ -602,9 +1184,9 @@ impl ConfigFile {
     pub fn set_keybinding(&mut self, action: &str, specs: &[KeySpec]) {
         let strings: Vec<String> = specs.iter().map(|k| format_key_spec(k)).collect();
         if strings.len() == 1 {
-            self.keybindings.insert(action.to_string(), OneOrMany::One(strings.into_iter().next().unwrap()));
+            self.keybindings.actions.insert(action.to_string(), OneOrMany::One(strings.into_iter().next().unwrap()));
         } else {
-            self.keybindings.insert(action.to_string(), OneOrMany::Many(strings));
+            self.keybindings.actions.insert(action.to_string(), OneOrMany::Many(strings));
         }
     }
 
@@ -625,27 +1207,265 @@ impl ConfigFile {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct KeySpec {
     pub code: KeyCode,
     pub modifiers: KeyModifiers,
 }
 
+/// Normal-mode actions in precedence order, matching the order
+/// `handle_normal_key` tests them. When two actions are bound to the same
+/// key, the earlier one keeps it and the key is removed from the later one.
+pub const NORMAL_ACTION_PRIORITY: &[&str] = &[
+    "quit",
+    "settings",
+    "canvas_toggle",
+    "jump_priority",
+    "jump_waiting",
+    "waiting_queue",
+    "profile_picker",
+    "message_log",
+    "up",
+    "down",
+    "half_page_down",
+    "half_page_up",
+    "page_down",
+    "page_up",
+    "top",
+    "bottom",
+    "move_session_up",
+    "move_session_down",
+    "select",
+    "collapse",
+    "expand",
+    "toggle_group",
+    "mark",
+    "start",
+    "stop",
+    "refresh",
+    "rename",
+    "boost",
+    "summarize",
+    "behavior_analysis",
+    "new_session",
+    "delete",
+    "undo",
+    "fork",
+    "lineage",
+    "create_group",
+    "move",
+    "move_group",
+    "group_notes",
+    "tag",
+    "label",
+    "add_to_canvas",
+    "preview_older",
+    "preview_newer",
+    "preview_focus",
+    "preview_page_up",
+    "preview_page_down",
+    "search",
+    "chat_toggle",
+    "help",
+    "restart",
+    "resume",
+    "skills_browser",
+    "unmanaged_agents",
+    "compare",
+    "transcript",
+    "toggle_autostart",
+    "pin",
+    "acknowledge_stall",
+    "cycle_layout",
+    "jump_back",
+    "notify_siblings",
+    "forward_output",
+    "wrapup",
+    "toggle_ui_mode",
+];
+
+/// Dialogs whose keys go through the bindings table, each with its actions
+/// in precedence order. Configured as `keybindings.dialog.<dialog>.<action>`.
+pub const DIALOG_ACTIONS: &[(&str, &[&str])] = &[
+    (
+        "delete_confirm",
+        &["cancel", "confirm", "toggle_kill_tmux", "toggle_remove_worktree"],
+    ),
+    ("delete_group", &["cancel", "confirm", "up", "down"]),
+];
+
+/// Two actions in the same scope bound to the same key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyConflict {
+    /// `"normal"` or `"dialog.<name>"`.
+    pub scope: String,
+    pub key: KeySpec,
+    /// Action that keeps the key.
+    pub winner: &'static str,
+    /// Action the key was taken away from.
+    pub loser: &'static str,
+}
+
+impl KeyConflict {
+    pub fn describe(&self) -> String {
+        format!(
+            "[{}] {}: {} shadows {}",
+            self.scope,
+            format_key_spec(&self.key),
+            self.winner,
+            self.loser
+        )
+    }
+}
+
+/// Uppercase letters are matched with or without Shift, so compare them
+/// without it.
+pub fn normalize_key(k: &KeySpec) -> KeySpec {
+    match k.code {
+        KeyCode::Char(c) if c.is_ascii_uppercase() => KeySpec {
+            code: k.code,
+            modifiers: k.modifiers - KeyModifiers::SHIFT,
+        },
+        _ => *k,
+    }
+}
+
+/// Find actions in one scope that share a key.
+///
+/// Actions are visited in `priority` order (unlisted ones afterwards, by
+/// name); the first action to claim a key wins it and every later claimant
+/// is reported as a conflict.
+pub fn detect_conflicts(
+    scope: &str,
+    priority: &[&'static str],
+    bindings: &HashMap<&'static str, Vec<KeySpec>>,
+) -> Vec<KeyConflict> {
+    let mut order: Vec<&'static str> = priority
+        .iter()
+        .copied()
+        .filter(|a| bindings.contains_key(a))
+        .collect();
+    let mut rest: Vec<&'static str> = bindings
+        .keys()
+        .copied()
+        .filter(|a| !priority.contains(a))
+        .collect();
+    rest.sort_unstable();
+    order.extend(rest);
+
+    let mut owners: HashMap<KeySpec, &'static str> = HashMap::new();
+    let mut conflicts = Vec::new();
+    for action in order {
+        for key in &bindings[action] {
+            match owners.get(&normalize_key(key)) {
+                Some(&winner) if winner != action => conflicts.push(KeyConflict {
+                    scope: scope.to_string(),
+                    key: *key,
+                    winner,
+                    loser: action,
+                }),
+                Some(_) => {}
+                None => {
+                    owners.insert(normalize_key(key), action);
+                }
+            }
+        }
+    }
+    conflicts
+}
+
+fn strip_conflicts(bindings: &mut HashMap<&'static str, Vec<KeySpec>>, conflicts: &[KeyConflict]) {
+    for c in conflicts {
+        if let Some(specs) = bindings.get_mut(c.loser) {
+            let lost = normalize_key(&c.key);
+            specs.retain(|k| normalize_key(k) != lost);
+        }
+    }
+}
+
+fn parse_key_specs(spec: &OneOrMany) -> Vec<KeySpec> {
+    spec.clone()
+        .into_vec()
+        .iter()
+        .filter_map(|s| parse_key_spec(s))
+        .collect()
+}
+
+fn key_matches(specs: &[KeySpec], code: &KeyCode, modifiers: KeyModifiers) -> bool {
+    // Crossterm reports Shift+A as Char('A') + SHIFT, but keybindings store
+    // uppercase letters as Char('A') + NONE. Strip the implicit SHIFT for
+    // uppercase characters so the comparison works.
+    let effective_modifiers = if let KeyCode::Char(c) = code {
+        if c.is_ascii_uppercase() {
+            modifiers - KeyModifiers::SHIFT
+        } else {
+            modifiers
+        }
+    } else {
+        modifiers
+    };
+
+    specs
+        .iter()
+        .any(|k| &k.code == code && k.modifiers == effective_modifiers)
+}
+
 #[derive(Debug, Clone)]
 pub struct KeyBindings {
     bindings: HashMap<&'static str, Vec<KeySpec>>,
+    dialogs: HashMap<&'static str, HashMap<&'static str, Vec<KeySpec>>>,
+    conflicts: Vec<KeyConflict>,
 }
 
 impl Default for KeyBindings {
     fn default() -> Self {
         let mut kb = Self {
             bindings: HashMap::new(),
+            dialogs: HashMap::new(),
+            conflicts: Vec::new(),
         };
 
-        kb.bindings.insert(
-            "quit",
-            vec![
-                KeySpec {
+        let plain = |c: char| KeySpec {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::NONE,
+        };
+        let named = |code: KeyCode| KeySpec {
+            code,
+            modifiers: KeyModifiers::NONE,
+        };
+
+        kb.dialogs.insert(
+            "delete_confirm",
+            HashMap::from([
+                ("cancel", vec![named(KeyCode::Esc), plain('n'), plain('N')]),
+                ("confirm", vec![named(KeyCode::Enter), plain('y'), plain('Y')]),
+                ("toggle_kill_tmux", vec![plain('t'), plain('T')]),
+                ("toggle_remove_worktree", vec![plain('w'), plain('W')]),
+            ]),
+        );
+        kb.dialogs.insert(
+            "delete_group",
+            HashMap::from([
+                (
+                    "cancel",
+                    vec![
+                        named(KeyCode::Esc),
+                        KeySpec {
+                            code: KeyCode::Char('c'),
+                            modifiers: KeyModifiers::CONTROL,
+                        },
+                    ],
+                ),
+                ("confirm", vec![named(KeyCode::Enter)]),
+                ("up", vec![named(KeyCode::Up), plain('k')]),
+                ("down", vec![named(KeyCode::Down), plain('j')]),
+            ]),
+        );
+
+        kb.bindings.insert(
+            "quit",
+            vec![
+                KeySpec {
                     code: KeyCode::Char('q'),
                     modifiers: KeyModifiers::NONE,
                 },
@@ -685,6 +1505,20 @@ impl Default for KeyBindings {
                 },
             ],
         );
+        kb.bindings.insert(
+            "move_session_up",
+            vec![KeySpec {
+                code: KeyCode::Up,
+                modifiers: KeyModifiers::SHIFT,
+            }],
+        );
+        kb.bindings.insert(
+            "move_session_down",
+            vec![KeySpec {
+                code: KeyCode::Down,
+                modifiers: KeyModifiers::SHIFT,
+            }],
+        );
 
         kb.bindings.insert(
             "select",
@@ -715,6 +1549,13 @@ impl Default for KeyBindings {
             }],
         );
 
+        kb.bindings.insert(
+            "mark",
+            vec![KeySpec {
+                code: KeyCode::Char('v'),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
         kb.bindings.insert(
             "start",
             vec![KeySpec {
@@ -764,6 +1605,13 @@ impl Default for KeyBindings {
                 modifiers: KeyModifiers::NONE,
             }],
         );
+        kb.bindings.insert(
+            "lineage",
+            vec![KeySpec {
+                code: KeyCode::Char('L'),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
         kb.bindings.insert(
             "create_group",
             vec![KeySpec {
@@ -778,6 +1626,20 @@ impl Default for KeyBindings {
                 modifiers: KeyModifiers::NONE,
             }],
         );
+        kb.bindings.insert(
+            "move_group",
+            vec![KeySpec {
+                code: KeyCode::Char('M'),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
+        kb.bindings.insert(
+            "group_notes",
+            vec![KeySpec {
+                code: KeyCode::Char('e'),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
         kb.bindings.insert(
             "tag",
             vec![KeySpec {
@@ -785,6 +1647,13 @@ impl Default for KeyBindings {
                 modifiers: KeyModifiers::NONE,
             }],
         );
+        kb.bindings.insert(
+            "label",
+            vec![KeySpec {
+                code: KeyCode::Char('c'),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
         kb.bindings.insert(
             "canvas_toggle",
             vec![KeySpec {
@@ -799,6 +1668,35 @@ impl Default for KeyBindings {
                 modifiers: KeyModifiers::NONE,
             }],
         );
+        kb.bindings.insert(
+            "preview_older",
+            vec![KeySpec {
+                code: KeyCode::Char('['),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
+        kb.bindings.insert(
+            "preview_newer",
+            vec![KeySpec {
+                code: KeyCode::Char(']'),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
+        kb.bindings.insert("preview_focus", vec![named(KeyCode::Tab)]);
+        kb.bindings.insert(
+            "preview_page_up",
+            vec![KeySpec {
+                code: KeyCode::PageUp,
+                modifiers: KeyModifiers::SHIFT,
+            }],
+        );
+        kb.bindings.insert(
+            "preview_page_down",
+            vec![KeySpec {
+                code: KeyCode::PageDown,
+                modifiers: KeyModifiers::SHIFT,
+            }],
+        );
         kb.bindings.insert(
             "search",
             vec![KeySpec {
@@ -820,6 +1718,20 @@ impl Default for KeyBindings {
                 modifiers: KeyModifiers::CONTROL,
             }],
         );
+        kb.bindings.insert(
+            "jump_waiting",
+            vec![KeySpec {
+                code: KeyCode::Char('W'),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
+        kb.bindings.insert(
+            "waiting_queue",
+            vec![KeySpec {
+                code: KeyCode::Char('w'),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
         kb.bindings.insert(
             "restart",
             vec![KeySpec {
@@ -862,6 +1774,10 @@ impl Default for KeyBindings {
                 modifiers: KeyModifiers::CONTROL,
             }],
         );
+        kb.bindings.insert("page_down", vec![named(KeyCode::PageDown)]);
+        kb.bindings.insert("page_up", vec![named(KeyCode::PageUp)]);
+        kb.bindings.insert("top", vec![named(KeyCode::Home)]);
+        kb.bindings.insert("bottom", vec![named(KeyCode::End)]);
         kb.bindings.insert(
             "settings",
             vec![
@@ -876,12 +1792,19 @@ impl Default for KeyBindings {
             ],
         );
         kb.bindings.insert(
-            "resume",
+            "undo",
             vec![KeySpec {
                 code: KeyCode::Char('u'),
                 modifiers: KeyModifiers::NONE,
             }],
         );
+        kb.bindings.insert(
+            "resume",
+            vec![KeySpec {
+                code: KeyCode::Char('o'),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
         kb.bindings.insert(
             "skills_browser",
             vec![KeySpec {
@@ -889,6 +1812,104 @@ impl Default for KeyBindings {
                 modifiers: KeyModifiers::NONE,
             }],
         );
+        kb.bindings.insert(
+            "unmanaged_agents",
+            vec![KeySpec {
+                code: KeyCode::Char('U'),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
+        kb.bindings.insert(
+            "compare",
+            vec![KeySpec {
+                code: KeyCode::Char('|'),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
+        kb.bindings.insert(
+            "transcript",
+            vec![KeySpec {
+                code: KeyCode::Char('T'),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
+        kb.bindings.insert(
+            "toggle_autostart",
+            vec![KeySpec {
+                code: KeyCode::Char('O'),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
+        kb.bindings.insert(
+            "pin",
+            vec![KeySpec {
+                code: KeyCode::Char('*'),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
+        kb.bindings.insert(
+            "acknowledge_stall",
+            vec![KeySpec {
+                code: KeyCode::Char('Z'),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
+        kb.bindings.insert(
+            "cycle_layout",
+            vec![KeySpec {
+                code: KeyCode::Char('z'),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
+        kb.bindings.insert(
+            "jump_back",
+            vec![KeySpec {
+                code: KeyCode::Char('`'),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
+        kb.bindings.insert(
+            "forward_output",
+            vec![KeySpec {
+                code: KeyCode::Char('F'),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
+        kb.bindings.insert(
+            "notify_siblings",
+            vec![KeySpec {
+                code: KeyCode::Char('P'),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
+        kb.bindings.insert(
+            "wrapup",
+            vec![KeySpec {
+                code: KeyCode::Char('E'),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
+        kb.bindings.insert(
+            "profile_picker",
+            vec![KeySpec {
+                code: KeyCode::Char('p'),
+                modifiers: KeyModifiers::CONTROL,
+            }],
+        );
+        kb.bindings.insert(
+            "message_log",
+            vec![KeySpec {
+                code: KeyCode::Char('!'),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
+        kb.bindings.insert(
+            "toggle_ui_mode",
+            vec![KeySpec {
+                code: KeyCode::F(9),
+                modifiers: KeyModifiers::NONE,
+            }],
+        );
         kb.bindings.insert(
             "chat_toggle",
             vec![KeySpec {
@@ -903,18 +1924,20 @@ impl Default for KeyBindings {
 
 impl KeyBindings {
     pub async fn load_or_default() -> Self {
+        match ConfigFile::load().await {
+            Ok(Some(cfg)) => Self::from_config(&cfg),
+            _ => Self::default(),
+        }
+    }
+
+    /// Defaults overlaid with the config's bindings, with conflicts resolved
+    /// by precedence (see [`NORMAL_ACTION_PRIORITY`] and [`DIALOG_ACTIONS`]).
+    /// Unknown actions and unparseable keys are ignored.
+    pub fn from_config(cfg: &ConfigFile) -> Self {
         let mut kb = Self::default();
-        let Ok(Some(cfg)) = ConfigFile::load().await else {
-            return kb;
-        };
 
-        for (action, spec) in cfg.keybindings {
-            let mut parsed = Vec::new();
-            for s in spec.into_vec() {
-                if let Some(k) = parse_key_spec(&s) {
-                    parsed.push(k);
-                }
-            }
+        for (action, spec) in &cfg.keybindings.actions {
+            let parsed = parse_key_specs(spec);
             if !parsed.is_empty() {
                 if let Some(slot) = kb.bindings.get_mut(action.as_str()) {
                     *slot = parsed;
@@ -922,34 +1945,97 @@ impl KeyBindings {
             }
         }
 
+        for (dialog, actions) in &cfg.keybindings.dialog {
+            let Some(scope) = kb.dialogs.get_mut(dialog.as_str()) else {
+                continue;
+            };
+            for (action, spec) in actions {
+                let parsed = parse_key_specs(spec);
+                if !parsed.is_empty() {
+                    if let Some(slot) = scope.get_mut(action.as_str()) {
+                        *slot = parsed;
+                    }
+                }
+            }
+        }
+
+        kb.resolve_conflicts();
         kb
     }
 
+    fn resolve_conflicts(&mut self) {
+        let mut conflicts = detect_conflicts("normal", NORMAL_ACTION_PRIORITY, &self.bindings);
+        strip_conflicts(&mut self.bindings, &conflicts);
+
+        for (dialog, priority) in DIALOG_ACTIONS {
+            if let Some(scope) = self.dialogs.get_mut(dialog) {
+                let found = detect_conflicts(&format!("dialog.{dialog}"), priority, scope);
+                strip_conflicts(scope, &found);
+                conflicts.extend(found);
+            }
+        }
+
+        self.conflicts = conflicts;
+    }
+
+    /// The conflicts binding `action` to `key` alone would cause, as
+    /// [`from_config`](Self::from_config) would resolve them.
+    pub fn conflicts_if_bound(&self, action: &'static str, key: KeySpec) -> Vec<KeyConflict> {
+        let mut bindings = self.bindings.clone();
+        bindings.insert(action, vec![key]);
+        detect_conflicts("normal", NORMAL_ACTION_PRIORITY, &bindings)
+            .into_iter()
+            .filter(|c| c.winner == action || c.loser == action)
+            .collect()
+    }
+
+    /// Conflicts found when the bindings were loaded.
+    pub fn conflicts(&self) -> &[KeyConflict] {
+        &self.conflicts
+    }
+
     pub fn get_specs(&self, action: &str) -> Option<&[KeySpec]> {
         self.bindings.get(action).map(|v| v.as_slice())
     }
 
+    /// The keys bound to `action` as shown in the help, e.g. `q/Q/Ctrl+c`.
+    /// None when the action is unbound.
+    pub fn describe(&self, action: &str) -> Option<String> {
+        let specs = self.get_specs(action).filter(|v| !v.is_empty())?;
+        Some(specs.iter().map(format_key_spec).collect::<Vec<_>>().join("/"))
+    }
+
     pub fn set_binding(&mut self, action: &'static str, specs: Vec<KeySpec>) {
         self.bindings.insert(action, specs);
     }
 
     pub fn matches(&self, action: &'static str, code: &KeyCode, modifiers: KeyModifiers) -> bool {
-        // Crossterm reports Shift+A as Char('A') + SHIFT, but keybindings store
-        // uppercase letters as Char('A') + NONE. Strip the implicit SHIFT for
-        // uppercase characters so the comparison works.
-        let effective_modifiers = if let KeyCode::Char(c) = code {
-            if c.is_ascii_uppercase() {
-                modifiers - KeyModifiers::SHIFT
-            } else {
-                modifiers
-            }
-        } else {
-            modifiers
-        };
+        self.bindings
+            .get(action)
+            .is_some_and(|v| key_matches(v, code, modifiers))
+    }
+
+    /// The normal-mode action a key triggers, if any.
+    pub fn normal_action(&self, code: &KeyCode, modifiers: KeyModifiers) -> Option<&'static str> {
+        NORMAL_ACTION_PRIORITY
+            .iter()
+            .copied()
+            .find(|action| self.matches(action, code, modifiers))
+    }
 
-        self.bindings.get(action).is_some_and(|v| {
-            v.iter()
-                .any(|k| &k.code == code && k.modifiers == effective_modifiers)
+    /// The action a key triggers inside `dialog`, if any.
+    pub fn dialog_action(
+        &self,
+        dialog: &str,
+        code: &KeyCode,
+        modifiers: KeyModifiers,
+    ) -> Option<&'static str> {
+        let scope = self.dialogs.get(dialog)?;
+        let (_, priority) = DIALOG_ACTIONS.iter().find(|(d, _)| *d == dialog)?;
+        priority.iter().copied().find(|action| {
+            scope
+                .get(action)
+                .is_some_and(|v| key_matches(v, code, modifiers))
         })
     }
 }
@@ -977,6 +2063,7 @@ pub fn format_key_spec(k: &KeySpec) -> String {
         KeyCode::Right => "Right".to_string(),
         KeyCode::Char(' ') => "Space".to_string(),
         KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{n}"),
         other => format!("{:?}", other),
     };
     parts.push(key);
@@ -1020,6 +2107,13 @@ fn parse_key_spec(s: &str) -> Option<KeySpec> {
         "down" => KeyCode::Down,
         "left" => KeyCode::Left,
         "right" => KeyCode::Right,
+        "pageup" | "pgup" => KeyCode::PageUp,
+        "pagedown" | "pgdn" => KeyCode::PageDown,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        f if f.len() > 1 && f.starts_with('f') && f[1..].parse::<u8>().is_ok() => {
+            KeyCode::F(f[1..].parse().ok()?)
+        }
         _ => {
             // Single-character fallback (keeps case for e.g. "R")
             if key_part_trim.chars().count() == 1 {
@@ -1090,6 +2184,86 @@ pub fn parse_tmux_key(s: &str) -> Option<String> {
     }
 }
 
+/// The tmux notation for a captured key — the reverse of
+/// [`parse_tmux_key`]. None for keys tmux bindings cannot express here.
+pub fn tmux_key_for_spec(k: &KeySpec) -> Option<String> {
+    let key = match k.code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Esc => "Escape".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        _ => return None,
+    };
+    let key = if k.modifiers.contains(KeyModifiers::CONTROL) {
+        format!("C-{key}")
+    } else if k.modifiers.contains(KeyModifiers::ALT) {
+        format!("M-{key}")
+    } else {
+        key
+    };
+    Some(escape_tmux_key(&key))
+}
+
+/// A binding the rebind helper can write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebindTarget {
+    /// A normal-mode action under `[keybindings]`.
+    Action(&'static str),
+    /// A key bound on the tmux server under `[tmux]`: "switcher" or "detach".
+    Tmux(&'static str),
+}
+
+impl RebindTarget {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Action(name) | Self::Tmux(name) => name,
+        }
+    }
+
+    /// The value written to the config file for `key`.
+    pub fn config_value(&self, key: &KeySpec) -> Option<String> {
+        match self {
+            Self::Action(_) => Some(format_key_spec(key)),
+            Self::Tmux(_) => tmux_key_for_spec(key),
+        }
+    }
+}
+
+/// Set one binding in a config file's source text. Only the edited key
+/// changes: comments, ordering and formatting elsewhere are kept.
+pub fn edit_binding(source: &str, target: RebindTarget, key: &KeySpec) -> Result<String> {
+    let value = target
+        .config_value(key)
+        .ok_or_else(|| crate::Error::config(format!("{} cannot be bound in tmux", format_key_spec(key))))?;
+    let mut doc: toml_edit::DocumentMut = source
+        .parse()
+        .map_err(|e: toml_edit::TomlError| crate::Error::config(e.to_string()))?;
+
+    let (section, name) = match target {
+        RebindTarget::Action(name) => ("keybindings", name),
+        RebindTarget::Tmux(name) => ("tmux", name),
+    };
+    let table = doc
+        .entry(section)
+        .or_insert_with(toml_edit::table)
+        .as_table_like_mut()
+        .ok_or_else(|| crate::Error::config(format!("[{section}] is not a table")))?;
+
+    match table.get_mut(name).and_then(|item| item.as_value_mut()) {
+        // Keep the comment after an existing binding.
+        Some(existing) => {
+            let decor = existing.decor().clone();
+            *existing = value.into();
+            *existing.decor_mut() = decor;
+        }
+        None => {
+            table.insert(name, toml_edit::value(value));
+        }
+    }
+    Ok(doc.to_string())
+}
+
 fn escape_tmux_key(s: &str) -> String {
     // tmux treats `;` as a command separator in its command language, so it must be escaped.
     let mut out = String::with_capacity(s.len());
@@ -1103,3 +2277,249 @@ fn escape_tmux_key(s: &str) -> String {
     }
     out
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(c: char) -> KeySpec {
+        KeySpec {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    fn cfg(toml_src: &str) -> ConfigFile {
+        toml::from_str(toml_src).unwrap()
+    }
+
+    #[test]
+    fn defaults_have_no_conflicts() {
+        let kb = KeyBindings::from_config(&ConfigFile::default());
+        assert!(kb.conflicts().is_empty(), "{:?}", kb.conflicts());
+    }
+
+    #[test]
+    fn detects_conflicts_in_priority_order() {
+        let bindings = HashMap::from([
+            ("delete", vec![key('x')]),
+            ("stop", vec![key('x'), key('X')]),
+            ("zz_custom", vec![key('X')]),
+        ]);
+        let conflicts = detect_conflicts("normal", NORMAL_ACTION_PRIORITY, &bindings);
+        let pairs: Vec<(&str, &str)> = conflicts.iter().map(|c| (c.winner, c.loser)).collect();
+        assert_eq!(pairs, vec![("stop", "delete"), ("stop", "zz_custom")]);
+    }
+
+    #[test]
+    fn shift_uppercase_counts_as_same_key() {
+        let shifted = KeySpec {
+            code: KeyCode::Char('R'),
+            modifiers: KeyModifiers::SHIFT,
+        };
+        let bindings = HashMap::from([("restart", vec![key('R')]), ("resume", vec![shifted])]);
+        let conflicts = detect_conflicts("normal", NORMAL_ACTION_PRIORITY, &bindings);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].loser, "resume");
+    }
+
+    #[test]
+    fn remapped_delete_loses_key_to_stop() {
+        let kb = KeyBindings::from_config(&cfg("[keybindings]\ndelete = \"x\"\n"));
+        assert_eq!(kb.conflicts().len(), 1);
+        assert_eq!(kb.conflicts()[0].describe(), "[normal] x: stop shadows delete");
+        assert!(kb.matches("stop", &KeyCode::Char('x'), KeyModifiers::NONE));
+        assert!(!kb.matches("delete", &KeyCode::Char('x'), KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn dialog_bindings_are_configurable_and_scoped() {
+        let kb = KeyBindings::from_config(&cfg(
+            "[keybindings]\nstart = \"s\"\n\n[keybindings.dialog.delete_confirm]\nconfirm = [\"d\", \"Enter\"]\n",
+        ));
+        assert!(kb.conflicts().is_empty());
+        assert_eq!(
+            kb.dialog_action("delete_confirm", &KeyCode::Char('d'), KeyModifiers::NONE),
+            Some("confirm")
+        );
+        assert_eq!(
+            kb.dialog_action("delete_confirm", &KeyCode::Char('y'), KeyModifiers::NONE),
+            None
+        );
+        assert_eq!(
+            kb.dialog_action("delete_group", &KeyCode::Char('d'), KeyModifiers::NONE),
+            None
+        );
+    }
+
+    #[test]
+    fn dialog_conflict_keeps_cancel() {
+        let kb = KeyBindings::from_config(&cfg(
+            "[keybindings.dialog.delete_confirm]\nconfirm = \"n\"\n",
+        ));
+        assert_eq!(kb.conflicts().len(), 1);
+        assert_eq!(kb.conflicts()[0].scope, "dialog.delete_confirm");
+        assert_eq!(
+            kb.dialog_action("delete_confirm", &KeyCode::Char('n'), KeyModifiers::NONE),
+            Some("cancel")
+        );
+    }
+
+    const REBIND_FIXTURE: &str = "\
+# agent-hand config
+animations_enabled = true   # keep the splash
+
+[keybindings]
+# destructive actions on capitals
+delete = \"D\" # was d
+start = \"s\"
+
+[tmux]
+switcher = \"C-g\"
+
+[notification]
+volume = 0.3 # quiet
+";
+
+    #[test]
+    fn rebind_edits_only_the_bound_keys() {
+        let ctrl_y = KeySpec {
+            code: KeyCode::Char('y'),
+            modifiers: KeyModifiers::CONTROL,
+        };
+        let alt_s = KeySpec {
+            code: KeyCode::Char('s'),
+            modifiers: KeyModifiers::ALT,
+        };
+        let mut edited = REBIND_FIXTURE.to_string();
+        edited = edit_binding(&edited, RebindTarget::Action("delete"), &ctrl_y).unwrap();
+        edited = edit_binding(&edited, RebindTarget::Action("fork"), &key('F')).unwrap();
+        edited = edit_binding(&edited, RebindTarget::Tmux("switcher"), &alt_s).unwrap();
+
+        let head = &REBIND_FIXTURE[..REBIND_FIXTURE.find("delete").unwrap()];
+        assert!(edited.starts_with(head), "{edited}");
+        assert!(edited.ends_with("\n[notification]\nvolume = 0.3 # quiet\n"), "{edited}");
+        assert!(edited.contains("delete = \"Ctrl+y\" # was d\nstart = \"s\"\n"), "{edited}");
+        assert!(edited.contains("switcher = \"M-s\""), "{edited}");
+
+        let cfg = cfg(&edited);
+        let kb = KeyBindings::from_config(&cfg);
+        assert!(kb.matches("delete", &KeyCode::Char('y'), KeyModifiers::CONTROL));
+        assert!(kb.matches("fork", &KeyCode::Char('F'), KeyModifiers::SHIFT));
+        assert!(kb.matches("start", &KeyCode::Char('s'), KeyModifiers::NONE));
+        assert_eq!(cfg.tmux_switcher_key().and_then(parse_tmux_key).as_deref(), Some("M-s"));
+        assert_eq!(cfg.notification().volume, 0.3);
+    }
+
+    #[test]
+    fn rebind_creates_missing_table() {
+        let edited = edit_binding("", RebindTarget::Action("fork"), &key('F')).unwrap();
+        assert_eq!(edited, "[keybindings]\nfork = \"F\"\n");
+        let f5 = KeySpec {
+            code: KeyCode::F(5),
+            modifiers: KeyModifiers::NONE,
+        };
+        assert!(edit_binding("", RebindTarget::Tmux("detach"), &f5).is_err());
+    }
+
+    #[test]
+    fn tmux_notation_reverses_parse_tmux_key() {
+        let ctrl = |c| KeySpec {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::CONTROL,
+        };
+        let alt = |c| KeySpec {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::ALT,
+        };
+        let enter = KeySpec {
+            code: KeyCode::Enter,
+            modifiers: KeyModifiers::NONE,
+        };
+        for spec in [ctrl('g'), alt('s'), key(';'), key('x'), enter] {
+            assert_eq!(
+                tmux_key_for_spec(&spec),
+                parse_tmux_key(&format_key_spec(&spec)),
+                "{spec:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn status_timing_defaults_and_clamps() {
+        use std::time::Duration;
+
+        assert_eq!(
+            cfg("").status_timing(),
+            StatusTiming {
+                refresh: Duration::from_secs(1),
+                cache_refresh: Duration::from_secs(2),
+                cooldown: Duration::from_secs(2),
+                fallback: Duration::from_secs(10),
+                capture_lines: 35,
+                stall: Some(Duration::from_secs(300)),
+            }
+        );
+
+        let t = cfg("[status]\nrefresh_secs = 0.05\nfallback_secs = 30\ncapture_lines = 1\n")
+            .status_timing();
+        assert_eq!(t.refresh, StatusTiming::MIN_INTERVAL);
+        assert_eq!(t.cache_refresh, Duration::from_secs(2));
+        assert_eq!(t.fallback, Duration::from_secs(30));
+        assert_eq!(t.capture_lines, StatusTiming::MIN_CAPTURE_LINES);
+        assert_eq!(cfg("[status]\nstall_secs = 0\n").status_timing().stall, None);
+
+        let t = cfg("[status]\nrefresh_secs = inf\nfallback_secs = 1e300\nstall_secs = nan\n")
+            .status_timing();
+        assert_eq!(t.refresh, Duration::from_secs(1));
+        assert_eq!(t.fallback, Duration::from_secs(10));
+        assert_eq!(t.stall, Some(Duration::from_secs(300)));
+    }
+
+    #[test]
+    fn session_columns_keep_their_order() {
+        use SessionColumn::*;
+
+        assert_eq!(cfg("").session_columns(), [Tool, Age]);
+        assert_eq!(
+            cfg("[ui]\nsession_columns = [\"age\", \"bogus\", \"Tool\", \"age\"]\n")
+                .session_columns(),
+            [Age, Tool]
+        );
+        assert!(cfg("[ui]\nsession_columns = []\n").session_columns().is_empty());
+    }
+
+    #[test]
+    fn grouping_rules_keep_their_order() {
+        assert!(cfg("").group_rules().is_empty());
+        let rules = cfg(concat!(
+            "[grouping]\n",
+            "rules = [{ pattern = \"~/work/*\", group = \"work/{1}\" },\n",
+            "         { pattern = \"~/**\", group = \"\" }]\n",
+        ));
+        let patterns: Vec<&str> = rules.group_rules().iter().map(|r| r.pattern.as_str()).collect();
+        assert_eq!(patterns, ["~/work/*", "~/**"]);
+        assert_eq!(rules.group_rules()[0].group, "work/{1}");
+    }
+
+    #[test]
+    fn page_keys_round_trip() {
+        for code in [KeyCode::PageUp, KeyCode::PageDown, KeyCode::Home, KeyCode::End] {
+            let spec = KeySpec {
+                code,
+                modifiers: KeyModifiers::NONE,
+            };
+            assert_eq!(parse_key_spec(&format_key_spec(&spec)), Some(spec), "{code:?}");
+        }
+        assert_eq!(parse_key_spec("pgdn").map(|k| k.code), Some(KeyCode::PageDown));
+    }
+
+    #[test]
+    fn previews_conflicts_before_rebinding() {
+        let kb = KeyBindings::default();
+        let conflicts = kb.conflicts_if_bound("delete", key('x'));
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].describe(), "[normal] x: stop shadows delete");
+        assert!(kb.conflicts_if_bound("delete", key('V')).is_empty());
+    }
+}
@@ -161,6 +161,8 @@ pub enum ControlResponse {
         running: usize,
         waiting: usize,
         idle: usize,
+        #[serde(default)]
+        stopped: usize,
         error: usize,
     },
     BatchResult {
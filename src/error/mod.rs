@@ -25,6 +25,10 @@ pub enum Error {
     #[error("Storage error: {0}")]
     Storage(String),
 
+    /// Another process changed the same saved data; reload and try again.
+    #[error("Save conflict: {0}")]
+    Conflict(String),
+
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
@@ -0,0 +1,110 @@
+//! Thin async wrappers around the `git` CLI for session project paths.
+
+use std::path::{Path, PathBuf};
+
+use tokio::process::Command as TokioCommand;
+
+use crate::error::{Error, Result};
+
+async fn git_output(path: &Path, args: &[&str]) -> Result<String> {
+    let output = TokioCommand::new("git")
+        .arg("-C")
+        .arg(path)
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| Error::CommandFailed(format!("Failed to run git: {e}")))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::CommandFailed(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            stderr.trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+}
+
+/// Shared `.git` directory of the repository containing `path`.
+///
+/// Worktrees of one repository resolve to the same directory, so this is the
+/// identity to compare when asking whether two paths belong to one repo.
+pub async fn common_dir(path: &Path) -> Result<PathBuf> {
+    let out = git_output(path, &["rev-parse", "--path-format=absolute", "--git-common-dir"]).await?;
+    let dir = PathBuf::from(out.trim());
+    Ok(dir.canonicalize().unwrap_or(dir))
+}
+
+/// Currently checked-out branch, or `None` on a detached HEAD.
+pub async fn current_branch(path: &Path) -> Result<Option<String>> {
+    let out = git_output(path, &["rev-parse", "--abbrev-ref", "HEAD"]).await?;
+    let branch = out.trim();
+    if branch.is_empty() || branch == "HEAD" {
+        Ok(None)
+    } else {
+        Ok(Some(branch.to_string()))
+    }
+}
+
+/// `git diff --stat base...head`, run inside `path`.
+pub async fn diff_stat(path: &Path, base: &str, head: &str) -> Result<String> {
+    let range = format!("{base}...{head}");
+    git_output(path, &["diff", "--stat", &range]).await
+}
+
+/// `git worktree add <dest> -b <branch>`, run in the repository at `repo`.
+pub async fn worktree_add(repo: &Path, dest: &Path, branch: &str) -> Result<()> {
+    let dest = dest.to_string_lossy();
+    git_output(repo, &["worktree", "add", &dest, "-b", branch]).await?;
+    Ok(())
+}
+
+/// Remove the linked worktree at `path`, keeping its branch.
+///
+/// Git refuses when the worktree has uncommitted changes; that error is
+/// returned as-is rather than forcing.
+pub async fn worktree_remove(path: &Path) -> Result<()> {
+    let repo = common_dir(path).await?;
+    let path = path.to_string_lossy();
+    git_output(&repo, &["worktree", "remove", &path]).await?;
+    Ok(())
+}
+
+/// Branch name suggested for a fork titled `title`: lowercase words joined
+/// by dashes, e.g. "API (fork)" becomes "api-fork".
+pub fn branch_slug(title: &str) -> String {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Directory a worktree on `branch` goes in under `root`. Slashes become
+/// dashes so `feat/x` does not create a nested folder.
+pub fn worktree_dir(root: &Path, branch: &str) -> PathBuf {
+    root.join(branch.trim().replace('/', "-"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn branch_slug_joins_words() {
+        assert_eq!(branch_slug("API (fork)"), "api-fork");
+        assert_eq!(branch_slug("  fix: login  "), "fix-login");
+        assert_eq!(branch_slug("()"), "");
+    }
+
+    #[test]
+    fn worktree_dir_flattens_branch_path() {
+        assert_eq!(
+            worktree_dir(Path::new("/w"), "feat/login"),
+            PathBuf::from("/w/feat-login")
+        );
+    }
+}
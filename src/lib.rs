@@ -10,7 +10,9 @@ pub mod config;
 pub mod control;
 pub mod hooks;
 pub mod error;
+pub mod git;
 pub mod i18n;
+pub mod network;
 pub mod session;
 pub mod sharing;
 #[cfg(feature = "pro")]
@@ -62,6 +62,9 @@ async fn main() {
         fmt().with_env_filter(filter).with_target(false).init();
     }
 
+    // Leave raw mode before a panic message is printed, not after
+    agent_hand::ui::term::install_panic_hook();
+
     // Parse CLI args
     let args = Args::parse();
 
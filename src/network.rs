@@ -0,0 +1,169 @@
+//! Single switch for everything that talks to the network.
+//!
+//! Offline mode comes from `offline = true` in the config. Separately, a
+//! request that fails to connect makes background callers back off for
+//! [`UNREACHABLE_BACKOFF`] before trying again. Background callers (update
+//! hint, heartbeat) check [`is_offline`], which covers both, and fall back
+//! to cached data; user-initiated ones only fail fast via [`ensure_online`]
+//! when offline mode is configured.
+//!
+//! New HTTP callers should go through [`HttpFetch`] so tests can substitute
+//! a failing or unreachable transport.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use crate::error::{Error, Result};
+
+/// How long background requests are skipped after one failed to connect.
+pub const UNREACHABLE_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+static UNREACHABLE: Backoff = Backoff::new();
+
+/// Offline mode is configured, or a recent request could not connect.
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed) || UNREACHABLE.active(Instant::now())
+}
+
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// Record that a request could not reach its host.
+pub fn mark_unreachable() {
+    UNREACHABLE.start(Instant::now());
+}
+
+/// A window after a failed connection in which no request is sent.
+struct Backoff {
+    until: Mutex<Option<Instant>>,
+}
+
+impl Backoff {
+    const fn new() -> Self {
+        Self {
+            until: Mutex::new(None),
+        }
+    }
+
+    fn start(&self, now: Instant) {
+        *self.until.lock().unwrap_or_else(|e| e.into_inner()) = Some(now + UNREACHABLE_BACKOFF);
+    }
+
+    fn active(&self, now: Instant) -> bool {
+        self.until
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_some_and(|until| now < until)
+    }
+}
+
+/// Error for user-initiated network actions while offline mode is on. A
+/// recent failed connection does not count: the user asked, so try again.
+pub fn ensure_online() -> Result<()> {
+    if OFFLINE.load(Ordering::Relaxed) {
+        return Err(Error::Other(
+            "offline mode is on (set offline = false in config to re-enable network access)"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FetchError {
+    /// Skipped because offline mode is on; nothing was sent.
+    Offline,
+    /// Could not reach the host (DNS, connect, timeout).
+    Unreachable(String),
+    /// Reached the host but the response was unusable.
+    Failed(String),
+}
+
+impl std::fmt::Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FetchError::Offline => write!(f, "offline"),
+            FetchError::Unreachable(e) => write!(f, "unreachable: {e}"),
+            FetchError::Failed(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+/// GET a JSON document.
+#[async_trait]
+pub trait HttpFetch: Send + Sync {
+    async fn get_json(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> std::result::Result<serde_json::Value, FetchError>;
+}
+
+/// [`HttpFetch`] over reqwest. Sends nothing while [`is_offline`], and
+/// starts the backoff when a request cannot reach its host.
+pub struct ReqwestFetch {
+    pub timeout: Duration,
+}
+
+impl Default for ReqwestFetch {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(2),
+        }
+    }
+}
+
+#[async_trait]
+impl HttpFetch for ReqwestFetch {
+    async fn get_json(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+    ) -> std::result::Result<serde_json::Value, FetchError> {
+        if is_offline() {
+            return Err(FetchError::Offline);
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .map_err(|e| FetchError::Failed(e.to_string()))?;
+        let mut req = client.get(url);
+        for (name, value) in headers {
+            req = req.header(*name, *value);
+        }
+
+        let resp = req.send().await.map_err(|e| {
+            if e.is_connect() || e.is_timeout() {
+                mark_unreachable();
+                FetchError::Unreachable(e.to_string())
+            } else {
+                FetchError::Failed(e.to_string())
+            }
+        })?;
+        resp.json()
+            .await
+            .map_err(|e| FetchError::Failed(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_ends_after_its_window() {
+        let backoff = Backoff::new();
+        let now = Instant::now();
+        assert!(!backoff.active(now));
+
+        backoff.start(now);
+        assert!(backoff.active(now + Duration::from_secs(1)));
+        assert!(!backoff.active(now + UNREACHABLE_BACKOFF));
+    }
+}
@@ -0,0 +1,403 @@
+//! Coalescing in front of notification sinks.
+//!
+//! A tmux restart or a bulk stop changes many sessions at once. Instead of
+//! one notification per session, events of the same kind that arrive in one
+//! burst become one [`Summary`] ("6 sessions went idle: a, b, c and 3 more"),
+//! each sink is capped at a number of notifications per minute with the
+//! overflow summarized, and during a known bulk operation nothing is emitted
+//! until it ends. Time is always passed in, so all of this is deterministic.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Default window within which events are merged.
+pub const DEFAULT_WINDOW: Duration = Duration::from_secs(2);
+/// Batches go out once no event has arrived for this long, without waiting
+/// for the rest of their window.
+pub const QUIET: Duration = Duration::from_millis(500);
+/// Default cap on notifications per sink per minute.
+pub const DEFAULT_MAX_PER_MINUTE: usize = 6;
+/// How long a bulk operation keeps suppressing after it was started, so
+/// the events it causes (which arrive later) are still folded into it.
+pub const BULK_SETTLE: Duration = Duration::from_secs(5);
+
+const RATE_PERIOD: Duration = Duration::from_secs(60);
+/// Titles listed in a summary before "and N more".
+const LISTED: usize = 3;
+
+/// Per-session events worth coalescing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NoticeKind {
+    WentIdle,
+    NeedsInput,
+    Error,
+}
+
+impl NoticeKind {
+    /// The kind behind a CESP sound category, if it is per-session news.
+    pub fn from_category(category: &str) -> Option<Self> {
+        match category {
+            "task.complete" => Some(Self::WentIdle),
+            "input.required" => Some(Self::NeedsInput),
+            "task.error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+
+    pub fn category(self) -> &'static str {
+        match self {
+            Self::WentIdle => "task.complete",
+            Self::NeedsInput => "input.required",
+            Self::Error => "task.error",
+        }
+    }
+
+    fn verb(self, plural: bool) -> &'static str {
+        match (self, plural) {
+            (Self::WentIdle, _) => "went idle",
+            (Self::NeedsInput, false) => "needs input",
+            (Self::NeedsInput, true) => "need input",
+            (Self::Error, _) => "hit an error",
+        }
+    }
+}
+
+/// One sink-ready notification covering one or more sessions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Summary {
+    pub kind: NoticeKind,
+    /// Sessions in the order their events arrived, without repeats.
+    pub sessions: Vec<String>,
+}
+
+impl Summary {
+    pub fn message(&self) -> String {
+        match self.sessions.as_slice() {
+            [only] => format!("{only} {}", self.kind.verb(false)),
+            sessions => {
+                let mut listed = sessions[..sessions.len().min(LISTED)].join(", ");
+                if sessions.len() > LISTED {
+                    listed.push_str(&format!(" and {} more", sessions.len() - LISTED));
+                }
+                format!("{} sessions {}: {listed}", sessions.len(), self.kind.verb(true))
+            }
+        }
+    }
+
+    fn absorb(&mut self, session: &str) {
+        if !self.sessions.iter().any(|s| s == session) {
+            self.sessions.push(session.to_string());
+        }
+    }
+}
+
+/// Merge summaries of the same kind, keeping first-seen order.
+fn merge(into: &mut Vec<Summary>, summary: Summary) {
+    match into.iter_mut().find(|s| s.kind == summary.kind) {
+        Some(existing) => summary.sessions.iter().for_each(|s| existing.absorb(s)),
+        None => into.push(summary),
+    }
+}
+
+#[derive(Debug)]
+struct Batch {
+    opened: Instant,
+    summary: Summary,
+}
+
+/// Collapses events of one kind arriving in a burst: until no event has
+/// come for [`QUIET`], and at most `window` after the first.
+#[derive(Debug)]
+pub struct Coalescer {
+    window: Duration,
+    batches: Vec<Batch>,
+    last_event: Option<Instant>,
+    bulk: bool,
+}
+
+impl Coalescer {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            batches: Vec::new(),
+            last_event: None,
+            bulk: false,
+        }
+    }
+
+    pub fn push(&mut self, now: Instant, session: &str, kind: NoticeKind) {
+        self.last_event = self.last_event.max(Some(now));
+        match self.batches.iter_mut().find(|b| b.summary.kind == kind) {
+            Some(batch) => batch.summary.absorb(session),
+            None => self.batches.push(Batch {
+                opened: now,
+                summary: Summary {
+                    kind,
+                    sessions: vec![session.to_string()],
+                },
+            }),
+        }
+    }
+
+    /// Every summary once the events have gone quiet, otherwise those whose
+    /// window has closed. Nothing closes during a bulk operation.
+    pub fn poll(&mut self, now: Instant) -> Vec<Summary> {
+        if self.bulk {
+            return Vec::new();
+        }
+        let window = self.window;
+        let quiet = self
+            .last_event
+            .is_some_and(|at| now.saturating_duration_since(at) >= QUIET.min(window));
+        let (due, open): (Vec<Batch>, Vec<Batch>) = std::mem::take(&mut self.batches)
+            .into_iter()
+            .partition(|b| quiet || now.saturating_duration_since(b.opened) >= window);
+        self.batches = open;
+        due.into_iter().map(|b| b.summary).collect()
+    }
+
+    pub fn is_bulk(&self) -> bool {
+        self.bulk
+    }
+
+    /// Hold every event until [`end_bulk`](Self::end_bulk).
+    pub fn begin_bulk(&mut self) {
+        self.bulk = true;
+    }
+
+    /// End the bulk operation and return everything it held, one summary
+    /// per kind.
+    pub fn end_bulk(&mut self) -> Vec<Summary> {
+        self.bulk = false;
+        std::mem::take(&mut self.batches)
+            .into_iter()
+            .map(|b| b.summary)
+            .collect()
+    }
+}
+
+/// Caps one sink at `max` notifications per minute; what does not fit is
+/// merged and sent as soon as there is room again.
+#[derive(Debug)]
+pub struct RateLimiter {
+    max: usize,
+    sent: VecDeque<Instant>,
+    overflow: Vec<Summary>,
+}
+
+impl RateLimiter {
+    pub fn new(max_per_minute: usize) -> Self {
+        Self {
+            max: max_per_minute.max(1),
+            sent: VecDeque::new(),
+            overflow: Vec::new(),
+        }
+    }
+
+    /// Summaries that may be sent now, held-back overflow first. Call with
+    /// nothing new to drain the overflow.
+    pub fn admit(&mut self, now: Instant, summaries: Vec<Summary>) -> Vec<Summary> {
+        while self
+            .sent
+            .front()
+            .is_some_and(|t| now.saturating_duration_since(*t) >= RATE_PERIOD)
+        {
+            self.sent.pop_front();
+        }
+
+        let mut out = Vec::new();
+        let mut queue = std::mem::take(&mut self.overflow);
+        // Everything held back goes out as one notification per kind.
+        queue.extend(summaries);
+        for summary in queue {
+            if self.sent.len() < self.max {
+                self.sent.push_back(now);
+                out.push(summary);
+            } else {
+                merge(&mut self.overflow, summary);
+            }
+        }
+        out
+    }
+
+    pub fn has_overflow(&self) -> bool {
+        !self.overflow.is_empty()
+    }
+}
+
+/// Coalescer and rate limit for one sink.
+#[derive(Debug)]
+pub struct Notifier {
+    coalescer: Coalescer,
+    limiter: RateLimiter,
+}
+
+impl Notifier {
+    pub fn new(window: Duration, max_per_minute: usize) -> Self {
+        Self {
+            coalescer: Coalescer::new(window),
+            limiter: RateLimiter::new(max_per_minute),
+        }
+    }
+
+    pub fn push(&mut self, now: Instant, session: &str, kind: NoticeKind) {
+        self.coalescer.push(now, session, kind);
+    }
+
+    /// What the sink should show now. `bulk` is whether a bulk operation is
+    /// in progress; when it ends, everything it held comes out at once.
+    pub fn poll(&mut self, now: Instant, bulk: bool) -> Vec<Summary> {
+        let mut ready = Vec::new();
+        match (bulk, self.coalescer.is_bulk()) {
+            (true, false) => self.coalescer.begin_bulk(),
+            (false, true) => ready = self.coalescer.end_bulk(),
+            _ => {}
+        }
+        ready.extend(self.coalescer.poll(now));
+        self.limiter.admit(now, ready)
+    }
+}
+
+/// Shared "bulk operation in progress" flag. Starting an operation marks
+/// the next [`BULK_SETTLE`] as bulk; overlapping operations extend it.
+#[derive(Debug, Clone, Default)]
+pub struct BulkOperations(Arc<Mutex<Option<Instant>>>);
+
+impl BulkOperations {
+    pub fn begin(&self, now: Instant) {
+        let mut until = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        let end = now + BULK_SETTLE;
+        if until.map_or(true, |u| u < end) {
+            *until = Some(end);
+        }
+    }
+
+    pub fn is_active(&self, now: Instant) -> bool {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_some_and(|until| now < until)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    fn summary(kind: NoticeKind, sessions: &[&str]) -> Summary {
+        Summary {
+            kind,
+            sessions: sessions.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn events_within_the_window_become_one_summary() {
+        let t0 = Instant::now();
+        let mut c = Coalescer::new(DEFAULT_WINDOW);
+        for (i, s) in ["a", "b", "a", "c", "d", "e", "f"].iter().enumerate() {
+            c.push(t0 + MS * (i as u32 * 100), s, NoticeKind::WentIdle);
+        }
+        c.push(t0 + MS * 300, "b", NoticeKind::NeedsInput);
+
+        // Out as soon as nothing new came for QUIET after the last event.
+        assert!(c.poll(t0 + MS * 1099).is_empty());
+        let out = c.poll(t0 + MS * 600 + QUIET);
+        assert_eq!(
+            out,
+            [
+                summary(NoticeKind::WentIdle, &["a", "b", "c", "d", "e", "f"]),
+                summary(NoticeKind::NeedsInput, &["b"]),
+            ]
+        );
+        assert_eq!(out[0].message(), "6 sessions went idle: a, b, c and 3 more");
+        assert_eq!(out[1].message(), "b needs input");
+
+        // A later event opens a new batch.
+        c.push(t0 + MS * 2500, "g", NoticeKind::WentIdle);
+        assert!(c.poll(t0 + MS * 2900).is_empty());
+        assert_eq!(c.poll(t0 + MS * 3000), [summary(NoticeKind::WentIdle, &["g"])]);
+    }
+
+    #[test]
+    fn a_steady_stream_still_goes_out_after_the_window() {
+        let t0 = Instant::now();
+        let mut c = Coalescer::new(DEFAULT_WINDOW);
+        let mut out = Vec::new();
+        for i in 0..12u32 {
+            let now = t0 + MS * (i * 200);
+            c.push(now, &format!("s{i}"), NoticeKind::Error);
+            out.extend(c.poll(now));
+        }
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].sessions.len(), 11);
+    }
+
+    #[test]
+    fn zero_window_passes_events_straight_through() {
+        let t0 = Instant::now();
+        let mut c = Coalescer::new(Duration::ZERO);
+        c.push(t0, "a", NoticeKind::Error);
+        assert_eq!(c.poll(t0), [summary(NoticeKind::Error, &["a"])]);
+    }
+
+    #[test]
+    fn rate_limit_summarizes_overflow() {
+        let t0 = Instant::now();
+        let mut r = RateLimiter::new(2);
+        let sent = r.admit(
+            t0,
+            vec![
+                summary(NoticeKind::WentIdle, &["a"]),
+                summary(NoticeKind::Error, &["b"]),
+                summary(NoticeKind::WentIdle, &["c"]),
+            ],
+        );
+        assert_eq!(sent.len(), 2);
+        let later = r.admit(t0 + MS * 10, vec![summary(NoticeKind::WentIdle, &["d", "c"])]);
+        assert!(later.is_empty());
+        assert!(r.has_overflow());
+
+        // Once the minute has passed, the overflow goes out merged.
+        let drained = r.admit(t0 + RATE_PERIOD, Vec::new());
+        assert_eq!(drained, [summary(NoticeKind::WentIdle, &["c", "d"])]);
+        assert!(!r.has_overflow());
+    }
+
+    #[test]
+    fn bulk_operation_holds_everything_for_one_final_summary() {
+        let t0 = Instant::now();
+        let mut n = Notifier::new(DEFAULT_WINDOW, DEFAULT_MAX_PER_MINUTE);
+        assert!(n.poll(t0, true).is_empty());
+        for (i, s) in ["a", "b", "c", "d"].iter().enumerate() {
+            n.push(t0 + Duration::from_secs(i as u64 * 3), s, NoticeKind::WentIdle);
+            // Well past the window, but still bulk.
+            assert!(n.poll(t0 + Duration::from_secs(i as u64 * 3 + 2), true).is_empty());
+        }
+        let out = n.poll(t0 + Duration::from_secs(20), false);
+        assert_eq!(out, [summary(NoticeKind::WentIdle, &["a", "b", "c", "d"])]);
+        assert_eq!(out[0].message(), "4 sessions went idle: a, b, c and 1 more");
+    }
+
+    #[test]
+    fn bulk_flag_settles_and_extends() {
+        let t0 = Instant::now();
+        let bulk = BulkOperations::default();
+        assert!(!bulk.is_active(t0));
+        bulk.begin(t0);
+        bulk.clone().begin(t0 + Duration::from_secs(3));
+        assert!(bulk.is_active(t0 + BULK_SETTLE));
+        assert!(!bulk.is_active(t0 + Duration::from_secs(3) + BULK_SETTLE));
+    }
+
+    #[test]
+    fn maps_sound_categories() {
+        for kind in [NoticeKind::WentIdle, NoticeKind::NeedsInput, NoticeKind::Error] {
+            assert_eq!(NoticeKind::from_category(kind.category()), Some(kind));
+        }
+        assert_eq!(NoticeKind::from_category("session.start"), None);
+    }
+}
@@ -0,0 +1,162 @@
+//! Desktop notifications when a session starts waiting for input.
+//!
+//! Linux: notify-send, macOS: osascript. Sending is fire-and-forget; a
+//! missing command is logged at debug level and otherwise ignored.
+
+use std::collections::HashMap;
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use tokio::process::Command;
+
+use super::sound::{Platform, PlayerCommand};
+use crate::session::Instance;
+
+/// Default `notification.desktop_interval_secs`.
+pub const DEFAULT_INTERVAL_SECS: u64 = 60;
+
+/// Command that shows `summary` / `body`, or `None` where unsupported.
+pub fn notify_command(platform: Platform, summary: &str, body: &str) -> Option<PlayerCommand> {
+    match platform {
+        Platform::MacOs => Some((
+            "osascript",
+            vec![
+                "-e".into(),
+                format!(
+                    "display notification {} with title {}",
+                    applescript_string(body),
+                    applescript_string(summary)
+                ),
+            ],
+        )),
+        Platform::Linux => Some((
+            "notify-send",
+            vec!["--app-name=agent-hand".into(), summary.into(), body.into()],
+        )),
+        Platform::Other => None,
+    }
+}
+
+/// Quote `s` as an AppleScript string literal.
+fn applescript_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Show a notification without waiting for it.
+pub fn send_async(summary: &str, body: &str) {
+    let Some((cmd, args)) = notify_command(Platform::current(), summary, body) else {
+        return;
+    };
+    tokio::spawn(async move {
+        let result = Command::new(cmd)
+            .args(&args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .await;
+        match result {
+            Ok(status) if status.success() => {}
+            Ok(status) => tracing::debug!("{cmd} exited with {status}"),
+            Err(e) => tracing::debug!("Desktop notification failed: {cmd}: {e}"),
+        }
+    });
+}
+
+/// Decides which Running → Waiting transitions become notifications.
+#[derive(Debug)]
+pub struct DesktopNotifier {
+    interval: Duration,
+    last_sent: HashMap<String, Instant>,
+}
+
+impl DesktopNotifier {
+    pub fn new(config: &crate::config::NotificationConfig) -> Self {
+        Self {
+            interval: Duration::from_secs(config.desktop_interval_secs),
+            last_sent: HashMap::new(),
+        }
+    }
+
+    /// Whether `inst` should be announced now. Muted sessions, the session
+    /// named by `attached` (a tmux session name) and sessions notified less
+    /// than the interval ago are skipped. Records the send when allowed.
+    pub fn should_notify(&mut self, inst: &Instance, attached: Option<&str>, now: Instant) -> bool {
+        if inst.mute_notifications || attached == Some(inst.tmux_name().as_str()) {
+            return false;
+        }
+        if self
+            .last_sent
+            .get(&inst.id)
+            .is_some_and(|t| now.duration_since(*t) < self.interval)
+        {
+            return false;
+        }
+        self.last_sent.insert(inst.id.clone(), now);
+        true
+    }
+
+    /// Announce that `inst` needs input, if [`Self::should_notify`] allows.
+    pub fn notify_waiting(&mut self, inst: &Instance, attached: Option<&str>) {
+        if !self.should_notify(inst, attached, Instant::now()) {
+            return;
+        }
+        let summary = format!("{} needs input", inst.title);
+        let body = if inst.group_path.is_empty() {
+            "agent-hand".to_string()
+        } else {
+            format!("agent-hand · {}", inst.group_path)
+        };
+        send_async(&summary, &body);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn notifier(secs: u64) -> DesktopNotifier {
+        let config = crate::config::NotificationConfig {
+            desktop_interval_secs: secs,
+            ..Default::default()
+        };
+        DesktopNotifier::new(&config)
+    }
+
+    #[test]
+    fn debounces_per_session() {
+        let mut n = notifier(60);
+        let a = Instance::new("api".into(), PathBuf::from("/w/api"));
+        let b = Instance::new("web".into(), PathBuf::from("/w/web"));
+        let t0 = Instant::now();
+
+        assert!(n.should_notify(&a, None, t0));
+        assert!(!n.should_notify(&a, None, t0 + Duration::from_secs(30)));
+        assert!(n.should_notify(&b, None, t0 + Duration::from_secs(30)));
+        assert!(n.should_notify(&a, None, t0 + Duration::from_secs(61)));
+    }
+
+    #[test]
+    fn skips_muted_and_attached_sessions() {
+        let mut n = notifier(0);
+        let mut a = Instance::new("api".into(), PathBuf::from("/w/api"));
+        let now = Instant::now();
+
+        assert!(!n.should_notify(&a, Some(&a.tmux_name()), now));
+        assert!(n.should_notify(&a, Some("agentdeck_rs_other"), now));
+        a.mute_notifications = true;
+        assert!(!n.should_notify(&a, None, now));
+    }
+
+    #[test]
+    fn applescript_body_is_escaped() {
+        let (cmd, args) = notify_command(Platform::MacOs, "say \"hi\"", r"a\b").unwrap();
+        assert_eq!(cmd, "osascript");
+        assert_eq!(
+            args[1],
+            r#"display notification "a\\b" with title "say \"hi\"""#
+        );
+        assert!(notify_command(Platform::Other, "x", "y").is_none());
+    }
+}
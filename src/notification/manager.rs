@@ -3,6 +3,8 @@
 use std::time::{Duration, Instant};
 
 use super::pack::SoundPack;
+use super::quiet::QuietHours;
+use super::sound::Alert;
 
 /// CESP event categories
 const CAT_TASK_COMPLETE: &str = "task.complete";
@@ -32,6 +34,30 @@ pub struct NotificationManager {
     spam_window: Duration,
     /// Spam detection threshold (prompts within window)
     spam_threshold: usize,
+    /// `sound`: replaces the pack's input-required sound when set.
+    alert: Option<Alert>,
+    /// `quiet_hours`: no sounds inside this window.
+    quiet_hours: Option<QuietHours>,
+}
+
+/// The configured input alert and quiet hours. Invalid quiet hours are
+/// ignored.
+fn parse_alert_settings(
+    config: &crate::config::NotificationConfig,
+) -> (Option<Alert>, Option<QuietHours>) {
+    let alert = config
+        .sound
+        .as_deref()
+        .filter(|_| config.enabled)
+        .map(Alert::parse);
+    let quiet_hours = config.quiet_hours.as_deref().and_then(|value| {
+        let parsed = QuietHours::parse(value);
+        if parsed.is_none() {
+            tracing::debug!("Ignoring invalid notification.quiet_hours {:?}", value);
+        }
+        parsed
+    });
+    (alert, quiet_hours)
 }
 
 impl NotificationManager {
@@ -51,8 +77,12 @@ impl NotificationManager {
             );
         }
 
+        let (alert, quiet_hours) = parse_alert_settings(config);
+
         Self {
             pack,
+            alert,
+            quiet_hours,
             config: config.clone(),
             last_notify: std::collections::HashMap::new(),
             cooldown: Duration::from_secs(3),
@@ -140,6 +170,13 @@ impl NotificationManager {
     }
 
     pub fn play_category(&mut self, session_id: &str, category: &str) {
+        if self
+            .quiet_hours
+            .is_some_and(|q| q.contains(chrono::Local::now().time()))
+        {
+            return;
+        }
+
         // Cooldown check
         let now = Instant::now();
         if let Some(last) = self.last_notify.get(session_id) {
@@ -149,6 +186,14 @@ impl NotificationManager {
         }
         self.last_notify.insert(session_id.to_string(), now);
 
+        if category == CAT_INPUT_REQUIRED {
+            if let Some(ref alert) = self.alert {
+                let pack_sound = self.pack.as_ref().and_then(|p| p.pick_sound(category));
+                super::sound::alert_async(alert, pack_sound.as_deref(), self.config.volume);
+                return;
+            }
+        }
+
         // Pick and play sound
         if let Some(ref pack) = self.pack {
             if let Some(path) = pack.pick_sound(category) {
@@ -159,6 +204,7 @@ impl NotificationManager {
 
     /// Reload pack (e.g., after config change)
     pub fn reload_pack(&mut self, config: &crate::config::NotificationConfig) {
+        (self.alert, self.quiet_hours) = parse_alert_settings(config);
         self.config = config.clone();
         self.pack = if config.enabled {
             SoundPack::load(&config.sound_pack)
@@ -1,5 +1,8 @@
+pub mod coalesce;
+pub mod desktop;
 mod manager;
 mod pack;
+pub mod quiet;
 pub mod registry;
 pub mod sound;
 
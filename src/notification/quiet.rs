@@ -0,0 +1,75 @@
+//! `notification.quiet_hours`: a daily window, in local time, with no sounds.
+
+use chrono::NaiveTime;
+
+/// A `"HH:MM-HH:MM"` window. The start is inclusive and the end exclusive.
+/// A window whose end is before its start runs past midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    pub fn parse(value: &str) -> Option<Self> {
+        let (start, end) = value.trim().split_once('-')?;
+        let time = |s: &str| NaiveTime::parse_from_str(s.trim(), "%H:%M").ok();
+        Some(Self {
+            start: time(start)?,
+            end: time(end)?,
+        })
+    }
+
+    /// Whether `now` falls inside the window. Equal start and end is an
+    /// empty window.
+    pub fn contains(&self, now: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= now && now < self.end
+        } else {
+            now >= self.start || now < self.end
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(s, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn parse_windows() {
+        let q = QuietHours::parse(" 22:00 - 08:30 ").unwrap();
+        assert_eq!((q.start, q.end), (at("22:00"), at("08:30")));
+        assert_eq!(QuietHours::parse("9:05-17:00").unwrap().start, at("09:05"));
+        for bad in ["", "22:00", "22:00-", "25:00-08:00", "22-08", "night"] {
+            assert_eq!(QuietHours::parse(bad), None, "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn window_across_midnight() {
+        let q = QuietHours::parse("22:00-08:00").unwrap();
+        for inside in ["22:00", "23:59", "00:00", "03:30", "07:59"] {
+            assert!(q.contains(at(inside)), "{inside}");
+        }
+        for outside in ["08:00", "12:00", "21:59"] {
+            assert!(!q.contains(at(outside)), "{outside}");
+        }
+    }
+
+    #[test]
+    fn window_within_a_day_and_empty_window() {
+        let q = QuietHours::parse("12:00-13:30").unwrap();
+        assert!(q.contains(at("12:00")));
+        assert!(q.contains(at("13:29")));
+        assert!(!q.contains(at("13:30")));
+        assert!(!q.contains(at("11:59")));
+
+        let empty = QuietHours::parse("09:00-09:00").unwrap();
+        assert!(!empty.contains(at("09:00")));
+        assert!(!empty.contains(at("21:00")));
+    }
+}
@@ -43,6 +43,7 @@ pub struct RegistryPack {
 /// Fetch the list of available packs from all registries.
 /// Priority: relay server `/api/packs` → `gh` CLI → direct GitHub API.
 pub async fn fetch_pack_list(relay_url: Option<&str>) -> Result<Vec<RegistryPack>, String> {
+    crate::network::ensure_online().map_err(|e| e.to_string())?;
     let pack_names = fetch_pack_names(relay_url).await?;
 
     let installed = super::SoundPack::list_installed();
@@ -202,6 +203,7 @@ pub async fn install_pack(
     pack_name: &str,
     on_progress: impl Fn(&str),
 ) -> Result<PathBuf, String> {
+    crate::network::ensure_online().map_err(|e| e.to_string())?;
     let client = reqwest::Client::builder()
         .user_agent("agent-hand")
         .timeout(std::time::Duration::from_secs(30))
@@ -1,72 +1,267 @@
 //! Cross-platform sound playback.
 //! macOS: afplay, Linux: pw-play / paplay / aplay
+//!
+//! Also the opt-in "needs input" alert (`notification.sound`): a terminal
+//! bell, the system player, or a configured file. An alert that cannot be
+//! played falls back to the bell.
 
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 
 use tokio::process::Command;
 
+/// Platforms with different player commands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    MacOs,
+    /// Linux and other Unix-likes.
+    Linux,
+    /// No known player; only the bell works.
+    Other,
+}
+
+impl Platform {
+    pub fn current() -> Self {
+        if cfg!(target_os = "macos") {
+            Self::MacOs
+        } else if cfg!(unix) {
+            Self::Linux
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// One player invocation: program and arguments.
+pub type PlayerCommand = (&'static str, Vec<String>);
+
+/// Players to try for `path`, in order. Volume is 0.0..=1.0.
+pub fn player_commands(platform: Platform, path: &Path, volume: f32) -> Vec<PlayerCommand> {
+    let path = path.to_string_lossy().to_string();
+    match platform {
+        Platform::MacOs => vec![("afplay", vec!["-v".into(), format!("{volume:.2}"), path])],
+        Platform::Linux => vec![
+            ("pw-play", vec!["--volume".into(), format!("{volume:.2}"), path.clone()]),
+            (
+                "paplay",
+                vec![format!("--volume={}", (volume * 65536.0) as u32), path.clone()],
+            ),
+            ("aplay", vec![path]),
+        ],
+        Platform::Other => Vec::new(),
+    }
+}
+
+/// A sound that ships with the OS, used by `sound = "system"` when the
+/// sound pack has nothing for the event.
+pub fn system_sound(platform: Platform) -> Option<PathBuf> {
+    let candidates: &[&str] = match platform {
+        Platform::MacOs => &["/System/Library/Sounds/Glass.aiff"],
+        Platform::Linux => &[
+            "/usr/share/sounds/freedesktop/stereo/message-new-instant.oga",
+            "/usr/share/sounds/freedesktop/stereo/bell.oga",
+            "/usr/share/sounds/alsa/Front_Center.wav",
+        ],
+        Platform::Other => &[],
+    };
+    candidates.iter().map(PathBuf::from).find(|p| p.exists())
+}
+
+/// `notification.sound`: how a session needing input is announced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Alert {
+    /// Write BEL to the terminal; works over SSH.
+    Bell,
+    /// Play the sound pack's input sound, or an OS sound.
+    System,
+    /// Play this file.
+    File(PathBuf),
+    Off,
+}
+
+impl Alert {
+    /// Parse a config value. Anything that is not a keyword is a file path.
+    pub fn parse(value: &str) -> Self {
+        match value.trim() {
+            "" | "off" | "none" => Self::Off,
+            "bell" => Self::Bell,
+            "system" => Self::System,
+            path => Self::File(expand_home(path)),
+        }
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().map_or_else(|| PathBuf::from(path), |h| h.join(rest)),
+        None => PathBuf::from(path),
+    }
+}
+
+/// What playing an alert comes down to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlertPlan {
+    Silent,
+    Bell,
+    /// Try these players in order; ring the bell if none succeeds.
+    Play(Vec<PlayerCommand>),
+}
+
+/// Decide how to play `alert`. `pack_sound` is the sound pack's file for
+/// the event, if any.
+pub fn alert_plan(
+    alert: &Alert,
+    platform: Platform,
+    pack_sound: Option<&Path>,
+    volume: f32,
+) -> AlertPlan {
+    let path = match alert {
+        Alert::Off => return AlertPlan::Silent,
+        Alert::Bell => return AlertPlan::Bell,
+        Alert::System => pack_sound.map(Path::to_path_buf).or_else(|| system_sound(platform)),
+        Alert::File(path) => Some(path.clone()),
+    };
+    let commands = path
+        .map(|p| player_commands(platform, &p, volume))
+        .unwrap_or_default();
+    if commands.is_empty() {
+        AlertPlan::Bell
+    } else {
+        AlertPlan::Play(commands)
+    }
+}
+
+/// Play an alert without blocking. Failures never surface: a missing
+/// player or file rings the bell instead.
+pub fn alert_async(alert: &Alert, pack_sound: Option<&Path>, volume: f32) {
+    match alert_plan(alert, Platform::current(), pack_sound, volume.clamp(0.0, 1.0)) {
+        AlertPlan::Silent => {}
+        AlertPlan::Bell => bell(),
+        AlertPlan::Play(commands) => {
+            tokio::spawn(async move {
+                if let Err(e) = run_players(&commands).await {
+                    tracing::debug!("Alert playback failed, ringing bell: {}", e);
+                    bell();
+                }
+            });
+        }
+    }
+}
+
+/// Ring the terminal bell. While the TUI owns the terminal this only
+/// queues it: the draw loop sends it between frames.
+pub fn bell() {
+    if crate::ui::term::is_active() {
+        crate::ui::term::queue_bell();
+        return;
+    }
+    let mut out = std::io::stdout();
+    let _ = out.write_all(b"\x07");
+    let _ = out.flush();
+}
+
 /// Play a sound file asynchronously (non-blocking, fire-and-forget).
 /// Volume is 0.0..=1.0.
 pub fn play_async(path: &Path, volume: f32) {
-    let path = path.to_path_buf();
-    let volume = volume.clamp(0.0, 1.0);
+    let commands = player_commands(Platform::current(), path, volume.clamp(0.0, 1.0));
 
     tokio::spawn(async move {
-        if let Err(e) = play_platform(&path, volume).await {
+        if let Err(e) = run_players(&commands).await {
             tracing::debug!("Sound playback failed: {}", e);
         }
     });
 }
 
-async fn play_platform(path: &Path, volume: f32) -> Result<(), String> {
-    let path_str = path.to_string_lossy();
-
-    if cfg!(target_os = "macos") {
-        // afplay -v <volume> <file>
-        Command::new("afplay")
-            .args(["-v", &format!("{:.2}", volume), &path_str.to_string()])
+/// Run players in order until one exits successfully.
+async fn run_players(commands: &[PlayerCommand]) -> Result<(), String> {
+    for (cmd, args) in commands {
+        let result = Command::new(cmd)
+            .args(args)
             .stdin(Stdio::null())
             .stdout(Stdio::null())
             .stderr(Stdio::null())
             .status()
-            .await
-            .map_err(|e| format!("afplay: {}", e))?;
-    } else {
-        // Linux: try players in priority order
-        let players: Vec<(&str, Vec<String>)> = vec![
-            (
-                "pw-play",
-                vec!["--volume".into(), format!("{:.2}", volume), path_str.to_string()],
-            ),
-            (
-                "paplay",
-                vec![
-                    format!("--volume={}", (volume * 65536.0) as u32),
-                    path_str.to_string(),
-                ],
-            ),
-            ("aplay", vec![path_str.to_string()]),
-        ];
-
-        for (cmd, args) in &players {
-            let result = Command::new(cmd)
-                .args(args)
-                .stdin(Stdio::null())
-                .stdout(Stdio::null())
-                .stderr(Stdio::null())
-                .status()
-                .await;
-
-            match result {
-                Ok(status) if status.success() => return Ok(()),
-                Ok(_) => continue,
-                Err(_) => continue, // Command not found, try next
-            }
+            .await;
+
+        match result {
+            Ok(status) if status.success() => return Ok(()),
+            Ok(_) => continue,
+            Err(_) => continue, // Command not found, try next
+        }
+    }
+
+    let tried: Vec<&str> = commands.iter().map(|(cmd, _)| *cmd).collect();
+    Err(format!("No audio player succeeded (tried {})", tried.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn programs(plan: &AlertPlan) -> Vec<&'static str> {
+        match plan {
+            AlertPlan::Play(commands) => commands.iter().map(|(cmd, _)| *cmd).collect(),
+            _ => Vec::new(),
         }
+    }
+
+    #[test]
+    fn parse_config_values() {
+        assert_eq!(Alert::parse("bell"), Alert::Bell);
+        assert_eq!(Alert::parse(" system "), Alert::System);
+        assert_eq!(Alert::parse("off"), Alert::Off);
+        assert_eq!(Alert::parse(""), Alert::Off);
+        assert_eq!(
+            Alert::parse("/tmp/ding.wav"),
+            Alert::File(PathBuf::from("/tmp/ding.wav"))
+        );
+    }
+
+    #[test]
+    fn player_commands_per_platform() {
+        let path = Path::new("/tmp/ding.wav");
+        let mac = player_commands(Platform::MacOs, path, 0.5);
+        let expected: Vec<String> = ["-v", "0.50", "/tmp/ding.wav"].map(String::from).to_vec();
+        assert_eq!(mac, [("afplay", expected)]);
+
+        let linux = player_commands(Platform::Linux, path, 0.5);
+        let names: Vec<&str> = linux.iter().map(|(cmd, _)| *cmd).collect();
+        assert_eq!(names, ["pw-play", "paplay", "aplay"]);
+        assert_eq!(linux[1].1[0], "--volume=32768");
 
-        return Err("No audio player found (tried pw-play, paplay, aplay)".into());
+        assert!(player_commands(Platform::Other, path, 0.5).is_empty());
     }
 
-    Ok(())
+    #[test]
+    fn alert_plan_matrix() {
+        let file = Alert::File(PathBuf::from("/tmp/ding.wav"));
+        let pack = Path::new("/packs/peon/input.wav");
+
+        assert_eq!(alert_plan(&Alert::Off, Platform::MacOs, Some(pack), 0.5), AlertPlan::Silent);
+        assert_eq!(alert_plan(&Alert::Bell, Platform::Linux, Some(pack), 0.5), AlertPlan::Bell);
+
+        let plan = alert_plan(&file, Platform::MacOs, Some(pack), 0.5);
+        assert_eq!(programs(&plan), ["afplay"]);
+        let plan = alert_plan(&file, Platform::Linux, None, 0.5);
+        assert_eq!(programs(&plan), ["pw-play", "paplay", "aplay"]);
+        // No player on this platform: the bell stands in.
+        assert_eq!(alert_plan(&file, Platform::Other, None, 0.5), AlertPlan::Bell);
+
+        // "system" prefers the pack's sound.
+        match alert_plan(&Alert::System, Platform::Linux, Some(pack), 0.5) {
+            AlertPlan::Play(commands) => {
+                assert!(commands.iter().all(|(_, args)| args.last().unwrap() == "/packs/peon/input.wav"));
+            }
+            other => panic!("expected players, got {other:?}"),
+        }
+        assert_eq!(alert_plan(&Alert::System, Platform::Other, None, 0.5), AlertPlan::Bell);
+    }
+
+    #[tokio::test]
+    async fn missing_player_is_an_error_not_a_panic() {
+        let commands = vec![("agent-hand-no-such-player", vec!["/tmp/ding.wav".to_string()])];
+        let err = run_players(&commands).await.unwrap_err();
+        assert!(err.contains("agent-hand-no-such-player"));
+    }
 }
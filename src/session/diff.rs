@@ -0,0 +1,109 @@
+//! Session-level diff between two versions of a profile's instance list.
+
+use std::collections::HashMap;
+
+use super::Instance;
+
+/// A session named in a diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffEntry {
+    pub id: String,
+    pub title: String,
+}
+
+/// Sessions added, removed and changed going from `before` to `after`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SessionDiff {
+    pub added: Vec<DiffEntry>,
+    pub removed: Vec<DiffEntry>,
+    pub changed: Vec<DiffEntry>,
+}
+
+impl SessionDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// One-line summary, e.g. `2 added, 1 removed, 0 changed`.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} added, {} removed, {} changed",
+            self.added.len(),
+            self.removed.len(),
+            self.changed.len()
+        )
+    }
+}
+
+fn entry(inst: &Instance) -> DiffEntry {
+    DiffEntry {
+        id: inst.id.clone(),
+        title: inst.title.clone(),
+    }
+}
+
+/// Diff two instance lists by session id.
+///
+/// A session counts as changed when its serialized form differs, so any
+/// persisted field (title, group, status, tags…) is considered. Entries keep
+/// the order of the list they were taken from.
+pub fn diff_instances(before: &[Instance], after: &[Instance]) -> SessionDiff {
+    let before_by_id: HashMap<&str, &Instance> =
+        before.iter().map(|s| (s.id.as_str(), s)).collect();
+    let after_by_id: HashMap<&str, &Instance> = after.iter().map(|s| (s.id.as_str(), s)).collect();
+
+    let mut diff = SessionDiff::default();
+    for inst in after {
+        match before_by_id.get(inst.id.as_str()) {
+            None => diff.added.push(entry(inst)),
+            Some(old) => {
+                let old_json = serde_json::to_value(old).ok();
+                let new_json = serde_json::to_value(inst).ok();
+                if old_json != new_json {
+                    diff.changed.push(entry(inst));
+                }
+            }
+        }
+    }
+    diff.removed = before
+        .iter()
+        .filter(|s| !after_by_id.contains_key(s.id.as_str()))
+        .map(entry)
+        .collect();
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn inst(id: &str, title: &str) -> Instance {
+        let mut s = Instance::new(title.to_string(), PathBuf::from("/tmp"));
+        s.id = id.to_string();
+        s
+    }
+
+    #[test]
+    fn detects_added_removed_and_changed() {
+        let before = vec![inst("a", "alpha"), inst("b", "beta"), inst("c", "gamma")];
+        let mut after = before.clone();
+        after.remove(1);
+        after[1].title = "gamma-renamed".to_string();
+        after.push(inst("d", "delta"));
+
+        let diff = diff_instances(&before, &after);
+        let ids = |v: &[DiffEntry]| v.iter().map(|e| e.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&diff.added), vec!["d"]);
+        assert_eq!(ids(&diff.removed), vec!["b"]);
+        assert_eq!(ids(&diff.changed), vec!["c"]);
+        assert_eq!(diff.changed[0].title, "gamma-renamed");
+        assert_eq!(diff.summary(), "1 added, 1 removed, 1 changed");
+    }
+
+    #[test]
+    fn identical_lists_are_empty() {
+        let list = vec![inst("a", "alpha")];
+        assert!(diff_instances(&list, &list.clone()).is_empty());
+    }
+}
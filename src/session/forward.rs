@@ -0,0 +1,64 @@
+//! Forwarding one session's output into another session's prompt, for
+//! handing an error from one agent to another without copy and paste.
+//!
+//! The text is typed into the target but not submitted, so it can be
+//! followed by a question before pressing Enter.
+
+use crate::error::{Error, Result};
+use crate::tmux::{strip_ansi, TmuxManager};
+
+/// Lines forwarded when `forward_lines` is not configured.
+pub const DEFAULT_FORWARD_LINES: usize = 50;
+
+/// The last `lines` lines of `content`, ANSI-stripped and without the blank
+/// rows below the prompt, wrapped in a header naming `source_title`. None
+/// when there is nothing to forward.
+pub fn forward_payload(source_title: &str, content: &str, lines: usize) -> Option<String> {
+    let content = strip_ansi(content);
+    let all: Vec<&str> = content.lines().map(str::trim_end).collect();
+    let end = all.iter().rposition(|l| !l.is_empty())? + 1;
+    let body = all[end.saturating_sub(lines.max(1))..end].join("\n");
+    Some(format!(
+        "--- output from {source_title} ---\n{body}\n--- end of output ---\n"
+    ))
+}
+
+/// Capture the last `lines` lines of `source_tmux` and type them into
+/// `target_tmux`. Returns how many lines were forwarded.
+pub async fn forward_output(
+    manager: &TmuxManager,
+    source_tmux: &str,
+    source_title: &str,
+    target_tmux: &str,
+    lines: usize,
+) -> Result<usize> {
+    let content = manager.capture_pane(source_tmux, lines).await?;
+    let payload = forward_payload(source_title, &content, lines)
+        .ok_or_else(|| Error::InvalidInput(format!("{source_title} has no output to forward")))?;
+    manager.type_text(target_tmux, &payload).await?;
+    // Header and footer lines aside.
+    Ok(payload.lines().count() - 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn payload_keeps_the_tail_without_ansi_or_trailing_blanks() {
+        let content = "one\n\x1b[31mtwo\x1b[0m\nthree   \n\n  \n";
+        assert_eq!(
+            forward_payload("api", content, 2).unwrap(),
+            "--- output from api ---\ntwo\nthree\n--- end of output ---\n"
+        );
+        assert_eq!(
+            forward_payload("api", content, 50).unwrap().lines().count(),
+            5
+        );
+    }
+
+    #[test]
+    fn blank_pane_has_nothing_to_forward() {
+        assert_eq!(forward_payload("api", "\n  \n\x1b[0m\n", 50), None);
+    }
+}
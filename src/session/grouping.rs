@@ -0,0 +1,363 @@
+//! Which group a session goes to when none is given.
+//!
+//! `[grouping] rules` map project paths to groups, and the first rule whose
+//! pattern matches wins:
+//!
+//! ```toml
+//! [grouping]
+//! rules = [
+//!   { pattern = "~/work/*/services/*", group = "work/{1}" },
+//!   { pattern = "~/code/github.com/*/**", group = "oss/{1}" },
+//! ]
+//! ```
+//!
+//! A pattern matches the whole path, component by component. `*` matches
+//! within one component (`api-*` works too), `**` matches any number of
+//! whole components, and a leading `~` is the home directory. Trailing and
+//! doubled slashes are ignored on both sides. Every wildcard is captured in
+//! order, and `{N}` in the group inserts the Nth capture. A path no rule
+//! matches falls back to [`guess_group`].
+//!
+//! The rules are installed once per process with [`set_rules`], so
+//! [`Instance::new`](super::Instance::new) can use them.
+
+use std::path::Path;
+use std::sync::RwLock;
+
+use serde::Serialize;
+
+use super::Instance;
+use crate::config::GroupRule;
+
+static RULES: RwLock<Vec<GroupRule>> = RwLock::new(Vec::new());
+
+/// Install the `[grouping]` rules for every new session in this process.
+pub fn set_rules(rules: &[GroupRule]) {
+    *RULES.write().unwrap_or_else(|e| e.into_inner()) = rules.to_vec();
+}
+
+/// The group for a new session in `path` under the installed rules.
+pub fn default_group(path: &Path) -> String {
+    resolve_group(path, &RULES.read().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// The group the first matching rule gives `path`, or the guess from the
+/// path when none matches. A rule may expand to `""`, i.e. no group.
+pub fn resolve_group(path: &Path, rules: &[GroupRule]) -> String {
+    let path = path.to_string_lossy();
+    rules
+        .iter()
+        .find_map(|rule| {
+            let captures = match_pattern(&expand_home(&rule.pattern), &path)?;
+            Some(expand_group(&rule.group, &captures))
+        })
+        .unwrap_or_else(|| guess_group(Path::new(path.as_ref())))
+}
+
+/// The group guessed from the path alone: the first two directories below
+/// home, e.g. `~/projects/work/app` -> `projects/work`, or the first
+/// directory outside it. `"default"` when that would be empty.
+pub fn guess_group(path: &Path) -> String {
+    let home = dirs::home_dir().unwrap_or_default();
+    let path_str = path.to_str().unwrap_or("");
+    let home_str = home.to_str().unwrap_or("");
+
+    let result = if path_str.starts_with(home_str) {
+        let relative = path_str.strip_prefix(home_str).unwrap_or("");
+        let parts: Vec<&str> = relative.trim_start_matches('/').split('/').collect();
+
+        // Use first 2 directory levels as group
+        if parts.len() >= 2 {
+            format!("{}/{}", parts[0], parts[1])
+        } else if parts.len() == 1 {
+            parts[0].to_string()
+        } else {
+            String::new()
+        }
+    } else {
+        // Not under home dir - use first directory
+        let parts: Vec<&str> = path_str.trim_start_matches('/').split('/').collect();
+        if !parts.is_empty() {
+            parts[0].to_string()
+        } else {
+            String::new()
+        }
+    };
+
+    if result.is_empty() {
+        "default".to_string()
+    } else {
+        result
+    }
+}
+
+/// A session `agent-hand regroup` would move.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GroupMove {
+    pub id: String,
+    pub title: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// Where `rules` would put the sessions whose group was never chosen:
+/// ungrouped ones and those still in the group guessed from their path.
+/// Sessions already where the rules put them are left out.
+pub fn proposed_moves(instances: &[Instance], rules: &[GroupRule]) -> Vec<GroupMove> {
+    instances
+        .iter()
+        .filter(|inst| {
+            let group = inst.group_path.as_str();
+            group.is_empty() || group == "default" || group == guess_group(&inst.project_path)
+        })
+        .filter_map(|inst| {
+            let to = resolve_group(&inst.project_path, rules);
+            (to != inst.group_path).then(|| GroupMove {
+                id: inst.id.clone(),
+                title: inst.title.clone(),
+                from: inst.group_path.clone(),
+                to,
+            })
+        })
+        .collect()
+}
+
+fn expand_home(pattern: &str) -> String {
+    match pattern.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+            let home = dirs::home_dir().unwrap_or_default();
+            format!("{}{rest}", home.to_string_lossy())
+        }
+        _ => pattern.to_string(),
+    }
+}
+
+/// The captures of `pattern` against `path`, or None when it does not
+/// match. `**` captures the components it spans joined by `/`.
+fn match_pattern(pattern: &str, path: &str) -> Option<Vec<String>> {
+    let pattern: Vec<&str> = pattern.split('/').filter(|c| !c.is_empty()).collect();
+    let path: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+    let mut captures = Vec::new();
+    match_components(&pattern, &path, &mut captures).then_some(captures)
+}
+
+fn match_components(pattern: &[&str], path: &[&str], captures: &mut Vec<String>) -> bool {
+    let Some((&first, rest)) = pattern.split_first() else {
+        return path.is_empty();
+    };
+    let mark = captures.len();
+    if first == "**" {
+        // Shortest span first, so later wildcards get the rest.
+        for n in 0..=path.len() {
+            captures.push(path[..n].join("/"));
+            if match_components(rest, &path[n..], captures) {
+                return true;
+            }
+            captures.truncate(mark);
+        }
+        return false;
+    }
+    let Some((&component, tail)) = path.split_first() else {
+        return false;
+    };
+    if match_component(first, component, captures) && match_components(rest, tail, captures) {
+        return true;
+    }
+    captures.truncate(mark);
+    false
+}
+
+/// Match one component against a pattern whose `*`s stand for any text,
+/// capturing what each one covered.
+fn match_component(pattern: &str, component: &str, captures: &mut Vec<String>) -> bool {
+    let pieces: Vec<&str> = pattern.split('*').collect();
+    let Some((&last, middle)) = pieces[1..].split_last() else {
+        return pattern == component;
+    };
+    let Some(mut rest) = component.strip_prefix(pieces[0]) else {
+        return false;
+    };
+    let mut found = Vec::with_capacity(pieces.len() - 1);
+    for piece in middle {
+        let Some(at) = rest.find(piece) else {
+            return false;
+        };
+        found.push(&rest[..at]);
+        rest = &rest[at + piece.len()..];
+    }
+    let Some(tail) = rest.strip_suffix(last) else {
+        return false;
+    };
+    found.push(tail);
+    captures.extend(found.into_iter().map(str::to_string));
+    true
+}
+
+/// Fill `{N}` in `template` with the Nth capture (from 1; missing ones are
+/// empty) and tidy the slashes that leaves behind.
+fn expand_group(template: &str, captures: &[String]) -> String {
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let index = after
+            .find('}')
+            .and_then(|close| Some((after[..close].parse::<usize>().ok()?, close)));
+        match index {
+            Some((n, close)) => {
+                if let Some(capture) = n.checked_sub(1).and_then(|i| captures.get(i)) {
+                    out.push_str(capture);
+                }
+                rest = &after[close + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out.split('/')
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn rule(pattern: &str, group: &str) -> GroupRule {
+        GroupRule {
+            pattern: pattern.into(),
+            group: group.into(),
+        }
+    }
+
+    fn captures(pattern: &str, path: &str) -> Option<Vec<String>> {
+        match_pattern(pattern, path)
+    }
+
+    #[test]
+    fn single_star_stays_within_one_component() {
+        assert_eq!(
+            captures("/w/*/services/*", "/w/acme/services/billing"),
+            Some(vec!["acme".into(), "billing".into()])
+        );
+        assert_eq!(captures("/w/*", "/w/acme/api"), None);
+        assert_eq!(captures("/w/*", "/w"), None);
+        assert_eq!(captures("/w/*/api", "/w/a/b/api"), None);
+    }
+
+    #[test]
+    fn star_inside_a_component() {
+        assert_eq!(
+            captures("/w/api-*", "/w/api-billing"),
+            Some(vec!["billing".into()])
+        );
+        assert_eq!(
+            captures("/w/*-*.rs", "/w/fix-login.rs"),
+            Some(vec!["fix".into(), "login".into()])
+        );
+        assert_eq!(captures("/w/api-*", "/w/web-billing"), None);
+        assert_eq!(captures("/w/*.git", "/w/repo"), None);
+        assert_eq!(captures("/w/api", "/w/api"), Some(vec![]));
+    }
+
+    #[test]
+    fn double_star_spans_any_number_of_components() {
+        assert_eq!(captures("/w/**", "/w"), Some(vec!["".into()]));
+        assert_eq!(captures("/w/**", "/w/a/b/c"), Some(vec!["a/b/c".into()]));
+        assert_eq!(
+            captures("/w/**/services/*", "/w/acme/eu/services/billing"),
+            Some(vec!["acme/eu".into(), "billing".into()])
+        );
+        assert_eq!(
+            captures("/w/**/services/*", "/w/services/billing"),
+            Some(vec!["".into(), "billing".into()])
+        );
+        // Backtracks past an earlier `services`.
+        assert_eq!(
+            captures("/w/**/services/*", "/w/services/x/services/y"),
+            Some(vec!["services/x".into(), "y".into()])
+        );
+        assert_eq!(
+            captures("/**/*", "/a/b/c"),
+            Some(vec!["a/b".into(), "c".into()])
+        );
+        assert_eq!(captures("/w/**/api", "/w/a/b/web"), None);
+        assert_eq!(captures("/w/**", "/x/w/a"), None);
+    }
+
+    #[test]
+    fn trailing_and_doubled_slashes_are_ignored() {
+        assert_eq!(captures("/w/*/", "/w/api"), Some(vec!["api".into()]));
+        assert_eq!(captures("/w/*", "/w/api/"), Some(vec!["api".into()]));
+        assert_eq!(captures("/w//*", "/w/api"), Some(vec!["api".into()]));
+        assert_eq!(captures("/w/**/", "/w/a/b/"), Some(vec!["a/b".into()]));
+    }
+
+    #[test]
+    fn group_templates_use_captures() {
+        let caps = vec!["acme".to_string(), "".to_string()];
+        assert_eq!(expand_group("work/{1}", &caps), "work/acme");
+        assert_eq!(expand_group("work/{2}/x", &caps), "work/x");
+        assert_eq!(expand_group("{9}/{0}/misc", &caps), "misc");
+        assert_eq!(expand_group("a/{x}/{1", &caps), "a/{x}/{1");
+        assert_eq!(expand_group(" scratch ", &caps), "scratch");
+        assert_eq!(expand_group("", &caps), "");
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let rules = [
+            rule("/w/*/services/*", "work/{1}/{2}"),
+            rule("/w/**", "work"),
+            rule("/tmp/**", ""),
+        ];
+        let group = |p: &str| resolve_group(&PathBuf::from(p), &rules);
+        assert_eq!(group("/w/acme/services/billing"), "work/acme/billing");
+        assert_eq!(group("/w/acme/web"), "work");
+        assert_eq!(group("/tmp/scratch"), "");
+        assert_eq!(group("/srv/api"), "srv", "falls back to the guess");
+    }
+
+    #[test]
+    fn tilde_is_the_home_directory() {
+        let Some(home) = dirs::home_dir() else {
+            return;
+        };
+        let path = home.join("code/github.com/acme/api");
+        let rules = [rule("~/code/github.com/*/**", "oss/{1}")];
+        assert_eq!(resolve_group(&path, &rules), "oss/acme");
+        assert_eq!(resolve_group(&path, &[]), "code/github.com");
+        assert_eq!(expand_home("~user/x"), "~user/x");
+    }
+
+    #[test]
+    fn regroup_leaves_chosen_groups_alone() {
+        let rules = [rule("/w/*", "work/{1}")];
+        let mut guessed = Instance::with_group("api".into(), "/w/api".into(), "w".into());
+        guessed.id = "a".into();
+        let mut ungrouped = Instance::with_group("web".into(), "/w/web".into(), String::new());
+        ungrouped.id = "b".into();
+        let chosen = Instance::with_group("db".into(), "/w/db".into(), "infra".into());
+        let settled = Instance::with_group("ui".into(), "/w/ui".into(), "work/ui".into());
+
+        let moves = proposed_moves(&[guessed, ungrouped, chosen, settled], &rules);
+        let moved: Vec<(&str, &str, &str)> = moves
+            .iter()
+            .map(|m| (m.id.as_str(), m.from.as_str(), m.to.as_str()))
+            .collect();
+        assert_eq!(moved, [("a", "w", "work/api"), ("b", "", "work/web")]);
+    }
+
+    #[test]
+    fn guess_uses_leading_directories() {
+        assert_eq!(guess_group(Path::new("/srv/api")), "srv");
+        assert_eq!(guess_group(Path::new("/")), "default");
+    }
+}
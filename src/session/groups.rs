@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use super::Instance;
+use crate::error::{Error, Result};
+
 /// Group data (persisted)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GroupData {
@@ -8,6 +11,9 @@ pub struct GroupData {
     pub path: String,
     pub expanded: bool,
     pub order: i32,
+    /// Free-form context shown in the group's preview.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub notes: String,
 }
 
 impl GroupData {
@@ -18,10 +24,40 @@ impl GroupData {
             path,
             expanded: true,
             order: 0,
+            notes: String::new(),
         }
     }
 }
 
+/// Notes as one line for a single-line input: newlines become a literal
+/// `\n` and backslashes are doubled.
+pub fn notes_to_input(notes: &str) -> String {
+    notes.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+/// Inverse of [`notes_to_input`]: `\n` is a newline and `\\` a backslash;
+/// any other backslash is kept. Surrounding whitespace is trimmed.
+pub fn notes_from_input(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('\\') => out.push('\\'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out.trim().to_string()
+}
+
 /// Group tree structure
 #[derive(Debug, Clone)]
 pub struct GroupTree {
@@ -66,24 +102,32 @@ impl GroupTree {
         self.groups.remove(path).is_some()
     }
 
-    /// Delete a group and all descendants.
-    pub fn delete_group_prefix(&mut self, path: &str) -> usize {
+    /// Delete a group and all descendants, returning them parents first.
+    pub fn delete_group_prefix(&mut self, path: &str) -> Vec<GroupData> {
         let path = path.trim();
         if path.is_empty() {
-            return 0;
+            return Vec::new();
         }
         let slash = format!("{}/", path);
-        let to_remove: Vec<String> = self
+        let mut to_remove: Vec<String> = self
             .groups
             .keys()
             .filter(|p| *p == path || p.starts_with(&slash))
             .cloned()
             .collect();
-        let n = to_remove.len();
-        for p in to_remove {
-            self.groups.remove(&p);
+        to_remove.sort();
+        to_remove
+            .into_iter()
+            .filter_map(|p| self.groups.remove(&p))
+            .collect()
+    }
+
+    /// Put back a deleted group as it was, creating missing parents.
+    pub fn restore_group(&mut self, group: GroupData) {
+        if let Some(parent_path) = self.parent_path(&group.path) {
+            self.create_group(parent_path);
         }
-        n
+        self.groups.insert(group.path.clone(), group);
     }
 
     /// Get a group
@@ -112,6 +156,17 @@ impl GroupTree {
         }
     }
 
+    /// Replace a group's notes. Returns false when there is no such group.
+    pub fn set_notes(&mut self, path: &str, notes: String) -> bool {
+        match self.groups.get_mut(path) {
+            Some(group) => {
+                group.notes = notes;
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Check if group is expanded
     pub fn is_expanded(&self, path: &str) -> bool {
         self.groups.get(path).map(|g| g.expanded).unwrap_or(true)
@@ -184,6 +239,57 @@ impl GroupTree {
 
         true
     }
+
+    /// Move the group `path` and its descendants under `new_parent` (empty
+    /// for the top level) and expand the destination. Returns the group's
+    /// new path.
+    pub fn move_group(&mut self, path: &str, new_parent: &str) -> Result<String> {
+        let path = path.trim();
+        let new_parent = new_parent.trim().trim_matches('/');
+        if !self.groups.contains_key(path) {
+            return Err(Error::InvalidInput(format!("no such group: {path}")));
+        }
+        if new_parent == path || new_parent.starts_with(&format!("{path}/")) {
+            return Err(Error::InvalidInput(format!(
+                "cannot move '{path}' into itself or one of its subgroups"
+            )));
+        }
+
+        let name = path.rsplit('/').next().unwrap_or(path);
+        let new_path = if new_parent.is_empty() {
+            name.to_string()
+        } else {
+            format!("{new_parent}/{name}")
+        };
+        if new_path == path {
+            return Ok(new_path);
+        }
+        if self.groups.contains_key(&new_path) {
+            return Err(Error::InvalidInput(format!(
+                "group already exists: {new_path}"
+            )));
+        }
+
+        self.rename_prefix(path, &new_path);
+        let mut ancestor = self.parent_path(&new_path);
+        while let Some(p) = ancestor {
+            self.set_expanded(&p, true);
+            ancestor = self.parent_path(&p);
+        }
+        Ok(new_path)
+    }
+}
+
+/// Point sessions in the group `old` (or below it) at `new` after the group
+/// was renamed or moved.
+pub fn rename_sessions_group(instances: &mut [Instance], old: &str, new: &str) {
+    for inst in instances.iter_mut() {
+        if let Some(suffix) = inst.group_path.strip_prefix(old) {
+            if suffix.is_empty() || suffix.starts_with('/') {
+                inst.group_path = format!("{new}{suffix}");
+            }
+        }
+    }
 }
 
 impl Default for GroupTree {
@@ -206,6 +312,25 @@ mod tests {
         assert!(tree.get_group("work/frontend").is_some());
     }
 
+    #[test]
+    fn deleted_groups_restore_with_their_settings() {
+        let mut tree = GroupTree::new();
+        tree.create_group("work/frontend".to_string());
+        tree.set_notes("work", "ship it".to_string());
+        tree.set_expanded("work/frontend", false);
+
+        let removed = tree.delete_group_prefix("work");
+        let paths: Vec<&str> = removed.iter().map(|g| g.path.as_str()).collect();
+        assert_eq!(paths, ["work", "work/frontend"]);
+        assert!(tree.all_groups().is_empty());
+
+        for group in removed {
+            tree.restore_group(group);
+        }
+        assert_eq!(tree.get_group("work").unwrap().notes, "ship it");
+        assert!(!tree.get_group("work/frontend").unwrap().expanded);
+    }
+
     #[test]
     fn test_children() {
         let mut tree = GroupTree::new();
@@ -235,11 +360,92 @@ mod tests {
         tree.create_group("work".to_string());
         tree.create_group("work/frontend".to_string());
         tree.set_expanded("work/frontend", false);
+        tree.set_notes("work/frontend", "deploy with make release".to_string());
 
         assert!(tree.rename_prefix("work", "jobs"));
         assert!(tree.get_group("jobs").is_some());
         assert!(tree.get_group("jobs/frontend").is_some());
         assert!(!tree.is_expanded("jobs/frontend"));
+        assert_eq!(
+            tree.get_group("jobs/frontend").unwrap().notes,
+            "deploy with make release"
+        );
         assert!(tree.get_group("work").is_none());
     }
+
+    #[test]
+    fn move_group_reparents_descendants_and_sessions() {
+        let mut tree = GroupTree::new();
+        tree.create_group("work/frontend/ui".to_string());
+        tree.create_group("archive".to_string());
+        tree.set_expanded("archive", false);
+        tree.set_expanded("work/frontend/ui", false);
+
+        let new_path = tree.move_group("work/frontend", "archive").unwrap();
+        assert_eq!(new_path, "archive/frontend");
+        assert!(tree.get_group("archive/frontend/ui").is_some());
+        assert!(!tree.is_expanded("archive/frontend/ui"));
+        assert!(tree.is_expanded("archive"));
+        assert!(tree.get_group("work/frontend").is_none());
+        assert!(tree.get_group("work").is_some());
+
+        let mut instances: Vec<Instance> = [
+            "work/frontend",
+            "work/frontend/ui",
+            "work/frontend-old",
+            "work",
+        ]
+        .iter()
+        .map(|g| Instance::with_group("s".into(), "/tmp".into(), g.to_string()))
+        .collect();
+        rename_sessions_group(&mut instances, "work/frontend", &new_path);
+        let groups: Vec<_> = instances.iter().map(|i| i.group_path.as_str()).collect();
+        assert_eq!(
+            groups,
+            [
+                "archive/frontend",
+                "archive/frontend/ui",
+                "work/frontend-old",
+                "work"
+            ]
+        );
+
+        // Back to the top level.
+        assert_eq!(
+            tree.move_group("archive/frontend", "/").unwrap(),
+            "frontend"
+        );
+        assert!(tree.get_group("frontend/ui").is_some());
+    }
+
+    #[test]
+    fn notes_round_trip_through_one_line_input() {
+        let notes = "creds: 1password \"staging\"\ndeploy: make release\nC:\\new";
+        let line = notes_to_input(notes);
+        assert!(!line.contains('\n'));
+        assert_eq!(notes_from_input(&line), notes);
+        assert_eq!(notes_from_input(r"  a\nb \q  "), "a\nb \\q");
+
+        let old: GroupData =
+            serde_json::from_str(r#"{"name":"w","path":"w","expanded":true,"order":0}"#).unwrap();
+        assert!(old.notes.is_empty());
+        assert!(!serde_json::to_string(&old).unwrap().contains("notes"));
+    }
+
+    #[test]
+    fn move_group_refuses_cycles_and_collisions() {
+        let mut tree = GroupTree::new();
+        tree.create_group("work/frontend".to_string());
+        tree.create_group("archive/frontend".to_string());
+
+        assert!(tree.move_group("work", "work").is_err());
+        assert!(tree.move_group("work", "work/frontend").is_err());
+        assert!(tree.move_group("work/frontend", "archive").is_err());
+        assert!(tree.move_group("missing", "archive").is_err());
+        // Moving to the current parent changes nothing.
+        assert_eq!(
+            tree.move_group("work/frontend", "work").unwrap(),
+            "work/frontend"
+        );
+    }
 }
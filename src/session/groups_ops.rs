@@ -0,0 +1,240 @@
+//! Group changes shared by the TUI and `agent-hand group`. They work on a
+//! loaded profile; callers save it and deal with tmux.
+
+use serde::Serialize;
+
+use super::{rename_sessions_group, GroupData, GroupTree, Instance};
+
+/// Whether a session in `group_path` belongs to the group `path` or one of
+/// its subgroups.
+pub fn in_group(group_path: &str, path: &str) -> bool {
+    group_path
+        .strip_prefix(path)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Create the group `path` and any missing parents, expanded so it shows.
+/// Returns false for an empty path.
+pub fn create_group(tree: &mut GroupTree, path: &str) -> bool {
+    let path = path.trim();
+    if path.is_empty() {
+        return false;
+    }
+    tree.create_group(path.to_string());
+    let parts: Vec<&str> = path.split('/').collect();
+    for i in 1..=parts.len() {
+        tree.set_expanded(&parts[..i].join("/"), true);
+    }
+    true
+}
+
+/// Rename the group `old` to `new`, with its subgroups and their sessions.
+/// Returns false when there is nothing to do.
+pub fn rename_group(
+    instances: &mut [Instance],
+    tree: &mut GroupTree,
+    old: &str,
+    new: &str,
+) -> bool {
+    let (old, new) = (old.trim(), new.trim());
+    if old.is_empty() || new.is_empty() || old == new {
+        return false;
+    }
+    rename_sessions_group(instances, old, new);
+    tree.rename_prefix(old, new)
+}
+
+/// What deleting a group removed.
+#[derive(Debug, Default)]
+pub struct DeletedGroup {
+    /// The group and its subgroups, parents first.
+    pub groups: Vec<GroupData>,
+    /// Sessions deleted along with the group.
+    pub sessions: Vec<Instance>,
+    /// Sessions moved out of the group, with the group each was in.
+    pub regrouped: Vec<(String, String)>,
+}
+
+/// Delete the group `path` and its subgroups, leaving their sessions
+/// ungrouped.
+pub fn delete_group_keep_sessions(
+    instances: &mut [Instance],
+    tree: &mut GroupTree,
+    path: &str,
+) -> DeletedGroup {
+    let path = path.trim();
+    if path.is_empty() {
+        return DeletedGroup::default();
+    }
+    let regrouped = instances
+        .iter_mut()
+        .filter(|inst| in_group(&inst.group_path, path))
+        .map(|inst| (inst.id.clone(), std::mem::take(&mut inst.group_path)))
+        .collect();
+    DeletedGroup {
+        groups: tree.delete_group_prefix(path),
+        sessions: Vec::new(),
+        regrouped,
+    }
+}
+
+/// Delete the group `path`, its subgroups and every session in them. The
+/// caller stops the removed sessions' tmux sessions.
+pub fn delete_group_with_sessions(
+    instances: &mut Vec<Instance>,
+    tree: &mut GroupTree,
+    path: &str,
+) -> DeletedGroup {
+    let path = path.trim();
+    if path.is_empty() {
+        return DeletedGroup::default();
+    }
+    let (sessions, kept): (Vec<Instance>, Vec<Instance>) = std::mem::take(instances)
+        .into_iter()
+        .partition(|inst| in_group(&inst.group_path, path));
+    *instances = kept;
+    DeletedGroup {
+        groups: tree.delete_group_prefix(path),
+        sessions,
+        regrouped: Vec::new(),
+    }
+}
+
+/// One line of `agent-hand group list`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GroupRow {
+    pub path: String,
+    pub name: String,
+    /// 0 for a top-level group.
+    pub depth: usize,
+    /// Sessions directly in the group.
+    pub sessions: usize,
+    /// Sessions in the group and its subgroups.
+    pub total: usize,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub notes: String,
+}
+
+/// Every group in tree order, parents before children, with session
+/// counts. Groups that only sessions mention are listed too.
+pub fn group_rows(tree: &GroupTree, instances: &[Instance]) -> Vec<GroupRow> {
+    let mut tree = tree.clone();
+    for inst in instances.iter().filter(|s| !s.group_path.is_empty()) {
+        tree.create_group(inst.group_path.clone());
+    }
+    let mut groups = tree.all_groups();
+    groups.sort_by(|a, b| a.path.split('/').cmp(b.path.split('/')));
+    groups
+        .into_iter()
+        .map(|g| GroupRow {
+            depth: g.path.matches('/').count(),
+            sessions: instances.iter().filter(|s| s.group_path == g.path).count(),
+            total: instances
+                .iter()
+                .filter(|s| in_group(&s.group_path, &g.path))
+                .count(),
+            path: g.path,
+            name: g.name,
+            notes: g.notes,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(title: &str, group: &str) -> Instance {
+        let mut inst = Instance::new(title.to_string(), "/tmp".into());
+        inst.group_path = group.to_string();
+        inst
+    }
+
+    fn fixture() -> (Vec<Instance>, GroupTree) {
+        let instances = vec![
+            session("api", "work"),
+            session("web", "work/frontend"),
+            session("blog", "personal"),
+            session("misc", ""),
+        ];
+        let mut tree = GroupTree::new();
+        for path in ["work/frontend", "work-old", "personal"] {
+            tree.create_group(path.to_string());
+        }
+        (instances, tree)
+    }
+
+    #[test]
+    fn in_group_matches_whole_path_segments() {
+        assert!(in_group("work", "work"));
+        assert!(in_group("work/frontend", "work"));
+        assert!(!in_group("work-old", "work"));
+        assert!(!in_group("", "work"));
+    }
+
+    #[test]
+    fn create_expands_the_new_group_and_its_parents() {
+        let mut tree = GroupTree::new();
+        tree.create_group("work".to_string());
+        tree.set_expanded("work", false);
+        assert!(create_group(&mut tree, " work/frontend "));
+        assert!(tree.is_expanded("work"));
+        assert!(tree.get_group("work/frontend").is_some());
+        assert!(!create_group(&mut tree, "  "));
+    }
+
+    #[test]
+    fn rename_moves_subgroups_and_sessions() {
+        let (mut instances, mut tree) = fixture();
+        assert!(rename_group(&mut instances, &mut tree, "work", "job"));
+        assert!(tree.get_group("job/frontend").is_some());
+        assert!(tree.get_group("work").is_none());
+        assert!(tree.get_group("work-old").is_some());
+        let groups: Vec<&str> = instances.iter().map(|s| s.group_path.as_str()).collect();
+        assert_eq!(groups, ["job", "job/frontend", "personal", ""]);
+        assert!(!rename_group(&mut instances, &mut tree, "job", "job"));
+    }
+
+    #[test]
+    fn delete_keeping_sessions_ungroups_them() {
+        let (mut instances, mut tree) = fixture();
+        let deleted = delete_group_keep_sessions(&mut instances, &mut tree, "work");
+        let paths: Vec<&str> = deleted.groups.iter().map(|g| g.path.as_str()).collect();
+        assert_eq!(paths, ["work", "work/frontend"]);
+        assert_eq!(deleted.regrouped.len(), 2);
+        assert_eq!(deleted.regrouped[1].1, "work/frontend");
+        assert_eq!(instances.len(), 4);
+        assert!(instances[..2].iter().all(|s| s.group_path.is_empty()));
+        assert!(tree.get_group("work-old").is_some());
+    }
+
+    #[test]
+    fn delete_with_sessions_removes_them() {
+        let (mut instances, mut tree) = fixture();
+        let deleted = delete_group_with_sessions(&mut instances, &mut tree, "work");
+        let removed: Vec<&str> = deleted.sessions.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(removed, ["api", "web"]);
+        let kept: Vec<&str> = instances.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(kept, ["blog", "misc"]);
+        assert!(tree.get_group("work/frontend").is_none());
+    }
+
+    #[test]
+    fn rows_are_in_tree_order_with_counts() {
+        let (instances, mut tree) = fixture();
+        tree.delete_group_prefix("personal");
+        let rows: Vec<(String, usize, usize, usize)> = group_rows(&tree, &instances)
+            .into_iter()
+            .map(|r| (r.path, r.depth, r.sessions, r.total))
+            .collect();
+        assert_eq!(
+            rows,
+            [
+                ("personal".to_string(), 0, 1, 1),
+                ("work".to_string(), 0, 1, 2),
+                ("work/frontend".to_string(), 1, 1, 1),
+                ("work-old".to_string(), 0, 0, 0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,267 @@
+//! Whether a TUI has a profile open, and removals it still has to pick up.
+//!
+//! The TUI keeps its sessions in memory and writes all of them back on every
+//! save, so a session removed from the CLI while it runs comes back on the
+//! next save. A running TUI refreshes `tui.heartbeat` every few seconds;
+//! destructive CLI commands check it and refuse without `--force`. `remove`
+//! also appends the removed ids to `tombstones.jsonl`, which the TUI takes
+//! on its next tick, dropping those sessions from memory before it saves.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::Instance;
+use crate::error::Result;
+
+pub const HEARTBEAT_FILE: &str = "tui.heartbeat";
+pub const TOMBSTONES_FILE: &str = "tombstones.jsonl";
+
+/// How often a running TUI rewrites its heartbeat.
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+/// A heartbeat this old belongs to a TUI that exited or hung.
+pub const HEARTBEAT_STALE_AFTER: Duration = Duration::from_secs(15);
+/// Tombstones this old are ignored; the TUI they were written for is gone
+/// and a later one loaded storage without those sessions anyway.
+pub const TOMBSTONE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Whether `at` is within `limit` of `now`, in either direction (the clock
+/// may have been adjusted since it was written).
+fn within(now: DateTime<Utc>, at: DateTime<Utc>, limit: Duration) -> bool {
+    let age_ms = now.signed_duration_since(at).num_milliseconds().unsigned_abs();
+    u128::from(age_ms) < limit.as_millis()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Heartbeat {
+    pub pid: u32,
+    pub at: DateTime<Utc>,
+}
+
+impl Heartbeat {
+    pub fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        within(now, self.at, HEARTBEAT_STALE_AFTER)
+    }
+}
+
+pub fn write_heartbeat(profile_dir: &Path, heartbeat: &Heartbeat) -> Result<()> {
+    fs::create_dir_all(profile_dir)?;
+    let tmp = profile_dir.join(format!("{HEARTBEAT_FILE}.tmp"));
+    fs::write(&tmp, serde_json::to_vec(heartbeat)?)?;
+    fs::rename(tmp, profile_dir.join(HEARTBEAT_FILE))?;
+    Ok(())
+}
+
+pub fn read_heartbeat(profile_dir: &Path) -> Option<Heartbeat> {
+    let bytes = fs::read(profile_dir.join(HEARTBEAT_FILE)).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// The heartbeat of a TUI in another process that is still running.
+pub fn live_tui(profile_dir: &Path, now: DateTime<Utc>) -> Option<Heartbeat> {
+    read_heartbeat(profile_dir).filter(|h| h.pid != std::process::id() && h.is_fresh(now))
+}
+
+/// Keeps a TUI's heartbeat current. Driven from the TUI's tick.
+#[derive(Debug)]
+pub struct HeartbeatWriter {
+    profile_dir: PathBuf,
+    pid: u32,
+    last: Option<Instant>,
+}
+
+impl HeartbeatWriter {
+    pub fn new(profile_dir: PathBuf) -> Self {
+        Self {
+            profile_dir,
+            pid: std::process::id(),
+            last: None,
+        }
+    }
+
+    pub fn profile_dir(&self) -> &Path {
+        &self.profile_dir
+    }
+
+    /// Rewrite the heartbeat if [`HEARTBEAT_INTERVAL`] has passed since the
+    /// last write. Returns whether it wrote.
+    pub fn tick(&mut self, now: Instant, wall: DateTime<Utc>) -> bool {
+        if self
+            .last
+            .is_some_and(|last| now.saturating_duration_since(last) < HEARTBEAT_INTERVAL)
+        {
+            return false;
+        }
+        self.last = Some(now);
+        let heartbeat = Heartbeat {
+            pid: self.pid,
+            at: wall,
+        };
+        if let Err(e) = write_heartbeat(&self.profile_dir, &heartbeat) {
+            tracing::debug!("failed to write TUI heartbeat: {e}");
+        }
+        true
+    }
+
+    /// Remove the heartbeat on exit, unless another TUI has taken it over.
+    pub fn clear(&self) {
+        if read_heartbeat(&self.profile_dir).is_some_and(|h| h.pid == self.pid) {
+            let _ = fs::remove_file(self.profile_dir.join(HEARTBEAT_FILE));
+        }
+    }
+}
+
+/// A session removed outside the TUI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tombstone {
+    pub id: String,
+    pub at: DateTime<Utc>,
+}
+
+pub fn append_tombstones(profile_dir: &Path, ids: &[String], now: DateTime<Utc>) -> Result<()> {
+    let mut lines = String::new();
+    for id in ids {
+        let tombstone = Tombstone {
+            id: id.clone(),
+            at: now,
+        };
+        lines.push_str(&serde_json::to_string(&tombstone)?);
+        lines.push('\n');
+    }
+    fs::create_dir_all(profile_dir)?;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(profile_dir.join(TOMBSTONES_FILE))?;
+    file.write_all(lines.as_bytes())?;
+    Ok(())
+}
+
+/// Take every pending tombstone, leaving none behind. Expired and
+/// unreadable entries are dropped.
+pub fn take_tombstones(profile_dir: &Path, now: DateTime<Utc>) -> Vec<Tombstone> {
+    let path = profile_dir.join(TOMBSTONES_FILE);
+    // Move the file aside first so an append racing with us starts a new one.
+    let taken = profile_dir.join(format!("{TOMBSTONES_FILE}.taken"));
+    if fs::rename(&path, &taken).is_err() {
+        return Vec::new();
+    }
+    let content = fs::read_to_string(&taken).unwrap_or_default();
+    let _ = fs::remove_file(&taken);
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Tombstone>(line).ok())
+        .filter(|t| within(now, t.at, TOMBSTONE_TTL))
+        .collect()
+}
+
+/// Remove tombstoned sessions from `sessions` and return them.
+pub fn drop_tombstoned(sessions: &mut Vec<Instance>, tombstones: &[Tombstone]) -> Vec<Instance> {
+    let (gone, kept): (Vec<Instance>, Vec<Instance>) = std::mem::take(sessions)
+        .into_iter()
+        .partition(|s| tombstones.iter().any(|t| t.id == s.id));
+    *sessions = kept;
+    gone
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(n: i64) -> chrono::Duration {
+        chrono::Duration::seconds(n)
+    }
+
+    #[test]
+    fn heartbeat_staleness() {
+        let now = Utc::now();
+        let beat = |age: i64| Heartbeat {
+            pid: 1,
+            at: now - secs(age),
+        };
+        assert!(beat(0).is_fresh(now));
+        assert!(beat(14).is_fresh(now));
+        assert!(!beat(15).is_fresh(now));
+        assert!(!beat(3600).is_fresh(now));
+        // A clock step back keeps a recent heartbeat fresh, not a wild one.
+        assert!(beat(-5).is_fresh(now));
+        assert!(!beat(-3600).is_fresh(now));
+    }
+
+    #[test]
+    fn writer_refreshes_on_interval_and_clears_its_own_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut writer = HeartbeatWriter::new(dir.path().to_path_buf());
+        let t0 = Instant::now();
+        let wall = Utc::now();
+
+        assert!(writer.tick(t0, wall));
+        assert!(!writer.tick(t0 + Duration::from_secs(4), wall + secs(4)));
+        assert!(writer.tick(t0 + HEARTBEAT_INTERVAL, wall + secs(5)));
+        let written = read_heartbeat(dir.path()).unwrap();
+        assert_eq!(written.pid, std::process::id());
+        assert_eq!(written.at, wall + secs(5));
+
+        // Our own heartbeat never counts as another TUI.
+        assert_eq!(live_tui(dir.path(), wall + secs(6)), None);
+        let other = Heartbeat {
+            pid: written.pid + 1,
+            at: wall,
+        };
+        write_heartbeat(dir.path(), &other).unwrap();
+        assert_eq!(live_tui(dir.path(), wall + secs(10)), Some(other.clone()));
+        assert_eq!(live_tui(dir.path(), wall + secs(60)), None);
+
+        // Another TUI took over: leave its heartbeat alone.
+        writer.clear();
+        assert_eq!(read_heartbeat(dir.path()), Some(other));
+        writer.tick(t0 + Duration::from_secs(20), wall);
+        writer.clear();
+        assert_eq!(read_heartbeat(dir.path()), None);
+    }
+
+    #[test]
+    fn tombstones_are_taken_once_and_expire() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = Utc::now();
+        assert!(take_tombstones(dir.path(), now).is_empty());
+
+        append_tombstones(dir.path(), &["old".to_string()], now - secs(3600)).unwrap();
+        append_tombstones(dir.path(), &["a".to_string(), "b".to_string()], now).unwrap();
+        let taken: Vec<String> = take_tombstones(dir.path(), now + secs(1))
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(taken, ["a", "b"]);
+        assert!(take_tombstones(dir.path(), now).is_empty());
+    }
+
+    #[test]
+    fn tombstoned_sessions_leave_memory() {
+        let session = |id: &str| {
+            let mut inst = Instance::new(id.into(), PathBuf::from("/tmp"));
+            inst.id = id.into();
+            inst
+        };
+        let mut sessions = vec![session("a"), session("b"), session("c")];
+        let tombstones = [
+            Tombstone {
+                id: "b".into(),
+                at: Utc::now(),
+            },
+            Tombstone {
+                id: "gone-already".into(),
+                at: Utc::now(),
+            },
+        ];
+        let dropped = drop_tombstoned(&mut sessions, &tombstones);
+        assert_eq!(dropped.len(), 1);
+        assert_eq!(dropped[0].id, "b");
+        let left: Vec<&str> = sessions.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(left, ["a", "c"]);
+    }
+}
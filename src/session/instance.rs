@@ -16,8 +16,63 @@ pub enum Status {
     Running,
     Waiting,
     Idle,
+    /// Exited unexpectedly, a tmux command failed, or the working directory
+    /// is gone.
     Error,
     Starting,
+    /// Not running because it was stopped.
+    Stopped,
+}
+
+impl Status {
+    /// Status once the session's process is gone: exiting in the middle of
+    /// a run is a crash, anything else a plain stop.
+    pub fn after_exit(self) -> Status {
+        match self {
+            Status::Running | Status::Error => Status::Error,
+            _ => Status::Stopped,
+        }
+    }
+}
+
+/// When a session entered the status it was last seen in. The status is
+/// kept alongside because the saved `status` is not always the live one
+/// (Running is never written), and a reload must not restart the clock.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StatusSince {
+    pub status: Status,
+    pub at: DateTime<Utc>,
+}
+
+/// An extra tmux window started with the session, e.g. a dev server next
+/// to the agent (`add --window dev:"npm run dev"`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WindowSpec {
+    pub name: String,
+    pub command: String,
+}
+
+impl std::str::FromStr for WindowSpec {
+    type Err = String;
+
+    /// `NAME:COMMAND`. The name may not contain `.` or `:`, which tmux
+    /// reads as target separators.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, command) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected NAME:COMMAND, got '{s}'"))?;
+        let (name, command) = (name.trim(), command.trim());
+        if name.is_empty() || command.is_empty() {
+            return Err(format!("expected NAME:COMMAND, got '{s}'"));
+        }
+        if name.chars().any(|c| c == '.' || c.is_control()) {
+            return Err(format!("invalid window name '{name}'"));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            command: command.to_string(),
+        })
+    }
 }
 
 impl From<SessionStatus> for Status {
@@ -28,6 +83,7 @@ impl From<SessionStatus> for Status {
             SessionStatus::Idle => Status::Idle,
             SessionStatus::Error => Status::Error,
             SessionStatus::Starting => Status::Starting,
+            SessionStatus::Stopped => Status::Stopped,
         }
     }
 }
@@ -51,6 +107,53 @@ impl Default for LabelColor {
     }
 }
 
+impl LabelColor {
+    /// Every color, in the order the label picker offers them.
+    pub const ALL: [LabelColor; 7] = [
+        Self::Gray,
+        Self::Magenta,
+        Self::Cyan,
+        Self::Green,
+        Self::Yellow,
+        Self::Red,
+        Self::Blue,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Gray => "gray",
+            Self::Magenta => "magenta",
+            Self::Cyan => "cyan",
+            Self::Green => "green",
+            Self::Yellow => "yellow",
+            Self::Red => "red",
+            Self::Blue => "blue",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_lowercase();
+        Self::ALL.into_iter().find(|c| c.name() == s)
+    }
+}
+
+/// Longest label shown in a badge before it is cut with `…`.
+pub const LABEL_BADGE_MAX_CHARS: usize = 10;
+
+/// `label` as a `[label]` badge, cut to [`LABEL_BADGE_MAX_CHARS`]. `None`
+/// for a blank label.
+pub fn label_badge(label: &str) -> Option<String> {
+    let label = label.trim();
+    if label.is_empty() {
+        return None;
+    }
+    if label.chars().count() <= LABEL_BADGE_MAX_CHARS {
+        return Some(format!("[{label}]"));
+    }
+    let cut: String = label.chars().take(LABEL_BADGE_MAX_CHARS - 1).collect();
+    Some(format!("[{}…]", cut.trim_end()))
+}
+
 /// Session instance
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Instance {
@@ -85,6 +188,10 @@ pub struct Instance {
     #[serde(default)]
     pub last_waiting_at: Option<DateTime<Utc>>,
 
+    /// When the current status began; only moved by an actual change
+    #[serde(default)]
+    pub status_since: Option<StatusSince>,
+
     // Claude integration
     pub claude_session_id: Option<String>,
     pub claude_detected_at: Option<DateTime<Utc>>,
@@ -118,6 +225,76 @@ pub struct Instance {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub tmux_session_name: Option<String>,
 
+    /// Git branch this session was forked onto, when the fork created one.
+    /// Used as the diff target against the parent's branch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fork_branch: Option<String>,
+
+    /// `project_path` is a git worktree agent-hand created for this fork;
+    /// deleting the session offers to remove it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub fork_worktree: bool,
+
+    /// Position within its group under the manual tree sort; ties (and
+    /// never-moved sessions, all 0) fall back to title.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub order: i32,
+
+    /// No desktop notification when this session starts waiting.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub mute_notifications: bool,
+
+    /// Started when the TUI launches if its tmux session is missing.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub autostart: bool,
+
+    /// Shown at the top of the session list; `1`-`9` attach to the pinned
+    /// sessions in order.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+
+    /// Sessions forked from this one are hidden in the session list.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub forks_collapsed: bool,
+
+    /// Never stopped by the idle reaper (`[reaper]`).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub keep_alive: bool,
+
+    /// Shell command run in the pane before `command`; the agent only
+    /// starts if it succeeds.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub pre_command: String,
+
+    /// Typed into the pane the first time it is attached after a start.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub post_attach_command: String,
+
+    /// Windows created after the first one, which runs `command`. Empty
+    /// for a single-pane session.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub windows: Vec<WindowSpec>,
+
+    /// Index into `windows` of the window the agent runs in; see
+    /// [`Instance::agent_window`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary_window: Option<usize>,
+
+    /// Pane hash at which a stall was acknowledged; the session is not
+    /// shown as stalled again until its pane changes. See [`super::stall`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stall_acked: Option<u64>,
+
+    /// The session's own MCP config (see [`crate::claude::mcp`]), used in
+    /// place of the project's `.mcp.json` by tools that can take one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mcp_config: Option<PathBuf>,
+
+    /// `server:session` of the tmux session `agent-hand adopt` took this one
+    /// from without migrating it; the original keeps running there.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adopted_from: Option<String>,
+
     // Non-serialized fields
     #[serde(skip)]
     tmux_session: Option<Arc<TmuxSession>>,
@@ -125,13 +302,18 @@ pub struct Instance {
     /// Number of /dev/ptmx FDs held by this session's process tree (runtime-only).
     #[serde(skip)]
     pub ptmx_count: u32,
+
+    /// What the prompt asks while Waiting, e.g. "Do you want to create
+    /// main.rs?" (runtime-only).
+    #[serde(skip)]
+    pub waiting_reason: Option<String>,
 }
 
 impl Instance {
-    /// Create a new session instance
+    /// Create a new session instance, grouped by the `[grouping]` rules.
     pub fn new(title: String, project_path: PathBuf) -> Self {
         let id = generate_id();
-        let group_path = extract_group_path(&project_path);
+        let group_path = super::grouping::default_group(&project_path);
         let tmux_session_name = Some(TmuxManager::build_session_name(&title, &id));
 
         Self {
@@ -150,6 +332,7 @@ impl Instance {
             last_accessed_at: None,
             last_running_at: None,
             last_waiting_at: None,
+            status_since: None,
             claude_session_id: None,
             claude_detected_at: None,
             gemini_session_id: None,
@@ -160,8 +343,24 @@ impl Instance {
             sharing: None,
             relationship_id: None,
             tmux_session_name,
+            fork_branch: None,
+            fork_worktree: false,
+            order: 0,
+            mute_notifications: false,
+            autostart: false,
+            pinned: false,
+            forks_collapsed: false,
+            keep_alive: false,
+            pre_command: String::new(),
+            post_attach_command: String::new(),
+            windows: Vec::new(),
+            primary_window: None,
+            stall_acked: None,
+            mcp_config: None,
+            adopted_from: None,
             tmux_session: None,
             ptmx_count: 0,
+            waiting_reason: None,
         }
     }
 
@@ -187,6 +386,95 @@ impl Instance {
             .unwrap_or_else(|| TmuxManager::session_name_legacy(&self.id))
     }
 
+    /// The extra window status probing, the preview and attach use: the
+    /// `primary_window`, else the first window running this session's tool
+    /// when the first window does not. None means the session's first
+    /// window.
+    pub fn agent_window(&self) -> Option<&str> {
+        if let Some(i) = self.primary_window {
+            return self.windows.get(i).map(|w| w.name.as_str());
+        }
+        if self.tool == Tool::Shell || Tool::from_command(&self.command) == self.tool {
+            return None;
+        }
+        self.windows
+            .iter()
+            .find(|w| Tool::from_command(&w.command) == self.tool)
+            .map(|w| w.name.as_str())
+    }
+
+    /// tmux target for the agent's pane: the session name, or its
+    /// [`agent_window`](Self::agent_window).
+    pub fn tmux_target(&self) -> String {
+        match self.agent_window() {
+            Some(window) => TmuxManager::window_target(&self.tmux_name(), window),
+            None => self.tmux_name(),
+        }
+    }
+
+    /// What a new pane runs for `main` (the stored command, or a resume
+    /// command): `main`, given the session's own MCP config, behind the
+    /// pre-command, if there is one.
+    pub fn launch_command(&self, main: Option<&str>) -> Option<String> {
+        let main = main.map(|main| {
+            crate::claude::mcp::with_session_config(self.tool, main, self.mcp_config.as_deref())
+        });
+        super::launch::compose_command(&self.pre_command, main.as_deref())
+    }
+
+    /// The session's label badge; see [`label_badge`].
+    pub fn label_badge(&self) -> Option<String> {
+        label_badge(&self.label)
+    }
+
+    /// Whether the label is `label`, ignoring case and surrounding spaces.
+    pub fn has_label(&self, label: &str) -> bool {
+        let label = label.trim();
+        !label.is_empty() && self.label.trim().to_lowercase() == label.to_lowercase()
+    }
+
+    /// Set the status. `status_since` only moves when the status differs
+    /// from the one it was stamped for, so re-probing the same status keeps
+    /// the clock running.
+    pub fn set_status(&mut self, status: Status, now: DateTime<Utc>) {
+        if self.status_since.map(|s| s.status) != Some(status) {
+            self.status_since = Some(StatusSince { status, at: now });
+        }
+        self.status = status;
+    }
+
+    /// How long the session has been in its current status, if known.
+    pub fn time_in_status(&self, now: DateTime<Utc>) -> Option<chrono::Duration> {
+        let since = self.status_since.filter(|s| s.status == self.status)?;
+        Some((now - since.at).max(chrono::Duration::zero()))
+    }
+
+    /// How long the session has been waiting for input; None unless it
+    /// is Waiting. Falls back to `last_waiting_at`, which the CLI and
+    /// hooks also set, when the wait began before `status_since` existed.
+    pub fn waiting_duration(&self, now: DateTime<Utc>) -> Option<chrono::Duration> {
+        if self.status != Status::Waiting {
+            return None;
+        }
+        self.time_in_status(now).or_else(|| {
+            self.last_waiting_at
+                .map(|at| (now - at).max(chrono::Duration::zero()))
+        })
+    }
+
+    /// How long the session has been running; None unless it is Running.
+    pub fn running_duration(&self, now: DateTime<Utc>) -> Option<chrono::Duration> {
+        if self.status != Status::Running {
+            return None;
+        }
+        self.time_in_status(now)
+    }
+
+    /// Whether the project directory no longer exists (moved or deleted).
+    pub fn path_missing(&self) -> bool {
+        !self.project_path.is_dir()
+    }
+
     /// Add a tag (no-op if already present)
     pub fn add_tag(&mut self, tag: &str) {
         let tag = tag.trim().to_string();
@@ -229,12 +517,10 @@ impl Instance {
 
     /// Initialize tmux session wrapper
     pub fn init_tmux(&mut self, manager: Arc<TmuxManager>) {
-        let tmux_session = Arc::new(TmuxSession::new(
-            self.tmux_name(),
-            self.project_path.clone(),
-            self.tool,
-            manager,
-        ));
+        let tmux_session = Arc::new(
+            TmuxSession::new(self.tmux_name(), self.project_path.clone(), self.tool, manager)
+                .with_window(self.agent_window().map(str::to_string)),
+        );
         self.tmux_session = Some(tmux_session);
     }
 
@@ -245,9 +531,10 @@ impl Instance {
 
     /// Update status from tmux
     pub async fn update_status(&mut self) -> crate::Result<()> {
-        if let Some(tmux) = &self.tmux_session {
+        if let Some(tmux) = self.tmux_session.clone() {
             let status = tmux.update_status().await?;
-            self.status = status.into();
+            self.set_status(status.into(), Utc::now());
+            self.waiting_reason = tmux.waiting_reason();
         }
         Ok(())
     }
@@ -260,9 +547,10 @@ impl Instance {
             } else {
                 Some(self.command.as_str())
             };
-            tmux.start(cmd).await?;
+            tmux.start(self.launch_command(cmd).as_deref()).await?;
+            super::launch::open_windows(tmux.manager(), self).await;
             let _ = tmux.set_title(&self.title).await;
-            self.status = Status::Idle;
+            self.set_status(Status::Idle, Utc::now());
         }
         Ok(())
     }
@@ -271,7 +559,7 @@ impl Instance {
     pub async fn stop(&mut self) -> crate::Result<()> {
         if let Some(tmux) = &self.tmux_session {
             tmux.stop().await?;
-            self.status = Status::Error;
+            self.set_status(Status::Stopped, Utc::now());
         }
         Ok(())
     }
@@ -304,6 +592,38 @@ impl Instance {
         }
     }
 
+    /// The command that reopens CLI conversation `cli_session_id` in this
+    /// session's tool, e.g. `claude --resume <id>`. Permission-skipping flags
+    /// are kept when `config` or the original command asked for them. None
+    /// for tools that cannot resume.
+    pub fn resume_command(
+        &self,
+        cli_session_id: &str,
+        config: &crate::config::ConfigFile,
+    ) -> Option<String> {
+        let skip_perms = match self.tool {
+            Tool::Claude => {
+                config.claude.dangerously_skip_permissions
+                    || self.command.contains("--dangerously-skip-permissions")
+            }
+            Tool::Codex => config.codex.full_auto || self.command.contains("--full-auto"),
+            Tool::Gemini => {
+                config.gemini.yolo
+                    || self.command.contains("--yolo")
+                    || self.command.contains("-y")
+            }
+            _ => false,
+        };
+        let extra_flags = crate::tmux::resume_adapter::extract_preserve_flags(&self.command);
+        crate::tmux::resume_adapter::build_resume_command(
+            self.tool,
+            cli_session_id,
+            skip_perms,
+            &extra_flags,
+        )
+        .map(|spec| spec.command)
+    }
+
     /// Upgrade the tool type from Shell to an actual detected tool.
     /// Migrates any pending_cli_session_id into the tool-specific field.
     /// Returns true if the upgrade was performed.
@@ -356,42 +676,13 @@ impl Instance {
 }
 
 /// Generate a unique session ID
-fn generate_id() -> String {
+pub(super) fn generate_id() -> String {
     // Use first 12 chars of UUID for shorter IDs
     Uuid::new_v4().to_string()[..12].to_string()
 }
 
-/// Extract group path from project path
-/// E.g., /home/user/projects/work/app -> projects/work
-/// Returns `"default"` if the derived path would be empty.
-fn extract_group_path(path: &PathBuf) -> String {
-    let home = dirs::home_dir().unwrap_or_default();
-    let path_str = path.to_str().unwrap_or("");
-    let home_str = home.to_str().unwrap_or("");
-
-    let result = if path_str.starts_with(home_str) {
-        let relative = path_str.strip_prefix(home_str).unwrap_or("");
-        let parts: Vec<&str> = relative.trim_start_matches('/').split('/').collect();
-
-        // Use first 2 directory levels as group
-        if parts.len() >= 2 {
-            format!("{}/{}", parts[0], parts[1])
-        } else if parts.len() == 1 {
-            parts[0].to_string()
-        } else {
-            String::new()
-        }
-    } else {
-        // Not under home dir - use first directory
-        let parts: Vec<&str> = path_str.trim_start_matches('/').split('/').collect();
-        if !parts.is_empty() {
-            parts[0].to_string()
-        } else {
-            String::new()
-        }
-    };
-
-    if result.is_empty() { "default".to_string() } else { result }
+fn is_zero(n: &i32) -> bool {
+    *n == 0
 }
 
 #[cfg(test)]
@@ -399,17 +690,74 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_generate_id() {
-        let id = generate_id();
-        assert_eq!(id.len(), 12);
+    fn windows_parse_and_pick_the_agent_window() {
+        let dev: WindowSpec = "dev: npm run dev".parse().unwrap();
+        assert_eq!(dev.name, "dev");
+        assert_eq!(dev.command, "npm run dev");
+        assert!("dev".parse::<WindowSpec>().is_err());
+        assert!(":claude".parse::<WindowSpec>().is_err());
+        assert!("v1.2:claude".parse::<WindowSpec>().is_err());
+
+        let mut inst = Instance::new("api".into(), PathBuf::from("/tmp"));
+        inst.tool = Tool::Claude;
+        inst.windows = vec![dev, "agent:claude".parse().unwrap()];
+        assert_eq!(inst.agent_window(), Some("agent"));
+        assert_eq!(inst.tmux_target(), format!("{}:=agent", inst.tmux_name()));
+
+        inst.primary_window = Some(0);
+        assert_eq!(inst.agent_window(), Some("dev"));
+
+        inst.primary_window = None;
+        inst.command = "claude".into();
+        assert_eq!(inst.agent_window(), None);
+        assert_eq!(inst.tmux_target(), inst.tmux_name());
     }
 
     #[test]
-    fn test_extract_group_path() {
-        let path = PathBuf::from("/home/user/projects/work/app");
-        let group = extract_group_path(&path);
-        println!("Group: {}", group);
-        assert!(!group.is_empty());
+    fn status_clock_only_moves_on_a_change() {
+        let start = Utc::now();
+        let later = start + chrono::Duration::minutes(12);
+        let mut inst = Instance::new("api".into(), PathBuf::from("/tmp"));
+
+        inst.set_status(Status::Running, start);
+        inst.set_status(Status::Running, later);
+        assert_eq!(
+            inst.running_duration(later),
+            Some(chrono::Duration::minutes(12))
+        );
+        assert_eq!(inst.waiting_duration(later), None);
+
+        inst.set_status(Status::Waiting, later);
+        assert_eq!(inst.time_in_status(later), Some(chrono::Duration::zero()));
+        assert_eq!(inst.running_duration(later), None);
+
+        // A save writes Idle for a running session; the clock survives it.
+        inst.set_status(Status::Running, start);
+        inst.status = Status::Idle;
+        assert_eq!(inst.time_in_status(later), None);
+        inst.set_status(Status::Running, later);
+        assert_eq!(
+            inst.running_duration(later),
+            Some(chrono::Duration::minutes(12))
+        );
+    }
+
+    #[test]
+    fn waiting_duration_falls_back_to_last_waiting_at() {
+        let now = Utc::now();
+        let mut inst = Instance::new("api".into(), PathBuf::from("/tmp"));
+        inst.status = Status::Waiting;
+        assert_eq!(inst.waiting_duration(now), None);
+        inst.last_waiting_at = Some(now - chrono::Duration::seconds(45));
+        assert_eq!(inst.waiting_duration(now), Some(chrono::Duration::seconds(45)));
+        inst.set_status(Status::Waiting, now - chrono::Duration::seconds(5));
+        assert_eq!(inst.waiting_duration(now), Some(chrono::Duration::seconds(5)));
+    }
+
+    #[test]
+    fn test_generate_id() {
+        let id = generate_id();
+        assert_eq!(id.len(), 12);
     }
 
     #[test]
@@ -420,4 +768,118 @@ mod tests {
         instance.set_parent("parent-id".to_string());
         assert!(instance.is_sub_session());
     }
+
+    #[test]
+    fn label_badge_is_cut_to_width() {
+        let mut instance = Instance::new("test".to_string(), PathBuf::from("/tmp"));
+        assert_eq!(instance.label_badge(), None);
+        instance.label = "  ".into();
+        assert_eq!(instance.label_badge(), None);
+        instance.label = "blocked".into();
+        assert_eq!(instance.label_badge().as_deref(), Some("[blocked]"));
+        instance.label = "waiting on review".into();
+        assert_eq!(instance.label_badge().as_deref(), Some("[waiting o…]"));
+        instance.label = "评审中评审中评审中评审中".into();
+        assert_eq!(instance.label_badge().as_deref(), Some("[评审中评审中评审中…]"));
+    }
+
+    #[test]
+    fn has_label_ignores_case_and_spaces() {
+        let mut instance = Instance::new("test".to_string(), PathBuf::from("/tmp"));
+        assert!(!instance.has_label(""));
+        instance.label = " Urgent ".into();
+        assert!(instance.has_label("urgent"));
+        assert!(!instance.has_label("urg"));
+        assert!(!instance.has_label("  "));
+    }
+
+    #[test]
+    fn path_missing_when_directory_is_gone() {
+        let dir = tempfile::tempdir().unwrap();
+        let instance = Instance::new("test".to_string(), dir.path().to_path_buf());
+        assert!(!instance.path_missing());
+        drop(dir);
+        assert!(instance.path_missing());
+    }
+
+    #[test]
+    fn resume_command_keeps_permission_and_model_flags() {
+        let mut instance = Instance::new("test".to_string(), PathBuf::from("/tmp"));
+        instance.tool = Tool::Claude;
+        instance.command = "claude --dangerously-skip-permissions --model opus".to_string();
+        let config = crate::config::ConfigFile::default();
+        assert_eq!(
+            instance.resume_command("abc", &config).as_deref(),
+            Some("claude --dangerously-skip-permissions --model opus --resume abc")
+        );
+
+        instance.tool = Tool::Shell;
+        assert_eq!(instance.resume_command("abc", &config), None);
+    }
+
+    #[test]
+    fn error_status_is_written_as_error() {
+        let load = |s: &str| serde_json::from_str::<Status>(s).unwrap();
+        assert_eq!(serde_json::to_string(&Status::Error).unwrap(), "\"error\"");
+        assert_eq!(load("\"error\""), Status::Error);
+        assert!(serde_json::from_str::<Status>("\"failed\"").is_err());
+        assert_eq!(load("\"stopped\""), Status::Stopped);
+
+        assert_eq!(Status::Running.after_exit(), Status::Error);
+        assert_eq!(Status::Waiting.after_exit(), Status::Stopped);
+        assert_eq!(Status::Error.after_exit(), Status::Error);
+    }
+
+    #[test]
+    fn label_color_names_round_trip() {
+        for color in LabelColor::ALL {
+            assert_eq!(LabelColor::parse(color.name()), Some(color));
+            let json = serde_json::to_string(&color).unwrap();
+            assert_eq!(json, format!("\"{}\"", color.name()));
+        }
+        assert_eq!(LabelColor::parse(" RED "), Some(LabelColor::Red));
+        assert_eq!(LabelColor::parse("orange"), None);
+    }
+
+    #[test]
+    fn sessions_without_labels_load_with_defaults() {
+        // A session as written before labels existed.
+        let old = r#"{
+            "id": "abc123def456",
+            "title": "api",
+            "project_path": "/tmp/api",
+            "group_path": "work",
+            "parent_session_id": null,
+            "command": "claude",
+            "status": "idle",
+            "created_at": "2025-01-01T00:00:00Z",
+            "last_accessed_at": null,
+            "claude_session_id": null,
+            "claude_detected_at": null,
+            "gemini_session_id": null,
+            "gemini_detected_at": null
+        }"#;
+        let inst: Instance = serde_json::from_str(old).unwrap();
+        assert_eq!(inst.label, "");
+        assert_eq!(inst.label_color, LabelColor::Gray);
+        assert_eq!(inst.label_badge(), None);
+        assert!(!inst.autostart);
+        assert!(!inst.pinned);
+
+        let mut labeled = inst.clone();
+        labeled.label = "blocked".into();
+        labeled.label_color = LabelColor::Red;
+        let back: Instance = serde_json::from_str(&serde_json::to_string(&labeled).unwrap()).unwrap();
+        assert_eq!(back.label, "blocked");
+        assert_eq!(back.label_color, LabelColor::Red);
+    }
+
+    #[test]
+    fn autostart_is_only_written_when_set() {
+        let mut inst = Instance::new("api".to_string(), PathBuf::from("/tmp/api"));
+        assert!(!serde_json::to_string(&inst).unwrap().contains("autostart"));
+        inst.autostart = true;
+        let back: Instance = serde_json::from_str(&serde_json::to_string(&inst).unwrap()).unwrap();
+        assert!(back.autostart);
+    }
 }
@@ -0,0 +1,97 @@
+//! Per-profile journal of bulk operations.
+//!
+//! One JSON object per line in `profiles/<p>/journal.jsonl`, appended and
+//! never rewritten, so a later look can reconstruct what a command did to
+//! which sessions.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct JournalEntry {
+    pub at: DateTime<Utc>,
+    /// Dotted event name, e.g. `wrapup.sent` or `resume.started`.
+    pub event: String,
+    pub session_id: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub detail: String,
+}
+
+impl JournalEntry {
+    pub fn new(event: &str, session_id: &str, title: &str, detail: impl Into<String>) -> Self {
+        Self {
+            at: Utc::now(),
+            event: event.to_string(),
+            session_id: session_id.to_string(),
+            title: title.to_string(),
+            detail: detail.into(),
+        }
+    }
+}
+
+pub fn journal_path(profile_dir: &Path) -> PathBuf {
+    profile_dir.join("journal.jsonl")
+}
+
+/// Append entries in one write so concurrent writers do not interleave
+/// within a batch.
+pub fn append(profile_dir: &Path, entries: &[JournalEntry]) -> Result<()> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let mut buf = String::new();
+    for entry in entries {
+        buf.push_str(&serde_json::to_string(entry)?);
+        buf.push('\n');
+    }
+
+    std::fs::create_dir_all(profile_dir)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(journal_path(profile_dir))?;
+    file.write_all(buf.as_bytes())?;
+    Ok(())
+}
+
+/// Read every entry, skipping lines that fail to parse.
+pub fn read(profile_dir: &Path) -> Result<Vec<JournalEntry>> {
+    let path = journal_path(profile_dir);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = std::fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn append_accumulates_across_calls() {
+        let dir = tempfile::tempdir().unwrap();
+        append(dir.path(), &[JournalEntry::new("wrapup.sent", "a", "A", "")]).unwrap();
+        append(
+            dir.path(),
+            &[JournalEntry::new("wrapup.finished", "a", "A", "killed")],
+        )
+        .unwrap();
+
+        let entries = read(dir.path()).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].event, "wrapup.sent");
+        assert_eq!(entries[1].detail, "killed");
+    }
+}
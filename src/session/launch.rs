@@ -0,0 +1,88 @@
+//! Session start hooks: a shell command run in the pane before the agent
+//! starts, one typed into the pane the first time it is attached, and the
+//! session's extra windows.
+
+use super::Instance;
+use crate::tmux::{shell_quote, TmuxManager};
+
+/// Message printed when the pre-command fails; the agent is not started.
+const PRE_FAILED: &str = "agent-hand: pre-command failed (exit $?)";
+
+/// What a shell-only session runs after its pre-command.
+const LOGIN_SHELL: &str = "exec \"${SHELL:-/bin/sh}\" -l";
+
+/// Script for a new pane: `pre` first, then `main` only if it succeeded.
+/// Both run in one `sh -c`, whatever the user's shell is, so the guard
+/// below works under fish too and `main` sees what `pre` exported. With
+/// no `main` the script ends in a login shell, which keeps that
+/// environment. A failed `pre` prints an error and exits the command; the
+/// pane wrapper then drops to a login shell, so the error stays on screen.
+/// None when there is nothing to run.
+pub fn compose_command(pre: &str, main: Option<&str>) -> Option<String> {
+    let pre = pre.trim();
+    if pre.is_empty() {
+        return main.map(str::to_string);
+    }
+    let main = main.unwrap_or(LOGIN_SHELL);
+    // `pre` gets its own line so a trailing comment can't swallow the guard.
+    let script = format!("{{ {pre}\n}} || {{ echo \"{PRE_FAILED}\" >&2; exit 1; }}\n{main}");
+    Some(format!("sh -c {}", shell_quote(&script)))
+}
+
+/// Type the session's post-attach command into its pane, unless it was
+/// already sent since the tmux session was created. Failures are logged;
+/// attaching goes ahead regardless.
+pub async fn send_post_attach(manager: &TmuxManager, inst: &Instance) {
+    let command = inst.post_attach_command.trim();
+    if command.is_empty() {
+        return;
+    }
+    if let Err(e) = manager.send_post_attach(&inst.tmux_name(), command).await {
+        tracing::warn!("post-attach command for {} failed: {e}", inst.title);
+    }
+}
+
+/// Create the session's extra windows; call right after its tmux session
+/// was created. Failures are logged and the rest still get created.
+pub async fn open_windows(manager: &TmuxManager, inst: &Instance) {
+    if inst.windows.is_empty() {
+        return;
+    }
+    let session = inst.tmux_name();
+    let dir = inst.project_path.to_string_lossy();
+    for window in &inst.windows {
+        if let Err(e) = manager
+            .new_window(&session, &window.name, &dir, &window.command)
+            .await
+        {
+            tracing::warn!("window {} for {} failed: {e}", window.name, inst.title);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_pre_command_leaves_the_main_command_alone() {
+        assert_eq!(
+            compose_command("", Some("claude")).as_deref(),
+            Some("claude")
+        );
+        assert_eq!(compose_command("  ", None), None);
+    }
+
+    #[test]
+    fn pre_command_guards_the_main_command() {
+        assert_eq!(
+            compose_command(" nvm use # node ", Some("claude")).as_deref(),
+            Some(
+                "sh -c '{ nvm use # node\n} || { echo \"agent-hand: pre-command failed (exit $?)\" >&2; exit 1; }\nclaude'"
+            )
+        );
+        let shell_only = compose_command("source .env", None).unwrap();
+        assert!(shell_only.starts_with("sh -c '{ source .env\n} || {"));
+        assert!(shell_only.ends_with("exit 1; }\nexec \"${SHELL:-/bin/sh}\" -l'"));
+    }
+}
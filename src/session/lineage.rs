@@ -0,0 +1,151 @@
+//! Fork lineage: the parent/child tree formed by `parent_session_id`.
+
+use std::collections::{HashMap, HashSet};
+
+use super::Instance;
+
+/// One row of a rendered fork tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineageRow {
+    pub id: String,
+    pub depth: usize,
+    pub is_focus: bool,
+}
+
+/// Whether `id` belongs to any fork tree (has a known parent or children).
+pub fn has_lineage(instances: &[Instance], id: &str) -> bool {
+    instances.iter().any(|s| {
+        (s.id == id
+            && s.parent_session_id
+                .as_deref()
+                .is_some_and(|p| instances.iter().any(|o| o.id == p)))
+            || s.parent_session_id.as_deref() == Some(id)
+    })
+}
+
+/// Build the fork tree containing `focus_id`, rooted at its oldest known ancestor.
+///
+/// Rows are in depth-first order with children sorted by creation time.
+/// Parents that no longer exist end the upward walk, and cycles in the
+/// parent links are cut at the first repeated id. Returns an empty list when
+/// `focus_id` is unknown.
+pub fn build_lineage(instances: &[Instance], focus_id: &str) -> Vec<LineageRow> {
+    let by_id: HashMap<&str, &Instance> = instances.iter().map(|s| (s.id.as_str(), s)).collect();
+    if !by_id.contains_key(focus_id) {
+        return Vec::new();
+    }
+
+    // Walk up to the root.
+    let mut root = focus_id;
+    let mut seen: HashSet<&str> = HashSet::from([focus_id]);
+    while let Some(parent) = by_id
+        .get(root)
+        .and_then(|s| s.parent_session_id.as_deref())
+        .filter(|p| by_id.contains_key(p))
+    {
+        if !seen.insert(parent) {
+            break;
+        }
+        root = parent;
+    }
+
+    let mut children: HashMap<&str, Vec<&Instance>> = HashMap::new();
+    for s in instances {
+        if let Some(parent) = s.parent_session_id.as_deref() {
+            if parent != s.id {
+                children.entry(parent).or_default().push(s);
+            }
+        }
+    }
+    for list in children.values_mut() {
+        list.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.id.cmp(&b.id)));
+    }
+
+    let mut rows = Vec::new();
+    let mut visited: HashSet<&str> = HashSet::new();
+    let mut stack: Vec<(&str, usize)> = vec![(root, 0)];
+    while let Some((id, depth)) = stack.pop() {
+        if !visited.insert(id) {
+            continue;
+        }
+        rows.push(LineageRow {
+            id: id.to_string(),
+            depth,
+            is_focus: id == focus_id,
+        });
+        if let Some(kids) = children.get(id) {
+            for kid in kids.iter().rev() {
+                stack.push((kid.id.as_str(), depth + 1));
+            }
+        }
+    }
+
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use std::path::PathBuf;
+
+    fn inst(id: &str, parent: Option<&str>, age_secs: i64) -> Instance {
+        let mut s = Instance::new(id.to_string(), PathBuf::from("/tmp"));
+        s.id = id.to_string();
+        s.parent_session_id = parent.map(str::to_string);
+        s.created_at = Utc::now() - Duration::seconds(age_secs);
+        s
+    }
+
+    fn ids(rows: &[LineageRow]) -> Vec<(&str, usize)> {
+        rows.iter().map(|r| (r.id.as_str(), r.depth)).collect()
+    }
+
+    #[test]
+    fn builds_tree_from_root_with_ordered_children() {
+        let all = vec![
+            inst("root", None, 100),
+            inst("b", Some("root"), 10),
+            inst("a", Some("root"), 50),
+            inst("a1", Some("a"), 5),
+            inst("unrelated", None, 1),
+        ];
+        let rows = build_lineage(&all, "a1");
+        assert_eq!(ids(&rows), vec![("root", 0), ("a", 1), ("a1", 2), ("b", 1)]);
+        assert!(rows.iter().find(|r| r.id == "a1").unwrap().is_focus);
+        assert_eq!(rows.iter().filter(|r| r.is_focus).count(), 1);
+    }
+
+    #[test]
+    fn missing_parent_becomes_root() {
+        let all = vec![inst("child", Some("deleted"), 10), inst("grand", Some("child"), 5)];
+        let rows = build_lineage(&all, "grand");
+        assert_eq!(ids(&rows), vec![("child", 0), ("grand", 1)]);
+    }
+
+    #[test]
+    fn cycles_terminate() {
+        let all = vec![inst("x", Some("y"), 10), inst("y", Some("x"), 5), inst("self", Some("self"), 1)];
+        let rows = build_lineage(&all, "x");
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().any(|r| r.id == "x"));
+        assert!(rows.iter().any(|r| r.id == "y"));
+
+        let rows = build_lineage(&all, "self");
+        assert_eq!(ids(&rows), vec![("self", 0)]);
+    }
+
+    #[test]
+    fn unknown_focus_is_empty() {
+        let all = vec![inst("a", None, 1)];
+        assert!(build_lineage(&all, "nope").is_empty());
+    }
+
+    #[test]
+    fn lineage_presence() {
+        let all = vec![inst("p", None, 10), inst("c", Some("p"), 5), inst("orphan", Some("gone"), 1)];
+        assert!(has_lineage(&all, "p"));
+        assert!(has_lineage(&all, "c"));
+        assert!(!has_lineage(&all, "orphan"));
+    }
+}
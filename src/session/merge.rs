@@ -0,0 +1,222 @@
+//! Three-way merge of `sessions.json` records.
+//!
+//! The TUI and CLI commands each load, modify and save the same file. When
+//! another process saved after our last load, our records are merged onto
+//! its file with what we last read as the common base: records added or
+//! removed on either side stay that way, and a field changed on one side
+//! only takes that side's value. Both sides changing the same field to
+//! different values is a conflict, except for fields that churn with
+//! activity, where our value wins.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::error::{Error, Result};
+
+/// Fields where our value wins over a concurrent change.
+const VOLATILE_FIELDS: [&str; 6] = [
+    "status",
+    "last_accessed_at",
+    "last_running_at",
+    "last_waiting_at",
+    "status_since",
+    "expanded",
+];
+
+/// `items` as JSON, keyed by `key`.
+pub(super) fn keyed<T: Serialize>(
+    items: &[T],
+    key: impl Fn(&T) -> &str,
+) -> Result<BTreeMap<String, Value>> {
+    items
+        .iter()
+        .map(|item| Ok((key(item).to_string(), serde_json::to_value(item)?)))
+        .collect()
+}
+
+/// Merge `ours` onto `theirs` given the `base` both started from. Our order
+/// is kept; records only they added follow in their order. `kind` names
+/// the records in the conflict error.
+pub(super) fn merge_records<T: Serialize + DeserializeOwned>(
+    kind: &str,
+    base: &BTreeMap<String, Value>,
+    ours: &[T],
+    theirs: &[T],
+    key: impl Fn(&T) -> &str,
+) -> Result<Vec<T>> {
+    let theirs_by_key = keyed(theirs, &key)?;
+    let ours_keys: HashSet<&str> = ours.iter().map(&key).collect();
+    let mut merged = Vec::new();
+    let mut conflicts = Vec::new();
+
+    for item in ours {
+        let k = key(item);
+        let o = serde_json::to_value(item)?;
+        match (base.get(k), theirs_by_key.get(k)) {
+            (b, Some(t)) => match merge_fields(b, &o, t) {
+                Ok(v) => merged.push(v),
+                Err(fields) => conflicts.extend(fields.iter().map(|f| format!("{kind} {k}: {f}"))),
+            },
+            (None, None) => merged.push(o),
+            // Removed elsewhere; only a conflict if we edited it meanwhile.
+            (Some(b), None) => {
+                if changed(b, &o) {
+                    conflicts.push(format!("{kind} {k}: edited here, removed elsewhere"));
+                }
+            }
+        }
+    }
+    for item in theirs {
+        let k = key(item);
+        if ours_keys.contains(k) {
+            continue;
+        }
+        let t = &theirs_by_key[k];
+        match base.get(k) {
+            None => merged.push(t.clone()),
+            Some(b) => {
+                if changed(b, t) {
+                    conflicts.push(format!("{kind} {k}: removed here, edited elsewhere"));
+                }
+            }
+        }
+    }
+
+    if !conflicts.is_empty() {
+        return Err(Error::Conflict(format!(
+            "another agent-hand process changed the same data ({}); reload and try again",
+            conflicts.join(", ")
+        )));
+    }
+    merged
+        .into_iter()
+        .map(|v| Ok(serde_json::from_value(v)?))
+        .collect()
+}
+
+/// Merge one record field by field. Returns the conflicting fields.
+fn merge_fields(
+    base: Option<&Value>,
+    ours: &Value,
+    theirs: &Value,
+) -> std::result::Result<Value, Vec<String>> {
+    if ours == theirs {
+        return Ok(ours.clone());
+    }
+    let (Value::Object(o), Value::Object(t)) = (ours, theirs) else {
+        return match base {
+            Some(b) if b == ours => Ok(theirs.clone()),
+            Some(b) if b == theirs => Ok(ours.clone()),
+            _ => Err(vec!["value".to_string()]),
+        };
+    };
+    let empty = Map::new();
+    let b = base.and_then(Value::as_object).unwrap_or(&empty);
+
+    let mut merged = Map::new();
+    let mut conflicts = Vec::new();
+    let fields: BTreeSet<&String> = o.keys().chain(t.keys()).collect();
+    for field in fields {
+        let (bv, ov, tv) = (b.get(field), o.get(field), t.get(field));
+        let pick = if ov == tv || tv == bv {
+            ov
+        } else if ov == bv {
+            tv
+        } else if VOLATILE_FIELDS.contains(&field.as_str()) {
+            ov
+        } else {
+            conflicts.push(field.clone());
+            continue;
+        };
+        if let Some(v) = pick {
+            merged.insert(field.clone(), v.clone());
+        }
+    }
+    if conflicts.is_empty() {
+        Ok(Value::Object(merged))
+    } else {
+        Err(conflicts)
+    }
+}
+
+/// Whether `value` differs from `base` outside the volatile fields.
+fn changed(base: &Value, value: &Value) -> bool {
+    match (base, value) {
+        (Value::Object(b), Value::Object(v)) => b
+            .keys()
+            .chain(v.keys())
+            .filter(|k| !VOLATILE_FIELDS.contains(&k.as_str()))
+            .any(|k| b.get(k) != v.get(k)),
+        _ => base != value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Rec {
+        id: String,
+        title: String,
+        #[serde(default)]
+        status: u8,
+    }
+
+    fn rec(id: &str, title: &str, status: u8) -> Rec {
+        Rec {
+            id: id.into(),
+            title: title.into(),
+            status,
+        }
+    }
+
+    fn merge(base: &[Rec], ours: &[Rec], theirs: &[Rec]) -> Result<Vec<Rec>> {
+        let base = keyed(base, |r| r.id.as_str()).unwrap();
+        merge_records("session", &base, ours, theirs, |r| r.id.as_str())
+    }
+
+    #[test]
+    fn additions_and_removals_from_both_sides_are_kept() {
+        let base = [rec("a", "A", 0), rec("b", "B", 0)];
+        // We removed b and added c; they removed a and added d.
+        let ours = [rec("a", "A", 0), rec("c", "C", 0)];
+        let theirs = [rec("b", "B", 0), rec("d", "D", 0)];
+        let merged = merge(&base, &ours, &theirs).unwrap();
+        assert_eq!(merged, [rec("c", "C", 0), rec("d", "D", 0)]);
+    }
+
+    #[test]
+    fn edits_to_different_fields_merge() {
+        let base = [rec("a", "A", 0)];
+        let ours = [rec("a", "A", 2)];
+        let theirs = [rec("a", "renamed", 0)];
+        assert_eq!(
+            merge(&base, &ours, &theirs).unwrap(),
+            [rec("a", "renamed", 2)]
+        );
+    }
+
+    #[test]
+    fn same_field_edits_conflict_unless_volatile() {
+        let base = [rec("a", "A", 0)];
+        let err = merge(&base, &[rec("a", "mine", 0)], &[rec("a", "theirs", 0)]).unwrap_err();
+        assert!(err.to_string().contains("session a: title"), "{err}");
+
+        let merged = merge(&base, &[rec("a", "A", 1)], &[rec("a", "A", 2)]).unwrap();
+        assert_eq!(merged, [rec("a", "A", 1)]);
+    }
+
+    #[test]
+    fn edit_against_removal_conflicts() {
+        let base = [rec("a", "A", 0)];
+        assert!(merge(&base, &[rec("a", "edited", 0)], &[]).is_err());
+        assert!(merge(&base, &[], &[rec("a", "edited", 0)]).is_err());
+        // A status change alone does not keep a removed record alive.
+        assert!(merge(&base, &[rec("a", "A", 3)], &[]).unwrap().is_empty());
+    }
+}
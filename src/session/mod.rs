@@ -1,13 +1,33 @@
 pub mod context;
+pub mod diff;
+pub mod forward;
 mod groups;
+pub mod grouping;
+pub mod groups_ops;
+pub mod heartbeat;
 mod instance;
+pub mod journal;
+pub mod launch;
+pub mod lineage;
+mod merge;
+pub mod reaper;
+pub mod reconcile;
 pub mod relationships;
+pub mod repo;
+pub mod snapshot;
+pub mod stall;
+pub mod status_engine;
 mod storage;
+pub mod templates;
+pub mod titles;
+pub mod transfer;
+pub mod waiting;
+pub mod wrapup;
 
-pub use groups::{GroupData, GroupTree};
-pub use instance::{Instance, LabelColor, Status};
+pub use groups::{notes_from_input, notes_to_input, rename_sessions_group, GroupData, GroupTree};
+pub use instance::{label_badge, Instance, LabelColor, Status, StatusSince, WindowSpec};
 pub use relationships::{RelationType, Relationship};
-pub use storage::{Storage, StorageData};
+pub use storage::{Storage, StorageData, MAX_BACKUP_GENERATIONS};
 
 /// Default profile name
 pub const DEFAULT_PROFILE: &str = "default";
@@ -0,0 +1,157 @@
+//! Idle shutdown: kill the tmux sessions nobody has used in a while, to
+//! free the memory their shells and agent processes hold.
+//!
+//! Only the tmux session goes; the [`Instance`] stays and can be started or
+//! resumed like any stopped session. Sessions marked `keep_alive`,
+//! `autostart` or `pinned` are never reaped.
+
+use std::time::Duration;
+
+use super::journal::JournalEntry;
+use super::Instance;
+use crate::tmux::TmuxManager;
+
+/// Hours without activity before a session is reaped, when `[reaper]
+/// idle_hours` is not set.
+pub const DEFAULT_IDLE_HOURS: u64 = 24;
+
+/// A running session idle for longer than the threshold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReapCandidate {
+    pub id: String,
+    pub title: String,
+    pub tmux_name: String,
+    /// Time since tmux last saw activity in the session.
+    pub idle: Duration,
+}
+
+impl ReapCandidate {
+    pub fn journal_entry(&self, error: Option<&str>) -> JournalEntry {
+        let mut detail = format!("idle {}h", self.idle.as_secs() / 3600);
+        if let Some(err) = error {
+            detail.push_str("; ");
+            detail.push_str(err);
+        }
+        let event = if error.is_some() {
+            "reap.failed"
+        } else {
+            "reap.killed"
+        };
+        JournalEntry::new(event, &self.id, &self.title, detail)
+    }
+}
+
+/// Sessions the reaper leaves alone however long they sit idle.
+pub fn is_exempt(inst: &Instance) -> bool {
+    inst.keep_alive || inst.autostart || inst.pinned
+}
+
+/// Time since `activity`, tmux's `session_activity` in unix seconds.
+pub fn idle_since(activity: i64, now: i64) -> Duration {
+    Duration::from_secs(now.saturating_sub(activity).max(0) as u64)
+}
+
+/// Running, non-exempt sessions without activity for at least
+/// `idle_after`, longest idle first. `activity` returns a tmux session's
+/// `session_activity`, None when it is not running. Whether each one is
+/// actually Idle is left to the caller.
+pub fn stale_sessions(
+    instances: &[Instance],
+    activity: impl Fn(&str) -> Option<i64>,
+    now: i64,
+    idle_after: Duration,
+) -> Vec<ReapCandidate> {
+    let mut stale: Vec<ReapCandidate> = instances
+        .iter()
+        .filter(|inst| !is_exempt(inst))
+        .filter_map(|inst| {
+            let tmux_name = inst.tmux_name();
+            // 0 means tmux reported no usable timestamp.
+            let at = activity(&tmux_name).filter(|&at| at > 0)?;
+            let idle = idle_since(at, now);
+            (idle >= idle_after).then(|| ReapCandidate {
+                id: inst.id.clone(),
+                title: inst.title.clone(),
+                tmux_name,
+                idle,
+            })
+        })
+        .collect();
+    stale.sort_by_key(|s| std::cmp::Reverse(s.idle));
+    stale
+}
+
+/// Kill each candidate's tmux session, pairing it with the error when
+/// that failed.
+pub async fn reap(
+    manager: &TmuxManager,
+    candidates: Vec<ReapCandidate>,
+) -> Vec<(ReapCandidate, Option<String>)> {
+    let mut results = Vec::with_capacity(candidates.len());
+    for candidate in candidates {
+        let error = manager
+            .kill_session(&candidate.tmux_name)
+            .await
+            .err()
+            .map(|e| e.to_string());
+        results.push((candidate, error));
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    const HOUR: i64 = 3600;
+
+    fn session(title: &str) -> Instance {
+        Instance::new(title.to_string(), PathBuf::from("/tmp"))
+    }
+
+    #[test]
+    fn only_old_unflagged_running_sessions_are_stale() {
+        let now = 100 * HOUR;
+        let old = session("old");
+        let older = session("older");
+        let fresh = session("fresh");
+        let stopped = session("stopped");
+        let mut kept = session("kept");
+        kept.keep_alive = true;
+        let mut pinned = session("pinned");
+        pinned.pinned = true;
+        let mut autostart = session("autostart");
+        autostart.autostart = true;
+
+        let activity: HashMap<String, i64> = [
+            (&old, now - 30 * HOUR),
+            (&older, now - 50 * HOUR),
+            (&fresh, now - HOUR),
+            (&kept, now - 50 * HOUR),
+            (&pinned, now - 50 * HOUR),
+            (&autostart, now - 50 * HOUR),
+        ]
+        .into_iter()
+        .map(|(inst, at)| (inst.tmux_name(), at))
+        .collect();
+        let instances = [old, older, fresh, stopped, kept, pinned, autostart];
+
+        let stale = stale_sessions(
+            &instances,
+            |name| activity.get(name).copied(),
+            now,
+            Duration::from_secs(24 * HOUR as u64),
+        );
+        let titles: Vec<&str> = stale.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, ["older", "old"]);
+        assert_eq!(stale[1].idle, Duration::from_secs(30 * HOUR as u64));
+    }
+
+    #[test]
+    fn activity_in_the_future_counts_as_no_idle_time() {
+        assert_eq!(idle_since(10, 5), Duration::ZERO);
+        assert_eq!(idle_since(5, 10), Duration::from_secs(5));
+    }
+}
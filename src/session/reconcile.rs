@@ -0,0 +1,142 @@
+//! Persisted statuses versus the live tmux server.
+//!
+//! `sessions.json` records each session's status as of the last save, which
+//! means nothing after a reboot. At load time sessions whose tmux session is
+//! gone are marked Stopped, unless they had failed; the rest keep their
+//! persisted status but are marked stale until a live probe reports on them.
+
+use std::collections::{HashMap, HashSet};
+
+use super::{Instance, Status};
+
+/// Correct persisted statuses against the cached tmux session list and
+/// return the ids whose status is unconfirmed.
+pub fn reconcile(instances: &mut [Instance], is_live: impl Fn(&str) -> bool) -> StaleStatuses {
+    let mut stale = HashSet::new();
+    for inst in instances {
+        if is_live(&inst.tmux_name()) {
+            stale.insert(inst.id.clone());
+        } else if inst.status != Status::Error {
+            inst.status = Status::Stopped;
+        }
+    }
+    StaleStatuses(stale)
+}
+
+/// Sessions showing a persisted status that no probe has confirmed yet.
+#[derive(Debug, Default)]
+pub struct StaleStatuses(HashSet<String>);
+
+impl StaleStatuses {
+    pub fn is_stale(&self, id: &str) -> bool {
+        self.0.contains(id)
+    }
+
+    /// Record a live probe of `id`, clearing its stale mark. Returns the
+    /// status the probe should treat as previous: Idle while the shown one
+    /// was only persisted, so a stored Running is not mistaken for a run
+    /// that just finished.
+    pub fn confirm(&mut self, id: &str, shown: Status) -> Status {
+        if self.0.remove(id) {
+            Status::Idle
+        } else {
+            shown
+        }
+    }
+}
+
+/// Running and Starting describe a moment, not a state worth restoring.
+fn is_transient(status: Status) -> bool {
+    matches!(status, Status::Running | Status::Starting)
+}
+
+/// Status to write for a session: settled statuses are written as they
+/// are, transient ones keep whatever was last written (Idle if nothing).
+pub fn persisted_status(last_written: Option<Status>, current: Status) -> Status {
+    if is_transient(current) {
+        last_written.unwrap_or(Status::Idle)
+    } else {
+        current
+    }
+}
+
+/// Apply [`persisted_status`] to every instance about to be saved.
+pub fn statuses_to_persist(
+    instances: &mut [Instance],
+    last_written: &HashMap<String, Status>,
+) {
+    for inst in instances {
+        inst.status = persisted_status(last_written.get(&inst.id).copied(), inst.status);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn session(title: &str, status: Status) -> Instance {
+        let mut inst = Instance::new(title.into(), PathBuf::from("/tmp"));
+        inst.status = status;
+        inst
+    }
+
+    #[test]
+    fn missing_sessions_are_stopped_and_live_ones_are_stale() {
+        let mut sessions = vec![
+            session("gone-running", Status::Running),
+            session("gone-waiting", Status::Waiting),
+            session("live-waiting", Status::Waiting),
+            session("live-idle", Status::Idle),
+        ];
+        let live: HashSet<String> = sessions[2..].iter().map(|s| s.tmux_name()).collect();
+
+        let stale = reconcile(&mut sessions, |name| live.contains(name));
+
+        let statuses: Vec<Status> = sessions.iter().map(|s| s.status).collect();
+        assert_eq!(
+            statuses,
+            [Status::Stopped, Status::Stopped, Status::Waiting, Status::Idle]
+        );
+        let flags: Vec<bool> = sessions.iter().map(|s| stale.is_stale(&s.id)).collect();
+        assert_eq!(flags, [false, false, true, true]);
+    }
+
+    #[test]
+    fn no_tmux_server_means_nothing_is_live() {
+        let mut sessions = vec![session("a", Status::Running), session("b", Status::Error)];
+        let stale = reconcile(&mut sessions, |_| false);
+        // A failure is still worth showing after a restart.
+        assert_eq!(sessions[0].status, Status::Stopped);
+        assert_eq!(sessions[1].status, Status::Error);
+        assert!(sessions.iter().all(|s| !stale.is_stale(&s.id)));
+    }
+
+    #[test]
+    fn first_probe_clears_only_its_session() {
+        let mut sessions = vec![session("a", Status::Running), session("b", Status::Waiting)];
+        let live: HashSet<String> = sessions.iter().map(|s| s.tmux_name()).collect();
+        let mut stale = reconcile(&mut sessions, |name| live.contains(name));
+        let (a, b) = (sessions[0].id.clone(), sessions[1].id.clone());
+
+        // The persisted Running is not reported as the previous status.
+        assert_eq!(stale.confirm(&a, Status::Running), Status::Idle);
+        assert!(!stale.is_stale(&a));
+        assert!(stale.is_stale(&b));
+
+        // Later probes see the real previous status.
+        assert_eq!(stale.confirm(&a, Status::Running), Status::Running);
+        assert_eq!(stale.confirm(&b, Status::Waiting), Status::Idle);
+        assert!(!stale.is_stale(&b));
+    }
+
+    #[test]
+    fn transient_statuses_are_not_persisted() {
+        assert_eq!(persisted_status(None, Status::Running), Status::Idle);
+        assert_eq!(persisted_status(Some(Status::Waiting), Status::Running), Status::Waiting);
+        assert_eq!(persisted_status(Some(Status::Idle), Status::Starting), Status::Idle);
+        assert_eq!(persisted_status(Some(Status::Idle), Status::Waiting), Status::Waiting);
+        assert_eq!(persisted_status(Some(Status::Waiting), Status::Error), Status::Error);
+        assert_eq!(persisted_status(None, Status::Idle), Status::Idle);
+    }
+}
@@ -0,0 +1,168 @@
+//! Which sessions work on the same repository.
+//!
+//! Clones are separate repos, but a main checkout and its worktrees share
+//! one common git dir; that directory is the repo identity. Resolution runs
+//! git and is cached by the caller; everything else here is pure over the
+//! resolved map.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use futures::StreamExt;
+
+/// Concurrent `git rev-parse` calls during one resolution pass.
+const MAX_CONCURRENT: usize = 4;
+/// A path whose git call takes longer than this is treated as not a repo.
+const RESOLVE_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub const DEFAULT_SIBLING_MESSAGE: &str =
+    "New commits landed on main in a sibling checkout of this repo. Pull or rebase before continuing.";
+
+/// Key for a project path in the resolution cache. Project paths are
+/// canonicalized when sessions are created, so this only normalizes
+/// trailing separators and `.` components.
+pub fn path_key(path: &Path) -> PathBuf {
+    path.components().collect()
+}
+
+/// Display name for a repository, from its common git dir:
+/// `/src/api/.git` and the bare `/src/api.git` are both `api`.
+pub fn repo_name(common_dir: &Path) -> String {
+    let name = if common_dir.file_name().is_some_and(|n| n == ".git") {
+        common_dir.parent().and_then(|p| p.file_name())
+    } else {
+        common_dir.file_name()
+    };
+    let name = name.map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    name.strip_suffix(".git").map(str::to_string).unwrap_or(name)
+}
+
+/// Shared-repo marker for one session row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoBadge {
+    pub repo: PathBuf,
+    pub name: String,
+    /// Sessions in this repo, including this one.
+    pub count: usize,
+}
+
+impl RepoBadge {
+    pub fn label(&self) -> String {
+        format!("⎇ {}×{}", self.name, self.count)
+    }
+}
+
+/// Badges for sessions whose repo is shared with at least one other session.
+/// `sessions` yields `(session id, path key)`; `resolved` maps path keys to
+/// common dirs (`None` for non-repos). Unresolved paths get no badge.
+pub fn repo_badges<'a>(
+    sessions: impl IntoIterator<Item = (&'a str, PathBuf)>,
+    resolved: &HashMap<PathBuf, Option<PathBuf>>,
+) -> HashMap<String, RepoBadge> {
+    let mut by_repo: HashMap<&Path, Vec<&'a str>> = HashMap::new();
+    for (id, key) in sessions {
+        if let Some(Some(repo)) = resolved.get(&key) {
+            by_repo.entry(repo.as_path()).or_default().push(id);
+        }
+    }
+
+    let mut badges = HashMap::new();
+    for (repo, ids) in by_repo.into_iter().filter(|(_, ids)| ids.len() > 1) {
+        let badge = RepoBadge {
+            repo: repo.to_path_buf(),
+            name: repo_name(repo),
+            count: ids.len(),
+        };
+        for id in ids {
+            badges.insert(id.to_string(), badge.clone());
+        }
+    }
+    badges
+}
+
+/// Other sessions in the same repo as `session_id`, sorted for stable output.
+pub fn siblings(session_id: &str, badges: &HashMap<String, RepoBadge>) -> Vec<String> {
+    let Some(own) = badges.get(session_id) else {
+        return Vec::new();
+    };
+    let mut ids: Vec<String> = badges
+        .iter()
+        .filter(|(id, b)| id.as_str() != session_id && b.repo == own.repo)
+        .map(|(id, _)| id.clone())
+        .collect();
+    ids.sort();
+    ids
+}
+
+/// Resolve common git dirs for `keys`, a few at a time. Failures, timeouts
+/// and non-repos all resolve to `None` so they are not retried.
+pub async fn resolve(keys: Vec<PathBuf>) -> HashMap<PathBuf, Option<PathBuf>> {
+    futures::stream::iter(keys)
+        .map(|key| async move {
+            let dir = tokio::time::timeout(RESOLVE_TIMEOUT, crate::git::common_dir(&key))
+                .await
+                .ok()
+                .and_then(|r| r.ok());
+            (key, dir)
+        })
+        .buffer_unordered(MAX_CONCURRENT)
+        .collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolved(pairs: &[(&str, Option<&str>)]) -> HashMap<PathBuf, Option<PathBuf>> {
+        pairs
+            .iter()
+            .map(|(k, v)| (PathBuf::from(k), v.map(PathBuf::from)))
+            .collect()
+    }
+
+    fn sessions<'a>(pairs: &'a [(&'a str, &'a str)]) -> Vec<(&'a str, PathBuf)> {
+        pairs.iter().map(|(id, p)| (*id, path_key(Path::new(p)))).collect()
+    }
+
+    #[test]
+    fn worktrees_and_main_checkout_share_a_badge() {
+        let map = resolved(&[
+            ("/src/api", Some("/src/api/.git")),
+            ("/src/api-wt/feature", Some("/src/api/.git")),
+            ("/src/web", Some("/src/web/.git")),
+        ]);
+        let badges = repo_badges(
+            sessions(&[("a", "/src/api"), ("b", "/src/api-wt/feature/"), ("c", "/src/web")]),
+            &map,
+        );
+        assert_eq!(badges.len(), 2);
+        assert_eq!(badges["a"].label(), "⎇ api×2");
+        assert_eq!(badges["b"], badges["a"]);
+        assert!(!badges.contains_key("c"));
+    }
+
+    #[test]
+    fn non_repos_and_unresolved_paths_get_no_badge() {
+        let map = resolved(&[("/tmp/x", None), ("/tmp/y", None)]);
+        let badges = repo_badges(sessions(&[("a", "/tmp/x"), ("b", "/tmp/y"), ("c", "/tmp/z")]), &map);
+        assert!(badges.is_empty());
+    }
+
+    #[test]
+    fn sessions_in_one_directory_count_separately() {
+        let map = resolved(&[("/src/api", Some("/src/api/.git"))]);
+        let badges = repo_badges(sessions(&[("a", "/src/api"), ("b", "/src/api"), ("c", "/src/api")]), &map);
+        assert_eq!(badges["c"].count, 3);
+        assert_eq!(siblings("a", &badges), vec!["b".to_string(), "c".to_string()]);
+        assert!(siblings("zzz", &badges).is_empty());
+    }
+
+    #[test]
+    fn repo_names_cover_bare_and_nested_layouts() {
+        assert_eq!(repo_name(Path::new("/src/api/.git")), "api");
+        assert_eq!(repo_name(Path::new("/srv/git/api.git")), "api");
+        assert_eq!(repo_name(Path::new("/src/app/.git/modules/lib")), "lib");
+    }
+}
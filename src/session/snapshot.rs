@@ -0,0 +1,354 @@
+//! Point-in-time snapshots of a profile's persisted state.
+//!
+//! Each snapshot lives in `profiles/<p>/snapshots/<id>/` and holds copies of
+//! the profile's state files plus a `manifest.json` describing them.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+
+use super::diff::{diff_instances, SessionDiff};
+use super::{Instance, StorageData};
+use crate::error::{Error, Result};
+
+/// Profile files captured by a snapshot. Groups and relationships are stored
+/// inside `sessions.json`; extend this list when new per-profile state files
+/// are introduced.
+pub const SNAPSHOT_FILES: &[&str] = &["sessions.json"];
+
+/// Unlabeled snapshots kept by the retention pass unless configured otherwise.
+pub const DEFAULT_SNAPSHOT_KEEP: usize = 10;
+
+const SNAPSHOTS_DIR: &str = "snapshots";
+const MANIFEST_FILE: &str = "manifest.json";
+const MAX_LABEL_SLUG_LEN: usize = 40;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotFile {
+    pub name: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub files: Vec<SnapshotFile>,
+    #[serde(default)]
+    pub session_count: usize,
+    /// Taken automatically (e.g. before a restore) rather than by the user.
+    #[serde(default)]
+    pub automatic: bool,
+}
+
+impl SnapshotManifest {
+    pub fn total_size(&self) -> u64 {
+        self.files.iter().map(|f| f.size).sum()
+    }
+}
+
+/// Outcome of [`restore_snapshot`].
+#[derive(Debug, Clone)]
+pub struct RestoreReport {
+    pub restored: SnapshotManifest,
+    /// Snapshot of the state that was replaced; `None` for dry runs.
+    pub safety: Option<SnapshotManifest>,
+    /// Changes from the current state to the restored one.
+    pub diff: SessionDiff,
+}
+
+pub fn snapshots_dir(profile_dir: &Path) -> PathBuf {
+    profile_dir.join(SNAPSHOTS_DIR)
+}
+
+fn slugify(label: &str) -> String {
+    let mut slug = String::new();
+    for c in label.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_matches('-');
+    slug.chars().take(MAX_LABEL_SLUG_LEN).collect::<String>().trim_end_matches('-').to_string()
+}
+
+fn load_instances(path: &Path) -> Result<Vec<Instance>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path)?;
+    let data: StorageData = serde_json::from_str(&content)?;
+    Ok(data.instances)
+}
+
+/// Copy the profile's state files into a new snapshot directory.
+pub fn create_snapshot(
+    profile_dir: &Path,
+    label: Option<&str>,
+    automatic: bool,
+) -> Result<SnapshotManifest> {
+    let label = label.map(str::trim).filter(|l| !l.is_empty());
+    let created_at = Utc::now();
+
+    let mut base_id = created_at.format("%Y%m%d-%H%M%S-%3f").to_string();
+    if let Some(slug) = label.map(slugify).filter(|s| !s.is_empty()) {
+        base_id = format!("{base_id}-{slug}");
+    }
+
+    let root = snapshots_dir(profile_dir);
+    fs::create_dir_all(&root)?;
+    let mut id = base_id.clone();
+    let mut n = 1;
+    while root.join(&id).exists() {
+        n += 1;
+        id = format!("{base_id}-{n}");
+    }
+    let dir = root.join(&id);
+    fs::create_dir_all(&dir)?;
+
+    let mut files = Vec::new();
+    for name in SNAPSHOT_FILES {
+        let src = profile_dir.join(name);
+        if !src.is_file() {
+            continue;
+        }
+        let size = fs::copy(&src, dir.join(name))?;
+        files.push(SnapshotFile {
+            name: name.to_string(),
+            size,
+        });
+    }
+
+    let manifest = SnapshotManifest {
+        id,
+        label: label.map(str::to_string),
+        created_at,
+        files,
+        session_count: load_instances(&dir.join("sessions.json"))?.len(),
+        automatic,
+    };
+    fs::write(dir.join(MANIFEST_FILE), serde_json::to_string_pretty(&manifest)?)?;
+    Ok(manifest)
+}
+
+/// All snapshots of a profile, newest first. Directories without a readable
+/// manifest are skipped.
+pub fn list_snapshots(profile_dir: &Path) -> Result<Vec<SnapshotManifest>> {
+    let root = snapshots_dir(profile_dir);
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut out = Vec::new();
+    for ent in fs::read_dir(&root)? {
+        let ent = ent?;
+        if !ent.file_type()?.is_dir() {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(ent.path().join(MANIFEST_FILE)) else {
+            continue;
+        };
+        if let Ok(manifest) = serde_json::from_str::<SnapshotManifest>(&content) {
+            out.push(manifest);
+        }
+    }
+    out.sort_by(|a, b| b.created_at.cmp(&a.created_at).then_with(|| b.id.cmp(&a.id)));
+    Ok(out)
+}
+
+/// Find a snapshot by exact id or unique id prefix.
+pub fn find_snapshot(profile_dir: &Path, id: &str) -> Result<SnapshotManifest> {
+    let all = list_snapshots(profile_dir)?;
+    if let Some(exact) = all.iter().find(|s| s.id == id) {
+        return Ok(exact.clone());
+    }
+    let mut matches = all.into_iter().filter(|s| s.id.starts_with(id));
+    match (matches.next(), matches.next()) {
+        (Some(found), None) => Ok(found),
+        (Some(_), Some(_)) => Err(Error::InvalidInput(format!(
+            "Snapshot id '{id}' is ambiguous"
+        ))),
+        _ => Err(Error::InvalidInput(format!("Snapshot '{id}' not found"))),
+    }
+}
+
+/// Remove unlabeled snapshots beyond the newest `keep`. Labeled snapshots
+/// are never pruned. Returns the ids that were removed.
+pub fn prune_snapshots(profile_dir: &Path, keep: usize) -> Result<Vec<String>> {
+    let root = snapshots_dir(profile_dir);
+    let mut removed = Vec::new();
+    for manifest in list_snapshots(profile_dir)?
+        .into_iter()
+        .filter(|s| s.label.is_none())
+        .skip(keep)
+    {
+        fs::remove_dir_all(root.join(&manifest.id))?;
+        removed.push(manifest.id);
+    }
+    Ok(removed)
+}
+
+/// Restore a snapshot over the profile's current files.
+///
+/// A TUI only takes `sessions.lock` while it saves, so it cannot be seen
+/// from here: callers refuse first while one has the profile open (the CLI
+/// uses its heartbeat). The lock is still taken, waiting out a save in
+/// flight so it cannot interleave with the restore. Unless `dry_run` is
+/// set, the current state is first captured as an automatic safety
+/// snapshot, then each snapshot file replaces its live counterpart via a
+/// temp file and rename.
+pub fn restore_snapshot(profile_dir: &Path, id: &str, dry_run: bool) -> Result<RestoreReport> {
+    let manifest = find_snapshot(profile_dir, id)?;
+    let snap_dir = snapshots_dir(profile_dir).join(&manifest.id);
+
+    let lock_file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(profile_dir.join("sessions.lock"))?;
+    lock_file
+        .lock_exclusive()
+        .map_err(|e| Error::storage(format!("Failed to lock the profile: {e}")))?;
+
+    let current = load_instances(&profile_dir.join("sessions.json"))?;
+    let restored = load_instances(&snap_dir.join("sessions.json"))?;
+    let diff = diff_instances(&current, &restored);
+
+    if dry_run {
+        return Ok(RestoreReport {
+            restored: manifest,
+            safety: None,
+            diff,
+        });
+    }
+
+    let safety = create_snapshot(profile_dir, None, true)?;
+
+    for file in &manifest.files {
+        let target = profile_dir.join(&file.name);
+        let temp = target.with_extension("restore.tmp");
+        fs::copy(snap_dir.join(&file.name), &temp)?;
+        fs::rename(&temp, &target)?;
+    }
+
+    Ok(RestoreReport {
+        restored: manifest,
+        safety: Some(safety),
+        diff,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write_sessions(dir: &Path, titles: &[&str]) {
+        let data = StorageData {
+            instances: titles
+                .iter()
+                .map(|t| Instance::new(t.to_string(), PathBuf::from("/tmp")))
+                .collect(),
+            groups: Vec::new(),
+            relationships: Vec::new(),
+            updated_at: Utc::now(),
+            revision: 0,
+        };
+        fs::write(dir.join("sessions.json"), serde_json::to_string_pretty(&data).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn slug_is_filesystem_safe() {
+        assert_eq!(slugify("Before regroup!"), "before-regroup");
+        assert_eq!(slugify("  ../etc  "), "etc");
+        assert_eq!(slugify("日本"), "");
+    }
+
+    #[test]
+    fn create_and_list_with_labels() {
+        let dir = tempdir().unwrap();
+        write_sessions(dir.path(), &["a", "b"]);
+
+        let first = create_snapshot(dir.path(), Some("before regroup"), false).unwrap();
+        let second = create_snapshot(dir.path(), None, false).unwrap();
+        assert!(first.id.ends_with("-before-regroup"));
+        assert_eq!(first.session_count, 2);
+        assert_eq!(first.files.len(), 1);
+        assert!(first.total_size() > 0);
+
+        let listed = list_snapshots(dir.path()).unwrap();
+        assert_eq!(listed.len(), 2);
+        assert_eq!(listed[0].id, second.id);
+        assert_eq!(listed[1].label.as_deref(), Some("before regroup"));
+    }
+
+    #[test]
+    fn prune_keeps_labeled_snapshots() {
+        let dir = tempdir().unwrap();
+        write_sessions(dir.path(), &["a"]);
+
+        create_snapshot(dir.path(), Some("keep me"), false).unwrap();
+        for _ in 0..4 {
+            create_snapshot(dir.path(), None, false).unwrap();
+        }
+
+        let removed = prune_snapshots(dir.path(), 2).unwrap();
+        assert_eq!(removed.len(), 2);
+        let left = list_snapshots(dir.path()).unwrap();
+        assert_eq!(left.len(), 3);
+        assert!(left.iter().any(|s| s.label.as_deref() == Some("keep me")));
+    }
+
+    #[test]
+    fn find_by_prefix_and_unknown_id() {
+        let dir = tempdir().unwrap();
+        write_sessions(dir.path(), &["a"]);
+        let snap = create_snapshot(dir.path(), Some("only"), false).unwrap();
+
+        assert_eq!(find_snapshot(dir.path(), &snap.id[..8]).unwrap().id, snap.id);
+        assert!(find_snapshot(dir.path(), "nope").is_err());
+    }
+
+    #[test]
+    fn dry_run_leaves_files_untouched() {
+        let dir = tempdir().unwrap();
+        write_sessions(dir.path(), &["a"]);
+        let snap = create_snapshot(dir.path(), None, false).unwrap();
+        write_sessions(dir.path(), &["a", "b"]);
+        let before = fs::read(dir.path().join("sessions.json")).unwrap();
+
+        let report = restore_snapshot(dir.path(), &snap.id, true).unwrap();
+        assert!(report.safety.is_none());
+        assert_eq!(report.diff.added.len(), 1);
+        assert_eq!(report.diff.removed.len(), 2);
+        assert_eq!(fs::read(dir.path().join("sessions.json")).unwrap(), before);
+        assert_eq!(list_snapshots(dir.path()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn restore_waits_for_a_save_in_flight() {
+        let dir = tempdir().unwrap();
+        write_sessions(dir.path(), &["a"]);
+        let snap = create_snapshot(dir.path(), None, false).unwrap();
+
+        let holder = fs::File::create(dir.path().join("sessions.lock")).unwrap();
+        holder.lock_exclusive().unwrap();
+        let saving = std::time::Duration::from_millis(100);
+        let save = std::thread::spawn(move || {
+            std::thread::sleep(saving);
+            drop(holder);
+        });
+
+        let start = std::time::Instant::now();
+        restore_snapshot(dir.path(), &snap.id, false).unwrap();
+        assert!(start.elapsed() >= saving);
+        save.join().unwrap();
+    }
+}
@@ -0,0 +1,154 @@
+//! Stall detection: a session that says it is Running while its pane has
+//! not changed for a long time.
+//!
+//! A hung agent keeps its "esc to interrupt" footer on screen, so the
+//! prompt detector keeps reporting Running. The captures status probing
+//! already takes are hashed; once a Running session's hash has stayed the
+//! same for `[status] stall_secs`, it is shown as stalled. Acknowledging a
+//! stall records the hash in [`Instance::stall_acked`], which the TUI and
+//! the statusline both read.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{Instance, Status};
+
+/// Hash of a pane capture. Trailing whitespace is ignored, so padding
+/// from a resize does not count as output.
+pub fn pane_hash(content: &str) -> u64 {
+    let mut h = DefaultHasher::new();
+    for line in content.trim_end().lines() {
+        line.trim_end().hash(&mut h);
+    }
+    h.finish()
+}
+
+/// The last capture of a Running session and since when it looked the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaneStall {
+    pub hash: u64,
+    /// When the pane last changed, or when the session was first seen
+    /// running with it.
+    pub since: DateTime<Utc>,
+}
+
+impl PaneStall {
+    /// Fold a capture of `inst`, already given its new status, into the
+    /// previous record. Only Running sessions are tracked, so one that
+    /// starts running again starts a fresh clock. A changed pane also
+    /// drops an acknowledgement of the old one.
+    pub fn observe(
+        prev: Option<Self>,
+        inst: &mut Instance,
+        content: &str,
+        now: DateTime<Utc>,
+    ) -> Option<Self> {
+        if inst.status != Status::Running {
+            return None;
+        }
+        let hash = pane_hash(content);
+        if inst.stall_acked.is_some_and(|acked| acked != hash) {
+            inst.stall_acked = None;
+        }
+        match prev {
+            Some(prev) if prev.hash == hash => Some(prev),
+            _ => Some(Self { hash, since: now }),
+        }
+    }
+}
+
+/// How long `inst` has been stalled: Running, its pane unchanged for at
+/// least `threshold`, and not acknowledged. None when `threshold` is None,
+/// i.e. stall detection is off.
+pub fn stalled_for(
+    inst: &Instance,
+    stall: Option<&PaneStall>,
+    threshold: Option<Duration>,
+    now: DateTime<Utc>,
+) -> Option<Duration> {
+    let (stall, threshold) = (stall?, threshold?);
+    if inst.status != Status::Running || inst.stall_acked == Some(stall.hash) {
+        return None;
+    }
+    let unchanged = (now - stall.since).to_std().ok()?;
+    (unchanged >= threshold).then_some(unchanged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const THRESHOLD: Option<Duration> = Some(Duration::from_secs(300));
+
+    fn running() -> Instance {
+        let mut inst = Instance::new("api".into(), PathBuf::from("/tmp"));
+        inst.status = Status::Running;
+        inst
+    }
+
+    #[test]
+    fn unchanged_pane_keeps_its_clock() {
+        let start = Utc::now() - chrono::Duration::minutes(10);
+        let mut inst = running();
+        let first = PaneStall::observe(None, &mut inst, "Working… esc to interrupt", start);
+        let same = PaneStall::observe(
+            first,
+            &mut inst,
+            "Working… esc to interrupt  \n",
+            Utc::now(),
+        );
+        assert_eq!(same, first);
+        assert!(stalled_for(&inst, same.as_ref(), THRESHOLD, Utc::now())
+            .is_some_and(|d| d >= Duration::from_secs(599)));
+
+        let changed = PaneStall::observe(same, &mut inst, "Editing main.rs", Utc::now());
+        assert_ne!(changed.unwrap().hash, first.unwrap().hash);
+        assert_eq!(
+            stalled_for(&inst, changed.as_ref(), THRESHOLD, Utc::now()),
+            None
+        );
+    }
+
+    #[test]
+    fn only_running_sessions_stall() {
+        let mut inst = running();
+        inst.status = Status::Idle;
+        assert_eq!(PaneStall::observe(None, &mut inst, "$ ", Utc::now()), None);
+
+        let stall = PaneStall {
+            hash: pane_hash("$ "),
+            since: Utc::now() - chrono::Duration::hours(1),
+        };
+        assert_eq!(
+            stalled_for(&inst, Some(&stall), THRESHOLD, Utc::now()),
+            None
+        );
+        inst.status = Status::Running;
+        assert!(stalled_for(&inst, Some(&stall), THRESHOLD, Utc::now()).is_some());
+        assert_eq!(stalled_for(&inst, Some(&stall), None, Utc::now()), None);
+    }
+
+    #[test]
+    fn acknowledgement_lasts_until_the_pane_changes() {
+        let mut inst = running();
+        let stall = PaneStall {
+            hash: pane_hash("Working…"),
+            since: Utc::now() - chrono::Duration::hours(1),
+        };
+        inst.stall_acked = Some(stall.hash);
+        assert_eq!(
+            stalled_for(&inst, Some(&stall), THRESHOLD, Utc::now()),
+            None
+        );
+
+        PaneStall::observe(Some(stall), &mut inst, "Working…", Utc::now());
+        assert_eq!(inst.stall_acked, Some(stall.hash));
+        PaneStall::observe(Some(stall), &mut inst, "Done", Utc::now());
+        assert_eq!(inst.stall_acked, None);
+    }
+}
@@ -0,0 +1,391 @@
+//! Status detection shared by the dashboard, the switcher, the statusline
+//! and `agent-hand events`.
+//!
+//! Reading tmux activity is cheap, capturing a pane is not. Activity is also
+//! noisy: a `watch`, a log tail or a status-line refresh bumps it while the
+//! agent does nothing. So activity alone only marks a session Running for a
+//! short window; a capture decides what it really is. [`plan_probe`] decides
+//! when to capture, [`probe_pane`] captures and classifies, and
+//! [`probe_statuses`] runs the whole pass over a session list for callers
+//! that keep no history of their own besides a [`ProbeCache`].
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::config::StatusTiming;
+use crate::session::stall::{stalled_for, PaneStall};
+use crate::session::{Instance, Status};
+use crate::tmux::{PromptDetector, TmuxManager, Tool};
+
+#[derive(Debug, Clone, Copy)]
+pub struct ProbeCheck {
+    /// Status currently shown for the session.
+    pub prev: Status,
+    /// Whether tmux activity advanced since the last tick.
+    pub activity_changed: bool,
+    /// Time since activity last advanced, not counting this tick. `None`
+    /// when no change has been seen yet.
+    pub since_change: Option<Duration>,
+    /// Time since the pane was last captured. `None` when never.
+    pub since_probe: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProbePlan {
+    /// Capture the pane now.
+    pub probe: bool,
+    /// Status to show until the next capture.
+    pub interim: Status,
+}
+
+impl ProbePlan {
+    fn probe(interim: Status) -> Self {
+        Self {
+            probe: true,
+            interim,
+        }
+    }
+
+    fn skip(interim: Status) -> Self {
+        Self {
+            probe: false,
+            interim,
+        }
+    }
+}
+
+/// Decide whether to capture the pane this tick.
+///
+/// - Every session is captured at least every `fallback`, and Running ones
+///   at least every `refresh`, so Running never sticks without evidence.
+/// - Activity after a quiet spell of `cooldown` marks Running right away.
+/// - Activity that never stops is noise until a capture says otherwise;
+///   it is checked at most every `refresh`.
+/// - Once output stops for `cooldown`, the final screen is captured once.
+pub fn plan_probe(check: &ProbeCheck, timing: &StatusTiming) -> ProbePlan {
+    let due = |interval: Duration| check.since_probe.is_none_or(|d| d >= interval);
+
+    if due(timing.fallback) {
+        return ProbePlan::probe(check.prev);
+    }
+    if check.prev == Status::Running {
+        return if due(timing.refresh) {
+            ProbePlan::probe(Status::Running)
+        } else {
+            ProbePlan::skip(Status::Running)
+        };
+    }
+    if check.activity_changed {
+        let after_quiet = check.since_change.is_none_or(|d| d >= timing.cooldown);
+        return if after_quiet {
+            ProbePlan::skip(Status::Running)
+        } else if due(timing.refresh) {
+            ProbePlan::probe(check.prev)
+        } else {
+            ProbePlan::skip(check.prev)
+        };
+    }
+
+    // Output stopped after the last capture and has stayed quiet since.
+    let settled_unseen = match (check.since_change, check.since_probe) {
+        (Some(change), Some(probe)) => change >= timing.cooldown && probe > change,
+        _ => false,
+    };
+    if settled_unseen {
+        ProbePlan::probe(check.prev)
+    } else {
+        ProbePlan::skip(check.prev)
+    }
+}
+
+/// What one capture of a pane showed.
+#[derive(Debug, Clone)]
+pub struct PaneProbe {
+    pub status: Status,
+    /// What the prompt asks, when the pane is Waiting.
+    pub waiting_reason: Option<String>,
+    /// The captured lines, for stall detection.
+    pub content: String,
+}
+
+/// Capture `target`'s pane (at most `lines` lines) and classify it.
+/// `shown` is the status on screen, which decides Stopped or Error when
+/// the pane's process has exited.
+pub async fn probe_pane(
+    manager: &TmuxManager,
+    target: &str,
+    tool: Tool,
+    shown: Status,
+    lines: usize,
+) -> PaneProbe {
+    let content = manager
+        .capture_pane(target, lines)
+        .await
+        .unwrap_or_default();
+    let dead = manager.pane_dead(target);
+    let (status, waiting_reason) = classify(&content, tool, dead, shown);
+    PaneProbe {
+        status,
+        waiting_reason,
+        content,
+    }
+}
+
+/// Status of a captured pane: exited when its process is `dead`,
+/// otherwise Waiting on a prompt, Running while busy, and Idle.
+pub fn classify(content: &str, tool: Tool, dead: bool, shown: Status) -> (Status, Option<String>) {
+    if dead {
+        return (shown.after_exit(), None);
+    }
+    let detector = PromptDetector::new(tool);
+    if detector.has_prompt(content) {
+        (Status::Waiting, detector.waiting_summary(content))
+    } else if detector.is_busy(content) {
+        (Status::Running, None)
+    } else {
+        (Status::Idle, None)
+    }
+}
+
+/// What earlier passes saw of one session.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ProbeRecord {
+    status: Status,
+    activity: i64,
+    /// When tmux activity last advanced.
+    changed_at: Option<DateTime<Utc>>,
+    /// When the pane was last captured.
+    probed_at: Option<DateTime<Utc>>,
+    /// What the prompt asked when last seen Waiting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    waiting_reason: Option<String>,
+    /// Last capture while Running, for stall detection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stall: Option<PaneStall>,
+}
+
+/// Probe history carried from one pass to the next, in memory or, for
+/// callers that run as a fresh process each time, on disk.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ProbeCache {
+    #[serde(default)]
+    sessions: HashMap<String, ProbeRecord>,
+}
+
+impl ProbeCache {
+    /// The cache saved at `path`; empty when missing or unreadable.
+    pub fn load(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    /// Best effort: a lost cache only costs a few extra captures. Written
+    /// through a per-process temp file, so runs for other formats never
+    /// read a half-written one.
+    pub fn save(&self, path: &Path) {
+        let Ok(json) = serde_json::to_string(self) else {
+            return;
+        };
+        let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+        if std::fs::write(&tmp, json).is_ok() && std::fs::rename(&tmp, path).is_err() {
+            let _ = std::fs::remove_file(&tmp);
+        }
+    }
+
+    /// How long `inst` has been stalled, going by the captures cached here.
+    pub fn stalled_for(
+        &self,
+        inst: &Instance,
+        timing: &StatusTiming,
+        now: DateTime<Utc>,
+    ) -> Option<Duration> {
+        let stall = self.sessions.get(&inst.id).and_then(|r| r.stall.as_ref());
+        stalled_for(inst, stall, timing.stall, now)
+    }
+}
+
+/// Update `instances`' statuses from tmux. A session without a tmux
+/// session, or whose pane process exited, is Stopped, or Error if it was
+/// last seen running. Expects a fresh tmux cache.
+pub async fn probe_statuses(
+    instances: &mut [Instance],
+    manager: &TmuxManager,
+    cache: &mut ProbeCache,
+    timing: &StatusTiming,
+) {
+    let now = Utc::now();
+    let elapsed = |t: Option<DateTime<Utc>>| t.and_then(|t| (now - t).to_std().ok());
+    cache
+        .sessions
+        .retain(|id, _| instances.iter().any(|i| &i.id == id));
+
+    for inst in instances.iter_mut() {
+        let tmux_name = inst.tmux_name();
+        let target = inst.tmux_target();
+        let record = cache.sessions.get(&inst.id).cloned();
+        let record = record.as_ref();
+        if !manager.session_exists(&tmux_name).unwrap_or(false) {
+            inst.status = record.map_or(inst.status, |r| r.status).after_exit();
+            cache.sessions.remove(&inst.id);
+            continue;
+        }
+
+        let activity = manager.session_activity(&tmux_name).unwrap_or(0);
+        let activity_changed = record.is_some_and(|r| activity > r.activity);
+        let plan = plan_probe(
+            &ProbeCheck {
+                prev: record.map_or(inst.status, |r| r.status),
+                activity_changed,
+                since_change: elapsed(record.and_then(|r| r.changed_at)),
+                since_probe: elapsed(record.and_then(|r| r.probed_at)),
+            },
+            timing,
+        );
+
+        let mut probed_at = record.and_then(|r| r.probed_at);
+        let mut captured = None;
+        inst.waiting_reason = record.and_then(|r| r.waiting_reason.clone());
+        inst.status = if plan.probe {
+            probed_at = Some(now);
+            let probe = probe_pane(
+                manager,
+                &target,
+                inst.tool,
+                plan.interim,
+                timing.capture_lines,
+            )
+            .await;
+            if probe.status == Status::Waiting {
+                inst.waiting_reason = probe.waiting_reason;
+            }
+            captured = Some(probe.content);
+            probe.status
+        } else {
+            plan.interim
+        };
+        if inst.status != Status::Waiting {
+            inst.waiting_reason = None;
+        }
+        let prev_stall = record.and_then(|r| r.stall);
+        let stall = match &captured {
+            Some(content) => PaneStall::observe(prev_stall, inst, content, now),
+            None => prev_stall.filter(|_| inst.status == Status::Running),
+        };
+
+        cache.sessions.insert(
+            inst.id.clone(),
+            ProbeRecord {
+                status: inst.status,
+                activity,
+                changed_at: if activity_changed {
+                    Some(now)
+                } else {
+                    record.and_then(|r| r.changed_at)
+                },
+                probed_at,
+                waiting_reason: inst.waiting_reason.clone(),
+                stall,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timing() -> StatusTiming {
+        StatusTiming {
+            refresh: Duration::from_secs(1),
+            cooldown: Duration::from_secs(2),
+            fallback: Duration::from_secs(10),
+            ..Default::default()
+        }
+    }
+
+    fn secs(s: f64) -> Option<Duration> {
+        Some(Duration::from_secs_f64(s))
+    }
+
+    fn plan(
+        prev: Status,
+        activity_changed: bool,
+        since_change: Option<Duration>,
+        since_probe: Option<Duration>,
+    ) -> ProbePlan {
+        let check = ProbeCheck {
+            prev,
+            activity_changed,
+            since_change,
+            since_probe,
+        };
+        plan_probe(&check, &timing())
+    }
+
+    #[test]
+    fn never_probed_or_fallback_elapsed_probes() {
+        assert!(plan(Status::Idle, false, None, None).probe);
+        assert!(plan(Status::Idle, false, None, secs(10.0)).probe);
+        assert!(!plan(Status::Idle, false, None, secs(9.0)).probe);
+    }
+
+    #[test]
+    fn activity_after_quiet_marks_running_without_probe() {
+        let p = plan(Status::Idle, true, secs(30.0), secs(0.5));
+        assert_eq!(p, ProbePlan::skip(Status::Running));
+        let p = plan(Status::Waiting, true, None, secs(0.5));
+        assert_eq!(p, ProbePlan::skip(Status::Running));
+    }
+
+    #[test]
+    fn running_is_reprobed_every_refresh() {
+        let p = plan(Status::Running, true, secs(0.2), secs(0.5));
+        assert_eq!(p, ProbePlan::skip(Status::Running));
+        let p = plan(Status::Running, true, secs(0.2), secs(1.0));
+        assert_eq!(p, ProbePlan::probe(Status::Running));
+        let p = plan(Status::Running, false, secs(5.0), secs(1.0));
+        assert!(p.probe);
+    }
+
+    #[test]
+    fn constant_noise_does_not_resurrect_running() {
+        // A capture found no agent work while output keeps trickling in.
+        let p = plan(Status::Idle, true, secs(0.5), secs(0.5));
+        assert_eq!(p, ProbePlan::skip(Status::Idle));
+        // Checked again at most every refresh, still not assumed Running.
+        let p = plan(Status::Idle, true, secs(0.5), secs(1.5));
+        assert_eq!(p, ProbePlan::probe(Status::Idle));
+    }
+
+    #[test]
+    fn classifies_captures() {
+        let prompt = "Do you want to create main.rs?\n❯ 1. Yes\nEsc to cancel";
+        let (status, reason) = classify(prompt, Tool::Claude, false, Status::Running);
+        assert_eq!(status, Status::Waiting);
+        assert!(reason.is_some());
+
+        let status = |content, dead, shown| classify(content, Tool::Claude, dead, shown).0;
+        let busy = "Thinking… (45s · 1234 tokens · esc to interrupt)";
+        assert_eq!(status(busy, false, Status::Idle), Status::Running);
+        assert_eq!(status("$ ", false, Status::Running), Status::Idle);
+        // An exited pane is a crash only if it was running.
+        assert_eq!(status(busy, true, Status::Running), Status::Error);
+        assert_eq!(status(prompt, true, Status::Idle), Status::Stopped);
+    }
+
+    #[test]
+    fn settled_output_is_captured_once() {
+        // Last change 3s ago, last capture 4s ago: the final screen is unseen.
+        assert!(plan(Status::Idle, false, secs(3.0), secs(4.0)).probe);
+        // Captured after it settled: nothing new to see.
+        assert!(!plan(Status::Idle, false, secs(3.0), secs(0.5)).probe);
+        // Not settled yet.
+        assert!(!plan(Status::Idle, false, secs(1.0), secs(4.0)).probe);
+    }
+}
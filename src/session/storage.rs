@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 
@@ -7,8 +10,10 @@ use chrono::{DateTime, Utc};
 use fs2::FileExt;
 use parking_lot::Mutex;
 
-use super::{GroupData, GroupTree, Instance, Relationship};
+use super::merge::{keyed, merge_records};
+use super::{GroupData, GroupTree, Instance, Relationship, Status};
 use crate::error::{Error, Result};
+use crate::tmux::Tool;
 
 fn copy_dir_recursive_sync(src: &PathBuf, dst: &PathBuf) -> Result<()> {
     std::fs::create_dir_all(dst)?;
@@ -62,7 +67,7 @@ async fn profiles_have_instances(profiles_dir: &PathBuf) -> Result<bool> {
     Ok(false)
 }
 
-const MAX_BACKUP_GENERATIONS: usize = 3;
+pub const MAX_BACKUP_GENERATIONS: usize = 3;
 
 /// Storage data format
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -72,6 +77,19 @@ pub struct StorageData {
     #[serde(default)]
     pub relationships: Vec<Relationship>,
     pub updated_at: DateTime<Utc>,
+    /// Bumped on every save. A save whose last load saw another revision
+    /// merges with the file instead of overwriting it.
+    #[serde(default)]
+    pub revision: u64,
+}
+
+/// One rolling backup of sessions.json; generation 1 is the newest.
+#[derive(Debug, Clone)]
+pub struct BackupInfo {
+    pub generation: usize,
+    pub path: PathBuf,
+    /// `updated_at` and session count, or why the file does not parse.
+    pub contents: std::result::Result<(DateTime<Utc>, usize), String>,
 }
 
 /// Session storage handler
@@ -79,6 +97,40 @@ pub struct Storage {
     path: PathBuf,
     profile: String,
     lock: Mutex<()>,
+    /// Status last read from or written to disk, per session id.
+    written_statuses: Mutex<HashMap<String, Status>>,
+    /// Backup generation the last load fell back to because
+    /// sessions.json did not parse.
+    recovered_from: Mutex<Option<usize>>,
+    /// What this handle last read or wrote; the base of a merge when
+    /// another process saved in between.
+    base: Mutex<Option<Base>>,
+    /// sessions.json's mtime after this handle last read or wrote it.
+    disk_mtime: Mutex<Option<SystemTime>>,
+}
+
+/// Records of one revision of sessions.json, keyed by id or group path.
+#[derive(Debug, Default)]
+struct Base {
+    revision: u64,
+    instances: BTreeMap<String, Value>,
+    groups: BTreeMap<String, Value>,
+    relationships: BTreeMap<String, Value>,
+}
+
+impl Base {
+    fn of(data: &StorageData) -> Result<Self> {
+        Ok(Self {
+            revision: data.revision,
+            instances: keyed(&data.instances, |i| i.id.as_str())?,
+            groups: keyed(&data.groups, |g| g.path.as_str())?,
+            relationships: keyed(&data.relationships, |r| r.id.as_str())?,
+        })
+    }
+}
+
+fn file_mtime(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
 }
 
 impl Storage {
@@ -96,6 +148,10 @@ impl Storage {
             path,
             profile: profile.to_string(),
             lock: Mutex::new(()),
+            written_statuses: Mutex::new(HashMap::new()),
+            recovered_from: Mutex::new(None),
+            base: Mutex::new(None),
+            disk_mtime: Mutex::new(None),
         })
     }
 
@@ -199,6 +255,20 @@ impl Storage {
         &self.profile
     }
 
+    /// Directory holding a profile's state (e.g. `~/.agent-hand/profiles/default/`).
+    pub fn profile_dir_for(profile: &str) -> Result<PathBuf> {
+        Ok(Self::get_agent_deck_dir()?.join("profiles").join(profile))
+    }
+
+    /// Where the TUI saves a session transcript, stamped with the local time
+    /// (e.g. `~/.agent-hand/profiles/default/transcripts/<id>-20260101-093000.txt`).
+    pub fn transcript_path(profile: &str, id: &str) -> Result<PathBuf> {
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        Ok(Self::profile_dir_for(profile)?
+            .join("transcripts")
+            .join(format!("{}-{}.txt", id, stamp)))
+    }
+
     /// Get the per-profile canvas directory (e.g. `~/.agent-hand/profiles/default/canvas/`).
     pub fn canvas_dir(&self) -> PathBuf {
         let base_dir = Self::get_agent_deck_dir().unwrap_or_else(|_| PathBuf::from("."));
@@ -213,11 +283,43 @@ impl Storage {
             return Ok((Vec::new(), GroupTree::new(), Vec::new()));
         }
 
+        // Before reading: a write in between shows up as a change later.
+        let mtime = file_mtime(&self.path);
         let content = fs::read_to_string(&self.path).await?;
-        let data: StorageData = serde_json::from_str(&content)?;
+        let data: StorageData = match serde_json::from_str(&content) {
+            Ok(data) => data,
+            Err(e) => {
+                let Some((generation, data)) = self.newest_valid_backup().await else {
+                    return Err(e.into());
+                };
+                tracing::warn!(
+                    "{} does not parse ({}); loaded backup {} instead",
+                    self.path.display(),
+                    e,
+                    generation
+                );
+                *self.recovered_from.lock() = Some(generation);
+                data
+            }
+        };
+
+        *self.written_statuses.lock() = data
+            .instances
+            .iter()
+            .map(|i| (i.id.clone(), i.status))
+            .collect();
+        *self.base.lock() = Some(Base::of(&data)?);
+        *self.disk_mtime.lock() = mtime;
+
+        // Sessions created with a command but saved as a plain shell (as the
+        // New Session dialog used to) get the tool the command runs.
+        let mut instances = data.instances;
+        for inst in instances.iter_mut().filter(|i| !i.command.trim().is_empty()) {
+            inst.upgrade_tool(Tool::from_command(&inst.command));
+        }
 
         let tree = GroupTree::from_groups(data.groups);
-        Ok((data.instances, tree, data.relationships))
+        Ok((instances, tree, data.relationships))
     }
 
     /// Save sessions, groups, and relationships
@@ -230,26 +332,116 @@ impl Storage {
         let _lock = self.lock.lock();
 
         // Acquire cross-process file lock for multi-instance safety
-        let lock_path = self.path.with_extension("lock");
-        let lock_file = std::fs::File::create(&lock_path)?;
-        lock_file
-            .lock_exclusive()
-            .map_err(|e| Error::Other(format!("Failed to acquire file lock: {}", e)))?;
+        let _lock_file = self.lock_file()?;
 
-        // Create rolling backups
-        self.create_backup().await?;
+        // Transient statuses keep the last settled value on disk.
+        let mut instances = instances.to_vec();
+        super::reconcile::statuses_to_persist(&mut instances, &self.written_statuses.lock());
 
-        // Serialize data
-        let data = StorageData {
-            instances: instances.to_vec(),
+        let mut data = StorageData {
+            instances,
             groups: tree.all_groups(),
             relationships: relationships.to_vec(),
             updated_at: Utc::now(),
+            revision: 0,
         };
 
+        // Another process saved since our last load: merge onto its file.
+        let base_revision = self.base.lock().as_ref().map(|b| b.revision);
+        let mut unmerged = None;
+        let revision = match self.read_current().await {
+            Some(disk) if Some(disk.revision) != base_revision => {
+                let revision = disk.revision;
+                let mut ours = Base::of(&data)?;
+                ours.revision = revision;
+                unmerged = Some(ours);
+                data = self.merge_onto(data, disk)?;
+                revision
+            }
+            Some(disk) => disk.revision,
+            None => base_revision.unwrap_or(0),
+        };
+        data.revision = revision + 1;
+
+        // Create rolling backups
+        self.create_backup().await?;
+
         let json = serde_json::to_string_pretty(&data)?;
+        self.write_atomic(&json).await?;
+
+        *self.written_statuses.lock() =
+            data.instances.iter().map(|i| (i.id.clone(), i.status)).collect();
+        match unmerged {
+            // The caller still holds its own records, not the merged ones:
+            // keep those as the base, at a revision that is already stale,
+            // so its next save merges again instead of dropping what the
+            // other process added. Forgetting the mtime makes
+            // `changed_on_disk` report the merge so the caller reloads.
+            Some(ours) => {
+                *self.base.lock() = Some(ours);
+                *self.disk_mtime.lock() = None;
+            }
+            None => {
+                *self.base.lock() = Some(Base::of(&data)?);
+                *self.disk_mtime.lock() = file_mtime(&self.path);
+            }
+        }
+
+        // Lock is automatically released when lock_file is dropped
+        Ok(())
+    }
+
+    /// sessions.json as it is on disk; `None` when missing or unreadable.
+    async fn read_current(&self) -> Option<StorageData> {
+        let content = fs::read_to_string(&self.path).await.ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Merge `ours` onto `disk` with the last loaded revision as the base.
+    fn merge_onto(&self, ours: StorageData, disk: StorageData) -> Result<StorageData> {
+        let base = self.base.lock();
+        let empty = Base::default();
+        let base = base.as_ref().unwrap_or(&empty);
+        Ok(StorageData {
+            instances: merge_records(
+                "session",
+                &base.instances,
+                &ours.instances,
+                &disk.instances,
+                |i| i.id.as_str(),
+            )?,
+            groups: merge_records("group", &base.groups, &ours.groups, &disk.groups, |g| {
+                g.path.as_str()
+            })?,
+            relationships: merge_records(
+                "relationship",
+                &base.relationships,
+                &ours.relationships,
+                &disk.relationships,
+                |r| r.id.as_str(),
+            )?,
+            ..ours
+        })
+    }
+
+    /// Whether sessions.json changed since this handle last loaded or saved
+    /// it, i.e. another process wrote it.
+    pub fn changed_on_disk(&self) -> bool {
+        file_mtime(&self.path) != *self.disk_mtime.lock()
+    }
 
-        // Atomic write: write to temp file, then rename
+    /// Cross-process lock on the profile's storage, held until dropped.
+    fn lock_file(&self) -> Result<std::fs::File> {
+        let lock_path = self.path.with_extension("lock");
+        let lock_file = std::fs::File::create(&lock_path)?;
+        lock_file
+            .lock_exclusive()
+            .map_err(|e| Error::Other(format!("Failed to acquire file lock: {}", e)))?;
+        Ok(lock_file)
+    }
+
+    /// Atomic write: write to temp file, then rename
+    async fn write_atomic(&self, json: &str) -> Result<()> {
         let temp_path = self.path.with_extension("tmp");
         let mut file = fs::File::create(&temp_path).await?;
         file.write_all(json.as_bytes()).await?;
@@ -257,25 +449,110 @@ impl Storage {
         drop(file);
 
         fs::rename(&temp_path, &self.path).await?;
-
-        // Lock is automatically released when lock_file is dropped
         Ok(())
     }
 
+    fn backup_path(&self, generation: usize) -> PathBuf {
+        Self::backup_path_for(&self.path, generation)
+    }
+
+    /// Backup `generation` of `sessions`: 1 is `sessions.bak`, later ones
+    /// `sessions.bak.N`.
+    pub fn backup_path_for(sessions: &Path, generation: usize) -> PathBuf {
+        if generation == 1 {
+            sessions.with_extension("bak")
+        } else {
+            sessions.with_extension(format!("bak.{}", generation))
+        }
+    }
+
+    /// Backups on disk, newest first.
+    pub async fn list_backups(&self) -> Vec<BackupInfo> {
+        let mut backups = Vec::new();
+        for generation in 1..=MAX_BACKUP_GENERATIONS {
+            let path = self.backup_path(generation);
+            let Ok(content) = fs::read_to_string(&path).await else {
+                continue;
+            };
+            let contents = serde_json::from_str::<StorageData>(&content)
+                .map(|d| (d.updated_at, d.instances.len()))
+                .map_err(|e| e.to_string());
+            backups.push(BackupInfo {
+                generation,
+                path,
+                contents,
+            });
+        }
+        backups
+    }
+
+    async fn newest_valid_backup(&self) -> Option<(usize, StorageData)> {
+        for generation in 1..=MAX_BACKUP_GENERATIONS {
+            let Ok(content) = fs::read_to_string(self.backup_path(generation)).await else {
+                continue;
+            };
+            if let Ok(data) = serde_json::from_str(&content) {
+                return Some((generation, data));
+            }
+        }
+        None
+    }
+
+    /// Backup generation the last `load` fell back to, if sessions.json
+    /// was corrupt.
+    pub fn recovered_from_backup(&self) -> Option<usize> {
+        *self.recovered_from.lock()
+    }
+
+    /// Swap backup `generation` in as sessions.json. The backup must parse;
+    /// the current file is rolled into the backups first, so a restore can
+    /// itself be undone.
+    pub async fn restore_backup(&self, generation: usize) -> Result<StorageData> {
+        if generation == 0 || generation > MAX_BACKUP_GENERATIONS {
+            return Err(Error::InvalidInput(format!(
+                "backup generation must be 1-{}",
+                MAX_BACKUP_GENERATIONS
+            )));
+        }
+        let _lock = self.lock.lock();
+        let _lock_file = self.lock_file()?;
+
+        let path = self.backup_path(generation);
+        let content = fs::read_to_string(&path)
+            .await
+            .map_err(|e| Error::InvalidInput(format!("{}: {}", path.display(), e)))?;
+        let mut data: StorageData = serde_json::from_str(&content).map_err(|e| {
+            Error::InvalidInput(format!("{} does not parse: {}", path.display(), e))
+        })?;
+        // A new revision, so other processes merge onto the restored file.
+        let current = self.read_current().await.map_or(0, |d| d.revision);
+        data.revision = current.max(data.revision) + 1;
+
+        // Read first: rolling moves the chosen file to the next generation.
+        self.create_backup().await?;
+        self.write_atomic(&serde_json::to_string_pretty(&data)?).await?;
+
+        *self.written_statuses.lock() = data
+            .instances
+            .iter()
+            .map(|i| (i.id.clone(), i.status))
+            .collect();
+        *self.recovered_from.lock() = None;
+        *self.base.lock() = Some(Base::of(&data)?);
+        *self.disk_mtime.lock() = file_mtime(&self.path);
+        Ok(data)
+    }
+
     /// Create rolling backup
     async fn create_backup(&self) -> Result<()> {
         if !self.path.exists() {
             return Ok(());
         }
 
-        // Roll backups: .bak.2 -> .bak.3, .bak.1 -> .bak.2, .bak -> .bak.1
+        // Roll backups: .bak.2 -> .bak.3, .bak -> .bak.2
         for i in (1..MAX_BACKUP_GENERATIONS).rev() {
-            let from = if i == 1 {
-                self.path.with_extension("bak")
-            } else {
-                self.path.with_extension(format!("bak.{}", i))
-            };
-            let to = self.path.with_extension(format!("bak.{}", i + 1));
+            let from = self.backup_path(i);
+            let to = self.backup_path(i + 1);
 
             if from.exists() {
                 // Remove target if exists (fs::rename doesn't overwrite on all platforms)
@@ -287,7 +564,7 @@ impl Storage {
         }
 
         // Current file -> .bak
-        let bak = self.path.with_extension("bak");
+        let bak = self.backup_path(1);
         if bak.exists() {
             let _ = fs::remove_file(&bak).await;
         }
@@ -342,6 +619,7 @@ impl Storage {
             groups: Vec::new(),
             relationships: Vec::new(),
             updated_at: Utc::now(),
+            revision: 0,
         };
         let json = serde_json::to_string_pretty(&data)?;
         fs::write(&sessions_file, json).await?;
@@ -382,6 +660,10 @@ mod tests {
             path: profile_dir.join("sessions.json"),
             profile: "test".to_string(),
             lock: Mutex::new(()),
+            written_statuses: Mutex::new(HashMap::new()),
+            recovered_from: Mutex::new(None),
+            base: Mutex::new(None),
+            disk_mtime: Mutex::new(None),
         };
 
         let mut instances = Vec::new();
@@ -396,4 +678,192 @@ mod tests {
         assert_eq!(loaded_instances.len(), 1);
         assert_eq!(loaded_instances[0].title, "test");
     }
+
+    #[tokio::test]
+    async fn test_running_is_saved_as_last_settled_status() {
+        let dir = tempdir().unwrap();
+        let storage = Storage {
+            path: dir.path().join("sessions.json"),
+            profile: "test".to_string(),
+            lock: Mutex::new(()),
+            written_statuses: Mutex::new(HashMap::new()),
+            recovered_from: Mutex::new(None),
+            base: Mutex::new(None),
+            disk_mtime: Mutex::new(None),
+        };
+        let tree = GroupTree::new();
+        let mut instance = Instance::new("test".to_string(), PathBuf::from("/tmp"));
+
+        instance.status = Status::Waiting;
+        storage.save(std::slice::from_ref(&instance), &tree, &[]).await.unwrap();
+        instance.status = Status::Running;
+        storage.save(std::slice::from_ref(&instance), &tree, &[]).await.unwrap();
+
+        let (loaded, _, _) = storage.load().await.unwrap();
+        assert_eq!(loaded[0].status, Status::Waiting);
+    }
+
+    fn storage_at(path: PathBuf) -> Storage {
+        Storage {
+            path,
+            profile: "test".to_string(),
+            lock: Mutex::new(()),
+            written_statuses: Mutex::new(HashMap::new()),
+            recovered_from: Mutex::new(None),
+            base: Mutex::new(None),
+            disk_mtime: Mutex::new(None),
+        }
+    }
+
+    async fn save_titles(storage: &Storage, titles: &[&str]) {
+        let instances: Vec<Instance> = titles
+            .iter()
+            .map(|t| Instance::new(t.to_string(), PathBuf::from("/tmp")))
+            .collect();
+        storage.save(&instances, &GroupTree::new(), &[]).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn shell_sessions_with_a_command_load_with_its_tool() {
+        let dir = tempdir().unwrap();
+        let storage = storage_at(dir.path().join("sessions.json"));
+        let mut claude = Instance::new("claude".to_string(), PathBuf::from("/tmp"));
+        claude.command = "claude --model opus".to_string();
+        let mut tail = Instance::new("logs".to_string(), PathBuf::from("/tmp"));
+        tail.command = "tail -f app.log".to_string();
+        let plain = Instance::new("shell".to_string(), PathBuf::from("/tmp"));
+        storage
+            .save(&[claude, tail, plain], &GroupTree::new(), &[])
+            .await
+            .unwrap();
+
+        let (loaded, _, _) = storage.load().await.unwrap();
+        let tools: Vec<Tool> = loaded.iter().map(|i| i.tool).collect();
+        assert_eq!(tools, vec![Tool::Claude, Tool::Shell, Tool::Shell]);
+    }
+
+    #[tokio::test]
+    async fn concurrent_saves_merge_instead_of_clobbering() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("sessions.json");
+        let (tui, cli) = (storage_at(path.clone()), storage_at(path));
+        save_titles(&tui, &["a"]).await;
+
+        let (mut tui_sessions, tree, _) = tui.load().await.unwrap();
+        let (mut cli_sessions, _, _) = cli.load().await.unwrap();
+        cli_sessions.push(Instance::new("from cli".to_string(), PathBuf::from("/tmp")));
+        cli.save(&cli_sessions, &tree, &[]).await.unwrap();
+        assert!(tui.changed_on_disk());
+        assert!(!cli.changed_on_disk());
+
+        // The TUI saves its stale list with an edit of its own.
+        tui_sessions[0].label = "blocked".into();
+        tui.save(&tui_sessions, &tree, &[]).await.unwrap();
+        let (loaded, _, _) = cli.load().await.unwrap();
+        let titles: Vec<_> = loaded.iter().map(|i| i.title.as_str()).collect();
+        assert_eq!(titles, ["a", "from cli"]);
+        assert_eq!(loaded[0].label, "blocked");
+
+        // Both renaming the same session is refused.
+        tui_sessions[0].title = "tui".into();
+        let mut cli_sessions = loaded;
+        cli_sessions[0].title = "cli".into();
+        cli.save(&cli_sessions, &tree, &[]).await.unwrap();
+        assert!(matches!(
+            tui.save(&tui_sessions, &tree, &[]).await,
+            Err(Error::Conflict(_))
+        ));
+        let (loaded, _, _) = tui.load().await.unwrap();
+        assert_eq!(loaded[0].title, "cli");
+    }
+
+    #[tokio::test]
+    async fn saves_after_a_merge_keep_the_other_process_records() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("sessions.json");
+        let (tui, cli) = (storage_at(path.clone()), storage_at(path));
+        save_titles(&tui, &["a"]).await;
+        let (mut tui_sessions, tree, _) = tui.load().await.unwrap();
+
+        let (mut cli_sessions, _, _) = cli.load().await.unwrap();
+        cli_sessions.push(Instance::new("from cli".to_string(), PathBuf::from("/tmp")));
+        cli.save(&cli_sessions, &tree, &[]).await.unwrap();
+
+        // Two saves of the TUI's stale list in a row: the second must not
+        // treat the merged file as its own and drop the CLI's session.
+        tui_sessions[0].label = "first".into();
+        tui.save(&tui_sessions, &tree, &[]).await.unwrap();
+        assert!(tui.changed_on_disk());
+        tui_sessions[0].label = "second".into();
+        tui_sessions.push(Instance::new("from tui".to_string(), PathBuf::from("/tmp")));
+        tui.save(&tui_sessions, &tree, &[]).await.unwrap();
+
+        let (loaded, _, _) = cli.load().await.unwrap();
+        let mut titles: Vec<_> = loaded.iter().map(|i| i.title.as_str()).collect();
+        titles.sort_unstable();
+        assert_eq!(titles, ["a", "from cli", "from tui"]);
+        assert_eq!(loaded[0].label, "second");
+
+        // Once the TUI reloads, saves stop merging and deletions stick.
+        let (mut tui_sessions, tree, _) = tui.load().await.unwrap();
+        assert!(!tui.changed_on_disk());
+        tui_sessions.retain(|i| i.title != "from cli");
+        tui.save(&tui_sessions, &tree, &[]).await.unwrap();
+        let (loaded, _, _) = cli.load().await.unwrap();
+        assert_eq!(loaded.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn corrupt_sessions_file_falls_back_to_newest_valid_backup() {
+        let dir = tempdir().unwrap();
+        let storage = storage_at(dir.path().join("sessions.json"));
+        save_titles(&storage, &["a"]).await;
+        save_titles(&storage, &["a", "b"]).await;
+        save_titles(&storage, &["a", "b", "c"]).await;
+        // Newest backup is unreadable too; generation 2 is the fallback.
+        fs::write(storage.backup_path(1), "{ truncated").await.unwrap();
+        fs::write(&storage.path, "{ truncated").await.unwrap();
+
+        let (loaded, _, _) = storage.load().await.unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(storage.recovered_from_backup(), Some(2));
+
+        let backups = storage.list_backups().await;
+        assert_eq!(
+            backups.iter().map(|b| b.generation).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert!(backups[0].contents.is_err());
+        assert_eq!(backups[1].contents.as_ref().unwrap().1, 1);
+    }
+
+    #[tokio::test]
+    async fn corrupt_sessions_file_without_backups_is_an_error() {
+        let dir = tempdir().unwrap();
+        let storage = storage_at(dir.path().join("sessions.json"));
+        fs::write(&storage.path, "not json").await.unwrap();
+        assert!(storage.load().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn restore_backup_swaps_in_and_keeps_current_as_backup() {
+        let dir = tempdir().unwrap();
+        let storage = storage_at(dir.path().join("sessions.json"));
+        save_titles(&storage, &["a"]).await;
+        save_titles(&storage, &["a", "b"]).await;
+        save_titles(&storage, &["a", "b", "c"]).await;
+
+        let data = storage.restore_backup(2).await.unwrap();
+        assert_eq!(data.instances.len(), 1);
+        let (loaded, _, _) = storage.load().await.unwrap();
+        assert_eq!(loaded.len(), 1);
+
+        // The file that was current before the restore is now backup 1.
+        let backups = storage.list_backups().await;
+        assert_eq!(backups[0].contents.as_ref().unwrap().1, 3);
+
+        fs::write(storage.backup_path(2), "garbage").await.unwrap();
+        assert!(storage.restore_backup(2).await.is_err());
+        assert!(storage.restore_backup(9).await.is_err());
+    }
 }
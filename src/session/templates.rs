@@ -0,0 +1,185 @@
+//! Session templates: reusable settings for sessions created over and over.
+//!
+//! Templates live in `templates.json` in the agent-hand directory and are
+//! shared by every profile. A template's title may contain `{dirname}`,
+//! which becomes the project directory's name when a session is created.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+
+use super::{Instance, Storage};
+use crate::error::{Error, Result};
+use crate::tmux::Tool;
+
+/// Placeholder replaced by the project directory's name.
+pub const DIRNAME_PLACEHOLDER: &str = "{dirname}";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionTemplate {
+    pub name: String,
+    /// Title pattern; may contain `{dirname}`.
+    #[serde(default = "default_title")]
+    pub title: String,
+    #[serde(default)]
+    pub tool: Tool,
+    #[serde(default)]
+    pub command: String,
+    #[serde(default)]
+    pub group_path: String,
+}
+
+fn default_title() -> String {
+    DIRNAME_PLACEHOLDER.to_string()
+}
+
+impl SessionTemplate {
+    /// Capture `inst`'s settings. A title equal to the directory name is
+    /// stored as `{dirname}` so it follows the next project.
+    pub fn from_instance(name: &str, inst: &Instance) -> Self {
+        let title = if inst.title == dirname(&inst.project_path) {
+            DIRNAME_PLACEHOLDER.to_string()
+        } else {
+            inst.title.clone()
+        };
+        Self {
+            name: name.to_string(),
+            title,
+            tool: inst.tool,
+            command: inst.command.clone(),
+            group_path: inst.group_path.clone(),
+        }
+    }
+
+    /// Title for a session in `project_path`.
+    pub fn title_for(&self, project_path: &Path) -> String {
+        expand_title(&self.title, project_path)
+    }
+}
+
+/// Replace `{dirname}` in `pattern` with `project_path`'s last component.
+pub fn expand_title(pattern: &str, project_path: &Path) -> String {
+    pattern.replace(DIRNAME_PLACEHOLDER, &dirname(project_path))
+}
+
+fn dirname(path: &Path) -> String {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("Untitled")
+        .to_string()
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TemplateStore {
+    #[serde(default)]
+    pub templates: Vec<SessionTemplate>,
+}
+
+impl TemplateStore {
+    /// `~/.agent-hand/templates.json`.
+    fn path() -> Result<PathBuf> {
+        Ok(Storage::get_agent_hand_dir()?.join("templates.json"))
+    }
+
+    /// Load the store; empty when the file does not exist yet. A file that
+    /// does not parse is an error, so saving cannot overwrite it.
+    pub async fn load() -> Result<Self> {
+        let path = Self::path()?;
+        let data = match fs::read_to_string(&path).await {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e.into()),
+        };
+        serde_json::from_str(&data)
+            .map_err(|e| Error::config(format!("{} is unreadable: {e}", path.display())))
+    }
+
+    pub async fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?).await?;
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&SessionTemplate> {
+        self.templates.iter().find(|t| t.name == name)
+    }
+
+    /// Add `template`, replacing one with the same name. Returns whether
+    /// one was replaced.
+    pub fn upsert(&mut self, template: SessionTemplate) -> bool {
+        match self.templates.iter_mut().find(|t| t.name == template.name) {
+            Some(slot) => {
+                *slot = template;
+                true
+            }
+            None => {
+                self.templates.push(template);
+                self.templates.sort_by(|a, b| a.name.cmp(&b.name));
+                false
+            }
+        }
+    }
+
+    /// Remove the template called `name`. Returns whether it existed.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let before = self.templates.len();
+        self.templates.retain(|t| t.name != name);
+        self.templates.len() != before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dirname_title_round_trips_through_a_template() {
+        let mut inst = Instance::with_group("api".into(), PathBuf::from("/w/api"), "work".into());
+        inst.command = "claude --continue".into();
+        inst.tool = Tool::Claude;
+
+        let t = SessionTemplate::from_instance("claude-work", &inst);
+        assert_eq!(t.title, "{dirname}");
+        assert_eq!(t.title_for(Path::new("/w/web")), "web");
+        assert_eq!(t.group_path, "work");
+        assert_eq!(t.tool, Tool::Claude);
+
+        inst.title = "api review".into();
+        let t = SessionTemplate::from_instance("review", &inst);
+        assert_eq!(t.title_for(Path::new("/w/web")), "api review");
+    }
+
+    #[test]
+    fn expand_title_replaces_every_placeholder() {
+        assert_eq!(
+            expand_title("{dirname}: fix ({dirname})", Path::new("/w/web")),
+            "web: fix (web)"
+        );
+        assert_eq!(expand_title("plain", Path::new("/w/web")), "plain");
+    }
+
+    #[test]
+    fn upsert_replaces_by_name_and_keeps_names_sorted() {
+        let template = |name: &str, command: &str| SessionTemplate {
+            name: name.into(),
+            title: DIRNAME_PLACEHOLDER.into(),
+            tool: Tool::Shell,
+            command: command.into(),
+            group_path: String::new(),
+        };
+        let mut store = TemplateStore::default();
+        assert!(!store.upsert(template("b", "")));
+        assert!(!store.upsert(template("a", "")));
+        assert!(store.upsert(template("b", "htop")));
+
+        let names: Vec<_> = store.templates.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["a", "b"]);
+        assert_eq!(store.get("b").unwrap().command, "htop");
+        assert!(store.remove("a"));
+        assert!(!store.remove("a"));
+    }
+}
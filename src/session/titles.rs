@@ -0,0 +1,153 @@
+//! Session titles: what a title may be, keeping titles unique within a
+//! group, and finding a session by id or title. Shared by the TUI dialogs
+//! and the CLI.
+
+use super::Instance;
+use crate::error::{Error, Result};
+
+/// Longest title accepted, in characters.
+pub const MAX_TITLE_CHARS: usize = 80;
+
+/// Why a title was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TitleError {
+    Empty,
+    TooLong,
+    /// Another session in the same group already has it.
+    Taken,
+}
+
+impl TitleError {
+    pub fn message(self, zh: bool) -> String {
+        match (self, zh) {
+            (Self::Empty, false) => "Title cannot be empty".to_string(),
+            (Self::Empty, true) => "标题不能为空".to_string(),
+            (Self::TooLong, false) => format!("Title is longer than {MAX_TITLE_CHARS} characters"),
+            (Self::TooLong, true) => format!("标题超过 {MAX_TITLE_CHARS} 个字符"),
+            (Self::Taken, false) => "Another session in this group has this title".to_string(),
+            (Self::Taken, true) => "该分组中已有同名会话".to_string(),
+        }
+    }
+}
+
+/// `title` trimmed, if it is one a session may have.
+pub fn check(title: &str) -> std::result::Result<&str, TitleError> {
+    let title = title.trim();
+    if title.is_empty() {
+        Err(TitleError::Empty)
+    } else if title.chars().count() > MAX_TITLE_CHARS {
+        Err(TitleError::TooLong)
+    } else {
+        Ok(title)
+    }
+}
+
+/// Whether a session other than `except` in `group` has `title`.
+pub fn taken(instances: &[Instance], group: &str, title: &str, except: Option<&str>) -> bool {
+    instances
+        .iter()
+        .any(|s| s.group_path == group && s.title == title && Some(s.id.as_str()) != except)
+}
+
+/// `title`, or the first of "title (2)", "title (3)"… free in `group`.
+pub fn unique(instances: &[Instance], group: &str, title: &str) -> String {
+    if !taken(instances, group, title, None) {
+        return title.to_string();
+    }
+    (2..)
+        .map(|n| format!("{title} ({n})"))
+        .find(|candidate| !taken(instances, group, candidate, None))
+        .expect("some suffix is free")
+}
+
+/// The session `query` names: an exact id, else an exact title, else an id
+/// prefix. A title or prefix shared by several sessions is an error that
+/// lists them.
+pub fn find(instances: &[Instance], query: &str) -> Result<usize> {
+    if let Some(i) = instances.iter().position(|s| s.id == query) {
+        return Ok(i);
+    }
+    let by_title: Vec<usize> = matching(instances, |s| s.title == query);
+    let found = if by_title.is_empty() {
+        matching(instances, |s| s.id.starts_with(query))
+    } else {
+        by_title
+    };
+    match found[..] {
+        [] => Err(Error::SessionNotFound(query.to_string())),
+        [i] => Ok(i),
+        _ => {
+            let names: Vec<String> = found
+                .iter()
+                .map(|&i| format!("{} ({})", instances[i].title, instances[i].id))
+                .collect();
+            Err(Error::InvalidInput(format!(
+                "Session '{query}' is ambiguous, matches: {}",
+                names.join(", ")
+            )))
+        }
+    }
+}
+
+fn matching(instances: &[Instance], pred: impl Fn(&Instance) -> bool) -> Vec<usize> {
+    (0..instances.len())
+        .filter(|&i| pred(&instances[i]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn session(id: &str, title: &str, group: &str) -> Instance {
+        let mut inst = Instance::with_group(title.into(), PathBuf::from("/src"), group.into());
+        inst.id = id.into();
+        inst
+    }
+
+    #[test]
+    fn checks_titles() {
+        assert_eq!(check("  api "), Ok("api"));
+        assert_eq!(check(" \t"), Err(TitleError::Empty));
+        assert_eq!(check(&"x".repeat(MAX_TITLE_CHARS)).map(str::len), Ok(80));
+        assert_eq!(
+            check(&"é".repeat(MAX_TITLE_CHARS + 1)),
+            Err(TitleError::TooLong)
+        );
+    }
+
+    #[test]
+    fn suffixes_titles_taken_in_the_group() {
+        let instances = [
+            session("a1", "api", "work"),
+            session("a2", "api (2)", "work"),
+            session("b1", "web", ""),
+        ];
+        assert_eq!(unique(&instances, "work", "api"), "api (3)");
+        assert_eq!(unique(&instances, "", "api"), "api");
+        assert!(taken(&instances, "work", "api", Some("a2")));
+        assert!(!taken(&instances, "work", "api", Some("a1")));
+    }
+
+    #[test]
+    fn ambiguous_titles_and_prefixes_are_errors() {
+        let instances = [
+            session("abc123", "api", "work"),
+            session("abd456", "api", "play"),
+            session("ff0000", "abc123x", ""),
+        ];
+        assert_eq!(find(&instances, "abc123").unwrap(), 0);
+        assert_eq!(find(&instances, "abc123x").unwrap(), 2);
+        assert_eq!(find(&instances, "abd").unwrap(), 1);
+        assert!(matches!(
+            find(&instances, "zz"),
+            Err(Error::SessionNotFound(_))
+        ));
+
+        let err = find(&instances, "api").unwrap_err().to_string();
+        assert!(err.contains("api (abc123), api (abd456)"), "{err}");
+        let err = find(&instances, "ab").unwrap_err().to_string();
+        assert!(err.contains("ambiguous"), "{err}");
+    }
+}
@@ -0,0 +1,338 @@
+//! Portable export and import of a profile's sessions and groups.
+//!
+//! An export is a self-contained JSON or TOML document holding the group
+//! tree and every session, minus runtime-only state. Importing never touches
+//! tmux: sessions arrive stopped and start on demand like any other.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::instance::generate_id;
+use super::{GroupData, GroupTree, Instance, Status};
+use crate::error::{Error, Result};
+use crate::tmux::TmuxManager;
+
+/// Bumped when the export layout changes incompatibly.
+pub const EXPORT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Toml,
+}
+
+impl Format {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+
+    /// Guess from a file extension; `None` when it says nothing.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        Self::parse(path.extension()?.to_str()?)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Export {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub profile: String,
+    #[serde(default)]
+    pub groups: Vec<GroupData>,
+    #[serde(default)]
+    pub sessions: Vec<Instance>,
+}
+
+impl Export {
+    /// Snapshot `instances` and `tree` for export, dropping runtime state.
+    pub fn new(profile: &str, instances: &[Instance], tree: &GroupTree) -> Self {
+        let sessions = instances
+            .iter()
+            .map(|inst| {
+                let mut inst = inst.clone();
+                inst.status = Status::Idle;
+                inst.last_running_at = None;
+                inst.last_waiting_at = None;
+                inst.status_since = None;
+                inst.sharing = None;
+                // Relationships are not exported, so the link would dangle.
+                inst.relationship_id = None;
+                inst
+            })
+            .collect();
+        Self {
+            version: EXPORT_VERSION,
+            exported_at: Utc::now(),
+            profile: profile.to_string(),
+            groups: tree.all_groups(),
+            sessions,
+        }
+    }
+
+    pub fn render(&self, format: Format) -> Result<String> {
+        match format {
+            Format::Json => Ok(serde_json::to_string_pretty(self)?),
+            Format::Toml => toml::to_string_pretty(self).map_err(|e| Error::Other(e.to_string())),
+        }
+    }
+
+    /// Parse an export. Without a `format`, JSON is tried first, then TOML.
+    pub fn parse(content: &str, format: Option<Format>) -> Result<Self> {
+        let export: Self = match format {
+            Some(Format::Json) => serde_json::from_str(content)?,
+            Some(Format::Toml) => toml::from_str(content)?,
+            None => match serde_json::from_str(content) {
+                Ok(export) => export,
+                Err(json_err) => toml::from_str(content).map_err(|_| Error::Json(json_err))?,
+            },
+        };
+        if export.version > EXPORT_VERSION {
+            return Err(Error::InvalidInput(format!(
+                "export version {} is newer than this build supports ({})",
+                export.version, EXPORT_VERSION
+            )));
+        }
+        Ok(export)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Keep existing sessions; skip imported ones whose project path is
+    /// already used.
+    Merge,
+    /// Drop existing sessions and groups first.
+    Replace,
+}
+
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub imported: usize,
+    /// Titles of sessions skipped as duplicates.
+    pub skipped: Vec<String>,
+    /// Sessions given a fresh id because theirs was taken.
+    pub renamed: usize,
+}
+
+/// Fold `export` into `instances` and `tree`.
+pub fn import(
+    instances: &mut Vec<Instance>,
+    tree: &mut GroupTree,
+    export: Export,
+    mode: ImportMode,
+) -> ImportReport {
+    let mut report = ImportReport::default();
+    if mode == ImportMode::Replace {
+        instances.clear();
+        *tree = GroupTree::new();
+    }
+
+    // Only sessions already here count as duplicates: imported ones may
+    // share a path, as a fork shares its parent's.
+    let paths: HashSet<_> = instances.iter().map(|s| s.project_path.clone()).collect();
+    let mut ids: HashSet<_> = instances.iter().map(|s| s.id.clone()).collect();
+    let mut id_map: HashMap<String, String> = HashMap::new();
+    let mut incoming = Vec::new();
+
+    for mut inst in export.sessions {
+        if paths.contains(&inst.project_path) {
+            report.skipped.push(inst.title);
+            continue;
+        }
+        // The file belongs to the exporting profile and did not come along.
+        inst.mcp_config = None;
+        if ids.contains(&inst.id) {
+            let mut id = generate_id();
+            while ids.contains(&id) {
+                id = generate_id();
+            }
+            id_map.insert(inst.id.clone(), id.clone());
+            inst.id = id;
+            inst.tmux_session_name = Some(TmuxManager::build_session_name(&inst.title, &inst.id));
+            report.renamed += 1;
+        }
+        ids.insert(inst.id.clone());
+        incoming.push(inst);
+    }
+
+    // Parents are remapped when renamed and dropped when they did not come
+    // along and are not already here.
+    for inst in &mut incoming {
+        if let Some(parent) = inst.parent_session_id.take() {
+            let parent = id_map.get(&parent).cloned().unwrap_or(parent);
+            if ids.contains(&parent) {
+                inst.parent_session_id = Some(parent);
+            }
+        }
+    }
+
+    // Exported groups keep their order and expansion; existing ones win.
+    let mut groups = tree.all_groups();
+    let known: HashSet<_> = groups.iter().map(|g| g.path.clone()).collect();
+    groups.extend(
+        export
+            .groups
+            .into_iter()
+            .filter(|g| !known.contains(&g.path)),
+    );
+    let group_paths: Vec<String> = groups.iter().map(|g| g.path.clone()).collect();
+    *tree = GroupTree::from_groups(groups);
+    for path in group_paths {
+        tree.create_group(path);
+    }
+    for inst in &incoming {
+        if !inst.group_path.is_empty() {
+            tree.create_group(inst.group_path.clone());
+        }
+    }
+
+    report.imported = incoming.len();
+    instances.extend(incoming);
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn session(title: &str, path: &str, group: &str) -> Instance {
+        Instance::with_group(title.to_string(), PathBuf::from(path), group.to_string())
+    }
+
+    fn tree(paths: &[&str]) -> GroupTree {
+        let mut tree = GroupTree::new();
+        for p in paths {
+            tree.create_group(p.to_string());
+        }
+        tree
+    }
+
+    #[test]
+    fn round_trip_into_empty_profile_is_identical() {
+        let mut a = session("api", "/w/api", "work");
+        a.status = Status::Running;
+        a.tags = vec!["backend".into()];
+        let mut b = session("web", "/w/web", "work/front");
+        b.set_parent(a.id.clone());
+        let mut src_tree = tree(&["work/front", "misc"]);
+        src_tree.set_expanded("misc", false);
+        let source = vec![a, b];
+
+        for format in [Format::Json, Format::Toml] {
+            let text = Export::new("default", &source, &src_tree)
+                .render(format)
+                .unwrap();
+            let export = Export::parse(&text, None).unwrap();
+
+            let mut instances = Vec::new();
+            let mut dst_tree = GroupTree::new();
+            let report = import(&mut instances, &mut dst_tree, export, ImportMode::Merge);
+            assert_eq!(report.imported, 2);
+            assert_eq!(report.renamed, 0);
+
+            let saved = |list: &[Instance]| {
+                serde_json::to_value(Export::new("default", list, &GroupTree::new()).sessions)
+                    .unwrap()
+            };
+            assert_eq!(saved(&instances), saved(&source));
+            assert_eq!(
+                serde_json::to_value(dst_tree.all_groups()).unwrap(),
+                serde_json::to_value(src_tree.all_groups()).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn export_drops_runtime_state() {
+        let mut a = session("api", "/w/api", "work");
+        a.status = Status::Waiting;
+        a.last_running_at = Some(Utc::now());
+        a.relationship_id = Some("rel".into());
+        let export = Export::new("default", &[a], &GroupTree::new());
+        let s = &export.sessions[0];
+        assert_eq!(s.status, Status::Idle);
+        assert!(s.last_running_at.is_none());
+        assert!(s.relationship_id.is_none());
+    }
+
+    #[test]
+    fn merge_skips_duplicate_paths_and_renames_colliding_ids() {
+        let existing = session("api", "/w/api", "work");
+        let mut clash = session("other", "/w/other", "work");
+        clash.id = existing.id.clone();
+        let mut child = session("child", "/w/child", "work");
+        child.set_parent(clash.id.clone());
+        let dup = session("api again", "/w/api", "work");
+        let export = Export::new("p", &[clash, child, dup], &tree(&["work"]));
+
+        let mut instances = vec![existing.clone()];
+        let mut dst_tree = tree(&["work"]);
+        let report = import(&mut instances, &mut dst_tree, export, ImportMode::Merge);
+
+        assert_eq!(report.imported, 2);
+        assert_eq!(report.skipped, vec!["api again".to_string()]);
+        assert_eq!(report.renamed, 1);
+        let clash = instances.iter().find(|s| s.title == "other").unwrap();
+        assert_ne!(clash.id, existing.id);
+        assert!(clash
+            .tmux_session_name
+            .as_ref()
+            .unwrap()
+            .ends_with(&clash.id[..8]));
+        let child = instances.iter().find(|s| s.title == "child").unwrap();
+        assert_eq!(child.parent_session_id.as_deref(), Some(clash.id.as_str()));
+    }
+
+    #[test]
+    fn replace_drops_existing_sessions_and_groups() {
+        let mut instances = vec![session("old", "/w/old", "legacy")];
+        let mut dst_tree = tree(&["legacy"]);
+        let export = Export::new(
+            "p",
+            &[session("new", "/w/new", "fresh/deep")],
+            &GroupTree::new(),
+        );
+
+        import(&mut instances, &mut dst_tree, export, ImportMode::Replace);
+
+        assert_eq!(instances.len(), 1);
+        assert_eq!(instances[0].title, "new");
+        assert!(dst_tree.get_group("legacy").is_none());
+        assert!(dst_tree.get_group("fresh").is_some());
+        assert!(dst_tree.get_group("fresh/deep").is_some());
+    }
+
+    #[test]
+    fn replace_keeps_forks_that_share_their_parent_path() {
+        let parent = session("api", "/w/api", "work");
+        let mut fork = session("api fork", "/w/api", "work");
+        fork.set_parent(parent.id.clone());
+        let export = Export::new("p", &[parent.clone(), fork], &tree(&["work"]));
+
+        let mut instances = vec![parent.clone()];
+        let mut dst_tree = tree(&["work"]);
+        let report = import(&mut instances, &mut dst_tree, export, ImportMode::Replace);
+
+        assert_eq!(report.imported, 2);
+        assert!(report.skipped.is_empty());
+        assert_eq!(report.renamed, 0);
+        let fork = instances.iter().find(|s| s.title == "api fork").unwrap();
+        assert_eq!(fork.parent_session_id.as_deref(), Some(parent.id.as_str()));
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let mut export = Export::new("p", &[], &GroupTree::new());
+        export.version = EXPORT_VERSION + 1;
+        let text = export.render(Format::Json).unwrap();
+        assert!(Export::parse(&text, None).is_err());
+    }
+}
@@ -0,0 +1,87 @@
+//! The waiting queue: sessions that need input, longest wait first.
+//!
+//! The status bar, the priority jump (tmux key and `W` in the TUI) and
+//! `agent-hand status --json` all read this order, so they agree on which
+//! session is next.
+
+use chrono::{DateTime, Utc};
+
+use super::{Instance, Status};
+
+/// When `inst` started its current wait. Sessions that have not recorded
+/// one fall back to their creation time, which puts them first.
+pub fn waiting_since(inst: &Instance) -> DateTime<Utc> {
+    inst.last_waiting_at.unwrap_or(inst.created_at)
+}
+
+/// Waiting sessions, oldest `last_waiting_at` first, ties broken by title
+/// and then id so the order never depends on storage order.
+pub fn waiting_queue(sessions: &[Instance]) -> Vec<&Instance> {
+    let mut queue: Vec<&Instance> = sessions
+        .iter()
+        .filter(|s| s.status == Status::Waiting)
+        .collect();
+    queue.sort_by(|a, b| {
+        waiting_since(a)
+            .cmp(&waiting_since(b))
+            .then_with(|| a.title.cmp(&b.title))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    queue
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn session(id: &str, title: &str, status: Status, waiting_secs_ago: Option<i64>) -> Instance {
+        let mut inst = Instance::new(title.into(), PathBuf::from("/tmp"));
+        inst.id = id.into();
+        inst.status = status;
+        inst.created_at = Utc::now() - chrono::Duration::days(1);
+        inst.last_waiting_at = waiting_secs_ago.map(|s| Utc::now() - chrono::Duration::seconds(s));
+        inst
+    }
+
+    fn ids(queue: Vec<&Instance>) -> Vec<&str> {
+        queue.into_iter().map(|s| s.id.as_str()).collect()
+    }
+
+    #[test]
+    fn oldest_wait_first_and_only_waiting() {
+        let sessions = [
+            session("new", "a", Status::Waiting, Some(10)),
+            session("running", "b", Status::Running, Some(500)),
+            session("old", "c", Status::Waiting, Some(300)),
+            session("mid", "d", Status::Waiting, Some(60)),
+        ];
+        assert_eq!(ids(waiting_queue(&sessions)), ["old", "mid", "new"]);
+    }
+
+    #[test]
+    fn ties_break_by_title_then_id() {
+        let at = Utc::now();
+        let mut sessions = vec![
+            session("2", "web", Status::Waiting, None),
+            session("1", "web", Status::Waiting, None),
+            session("3", "api", Status::Waiting, None),
+        ];
+        for s in &mut sessions {
+            s.last_waiting_at = Some(at);
+        }
+        assert_eq!(ids(waiting_queue(&sessions)), ["3", "1", "2"]);
+        sessions.reverse();
+        assert_eq!(ids(waiting_queue(&sessions)), ["3", "1", "2"]);
+    }
+
+    #[test]
+    fn unknown_wait_start_counts_from_creation() {
+        let sessions = [
+            session("recent", "a", Status::Waiting, Some(5)),
+            session("legacy", "b", Status::Waiting, None),
+        ];
+        assert_eq!(ids(waiting_queue(&sessions)), ["legacy", "recent"]);
+        assert!(waiting_queue(&[]).is_empty());
+    }
+}
@@ -0,0 +1,356 @@
+//! End-of-day wrap-up: ask working agents to finish, wait for them to go
+//! quiet, then stop their tmux sessions.
+//!
+//! The per-session logic is a small state machine ([`WrapupTracker`]) fed
+//! with status observations, so it can be tested with scripted sequences;
+//! [`run_wrapup`] drives one tracker per session concurrently.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+
+use super::journal::JournalEntry;
+use super::{Instance, Status};
+use crate::tmux::TmuxManager;
+
+pub const DEFAULT_WRAPUP_MESSAGE: &str = "Please wrap up: finish the current step and commit your work.";
+pub const DEFAULT_GRACE_SECS: u64 = 120;
+
+/// How long a quiet agent gets to react to the message before it counts as
+/// finished without ever having started working on it.
+const SETTLE: Duration = Duration::from_secs(10);
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrapupOutcome {
+    /// Not working when the wrap-up started; no message was sent.
+    AlreadyIdle,
+    /// Went quiet within the grace period.
+    Finished,
+    /// Still working when the grace period ran out.
+    TimedOut,
+    /// The tmux session disappeared while we were waiting.
+    Vanished,
+    /// The message could not be delivered; the session is left alone.
+    SendFailed,
+}
+
+impl WrapupOutcome {
+    /// Journal event suffix.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            WrapupOutcome::AlreadyIdle => "already_idle",
+            WrapupOutcome::Finished => "finished",
+            WrapupOutcome::TimedOut => "timed_out",
+            WrapupOutcome::Vanished => "vanished",
+            WrapupOutcome::SendFailed => "send_failed",
+        }
+    }
+
+    /// Whether the session should be killed once the outcome is known.
+    pub fn should_kill(self) -> bool {
+        matches!(
+            self,
+            WrapupOutcome::AlreadyIdle | WrapupOutcome::Finished | WrapupOutcome::TimedOut
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WrapupPhase {
+    /// Message sent; the agent has not been seen working since.
+    Sent,
+    /// The agent picked the message up and is working on it.
+    WaitingForIdle,
+    Done(WrapupOutcome),
+}
+
+/// Outcome decided before sending anything: only agents that are currently
+/// working get the message.
+pub fn initial_outcome(status: Status) -> Option<WrapupOutcome> {
+    match status {
+        Status::Running | Status::Starting => None,
+        Status::Waiting | Status::Idle | Status::Error | Status::Stopped => {
+            Some(WrapupOutcome::AlreadyIdle)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WrapupTracker {
+    phase: WrapupPhase,
+    grace: Duration,
+    settle: Duration,
+}
+
+impl WrapupTracker {
+    pub fn new(grace: Duration) -> Self {
+        Self {
+            phase: WrapupPhase::Sent,
+            grace,
+            settle: SETTLE.min(grace),
+        }
+    }
+
+    pub fn phase(&self) -> WrapupPhase {
+        self.phase
+    }
+
+    /// Feed one observation taken `elapsed` after the wrap-up started.
+    /// `None` means the tmux session no longer exists.
+    pub fn observe(&mut self, status: Option<Status>, elapsed: Duration) -> WrapupPhase {
+        if let WrapupPhase::Done(_) = self.phase {
+            return self.phase;
+        }
+
+        self.phase = match (self.phase, status) {
+            (_, None) => WrapupPhase::Done(WrapupOutcome::Vanished),
+            (_, Some(Status::Running | Status::Starting)) => WrapupPhase::WaitingForIdle,
+            (WrapupPhase::WaitingForIdle, Some(_)) => WrapupPhase::Done(WrapupOutcome::Finished),
+            (_, Some(_)) if elapsed >= self.settle => WrapupPhase::Done(WrapupOutcome::Finished),
+            (phase, Some(_)) => phase,
+        };
+
+        if !matches!(self.phase, WrapupPhase::Done(_)) && elapsed >= self.grace {
+            self.phase = WrapupPhase::Done(WrapupOutcome::TimedOut);
+        }
+        self.phase
+    }
+}
+
+/// Sessions a wrap-up applies to: everything, or one group and its subgroups.
+pub fn select_targets<'a>(instances: &'a [Instance], group: Option<&str>) -> Vec<&'a Instance> {
+    instances
+        .iter()
+        .filter(|inst| match group {
+            None => true,
+            Some(g) => {
+                let g = g.trim_matches('/');
+                inst.group_path == g || inst.group_path.starts_with(&format!("{g}/"))
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct WrapupOptions {
+    pub message: String,
+    pub grace: Duration,
+    /// Kill sessions once they are done (or time out).
+    pub kill: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct WrapupResult {
+    pub id: String,
+    pub title: String,
+    pub outcome: WrapupOutcome,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub killed: bool,
+    pub error: Option<String>,
+}
+
+impl WrapupResult {
+    /// Journal entries for this session: the send (if any) and the outcome.
+    pub fn journal_entries(&self) -> Vec<JournalEntry> {
+        let mut entries = Vec::new();
+        if let Some(at) = self.sent_at {
+            let mut sent = JournalEntry::new("wrapup.sent", &self.id, &self.title, "");
+            sent.at = at;
+            entries.push(sent);
+        }
+        let mut detail = if self.killed { "killed".to_string() } else { String::new() };
+        if let Some(err) = &self.error {
+            if !detail.is_empty() {
+                detail.push_str("; ");
+            }
+            detail.push_str(err);
+        }
+        entries.push(JournalEntry::new(
+            &format!("wrapup.{}", self.outcome.as_str()),
+            &self.id,
+            &self.title,
+            detail,
+        ));
+        entries
+    }
+}
+
+/// Wrap up live sessions among `targets` concurrently. All trackers share
+/// one start time, so `grace` bounds the whole run, not each session.
+/// Sessions with no tmux session are skipped.
+pub async fn run_wrapup(
+    manager: Arc<TmuxManager>,
+    targets: Vec<Instance>,
+    opts: &WrapupOptions,
+) -> Vec<WrapupResult> {
+    let _ = manager.refresh_cache().await;
+    let started = Instant::now();
+
+    let tasks = targets
+        .into_iter()
+        .filter(|inst| manager.session_exists(&inst.tmux_name()).unwrap_or(false))
+        .map(|inst| wrap_one(manager.clone(), inst, opts, started));
+    futures::future::join_all(tasks).await
+}
+
+/// Classify the pane from its content, as the switcher does. Activity
+/// timestamps are no use here: a fresh probe has no previous sample, so it
+/// would report every pane as idle. A pane whose process exited reports
+/// Stopped, or Error if it was running.
+pub async fn probe_status(manager: &TmuxManager, inst: &Instance) -> Status {
+    let target = inst.tmux_target();
+    super::status_engine::probe_pane(manager, &target, inst.tool, inst.status, 15)
+        .await
+        .status
+}
+
+async fn wrap_one(
+    manager: Arc<TmuxManager>,
+    inst: Instance,
+    opts: &WrapupOptions,
+    started: Instant,
+) -> WrapupResult {
+    let name = inst.tmux_name();
+
+    let mut result = WrapupResult {
+        id: inst.id.clone(),
+        title: inst.title.clone(),
+        outcome: WrapupOutcome::AlreadyIdle,
+        sent_at: None,
+        killed: false,
+        error: None,
+    };
+
+    result.outcome = match initial_outcome(probe_status(&manager, &inst).await) {
+        Some(outcome) => outcome,
+        None => match manager.send_text(&name, &opts.message).await {
+            Err(e) => {
+                result.error = Some(e.to_string());
+                WrapupOutcome::SendFailed
+            }
+            Ok(()) => {
+                result.sent_at = Some(Utc::now());
+                let mut tracker = WrapupTracker::new(opts.grace);
+                loop {
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                    let _ = manager.refresh_cache().await;
+                    let observed = if manager.session_exists(&name).unwrap_or(false) {
+                        Some(probe_status(&manager, &inst).await)
+                    } else {
+                        None
+                    };
+                    if let WrapupPhase::Done(outcome) =
+                        tracker.observe(observed, started.elapsed())
+                    {
+                        break outcome;
+                    }
+                }
+            }
+        },
+    };
+
+    if opts.kill && result.outcome.should_kill() {
+        match manager.kill_session(&name).await {
+            Ok(()) => result.killed = true,
+            Err(e) => result.error = Some(e.to_string()),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed `(seconds, status)` observations and return the final phase.
+    fn run_script(grace_secs: u64, script: &[(u64, Option<Status>)]) -> WrapupPhase {
+        let mut tracker = WrapupTracker::new(Duration::from_secs(grace_secs));
+        for &(secs, status) in script {
+            tracker.observe(status, Duration::from_secs(secs));
+        }
+        tracker.phase()
+    }
+
+    #[test]
+    fn working_agent_that_goes_quiet_finishes() {
+        let phase = run_script(
+            120,
+            &[
+                (2, Some(Status::Running)),
+                (4, Some(Status::Running)),
+                (6, Some(Status::Waiting)),
+            ],
+        );
+        assert_eq!(phase, WrapupPhase::Done(WrapupOutcome::Finished));
+    }
+
+    #[test]
+    fn quiet_right_after_send_waits_for_settle() {
+        let mut tracker = WrapupTracker::new(Duration::from_secs(120));
+        assert_eq!(
+            tracker.observe(Some(Status::Idle), Duration::from_secs(2)),
+            WrapupPhase::Sent
+        );
+        assert_eq!(
+            tracker.observe(Some(Status::Idle), Duration::from_secs(12)),
+            WrapupPhase::Done(WrapupOutcome::Finished)
+        );
+    }
+
+    #[test]
+    fn still_running_at_grace_times_out() {
+        let phase = run_script(
+            30,
+            &[
+                (2, Some(Status::Running)),
+                (20, Some(Status::Running)),
+                (30, Some(Status::Running)),
+            ],
+        );
+        assert_eq!(phase, WrapupPhase::Done(WrapupOutcome::TimedOut));
+    }
+
+    #[test]
+    fn session_dying_mid_wait_is_vanished() {
+        let phase = run_script(120, &[(2, Some(Status::Running)), (4, None)]);
+        assert_eq!(phase, WrapupPhase::Done(WrapupOutcome::Vanished));
+    }
+
+    #[test]
+    fn done_is_terminal() {
+        let phase = run_script(
+            120,
+            &[
+                (2, Some(Status::Running)),
+                (4, Some(Status::Idle)),
+                (6, Some(Status::Running)),
+                (200, None),
+            ],
+        );
+        assert_eq!(phase, WrapupPhase::Done(WrapupOutcome::Finished));
+    }
+
+    #[test]
+    fn only_working_agents_get_the_message() {
+        assert_eq!(initial_outcome(Status::Running), None);
+        assert_eq!(initial_outcome(Status::Starting), None);
+        assert_eq!(initial_outcome(Status::Waiting), Some(WrapupOutcome::AlreadyIdle));
+        assert_eq!(initial_outcome(Status::Idle), Some(WrapupOutcome::AlreadyIdle));
+    }
+
+    #[test]
+    fn group_filter_includes_subgroups_only() {
+        let mk = |group: &str| {
+            Instance::with_group("t".into(), std::path::PathBuf::from("/tmp"), group.into())
+        };
+        let instances = vec![mk("work"), mk("work/api"), mk("workshop"), mk("")];
+        let groups: Vec<&str> = select_targets(&instances, Some("work"))
+            .iter()
+            .map(|i| i.group_path.as_str())
+            .collect();
+        assert_eq!(groups, vec!["work", "work/api"]);
+        assert_eq!(select_targets(&instances, None).len(), 4);
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 
@@ -10,6 +10,9 @@ use parking_lot::RwLock;
 #[derive(Debug)]
 pub struct SessionCache {
     data: Arc<RwLock<HashMap<String, i64>>>,
+    /// Targets (`session` or `session:=window`) whose pane outlived its
+    /// process, as of the last update.
+    dead_panes: Arc<RwLock<HashSet<String>>>,
     last_update: Arc<RwLock<Option<SystemTime>>>,
     ttl: Duration,
 }
@@ -18,14 +21,16 @@ impl SessionCache {
     pub fn new() -> Self {
         Self {
             data: Arc::new(RwLock::new(HashMap::new())),
+            dead_panes: Arc::new(RwLock::new(HashSet::new())),
             last_update: Arc::new(RwLock::new(None)),
             ttl: Duration::from_secs(2), // 2 seconds TTL
         }
     }
 
     /// Update cache with new session data
-    pub fn update(&self, sessions: HashMap<String, i64>) {
+    pub fn update(&self, sessions: HashMap<String, i64>, dead_panes: HashSet<String>) {
         *self.data.write() = sessions;
+        *self.dead_panes.write() = dead_panes;
         *self.last_update.write() = Some(SystemTime::now());
     }
 
@@ -45,6 +50,12 @@ impl SessionCache {
         self.data.read().get(name).copied()
     }
 
+    /// Whether `target`'s pane was dead at the last update. Not limited by
+    /// the TTL: a pane only dies once, so an older answer is still useful.
+    pub fn pane_dead(&self, target: &str) -> bool {
+        self.dead_panes.read().contains(target)
+    }
+
     /// Register a newly created session
     pub fn register(&self, name: String) {
         let mut data = self.data.write();
@@ -69,6 +80,7 @@ impl SessionCache {
     /// Clear cache
     pub fn clear(&self) {
         self.data.write().clear();
+        self.dead_panes.write().clear();
         *self.last_update.write() = None;
     }
 }
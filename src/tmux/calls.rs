@@ -0,0 +1,90 @@
+//! Counts of tmux subprocesses this process has started, by kind, for the
+//! perf overlay (F11).
+//!
+//! Every command built by [`super::TmuxManager::tmux_cmd`],
+//! [`super::async_tmux_cmd`] or [`super::sync_tmux_cmd`] counts once; the
+//! hot paths also record their kind. Whatever is left over is "other".
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallKind {
+    /// `list-sessions` for the session cache.
+    List,
+    /// `capture-pane` and pane size probes.
+    Capture,
+    /// Global environment reads and writes.
+    Env,
+    /// `send-keys`.
+    Send,
+}
+
+impl CallKind {
+    const COUNT: usize = 4;
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+static TOTAL: AtomicU64 = AtomicU64::new(0);
+static BY_KIND: [AtomicU64; CallKind::COUNT] = [
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+    AtomicU64::new(0),
+];
+
+/// Count one tmux subprocess. Called by the command builders.
+pub(crate) fn count_spawn() {
+    TOTAL.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Attribute the next counted spawn to `kind`.
+pub(crate) fn record(kind: CallKind) {
+    BY_KIND[kind.index()].fetch_add(1, Ordering::Relaxed);
+}
+
+/// Invocation counts, since start or over a window.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CallCounts {
+    pub list: u64,
+    pub capture: u64,
+    pub env: u64,
+    pub send: u64,
+    pub other: u64,
+}
+
+impl CallCounts {
+    /// Counts since this process started.
+    pub fn snapshot() -> Self {
+        let kind = |k: CallKind| BY_KIND[k.index()].load(Ordering::Relaxed);
+        let list = kind(CallKind::List);
+        let capture = kind(CallKind::Capture);
+        let env = kind(CallKind::Env);
+        let send = kind(CallKind::Send);
+        let total = TOTAL.load(Ordering::Relaxed);
+        Self {
+            list,
+            capture,
+            env,
+            send,
+            other: total.saturating_sub(list + capture + env + send),
+        }
+    }
+
+    /// Calls made between `earlier` and `self`.
+    pub fn since(&self, earlier: &Self) -> Self {
+        Self {
+            list: self.list.saturating_sub(earlier.list),
+            capture: self.capture.saturating_sub(earlier.capture),
+            env: self.env.saturating_sub(earlier.env),
+            send: self.send.saturating_sub(earlier.send),
+            other: self.other.saturating_sub(earlier.other),
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.list + self.capture + self.env + self.send + self.other
+    }
+}
@@ -1,5 +1,6 @@
 use parking_lot::RwLock;
 use regex::Regex;
+use std::collections::HashMap;
 use std::fmt;
 use std::sync::OnceLock;
 
@@ -32,7 +33,18 @@ impl Default for Tool {
 }
 
 impl Tool {
-    #[allow(dead_code)]
+    /// Parse a tool name as written in config and on the command line.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "claude" => Some(Self::Claude),
+            "gemini" => Some(Self::Gemini),
+            "opencode" => Some(Self::OpenCode),
+            "codex" => Some(Self::Codex),
+            "shell" => Some(Self::Shell),
+            _ => None,
+        }
+    }
+
     pub fn from_command(cmd: &str) -> Self {
         let cmd_lower = cmd.to_lowercase();
         if cmd_lower.contains("claude") {
@@ -50,68 +62,199 @@ impl Tool {
 }
 
 /// Prompt detector - identifies when AI agents are waiting for input
-/// Uses unified pattern matching across all tools (Claude, Copilot, OpenCode, etc.)
-pub struct PromptDetector;
+/// Uses unified pattern matching across all tools (Claude, Copilot, OpenCode, etc.),
+/// plus whatever `status_detection` adds for the detector's tool.
+pub struct PromptDetector {
+    tool: Tool,
+}
 
-#[derive(Debug, Clone)]
+/// User patterns from one `status_detection` table, compiled.
+#[derive(Debug, Clone, Default)]
+struct Patterns {
+    prompt_contains: Vec<String>,
+    prompt_regex: Vec<Regex>,
+    busy_contains: Vec<String>,
+    busy_regex: Vec<Regex>,
+}
+
+impl Patterns {
+    /// Compile `raw`, skipping (and describing in `invalid`) bad regexes.
+    fn compile(
+        raw: &crate::config::StatusDetectionConfig,
+        scope: &str,
+        invalid: &mut Vec<String>,
+    ) -> Self {
+        let mut regexes = |list: &[String]| -> Vec<Regex> {
+            list.iter()
+                .filter_map(|p| match Regex::new(p) {
+                    Ok(re) => Some(re),
+                    Err(e) => {
+                        let reason = e.to_string();
+                        let reason = reason.lines().last().unwrap_or_default().trim();
+                        invalid.push(format!("{scope}: {p:?}: {reason}"));
+                        None
+                    }
+                })
+                .collect()
+        };
+        let lower =
+            |list: &[String]| -> Vec<String> { list.iter().map(|s| s.to_lowercase()).collect() };
+        Self {
+            prompt_regex: regexes(&raw.prompt_regex),
+            busy_regex: regexes(&raw.busy_regex),
+            prompt_contains: lower(&raw.prompt_contains),
+            busy_contains: lower(&raw.busy_contains),
+        }
+    }
+
+    fn find(contains: &[String], regex: &[Regex], raw: &str, lower: &str) -> Option<String> {
+        contains
+            .iter()
+            .find(|p| lower.contains(p.as_str()))
+            .cloned()
+            .or_else(|| {
+                regex
+                    .iter()
+                    .find(|re| re.is_match(raw))
+                    .map(|re| re.as_str().to_string())
+            })
+    }
+}
+
+/// Compiled `status_detection`: global patterns plus per-tool ones.
+#[derive(Debug, Clone, Default)]
 pub struct StatusDetectionConfig {
-    pub prompt_contains: Vec<String>,
-    pub prompt_regex: Vec<Regex>,
-    pub busy_contains: Vec<String>,
-    pub busy_regex: Vec<Regex>,
+    global: Patterns,
+    tools: HashMap<String, Patterns>,
+}
+
+impl StatusDetectionConfig {
+    /// Compile `raw`. Invalid regexes and unknown tool names are left out
+    /// and described in the returned list, one entry each.
+    pub fn compile(raw: &crate::config::StatusDetectionConfig) -> (Self, Vec<String>) {
+        let mut invalid = Vec::new();
+        let global = Patterns::compile(raw, "status_detection", &mut invalid);
+        let mut tools = HashMap::new();
+        for (name, table) in &raw.tools {
+            let name = name.to_lowercase();
+            let scope = format!("status_detection.tools.{name}");
+            if Tool::from_name(&name).is_none() {
+                invalid.push(format!("{scope}: unknown tool"));
+                continue;
+            }
+            tools.insert(name, Patterns::compile(table, &scope, &mut invalid));
+        }
+        (Self { global, tools }, invalid)
+    }
+
+    /// User pattern sets that apply to `tool`, most specific first.
+    fn for_tool(&self, tool: Tool) -> Vec<(String, &Patterns)> {
+        let mut sets = Vec::with_capacity(2);
+        if let Some(p) = self.tools.get(&tool.to_string()) {
+            sets.push((format!("status_detection.tools.{tool}"), p));
+        }
+        sets.push(("status_detection".to_string(), &self.global));
+        sets
+    }
+}
+
+/// The rule that decided a status, as reported by `agent-hand detect`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternMatch {
+    /// `status_detection.tools.<tool>`, `status_detection` or `built-in`.
+    pub source: String,
+    pub pattern: String,
+}
+
+impl PatternMatch {
+    fn built_in(pattern: &str) -> Self {
+        Self {
+            source: "built-in".to_string(),
+            pattern: pattern.to_string(),
+        }
+    }
 }
 
 static DETECTION_CONFIG: OnceLock<RwLock<StatusDetectionConfig>> = OnceLock::new();
 
-pub fn set_status_detection_config(
-    raw: &crate::config::StatusDetectionConfig,
-) -> Result<(), regex::Error> {
-    let prompt_regex = raw
-        .prompt_regex
-        .iter()
-        .map(|p| Regex::new(p))
-        .collect::<Result<Vec<_>, _>>()?;
-    let busy_regex = raw
-        .busy_regex
-        .iter()
-        .map(|p| Regex::new(p))
-        .collect::<Result<Vec<_>, _>>()?;
-
-    let cfg = StatusDetectionConfig {
-        prompt_contains: raw
-            .prompt_contains
-            .iter()
-            .map(|s| s.to_lowercase())
-            .collect(),
-        prompt_regex,
-        busy_contains: raw.busy_contains.iter().map(|s| s.to_lowercase()).collect(),
-        busy_regex,
-    };
-
-    let lock = DETECTION_CONFIG.get_or_init(|| {
-        RwLock::new(StatusDetectionConfig {
-            prompt_contains: Vec::new(),
-            prompt_regex: Vec::new(),
-            busy_contains: Vec::new(),
-            busy_regex: Vec::new(),
-        })
-    });
+/// Install the user's `status_detection` patterns for every detector in
+/// this process. Bad entries are skipped; their descriptions are returned
+/// so the caller can warn once.
+pub fn set_status_detection_config(raw: &crate::config::StatusDetectionConfig) -> Vec<String> {
+    let (cfg, invalid) = StatusDetectionConfig::compile(raw);
+    let lock = DETECTION_CONFIG.get_or_init(|| RwLock::new(StatusDetectionConfig::default()));
     *lock.write() = cfg;
-    Ok(())
+    invalid
 }
 
 impl PromptDetector {
-    pub fn new(_tool: Tool) -> Self {
-        // Tool parameter kept for API compatibility but no longer used for dispatch
-        Self
+    pub fn new(tool: Tool) -> Self {
+        Self { tool }
     }
 
     /// Check if terminal content shows the agent is currently busy (running/thinking).
     pub fn is_busy(&self, content: &str) -> bool {
+        self.busy_match(content).is_some()
+    }
+
+    /// Check if terminal content shows a prompt waiting for user input.
+    pub fn has_prompt(&self, content: &str) -> bool {
+        self.prompt_match(content).is_some()
+    }
+
+    /// The rule that makes `content` look busy, if any. User patterns are
+    /// consulted before the built-in ones.
+    pub fn busy_match(&self, content: &str) -> Option<PatternMatch> {
+        match DETECTION_CONFIG.get() {
+            Some(cfg) => self.busy_match_with(content, &cfg.read()),
+            None => self.busy_match_with(content, &StatusDetectionConfig::default()),
+        }
+    }
+
+    /// The rule that makes `content` look like a prompt, if any. User
+    /// patterns are consulted before the built-in ones.
+    pub fn prompt_match(&self, content: &str) -> Option<PatternMatch> {
+        match DETECTION_CONFIG.get() {
+            Some(cfg) => self.prompt_match_with(content, &cfg.read()),
+            None => self.prompt_match_with(content, &StatusDetectionConfig::default()),
+        }
+    }
+
+    /// One line saying what the prompt in `content` asks: the last line
+    /// with a question mark up to the one the prompt rule matched, else
+    /// that line itself. ANSI codes and dialog borders are stripped and
+    /// the line is cut to [`SUMMARY_CHARS`]. None when there is no prompt.
+    pub fn waiting_summary(&self, content: &str) -> Option<String> {
+        let pattern = self.prompt_match(content)?.pattern.to_lowercase();
+        let recent = strip_ansi(&get_last_lines(content, 35).join("\n"));
+        let lines: Vec<&str> = recent.lines().collect();
+        // Regex rules rarely appear verbatim; fall back to the last line.
+        let matched = lines
+            .iter()
+            .rposition(|l| l.to_lowercase().contains(&pattern))
+            .or(lines.len().checked_sub(1))?;
+        let question = lines[..=matched].iter().rposition(|l| l.contains('?'));
+        let summary = summary_line(lines[question.unwrap_or(matched)]);
+        (!summary.is_empty()).then_some(summary)
+    }
+
+    fn busy_match_with(&self, content: &str, cfg: &StatusDetectionConfig) -> Option<PatternMatch> {
         let lines = get_last_lines(content, 35);
         let recent_raw = strip_ansi(&lines.join("\n"));
         let recent = recent_raw.to_lowercase();
 
+        for (source, patterns) in cfg.for_tool(self.tool) {
+            let found = Patterns::find(
+                &patterns.busy_contains,
+                &patterns.busy_regex,
+                &recent_raw,
+                &recent,
+            );
+            if let Some(pattern) = found {
+                return Some(PatternMatch { source, pattern });
+            }
+        }
+
         // Busy indicators across all tools
         // NOTE: avoid matching Copilot CLI footer hints like "ctrl+c ..." which can appear while idle.
         let busy_indicators = [
@@ -121,8 +264,8 @@ impl PromptDetector {
             "esc to cancel",
             "(esc to cancel)",
         ];
-        if busy_indicators.iter().any(|m| recent.contains(m)) {
-            return true;
+        if let Some(m) = busy_indicators.iter().find(|m| recent.contains(*m)) {
+            return Some(PatternMatch::built_in(m));
         }
 
         // Spinner characters (Claude braille dots)
@@ -135,7 +278,7 @@ impl PromptDetector {
         for line in last_3 {
             for c in &spinner_chars {
                 if line.contains(*c) {
-                    return true;
+                    return Some(PatternMatch::built_in(&format!("spinner {c}")));
                 }
             }
         }
@@ -143,14 +286,14 @@ impl PromptDetector {
         // OpenCode/Copilot progress dots
         let dots = recent.chars().filter(|&c| c == '⬝').count();
         if dots >= 3 {
-            return true;
+            return Some(PatternMatch::built_in("progress dots ⬝⬝⬝"));
         }
 
         // Thinking/connecting indicators
         if (recent.contains("thinking") && recent.contains("tokens"))
             || (recent.contains("connecting") && recent.contains("tokens"))
         {
-            return true;
+            return Some(PatternMatch::built_in("thinking/connecting + tokens"));
         }
 
         // Some tools show "ctrl+c ..." help text even when idle; only treat it as busy when paired
@@ -161,28 +304,33 @@ impl PromptDetector {
                 || recent.contains("connecting")
                 || recent.contains("tokens"))
         {
-            return true;
+            return Some(PatternMatch::built_in("ctrl+c to interrupt + activity"));
         }
 
-        if let Some(cfg) = DETECTION_CONFIG.get() {
-            let cfg = cfg.read();
-            if cfg.busy_contains.iter().any(|p| recent.contains(p)) {
-                return true;
-            }
-            if cfg.busy_regex.iter().any(|re| re.is_match(&recent_raw)) {
-                return true;
-            }
-        }
-
-        false
+        None
     }
 
-    /// Check if terminal content shows a prompt waiting for user input.
-    pub fn has_prompt(&self, content: &str) -> bool {
+    fn prompt_match_with(
+        &self,
+        content: &str,
+        cfg: &StatusDetectionConfig,
+    ) -> Option<PatternMatch> {
         let lines = get_last_lines(content, 35);
         let recent = strip_ansi(&lines.join("\n"));
         let recent_lower = recent.to_lowercase();
 
+        for (source, patterns) in cfg.for_tool(self.tool) {
+            let found = Patterns::find(
+                &patterns.prompt_contains,
+                &patterns.prompt_regex,
+                &recent,
+                &recent_lower,
+            );
+            if let Some(pattern) = found {
+                return Some(PatternMatch { source, pattern });
+            }
+        }
+
         // Blocking confirmation prompts (all tools)
         let blocking_prompts = [
             // Claude permission dialogs
@@ -213,38 +361,34 @@ impl PromptDetector {
             "press enter to confirm",
             "press enter to confirm or esc to cancel",
         ];
-
-        if blocking_prompts.iter().any(|p| recent_lower.contains(p)) {
-            return true;
-        }
-
         // Selection prompts with arrow indicator (Claude/Copilot numbered options)
         let selection_indicators = ["❯ yes", "❯ no", "❯ allow", "❯ 1.", "❯ 2.", "❯ 3."];
-        if selection_indicators
-            .iter()
-            .any(|p| recent_lower.contains(p))
-        {
-            return true;
-        }
-
         // Box-drawing prompts (Claude dialog boxes)
         let box_prompts = ["│ do you want", "│ would you like", "│ allow"];
-        if box_prompts.iter().any(|p| recent_lower.contains(p)) {
-            return true;
-        }
 
-        if let Some(cfg) = DETECTION_CONFIG.get() {
-            let cfg = cfg.read();
-            if cfg.prompt_contains.iter().any(|p| recent_lower.contains(p)) {
-                return true;
-            }
-            if cfg.prompt_regex.iter().any(|re| re.is_match(&recent)) {
-                return true;
-            }
-        }
+        blocking_prompts
+            .iter()
+            .chain(&selection_indicators)
+            .chain(&box_prompts)
+            .find(|p| recent_lower.contains(*p))
+            .map(|p| PatternMatch::built_in(p))
+    }
+}
+
+/// Longest [`PromptDetector::waiting_summary`], in characters.
+pub const SUMMARY_CHARS: usize = 80;
 
-        false
+/// `line` without dialog borders or the selection arrow, cut to
+/// [`SUMMARY_CHARS`].
+fn summary_line(line: &str) -> String {
+    let line = line.trim_matches(|c: char| {
+        c.is_whitespace() || matches!(c, '│' | '╭' | '╮' | '╰' | '╯' | '─' | '❯')
+    });
+    if line.chars().count() <= SUMMARY_CHARS {
+        return line.to_string();
     }
+    let cut: String = line.chars().take(SUMMARY_CHARS - 1).collect();
+    format!("{}…", cut.trim_end())
 }
 
 /// Strip ANSI escape codes from content
@@ -315,8 +459,9 @@ mod tests {
             prompt_regex: vec!["Do you want to proceed\\?".to_string()],
             busy_contains: vec!["building project".to_string()],
             busy_regex: vec!["\\bcompiling\\b".to_string()],
+            ..Default::default()
         };
-        set_status_detection_config(&cfg).unwrap();
+        assert!(set_status_detection_config(&cfg).is_empty());
 
         let detector = PromptDetector::new(Tool::Shell);
         assert!(detector.has_prompt("Press Enter to confirm or Esc to cancel"));
@@ -325,6 +470,123 @@ mod tests {
         assert!(detector.is_busy("compiling crate foo"));
     }
 
+    #[test]
+    fn tool_patterns_apply_to_their_tool_first() {
+        let claude_only = crate::config::StatusDetectionConfig {
+            prompt_regex: vec!["実行しますか".to_string()],
+            ..Default::default()
+        };
+        let raw = crate::config::StatusDetectionConfig {
+            prompt_contains: vec!["allow once".to_string()],
+            tools: HashMap::from([("Claude".to_string(), claude_only)]),
+            ..Default::default()
+        };
+        let (cfg, invalid) = StatusDetectionConfig::compile(&raw);
+        assert!(invalid.is_empty());
+
+        let claude = PromptDetector::new(Tool::Claude);
+        let m = claude
+            .prompt_match_with("このコマンドを実行しますか？", &cfg)
+            .unwrap();
+        assert_eq!(m.source, "status_detection.tools.claude");
+        assert!(PromptDetector::new(Tool::Codex)
+            .prompt_match_with("このコマンドを実行しますか？", &cfg)
+            .is_none());
+
+        // User patterns are consulted before the built-in list.
+        let m = claude.prompt_match_with("Yes, allow once", &cfg).unwrap();
+        assert_eq!(m.source, "status_detection");
+        let m = claude
+            .prompt_match_with("Yes, allow once", &StatusDetectionConfig::default())
+            .unwrap();
+        assert_eq!(m, PatternMatch::built_in("yes, allow once"));
+    }
+
+    #[test]
+    fn invalid_patterns_are_skipped_and_reported() {
+        let raw = crate::config::StatusDetectionConfig {
+            busy_regex: vec!["(unclosed".to_string(), "\\bbuilding\\b".to_string()],
+            tools: HashMap::from([("vim".to_string(), Default::default())]),
+            ..Default::default()
+        };
+        let (cfg, invalid) = StatusDetectionConfig::compile(&raw);
+        assert_eq!(invalid.len(), 2, "{invalid:?}");
+        assert!(invalid[0].starts_with("status_detection: \"(unclosed\""));
+        assert_eq!(invalid[1], "status_detection.tools.vim: unknown tool");
+
+        let m = PromptDetector::new(Tool::Shell)
+            .busy_match_with("building", &cfg)
+            .unwrap();
+        assert_eq!(m.pattern, "\\bbuilding\\b");
+    }
+
+    #[test]
+    fn waiting_summary_claude_permission_dialog() {
+        let pane = "\
+● I'll create a script to look at the tables.
+
+╭──────────────────────────────────────────────────────────────────────╮
+│ Create file                                                          │
+│ ╭──────────────────────────────────────────────────────────────────╮ │
+│ │ explore_db.py                                                    │ │
+│ ╰──────────────────────────────────────────────────────────────────╯ │
+│ Do you want to create \x1b[1mexplore_db.py\x1b[0m?                         │
+│ ❯ 1. Yes                                                             │
+│   2. Yes, and don't ask again this session (shift+tab)               │
+│   3. No, and tell Claude what to do differently (esc)                │
+╰──────────────────────────────────────────────────────────────────────╯
+";
+        let detector = PromptDetector::new(Tool::Claude);
+        assert_eq!(
+            detector.waiting_summary(pane).as_deref(),
+            Some("Do you want to create explore_db.py?")
+        );
+        // Busy output is not a prompt.
+        assert_eq!(
+            detector.waiting_summary("✻ Thinking… (12s · 340 tokens · esc to interrupt)"),
+            None
+        );
+    }
+
+    #[test]
+    fn waiting_summary_gemini_confirmation() {
+        let pane = "\
+ ╭─────────────────────────────────────────────────────────────────────╮
+ │ ?  Shell cargo test --workspace (Run the test suite)                 │
+ │                                                                     │
+ │   cargo test --workspace                                            │
+ │                                                                     │
+ │ Allow execution of: 'cargo'?                                        │
+ │                                                                     │
+ │ ● 1. Yes, allow once                                                │
+ │   2. Yes, allow always ...                                          │
+ │   3. No, suggest changes (esc)                                      │
+ ╰─────────────────────────────────────────────────────────────────────╯
+";
+        let detector = PromptDetector::new(Tool::Gemini);
+        assert_eq!(
+            detector.waiting_summary(pane).as_deref(),
+            Some("Allow execution of: 'cargo'?")
+        );
+    }
+
+    #[test]
+    fn waiting_summary_falls_back_to_matched_line_and_truncates() {
+        let detector = PromptDetector::new(Tool::Shell);
+        assert_eq!(
+            detector
+                .waiting_summary("Building...\nPress Enter to confirm or Esc to cancel")
+                .as_deref(),
+            Some("Press Enter to confirm or Esc to cancel")
+        );
+
+        let long = format!("Overwrite {}? (y/n)", "very/long/path/".repeat(10));
+        let summary = detector.waiting_summary(&long).unwrap();
+        assert_eq!(summary.chars().count(), SUMMARY_CHARS);
+        assert!(summary.starts_with("Overwrite very/long/path/"));
+        assert!(summary.ends_with('…'));
+    }
+
     #[test]
     fn test_strip_ansi() {
         let input = "\x1b[32mGreen text\x1b[0m";
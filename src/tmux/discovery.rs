@@ -0,0 +1,507 @@
+//! Discovery of agent CLIs running outside agent-hand's tmux server.
+//!
+//! Opt-in via `discovery.enabled`. Each pass takes one `ps` snapshot, drops
+//! every process inside our panes' process trees (and our own, both via
+//! `ptmx::collect_process_tree`), and reports
+//! the remaining top-level claude/codex/gemini processes as adoption
+//! candidates. Scanning runs on a background task; the UI only reads the
+//! shared state.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use tokio::process::Command;
+use tokio::sync::RwLock;
+
+use super::detector::Tool;
+use crate::error::Result;
+
+/// Agent binaries we look for, by executable name.
+const AGENT_BINARIES: &[(&str, Tool)] = &[
+    ("claude", Tool::Claude),
+    ("codex", Tool::Codex),
+    ("gemini", Tool::Gemini),
+];
+
+/// Interpreters an agent CLI may be launched through (`node …/claude`).
+const INTERPRETERS: &[&str] = &["node", "bun", "deno", "python", "python3"];
+
+/// One row of a `ps` snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessEntry {
+    pub pid: u32,
+    pub ppid: u32,
+    /// Seconds since the process started.
+    pub elapsed_secs: u64,
+    pub args: String,
+}
+
+/// An agent process not attributable to any managed session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnmanagedAgent {
+    pub pid: u32,
+    pub tool: Tool,
+    pub cwd: Option<PathBuf>,
+    pub started_at: DateTime<Utc>,
+}
+
+/// Shared discovery state, written by the background task, read by the UI.
+#[derive(Debug, Clone, Default)]
+pub struct DiscoveryState {
+    pub agents: Vec<UnmanagedAgent>,
+    pub last_scan: Option<Instant>,
+}
+
+pub type SharedDiscoveryState = Arc<RwLock<DiscoveryState>>;
+
+/// Parse `ps` `etime` (`[[dd-]hh:]mm:ss`) into seconds.
+pub fn parse_etime(s: &str) -> Option<u64> {
+    let (days, rest) = match s.trim().split_once('-') {
+        Some((d, rest)) => (d.parse::<u64>().ok()?, rest),
+        None => (0, s.trim()),
+    };
+    let parts: Vec<u64> = rest
+        .split(':')
+        .map(|p| p.parse::<u64>().ok())
+        .collect::<Option<_>>()?;
+    let (h, m, sec) = match parts.as_slice() {
+        [m, s] => (0, *m, *s),
+        [h, m, s] => (*h, *m, *s),
+        _ => return None,
+    };
+    Some(days * 86_400 + h * 3_600 + m * 60 + sec)
+}
+
+/// Parse `ps -Ao pid=,ppid=,etime=,args=` output. Malformed lines are skipped.
+pub fn parse_ps_output(out: &str) -> Vec<ProcessEntry> {
+    out.lines()
+        .filter_map(|line| {
+            let mut cols = line.split_whitespace();
+            let pid = cols.next()?.parse().ok()?;
+            let ppid = cols.next()?.parse().ok()?;
+            let elapsed_secs = parse_etime(cols.next()?)?;
+            let args = cols.collect::<Vec<_>>().join(" ");
+            Some(ProcessEntry {
+                pid,
+                ppid,
+                elapsed_secs,
+                args,
+            })
+        })
+        .collect()
+}
+
+fn basename(arg: &str) -> &str {
+    Path::new(arg)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(arg)
+}
+
+/// Which agent a command line runs, judged by the executable name (or the
+/// script name when launched through an interpreter).
+pub fn agent_tool(args: &str) -> Option<Tool> {
+    let mut argv = args.split_whitespace();
+    let mut exe = basename(argv.next()?);
+    if INTERPRETERS.contains(&exe) {
+        exe = basename(argv.find(|a| !a.starts_with('-'))?);
+    }
+    AGENT_BINARIES
+        .iter()
+        .find(|(name, _)| *name == exe)
+        .map(|(_, tool)| *tool)
+}
+
+/// Agent processes outside every excluded tree.
+///
+/// `excluded` holds the process trees of our tmux panes and of agent-hand
+/// itself, as [`collect_process_tree`](super::ptmx::collect_process_tree)
+/// found them. A process below an excluded one counts as excluded too, so a
+/// child that started after its tree was collected is not misreported. An
+/// agent whose ancestor is itself an agent (subagents, helper processes) is
+/// folded into that ancestor so each external run is reported once.
+pub fn find_unmanaged(
+    procs: &[ProcessEntry],
+    excluded: &HashSet<u32>,
+) -> Vec<(ProcessEntry, Tool)> {
+    let by_pid: HashMap<u32, &ProcessEntry> = procs.iter().map(|p| (p.pid, p)).collect();
+    let tools: HashMap<u32, Tool> = procs
+        .iter()
+        .filter_map(|p| agent_tool(&p.args).map(|t| (p.pid, t)))
+        .collect();
+
+    // Whether any ancestor of `p` satisfies `pred`; stops at parent cycles.
+    let has_ancestor = |p: &ProcessEntry, pred: &dyn Fn(u32) -> bool| {
+        let mut seen = HashSet::from([p.pid]);
+        let mut cur = p.ppid;
+        while let Some(parent) = by_pid.get(&cur) {
+            if !seen.insert(parent.pid) {
+                return false;
+            }
+            if pred(parent.pid) {
+                return true;
+            }
+            cur = parent.ppid;
+        }
+        false
+    };
+    let is_excluded = |p: &ProcessEntry| {
+        excluded.contains(&p.pid) || has_ancestor(p, &|pid| excluded.contains(&pid))
+    };
+
+    let mut out: Vec<(ProcessEntry, Tool)> = procs
+        .iter()
+        .filter_map(|p| tools.get(&p.pid).map(|t| (p, *t)))
+        .filter(|(p, _)| !is_excluded(p))
+        .filter(|(p, _)| !has_ancestor(p, &|pid| tools.contains_key(&pid)))
+        .map(|(p, t)| (p.clone(), t))
+        .collect();
+    out.sort_by_key(|(p, _)| p.pid);
+    out
+}
+
+async fn process_snapshot() -> Vec<ProcessEntry> {
+    let Ok(out) = Command::new("ps")
+        .args(["-Ao", "pid=,ppid=,etime=,args="])
+        .output()
+        .await
+    else {
+        return Vec::new();
+    };
+    if !out.status.success() {
+        return Vec::new();
+    }
+    parse_ps_output(&String::from_utf8_lossy(&out.stdout))
+}
+
+async fn process_cwd(pid: u32) -> Option<PathBuf> {
+    #[cfg(target_os = "linux")]
+    {
+        if let Ok(path) = tokio::fs::read_link(format!("/proc/{pid}/cwd")).await {
+            return Some(path);
+        }
+    }
+
+    // lsof -Fn prints one field per line; the cwd path is the `n` field.
+    let out = Command::new("lsof")
+        .args(["-a", "-p", &pid.to_string(), "-d", "cwd", "-Fn"])
+        .output()
+        .await
+        .ok()?;
+    String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .find_map(|l| l.strip_prefix('n'))
+        .map(PathBuf::from)
+}
+
+/// One discovery pass against the live system.
+pub async fn scan_unmanaged_agents(server_name: &str) -> Vec<UnmanagedAgent> {
+    let (procs, panes) = tokio::join!(
+        process_snapshot(),
+        super::ptmx::get_tmux_pane_pids(server_name)
+    );
+
+    // Collected after the snapshot, so every managed process in it is
+    // either in these trees or already gone.
+    let mut excluded = HashSet::new();
+    let roots = panes.into_iter().map(|(_, pid)| pid);
+    for root in roots.chain(std::iter::once(std::process::id())) {
+        excluded.extend(super::ptmx::collect_process_tree(root).await);
+    }
+
+    let now = Utc::now();
+    let mut agents = Vec::new();
+    for (p, tool) in find_unmanaged(&procs, &excluded) {
+        agents.push(UnmanagedAgent {
+            pid: p.pid,
+            tool,
+            cwd: process_cwd(p.pid).await,
+            started_at: now - chrono::Duration::seconds(p.elapsed_secs as i64),
+        });
+    }
+    agents
+}
+
+/// Spawn the periodic discovery task. The first pass runs after a short
+/// delay so startup isn't slowed down.
+pub fn spawn_discovery_monitor(
+    every: Duration,
+    state: SharedDiscoveryState,
+    server_name: String,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_secs(15)).await;
+        let mut interval = tokio::time::interval(every);
+        loop {
+            interval.tick().await;
+            let agents = scan_unmanaged_agents(&server_name).await;
+            let mut guard = state.write().await;
+            guard.agents = agents;
+            guard.last_scan = Some(Instant::now());
+        }
+    })
+}
+
+/// Shells a pane idles in; adopting one of these needs no command.
+const SHELLS: &[&str] = &["bash", "zsh", "fish", "sh", "dash", "ksh", "tcsh", "nu"];
+
+/// A session on a tmux server other than agent-hand's, for `agent-hand adopt`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForeignSession {
+    pub name: String,
+    /// The active pane's working directory.
+    pub path: PathBuf,
+    /// The active pane's foreground command, e.g. `zsh` or `claude`.
+    pub command: String,
+    /// The command line the pane was started with, arguments included;
+    /// empty when it started the default shell.
+    pub start_command: String,
+}
+
+impl ForeignSession {
+    /// Tool and command for a session adopting this one: the pane's start
+    /// command with its arguments, unless that was a shell; otherwise an
+    /// agent CLI in the foreground runs as that tool, a shell needs no
+    /// command, and anything else is kept as is.
+    pub fn adopted_command(&self) -> (Tool, String) {
+        let start = unquote(self.start_command.trim());
+        let start_exe = basename(start.split_whitespace().next().unwrap_or(""));
+        if !start.is_empty() && !SHELLS.contains(&start_exe.trim_start_matches('-')) {
+            return (agent_tool(&start).unwrap_or(Tool::from_command(start_exe)), start);
+        }
+        if let Some(tool) = agent_tool(&self.command) {
+            return (tool, tool.to_string());
+        }
+        let exe = basename(self.command.trim()).trim_start_matches('-');
+        if exe.is_empty() || SHELLS.contains(&exe) {
+            (Tool::Shell, String::new())
+        } else {
+            (Tool::from_command(exe), exe.to_string())
+        }
+    }
+}
+
+/// tmux shows a start command given as one string in double quotes.
+fn unquote(command: &str) -> String {
+    match command.strip_prefix('"').and_then(|c| c.strip_suffix('"')) {
+        Some(inner) => inner.replace("\\\"", "\""),
+        None => command.to_string(),
+    }
+}
+
+/// Parse `list-sessions -F` output in [`FOREIGN_FORMAT`].
+pub fn parse_foreign_sessions(out: &str) -> Vec<ForeignSession> {
+    out.lines()
+        .filter_map(|line| {
+            let mut cols = line.splitn(4, '\t');
+            let name = cols.next()?.to_string();
+            let path = PathBuf::from(cols.next()?);
+            let command = cols.next().unwrap_or("").to_string();
+            let start_command = cols.next().unwrap_or("").to_string();
+            (!name.is_empty()).then_some(ForeignSession {
+                name,
+                path,
+                command,
+                start_command,
+            })
+        })
+        .collect()
+}
+
+/// Name, working directory, foreground command and start command of each
+/// session's active pane.
+const FOREIGN_FORMAT: &str =
+    "#{session_name}\t#{pane_current_path}\t#{pane_current_command}\t#{pane_start_command}";
+
+fn foreign_tmux_cmd(server: &str) -> Command {
+    super::calls::count_spawn();
+    let mut cmd = Command::new("tmux");
+    // Always explicit: inside one of our panes, `$TMUX` would point plain
+    // `tmux` at agent-hand's own server.
+    cmd.args(["-L", server]);
+    cmd
+}
+
+/// Sessions on tmux server `server` (a `-L` socket name; the plain `tmux`
+/// server is `default`). Empty when that server is not running.
+pub async fn list_foreign_sessions(server: &str) -> Result<Vec<ForeignSession>> {
+    let out = foreign_tmux_cmd(server)
+        .args(["list-sessions", "-F", FOREIGN_FORMAT])
+        .output()
+        .await?;
+    if !out.status.success() {
+        return Ok(Vec::new());
+    }
+    Ok(parse_foreign_sessions(&String::from_utf8_lossy(&out.stdout)))
+}
+
+pub async fn kill_foreign_session(server: &str, name: &str) -> Result<()> {
+    let out = foreign_tmux_cmd(server)
+        .args(["kill-session", "-t", &format!("={name}")])
+        .output()
+        .await?;
+    if !out.status.success() {
+        return Err(crate::Error::tmux(format!(
+            "failed to kill {server}:{name}: {}",
+            String::from_utf8_lossy(&out.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Directories the user chose to ignore, stored as a JSON list of paths.
+pub fn load_ignore_list(path: &Path) -> Vec<PathBuf> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+pub fn add_to_ignore_list(path: &Path, cwd: &Path) -> Result<Vec<PathBuf>> {
+    let mut list = load_ignore_list(path);
+    if !list.iter().any(|p| p == cwd) {
+        list.push(cwd.to_path_buf());
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(&list)?)?;
+    Ok(list)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(pid: u32, ppid: u32, args: &str) -> ProcessEntry {
+        ProcessEntry {
+            pid,
+            ppid,
+            elapsed_secs: 60,
+            args: args.to_string(),
+        }
+    }
+
+    /// launchd/init → terminal shell → external claude; tmux server → pane
+    /// shell → managed claude with a helper; agent-hand → summarizer claude.
+    fn fixture() -> Vec<ProcessEntry> {
+        vec![
+            p(1, 0, "/sbin/init"),
+            p(100, 1, "-zsh"),
+            p(101, 100, "node /usr/local/bin/claude --resume"),
+            p(102, 101, "claude mcp serve"),
+            p(200, 1, "tmux -L agenthand_default new-session"),
+            p(201, 200, "-zsh"),
+            p(202, 201, "claude"),
+            p(203, 202, "node /opt/mcp/server.js"),
+            p(300, 100, "agent-hand"),
+            p(301, 300, "claude -p summarize"),
+            p(400, 1, "/usr/bin/codex exec"),
+            p(500, 1, "vim claude.md"),
+        ]
+    }
+
+    #[test]
+    fn parses_etime_formats() {
+        assert_eq!(parse_etime("05:07"), Some(307));
+        assert_eq!(parse_etime("01:00:00"), Some(3600));
+        assert_eq!(parse_etime("2-03:04:05"), Some(2 * 86_400 + 3 * 3600 + 4 * 60 + 5));
+        assert_eq!(parse_etime("garbage"), None);
+    }
+
+    #[test]
+    fn parses_ps_lines() {
+        let out = "  101   100    10:00 node /usr/local/bin/claude --resume\nbad line\n";
+        let procs = parse_ps_output(out);
+        assert_eq!(procs, vec![ProcessEntry {
+            pid: 101,
+            ppid: 100,
+            elapsed_secs: 600,
+            args: "node /usr/local/bin/claude --resume".to_string(),
+        }]);
+    }
+
+    #[test]
+    fn recognizes_agents_by_executable() {
+        assert_eq!(agent_tool("/usr/local/bin/claude"), Some(Tool::Claude));
+        assert_eq!(agent_tool("node --no-warnings /x/codex"), Some(Tool::Codex));
+        assert_eq!(agent_tool("gemini -y"), Some(Tool::Gemini));
+        assert_eq!(agent_tool("vim claude.md"), None);
+        assert_eq!(agent_tool("node"), None);
+    }
+
+    #[test]
+    fn excludes_managed_and_own_trees() {
+        // What collect_process_tree returns for pane 201 and for us (300).
+        let excluded = HashSet::from([201, 202, 203, 300, 301]);
+        let found = find_unmanaged(&fixture(), &excluded);
+        let pids: Vec<u32> = found.iter().map(|(p, _)| p.pid).collect();
+        assert_eq!(pids, vec![101, 400]);
+        assert_eq!(found[0].1, Tool::Claude);
+        assert_eq!(found[1].1, Tool::Codex);
+    }
+
+    #[test]
+    fn children_missing_from_a_collected_tree_stay_excluded() {
+        // The pane's claude (202) started after its tree was collected.
+        let found = find_unmanaged(&fixture(), &HashSet::from([201, 300]));
+        let pids: Vec<u32> = found.iter().map(|(p, _)| p.pid).collect();
+        assert_eq!(pids, vec![101, 400]);
+    }
+
+    #[test]
+    fn without_exclusions_nested_agents_fold_into_parent() {
+        let pids: Vec<u32> = find_unmanaged(&fixture(), &HashSet::new())
+            .iter()
+            .map(|(p, _)| p.pid)
+            .collect();
+        assert_eq!(pids, vec![101, 202, 301, 400]);
+    }
+
+    #[test]
+    fn parent_cycles_terminate() {
+        let procs = vec![p(10, 11, "claude"), p(11, 10, "bash")];
+        let pids: Vec<u32> = find_unmanaged(&procs, &HashSet::new())
+            .iter()
+            .map(|(p, _)| p.pid)
+            .collect();
+        assert_eq!(pids, vec![10]);
+    }
+
+    #[test]
+    fn foreign_sessions_parse_and_map_to_commands() {
+        let out = "api\t/work/api\tzsh\t\n\
+                   chat\t/work/chat\tclaude\tzsh\n\
+                   logs\t/var/log\ttail\n\
+                   opus\t/work/api\tnode\t\"claude --model opus\"\n\
+                   \t/x\tbash\n";
+        let sessions = parse_foreign_sessions(out);
+        assert_eq!(sessions.len(), 4);
+        assert_eq!(sessions[0].path, PathBuf::from("/work/api"));
+
+        let commands: Vec<(Tool, String)> =
+            sessions.iter().map(ForeignSession::adopted_command).collect();
+        assert_eq!(
+            commands,
+            vec![
+                (Tool::Shell, String::new()),
+                (Tool::Claude, "claude".to_string()),
+                (Tool::Shell, "tail".to_string()),
+                (Tool::Claude, "claude --model opus".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignore_list_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("discovery_ignore.json");
+        assert!(load_ignore_list(&path).is_empty());
+        add_to_ignore_list(&path, Path::new("/work/a")).unwrap();
+        add_to_ignore_list(&path, Path::new("/work/a")).unwrap();
+        assert_eq!(load_ignore_list(&path), vec![PathBuf::from("/work/a")]);
+    }
+}
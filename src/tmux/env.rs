@@ -0,0 +1,207 @@
+//! Global environment variables agent-hand keeps on its tmux server.
+//!
+//! Key bindings, the switcher and the statusline talk to each other through
+//! these. Several of them name a tmux session, and nothing ties their
+//! lifetime to that session, so they are checked against the session cache
+//! and cleared once the session is gone.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+
+use super::TmuxManager;
+use crate::error::Result;
+
+/// Session the user last detached from (Ctrl+Q) or switched to.
+pub const LAST_SESSION: &str = "AGENTHAND_LAST_SESSION";
+/// `client_activity` at the last Ctrl+Q detach.
+pub const LAST_DETACH_AT: &str = "AGENTHAND_LAST_DETACH_AT";
+/// Session the jump key targets, written by the statusline.
+pub const PRIORITY_SESSION: &str = "AGENTHAND_PRIORITY_SESSION";
+/// tmux key currently bound to detach.
+pub const DETACH_KEY: &str = "AGENTHAND_DETACH_KEY";
+/// tmux key currently bound to the switcher popup.
+pub const SWITCHER_KEY: &str = "AGENTHAND_SWITCHER_KEY";
+/// tmux key currently bound to jump.
+pub const JUMP_KEY: &str = "AGENTHAND_JUMP_KEY";
+/// Profile popups run under.
+pub const PROFILE: &str = "AGENTHAND_PROFILE";
+
+pub struct EnvKey {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// The value is a tmux session name and goes stale with it.
+    pub names_session: bool,
+}
+
+/// Every key we own, in display order.
+pub const OWNED_KEYS: &[EnvKey] = &[
+    EnvKey { name: LAST_SESSION, description: "last detached/switched session", names_session: true },
+    EnvKey { name: LAST_DETACH_AT, description: "time of last Ctrl+Q detach", names_session: false },
+    EnvKey { name: PRIORITY_SESSION, description: "jump target from statusline", names_session: true },
+    EnvKey { name: DETACH_KEY, description: "bound detach key", names_session: false },
+    EnvKey { name: SWITCHER_KEY, description: "bound switcher key", names_session: false },
+    EnvKey { name: JUMP_KEY, description: "bound jump key", names_session: false },
+    EnvKey { name: PROFILE, description: "profile for popups", names_session: false },
+];
+
+/// Get/set access to the server's global environment. Implemented by
+/// [`TmuxManager`]; tests use an in-memory map.
+#[async_trait]
+pub trait EnvStore: Send + Sync {
+    async fn get(&self, key: &str) -> Result<Option<String>>;
+    async fn set(&self, key: &str, value: &str) -> Result<()>;
+    async fn unset(&self, key: &str) -> Result<()>;
+}
+
+#[async_trait]
+impl EnvStore for TmuxManager {
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        self.get_environment_global(key).await
+    }
+
+    async fn set(&self, key: &str, value: &str) -> Result<()> {
+        self.set_environment_global(key, value).await
+    }
+
+    async fn unset(&self, key: &str) -> Result<()> {
+        self.unset_environment_global(key).await
+    }
+}
+
+/// Current values of all owned keys (unset keys are omitted).
+pub async fn read_all(store: &dyn EnvStore) -> HashMap<&'static str, String> {
+    let mut values = HashMap::new();
+    for key in OWNED_KEYS {
+        if let Ok(Some(value)) = store.get(key.name).await {
+            values.insert(key.name, value);
+        }
+    }
+    values
+}
+
+/// Clear session markers that name a session `exists` reports as gone.
+/// `exists` should read the session cache; `None` (cache stale) leaves the
+/// marker alone rather than guessing. Returns the keys that were cleared.
+pub async fn clear_stale_markers(
+    store: &dyn EnvStore,
+    exists: impl Fn(&str) -> Option<bool> + Send,
+) -> Result<Vec<&'static str>> {
+    let mut cleared = Vec::new();
+    for key in OWNED_KEYS.iter().filter(|k| k.names_session) {
+        let Some(value) = store.get(key.name).await? else {
+            continue;
+        };
+        if value.is_empty() || exists(&value) != Some(false) {
+            continue;
+        }
+        store.unset(key.name).await?;
+        cleared.push(key.name);
+    }
+    Ok(cleared)
+}
+
+/// Clear session markers that name `tmux_name`; call when deleting it.
+pub async fn clear_markers_for(store: &dyn EnvStore, tmux_name: &str) -> Result<Vec<&'static str>> {
+    clear_stale_markers(store, |name| if name == tmux_name { Some(false) } else { None }).await
+}
+
+/// Unset every owned key. Bindings and the profile are written again the
+/// next time the TUI starts.
+pub async fn clear_all(store: &dyn EnvStore) -> Result<()> {
+    for key in OWNED_KEYS {
+        store.unset(key.name).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+
+    #[derive(Default)]
+    struct MemEnv(Mutex<HashMap<String, String>>);
+
+    impl MemEnv {
+        fn with(pairs: &[(&str, &str)]) -> Self {
+            let env = Self::default();
+            for (k, v) in pairs {
+                env.0.lock().insert(k.to_string(), v.to_string());
+            }
+            env
+        }
+
+        fn value(&self, key: &str) -> Option<String> {
+            self.0.lock().get(key).cloned()
+        }
+    }
+
+    #[async_trait]
+    impl EnvStore for MemEnv {
+        async fn get(&self, key: &str) -> Result<Option<String>> {
+            Ok(self.value(key))
+        }
+        async fn set(&self, key: &str, value: &str) -> Result<()> {
+            self.0.lock().insert(key.to_string(), value.to_string());
+            Ok(())
+        }
+        async fn unset(&self, key: &str) -> Result<()> {
+            self.0.lock().remove(key);
+            Ok(())
+        }
+    }
+
+    fn live(name: &str) -> Option<bool> {
+        Some(name == "agentdeck_rs_alive")
+    }
+
+    #[tokio::test]
+    async fn last_session_naming_dead_session_is_cleared() {
+        let env = MemEnv::with(&[(LAST_SESSION, "agentdeck_rs_gone"), (LAST_DETACH_AT, "123")]);
+        let cleared = clear_stale_markers(&env, live).await.unwrap();
+        assert_eq!(cleared, vec![LAST_SESSION]);
+        assert_eq!(env.value(LAST_SESSION), None);
+        // The detach timestamp is not a session reference and stays.
+        assert_eq!(env.value(LAST_DETACH_AT).as_deref(), Some("123"));
+    }
+
+    #[tokio::test]
+    async fn priority_session_naming_dead_session_is_cleared() {
+        let env = MemEnv::with(&[
+            (LAST_SESSION, "agentdeck_rs_alive"),
+            (PRIORITY_SESSION, "agentdeck_rs_gone"),
+        ]);
+        let cleared = clear_stale_markers(&env, live).await.unwrap();
+        assert_eq!(cleared, vec![PRIORITY_SESSION]);
+        assert_eq!(env.value(LAST_SESSION).as_deref(), Some("agentdeck_rs_alive"));
+    }
+
+    #[tokio::test]
+    async fn empty_markers_and_stale_cache_are_left_alone() {
+        let env = MemEnv::with(&[(LAST_SESSION, "agentdeck_rs_gone"), (PRIORITY_SESSION, "")]);
+        let cleared = clear_stale_markers(&env, |_| None).await.unwrap();
+        assert!(cleared.is_empty());
+        assert_eq!(env.value(LAST_SESSION).as_deref(), Some("agentdeck_rs_gone"));
+        assert_eq!(env.value(PRIORITY_SESSION).as_deref(), Some(""));
+    }
+
+    #[tokio::test]
+    async fn deleting_a_session_clears_only_its_markers() {
+        let env = MemEnv::with(&[
+            (LAST_SESSION, "agentdeck_rs_a"),
+            (PRIORITY_SESSION, "agentdeck_rs_b"),
+        ]);
+        let cleared = clear_markers_for(&env, "agentdeck_rs_a").await.unwrap();
+        assert_eq!(cleared, vec![LAST_SESSION]);
+        assert_eq!(env.value(PRIORITY_SESSION).as_deref(), Some("agentdeck_rs_b"));
+    }
+
+    #[tokio::test]
+    async fn clear_all_removes_every_owned_key() {
+        let env = MemEnv::with(&[(DETACH_KEY, "C-q"), (PROFILE, "work"), ("OTHER", "x")]);
+        clear_all(&env).await.unwrap();
+        assert!(read_all(&env).await.is_empty());
+        assert_eq!(env.value("OTHER").as_deref(), Some("x"));
+    }
+}
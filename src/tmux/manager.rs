@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use tokio::process::Command;
@@ -6,25 +6,38 @@ use tokio::process::Command;
 use crate::error::Result;
 
 use super::cache::SessionCache;
-use super::SESSION_PREFIX;
+use super::calls::{self, CallKind};
+use super::version::{self, DetectedTmux, Unsupported};
+use super::{COMPARE_PREFIX, SESSION_PREFIX};
 
 /// Tmux manager - handles all tmux operations
 #[derive(Debug)]
 pub struct TmuxManager {
+    /// Profile the server belongs to; published to it for popups.
+    profile: String,
     server_name: String,
     cache: Arc<SessionCache>,
+    version: Option<&'static DetectedTmux>,
 }
 
 impl TmuxManager {
     pub fn new(profile: &str) -> Self {
         Self {
+            profile: profile.to_string(),
             server_name: super::server_name_for_profile(profile),
             cache: Arc::new(SessionCache::new()),
+            version: version::detected(),
         }
     }
 
+    /// The installed tmux version, if `tmux -V` could be run.
+    pub fn version(&self) -> Option<&'static DetectedTmux> {
+        self.version
+    }
+
     /// Build an async tmux command with the correct `-L <server>`.
     pub fn tmux_cmd(&self) -> Command {
+        calls::count_spawn();
         let mut cmd = Command::new("tmux");
         cmd.args(["-L", &self.server_name]);
         cmd
@@ -35,8 +48,9 @@ impl TmuxManager {
         &self.server_name
     }
 
-    async fn ensure_server_bindings(&self) {
+    async fn ensure_server_bindings(&self) -> Vec<Unsupported> {
         // Best-effort: bind keys on our dedicated tmux server.
+        let mut unsupported = Vec::new();
         let cfg = crate::config::ConfigFile::load().await.ok().flatten();
 
         let detach_key = cfg
@@ -67,17 +81,17 @@ impl TmuxManager {
 
         // Check current bindings - skip if already correct (multi-instance safety)
         let current_detach = self
-            .get_environment_global("AGENTHAND_DETACH_KEY")
+            .get_environment_global(super::env::DETACH_KEY)
             .await
             .ok()
             .flatten();
         let current_switch = self
-            .get_environment_global("AGENTHAND_SWITCHER_KEY")
+            .get_environment_global(super::env::SWITCHER_KEY)
             .await
             .ok()
             .flatten();
         let current_jump = self
-            .get_environment_global("AGENTHAND_JUMP_KEY")
+            .get_environment_global(super::env::JUMP_KEY)
             .await
             .ok()
             .flatten();
@@ -125,12 +139,12 @@ impl TmuxManager {
                     detach_key.as_str(),
                     "set-environment",
                     "-g",
-                    "AGENTHAND_LAST_SESSION",
+                    super::env::LAST_SESSION,
                     "#{session_name}",
                     "\\;",
                     "set-environment",
                     "-g",
-                    "AGENTHAND_LAST_DETACH_AT",
+                    super::env::LAST_DETACH_AT,
                     "#{client_activity}",
                     "\\;",
                     "detach-client",
@@ -138,17 +152,27 @@ impl TmuxManager {
                 .status()
                 .await;
             let _ = self
-                .set_environment_global("AGENTHAND_DETACH_KEY", detach_key.as_str())
+                .set_environment_global(super::env::DETACH_KEY, detach_key.as_str())
                 .await;
         }
 
-        // Popup switcher key - only bind if needed
-        if need_switch_bind {
+        // Popup switcher key - only bind if needed, and never to a popup the
+        // installed tmux cannot open.
+        let popup = if need_switch_bind {
+            version::popup(self.version)
+        } else {
+            Ok(())
+        };
+        if let Err(e) = &popup {
+            tracing::warn!("not binding {switch_key}: {e}");
+            unsupported.push(e.clone());
+        }
+        if need_switch_bind && popup.is_ok() {
             let switch_bin = std::env::current_exe()
                 .ok()
                 .and_then(|p| p.to_str().map(|s| s.to_string()))
                 .unwrap_or_else(|| "agent-hand".to_string());
-            let _ = self
+            let bound = self
                 .tmux_cmd()
                 .args([
                     "bind-key",
@@ -164,10 +188,15 @@ impl TmuxManager {
                     "switch",
                 ])
                 .status()
-                .await;
-            let _ = self
-                .set_environment_global("AGENTHAND_SWITCHER_KEY", switch_key.as_str())
-                .await;
+                .await
+                .is_ok_and(|s| s.success());
+            if bound {
+                let _ = self
+                    .set_environment_global(super::env::SWITCHER_KEY, switch_key.as_str())
+                    .await;
+            } else {
+                tracing::warn!("failed to bind switcher key {switch_key}");
+            }
         }
 
         // Jump-to-priority key (Ctrl+N by default) - call `agent-hand jump` which
@@ -192,18 +221,16 @@ impl TmuxManager {
                 .status()
                 .await;
             let _ = self
-                .set_environment_global("AGENTHAND_JUMP_KEY", jump_key.as_str())
+                .set_environment_global(super::env::JUMP_KEY, jump_key.as_str())
                 .await;
         }
 
         // Ensure tmux popups see the active profile.
-        if let Ok(profile) = std::env::var("AGENTHAND_PROFILE") {
-            let _ = self
-                .tmux_cmd()
-                .args(["set-environment", "-g", "AGENTHAND_PROFILE", &profile])
-                .status()
-                .await;
-        }
+        let _ = self
+            .tmux_cmd()
+            .args(["set-environment", "-g", super::env::PROFILE, &self.profile])
+            .status()
+            .await;
 
         // Enable mouse so scroll wheel drives tmux copy-mode/pane scrolling.
         let _ = self
@@ -219,7 +246,17 @@ impl TmuxManager {
             .map(|s| s.trim().to_lowercase())
             .unwrap_or_else(|| "vi".to_string());
 
-        if copy_mode != "off" && copy_mode != "none" {
+        let copy_mode_on = copy_mode != "off" && copy_mode != "none";
+        let copy_mode_ok = copy_mode_on
+            && match version::copy_mode_commands(self.version) {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::warn!("skipping copy-mode defaults: {e}");
+                    unsupported.push(e);
+                    false
+                }
+            };
+        if copy_mode_ok {
             let mode_keys = if copy_mode == "emacs" { "emacs" } else { "vi" };
             let _ = self
                 .tmux_cmd()
@@ -330,11 +367,24 @@ impl TmuxManager {
             .args(["set-option", "-g", "status-left", status_left.as_str()])
             .status()
             .await;
+
+        unsupported
     }
 
     /// Ensure our dedicated tmux server has required bindings/options.
-    pub async fn ensure_server(&self) {
-        self.ensure_server_bindings().await;
+    /// Returns the features left out because the installed tmux is too old.
+    pub async fn ensure_server(&self) -> Vec<Unsupported> {
+        self.ensure_server_bindings().await
+    }
+
+    /// Remove a root-table key binding, best effort. `ensure_server` only
+    /// unbinds keys it recorded binding itself.
+    pub async fn unbind_root_key(&self, key: &str) {
+        let _ = self
+            .tmux_cmd()
+            .args(["unbind-key", "-n", key])
+            .status()
+            .await;
     }
 
     /// Check if tmux is available
@@ -346,13 +396,10 @@ impl TmuxManager {
     /// Refresh session cache from tmux
     /// Call this ONCE per tick, then use cached methods
     pub async fn refresh_cache(&self) -> Result<()> {
+        calls::record(CallKind::List);
         let output = self
             .tmux_cmd()
-            .args(&[
-                "list-sessions",
-                "-F",
-                "#{session_name}\t#{session_activity}",
-            ])
+            .args(&["list-panes", "-a", "-F", PANE_LIST_FORMAT])
             .output()
             .await?;
 
@@ -362,19 +409,8 @@ impl TmuxManager {
             return Ok(());
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let mut sessions = HashMap::new();
-
-        for line in stdout.lines() {
-            let parts: Vec<&str> = line.splitn(2, '\t').collect();
-            if parts.len() == 2 {
-                let name = parts[0].to_string();
-                let activity = parts[1].parse::<i64>().unwrap_or(0);
-                sessions.insert(name, activity);
-            }
-        }
-
-        self.cache.update(sessions);
+        let (sessions, dead_panes) = parse_pane_list(&String::from_utf8_lossy(&output.stdout));
+        self.cache.update(sessions, dead_panes);
         Ok(())
     }
 
@@ -402,7 +438,14 @@ impl TmuxManager {
     /// Format: `{sanitized_title}_{first_8_of_id}`
     pub fn build_session_name(title: &str, id: &str) -> String {
         let sanitized = sanitize_for_tmux(title);
-        let short_id = &id[..id.len().min(8)];
+        let short_id: String = id
+            .chars()
+            .take(8)
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' => c,
+                _ => '-',
+            })
+            .collect();
         if sanitized.is_empty() {
             format!("session_{}", short_id)
         } else {
@@ -412,6 +455,7 @@ impl TmuxManager {
 
     /// Rename a tmux session
     pub async fn rename_session(&self, old_name: &str, new_name: &str) -> Result<()> {
+        validate_session_name(new_name)?;
         let output = self
             .tmux_cmd()
             .args(&["rename-session", "-t", old_name, new_name])
@@ -445,37 +489,12 @@ impl TmuxManager {
         command: Option<&str>,
         title: Option<&str>,
     ) -> Result<()> {
-        let mut cmd = self.tmux_cmd();
-        cmd.args(&[
-            "new-session",
-            "-d", // Detached
-            "-s",
-            name,
-            "-c",
-            working_dir,
-        ]);
-
-        // Build the shell command.
-        // When a command is provided, wrap it so the shell survives after the
-        // command exits (e.g. Ctrl+C kills claude → drops to shell, not closes pane).
+        validate_session_name(name)?;
+        // The command runs inside the user's shell; with no command the pane
+        // is a plain login shell.
         let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string());
-
-        if let Some(command) = command {
-            // Escape single quotes in the command for safe embedding
-            let escaped = command.replace('\'', "'\\''");
-            // Trap SIGINT in the outer shell so Ctrl+C only kills the child command,
-            // not the wrapper. The child runs in a subshell with default signal handling.
-            // After the child exits (by any means), restore default INT and exec login shell.
-            let wrapped = format!(
-                "{shell} -c 'trap \"\" INT; (trap - INT; exec {escaped}); trap - INT; exec {shell} -l'",
-                shell = shell,
-                escaped = escaped,
-            );
-            cmd.arg(wrapped);
-        } else {
-            // Login shell (no command)
-            cmd.args([&shell, "-l"]);
-        }
+        let mut cmd = self.tmux_cmd();
+        cmd.args(new_session_args(name, working_dir, &shell, command));
 
         let output = cmd.output().await?;
         if !output.status.success() {
@@ -509,6 +528,61 @@ impl TmuxManager {
         Ok(())
     }
 
+    /// Add a window named `window` to an existing session, running
+    /// `command` the way `create_session` runs its command. The session's
+    /// current window stays selected.
+    pub async fn new_window(
+        &self,
+        session: &str,
+        window: &str,
+        working_dir: &str,
+        command: &str,
+    ) -> Result<()> {
+        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/zsh".to_string());
+        // The trailing `:` makes tmux pick the next free window index.
+        let target = format!("{session}:");
+        let output = self
+            .tmux_cmd()
+            .args(new_window_args(&target, window, working_dir, &shell, command))
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(crate::Error::tmux(format!(
+                "Failed to create window {window}: {}",
+                stderr
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Target for the window named `window` of `session`; `=` makes tmux
+    /// match the name exactly rather than as a prefix.
+    pub fn window_target(session: &str, window: &str) -> String {
+        format!("{session}:={window}")
+    }
+
+    /// Make a window the session's current one, so attaching lands on it.
+    pub async fn select_window(&self, target: &str) -> Result<()> {
+        let output = self
+            .tmux_cmd()
+            .args(["select-window", "-t", target])
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(crate::Error::tmux(format!(
+                "Failed to select window: {}",
+                stderr
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Create a tmux session for viewing a remote shared session.
     ///
     /// The session runs `agent-hand pty-viewer` which bridges WebSocket ↔ stdio.
@@ -523,8 +597,10 @@ impl TmuxManager {
         user_token: Option<&str>,
         display_name: Option<&str>,
     ) -> Result<String> {
-        let short_id = &room_id[..room_id.len().min(8)];
-        let session_name = format!("viewer-{}", short_id);
+        let short_id: String = room_id.chars().take(8).collect();
+        let short_id = short_id.as_str();
+        let session_name = format!("viewer-{}", short_id.replace([':', '.'], "-"));
+        validate_session_name(&session_name)?;
 
         // Build the pty-viewer command
         let bin = std::env::current_exe()
@@ -553,29 +629,12 @@ impl TmuxManager {
             cmd_parts.push(dn.to_string());
         }
 
-        // Shell-escape and join
-        let shell_cmd = cmd_parts
-            .iter()
-            .map(|s| {
-                if s.contains(' ') || s.contains('\'') || s.contains('"') {
-                    format!("'{}'", s.replace('\'', "'\\''"))
-                } else {
-                    s.clone()
-                }
-            })
-            .collect::<Vec<_>>()
-            .join(" ");
-
-        // Create detached tmux session running the pty-viewer
+        // Create detached tmux session running the pty-viewer. Each part is
+        // its own argv element, so tmux execs it without a shell.
         let output = self
             .tmux_cmd()
-            .args([
-                "new-session",
-                "-d",
-                "-s",
-                &session_name,
-                &shell_cmd,
-            ])
+            .args(["new-session", "-d", "-s", &session_name])
+            .args(&cmd_parts)
             .output()
             .await?;
 
@@ -620,6 +679,10 @@ impl TmuxManager {
             .await;
 
         // Bind Ctrl+G to display info popup for this viewer session
+        if let Err(e) = version::popup(self.version) {
+            tracing::warn!("no viewer info key: {e}");
+            return Ok(session_name);
+        }
         let info_cmd = format!(
             "'{}' viewer-info --room-id '{}'",
             bin.replace('\'', "'\\''"),
@@ -647,6 +710,72 @@ impl TmuxManager {
         Ok(session_name)
     }
 
+    /// Whether `name` is a comparison session made by
+    /// [`Self::create_compare_session`] rather than an agent session.
+    pub fn is_compare_session(name: &str) -> bool {
+        name.starts_with(COMPARE_PREFIX)
+    }
+
+    /// Create a throwaway session showing `left` and `right` side by side,
+    /// replacing an earlier one for the same pair. Returns its name.
+    ///
+    /// Each pane runs a nested client attached to its session: linking or
+    /// joining would move the agents' own windows around, while killing
+    /// this session only detaches the nested clients.
+    pub async fn create_compare_session(&self, left: &str, right: &str) -> Result<String> {
+        let name = format!("{COMPARE_PREFIX}{left}~{right}");
+        let _ = self
+            .tmux_cmd()
+            .args(["kill-session", "-t", &format!("={name}")])
+            .output()
+            .await;
+
+        let attach = |target: &str| {
+            format!(
+                "env -u TMUX tmux -L {} attach-session -t {}",
+                shell_quote(&self.server_name),
+                shell_quote(&format!("={target}")),
+            )
+        };
+        let output = self
+            .tmux_cmd()
+            .args(["new-session", "-d", "-s", &name, &attach(left)])
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(crate::Error::tmux(format!(
+                "Failed to create compare session: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+        let target = format!("={name}");
+        let output = self
+            .tmux_cmd()
+            .args(["split-window", "-h", "-t", &target, &attach(right)])
+            .output()
+            .await?;
+        if !output.status.success() {
+            let _ = self.kill_session(&name).await;
+            return Err(crate::Error::tmux(format!(
+                "Failed to split compare session: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+
+        self.ensure_server_bindings().await;
+        // Clicking picks the pane that gets the keys.
+        for args in [
+            vec!["select-layout", "-t", &target, "even-horizontal"],
+            vec!["set-option", "-t", &target, "mouse", "on"],
+        ] {
+            let _ = self.tmux_cmd().args(args).output().await;
+        }
+        let _ = self
+            .set_session_title(&name, &format!("⇆ {left} | {right}"))
+            .await;
+        Ok(name)
+    }
+
     /// Kill a tmux session
     pub async fn kill_session(&self, name: &str) -> Result<()> {
         let output = self
@@ -668,6 +797,7 @@ impl TmuxManager {
 
     /// Capture pane content (for status detection)
     pub async fn capture_pane(&self, name: &str, lines: usize) -> Result<String> {
+        calls::record(CallKind::Capture);
         let output = self
             .tmux_cmd()
             .args(&[
@@ -688,10 +818,48 @@ impl TmuxManager {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
 
+    /// Whether `target`'s pane outlived its process, which only happens with
+    /// `remain-on-exit`, as of the last [`Self::refresh_cache`]. False when
+    /// tmux has not said so.
+    pub fn pane_dead(&self, target: &str) -> bool {
+        self.cache.pane_dead(target)
+    }
+
+    /// Capture a pane's scrollback for a transcript: the last `lines` lines,
+    /// or the whole history when `None`. Wrapped lines are joined and blank
+    /// rows below the output dropped. `escapes` keeps colors (`-e`).
+    pub async fn capture_history(
+        &self,
+        name: &str,
+        lines: Option<usize>,
+        escapes: bool,
+    ) -> Result<String> {
+        calls::record(CallKind::Capture);
+        // `-S -N` counts history above the screen, so ask for at least N
+        // and trim to the tail afterwards.
+        let start = lines.map_or_else(|| "-".to_string(), |n| format!("-{}", n));
+        let mut args = vec!["capture-pane", "-t", name, "-p", "-J", "-S", start.as_str()];
+        if escapes {
+            args.push("-e");
+        }
+        let output = self.tmux_cmd().args(&args).output().await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(crate::Error::tmux(format!(
+                "Failed to capture pane: {}",
+                stderr.trim()
+            )));
+        }
+
+        Ok(tail_lines(&String::from_utf8_lossy(&output.stdout), lines))
+    }
+
     /// Capture pane content with ANSI escape codes for full visual fidelity.
     /// Used by the relay client to send terminal snapshots to viewers.
     #[cfg(feature = "pro")]
     pub async fn capture_pane_ansi(&self, name: &str) -> Result<Vec<u8>> {
+        calls::record(CallKind::Capture);
         let output = self
             .tmux_cmd()
             .args(&[
@@ -720,6 +888,7 @@ impl TmuxManager {
         name: &str,
         scrollback_lines: usize,
     ) -> Result<Vec<u8>> {
+        calls::record(CallKind::Capture);
         let start_line = format!("-{}", scrollback_lines);
         let output = self
             .tmux_cmd()
@@ -799,6 +968,7 @@ impl TmuxManager {
     /// Get the current terminal size of a pane.
     #[cfg(feature = "pro")]
     pub async fn pane_size(&self, name: &str) -> Result<(u16, u16)> {
+        calls::record(CallKind::Capture);
         let output = self
             .tmux_cmd()
             .args(&[
@@ -826,13 +996,14 @@ impl TmuxManager {
         }
     }
 
-    /// Send keys to a session
+    /// Send tmux key names (`C-c`, `Escape`, ...) to a session, then Enter.
+    /// Words in `keys` that name a key are pressed as that key; use
+    /// [`Self::send_text`] for text that must arrive verbatim.
     pub async fn send_keys(&self, name: &str, keys: &str) -> Result<()> {
-        let output = self
-            .tmux_cmd()
-            .args(&["send-keys", "-t", name, keys, "Enter"])
-            .output()
-            .await?;
+        calls::record(CallKind::Send);
+        let mut args = send_keys_args(name, keys, false);
+        args.push("Enter");
+        let output = self.tmux_cmd().args(args).output().await?;
 
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
@@ -845,13 +1016,75 @@ impl TmuxManager {
         Ok(())
     }
 
+    /// Type `text` into a pane literally (no key-name interpretation),
+    /// then press Enter. Use this for prompts; `send_keys` would turn words
+    /// like "Enter" or "C-c" in the text into keystrokes.
+    pub async fn send_text(&self, name: &str, text: &str) -> Result<()> {
+        calls::record(CallKind::Send);
+        let output = self
+            .tmux_cmd()
+            .args(send_keys_args(name, text, true))
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(crate::Error::tmux(format!("Failed to send text: {}", stderr)));
+        }
+
+        self.send_raw_keys(name, "Enter").await
+    }
+
+    /// Type `text` into a pane without pressing Enter. A single line is
+    /// sent literally; multi-line text is pasted (bracketed, when the
+    /// program asks for it) so its newlines don't submit line by line.
+    pub async fn type_text(&self, name: &str, text: &str) -> Result<()> {
+        const BUFFER: &str = "agenthand_send";
+
+        calls::record(CallKind::Send);
+        if !text.contains('\n') {
+            let output = self
+                .tmux_cmd()
+                .args(send_keys_args(name, text, true))
+                .output()
+                .await?;
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                return Err(crate::Error::tmux(format!("Failed to send text: {}", stderr)));
+            }
+            return Ok(());
+        }
+
+        let output = self
+            .tmux_cmd()
+            .args(["set-buffer", "-b", BUFFER, "--", text])
+            .output()
+            .await?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(crate::Error::tmux(format!("Failed to set paste buffer: {}", stderr)));
+        }
+        calls::record(CallKind::Send);
+        let output = self
+            .tmux_cmd()
+            .args(["paste-buffer", "-p", "-d", "-b", BUFFER, "-t", name])
+            .output()
+            .await?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(crate::Error::tmux(format!("Failed to paste text: {}", stderr)));
+        }
+        Ok(())
+    }
+
     /// Send literal text to a tmux pane without appending Enter.
     /// Used for forwarding raw viewer input from relay collaboration.
     #[cfg(feature = "pro")]
     pub async fn send_keys_literal(&self, name: &str, text: &str) -> Result<()> {
+        calls::record(CallKind::Send);
         let output = self
             .tmux_cmd()
-            .args(&["send-keys", "-t", name, "-l", text])
+            .args(send_keys_args(name, text, true))
             .output()
             .await?;
 
@@ -868,9 +1101,10 @@ impl TmuxManager {
 
     /// Send raw tmux keys WITHOUT appending Enter.
     pub async fn send_raw_keys(&self, name: &str, keys: &str) -> Result<()> {
+        calls::record(CallKind::Send);
         let output = self
             .tmux_cmd()
-            .args(&["send-keys", "-t", name, keys])
+            .args(send_keys_args(name, keys, false))
             .output()
             .await?;
         if !output.status.success() {
@@ -887,13 +1121,7 @@ impl TmuxManager {
 
     /// Attach to a session (blocking)
     pub async fn attach_session(&self, name: &str) -> Result<()> {
-        self.ensure_server_bindings().await;
-
-        let status = self
-            .tmux_cmd()
-            .args(&["attach-session", "-t", name])
-            .status()
-            .await?;
+        let status = self.attach_session_status(name).await?;
 
         if !status.success() {
             return Err(crate::Error::tmux("Failed to attach to session"));
@@ -902,6 +1130,18 @@ impl TmuxManager {
         Ok(())
     }
 
+    /// Attach to a session (blocking) and return how `tmux attach-session`
+    /// exited, for callers that pass the status on.
+    pub async fn attach_session_status(&self, name: &str) -> Result<std::process::ExitStatus> {
+        self.ensure_server_bindings().await;
+
+        Ok(self
+            .tmux_cmd()
+            .args(["attach-session", "-t", name])
+            .status()
+            .await?)
+    }
+
     /// Set the user-visible title on a tmux session (stored as `@agenthand_title`).
     /// The status-left format reads this to display the friendly name.
     pub async fn set_session_title(&self, session_name: &str, title: &str) -> Result<()> {
@@ -920,8 +1160,41 @@ impl TmuxManager {
         Ok(())
     }
 
+    /// Type `command` into a session once per tmux session: the first call
+    /// marks it with `@agenthand_post_attach` and sends, later ones return
+    /// false. The option goes with the session, so a restart sends again.
+    pub async fn send_post_attach(&self, name: &str, command: &str) -> Result<bool> {
+        const SENT: &str = "@agenthand_post_attach";
+        let output = self
+            .tmux_cmd()
+            .args(["show-options", "-qv", "-t", name, SENT])
+            .output()
+            .await?;
+        if !String::from_utf8_lossy(&output.stdout).trim().is_empty() {
+            return Ok(false);
+        }
+
+        // Marked before sending so a second attach racing this one skips it.
+        let output = self
+            .tmux_cmd()
+            .args(["set-option", "-t", name, SENT, "1"])
+            .output()
+            .await?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(crate::Error::tmux(format!(
+                "Failed to mark post-attach command: {}",
+                stderr
+            )));
+        }
+
+        self.send_text(name, command).await?;
+        Ok(true)
+    }
+
     /// Set a global tmux environment variable on our dedicated server.
     pub async fn set_environment_global(&self, key: &str, value: &str) -> Result<()> {
+        calls::record(CallKind::Env);
         let output = self
             .tmux_cmd()
             .args(["set-environment", "-g", key, value])
@@ -939,8 +1212,29 @@ impl TmuxManager {
         Ok(())
     }
 
+    /// Remove a global tmux environment variable from our dedicated server.
+    pub async fn unset_environment_global(&self, key: &str) -> Result<()> {
+        calls::record(CallKind::Env);
+        let output = self
+            .tmux_cmd()
+            .args(["set-environment", "-gu", key])
+            .output()
+            .await?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(crate::Error::tmux(format!(
+                "Failed to unset tmux env {key}: {}",
+                stderr
+            )));
+        }
+
+        Ok(())
+    }
+
     /// Get a global tmux environment variable from our dedicated server.
     pub async fn get_environment_global(&self, key: &str) -> Result<Option<String>> {
+        calls::record(CallKind::Env);
         let output = self
             .tmux_cmd()
             .args(["show-environment", "-g", key])
@@ -960,6 +1254,19 @@ impl TmuxManager {
         }
     }
 
+    /// Session of the client this process runs under, e.g. the one a
+    /// popup was opened over. None when tmux can't tell.
+    pub async fn current_session(&self) -> Option<String> {
+        let output = self
+            .tmux_cmd()
+            .args(["display-message", "-p", "#{session_name}"])
+            .output()
+            .await
+            .ok()?;
+        let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (output.status.success() && !name.is_empty()).then_some(name)
+    }
+
     /// Switch current tmux client to a target session
     pub async fn switch_client(&self, name: &str) -> Result<()> {
         let output = self
@@ -1002,6 +1309,7 @@ impl TmuxManager {
 
     /// List all sessions on our dedicated tmux server
     pub async fn list_sessions(&self) -> Result<Vec<String>> {
+        calls::record(CallKind::List);
         let output = self
             .tmux_cmd()
             .args(&["list-sessions", "-F", "#{session_name}"])
@@ -1013,10 +1321,11 @@ impl TmuxManager {
         }
 
         let stdout = String::from_utf8_lossy(&output.stdout);
-        // All sessions on our dedicated server (agenthand_{profile}) belong to us.
+        // All sessions on our dedicated server (agenthand_{profile}) belong to us;
+        // comparison sessions are views onto other sessions, not sessions of their own.
         let sessions: Vec<String> = stdout
             .lines()
-            .filter(|line| !line.is_empty())
+            .filter(|line| !line.is_empty() && !Self::is_compare_session(line))
             .map(|s| s.to_string())
             .collect();
 
@@ -1030,6 +1339,84 @@ impl Default for TmuxManager {
     }
 }
 
+/// Script the user's shell runs around a session command. The wrapper
+/// ignores SIGINT, so Ctrl+C stops only the command and the pane then drops
+/// to a login shell instead of closing. The command arrives as `$1` and the
+/// shell's path as `$0`; neither is ever spliced into the script text.
+const COMMAND_WRAPPER: &str = r#"trap "" INT; (trap - INT; eval "$1"); trap - INT; exec "$0" -l"#;
+
+/// Arguments for `new-session`. Every value is its own argv element, so tmux
+/// runs the shell directly and nothing is re-parsed by an intermediate shell.
+fn new_session_args<'a>(
+    name: &'a str,
+    working_dir: &'a str,
+    shell: &'a str,
+    command: Option<&'a str>,
+) -> Vec<&'a str> {
+    let mut args = vec!["new-session", "-d", "-s", name, "-c", working_dir];
+    match command {
+        Some(command) => args.extend([shell, "-c", COMMAND_WRAPPER, shell, command]),
+        None => args.extend([shell, "-l"]),
+    }
+    args
+}
+
+/// Arguments for `new-window`, built like [`new_session_args`]. `-d` keeps
+/// the new window in the background.
+fn new_window_args<'a>(
+    target: &'a str,
+    window: &'a str,
+    working_dir: &'a str,
+    shell: &'a str,
+    command: &'a str,
+) -> Vec<&'a str> {
+    vec![
+        "new-window",
+        "-d",
+        "-t",
+        target,
+        "-n",
+        window,
+        "-c",
+        working_dir,
+        shell,
+        "-c",
+        COMMAND_WRAPPER,
+        shell,
+        command,
+    ]
+}
+
+/// Arguments for `send-keys`. `--` keeps keys starting with `-` from being
+/// read as flags; `literal` types them as text instead of key names.
+fn send_keys_args<'a>(target: &'a str, keys: &'a str, literal: bool) -> Vec<&'a str> {
+    let mut args = vec!["send-keys", "-t", target];
+    if literal {
+        args.push("-l");
+    }
+    args.extend(["--", keys]);
+    args
+}
+
+/// Check that `name` can be a tmux session name. tmux reads `:` and `.` in
+/// a target as window and pane separators, so names may not contain them.
+pub(crate) fn validate_session_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        return Err(crate::Error::tmux("Session name is empty"));
+    }
+    if let Some(c) = name.chars().find(|&c| c == ':' || c == '.' || c.is_control()) {
+        return Err(crate::Error::tmux(format!(
+            "Invalid character {c:?} in session name '{name}'"
+        )));
+    }
+    Ok(())
+}
+
+/// Quote `s` for a POSIX shell command line.
+pub(crate) fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
 /// Sanitize a title for use as a tmux session name component.
 /// Tmux forbids dots, colons, and certain special chars in session names.
 fn sanitize_for_tmux(title: &str) -> String {
@@ -1067,10 +1454,62 @@ fn sanitize_for_tmux(title: &str) -> String {
     }
 }
 
+/// Drop trailing blank lines, then keep the last `lines` lines if given.
+fn tail_lines(text: &str, lines: Option<usize>) -> String {
+    let all: Vec<&str> = text.trim_end().lines().collect();
+    let skip = lines.map_or(0, |n| all.len().saturating_sub(n));
+    let mut out = all[skip..].join("\n");
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out
+}
+
+/// `list-panes -a` format read by [`TmuxManager::refresh_cache`]. The
+/// window name goes last as the one field that may contain anything.
+const PANE_LIST_FORMAT: &str = "#{session_name}\t#{session_activity}\t#{window_active}\t\
+                                #{pane_active}\t#{pane_dead}\t#{window_name}";
+
+/// Sessions with their activity, and the targets whose pane is dead: the
+/// window's (`session:=window`) and, for the current window, the session's.
+fn parse_pane_list(out: &str) -> (HashMap<String, i64>, HashSet<String>) {
+    let mut sessions = HashMap::new();
+    let mut dead = HashSet::new();
+    for line in out.lines() {
+        let parts: Vec<&str> = line.splitn(6, '\t').collect();
+        let [name, activity, window_active, pane_active, pane_dead, window] = parts[..] else {
+            continue;
+        };
+        sessions.insert(name.to_string(), activity.parse::<i64>().unwrap_or(0));
+        if pane_active == "1" && pane_dead == "1" {
+            dead.insert(TmuxManager::window_target(name, window));
+            if window_active == "1" {
+                dead.insert(name.to_string());
+            }
+        }
+    }
+    (sessions, dead)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn pane_list_gives_activity_and_dead_targets() {
+        let out = "api_1\t100\t1\t1\t0\tmain\n\
+                   api_1\t100\t0\t1\t1\tdev server\n\
+                   web_2\t200\t1\t0\t1\tmain\n\
+                   web_2\t200\t1\t1\t1\tmain\n\
+                   garbage\n";
+        let (sessions, dead) = parse_pane_list(out);
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions["web_2"], 200);
+        let mut dead: Vec<_> = dead.into_iter().collect();
+        dead.sort();
+        assert_eq!(dead, ["api_1:=dev server", "web_2", "web_2:=main"]);
+    }
+
     #[allow(deprecated)]
     #[test]
     fn test_session_name_legacy() {
@@ -1097,6 +1536,104 @@ mod tests {
         );
     }
 
+    #[test]
+    fn compare_sessions_never_look_like_agent_sessions() {
+        let agent = TmuxManager::build_session_name("compare~x", "abcdef123456");
+        assert!(!TmuxManager::is_compare_session(&agent));
+        assert!(TmuxManager::is_compare_session(&format!(
+            "{COMPARE_PREFIX}api_1~web_2"
+        )));
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+    }
+
+    #[test]
+    fn build_session_name_survives_unicode_and_odd_ids() {
+        assert_eq!(
+            TmuxManager::build_session_name("日本語 ✨", "abcdef123456"),
+            "session_abcdef12"
+        );
+        let name = TmuxManager::build_session_name("a;b", "é.b:c");
+        assert_eq!(name, "a-b_--b-c");
+        assert!(validate_session_name(&name).is_ok());
+    }
+
+    #[test]
+    fn session_names_reject_target_separators() {
+        assert!(validate_session_name("api_1a2b3c4d").is_ok());
+        assert!(validate_session_name("日本語").is_ok());
+        assert!(validate_session_name("").is_err());
+        assert!(validate_session_name("a:b").is_err());
+        assert!(validate_session_name("v1.2").is_err());
+        assert!(validate_session_name("a\nb").is_err());
+    }
+
+    #[test]
+    fn commands_are_one_argv_element_after_the_wrapper() {
+        for command in [
+            r#"claude -p "it's done""#,
+            "echo a; echo b",
+            "echo $HOME `id` $(whoami)",
+            "echo 日本語 ✨",
+        ] {
+            let args = new_session_args("s_1", "/w/a b", "/bin/zsh", Some(command));
+            assert_eq!(
+                args,
+                [
+                    "new-session",
+                    "-d",
+                    "-s",
+                    "s_1",
+                    "-c",
+                    "/w/a b",
+                    "/bin/zsh",
+                    "-c",
+                    COMMAND_WRAPPER,
+                    "/bin/zsh",
+                    command,
+                ]
+            );
+        }
+        assert_eq!(
+            new_session_args("s_1", "/w", "/bin/bash", None),
+            ["new-session", "-d", "-s", "s_1", "-c", "/w", "/bin/bash", "-l"]
+        );
+    }
+
+    #[test]
+    fn new_windows_run_their_command_through_the_wrapper() {
+        assert_eq!(
+            new_window_args("s_1:", "dev", "/w", "/bin/zsh", "npm run dev"),
+            [
+                "new-window",
+                "-d",
+                "-t",
+                "s_1:",
+                "-n",
+                "dev",
+                "-c",
+                "/w",
+                "/bin/zsh",
+                "-c",
+                COMMAND_WRAPPER,
+                "/bin/zsh",
+                "npm run dev",
+            ]
+        );
+        assert_eq!(TmuxManager::window_target("s_1", "dev"), "s_1:=dev");
+    }
+
+    #[test]
+    fn send_keys_args_end_options_before_the_keys() {
+        assert_eq!(
+            send_keys_args("s_1", "-n $x; 'q' \"日本\"", true),
+            ["send-keys", "-t", "s_1", "-l", "--", "-n $x; 'q' \"日本\""]
+        );
+        assert_eq!(
+            send_keys_args("s_1", "C-c", false),
+            ["send-keys", "-t", "s_1", "--", "C-c"]
+        );
+    }
+
     #[test]
     fn test_empty_title() {
         assert_eq!(
@@ -1110,4 +1647,13 @@ mod tests {
         let available = TmuxManager::is_available().await.unwrap_or(false);
         println!("Tmux available: {}", available);
     }
+
+    #[test]
+    fn tail_lines_trims_blank_screen_and_keeps_the_tail() {
+        let text = "one\ntwo\nthree\n\n\n";
+        assert_eq!(tail_lines(text, None), "one\ntwo\nthree\n");
+        assert_eq!(tail_lines(text, Some(2)), "two\nthree\n");
+        assert_eq!(tail_lines(text, Some(10)), "one\ntwo\nthree\n");
+        assert_eq!(tail_lines("\n\n", None), "");
+    }
 }
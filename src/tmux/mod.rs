@@ -1,26 +1,66 @@
 mod cache;
+pub mod calls;
 mod detector;
+pub mod discovery;
+pub mod env;
 mod manager;
 pub mod ptmx;
 pub mod resume_adapter;
 mod session;
 pub mod session_id_scanner;
+pub mod tool_integration;
+pub mod version;
+
+use std::sync::RwLock;
 
 pub use cache::SessionCache;
-pub use detector::{set_status_detection_config, PromptDetector, Tool};
+pub use detector::{set_status_detection_config, strip_ansi, PatternMatch, PromptDetector, Tool};
+pub(crate) use manager::shell_quote;
 pub use manager::TmuxManager;
 pub use session::{SessionStatus, TmuxSession};
 
 pub const SESSION_PREFIX: &str = "agentdeck_rs_";
 
+/// Prefix of the temporary side-by-side comparison sessions. `~` never
+/// appears in names built from titles, so these cannot collide.
+pub const COMPARE_PREFIX: &str = "compare~";
+
+/// Server name template when `[tmux] server` is unset.
+const DEFAULT_SERVER_TEMPLATE: &str = "agenthand_{profile}";
+
+/// The one server all profiles shared before each got its own. Sessions
+/// still running there are reported by `agent-hand doctor`.
+pub const LEGACY_SERVER: &str = "agentdeck_rs";
+
+static SERVER_TEMPLATE: RwLock<Option<String>> = RwLock::new(None);
+
+/// Install the `[tmux] server` template for this process. A template that
+/// cannot name a socket is refused (returns false) and the default kept.
+pub fn set_server_template(template: &str) -> bool {
+    if template.trim().is_empty() || template.contains('/') {
+        return false;
+    }
+    *SERVER_TEMPLATE.write().unwrap_or_else(|e| e.into_inner()) = Some(template.to_string());
+    true
+}
+
 /// Single source of truth for the tmux server socket name.
 /// All tmux commands must use `-L <server_name>` with this value.
 pub fn server_name_for_profile(profile: &str) -> String {
-    format!("agenthand_{}", profile)
+    let template = SERVER_TEMPLATE.read().unwrap_or_else(|e| e.into_inner());
+    let template = template.as_deref().unwrap_or(DEFAULT_SERVER_TEMPLATE);
+    server_name_from(template, profile)
+}
+
+/// `{profile}` in `template` replaced by the profile name. A template
+/// without it puts every profile on the same server.
+fn server_name_from(template: &str, profile: &str) -> String {
+    template.replace("{profile}", profile)
 }
 
 /// Build a `tokio::process::Command` pre-configured with `-L <server>`.
 pub fn async_tmux_cmd(profile: &str) -> tokio::process::Command {
+    calls::count_spawn();
     let mut cmd = tokio::process::Command::new("tmux");
     cmd.args(["-L", &server_name_for_profile(profile)]);
     cmd
@@ -29,7 +69,23 @@ pub fn async_tmux_cmd(profile: &str) -> tokio::process::Command {
 /// Build a `std::process::Command` pre-configured with `-L <server>`.
 /// Use in `spawn_blocking` or other sync contexts.
 pub fn sync_tmux_cmd(profile: &str) -> std::process::Command {
+    calls::count_spawn();
     let mut cmd = std::process::Command::new("tmux");
     cmd.args(["-L", &server_name_for_profile(profile)]);
     cmd
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn server_names_follow_the_template() {
+        assert_eq!(
+            server_name_from(DEFAULT_SERVER_TEMPLATE, "work"),
+            "agenthand_work"
+        );
+        assert_eq!(server_name_from("ah-{profile}-2", "work"), "ah-work-2");
+        assert_eq!(server_name_from(LEGACY_SERVER, "work"), LEGACY_SERVER);
+    }
+}
@@ -182,12 +182,43 @@ pub async fn scan_ptmx_usage(system_max: u32, server_name: &str) -> PtmxReport {
     }
 }
 
+/// PTY FDs held by one tmux session's process tree; 0 when it is not running.
+pub async fn scan_session_ptmx(server_name: &str, session_name: &str) -> u32 {
+    let (fd_counts, pane_pids) =
+        tokio::join!(lsof_ptmx_counts(), get_tmux_pane_pids(server_name));
+
+    let mut count = 0;
+    for (_, pane_pid) in pane_pids.iter().filter(|(name, _)| name == session_name) {
+        let tree = collect_process_tree(*pane_pid).await;
+        count += tree.iter().filter_map(|pid| fd_counts.get(pid)).sum::<u32>();
+    }
+    count
+}
+
+/// Whether more than `percent`% of the system PTY limit is in use.
+pub fn over_limit(system_total: u32, system_max: u32, percent: u32) -> bool {
+    system_max > 0 && u64::from(system_total) * 100 > u64::from(system_max) * u64::from(percent)
+}
+
+/// The `n` largest PTY holders, largest first. Ties keep their order.
+pub fn top_consumers<'a>(
+    counts: impl IntoIterator<Item = (&'a str, u32)>,
+    n: usize,
+) -> Vec<(&'a str, u32)> {
+    let mut counts: Vec<_> = counts.into_iter().filter(|(_, c)| *c > 0).collect();
+    counts.sort_by_key(|&(_, c)| std::cmp::Reverse(c));
+    counts.truncate(n);
+    counts
+}
+
 /// Spawn a background task that periodically scans PTY usage.
 ///
-/// The task runs immediately upon spawn, then every 30 minutes.
-/// It updates the shared state which can be read by the UI thread.
+/// The task runs immediately upon spawn, then every `every` (`[ptmx]
+/// interval_minutes`). It updates the shared state which can be read by
+/// the UI thread.
 pub fn spawn_ptmx_monitor(
     system_max: u32,
+    every: Duration,
     state: SharedPtmxState,
     server_name: String,
 ) -> tokio::task::JoinHandle<()> {
@@ -195,8 +226,9 @@ pub fn spawn_ptmx_monitor(
         // Perform initial scan immediately
         perform_scan(&state, system_max, &server_name).await;
 
-        // Then scan every 30 minutes
-        let mut interval = tokio::time::interval(Duration::from_secs(30 * 60));
+        let mut interval = tokio::time::interval(every);
+        // The first tick completes at once; the initial scan stands in for it.
+        interval.tick().await;
 
         loop {
             interval.tick().await;
@@ -226,3 +258,23 @@ async fn perform_scan(state: &SharedPtmxState, system_max: u32, server_name: &st
         guard.is_scanning = false;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warns_only_above_the_percentage() {
+        assert!(!over_limit(80, 100, 80));
+        assert!(over_limit(81, 100, 80));
+        assert!(!over_limit(5, 0, 80));
+        assert!(over_limit(u32::MAX, u32::MAX - 1, 100));
+    }
+
+    #[test]
+    fn top_consumers_are_largest_first() {
+        let counts = [("a", 2), ("b", 9), ("c", 0), ("d", 4), ("e", 4)];
+        assert_eq!(top_consumers(counts, 3), vec![("b", 9), ("d", 4), ("e", 4)]);
+        assert_eq!(top_consumers([("c", 0)], 3), vec![]);
+    }
+}
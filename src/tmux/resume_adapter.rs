@@ -142,7 +142,7 @@ fn parse_codex_session_id(parts: &[&str]) -> Option<String> {
     None
 }
 
-/// Parse Gemini session ID: gemini --resume <id>, -r <id>
+/// Parse Gemini session ID: gemini --resume <id>, -r <id>, --resume=<id>
 fn parse_gemini_session_id(parts: &[&str]) -> Option<String> {
     for (i, part) in parts.iter().enumerate() {
         if (*part == "--resume" || *part == "-r") && i + 1 < parts.len() {
@@ -151,6 +151,11 @@ fn parse_gemini_session_id(parts: &[&str]) -> Option<String> {
                 return Some(candidate.to_string());
             }
         }
+        if let Some(rest) = part.strip_prefix("--resume=") {
+            if looks_like_session_id(rest) {
+                return Some(rest.to_string());
+            }
+        }
     }
     None
 }
@@ -264,6 +269,14 @@ mod tests {
         assert_eq!(parsed_id.as_deref(), Some(sid));
     }
 
+    #[test]
+    fn test_parse_gemini_resume_equals() {
+        let (tool, parsed_id) =
+            parse_resume_args("gemini --resume=3b44bc68-8a1c-4f0e-9c2d-5e6f7a8b9c0d");
+        assert_eq!(tool, Some(Tool::Gemini));
+        assert_eq!(parsed_id.as_deref(), Some("3b44bc68-8a1c-4f0e-9c2d-5e6f7a8b9c0d"));
+    }
+
     #[test]
     fn test_round_trip_with_extra_flags() {
         let sid = "abc12345def";
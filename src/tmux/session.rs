@@ -14,38 +14,64 @@ pub enum SessionStatus {
     Running,  // Actively working
     Waiting,  // Needs user input
     Idle,     // Ready for commands
-    Error,    // Session doesn't exist or error
+    Error,    // Exited unexpectedly or failed
     Starting, // Being created
+    Stopped,  // Session doesn't exist
 }
 
 /// Wrapper around a tmux session
 #[derive(Debug)]
 pub struct TmuxSession {
     name: String,
+    /// Window the agent runs in, when the session has several.
+    window: Option<String>,
     working_dir: PathBuf,
     tool: Tool,
     manager: Arc<TmuxManager>,
     status: Arc<RwLock<SessionStatus>>,
     last_activity: Arc<RwLock<Option<SystemTime>>>,
+    waiting_reason: Arc<RwLock<Option<String>>>,
 }
 
 impl TmuxSession {
     pub fn new(name: String, working_dir: PathBuf, tool: Tool, manager: Arc<TmuxManager>) -> Self {
         Self {
             name,
+            window: None,
             working_dir,
             tool,
             manager,
             status: Arc::new(RwLock::new(SessionStatus::Idle)),
             last_activity: Arc::new(RwLock::new(None)),
+            waiting_reason: Arc::new(RwLock::new(None)),
         }
     }
 
+    /// Probe, capture and attach to the window named `window` instead of
+    /// the session's current one.
+    pub fn with_window(mut self, window: Option<String>) -> Self {
+        self.window = window;
+        self
+    }
+
     /// Get session name
     pub fn name(&self) -> &str {
         &self.name
     }
 
+    /// tmux target for the agent's pane: the session, or its window
+    pub fn target(&self) -> String {
+        match &self.window {
+            Some(window) => TmuxManager::window_target(&self.name, window),
+            None => self.name.clone(),
+        }
+    }
+
+    /// The manager this session talks to tmux through
+    pub fn manager(&self) -> &Arc<TmuxManager> {
+        &self.manager
+    }
+
     /// Get working directory
     pub fn working_dir(&self) -> &PathBuf {
         &self.working_dir
@@ -66,6 +92,11 @@ impl TmuxSession {
         *self.status.write() = status;
     }
 
+    /// What the prompt asks, as of the last `update_status` that found one
+    pub fn waiting_reason(&self) -> Option<String> {
+        self.waiting_reason.read().clone()
+    }
+
     /// Check if session exists in tmux
     pub fn exists(&self) -> bool {
         self.manager.session_exists(&self.name).unwrap_or(false)
@@ -73,18 +104,28 @@ impl TmuxSession {
 
     /// Update status by checking tmux pane content
     pub async fn update_status(&self) -> Result<SessionStatus> {
-        // Check if session exists
-        if !self.exists() {
-            self.set_status(SessionStatus::Error);
-            return Ok(SessionStatus::Error);
+        // A session or pane that went away mid-run crashed; otherwise it stopped
+        if !self.exists() || self.manager.pane_dead(&self.target()) {
+            let status = match self.status() {
+                SessionStatus::Running | SessionStatus::Error => SessionStatus::Error,
+                _ => SessionStatus::Stopped,
+            };
+            self.set_status(status);
+            *self.waiting_reason.write() = None;
+            return Ok(status);
         }
 
         // Capture recent pane content
-        let content = self.manager.capture_pane(&self.name, 50).await?;
+        let content = self.manager.capture_pane(&self.target(), 50).await?;
 
         // Use prompt detector to determine state
         let detector = PromptDetector::new(self.tool);
         let has_prompt = detector.has_prompt(&content);
+        *self.waiting_reason.write() = if has_prompt {
+            detector.waiting_summary(&content)
+        } else {
+            None
+        };
 
         // Check for activity changes
         let activity = self.manager.session_activity(&self.name);
@@ -139,23 +180,34 @@ impl TmuxSession {
     /// Stop the session (kill in tmux)
     pub async fn stop(&self) -> Result<()> {
         self.manager.kill_session(&self.name).await?;
-        self.set_status(SessionStatus::Error);
+        self.set_status(SessionStatus::Stopped);
         Ok(())
     }
 
     /// Send keys to the session
     pub async fn send_keys(&self, keys: &str) -> Result<()> {
-        self.manager.send_keys(&self.name, keys).await
+        self.manager.send_keys(&self.target(), keys).await
     }
 
     /// Attach to the session
     pub async fn attach(&self) -> Result<()> {
+        if self.window.is_some() {
+            let _ = self.manager.select_window(&self.target()).await;
+        }
         self.manager.attach_session(&self.name).await
     }
 
+    /// Attach to the session and return tmux's exit status
+    pub async fn attach_status(&self) -> Result<std::process::ExitStatus> {
+        if self.window.is_some() {
+            let _ = self.manager.select_window(&self.target()).await;
+        }
+        self.manager.attach_session_status(&self.name).await
+    }
+
     /// Get pane content (for debugging or output extraction)
     pub async fn get_content(&self, lines: usize) -> Result<String> {
-        self.manager.capture_pane(&self.name, lines).await
+        self.manager.capture_pane(&self.target(), lines).await
     }
 
     /// Capture full pane output (convenience wrapper for context collection)
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -99,16 +99,19 @@ async fn perform_scan(state: &SharedScanState, server_name: &str) {
         let (detected_tool, detected_id) =
             detect_from_process_tree(pane_pid, target.tool).await;
 
-        // Strategy 2: If Claude detected but no ID from args, try project files
-        let final_id = if detected_id.is_some() {
+        // Strategy 2: no ID in the args — look where the tool stores its
+        // conversations (Claude, Gemini). Skipped once an ID is known.
+        let final_id = if detected_id.is_some() || target.has_session_id {
             detected_id
         } else {
             let tool = detected_tool.unwrap_or(target.tool);
-            if tool == Tool::Claude {
-                detect_from_claude_project_files(&target.project_path).await
-            } else {
-                None
-            }
+            let project_path = target.project_path.clone();
+            tokio::task::spawn_blocking(move || {
+                super::tool_integration::stored_session_id(tool, &project_path)
+            })
+            .await
+            .ok()
+            .flatten()
         };
 
         if detected_tool.is_some() || final_id.is_some() {
@@ -233,60 +236,11 @@ fn looks_like_session_id(s: &str) -> bool {
     super::resume_adapter::looks_like_session_id(s)
 }
 
-/// Try to detect a Claude session ID from project files.
-///
-/// Scans `~/.claude/projects/<encoded-project-path>/` for `.jsonl` files,
-/// whose filenames (without extension) are session IDs.
-///
-/// Returns `Some(id)` only when exactly one matching file exists (medium confidence).
-/// When multiple sessions exist in the same directory, returns `None` to avoid
-/// guessing the wrong session.
-async fn detect_from_claude_project_files(project_path: &PathBuf) -> Option<String> {
-    let project_dir = derive_claude_project_dir(project_path)?;
-
-    if !project_dir.exists() {
-        return None;
-    }
-
-    let mut entries = match tokio::fs::read_dir(&project_dir).await {
-        Ok(e) => e,
-        Err(_) => return None,
-    };
-
-    let mut candidates: Vec<String> = Vec::new();
-
-    while let Ok(Some(entry)) = entries.next_entry().await {
-        let path = entry.path();
-        if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
-            continue;
-        }
-
-        let stem = match path.file_stem().and_then(|s| s.to_str()) {
-            Some(s) => s.to_string(),
-            None => continue,
-        };
-
-        if !super::resume_adapter::looks_like_session_id(&stem) {
-            continue;
-        }
-
-        candidates.push(stem);
-    }
-
-    // Only use the file-based fallback when there's exactly one session file.
-    // Multiple files → ambiguous, don't guess.
-    if candidates.len() == 1 {
-        candidates.into_iter().next()
-    } else {
-        None
-    }
-}
-
 /// Derive the Claude project directory for a given project path.
 ///
 /// Claude stores project data at `~/.claude/projects/<encoded-path>/`
 /// where the path is the absolute project path with `/` replaced by `-`.
-fn derive_claude_project_dir(project_path: &PathBuf) -> Option<PathBuf> {
+pub(crate) fn derive_claude_project_dir(project_path: &Path) -> Option<PathBuf> {
     let home = dirs::home_dir()?;
     let path_str = project_path.to_str()?;
 
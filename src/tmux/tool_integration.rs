@@ -0,0 +1,169 @@
+//! Where each AI CLI keeps its conversations on disk, so the session-ID
+//! scanner can find a conversation the process arguments do not name (a
+//! fresh `claude` or `gemini` started without `--resume`).
+
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use super::detector::Tool;
+use super::resume_adapter::looks_like_session_id;
+
+/// A CLI whose conversations can be found on disk.
+pub trait ToolIntegration: Sync {
+    /// Conversation files the tool has stored for `project_path`.
+    fn conversation_files(&self, project_path: &Path) -> Vec<PathBuf>;
+
+    /// The session ID `--resume` takes for one of those files.
+    fn session_id_of(&self, file: &Path) -> Option<String>;
+}
+
+/// Claude Code: `~/.claude/projects/<encoded path>/<session id>.jsonl`.
+pub struct Claude;
+
+impl ToolIntegration for Claude {
+    fn conversation_files(&self, project_path: &Path) -> Vec<PathBuf> {
+        let Some(dir) = super::session_id_scanner::derive_claude_project_dir(project_path) else {
+            return Vec::new();
+        };
+        files_in(&dir, |path| {
+            path.extension().and_then(|e| e.to_str()) == Some("jsonl")
+                && path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .is_some_and(looks_like_session_id)
+        })
+    }
+
+    fn session_id_of(&self, file: &Path) -> Option<String> {
+        file.file_stem()?.to_str().map(str::to_string)
+    }
+}
+
+/// Gemini CLI: `~/.gemini/tmp/<sha256 of path>/chats/session-*.json`, with
+/// the ID in the file's `sessionId` field.
+pub struct Gemini;
+
+impl ToolIntegration for Gemini {
+    fn conversation_files(&self, project_path: &Path) -> Vec<PathBuf> {
+        let Some(dir) = gemini_project_dir(project_path) else {
+            return Vec::new();
+        };
+        files_in(&dir.join("chats"), |path| {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            name.starts_with("session-") && name.ends_with(".json")
+        })
+    }
+
+    fn session_id_of(&self, file: &Path) -> Option<String> {
+        let text = std::fs::read_to_string(file).ok()?;
+        gemini_session_id(&text)
+    }
+}
+
+/// The integration for `tool`, if it stores conversations we can read.
+pub fn integration(tool: Tool) -> Option<&'static dyn ToolIntegration> {
+    match tool {
+        Tool::Claude => Some(&Claude),
+        Tool::Gemini => Some(&Gemini),
+        _ => None,
+    }
+}
+
+/// The session ID of the conversation `tool` stored for `project_path`.
+/// Only when there is exactly one: with several, guessing could resume the
+/// wrong conversation.
+pub fn stored_session_id(tool: Tool, project_path: &Path) -> Option<String> {
+    let integration = integration(tool)?;
+    let files = integration.conversation_files(project_path);
+    let [file] = files.as_slice() else {
+        return None;
+    };
+    integration
+        .session_id_of(file)
+        .filter(|id| looks_like_session_id(id))
+}
+
+/// Gemini CLI's per-project directory: `~/.gemini/tmp/<hex sha256 of the
+/// absolute project path>`.
+pub fn gemini_project_dir(project_path: &Path) -> Option<PathBuf> {
+    let home = dirs::home_dir()?;
+    let path_str = project_path.to_str()?;
+    let hash = hex::encode(Sha256::digest(path_str.as_bytes()));
+    Some(home.join(".gemini").join("tmp").join(hash))
+}
+
+fn gemini_session_id(chat_json: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(chat_json).ok()?;
+    value.get("sessionId")?.as_str().map(str::to_string)
+}
+
+fn files_in(dir: &Path, keep: impl Fn(&Path) -> bool) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| keep(path))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gemini_dir_is_keyed_by_path_hash() {
+        let dir = gemini_project_dir(Path::new("/home/me/project")).unwrap();
+        let hash = dir.file_name().unwrap().to_str().unwrap();
+        assert_eq!(hash.len(), 64);
+        assert!(dir.ends_with(Path::new(".gemini/tmp").join(hash)));
+        assert_ne!(
+            gemini_project_dir(Path::new("/home/me/other")),
+            Some(dir.clone())
+        );
+    }
+
+    #[test]
+    fn gemini_session_id_comes_from_the_chat_file() {
+        let chat = r#"{"sessionId":"3b44bc68-8a1c-4f0e-9c2d-5e6f7a8b9c0d","projectHash":"ab","messages":[]}"#;
+        assert_eq!(
+            gemini_session_id(chat).as_deref(),
+            Some("3b44bc68-8a1c-4f0e-9c2d-5e6f7a8b9c0d")
+        );
+        assert_eq!(gemini_session_id(r#"{"messages":[]}"#), None);
+        assert_eq!(gemini_session_id("not json"), None);
+    }
+
+    #[test]
+    fn gemini_chat_files_are_read_for_their_id() {
+        let home = tempfile::tempdir().unwrap();
+        let previous_home = std::env::var_os("HOME");
+        std::env::set_var("HOME", home.path());
+
+        let project = Path::new("/home/me/project");
+        let chats = gemini_project_dir(project).unwrap().join("chats");
+        std::fs::create_dir_all(&chats).unwrap();
+        std::fs::write(
+            chats.join("session-2025-01-01T10-00-aaaa1111.json"),
+            r#"{"sessionId":"aaaa1111-0000-0000-0000-000000000000"}"#,
+        )
+        .unwrap();
+        std::fs::write(chats.join("session-notes.txt"), "").unwrap();
+        std::fs::write(chats.join("notes.json"), "{}").unwrap();
+
+        let files = Gemini.conversation_files(project);
+
+        match previous_home {
+            Some(h) => std::env::set_var("HOME", h),
+            None => std::env::remove_var("HOME"),
+        }
+        assert_eq!(files.len(), 1);
+        assert!(files[0].starts_with(home.path()));
+        assert_eq!(
+            Gemini.session_id_of(&files[0]).as_deref(),
+            Some("aaaa1111-0000-0000-0000-000000000000")
+        );
+    }
+}
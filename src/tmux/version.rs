@@ -0,0 +1,251 @@
+//! The installed tmux version and what it supports.
+//!
+//! `tmux -V` is run once per process. Every version-dependent feature has a
+//! check function here returning [`Unsupported`] when the detected tmux is
+//! too old; an undetectable version is assumed to support everything.
+
+use std::fmt;
+use std::sync::OnceLock;
+
+/// A parsed tmux version, ordered by release.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TmuxVersion {
+    pub major: u32,
+    pub minor: u32,
+    /// False for development builds ("next-3.4", "3.4-rc"), which sort
+    /// before the release they lead up to.
+    pub release: bool,
+    /// Patch letter: "3.3a" is 3.3 with `Some('a')`.
+    pub letter: Option<char>,
+}
+
+impl TmuxVersion {
+    pub const fn new(major: u32, minor: u32) -> Self {
+        Self {
+            major,
+            minor,
+            release: true,
+            letter: None,
+        }
+    }
+
+    /// Parse the output of `tmux -V`, e.g. "tmux 3.3a", "tmux next-3.4" or
+    /// "tmux openbsd-7.4".
+    pub fn parse(output: &str) -> Option<Self> {
+        let token = output.trim().strip_prefix("tmux").unwrap_or(output).trim();
+        if let Some(rest) = token.strip_prefix("openbsd-") {
+            let (major, minor, _) = numeric(rest)?;
+            return Some(openbsd_tmux(major, minor));
+        }
+        let (token, release) = match token.strip_prefix("next-") {
+            Some(rest) => (rest, false),
+            None => (token, true),
+        };
+        let (major, minor, rest) = numeric(token)?;
+        let mut chars = rest.chars();
+        let letter = chars.next().filter(char::is_ascii_lowercase);
+        let rest = if letter.is_some() { chars.as_str() } else { rest };
+        Some(Self {
+            major,
+            minor,
+            release: release && !rest.starts_with('-'),
+            letter,
+        })
+    }
+
+    /// Whether this is at least `major.minor`. Development builds count as
+    /// the release they lead up to.
+    pub fn at_least(&self, major: u32, minor: u32) -> bool {
+        (self.major, self.minor) >= (major, minor)
+    }
+}
+
+impl fmt::Display for TmuxVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.release {
+            write!(f, "next-")?;
+        }
+        write!(f, "{}.{}", self.major, self.minor)?;
+        if let Some(letter) = self.letter {
+            write!(f, "{letter}")?;
+        }
+        Ok(())
+    }
+}
+
+/// "3.1c" → (3, 1, "c"); the minor part may be missing ("3" → (3, 0, "")).
+fn numeric(s: &str) -> Option<(u32, u32, &str)> {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let major = s[..end].parse().ok()?;
+    let rest = &s[end..];
+    let Some(rest) = rest.strip_prefix('.') else {
+        return Some((major, 0, rest));
+    };
+    let end = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+    let minor = rest[..end].parse().ok()?;
+    Some((major, minor, &rest[end..]))
+}
+
+/// OpenBSD's base tmux reports the OS release instead of its own version.
+fn openbsd_tmux(major: u32, minor: u32) -> TmuxVersion {
+    match (major, minor) {
+        (7, 5..) | (8.., _) => TmuxVersion::new(3, 4),
+        (7, 1..) => TmuxVersion::new(3, 3),
+        (6, 9..) | (7, 0) => TmuxVersion::new(3, 2),
+        (6, 7..) => TmuxVersion::new(3, 1),
+        (6, 6) => TmuxVersion::new(3, 0),
+        _ => TmuxVersion::new(2, 9),
+    }
+}
+
+/// What `tmux -V` reported, and what it parsed to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedTmux {
+    /// The reported version string without the "tmux " prefix.
+    pub raw: String,
+    pub version: Option<TmuxVersion>,
+}
+
+impl DetectedTmux {
+    fn from_output(output: &str) -> Self {
+        let raw = output.trim();
+        Self {
+            raw: raw.strip_prefix("tmux ").unwrap_or(raw).to_string(),
+            version: TmuxVersion::parse(raw),
+        }
+    }
+}
+
+/// The installed tmux, probed on first use. None if tmux could not be run.
+pub fn detected() -> Option<&'static DetectedTmux> {
+    static DETECTED: OnceLock<Option<DetectedTmux>> = OnceLock::new();
+    DETECTED
+        .get_or_init(|| {
+            let output = std::process::Command::new("tmux").arg("-V").output().ok()?;
+            if !output.status.success() {
+                return None;
+            }
+            let detected = DetectedTmux::from_output(&String::from_utf8_lossy(&output.stdout));
+            if detected.version.is_none() {
+                tracing::warn!("unrecognized tmux version '{}'", detected.raw);
+            }
+            Some(detected)
+        })
+        .as_ref()
+}
+
+/// A feature the detected tmux is too old for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unsupported {
+    pub feature: &'static str,
+    pub required: TmuxVersion,
+    pub found: String,
+}
+
+impl fmt::Display for Unsupported {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} requires tmux ≥{} (found {})",
+            self.feature, self.required, self.found
+        )
+    }
+}
+
+fn require(
+    detected: Option<&DetectedTmux>,
+    feature: &'static str,
+    major: u32,
+    minor: u32,
+) -> Result<(), Unsupported> {
+    match detected {
+        Some(DetectedTmux {
+            raw,
+            version: Some(v),
+        }) if !v.at_least(major, minor) => Err(Unsupported {
+            feature,
+            required: TmuxVersion::new(major, minor),
+            found: raw.clone(),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// `display-popup`, used by the switcher key and the viewer info key.
+pub fn popup(detected: Option<&DetectedTmux>) -> Result<(), Unsupported> {
+    require(detected, "switcher popup", 3, 2)
+}
+
+/// `new-session -e VAR=value` for per-session environment.
+pub fn new_session_env(detected: Option<&DetectedTmux>) -> Result<(), Unsupported> {
+    require(detected, "new-session -e", 3, 0)
+}
+
+/// The `copy-mode-vi` key table and `send -X` copy commands.
+pub fn copy_mode_commands(detected: Option<&DetectedTmux>) -> Result<(), Unsupported> {
+    require(detected, "copy-mode bindings", 2, 4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(major: u32, minor: u32, release: bool, letter: Option<char>) -> TmuxVersion {
+        TmuxVersion {
+            major,
+            minor,
+            release,
+            letter,
+        }
+    }
+
+    #[test]
+    fn parses_version_strings() {
+        let table = [
+            ("tmux 3.3a", Some(v(3, 3, true, Some('a')))),
+            ("tmux 3.1c\n", Some(v(3, 1, true, Some('c')))),
+            ("tmux 2.9", Some(v(2, 9, true, None))),
+            ("tmux 3.4", Some(v(3, 4, true, None))),
+            ("tmux next-3.4", Some(v(3, 4, false, None))),
+            ("tmux 3.4-rc", Some(v(3, 4, false, None))),
+            ("tmux 3.0-rc5", Some(v(3, 0, false, None))),
+            ("tmux 3", Some(v(3, 0, true, None))),
+            ("tmux openbsd-7.4", Some(TmuxVersion::new(3, 3))),
+            ("tmux openbsd-6.8", Some(TmuxVersion::new(3, 1))),
+            ("tmux openbsd-6.4", Some(TmuxVersion::new(2, 9))),
+            ("3.2a", Some(v(3, 2, true, Some('a')))),
+            ("tmux master", None),
+            ("", None),
+        ];
+        for (input, expected) in table {
+            assert_eq!(TmuxVersion::parse(input), expected, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn orders_by_release() {
+        let parse = |s| TmuxVersion::parse(s).unwrap();
+        assert!(parse("tmux 3.1c") < parse("tmux 3.2"));
+        assert!(parse("tmux 3.3") < parse("tmux 3.3a"));
+        assert!(parse("tmux next-3.4") < parse("tmux 3.4"));
+        assert!(parse("tmux 3.3a") < parse("tmux next-3.4"));
+        assert!(parse("tmux next-3.2").at_least(3, 2));
+        assert_eq!(parse("tmux next-3.4").to_string(), "next-3.4");
+        assert_eq!(parse("tmux 3.1c").to_string(), "3.1c");
+    }
+
+    #[test]
+    fn capability_checks() {
+        let old = DetectedTmux::from_output("tmux 3.1c");
+        let err = popup(Some(&old)).unwrap_err();
+        assert_eq!(err.to_string(), "switcher popup requires tmux ≥3.2 (found 3.1c)");
+        assert!(new_session_env(Some(&old)).is_ok());
+
+        let ancient = DetectedTmux::from_output("tmux 2.3");
+        assert!(copy_mode_commands(Some(&ancient)).is_err());
+
+        // Unknown or undetectable versions are not held back.
+        assert!(popup(Some(&DetectedTmux::from_output("tmux master"))).is_ok());
+        assert!(popup(None).is_ok());
+    }
+}
@@ -131,6 +131,8 @@ impl App {
             Err(e) => return ControlResponse::Error { message: format!("storage error: {e}") },
         };
 
+        instance.title =
+            crate::session::titles::unique(&instances, &instance.group_path, &instance.title);
         if !instance.group_path.is_empty() {
             tree.create_group(instance.group_path.clone());
         }
@@ -236,8 +238,9 @@ impl App {
         let command = if session.command.trim().is_empty() {
             None
         } else {
-            Some(session.command.clone())
+            Some(session.command.as_str())
         };
+        let command = session.launch_command(command);
         let title = session.title.clone();
 
         match self
@@ -251,6 +254,7 @@ impl App {
             .await
         {
             Ok(_) => {
+                self.open_windows_for(id).await;
                 let _ = self.refresh_sessions().await;
                 ControlResponse::Ok {
                     message: format!("session started: {id}"),
@@ -281,6 +285,7 @@ impl App {
 
         match self.tmux.kill_session(&tmux_name).await {
             Ok(_) => {
+                self.mark_stopped(id);
                 let _ = self.refresh_sessions().await;
                 ControlResponse::Ok {
                     message: format!("session stopped: {id}"),
@@ -318,7 +323,7 @@ impl App {
             let project_path = session.project_path.to_string_lossy().to_string();
             let sid = session.cli_session_id().unwrap().to_string();
             let resume_cmd = match self.build_resume_command_for_session(session, &sid) {
-                Ok(cmd) => cmd,
+                Ok(cmd) => session.launch_command(Some(&cmd)).unwrap_or(cmd),
                 Err(e) => {
                     return ControlResponse::Error {
                         message: format!("resume build error: {e}"),
@@ -340,6 +345,7 @@ impl App {
                 .await
             {
                 Ok(_) => {
+                    self.open_windows_for(id).await;
                     let _ = self.refresh_sessions().await;
                     ControlResponse::Ok {
                         message: format!("session restarted with resume: {id}"),
@@ -383,7 +389,7 @@ impl App {
         let title = session.title.clone();
 
         let resume_cmd = match self.build_resume_command_for_session(session, &cli_session_id) {
-            Ok(cmd) => cmd,
+            Ok(cmd) => session.launch_command(Some(&cmd)).unwrap_or(cmd),
             Err(_) => {
                 return ControlResponse::Error {
                     message: format!("resume not supported for this tool type: {id}"),
@@ -403,6 +409,7 @@ impl App {
                 .await
             {
                 Ok(_) => {
+                    self.open_windows_for(id).await;
                     let _ = self.refresh_sessions().await;
                     ControlResponse::Ok {
                         message: format!("session resumed (new tmux): {id}"),
@@ -472,12 +479,12 @@ impl App {
             };
         }
 
-        match self.tmux.send_keys(&tmux_name, text).await {
+        match self.tmux.send_text(&tmux_name, text).await {
             Ok(_) => ControlResponse::Ok {
                 message: format!("prompt sent: {id}"),
             },
             Err(e) => ControlResponse::Error {
-                message: format!("send_keys error: {e}"),
+                message: format!("send_text error: {e}"),
             },
         }
     }
@@ -639,7 +646,7 @@ impl App {
             };
         }
 
-        match self.tmux.capture_pane(&tmux_name, lines).await {
+        match self.tmux.capture_pane(&session.tmux_target(), lines).await {
             Ok(content) => ControlResponse::TextContent { content },
             Err(e) => ControlResponse::Error {
                 message: format!("capture_pane error: {e}"),
@@ -879,6 +886,11 @@ impl App {
             .iter()
             .filter(|s| matches!(s.status, Status::Idle))
             .count();
+        let stopped = self
+            .sessions
+            .iter()
+            .filter(|s| matches!(s.status, Status::Stopped))
+            .count();
         let error = self
             .sessions
             .iter()
@@ -890,6 +902,7 @@ impl App {
             running,
             waiting,
             idle,
+            stopped,
             error,
         }
     }
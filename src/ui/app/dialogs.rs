@@ -1,5 +1,7 @@
 use super::*;
 
+use super::messages::GroupOp;
+
 impl App {
 
 
@@ -7,23 +9,80 @@ impl App {
 
     /// Open the AI analysis mode picker dialog (Max tier).
 
-    pub(super) fn open_fork_dialog(&mut self) {
+    pub(super) async fn open_fork_dialog(&mut self) {
         let Some(parent) = self.selected_session() else {
             return;
         };
 
         let title = format!("{} (fork)", parent.title);
 
+        // Only probe the claude CLI for Claude parents.
+        let caps = if parent.tool == crate::tmux::Tool::Claude {
+            crate::claude::fork::detect_capabilities().await
+        } else {
+            None
+        };
+        let context_copy = crate::claude::fork::plan_context_copy(parent, caps, |path, id| {
+            crate::claude::fork::claude_projects_dir()
+                .and_then(|dir| crate::claude::fork::find_conversation(&dir, path, id))
+        });
+
+        let worktree_root = self.config.worktree_root().unwrap_or_else(|| {
+            parent
+                .project_path
+                .parent()
+                .unwrap_or(&parent.project_path)
+                .to_path_buf()
+        });
+        let branch = crate::git::branch_slug(&title);
+
         self.dialog = Some(Dialog::Fork(ForkDialog {
             parent_session_id: parent.id.clone(),
             project_path: parent.project_path.clone(),
             title: TextInput::with_text(title),
             group_path: TextInput::with_text(parent.group_path.clone()),
             field: ForkField::Title,
+            copy_context: false,
+            context_copy,
+            worktree: false,
+            branch: TextInput::with_text(branch),
+            worktree_root,
+            error: None,
+        }));
+        self.state = AppState::Dialog;
+    }
+
+    pub(super) fn open_lineage_dialog(&mut self) {
+        let Some(focus_id) = self.selected_session().map(|s| s.id.clone()) else {
+            return;
+        };
+
+        if !crate::session::lineage::has_lineage(&self.sessions, &focus_id) {
+            self.set_info_bar(
+                "No forks: this session has no parent or children".to_string(),
+                ratatui::style::Color::Yellow,
+            );
+            return;
+        }
+
+        let rows = crate::session::lineage::build_lineage(&self.sessions, &focus_id);
+        let selected = rows.iter().position(|r| r.is_focus).unwrap_or(0);
+        self.dialog = Some(Dialog::Lineage(LineageDialog {
+            focus_id,
+            rows,
+            selected,
         }));
         self.state = AppState::Dialog;
     }
 
+    pub(super) fn open_waiting_queue_dialog(&mut self) {
+        let selected_id = crate::session::waiting::waiting_queue(&self.sessions)
+            .first()
+            .map(|s| s.id.clone());
+        self.dialog = Some(Dialog::WaitingQueue(WaitingQueueDialog { selected_id }));
+        self.state = AppState::Dialog;
+    }
+
     pub(super) fn open_create_group_dialog(&mut self) {
         let mut all_groups: Vec<String> = self
             .groups
@@ -47,8 +106,13 @@ impl App {
     }
 
     pub(super) fn open_move_group_dialog(&mut self) {
-        let Some(s) = self.selected_session() else {
-            return;
+        // With marks, the move applies to all of them and starts from an
+        // empty filter rather than one session's group.
+        let batch = self.marked_sessions();
+        let (session_id, title, group) = match (batch.first(), self.selected_session()) {
+            (Some((id, title)), _) => (id.clone(), title.clone(), String::new()),
+            (None, Some(s)) => (s.id.clone(), s.title.clone(), s.group_path.clone()),
+            (None, None) => return,
         };
 
         let mut all_groups: Vec<String> = self
@@ -62,9 +126,10 @@ impl App {
         all_groups.insert(0, String::new());
 
         let mut d = MoveGroupDialog {
-            session_id: s.id.clone(),
-            title: s.title.clone(),
-            input: TextInput::with_text(s.group_path.clone()),
+            session_id,
+            title,
+            batch,
+            input: TextInput::with_text(group),
             all_groups,
             matches: Vec::new(),
             selected: 0,
@@ -75,6 +140,91 @@ impl App {
         self.state = AppState::Dialog;
     }
 
+    /// Delete what the delete-confirm dialog names, as its toggles say.
+    pub(super) async fn confirm_delete_sessions(&mut self) -> Result<()> {
+        let Some(Dialog::DeleteConfirm(d)) = self.dialog.take() else {
+            return Ok(());
+        };
+        self.state = AppState::Normal;
+        let session_ids = d.session_ids();
+        let kill_tmux = d.kill_tmux;
+        let worktree = d.worktree.filter(|_| d.remove_worktree);
+        let target = d.target;
+        let title = d.title;
+        if !self.selection_matches(&target) {
+            self.notify_warning("Session list changed while confirming — delete aborted");
+            return Ok(());
+        }
+        self.activity.push_default(super::activity::ActivityOp::KillingSession);
+        if session_ids.len() > 1 {
+            self.bulk_operations.begin(Instant::now());
+        }
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        let mut deletion = super::undo::Deletion::new(match session_ids.len() {
+            1 => title,
+            n if is_zh => format!("{n} 个会话"),
+            n => format!("{n} sessions"),
+        });
+        for session_id in &session_ids {
+            deletion.absorb(self.delete_session(session_id, kill_tmux).await?);
+        }
+        let msg = if is_zh {
+            format!("已删除 {}", deletion.label)
+        } else {
+            format!("Deleted {}", deletion.label)
+        };
+        self.undo.push(deletion);
+        self.notify_info(msg);
+        if let Some(path) = worktree {
+            if let Err(e) = crate::git::worktree_remove(&path).await {
+                self.set_info_bar(
+                    format!("Session deleted; worktree kept: {e}"),
+                    ratatui::style::Color::Yellow,
+                );
+            }
+        }
+        self.clear_marks();
+        self.refresh_sessions().await?;
+        // Sync canvas: remove orphaned nodes/edges for deleted sessions & relationships
+        #[cfg(feature = "pro")]
+        {
+            self.canvas_state.sync_relationship_edges(&self.relationships);
+            self.sync_canvas_after_deletion();
+        }
+        self.activity.complete(super::activity::ActivityOp::KillingSession);
+        Ok(())
+    }
+
+    /// Carry out the delete-group dialog's chosen option.
+    pub(super) async fn confirm_delete_group(&mut self) -> Result<()> {
+        let Some(Dialog::DeleteGroup(d)) = self.dialog.take() else {
+            return Ok(());
+        };
+        self.state = AppState::Normal;
+        let group_path = d.group_path;
+        let choice = d.choice;
+        let target = d.target;
+        if choice != DeleteGroupChoice::Cancel && !self.selection_matches(&target) {
+            self.notify_warning("Session list changed while confirming — delete aborted");
+            return Ok(());
+        }
+        match choice {
+            DeleteGroupChoice::DeleteGroupKeepSessions => {
+                let deletion = self.apply_delete_group_keep_sessions(&group_path).await?;
+                self.undo.push(deletion);
+                self.notify_group_done(GroupOp::Deleted, &group_path);
+            }
+            DeleteGroupChoice::Cancel => {}
+            DeleteGroupChoice::DeleteGroupAndSessions => {
+                let deletion = self.apply_delete_group_and_sessions(&group_path).await?;
+                self.undo.push(deletion);
+                self.notify_group_done(GroupOp::DeletedWithSessions, &group_path);
+            }
+        }
+        self.refresh_sessions().await?;
+        Ok(())
+    }
+
     pub(super) fn open_rename_session_dialog(&mut self) {
         let Some(s) = self.selected_session() else {
             return;
@@ -90,10 +240,23 @@ impl App {
             label_color: s.label_color,
             cli_session_id: TextInput::with_text(cli_sid),
             field: SessionEditField::Title,
+            error: None,
         }));
         self.state = AppState::Dialog;
     }
 
+    pub(super) fn open_repair_path_dialog(&mut self, id: &str) {
+        let Some(s) = self.session_by_id(id) else {
+            return;
+        };
+        self.dialog = Some(Dialog::RepairPath(RepairPathDialog::new(
+            s.id.clone(),
+            s.title.clone(),
+            s.project_path.clone(),
+        )));
+        self.state = AppState::Dialog;
+    }
+
     pub(super) fn collect_existing_tags(&self) -> Vec<TagSpec> {
         let mut out: Vec<TagSpec> = Vec::new();
         let mut seen: std::collections::HashMap<String, ()> = std::collections::HashMap::new();
@@ -138,6 +301,23 @@ impl App {
         self.state = AppState::Dialog;
     }
 
+    pub(super) fn open_label_picker_dialog(&mut self) {
+        let Some(s) = self.selected_session() else {
+            return;
+        };
+
+        let choice = crate::session::LabelColor::ALL
+            .iter()
+            .position(|&c| c == s.label_color)
+            .unwrap_or(0);
+        self.dialog = Some(Dialog::LabelPicker(LabelPickerDialog {
+            session_id: s.id.clone(),
+            label: TextInput::with_text(s.label.clone()),
+            choice,
+        }));
+        self.state = AppState::Dialog;
+    }
+
     pub(super) fn open_rename_group_dialog(&mut self) {
         let Some(TreeItem::Group { path, .. }) = self.selected_tree_item() else {
             return;
@@ -150,6 +330,170 @@ impl App {
         self.state = AppState::Dialog;
     }
 
+    pub(super) fn open_group_notes_dialog(&mut self) {
+        let Some(TreeItem::Group { path, .. }) = self.selected_tree_item() else {
+            return;
+        };
+
+        let notes = self
+            .groups
+            .get_group(path)
+            .map(|g| crate::session::notes_to_input(&g.notes))
+            .unwrap_or_default();
+        self.dialog = Some(Dialog::GroupNotes(GroupNotesDialog {
+            path: path.clone(),
+            notes: TextInput::with_text(notes),
+        }));
+        self.state = AppState::Dialog;
+    }
+
+    pub(super) fn open_reparent_group_dialog(&mut self) {
+        let Some(TreeItem::Group { path, .. }) = self.selected_tree_item() else {
+            return;
+        };
+
+        let parent = path.rsplit_once('/').map_or("", |(parent, _)| parent);
+        self.dialog = Some(Dialog::ReparentGroup(ReparentGroupDialog {
+            path: path.clone(),
+            new_parent: TextInput::with_text(parent.to_string()),
+            error: None,
+        }));
+        self.state = AppState::Dialog;
+    }
+
+    /// Rows for the rebind dialog: normal-mode actions in precedence
+    /// order, then the tmux switcher and detach keys.
+    fn rebind_rows(&self) -> Vec<RebindRow> {
+        use crate::config::{format_key_spec, RebindTarget, NORMAL_ACTION_PRIORITY};
+
+        let mut rows: Vec<RebindRow> = NORMAL_ACTION_PRIORITY
+            .iter()
+            .filter_map(|&action| {
+                let specs = self.keybindings.get_specs(action)?;
+                Some(RebindRow {
+                    target: RebindTarget::Action(action),
+                    current: specs.iter().map(format_key_spec).collect::<Vec<_>>().join(" / "),
+                })
+            })
+            .collect();
+        rows.push(RebindRow {
+            target: RebindTarget::Tmux("switcher"),
+            current: self.config.tmux_switcher_key().unwrap_or("C-g").to_string(),
+        });
+        rows.push(RebindRow {
+            target: RebindTarget::Tmux("detach"),
+            current: self.config.tmux_detach_key().unwrap_or("C-q").to_string(),
+        });
+        rows
+    }
+
+    /// The key `[tmux] <name>` binds, in tmux notation.
+    fn tmux_key(&self, name: &str) -> Option<String> {
+        let (key, default) = match name {
+            "switcher" => (self.config.tmux_switcher_key(), "C-g"),
+            "detach" => (self.config.tmux_detach_key(), "C-q"),
+            _ => return None,
+        };
+        crate::config::parse_tmux_key(key.unwrap_or(default))
+    }
+
+    pub(super) fn open_rebind_dialog(&mut self) {
+        self.dialog = Some(Dialog::Rebind(RebindDialog {
+            rows: self.rebind_rows(),
+            selected: 0,
+            stage: RebindStage::Browse,
+        }));
+        self.state = AppState::Dialog;
+    }
+
+    /// What capturing `key` for `target` would do: the value to write and
+    /// the bindings it would shadow or be shadowed by. None if tmux cannot
+    /// bind the key.
+    pub(super) fn preview_rebind(
+        &self,
+        target: crate::config::RebindTarget,
+        key: crate::config::KeySpec,
+    ) -> Option<RebindStage> {
+        use crate::config::RebindTarget;
+
+        let value = target.config_value(&key)?;
+        let conflicts = match target {
+            RebindTarget::Action(action) => self
+                .keybindings
+                .conflicts_if_bound(action, key)
+                .iter()
+                .map(|c| c.describe())
+                .collect(),
+            RebindTarget::Tmux(name) => {
+                let (other, current) = match name {
+                    "switcher" => ("detach", self.config.tmux_detach_key().unwrap_or("C-q")),
+                    _ => ("switcher", self.config.tmux_switcher_key().unwrap_or("C-g")),
+                };
+                if crate::config::parse_tmux_key(current).as_deref() == Some(value.as_str()) {
+                    vec![format!("[tmux] {value}: already the {other} key")]
+                } else {
+                    Vec::new()
+                }
+            }
+        };
+        Some(RebindStage::Confirm {
+            key,
+            value,
+            conflicts,
+        })
+    }
+
+    /// Write the confirmed key into the config file and reload bindings.
+    pub(super) async fn apply_rebind(&mut self) {
+        let Some(Dialog::Rebind(d)) = self.dialog.as_ref() else {
+            return;
+        };
+        let RebindStage::Confirm { key, value, .. } = &d.stage else {
+            return;
+        };
+        let Some(target) = d.rows.get(d.selected).map(|r| r.target) else {
+            return;
+        };
+        let (key, value) = (*key, value.clone());
+        let replaced = match target {
+            crate::config::RebindTarget::Tmux(name) => self.tmux_key(name),
+            crate::config::RebindTarget::Action(_) => None,
+        };
+
+        match crate::config::ConfigFile::write_binding(target, &key).await {
+            Ok(cfg) => {
+                self.config = cfg;
+                self.keybindings = crate::config::KeyBindings::from_config(&self.config);
+                if let crate::config::RebindTarget::Tmux(name) = target {
+                    // Unless the other tmux key uses it too.
+                    let other = if name == "switcher" { "detach" } else { "switcher" };
+                    let other = self.tmux_key(other);
+                    if let Some(old) =
+                        replaced.filter(|old| *old != value && other.as_ref() != Some(old))
+                    {
+                        self.tmux.unbind_root_key(&old).await;
+                    }
+                    let _ = self.tmux.ensure_server().await;
+                }
+                let rows = self.rebind_rows();
+                if let Some(Dialog::Rebind(d)) = self.dialog.as_mut() {
+                    d.rows = rows;
+                    d.stage = RebindStage::Browse;
+                }
+                self.set_info_bar(
+                    format!("{} → {value}", target.name()),
+                    ratatui::style::Color::Green,
+                );
+            }
+            Err(e) => {
+                if let Some(Dialog::Rebind(d)) = self.dialog.as_mut() {
+                    d.stage = RebindStage::Browse;
+                }
+                self.set_info_bar(format!("Rebind failed: {e}"), ratatui::style::Color::Red);
+            }
+        }
+    }
+
     /// Apply settings from the dialog: update config, save to disk, hot-reload subsystems.
     pub(super) async fn apply_settings(&mut self) -> Result<()> {
         let Some(Dialog::Settings(d)) = self.dialog.as_ref() else {
@@ -254,10 +598,9 @@ impl App {
         // Save to disk
         self.config.save()?;
 
-        // Hot-reload: update keybindings
-        for (action, specs) in &d.key_bindings {
-            self.keybindings.set_binding(action, specs.clone());
-        }
+        // Hot-reload: rebuild keybindings so conflicts are re-resolved
+        self.keybindings = crate::config::KeyBindings::from_config(&self.config);
+        self.report_keybinding_conflicts();
 
         // Hot-reload: update attention TTL
         self.attention_ttl =
@@ -0,0 +1,234 @@
+use super::*;
+
+use crate::tmux::discovery::UnmanagedAgent;
+
+impl App {
+    /// Pull the latest discovery results, dropping ignored directories and
+    /// PIDs dismissed this run. Toasts when previously unseen agents appear.
+    pub(super) async fn poll_unmanaged_agents(&mut self) {
+        let Some(state) = self.discovery_state.as_ref() else {
+            return;
+        };
+        let agents = state.read().await.agents.clone();
+
+        let visible: Vec<UnmanagedAgent> = agents
+            .into_iter()
+            .filter(|a| !self.dismissed_agent_pids.contains(&a.pid))
+            .filter(|a| {
+                a.cwd
+                    .as_ref()
+                    .is_none_or(|cwd| !self.discovery_ignored.contains(cwd))
+            })
+            .collect();
+        if visible == self.unmanaged_agents {
+            return;
+        }
+
+        let new_count = visible
+            .iter()
+            .filter(|a| !self.unmanaged_agents.iter().any(|old| old.pid == a.pid))
+            .count();
+        self.unmanaged_agents = visible;
+        self.rebuild_tree();
+
+        if new_count > 0 {
+            let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+            let count = self.unmanaged_agents.len();
+            let msg = if is_zh {
+                format!("检测到 {count} 个未托管的 Agent 进程，列在会话树末尾")
+            } else {
+                format!("{count} unmanaged agent(s) detected — listed at the end of the tree")
+            };
+            self.set_info_bar(msg, ratatui::style::Color::Yellow);
+        }
+    }
+
+    /// Rows of the "unmanaged agents detected" section; none while there
+    /// is nothing to show.
+    pub(super) fn unmanaged_rows(&self) -> Vec<TreeItem> {
+        if self.unmanaged_agents.is_empty() {
+            return Vec::new();
+        }
+        let header = TreeItem::UnmanagedHeader {
+            count: self.unmanaged_agents.len(),
+        };
+        std::iter::once(header)
+            .chain(
+                self.unmanaged_agents
+                    .iter()
+                    .map(|a| TreeItem::Unmanaged { pid: a.pid }),
+            )
+            .collect()
+    }
+
+    pub fn unmanaged_agent(&self, pid: u32) -> Option<&UnmanagedAgent> {
+        self.unmanaged_agents.iter().find(|a| a.pid == pid)
+    }
+
+    /// Move the cursor to the section header, or say why there is none.
+    pub(super) fn focus_unmanaged_agents(&mut self) {
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        if self.discovery_state.is_none() {
+            let msg = if is_zh {
+                "Agent 发现未开启（设置 discovery.enabled = true）"
+            } else {
+                "Agent discovery is off (set discovery.enabled = true)"
+            };
+            self.set_info_bar(msg.to_string(), ratatui::style::Color::DarkGray);
+            return;
+        }
+        let header = self
+            .tree
+            .iter()
+            .position(|item| matches!(item, TreeItem::UnmanagedHeader { .. }));
+        let Some(pos) = header else {
+            let msg = if is_zh {
+                "没有未托管的 Agent 进程"
+            } else {
+                "No unmanaged agents detected"
+            };
+            self.set_info_bar(msg.to_string(), ratatui::style::Color::DarkGray);
+            return;
+        };
+        self.selected_index = pos;
+        self.enforce_scrolloff();
+        self.on_navigation();
+        self.preview.clear();
+    }
+
+    /// Keys on the unmanaged-agents section: `select` adopts the agent,
+    /// `delete` dismisses it (on the header, the whole section) until the
+    /// next run, and `unmanaged_agents` ignores its directory for good.
+    /// Returns whether the key was taken.
+    pub(super) async fn handle_unmanaged_key(
+        &mut self,
+        key: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> Result<bool> {
+        let agent = match self.selected_tree_item() {
+            Some(TreeItem::UnmanagedHeader { .. }) => None,
+            Some(TreeItem::Unmanaged { pid }) => self.unmanaged_agent(*pid).cloned(),
+            _ => return Ok(false),
+        };
+
+        if self.keybindings.matches("select", &key, modifiers) {
+            if let Some(agent) = agent {
+                self.adopt_unmanaged_agent(&agent).await?;
+            }
+            return Ok(true);
+        }
+        if self.keybindings.matches("delete", &key, modifiers) {
+            let pids: Vec<u32> = match agent {
+                Some(agent) => vec![agent.pid],
+                None => self.unmanaged_agents.iter().map(|a| a.pid).collect(),
+            };
+            self.dismissed_agent_pids.extend(&pids);
+            self.unmanaged_agents.retain(|a| !pids.contains(&a.pid));
+            self.rebuild_tree();
+            self.preview.clear();
+            return Ok(true);
+        }
+        if self.keybindings.matches("unmanaged_agents", &key, modifiers) {
+            if let Some(agent) = agent {
+                self.ignore_unmanaged_agent(&agent);
+                self.rebuild_tree();
+                self.preview.clear();
+            }
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Preview text for a row of the unmanaged-agents section.
+    pub(super) fn unmanaged_preview(&self) -> Option<String> {
+        let key = |action: &str| self.keybindings.describe(action).unwrap_or_default();
+        match self.selected_tree_item()? {
+            TreeItem::UnmanagedHeader { count } => Some(format!(
+                "Unmanaged agents detected: {count}\n\n\
+                 Agent CLIs running outside agent-hand's tmux server.\n\n  \
+                 {:<5} = dismiss all until restart",
+                key("delete"),
+            )),
+            TreeItem::Unmanaged { pid } => {
+                let agent = self.unmanaged_agent(*pid)?;
+                let cwd = agent
+                    .cwd
+                    .as_ref()
+                    .map_or_else(|| "?".to_string(), |p| p.display().to_string());
+                Some(format!(
+                    "{} (pid {})\n\nPath: {}\nStarted: {}\n\n  \
+                     {:<5} = adopt: new session for this directory\n  \
+                     {:<5} = dismiss until restart\n  \
+                     {:<5} = ignore this directory",
+                    agent.tool,
+                    agent.pid,
+                    cwd,
+                    agent.started_at.format("%Y-%m-%d %H:%M UTC"),
+                    key("select"),
+                    key("delete"),
+                    key("unmanaged_agents"),
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// Create a (stopped) session for an external agent's directory. The
+    /// process keeps running where it is; the label records that.
+    pub(super) async fn adopt_unmanaged_agent(&mut self, agent: &UnmanagedAgent) -> Result<()> {
+        let Some(cwd) = agent.cwd.clone() else {
+            self.set_info_bar(
+                format!("Cannot adopt pid {}: working directory unknown", agent.pid),
+                ratatui::style::Color::Yellow,
+            );
+            return Ok(());
+        };
+
+        let title = cwd
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("external")
+            .to_string();
+        let mut inst = Instance::new(title, cwd);
+        inst.tool = agent.tool;
+        inst.command = agent.tool.to_string();
+        inst.label = format!("external pid {}", agent.pid);
+        inst.label_color = crate::session::LabelColor::Yellow;
+        let id = inst.id.clone();
+
+        {
+            let storage = self.storage.lock().await;
+            let (mut instances, tree, relationships) = storage.load().await?;
+            instances.push(inst);
+            storage.save(&instances, &tree, &relationships).await?;
+        }
+
+        self.dismissed_agent_pids.insert(agent.pid);
+        self.unmanaged_agents.retain(|a| a.pid != agent.pid);
+        self.refresh_sessions().await?;
+        self.focus_session(&id).await?;
+        Ok(())
+    }
+
+    /// Stop suggesting agents running in this agent's directory.
+    pub(super) fn ignore_unmanaged_agent(&mut self, agent: &UnmanagedAgent) {
+        let Some(cwd) = agent.cwd.as_ref() else {
+            self.dismissed_agent_pids.insert(agent.pid);
+            self.unmanaged_agents.retain(|a| a.pid != agent.pid);
+            return;
+        };
+
+        match crate::tmux::discovery::add_to_ignore_list(&self.discovery_ignore_path, cwd) {
+            Ok(list) => self.discovery_ignored = list,
+            Err(e) => {
+                self.set_info_bar(
+                    format!("Failed to save ignore list: {e}"),
+                    ratatui::style::Color::Red,
+                );
+                return;
+            }
+        }
+        self.unmanaged_agents
+            .retain(|a| a.cwd.as_ref() != Some(cwd));
+    }
+}
@@ -0,0 +1,160 @@
+use super::*;
+
+use crate::session::forward::{forward_output, forward_payload};
+
+/// Longest wait for a target started by the forward action to show its
+/// prompt; the output is sent anyway once it runs out.
+const STARTUP: Duration = Duration::from_secs(15);
+
+/// Output captured for a target that was stopped when it was picked.
+pub(super) struct PendingForward {
+    target_id: String,
+    source_title: String,
+    payload: String,
+    started_at: Instant,
+}
+
+impl App {
+    /// F: pick a session to receive the selected session's recent output.
+    pub(super) fn open_forward_dialog(&mut self) {
+        let Some(source) = self.selected_session() else {
+            return;
+        };
+        let (source_id, source_title) = (source.id.clone(), source.title.clone());
+        let running = self.tmux.session_exists(&source.tmux_name()).unwrap_or(false);
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        if !running {
+            let msg = if is_zh {
+                format!("{source_title} 未运行，没有可转发的输出")
+            } else {
+                format!("{source_title} is not running; no output to forward")
+            };
+            self.set_info_bar(msg, ratatui::style::Color::Yellow);
+            return;
+        }
+
+        let mut targets: Vec<ForwardTarget> = self
+            .sessions
+            .iter()
+            .filter(|s| s.id != source_id)
+            .map(|s| ForwardTarget {
+                id: s.id.clone(),
+                title: s.title.clone(),
+                running: self.tmux.session_exists(&s.tmux_name()).unwrap_or(false),
+            })
+            .collect();
+        if targets.is_empty() {
+            let msg = if is_zh { "没有其他会话可转发" } else { "No other session to forward to" };
+            self.set_info_bar(msg.to_string(), ratatui::style::Color::Yellow);
+            return;
+        }
+        // Running sessions first: they take the output right away.
+        targets.sort_by(|a, b| {
+            b.running
+                .cmp(&a.running)
+                .then_with(|| a.title.to_lowercase().cmp(&b.title.to_lowercase()))
+        });
+
+        self.dialog = Some(Dialog::ForwardOutput(ForwardOutputDialog {
+            source_id,
+            source_title,
+            targets,
+            selected: 0,
+            confirm_start: false,
+        }));
+        self.state = AppState::Dialog;
+    }
+
+    /// Send the source's last `forward_lines` lines to `target`. A stopped
+    /// target is started first and gets the output once it is ready.
+    pub(super) async fn forward_to(&mut self, source_id: &str, target: &ForwardTarget) -> Result<()> {
+        let Some(source) = self.session_by_id(source_id) else {
+            return Ok(());
+        };
+        let (source_tmux, source_title) = (source.tmux_name(), source.title.clone());
+        let lines = self.config.forward_lines();
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+
+        if target.running {
+            let target_tmux = self.tmux_name_for_id(&target.id);
+            let (msg, color) =
+                match forward_output(&self.tmux, &source_tmux, &source_title, &target_tmux, lines)
+                    .await
+                {
+                    Ok(n) if is_zh => (
+                        format!("已将 {source_title} 的 {n} 行输出转发到 {}", target.title),
+                        ratatui::style::Color::Green,
+                    ),
+                    Ok(n) => (
+                        format!("Forwarded {n} line(s) from {source_title} to {}", target.title),
+                        ratatui::style::Color::Green,
+                    ),
+                    Err(e) => (e.to_string(), ratatui::style::Color::Red),
+                };
+            self.set_info_bar(msg, color);
+            return Ok(());
+        }
+
+        let content = self.tmux.capture_pane(&source_tmux, lines).await?;
+        let Some(payload) = forward_payload(&source_title, &content, lines) else {
+            let msg = if is_zh {
+                format!("{source_title} 没有可转发的输出")
+            } else {
+                format!("{source_title} has no output to forward")
+            };
+            self.set_info_bar(msg, ratatui::style::Color::Yellow);
+            return Ok(());
+        };
+        if !self.start_session(&target.id).await? {
+            return Ok(());
+        }
+        self.pending_forward = Some(PendingForward {
+            target_id: target.id.clone(),
+            source_title,
+            payload,
+            started_at: Instant::now(),
+        });
+        self.refresh_sessions().await?;
+        let msg = if is_zh {
+            format!("正在启动 {}，就绪后转发输出", target.title)
+        } else {
+            format!("Starting {}; the output follows once it is ready", target.title)
+        };
+        self.set_info_bar(msg, ratatui::style::Color::Cyan);
+        Ok(())
+    }
+
+    /// Type a pending forward into its target once the target waits for
+    /// input, or once [`STARTUP`] has passed.
+    pub(super) async fn deliver_pending_forward(&mut self) {
+        let Some(pending) = &self.pending_forward else {
+            return;
+        };
+        let Some(target) = self.session_by_id(&pending.target_id) else {
+            self.pending_forward = None;
+            return;
+        };
+        let ready = target.status == Status::Waiting || pending.started_at.elapsed() >= STARTUP;
+        if !ready {
+            return;
+        }
+        let (target_tmux, target_title) = (target.tmux_name(), target.title.clone());
+        let Some(pending) = self.pending_forward.take() else {
+            return;
+        };
+
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        let (msg, color) = match self.tmux.type_text(&target_tmux, &pending.payload).await {
+            Ok(()) if is_zh => (
+                format!("已将 {} 的输出转发到 {target_title}", pending.source_title),
+                ratatui::style::Color::Green,
+            ),
+            Ok(()) => (
+                format!("Forwarded output from {} to {target_title}", pending.source_title),
+                ratatui::style::Color::Green,
+            ),
+            Err(e) => (e.to_string(), ratatui::style::Color::Red),
+        };
+        self.set_info_bar(msg, color);
+    }
+}
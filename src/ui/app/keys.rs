@@ -1,5 +1,7 @@
 use super::*;
 
+use super::messages::GroupOp;
+
 impl App {
 
     /// Handle keyboard input
@@ -12,6 +14,12 @@ impl App {
             return Ok(());
         }
 
+        // The perf overlay draws over every screen, so F11 works in all of them.
+        if key == KeyCode::F(11) {
+            self.perf.toggle_overlay();
+            return Ok(());
+        }
+
         match self.state {
             AppState::Startup => unreachable!(), // handled above
             AppState::Normal => self.handle_normal_key(key, modifiers).await,
@@ -34,13 +42,24 @@ impl App {
                 self.dismiss_onboarding();
                 // Save first_launch = false to config
                 self.config.first_launch = Some(false);
-                let _ = self.config.save();
+                if let Err(e) = self.config.save() {
+                    let msg = if matches!(self.language(), crate::i18n::Language::Chinese) {
+                        format!("保存配置失败: {e}")
+                    } else {
+                        format!("Failed to save config: {e}")
+                    };
+                    self.notify_error(msg);
+                }
                 return Ok(());
             }
             // Block all other keys while onboarding is shown
             return Ok(());
         }
 
+        if self.preview_view.focused {
+            return self.handle_preview_focus_key(key, modifiers).await;
+        }
+
         // Dismiss AI summary overlay: Esc closes, 'C' adds to canvas, j/k scroll, 'A' reopens picker
         #[cfg(feature = "pro")]
         if self.max.show_ai_summary_overlay {
@@ -171,6 +190,44 @@ impl App {
             }
         }
 
+        if self.keybindings.matches("toggle_ui_mode", &key, modifiers) {
+            self.toggle_ui_mode();
+            return Ok(());
+        }
+
+        // Esc drops a multi-selection before anything else sees it.
+        if key == KeyCode::Esc
+            && modifiers == KeyModifiers::NONE
+            && !self.canvas_focused
+            && self.marked_count() > 0
+        {
+            self.clear_marks();
+            return Ok(());
+        }
+
+        // Esc puts the live preview back in place of a fork diff.
+        if key == KeyCode::Esc
+            && modifiers == KeyModifiers::NONE
+            && !self.canvas_focused
+            && self.preview_shows_diff
+        {
+            return self.update_preview().await;
+        }
+
+        // Rows of the unmanaged-agents section take their own keys.
+        if !self.canvas_focused && self.handle_unmanaged_key(key, modifiers).await? {
+            return Ok(());
+        }
+
+        // Actions the current mode hides are refused here, with a toast.
+        if !self.canvas_focused {
+            if let Some(action) = self.keybindings.normal_action(&key, modifiers) {
+                if !self.guard(action) {
+                    return Ok(());
+                }
+            }
+        }
+
         if self.keybindings.matches("quit", &key, modifiers) {
             self.transition_engine.request_transition();
             self.dialog = Some(Dialog::QuitConfirm);
@@ -185,7 +242,7 @@ impl App {
             return Ok(());
         }
 
-        // Tab: cycle panel focus (Pro: tree → active → viewer → tree)
+        // Tab: cycle panel focus (Pro: tree → preview → active → viewer → tree)
         // Canvas is only toggled via 'p' (canvas_toggle keybinding), not Tab.
         #[cfg(feature = "pro")]
         if key == KeyCode::Tab && modifiers == KeyModifiers::NONE {
@@ -200,6 +257,15 @@ impl App {
                 return Ok(());
             }
 
+            // tree → preview; leaving the preview with Tab moves on to the panels
+            if !self.active_panel_focused
+                && !self.pro.viewer_panel_focused
+                && self.keybindings.matches("preview_focus", &key, modifiers)
+                && self.enter_preview_focus().await?
+            {
+                return Ok(());
+            }
+
             if is_pro && (active_count > 0 || viewer_count > 0) {
                 if self.active_panel_focused {
                     // active → viewer (if has viewers) or tree
@@ -222,18 +288,7 @@ impl App {
                 } else if self.pro.viewer_panel_focused {
                     self.pro.viewer_panel_focused = false;
                 } else {
-                    // tree → active (if has active sessions)
-                    if active_count > 0 {
-                        self.active_panel_focused = true;
-                        if self.active_panel_selected >= active_count {
-                            self.active_panel_selected = active_count.saturating_sub(1);
-                        }
-                    } else if viewer_count > 0 {
-                        self.pro.viewer_panel_focused = true;
-                        if self.pro.viewer_panel_selected >= viewer_count {
-                            self.pro.viewer_panel_selected = viewer_count.saturating_sub(1);
-                        }
-                    }
+                    self.focus_side_panel();
                 }
                 return Ok(());
             }
@@ -259,6 +314,7 @@ impl App {
                         matches!(item, crate::ui::TreeItem::Session { id, .. } | crate::ui::TreeItem::Relationship { id, .. } if id == &sid)
                     }) {
                         self.selected_index = pos;
+                        self.enforce_scrolloff();
                         self.on_navigation();
                         self.preview.clear();
                     }
@@ -699,56 +755,84 @@ impl App {
             self.preview.clear();
             return Ok(());
         }
-
-        #[cfg(feature = "pro")]
-        {
-            if self.keybindings.matches("half_page_down", &key, modifiers) {
-                self.move_half_page_down();
-                self.enforce_scrolloff();
-                self.on_navigation();
-                self.preview.clear();
-                return Ok(());
-            }
-            if self.keybindings.matches("half_page_up", &key, modifiers) {
-                self.move_half_page_up();
-                self.enforce_scrolloff();
-                self.on_navigation();
-                self.preview.clear();
-                return Ok(());
-            }
+        if self.keybindings.matches("move_session_up", &key, modifiers) {
+            self.move_selected_in_group(true).await?;
+            return Ok(());
+        }
+        if self.keybindings.matches("move_session_down", &key, modifiers) {
+            self.move_selected_in_group(false).await?;
+            return Ok(());
+        }
+        if let Some(target) = self.page_target(&key, modifiers) {
+            self.selected_index = target;
+            self.enforce_scrolloff();
+            self.on_navigation();
+            self.preview.clear();
+            return Ok(());
         }
 
         // Actions
         if self.keybindings.matches("select", &key, modifiers) {
-            if self.toggle_selected_group(None).await? {
-                self.preview.clear();
-            } else {
-                self.last_attach_source = Some(super::AttachSource::TreePanel);
-                self.queue_attach_selected().await?;
-            }
-            return Ok(());
+            return self.activate_selected().await;
         }
         if self.keybindings.matches("collapse", &key, modifiers) {
-            let _ = self.toggle_selected_group(Some(false)).await?;
+            // On a session row: hide its forks.
+            if !self.toggle_selected_group(Some(false)).await? {
+                let _ = self.toggle_selected_forks(Some(false)).await?;
+            }
             return Ok(());
         }
         if self.keybindings.matches("expand", &key, modifiers) {
-            let _ = self.toggle_selected_group(Some(true)).await?;
+            if !self.toggle_selected_group(Some(true)).await? {
+                let _ = self.toggle_selected_forks(Some(true)).await?;
+            }
             return Ok(());
         }
         if self.keybindings.matches("toggle_group", &key, modifiers) {
-            let _ = self.toggle_selected_group(None).await?;
+            // On a session row the same key marks it instead.
+            if !self.toggle_selected_group(None).await?
+                && self.selected_session().is_some()
+                && self.guard("mark")
+            {
+                self.toggle_mark_selected();
+            }
+            return Ok(());
+        }
+        if self.keybindings.matches("mark", &key, modifiers) {
+            self.toggle_mark_selected();
             return Ok(());
         }
         if self.keybindings.matches("start", &key, modifiers) {
             self.activity.push_default(super::activity::ActivityOp::StartingSession);
-            self.start_selected().await?;
+            if self.marked_count() > 0 {
+                self.start_marked().await?;
+            } else if let Some(title) = self.selected_session().map(|s| s.title.clone()) {
+                if self.start_selected().await? {
+                    let msg = if matches!(self.language(), crate::i18n::Language::Chinese) {
+                        format!("已启动 {title}")
+                    } else {
+                        format!("Started {title}")
+                    };
+                    self.notify_info(msg);
+                }
+            }
             self.activity.complete(super::activity::ActivityOp::StartingSession);
             return Ok(());
         }
         if self.keybindings.matches("stop", &key, modifiers) {
             self.activity.push_default(super::activity::ActivityOp::KillingSession);
-            self.stop_selected().await?;
+            if self.marked_count() > 0 {
+                self.stop_marked().await?;
+            } else if let Some(title) = self.selected_session().map(|s| s.title.clone()) {
+                if self.stop_selected().await? {
+                    let msg = if matches!(self.language(), crate::i18n::Language::Chinese) {
+                        format!("已停止 {title}")
+                    } else {
+                        format!("Stopped {title}")
+                    };
+                    self.notify_info(msg);
+                }
+            }
             self.activity.complete(super::activity::ActivityOp::KillingSession);
             return Ok(());
         }
@@ -771,11 +855,20 @@ impl App {
         if self.keybindings.matches("boost", &key, modifiers) {
             if let Some(session) = self.selected_session() {
                 let id = session.id.clone();
-                if let Some(&idx) = self.sessions_by_id.get(&id) {
-                    self.sessions[idx].last_running_at = Some(chrono::Utc::now());
+                let boosted = self
+                    .sessions_by_id
+                    .get(&id)
+                    .and_then(|&idx| self.sessions.get_mut(idx));
+                if let Some(session) = boosted {
+                    session.last_running_at = Some(chrono::Utc::now());
                     // Persist the change
-                    let storage = self.storage.lock().await;
-                    let _ = storage.save(&self.sessions, &self.groups, &self.relationships).await;
+                    let saved = {
+                        let storage = self.storage.lock().await;
+                        storage.save(&self.sessions, &self.groups, &self.relationships).await
+                    };
+                    if let Err(e) = saved {
+                        self.notify_save_error(&e);
+                    }
                 }
             }
             return Ok(());
@@ -856,34 +949,59 @@ impl App {
             all_groups.dedup();
             all_groups.insert(0, String::new());
 
-            self.dialog = Some(Dialog::NewSession(NewSessionDialog::new(
-                default_path,
-                default_group,
-                all_groups,
-            )));
+            let mut d = NewSessionDialog::new(default_path, default_group, all_groups);
+            match crate::session::templates::TemplateStore::load().await {
+                Ok(store) => d.templates = store.templates,
+                Err(e) => self.set_info_bar(e.to_string(), ratatui::style::Color::Red),
+            }
+            self.dialog = Some(Dialog::NewSession(d));
             self.state = AppState::Dialog;
             return Ok(());
         }
 
         if self.keybindings.matches("delete", &key, modifiers) {
-            if let Some(session) = self.selected_session() {
+            let Some(target) = self.selection_identity() else {
+                return Ok(());
+            };
+            let batch = self.marked_sessions();
+            if let Some((id, title)) = batch.first() {
+                self.dialog = Some(Dialog::DeleteConfirm(DeleteConfirmDialog {
+                    session_id: id.clone(),
+                    title: title.clone(),
+                    batch: batch.clone(),
+                    kill_tmux: true,
+                    worktree: None,
+                    remove_worktree: false,
+                    target,
+                }));
+                self.state = AppState::Dialog;
+            } else if let Some(session) = self.selected_session() {
                 self.dialog = Some(Dialog::DeleteConfirm(DeleteConfirmDialog {
                     session_id: session.id.clone(),
                     title: session.title.clone(),
+                    batch: Vec::new(),
                     kill_tmux: true,
+                    worktree: session
+                        .fork_worktree
+                        .then(|| session.project_path.clone()),
+                    remove_worktree: false,
+                    target,
                 }));
                 self.state = AppState::Dialog;
             } else if let Some(TreeItem::Group { path, .. }) = self.selected_tree_item() {
                 let path = path.clone();
                 let session_ids = self.group_session_ids(&path);
                 if session_ids.is_empty() {
-                    self.apply_delete_group_prefix(&path).await?;
+                    let deletion = self.apply_delete_group_prefix(&path).await?;
+                    self.undo.push(deletion);
                     self.refresh_sessions().await?;
+                    self.notify_group_done(GroupOp::Deleted, &path);
                 } else {
                     self.dialog = Some(Dialog::DeleteGroup(DeleteGroupDialog {
                         group_path: path,
                         session_count: session_ids.len(),
                         choice: DeleteGroupChoice::DeleteGroupKeepSessions,
+                        target,
                     }));
                     self.state = AppState::Dialog;
                 }
@@ -891,9 +1009,21 @@ impl App {
             return Ok(());
         }
 
+        if self.keybindings.matches("undo", &key, modifiers) {
+            self.undo_delete().await?;
+            return Ok(());
+        }
+
         if self.keybindings.matches("fork", &key, modifiers) {
             if self.selected_session().is_some() {
-                self.open_fork_dialog();
+                self.open_fork_dialog().await;
+            }
+            return Ok(());
+        }
+
+        if self.keybindings.matches("lineage", &key, modifiers) {
+            if self.selected_session().is_some() {
+                self.open_lineage_dialog();
             }
             return Ok(());
         }
@@ -904,12 +1034,22 @@ impl App {
         }
 
         if self.keybindings.matches("move", &key, modifiers) {
-            if self.selected_session().is_some() {
+            if self.selected_session().is_some() || self.marked_count() > 0 {
                 self.open_move_group_dialog();
             }
             return Ok(());
         }
 
+        if self.keybindings.matches("move_group", &key, modifiers) {
+            self.open_reparent_group_dialog();
+            return Ok(());
+        }
+
+        if self.keybindings.matches("group_notes", &key, modifiers) {
+            self.open_group_notes_dialog();
+            return Ok(());
+        }
+
         if self.keybindings.matches("tag", &key, modifiers) {
             if self.selected_session().is_some() {
                 self.open_tag_picker_dialog();
@@ -917,6 +1057,33 @@ impl App {
             return Ok(());
         }
 
+        if self.keybindings.matches("jump_waiting", &key, modifiers) {
+            self.focus_longest_waiting().await?;
+            return Ok(());
+        }
+
+        if self.keybindings.matches("waiting_queue", &key, modifiers) {
+            self.open_waiting_queue_dialog();
+            return Ok(());
+        }
+
+        if self.keybindings.matches("profile_picker", &key, modifiers) {
+            self.open_profile_picker().await;
+            return Ok(());
+        }
+
+        if self.keybindings.matches("message_log", &key, modifiers) {
+            self.open_message_log();
+            return Ok(());
+        }
+
+        if self.keybindings.matches("label", &key, modifiers) {
+            if self.selected_session().is_some() {
+                self.open_label_picker_dialog();
+            }
+            return Ok(());
+        }
+
         // a: add selected session to canvas (Pro only)
         // Only adds if the session belongs to the current canvas group.
         #[cfg(feature = "pro")]
@@ -944,12 +1111,41 @@ impl App {
             self.refresh_preview_cache_selected().await?;
             return Ok(());
         }
+        if self.keybindings.matches("preview_older", &key, modifiers) {
+            self.browse_preview(true).await?;
+            return Ok(());
+        }
+        if self.keybindings.matches("preview_newer", &key, modifiers) {
+            self.browse_preview(false).await?;
+            return Ok(());
+        }
+        if self.keybindings.matches("preview_focus", &key, modifiers) {
+            self.enter_preview_focus().await?;
+            return Ok(());
+        }
+        if self.keybindings.matches("preview_page_up", &key, modifiers) {
+            self.scroll_preview(-(self.visible_tree_height() as isize));
+            return Ok(());
+        }
+        if self.keybindings.matches("preview_page_down", &key, modifiers) {
+            self.scroll_preview(self.visible_tree_height() as isize);
+            return Ok(());
+        }
+        if self.keybindings.matches("half_page_down", &key, modifiers) {
+            self.scroll_preview_half_page(true);
+            return Ok(());
+        }
+        if self.keybindings.matches("half_page_up", &key, modifiers) {
+            self.scroll_preview_half_page(false);
+            return Ok(());
+        }
 
         if self.keybindings.matches("search", &key, modifiers) {
             self.state = AppState::Search;
             self.search_query.clear();
             self.search_results.clear();
             self.search_selected = 0;
+            self.search_offset = 0;
             self.update_search_results();
             return Ok(());
         }
@@ -1006,9 +1202,74 @@ impl App {
             return Ok(());
         }
 
+        if self.keybindings.matches("unmanaged_agents", &key, modifiers) {
+            self.focus_unmanaged_agents();
+            return Ok(());
+        }
+
+        if self.keybindings.matches("compare", &key, modifiers) {
+            self.compare_marked().await?;
+            return Ok(());
+        }
+
+        if self.keybindings.matches("transcript", &key, modifiers) {
+            self.save_selected_transcript().await?;
+            return Ok(());
+        }
+
+        if self.keybindings.matches("toggle_autostart", &key, modifiers) {
+            self.toggle_selected_autostart().await?;
+            return Ok(());
+        }
+
+        if self.keybindings.matches("pin", &key, modifiers) {
+            self.toggle_selected_pin().await?;
+            return Ok(());
+        }
+
+        if self.keybindings.matches("acknowledge_stall", &key, modifiers) {
+            self.acknowledge_selected_stall().await?;
+            return Ok(());
+        }
+
+        if self.keybindings.matches("cycle_layout", &key, modifiers) {
+            self.cycle_layout().await?;
+            return Ok(());
+        }
+
+        if self.keybindings.matches("jump_back", &key, modifiers) {
+            self.jump_back().await?;
+            return Ok(());
+        }
+
+        // 1-9: attach to the Nth pinned session
+        if let KeyCode::Char(c @ '1'..='9') = key {
+            if modifiers == KeyModifiers::NONE {
+                self.attach_pinned(c as usize - '0' as usize).await?;
+                return Ok(());
+            }
+        }
+
+        if self.keybindings.matches("notify_siblings", &key, modifiers) {
+            self.notify_repo_siblings().await;
+            return Ok(());
+        }
+
+        if self.keybindings.matches("wrapup", &key, modifiers) {
+            self.open_wrapup_dialog();
+            return Ok(());
+        }
+        if self.keybindings.matches("forward_output", &key, modifiers) {
+            self.open_forward_dialog();
+            return Ok(());
+        }
+
         // Ctrl+E: open Relationships panel (Pro/Max)
         #[cfg(feature = "pro")]
         if key == KeyCode::Char('e') && modifiers == KeyModifiers::CONTROL {
+            if !self.guard("relationships") {
+                return Ok(());
+            }
             if self.auth_token.as_ref().is_some_and(|t| t.is_pro()) {
                 self.refresh_snapshot_counts_async().await;
                 self.state = AppState::Relationships;
@@ -1019,6 +1280,9 @@ impl App {
         // J: Join a shared session via URL (Max tier)
         #[cfg(feature = "pro")]
         if key == KeyCode::Char('J') && modifiers == KeyModifiers::SHIFT {
+            if !self.guard("join_share") {
+                return Ok(());
+            }
             if crate::auth::AuthToken::require_max("sharing").is_ok() {
                 let mut join_d = crate::ui::dialogs::JoinSessionDialog::new();
                 join_d.viewer_identity = self.auth_token.as_ref().map(|t| t.email.clone());
@@ -1031,6 +1295,9 @@ impl App {
         // S: Share selected session (Max tier)
         #[cfg(feature = "pro")]
         if key == KeyCode::Char('S') && modifiers == KeyModifiers::SHIFT {
+            if !self.guard("share") {
+                return Ok(());
+            }
             if let Some(inst) = self.selected_session() {
                 if crate::auth::AuthToken::require_max("sharing").is_ok() {
                     let already_sharing = inst.sharing.is_some()
@@ -1125,6 +1392,17 @@ impl App {
                     self.search_selected = (self.search_selected + 1) % self.search_results.len();
                 }
             }
+            KeyCode::PageDown => {
+                self.search_selected = crate::ui::page_down(
+                    self.search_selected,
+                    self.search_results.len(),
+                    self.search_viewport(),
+                );
+            }
+            KeyCode::PageUp => {
+                self.search_selected =
+                    crate::ui::page_up(self.search_selected, self.search_viewport());
+            }
             KeyCode::Char(ch) => {
                 if !modifiers.contains(KeyModifiers::CONTROL) {
                     self.search_query.push(ch);
@@ -1134,6 +1412,7 @@ impl App {
             _ => {}
         }
 
+        self.sync_search_offset();
         Ok(())
     }
 
@@ -1184,11 +1463,32 @@ impl App {
     }
 
     pub(super) async fn handle_dialog_key(&mut self, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+        let simple_mode = self.is_simple_mode();
+        let new_session_advanced = self.new_session_advanced();
         let Some(dialog) = self.dialog.as_mut() else {
             self.state = AppState::Normal;
             return Ok(());
         };
 
+        // Ctrl+arrows resize fitted dialogs; the size sticks per dialog kind.
+        if modifiers == KeyModifiers::CONTROL {
+            let step = match key {
+                KeyCode::Left => Some((-1, 0)),
+                KeyCode::Right => Some((1, 0)),
+                KeyCode::Up => Some((0, -1)),
+                KeyCode::Down => Some((0, 1)),
+                _ => None,
+            };
+            if let (Some((dw, dh)), Some(kind)) = (step, dialog.size_key()) {
+                let nudge = self.ui_state.dialog_nudge(kind).nudged(dw, dh);
+                self.ui_state.dialog_sizes.insert(kind.to_string(), nudge);
+                if let Err(e) = self.ui_state.save() {
+                    tracing::warn!("failed to save UI state: {e}");
+                }
+                return Ok(());
+            }
+        }
+
         match dialog {
             Dialog::NewSession(d) => match key {
                 KeyCode::Esc => {
@@ -1196,19 +1496,24 @@ impl App {
                     self.state = AppState::Normal;
                 }
                 KeyCode::Tab => {
-                    // Tab is reserved for Path completion/suggestions (no field cycling).
+                    // On Path, Tab completes; elsewhere it moves to the next field.
                     if d.field == NewSessionField::Path {
                         if d.path_suggestions_visible {
                             d.apply_selected_path_suggestion();
                         } else {
                             d.complete_path_or_cycle(false);
                         }
+                    } else {
+                        d.field = d.field.next(new_session_advanced);
                     }
                 }
                 KeyCode::BackTab => {
-                    // No Shift-Tab field cycling.
-                    if d.field == NewSessionField::Path && d.path_suggestions_visible {
-                        d.complete_path_or_cycle(true);
+                    if d.field == NewSessionField::Path {
+                        if d.path_suggestions_visible {
+                            d.complete_path_or_cycle(true);
+                        }
+                    } else {
+                        d.field = d.field.prev();
                     }
                 }
                 KeyCode::Up | KeyCode::Down => {
@@ -1230,39 +1535,65 @@ impl App {
                     }
                 }
                 KeyCode::Enter => {
+                    // With the advanced fields collapsed, Title is the last field.
+                    let last_field = if new_session_advanced {
+                        NewSessionField::Group
+                    } else {
+                        NewSessionField::Title
+                    };
                     if d.field == NewSessionField::Path && d.path_suggestions_visible {
                         d.apply_selected_path_suggestion();
-                    } else if d.field != NewSessionField::Group {
+                    } else if d.field != last_field {
                         d.clear_path_suggestions();
                         d.path_dirty = false;
-                        d.field = match d.field {
-                            NewSessionField::Path => NewSessionField::Title,
-                            NewSessionField::Title => NewSessionField::Group,
-                            NewSessionField::Group => NewSessionField::Group,
-                        };
+                        d.field = d.field.next(new_session_advanced);
                     } else {
-                        if let Some(sel) = d.selected_group_value() {
-                            d.group_path.set_text(sel.to_string());
-                            d.update_group_matches();
-                        } else {
-                            d.group_path
-                                .set_text(d.group_path.text().trim().to_string());
+                        // Collapsed, the prefilled group is used as is.
+                        if d.field == NewSessionField::Group {
+                            if let Some(sel) = d.selected_group_value() {
+                                d.group_path.set_text(sel.to_string());
+                            } else {
+                                d.group_path
+                                    .set_text(d.group_path.text().trim().to_string());
+                            }
                             d.update_group_matches();
                         }
 
                         self.activity.push_default(super::activity::ActivityOp::CreatingSession);
-                        self.create_session_from_dialog().await?;
+                        let created = self.create_session_from_dialog().await;
+                        self.activity.complete(super::activity::ActivityOp::CreatingSession);
+                        if let Err(e) = created {
+                            if let Some(Dialog::NewSession(d)) = self.dialog.as_mut() {
+                                d.error = Some(match e {
+                                    crate::Error::InvalidInput(msg) => msg,
+                                    e => e.to_string(),
+                                });
+                            }
+                            return Ok(());
+                        }
                         self.dialog = None;
                         self.state = AppState::Normal;
                         self.refresh_sessions().await?;
-                        self.activity.complete(super::activity::ActivityOp::CreatingSession);
                     }
                 }
+                KeyCode::Char('o') if simple_mode && modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.ui_state.new_session_advanced = !self.ui_state.new_session_advanced;
+                    if !self.ui_state.new_session_advanced && d.field.is_advanced() {
+                        d.field = NewSessionField::Title;
+                    }
+                    if let Err(e) = self.ui_state.save() {
+                        tracing::warn!("failed to save UI state: {e}");
+                    }
+                }
+                KeyCode::Char('t') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    d.cycle_template();
+                }
                 KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
                     self.dialog = None;
                     self.state = AppState::Normal;
                 }
                 KeyCode::Backspace => {
+                    d.error = None;
                     match d.field {
                         NewSessionField::Path => {
                             d.path.backspace();
@@ -1273,6 +1604,13 @@ impl App {
                         NewSessionField::Title => {
                             d.title.backspace();
                         }
+                        NewSessionField::Tool => {}
+                        NewSessionField::Command => {
+                            d.command.backspace();
+                        }
+                        NewSessionField::Pre => {
+                            d.pre_command.backspace();
+                        }
                         NewSessionField::Group => {
                             d.group_path.backspace();
                             d.update_group_matches();
@@ -1280,6 +1618,7 @@ impl App {
                     };
                 }
                 KeyCode::Delete => {
+                    d.error = None;
                     match d.field {
                         NewSessionField::Path => {
                             d.path.delete();
@@ -1290,6 +1629,13 @@ impl App {
                         NewSessionField::Title => {
                             d.title.delete();
                         }
+                        NewSessionField::Tool => {}
+                        NewSessionField::Command => {
+                            d.command.delete();
+                        }
+                        NewSessionField::Pre => {
+                            d.pre_command.delete();
+                        }
                         NewSessionField::Group => {
                             d.group_path.delete();
                             d.update_group_matches();
@@ -1307,6 +1653,13 @@ impl App {
                     NewSessionField::Title => {
                         d.title.move_left();
                     }
+                    NewSessionField::Tool => d.cycle_tool(true),
+                    NewSessionField::Command => {
+                        d.command.move_left();
+                    }
+                    NewSessionField::Pre => {
+                        d.pre_command.move_left();
+                    }
                     NewSessionField::Group => {
                         if !d.group_matches.is_empty() {
                             if d.group_selected == 0 {
@@ -1330,6 +1683,13 @@ impl App {
                     NewSessionField::Title => {
                         d.title.move_right();
                     }
+                    NewSessionField::Tool => d.cycle_tool(false),
+                    NewSessionField::Command => {
+                        d.command.move_right();
+                    }
+                    NewSessionField::Pre => {
+                        d.pre_command.move_right();
+                    }
                     NewSessionField::Group => {
                         if !d.group_matches.is_empty() {
                             d.group_selected = (d.group_selected + 1) % d.group_matches.len();
@@ -1341,11 +1701,17 @@ impl App {
                 KeyCode::Home => match d.field {
                     NewSessionField::Path => d.path.move_home(),
                     NewSessionField::Title => d.title.move_home(),
+                    NewSessionField::Tool => {}
+                    NewSessionField::Command => d.command.move_home(),
+                    NewSessionField::Pre => d.pre_command.move_home(),
                     NewSessionField::Group => d.group_path.move_home(),
                 },
                 KeyCode::End => match d.field {
                     NewSessionField::Path => d.path.move_end(),
                     NewSessionField::Title => d.title.move_end(),
+                    NewSessionField::Tool => {}
+                    NewSessionField::Command => d.command.move_end(),
+                    NewSessionField::Pre => d.pre_command.move_end(),
                     NewSessionField::Group => d.group_path.move_end(),
                 },
                 KeyCode::Char(ch) => {
@@ -1376,6 +1742,7 @@ impl App {
                         }
                     }
 
+                    d.error = None;
                     match d.field {
                         NewSessionField::Path => {
                             d.path.insert(ch);
@@ -1384,6 +1751,13 @@ impl App {
                             d.path_last_edit = Instant::now();
                         }
                         NewSessionField::Title => d.title.insert(ch),
+                        NewSessionField::Tool => {
+                            if ch == ' ' {
+                                d.cycle_tool(false);
+                            }
+                        }
+                        NewSessionField::Command => d.command.insert(ch),
+                        NewSessionField::Pre => d.pre_command.insert(ch),
                         NewSessionField::Group => {
                             d.group_path.insert(ch);
                             d.update_group_matches();
@@ -1392,6 +1766,77 @@ impl App {
                 }
                 _ => {}
             },
+            Dialog::Rebind(d) => match d.stage {
+                RebindStage::Browse => match key {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        self.dialog = None;
+                        self.state = AppState::Help;
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        d.selected = d.selected.saturating_sub(1);
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        if d.selected + 1 < d.rows.len() {
+                            d.selected += 1;
+                        }
+                    }
+                    KeyCode::Enter | KeyCode::Char('e') => {
+                        d.stage = RebindStage::Capture;
+                    }
+                    _ => {}
+                },
+                RebindStage::Capture => {
+                    if key == KeyCode::Esc {
+                        d.stage = RebindStage::Browse;
+                        return Ok(());
+                    }
+                    let Some(target) = d.rows.get(d.selected).map(|r| r.target) else {
+                        return Ok(());
+                    };
+                    let captured = crate::config::normalize_key(&crate::config::KeySpec {
+                        code: key,
+                        modifiers,
+                    });
+                    match self.preview_rebind(target, captured) {
+                        Some(stage) => {
+                            if let Some(Dialog::Rebind(d)) = self.dialog.as_mut() {
+                                d.stage = stage;
+                            }
+                        }
+                        None => {
+                            let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+                            let msg = if is_zh { "tmux 无法绑定此键" } else { "tmux cannot bind that key" };
+                            self.set_info_bar(msg.to_string(), ratatui::style::Color::Yellow);
+                        }
+                    }
+                }
+                RebindStage::Confirm { .. } => match key {
+                    KeyCode::Enter | KeyCode::Char('y') => self.apply_rebind().await,
+                    KeyCode::Esc | KeyCode::Char('n') => d.stage = RebindStage::Browse,
+                    _ => {}
+                },
+            },
+            Dialog::RestartExited(d) => {
+                let id = d.session_id.clone();
+                self.dialog = None;
+                self.state = AppState::Normal;
+                if matches!(key, KeyCode::Enter | KeyCode::Char('y')) {
+                    self.focus_session(&id).await?;
+                    if self.selected_session().is_some_and(|s| s.id == id) {
+                        self.activity.push(super::activity::ActivityOp::StartingSession, "Restarting session...");
+                        self.restart_selected().await?;
+                        self.activity.complete(super::activity::ActivityOp::StartingSession);
+                    }
+                }
+            }
+            Dialog::WrapupConfirm(d) => {
+                let group = d.group.clone();
+                self.dialog = None;
+                self.state = AppState::Normal;
+                if matches!(key, KeyCode::Enter | KeyCode::Char('y')) {
+                    self.start_wrapup(group.as_deref());
+                }
+            }
             Dialog::QuitConfirm => match key {
                 KeyCode::Char('q') | KeyCode::Char('Q') => {
                     self.dialog = None;
@@ -1403,42 +1848,32 @@ impl App {
                     self.state = AppState::Normal;
                 }
             },
-            Dialog::DeleteConfirm(d) => match key {
-                KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+            Dialog::DeleteConfirm(d) => match self
+                .keybindings
+                .dialog_action("delete_confirm", &key, modifiers)
+            {
+                Some("cancel") => {
                     self.dialog = None;
                     self.state = AppState::Normal;
                 }
-                KeyCode::Char('t') | KeyCode::Char('T') => {
+                Some("toggle_kill_tmux") => {
                     d.kill_tmux = !d.kill_tmux;
                 }
-                KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
-                    let session_id = d.session_id.clone();
-                    let kill_tmux = d.kill_tmux;
-                    self.dialog = None;
-                    self.state = AppState::Normal;
-                    self.activity.push_default(super::activity::ActivityOp::KillingSession);
-                    self.delete_session(&session_id, kill_tmux).await?;
-                    self.refresh_sessions().await?;
-                    // Sync canvas: remove orphaned nodes/edges for deleted sessions & relationships
-                    #[cfg(feature = "pro")]
-                    {
-                        self.canvas_state.sync_relationship_edges(&self.relationships);
-                        self.sync_canvas_after_deletion();
-                    }
-                    self.activity.complete(super::activity::ActivityOp::KillingSession);
+                Some("toggle_remove_worktree") if d.worktree.is_some() => {
+                    d.remove_worktree = !d.remove_worktree;
                 }
+                Some("confirm") => self.confirm_delete_sessions().await?,
                 _ => {}
             },
-            Dialog::DeleteGroup(d) => match key {
-                KeyCode::Esc => {
-                    self.dialog = None;
-                    self.state = AppState::Normal;
-                }
-                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+            Dialog::DeleteGroup(d) => match self
+                .keybindings
+                .dialog_action("delete_group", &key, modifiers)
+            {
+                Some("cancel") => {
                     self.dialog = None;
                     self.state = AppState::Normal;
                 }
-                KeyCode::Up | KeyCode::Char('k') => {
+                Some("up") => {
                     d.choice = match d.choice {
                         DeleteGroupChoice::DeleteGroupKeepSessions => {
                             DeleteGroupChoice::DeleteGroupAndSessions
@@ -1447,7 +1882,7 @@ impl App {
                         DeleteGroupChoice::DeleteGroupAndSessions => DeleteGroupChoice::Cancel,
                     };
                 }
-                KeyCode::Down | KeyCode::Char('j') => {
+                Some("down") => {
                     d.choice = match d.choice {
                         DeleteGroupChoice::DeleteGroupKeepSessions => DeleteGroupChoice::Cancel,
                         DeleteGroupChoice::Cancel => DeleteGroupChoice::DeleteGroupAndSessions,
@@ -1456,26 +1891,13 @@ impl App {
                         }
                     };
                 }
-                KeyCode::Char('1') => d.choice = DeleteGroupChoice::DeleteGroupKeepSessions,
-                KeyCode::Char('2') => d.choice = DeleteGroupChoice::Cancel,
-                KeyCode::Char('3') => d.choice = DeleteGroupChoice::DeleteGroupAndSessions,
-                KeyCode::Enter => {
-                    let group_path = d.group_path.clone();
-                    let choice = d.choice;
-                    self.dialog = None;
-                    self.state = AppState::Normal;
-                    match choice {
-                        DeleteGroupChoice::DeleteGroupKeepSessions => {
-                            self.apply_delete_group_keep_sessions(&group_path).await?;
-                        }
-                        DeleteGroupChoice::Cancel => {}
-                        DeleteGroupChoice::DeleteGroupAndSessions => {
-                            self.apply_delete_group_and_sessions(&group_path).await?;
-                        }
-                    }
-                    self.refresh_sessions().await?;
-                }
-                _ => {}
+                Some("confirm") => self.confirm_delete_group().await?,
+                _ => match key {
+                    KeyCode::Char('1') => d.choice = DeleteGroupChoice::DeleteGroupKeepSessions,
+                    KeyCode::Char('2') => d.choice = DeleteGroupChoice::Cancel,
+                    KeyCode::Char('3') => d.choice = DeleteGroupChoice::DeleteGroupAndSessions,
+                    _ => {}
+                },
             },
             Dialog::Fork(d) => match key {
                 KeyCode::Esc => {
@@ -1483,29 +1905,60 @@ impl App {
                     self.state = AppState::Normal;
                 }
                 KeyCode::Tab => {
-                    d.field = match d.field {
-                        ForkField::Title => ForkField::Group,
-                        ForkField::Group => ForkField::Title,
-                    };
+                    d.next_field();
                 }
                 KeyCode::Enter => {
                     if d.field == ForkField::Title {
                         d.field = ForkField::Group;
                     } else {
                         let parent_session_id = d.parent_session_id.clone();
-                        let project_path = d.project_path.clone();
                         let title = d.title.text().to_string();
                         let group_path = d.group_path.text().to_string();
+                        let context = if d.copy_context {
+                            d.context_copy.clone().ok()
+                        } else {
+                            None
+                        };
+                        let (project_path, branch) = match d.worktree_dest() {
+                            Some(dest) => {
+                                let branch = d.branch.text().trim().to_string();
+                                if branch.is_empty() {
+                                    d.error = Some("Branch name is required for a worktree".to_string());
+                                    d.field = ForkField::Branch;
+                                    return Ok(());
+                                }
+                                if let Err(e) = crate::git::worktree_add(&d.project_path, &dest, &branch).await {
+                                    d.error = Some(e.to_string());
+                                    return Ok(());
+                                }
+                                (dest, Some(branch))
+                            }
+                            None => (d.project_path.clone(), None),
+                        };
                         self.dialog = None;
                         self.state = AppState::Normal;
-                        let new_id = self
+                        let worktree = branch.is_some().then(|| project_path.clone());
+                        let created = self
                             .create_fork_session(
                                 &parent_session_id,
                                 project_path,
                                 &title,
                                 &group_path,
+                                context,
+                                branch,
                             )
-                            .await?;
+                            .await;
+                        let new_id = match created {
+                            Ok(id) => id,
+                            Err(e) => {
+                                // Don't leave the worktree behind without a session.
+                                if let Some(worktree) = worktree {
+                                    let _ = crate::git::worktree_remove(&worktree).await;
+                                }
+                                self.set_info_bar(e.to_string(), ratatui::style::Color::Red);
+                                return Ok(());
+                            }
+                        };
                         self.refresh_sessions().await?;
                         self.focus_session(&new_id).await?;
                     }
@@ -1521,6 +1974,10 @@ impl App {
                     ForkField::Group => {
                         d.group_path.backspace();
                     }
+                    ForkField::Branch => {
+                        d.branch.backspace();
+                    }
+                    ForkField::Context | ForkField::Worktree => {}
                 },
                 KeyCode::Delete => match d.field {
                     ForkField::Title => {
@@ -1529,28 +1986,52 @@ impl App {
                     ForkField::Group => {
                         d.group_path.delete();
                     }
+                    ForkField::Branch => {
+                        d.branch.delete();
+                    }
+                    ForkField::Context | ForkField::Worktree => {}
                 },
                 KeyCode::Left => match d.field {
                     ForkField::Title => d.title.move_left(),
                     ForkField::Group => d.group_path.move_left(),
+                    ForkField::Branch => d.branch.move_left(),
+                    ForkField::Context | ForkField::Worktree => {}
                 },
                 KeyCode::Right => match d.field {
                     ForkField::Title => d.title.move_right(),
                     ForkField::Group => d.group_path.move_right(),
+                    ForkField::Branch => d.branch.move_right(),
+                    ForkField::Context | ForkField::Worktree => {}
                 },
                 KeyCode::Home => match d.field {
                     ForkField::Title => d.title.move_home(),
                     ForkField::Group => d.group_path.move_home(),
+                    ForkField::Branch => d.branch.move_home(),
+                    ForkField::Context | ForkField::Worktree => {}
                 },
                 KeyCode::End => match d.field {
                     ForkField::Title => d.title.move_end(),
                     ForkField::Group => d.group_path.move_end(),
+                    ForkField::Branch => d.branch.move_end(),
+                    ForkField::Context | ForkField::Worktree => {}
                 },
                 KeyCode::Char(ch) => {
                     if !modifiers.contains(KeyModifiers::CONTROL) {
                         match d.field {
                             ForkField::Title => d.title.insert(ch),
                             ForkField::Group => d.group_path.insert(ch),
+                            ForkField::Branch => d.branch.insert(ch),
+                            ForkField::Context => {
+                                if ch == ' ' && d.context_copy.is_ok() {
+                                    d.copy_context = !d.copy_context;
+                                }
+                            }
+                            ForkField::Worktree => {
+                                if ch == ' ' {
+                                    d.worktree = !d.worktree;
+                                    d.error = None;
+                                }
+                            }
                         }
                     }
                 }
@@ -1569,6 +2050,9 @@ impl App {
                     self.apply_rename_group(&old_path, &new_path).await?;
                     self.refresh_sessions().await?;
                     self.focus_group(&new_path).await?;
+                    if old_path.trim() != new_path.trim() {
+                        self.notify_group_done(GroupOp::Renamed, new_path.trim());
+                    }
                 }
                 KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
                     self.dialog = None;
@@ -1599,6 +2083,187 @@ impl App {
                 }
                 _ => {}
             },
+            Dialog::GroupNotes(d) => match key {
+                KeyCode::Esc => {
+                    self.dialog = None;
+                    self.state = AppState::Normal;
+                }
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.dialog = None;
+                    self.state = AppState::Normal;
+                }
+                KeyCode::Enter => {
+                    let path = d.path.clone();
+                    let text = d.notes.text().to_string();
+                    self.dialog = None;
+                    self.state = AppState::Normal;
+                    self.apply_group_notes(&path, &text).await?;
+                    self.refresh_sessions().await?;
+                }
+                KeyCode::Backspace => {
+                    d.notes.backspace();
+                }
+                KeyCode::Delete => {
+                    d.notes.delete();
+                }
+                KeyCode::Left => {
+                    d.notes.move_left();
+                }
+                KeyCode::Right => {
+                    d.notes.move_right();
+                }
+                KeyCode::Home => {
+                    d.notes.move_home();
+                }
+                KeyCode::End => {
+                    d.notes.move_end();
+                }
+                KeyCode::Char(ch) => {
+                    if !modifiers.contains(KeyModifiers::CONTROL) {
+                        d.notes.insert(ch);
+                    }
+                }
+                _ => {}
+            },
+            Dialog::ReparentGroup(d) => match key {
+                KeyCode::Esc => {
+                    self.dialog = None;
+                    self.state = AppState::Normal;
+                }
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.dialog = None;
+                    self.state = AppState::Normal;
+                }
+                KeyCode::Enter => {
+                    let path = d.path.clone();
+                    let new_parent = d.new_parent.text().to_string();
+                    match self.apply_reparent_group(&path, &new_parent).await {
+                        Ok(new_path) => {
+                            self.dialog = None;
+                            self.state = AppState::Normal;
+                            self.refresh_sessions().await?;
+                            self.focus_group(&new_path).await?;
+                            self.notify_group_done(GroupOp::Moved, &new_path);
+                        }
+                        Err(e) => {
+                            if let Some(Dialog::ReparentGroup(d)) = self.dialog.as_mut() {
+                                d.error = Some(e.to_string());
+                            }
+                        }
+                    }
+                }
+                KeyCode::Backspace => {
+                    d.new_parent.backspace();
+                    d.error = None;
+                }
+                KeyCode::Delete => {
+                    d.new_parent.delete();
+                    d.error = None;
+                }
+                KeyCode::Left => d.new_parent.move_left(),
+                KeyCode::Right => d.new_parent.move_right(),
+                KeyCode::Home => d.new_parent.move_home(),
+                KeyCode::End => d.new_parent.move_end(),
+                KeyCode::Char(ch) => {
+                    if !modifiers.contains(KeyModifiers::CONTROL) {
+                        d.new_parent.insert(ch);
+                        d.error = None;
+                    }
+                }
+                _ => {}
+            },
+            Dialog::ForwardOutput(d) => match key {
+                KeyCode::Esc => {
+                    self.dialog = None;
+                    self.state = AppState::Normal;
+                }
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.dialog = None;
+                    self.state = AppState::Normal;
+                }
+                KeyCode::Up | KeyCode::Char('k') => d.move_selection(false),
+                KeyCode::Down | KeyCode::Char('j') => d.move_selection(true),
+                KeyCode::Enter => {
+                    let Some(target) = d.selected_target().cloned() else {
+                        return Ok(());
+                    };
+                    if !target.running && !d.confirm_start {
+                        d.confirm_start = true;
+                        return Ok(());
+                    }
+                    let source_id = d.source_id.clone();
+                    self.dialog = None;
+                    self.state = AppState::Normal;
+                    self.forward_to(&source_id, &target).await?;
+                }
+                _ => {}
+            },
+            Dialog::RepairPath(d) => match key {
+                KeyCode::Esc => {
+                    self.dialog = None;
+                    self.state = AppState::Normal;
+                }
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.dialog = None;
+                    self.state = AppState::Normal;
+                }
+                KeyCode::Char('d') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    let session_id = d.session_id.clone();
+                    let title = d.title.clone();
+                    let Some(target) = self.selection_identity() else {
+                        return Ok(());
+                    };
+                    self.dialog = Some(Dialog::DeleteConfirm(DeleteConfirmDialog {
+                        session_id,
+                        title,
+                        batch: Vec::new(),
+                        kill_tmux: true,
+                        worktree: None,
+                        remove_worktree: false,
+                        target,
+                    }));
+                }
+                KeyCode::Tab => d.complete(),
+                KeyCode::BackTab | KeyCode::Up => d.cycle_suggestion(true),
+                KeyCode::Down => d.cycle_suggestion(false),
+                KeyCode::Enter => {
+                    if d.suggestions_visible {
+                        d.complete();
+                        return Ok(());
+                    }
+                    let path = match d.validate() {
+                        Ok(path) => path,
+                        Err(e) => {
+                            self.set_info_bar(e.to_string(), ratatui::style::Color::Red);
+                            return Ok(());
+                        }
+                    };
+                    let session_id = d.session_id.clone();
+                    self.dialog = None;
+                    self.state = AppState::Normal;
+                    self.apply_repair_path(&session_id, path).await?;
+                    self.refresh_sessions().await?;
+                    self.focus_session(&session_id).await?;
+                    self.start_selected().await?;
+                }
+                KeyCode::Backspace => {
+                    d.path.backspace();
+                    d.clear_suggestions();
+                }
+                KeyCode::Delete => {
+                    d.path.delete();
+                    d.clear_suggestions();
+                }
+                KeyCode::Left => d.path.move_left(),
+                KeyCode::Right => d.path.move_right(),
+                KeyCode::Home => d.path.move_home(),
+                KeyCode::End => d.path.move_end(),
+                KeyCode::Char(ch) => {
+                    d.path.insert(ch);
+                    d.clear_suggestions();
+                }
+                _ => {}
+            },
             Dialog::RenameSession(d) => match key {
                 KeyCode::Esc => {
                     self.dialog = None;
@@ -1634,12 +2299,22 @@ impl App {
                     } else {
                         Some(cli_sid.as_str())
                     };
+                    let applied = self
+                        .apply_edit_session(
+                            &session_id, &old_title, &title, &label, label_color, cli_sid_opt,
+                        )
+                        .await;
+                    if let Err(e) = applied {
+                        if let Some(Dialog::RenameSession(d)) = self.dialog.as_mut() {
+                            d.error = Some(match e {
+                                crate::Error::InvalidInput(msg) => msg,
+                                e => e.to_string(),
+                            });
+                        }
+                        return Ok(());
+                    }
                     self.dialog = None;
                     self.state = AppState::Normal;
-                    self.apply_edit_session(
-                        &session_id, &old_title, &title, &label, label_color, cli_sid_opt,
-                    )
-                        .await?;
                     self.refresh_sessions().await?;
                     self.focus_session(&session_id).await?;
                 }
@@ -1650,6 +2325,7 @@ impl App {
                 KeyCode::Backspace => match d.field {
                     SessionEditField::Title => {
                         d.new_title.backspace();
+                        d.error = None;
                     }
                     SessionEditField::Label => {
                         d.label.backspace();
@@ -1662,6 +2338,7 @@ impl App {
                 KeyCode::Delete => match d.field {
                     SessionEditField::Title => {
                         d.new_title.delete();
+                        d.error = None;
                     }
                     SessionEditField::Label => {
                         d.label.delete();
@@ -1781,7 +2458,10 @@ impl App {
                             }
                         } else {
                             match d.field {
-                                SessionEditField::Title => d.new_title.insert(ch),
+                                SessionEditField::Title => {
+                                    d.new_title.insert(ch);
+                                    d.error = None;
+                                }
                                 SessionEditField::Label => d.label.insert(ch),
                                 SessionEditField::SessionId => d.cli_session_id.insert(ch),
                                 SessionEditField::Color => {}
@@ -1827,6 +2507,7 @@ impl App {
                     self.apply_create_group(&group_path).await?;
                     self.refresh_sessions().await?;
                     self.focus_group(&group_path).await?;
+                    self.notify_group_done(GroupOp::Created, group_path.trim());
                 }
                 KeyCode::Backspace => {
                     d.input.backspace();
@@ -1880,16 +2561,27 @@ impl App {
                     }
                 }
                 KeyCode::Enter => {
-                    let session_id = d.session_id.clone();
+                    let session_ids = d.session_ids();
                     let group_path = d
                         .selected_value()
                         .map(|s| s.to_string())
                         .unwrap_or_else(|| d.input.text().trim().to_string());
                     self.dialog = None;
                     self.state = AppState::Normal;
-                    self.apply_move_group(&session_id, &group_path).await?;
+                    for session_id in &session_ids {
+                        self.apply_move_group(session_id, &group_path).await?;
+                    }
+                    self.clear_marks();
                     self.refresh_sessions().await?;
-                    self.focus_session(&session_id).await?;
+                    self.focus_session(&session_ids[0]).await?;
+                    let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+                    let msg = match (session_ids.len(), is_zh) {
+                        (1, true) => format!("已移动到 {group_path}"),
+                        (1, false) => format!("Moved to {group_path}"),
+                        (n, true) => format!("已将 {n} 个会话移动到 {group_path}"),
+                        (n, false) => format!("Moved {n} sessions to {group_path}"),
+                    };
+                    self.notify_info(msg);
                 }
                 KeyCode::Backspace => {
                     d.input.backspace();
@@ -1969,7 +2661,155 @@ impl App {
                 }
                 _ => {}
             },
+            Dialog::LabelPicker(d) => match key {
+                KeyCode::Esc => {
+                    self.dialog = None;
+                    self.state = AppState::Normal;
+                }
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.dialog = None;
+                    self.state = AppState::Normal;
+                }
+                KeyCode::Up | KeyCode::BackTab => {
+                    d.choice = (d.choice + LabelPickerDialog::CHOICES - 1) % LabelPickerDialog::CHOICES;
+                }
+                KeyCode::Down | KeyCode::Tab => {
+                    d.choice = (d.choice + 1) % LabelPickerDialog::CHOICES;
+                }
+                KeyCode::Left => d.label.move_left(),
+                KeyCode::Right => d.label.move_right(),
+                KeyCode::Home => d.label.move_home(),
+                KeyCode::End => d.label.move_end(),
+                KeyCode::Backspace => d.label.backspace(),
+                KeyCode::Delete => d.label.delete(),
+                KeyCode::Enter => {
+                    let session_id = d.session_id.clone();
+                    let (label, color) = d.result();
+                    self.dialog = None;
+                    self.state = AppState::Normal;
 
+                    let Some(s) = self.session_by_id(&session_id) else {
+                        return Ok(());
+                    };
+                    let old_title = s.title.clone();
+                    self.apply_edit_session(&session_id, &old_title, &old_title, &label, color, None)
+                        .await?;
+                    self.refresh_sessions().await?;
+                    self.focus_session(&session_id).await?;
+                }
+                KeyCode::Char(ch) => {
+                    if !modifiers.contains(KeyModifiers::CONTROL) {
+                        d.label.insert(ch);
+                    }
+                }
+                _ => {}
+            },
+            Dialog::Lineage(d) => match key {
+                KeyCode::Esc | KeyCode::Char('L') | KeyCode::Char('q') => {
+                    self.dialog = None;
+                    self.state = AppState::Normal;
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    d.selected = d.selected.saturating_sub(1);
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    if d.selected + 1 < d.rows.len() {
+                        d.selected += 1;
+                    }
+                }
+                KeyCode::Enter => {
+                    let target = d.rows.get(d.selected).map(|r| r.id.clone());
+                    self.dialog = None;
+                    self.state = AppState::Normal;
+                    if let Some(id) = target {
+                        self.focus_session(&id).await?;
+                    }
+                }
+                KeyCode::Char('D') | KeyCode::Char('d') => {
+                    let target = d.rows.get(d.selected).map(|r| r.id.clone());
+                    self.dialog = None;
+                    self.state = AppState::Normal;
+                    if let Some(id) = target {
+                        self.focus_session(&id).await?;
+                        self.show_fork_diff(&id).await;
+                    }
+                }
+                _ => {}
+            },
+            Dialog::WaitingQueue(d) => {
+                let queue = crate::session::waiting::waiting_queue(&self.sessions);
+                let selected = d.selected_index(&queue);
+                let ids: Vec<String> = queue.iter().map(|s| s.id.clone()).collect();
+                match key {
+                    KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('w') => {
+                        self.dialog = None;
+                        self.state = AppState::Normal;
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        if let Some(id) = selected.checked_sub(1).and_then(|i| ids.get(i)) {
+                            d.selected_id = Some(id.clone());
+                        }
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        if let Some(id) = ids.get(selected + 1) {
+                            d.selected_id = Some(id.clone());
+                        }
+                    }
+                    KeyCode::Enter | KeyCode::Char('1'..='9') => {
+                        let pos = match key {
+                            KeyCode::Char(c) => c as usize - '1' as usize,
+                            _ => selected,
+                        };
+                        if let Some(id) = ids.get(pos) {
+                            self.dialog = None;
+                            self.state = AppState::Normal;
+                            self.canvas_focused = false;
+                            self.active_panel_focused = false;
+                            self.last_attach_source = Some(super::AttachSource::TreePanel);
+                            self.queue_attach_by_id(id).await?;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Dialog::ProfilePicker(d) => match key {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('P') => {
+                    self.dialog = None;
+                    self.state = AppState::Normal;
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    d.selected = d.selected.saturating_sub(1);
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    if d.selected + 1 < d.profiles.len() {
+                        d.selected += 1;
+                    }
+                }
+                KeyCode::Enter => {
+                    let profile = d.profiles.get(d.selected).cloned();
+                    self.dialog = None;
+                    self.state = AppState::Normal;
+                    if let Some(profile) = profile {
+                        self.switch_profile(&profile).await;
+                    }
+                }
+                _ => {}
+            },
+            Dialog::MessageLog(d) => match key {
+                KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('!') => {
+                    self.dialog = None;
+                    self.state = AppState::Normal;
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    d.selected = d.selected.saturating_sub(1);
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    if d.selected + 1 < self.message_log.len() {
+                        d.selected += 1;
+                    }
+                }
+                _ => {}
+            },
             #[cfg(feature = "pro")]
             Dialog::CreateRelationship(d) => match key {
                 KeyCode::Esc => {
@@ -2184,7 +3024,8 @@ impl App {
                             self.pro.share_task_rx = Some(rx);
                             self.activity.push_default(super::activity::ActivityOp::StartingShare);
 
-                            tokio::spawn(async move {
+                            let share_session = sid.clone();
+                            let task = tokio::spawn(async move {
                                 use super::ShareTaskResult;
                                 use super::ShareTaskError;
 
@@ -2243,6 +3084,7 @@ impl App {
 
                                 let _ = tx.send(result);
                             });
+                            self.pending_ops.register_task(&share_session, task.abort_handle());
                         } else if crate::pro::tmate::TmateManager::is_available().await {
                             // Tmate fallback — stays blocking (rare path)
                             let mut mgr = crate::pro::tmate::TmateManager::from_config().await;
@@ -2506,7 +3348,7 @@ impl App {
                                         let escaped = context.replace('\'', "'\\''");
                                         let cmd = format!("echo '{}'", escaped);
                                         let _ =
-                                            self.tmux.send_keys(&tmux_name, &cmd).await;
+                                            self.tmux.send_text(&tmux_name, &cmd).await;
                                     }
                                     crate::ui::ContextInjectionMethod::ClaudeMd => {
                                         // Write context to {project_path}/CLAUDE.md
@@ -2522,7 +3364,7 @@ impl App {
                                             escaped
                                         );
                                         let _ =
-                                            self.tmux.send_keys(&tmux_name, &cmd).await;
+                                            self.tmux.send_text(&tmux_name, &cmd).await;
                                     }
                                 }
 
@@ -3655,6 +4497,7 @@ impl App {
                 self.help_visible = false;
                 self.state = AppState::Normal;
             }
+            KeyCode::Char('e') => self.open_rebind_dialog(),
             _ => {}
         }
         Ok(())
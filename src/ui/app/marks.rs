@@ -0,0 +1,136 @@
+use super::*;
+
+impl App {
+    pub fn is_marked(&self, session_id: &str) -> bool {
+        self.marked.contains(session_id)
+    }
+
+    pub fn marked_count(&self) -> usize {
+        self.marked
+            .iter()
+            .filter(|id| self.sessions_by_id.contains_key(*id))
+            .count()
+    }
+
+    /// Mark or unmark the selected session. Returns false on group rows.
+    pub(super) fn toggle_mark_selected(&mut self) -> bool {
+        let Some(id) = self.selected_session().map(|s| s.id.clone()) else {
+            return false;
+        };
+        if !self.marked.remove(&id) {
+            self.marked.insert(id);
+        }
+        true
+    }
+
+    pub(super) fn clear_marks(&mut self) {
+        self.marked.clear();
+    }
+
+    /// Drop marks whose session is gone; called after a reload.
+    pub(super) fn retain_marks(&mut self) {
+        let sessions_by_id = &self.sessions_by_id;
+        self.marked.retain(|id| sessions_by_id.contains_key(id));
+    }
+
+    /// Marked sessions as (id, title), in saved order. Sessions inside
+    /// collapsed groups count too, as they do in [`marked_count`](Self::marked_count).
+    pub(super) fn marked_sessions(&self) -> Vec<(String, String)> {
+        self.sessions
+            .iter()
+            .filter(|s| self.marked.contains(&s.id))
+            .map(|s| (s.id.clone(), s.title.clone()))
+            .collect()
+    }
+
+    /// Start every marked session that is not running, then clear the marks.
+    pub(super) async fn start_marked(&mut self) -> Result<()> {
+        let ids: Vec<String> = self.marked_sessions().into_iter().map(|(id, _)| id).collect();
+        self.clear_marks();
+        let mut started = 0;
+        for id in &ids {
+            if self.start_session(id).await? {
+                started += 1;
+            }
+        }
+        if started > 0 {
+            self.refresh_sessions().await?;
+        }
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        let msg = if is_zh {
+            format!("已启动 {started}/{} 个会话", ids.len())
+        } else {
+            format!("Started {started} of {} sessions", ids.len())
+        };
+        self.set_info_bar(msg, ratatui::style::Color::Green);
+        Ok(())
+    }
+
+    /// Show two sessions side by side: the two marked ones, or the one
+    /// marked plus the selected one. They are started first if needed.
+    pub(super) async fn compare_marked(&mut self) -> Result<()> {
+        let mut ids: Vec<String> = self.marked_sessions().into_iter().map(|(id, _)| id).collect();
+        if ids.len() == 1 {
+            if let Some(selected) = self.selected_session().map(|s| s.id.clone()) {
+                if selected != ids[0] {
+                    ids.push(selected);
+                }
+            }
+        }
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        if ids.len() != 2 {
+            let msg = if is_zh {
+                "对比需要两个会话：标记两个（v），或标记一个再选中另一个"
+            } else {
+                "Compare needs two sessions: mark two (v), or mark one and select the other"
+            };
+            self.set_info_bar(msg.to_string(), ratatui::style::Color::Yellow);
+            return Ok(());
+        }
+        self.clear_marks();
+
+        let mut started = false;
+        for id in &ids {
+            started |= self.start_session(id).await?;
+        }
+        if started {
+            self.refresh_sessions().await?;
+        }
+        let names: Vec<String> = ids
+            .iter()
+            .filter_map(|id| self.session_by_id(id).map(|s| s.tmux_name()))
+            .collect();
+        let [left, right] = names.as_slice() else {
+            return Ok(());
+        };
+        let name = self.tmux.create_compare_session(left, right).await?;
+        self.pending_attach = Some(name);
+        Ok(())
+    }
+
+    /// Stop every marked session that is running, then clear the marks.
+    /// A failed kill is logged and the rest still stop.
+    pub(super) async fn stop_marked(&mut self) -> Result<()> {
+        let ids: Vec<String> = self.marked_sessions().into_iter().map(|(id, _)| id).collect();
+        self.clear_marks();
+        let mut stopped = 0;
+        for id in &ids {
+            match self.stop_session(id).await {
+                Ok(true) => stopped += 1,
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Failed to stop session {}: {}", id, e),
+            }
+        }
+        if stopped > 0 {
+            self.refresh_sessions().await?;
+        }
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        let msg = if is_zh {
+            format!("已停止 {stopped}/{} 个会话", ids.len())
+        } else {
+            format!("Stopped {stopped} of {} sessions", ids.len())
+        };
+        self.set_info_bar(msg, ratatui::style::Color::Green);
+        Ok(())
+    }
+}
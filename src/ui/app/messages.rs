@@ -0,0 +1,195 @@
+use super::*;
+
+use std::collections::VecDeque;
+
+use ratatui::style::Color;
+
+/// Messages the `!` popup keeps, oldest dropped first.
+const MESSAGE_LOG_LIMIT: usize = 20;
+
+/// How an action went, which picks the info bar's color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn color(self) -> Color {
+        match self {
+            Severity::Info => Color::Green,
+            Severity::Warning => Color::Yellow,
+            Severity::Error => Color::Red,
+        }
+    }
+}
+
+/// A finished group change, for its info-bar message.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum GroupOp {
+    Created,
+    Renamed,
+    Moved,
+    Deleted,
+    DeletedWithSessions,
+}
+
+/// One info-bar message, as the `!` popup lists it.
+#[derive(Debug, Clone)]
+pub struct LoggedMessage {
+    pub at: chrono::DateTime<chrono::Local>,
+    pub text: String,
+    pub color: Color,
+    /// How many times in a row it was shown.
+    pub repeats: usize,
+}
+
+impl LoggedMessage {
+    pub fn new(text: String, color: Color) -> Self {
+        Self {
+            at: chrono::Local::now(),
+            text,
+            color,
+            repeats: 1,
+        }
+    }
+}
+
+/// The last info-bar messages, newest last. Kept for this run only.
+#[derive(Default)]
+pub(super) struct MessageLog {
+    entries: VecDeque<LoggedMessage>,
+}
+
+impl MessageLog {
+    /// Record a message. One repeating the last is folded into it, so an
+    /// error that comes back every tick does not push everything else out.
+    pub(super) fn push(&mut self, message: LoggedMessage) {
+        if let Some(last) = self.entries.back_mut() {
+            if last.text == message.text {
+                last.at = message.at;
+                last.color = message.color;
+                last.repeats += 1;
+                return;
+            }
+        }
+        if self.entries.len() == MESSAGE_LOG_LIMIT {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(message);
+    }
+
+    /// Newest first.
+    pub(super) fn newest_first(&self) -> Vec<LoggedMessage> {
+        self.entries.iter().rev().cloned().collect()
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+impl App {
+    /// Show `message` in the info bar and keep it in the message log.
+    pub(super) fn notify(&mut self, message: impl Into<String>, severity: Severity) {
+        self.set_info_bar(message.into(), severity.color());
+    }
+
+    /// An action went through.
+    pub(super) fn notify_info(&mut self, message: impl Into<String>) {
+        self.notify(message, Severity::Info);
+    }
+
+    /// An action did not go as asked, but nothing failed.
+    pub(super) fn notify_warning(&mut self, message: impl Into<String>) {
+        self.notify(message, Severity::Warning);
+    }
+
+    /// An action failed; also logged, as the bar goes away on its own.
+    pub(super) fn notify_error(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        tracing::warn!("{message}");
+        self.notify(message, Severity::Error);
+    }
+
+    /// Report a failed save instead of dropping it: the change is still
+    /// on screen but would be gone after a restart.
+    pub(super) fn notify_save_error(&mut self, err: &crate::Error) {
+        let msg = if matches!(self.language(), crate::i18n::Language::Chinese) {
+            format!("保存失败: {err}")
+        } else {
+            format!("Failed to save sessions: {err}")
+        };
+        self.notify_error(msg);
+    }
+
+    /// Report a group change; `path` is the group's path after it.
+    pub(super) fn notify_group_done(&mut self, op: GroupOp, path: &str) {
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        let msg = match (op, is_zh) {
+            (GroupOp::Created, false) => format!("Created group {path}"),
+            (GroupOp::Created, true) => format!("已创建分组 {path}"),
+            (GroupOp::Renamed, false) => format!("Renamed group to {path}"),
+            (GroupOp::Renamed, true) => format!("已将分组重命名为 {path}"),
+            (GroupOp::Moved, false) => format!("Moved group to {path}"),
+            (GroupOp::Moved, true) => format!("已将分组移动到 {path}"),
+            (GroupOp::Deleted, false) => format!("Deleted group {path}"),
+            (GroupOp::Deleted, true) => format!("已删除分组 {path}"),
+            (GroupOp::DeletedWithSessions, false) => {
+                format!("Deleted group {path} and its sessions")
+            }
+            (GroupOp::DeletedWithSessions, true) => format!("已删除分组 {path} 及其会话"),
+        };
+        self.notify_info(msg);
+    }
+
+    /// Info-bar messages of this run, newest first.
+    pub fn message_log(&self) -> Vec<LoggedMessage> {
+        self.message_log.newest_first()
+    }
+
+    pub(super) fn open_message_log(&mut self) {
+        self.dialog = Some(Dialog::MessageLog(MessageLogDialog::default()));
+        self.state = AppState::Dialog;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_keeps_the_newest_twenty() {
+        let mut log = MessageLog::default();
+        for i in 0..25 {
+            log.push(LoggedMessage::new(format!("m{i}"), Color::Green));
+        }
+        let texts: Vec<String> = log.newest_first().into_iter().map(|m| m.text).collect();
+        assert_eq!(texts.len(), MESSAGE_LOG_LIMIT);
+        assert_eq!(texts.first().map(String::as_str), Some("m24"));
+        assert_eq!(texts.last().map(String::as_str), Some("m5"));
+    }
+
+    #[test]
+    fn repeated_messages_fold_into_one() {
+        let mut log = MessageLog::default();
+        log.push(LoggedMessage::new("tmux failed".into(), Color::Red));
+        log.push(LoggedMessage::new("tmux failed".into(), Color::Red));
+        log.push(LoggedMessage::new("Started api".into(), Color::Green));
+        log.push(LoggedMessage::new("tmux failed".into(), Color::Red));
+        let repeats: Vec<(String, usize)> = log
+            .newest_first()
+            .into_iter()
+            .map(|m| (m.text, m.repeats))
+            .collect();
+        assert_eq!(
+            repeats,
+            [
+                ("tmux failed".to_string(), 1),
+                ("Started api".to_string(), 1),
+                ("tmux failed".to_string(), 2),
+            ]
+        );
+    }
+}
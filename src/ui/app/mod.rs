@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::io;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -25,9 +25,17 @@ use crate::tmux::{
 use super::{
     AppState, CreateGroupDialog,
     DeleteConfirmDialog, DeleteGroupChoice, DeleteGroupDialog, Dialog, ForkDialog, ForkField,
-    MoveGroupDialog, NewSessionDialog, NewSessionField, RenameGroupDialog, RenameSessionDialog,
-    SessionEditField, SettingsDialog, SettingsField, TagPickerDialog, TagSpec, TextInput, TreeItem,
+    ForwardOutputDialog, ForwardTarget, GroupNotesDialog,
+    LabelPickerDialog, LineageDialog, MoveGroupDialog, NewSessionDialog, NewSessionField,
+    MessageLogDialog, ProfilePickerDialog, RebindDialog, RebindRow,
+    RebindStage, RenameGroupDialog, RenameSessionDialog, RepairPathDialog, ReparentGroupDialog,
+    SelectionIdentity, SessionEditField, SettingsDialog, SettingsField, TagPickerDialog, TagSpec,
+    RestartExitedDialog, TextInput, TreeItem, WaitingQueueDialog,
+    WrapupConfirmDialog,
 };
+use super::layout::MainLayout;
+use super::perf::Phase;
+use super::{compare_sessions, should_resort, ResortCheck, SortChanges};
 
 #[cfg(feature = "pro")]
 use super::{CreateRelationshipDialog, CreateRelationshipField, OrphanedRoomsDialog, ShareDialog};
@@ -41,7 +49,19 @@ mod sessions;
 mod navigation;
 mod keys;
 mod dialogs;
+mod discovery;
+mod repos;
+mod forward;
+mod marks;
+mod mouse;
+pub(super) mod messages;
+mod preview;
+mod profile;
+mod reaper;
 mod search;
+mod startup;
+mod undo;
+mod wrapup;
 #[cfg(feature = "pro")]
 #[path = "../../../pro/src/ui/viewer.rs"]
 mod viewer;
@@ -101,6 +121,10 @@ pub struct App {
     selected_relationship_index: usize,
     relationship_snapshot_counts: HashMap<String, usize>,
     tree: Vec<TreeItem>,
+    /// Session statuses per group, for the counts on group rows
+    group_counts: HashMap<String, crate::ui::tree::GroupCounts>,
+    /// Fork nesting of session rows, for connectors and fork counts
+    fork_rows: HashMap<String, crate::ui::tree::ForkRow>,
     selected_index: usize,
 
     // Active sessions panel (premium)
@@ -110,7 +134,10 @@ pub struct App {
     // UI state
     help_visible: bool,
     preview: String,
-    preview_cache: HashMap<String, String>,
+    /// Recent pane captures per session; the newest is the preview cache.
+    preview_history: crate::ui::preview_history::PreviewHistories,
+    /// Scroll offset, focus mode and search of the preview pane.
+    preview_view: crate::ui::preview_view::PreviewView,
 
     // Canvas workflow editor
     canvas_state: crate::ui::canvas::CanvasState,
@@ -129,6 +156,8 @@ pub struct App {
     _control_socket: crate::control::socket::ControlSocketServer,
     /// Per-profile runtime coordination directory.
     runtime_dir: std::path::PathBuf,
+    /// Dirs the agent runner and executor write to; moved on a profile switch.
+    agent_dirs: crate::agent::runner::SharedProfileDirs,
     language: crate::i18n::Language,
     show_onboarding: bool,
 
@@ -136,12 +165,15 @@ pub struct App {
     search_query: String,
     search_results: Vec<String>,
     search_selected: usize,
+    search_offset: usize,
 
     // Dialog state
     dialog: Option<Dialog>,
 
     // Deferred actions that require terminal access
     pending_attach: Option<String>,
+    // Background work per session, cancelled when the session is deleted
+    pending_ops: super::CancelRegistry,
     last_attach_source: Option<AttachSource>,
 
     // Keybindings (configurable via ~/.agent-hand/config.json)
@@ -151,14 +183,49 @@ pub struct App {
     last_navigation_time: Instant,
     is_navigating: bool,
     pending_preview_id: Option<String>,
+    // Sessions marked with v/Space; x, d, s and m act on all of them
+    marked: HashSet<String>,
+    // Pinned session ids in slot order; 1-9 attach to them
+    pinned_ids: Vec<String>,
+    // Output waiting for a just-started session to come up (F on a stopped target)
+    pending_forward: Option<forward::PendingForward>,
     last_status_refresh: Instant,
     last_cache_refresh: Instant,
+    last_live_preview: Instant,
+    /// The preview holds a fork diff; live refresh leaves it alone until
+    /// navigation or Esc.
+    preview_shows_diff: bool,
+    /// `[status]` probe intervals and capture depth.
+    status_timing: crate::config::StatusTiming,
+    /// Debounce for `notification.desktop` Running → Waiting notices.
+    desktop_notifier: crate::notification::desktop::DesktopNotifier,
+    last_env_check: Instant,
+    /// Sessions whose project directory was gone at the last cache refresh.
+    missing_paths: HashSet<String>,
+    // Last pass of the idle reaper; see `[reaper]`. None until the first
+    // pass, which runs once the dashboard is up and the startup sweep is in
+    last_reap: Option<Instant>,
+    // Sort inputs changed since the last rebuild_tree (status/recency sorts)
+    pending_sort_changes: SortChanges,
+    last_resort: Instant,
+    // Phase timings behind the F11 overlay; see ui::perf
+    perf: crate::ui::perf::Perf,
 
     // Status/probing
     previous_statuses: HashMap<String, Status>,
+    // Persisted statuses not yet confirmed by a probe
+    stale_statuses: crate::session::reconcile::StaleStatuses,
+    // Tells CLI commands this profile is open; see session::heartbeat
+    tui_heartbeat: crate::session::heartbeat::HeartbeatWriter,
+    // Set during startup and bulk deletes so notifications are summarized
+    bulk_operations: crate::notification::coalesce::BulkOperations,
     last_tmux_activity: HashMap<String, i64>,
     last_tmux_activity_change: HashMap<String, Instant>,
     last_status_probe: HashMap<String, Instant>,
+    // Last capture of each Running session; see session::stall
+    pane_stalls: HashMap<String, crate::session::stall::PaneStall>,
+    // Sessions attached to this run, for jump-back and the status bar
+    attach_history: crate::ui::attach_history::AttachHistory,
     last_seen_detach_at: Option<String>,
     force_probe_tmux: Option<String>,
 
@@ -174,11 +241,19 @@ pub struct App {
 
     // Info bar (version update / tier mismatch hint). Auto-expires after 8 seconds.
     info_bar_message: Option<(String, ratatui::style::Color, Instant)>,
+    /// Messages waiting for the info bar; see `queue_info_bar`.
+    info_bar_queue: VecDeque<(String, ratatui::style::Color)>,
+    /// Recent info-bar messages for the `!` popup.
+    message_log: messages::MessageLog,
     info_bar_rx: Option<tokio::sync::oneshot::Receiver<Option<(String, ratatui::style::Color)>>>,
+    // First status sweep, probed in the background; see startup.rs
+    startup_sweep_rx: Option<tokio::sync::oneshot::Receiver<startup::SweepResult>>,
+    /// A wrap-up started from the TUI, running in the background.
+    wrapup_rx: Option<tokio::sync::oneshot::Receiver<Vec<crate::session::wrapup::WrapupResult>>>,
 
     // PTY monitoring (background task + shared state)
     ptmx_state: crate::tmux::ptmx::SharedPtmxState,
-    _ptmx_task: tokio::task::JoinHandle<()>,
+    _ptmx_task: Option<tokio::task::JoinHandle<()>>,
     cached_ptmx_total: u32,
     cached_ptmx_max: u32,
 
@@ -186,6 +261,26 @@ pub struct App {
     scan_state: crate::tmux::session_id_scanner::SharedScanState,
     _scan_task: tokio::task::JoinHandle<()>,
 
+    // Unmanaged agent discovery (opt-in background task + shared state)
+    discovery_state: Option<crate::tmux::discovery::SharedDiscoveryState>,
+    _discovery_task: Option<tokio::task::JoinHandle<()>>,
+    discovery_ignore_path: std::path::PathBuf,
+    discovery_ignored: Vec<std::path::PathBuf>,
+    /// Candidates not ignored or dismissed, refreshed from `discovery_state`.
+    unmanaged_agents: Vec<crate::tmux::discovery::UnmanagedAgent>,
+
+    // Shared-repo detection: common git dir per project path key (None = not a repo)
+    repo_ids: HashMap<std::path::PathBuf, Option<std::path::PathBuf>>,
+    repo_ids_rx: Option<tokio::sync::oneshot::Receiver<HashMap<std::path::PathBuf, Option<std::path::PathBuf>>>>,
+    repo_badges: HashMap<String, crate::session::repo::RepoBadge>,
+
+    // Persisted UI preferences (dialog sizes, simple/full mode)
+    ui_state: crate::ui::dialog_frame::UiState,
+    // Actions the current mode allows
+    capabilities: crate::ui::guard::Capabilities,
+    /// PIDs adopted or dismissed during this run.
+    dismissed_agent_pids: HashSet<u32>,
+
     // UI animation
     tick_count: u64,
     attention_ttl: Duration,
@@ -198,8 +293,11 @@ pub struct App {
 
     // Async operation activity tracking (spinner in status bar)
     activity: activity::ActivityTracker,
+    // Recent deletions `u` can restore
+    undo: undo::UndoStack,
 
     // Backend
+    profile: String,
     storage: Arc<Mutex<Storage>>,
     tmux: Arc<TmuxManager>,
     analytics: crate::analytics::ActivityTracker,
@@ -218,6 +316,9 @@ pub struct App {
 
     // Mouse capture state
     mouse_captured: bool,
+    /// Where the last frame put what mouse events can hit.
+    hit_areas: crate::ui::mouse::HitAreas,
+    clicks: crate::ui::mouse::ClickTracker,
     /// Set when settings change mouse_capture; applied next event loop iteration.
     mouse_capture_changed: bool,
 
@@ -288,10 +389,7 @@ fn resolve_mouse_capture(config: &crate::config::ConfigFile) -> bool {
 impl App {
     const PREVIEW_DEBOUNCE: Duration = Duration::from_millis(150);
     const NAVIGATION_SETTLE: Duration = Duration::from_millis(300);
-    const STATUS_REFRESH: Duration = Duration::from_secs(1);
-    const CACHE_REFRESH: Duration = Duration::from_secs(2);
-    const STATUS_COOLDOWN: Duration = Duration::from_secs(2);
-    const STATUS_FALLBACK: Duration = Duration::from_secs(10);
+    const ENV_CHECK: Duration = Duration::from_secs(10);
 
     /// Dynamic tick rate: 60 FPS during animations, 4 FPS otherwise.
     fn tick_rate(&self) -> Duration {
@@ -309,35 +407,37 @@ impl App {
 
     /// Create new application
     pub async fn new(profile: &str) -> Result<Self> {
-        let storage = Storage::new(profile).await?;
-        let (mut sessions, groups, relationships) = storage.load().await?;
-        // Status is derived from tmux probes; the persisted value can be stale across restarts.
-        // Reset to avoid treating old Running→Idle as a fresh completion.
-        // Also clear stale sharing state — relay rooms are ephemeral and won't survive TUI restart.
-        // Enforce default group: sessions with empty group_path get assigned to "default".
-        for s in &mut sessions {
-            s.status = Status::Idle;
-            if s.sharing.as_ref().is_some_and(|sh| sh.active) {
-                s.sharing = None;
-            }
-            if s.group_path.is_empty() {
-                s.group_path = "default".to_string();
-            }
-        }
-
-        let tmux = TmuxManager::new(profile);
-
-        // Clean up orphaned tmux sessions (exist in tmux but not in storage).
-        // This prevents PTY leaks from sessions that were deleted but whose tmux
-        // process was not properly killed.
-        {
-            let known_names: Vec<String> = sessions.iter().map(|s| s.tmux_name()).collect();
-            let known_refs: Vec<&str> = known_names.iter().map(|s| s.as_str()).collect();
-            let killed = tmux.cleanup_orphaned_sessions(&known_refs).await;
-            if killed > 0 {
-                tracing::info!("Cleaned up {} orphaned tmux session(s)", killed);
+        let profile::LoadedProfile {
+            profile_dir,
+            storage,
+            sessions,
+            groups,
+            relationships,
+            recovered_from,
+            tmux,
+            tmux_unsupported,
+            stale_statuses,
+            persisted,
+        } = profile::LoadedProfile::load(profile).await?;
+        let startup_summary = {
+            let mut coalescer =
+                crate::notification::coalesce::Coalescer::new(Duration::ZERO);
+            coalescer.begin_bulk();
+            let now = Instant::now();
+            for (s, _) in sessions
+                .iter()
+                .zip(&persisted)
+                .filter(|(s, was)| {
+                    s.status == Status::Stopped && !matches!(**was, Status::Idle | Status::Stopped)
+                })
+            {
+                coalescer.push(now, &s.title, crate::notification::coalesce::NoticeKind::WentIdle);
             }
-        }
+            coalescer.end_bulk()
+        };
+        // Hook events replayed by sessions coming back all land at startup.
+        let bulk_operations = crate::notification::coalesce::BulkOperations::default();
+        bulk_operations.begin(Instant::now());
 
         let keybindings = crate::config::KeyBindings::load_or_default().await;
         let analytics = crate::analytics::ActivityTracker::new(profile).await;
@@ -350,16 +450,33 @@ impl App {
             .ok()
             .flatten()
             .unwrap_or_default();
+        crate::ui::theme::set_theme(config.theme(profile));
         let attention_ttl = Duration::from_secs(config.ready_ttl_minutes() * 60);
+        let mut preview_history = crate::ui::preview_history::PreviewHistories::load(
+            profile_dir.join("previews"),
+            config.preview_history(),
+            crate::ui::preview_history::PREVIEW_BYTE_BUDGET,
+        );
+        preview_history.retain_sessions(|id| sessions.iter().any(|s| s.id == id));
+        if config.offline {
+            crate::network::set_offline(true);
+        }
 
-        // Create shared PTY state and spawn background monitor
+        // Create shared PTY state and spawn background monitor (opt-in: each scan runs lsof)
         let ptmx_state: SharedPtmxState = Arc::new(RwLock::new(
             crate::tmux::ptmx::PtmxState {
                 system_max: system_ptmx_max,
                 ..Default::default()
             }
         ));
-        let ptmx_task = spawn_ptmx_monitor(system_ptmx_max, Arc::clone(&ptmx_state), tmux.server_name().to_string());
+        let ptmx_task = config.ptmx.enabled.then(|| {
+            spawn_ptmx_monitor(
+                system_ptmx_max,
+                config.ptmx_interval(),
+                Arc::clone(&ptmx_state),
+                tmux.server_name().to_string(),
+            )
+        });
 
         // Create shared session ID scanner state and spawn background scanner
         let scan_state: crate::tmux::session_id_scanner::SharedScanState =
@@ -369,6 +486,22 @@ impl App {
             tmux.server_name().to_string(),
         );
 
+        // Opt-in discovery of agent processes running outside our tmux server
+        let (discovery_state, discovery_task) = if config.discovery.enabled {
+            let state: crate::tmux::discovery::SharedDiscoveryState =
+                Arc::new(RwLock::new(Default::default()));
+            let task = crate::tmux::discovery::spawn_discovery_monitor(
+                Duration::from_secs(config.discovery.interval_minutes.max(1) * 60),
+                Arc::clone(&state),
+                tmux.server_name().to_string(),
+            );
+            (Some(state), Some(task))
+        } else {
+            (None, None)
+        };
+        let discovery_ignore_path = profile_dir.join("discovery_ignore.json");
+        let discovery_ignored = crate::tmux::discovery::load_ignore_list(&discovery_ignore_path);
+
         // Start canvas socket server for external tool communication
         let (canvas_rx, canvas_socket) = crate::ui::canvas::socket::CanvasSocketServer::start();
 
@@ -403,6 +536,7 @@ impl App {
         #[cfg(feature = "wasm")]
         let wasm_action_tx;
         let chat_response_rx;
+        let agent_dirs;
         let (attached_session, sound_config) = {
             let attached: sound_task::AttachedSession =
                 Arc::new(std::sync::RwLock::new(None));
@@ -422,16 +556,12 @@ impl App {
                     wasm_action_tx = action_tx.clone();
                 }
 
-                let agent_hand_base = Storage::get_agent_hand_dir()
-                    .unwrap_or_else(|_| std::path::PathBuf::from(".agent-hand"))
-                    .join("profiles")
-                    .join(profile);
-
                 // Progress dir: ~/.agent-hand/profiles/{profile}/progress/
-                let progress_dir = agent_hand_base.join("progress");
-
                 // Runtime dir: ~/.agent-hand/profiles/{profile}/agent-runtime/
-                let runtime_dir = agent_hand_base.join("agent-runtime");
+                let dirs = crate::agent::runner::ProfileDirs::for_profile(profile);
+                let (progress_dir, runtime_dir) =
+                    (dirs.progress_dir.clone(), dirs.runtime_dir.clone());
+                agent_dirs = Arc::new(std::sync::RwLock::new(dirs));
 
                 // Build SystemRunner with registered Systems
                 let mut runner = crate::agent::runner::SystemRunner::new();
@@ -441,10 +571,12 @@ impl App {
                 ));
                 runner.register(crate::agent::systems::token_burst::TokenBurstSystem::new());
                 runner.register(crate::agent::systems::progress::ProgressSystem);
-                runner.register(crate::agent::systems::context::ContextGuardSystem::new(
+                let mut context_guard = crate::agent::systems::context::ContextGuardSystem::new(
                     config.context_bridge().clone(),
                     runtime_dir.clone(),
-                ));
+                );
+                context_guard.set_profile_dirs(Arc::clone(&agent_dirs));
+                runner.register(context_guard);
                 runner.register(crate::agent::systems::chat::ChatSystem::new());
                 runner.register(crate::agent::systems::tool_activity::ToolActivitySystem::new());
 
@@ -466,6 +598,8 @@ impl App {
                 let (chat_tx, chat_rx) = mpsc::unbounded_channel::<crate::chat::ChatResponsePayload>();
                 executor.set_chat_response_tx(chat_tx);
                 chat_response_rx = chat_rx;
+                executor.set_bulk_operations(bulk_operations.clone());
+                executor.set_profile_dirs(Arc::clone(&agent_dirs));
 
                 tokio::spawn(runner.run(system_rx, action_tx));
                 tokio::spawn(executor.run(action_rx));
@@ -550,6 +684,10 @@ impl App {
             ws_server,
         );
 
+        let ui_state = crate::ui::dialog_frame::UiState::load();
+        let capabilities =
+            crate::ui::guard::Capabilities::new(ui_state.ui_mode.unwrap_or(config.ui_mode()));
+
         let initial_state = if config.animations_enabled() {
             AppState::Startup
         } else {
@@ -567,12 +705,15 @@ impl App {
             selected_relationship_index: 0,
             relationship_snapshot_counts: HashMap::new(),
             tree: Vec::new(),
+            group_counts: HashMap::new(),
+            fork_rows: HashMap::new(),
             selected_index: 0,
             active_panel_focused: false,
             active_panel_selected: 0,
             help_visible: false,
             preview: String::new(),
-            preview_cache: HashMap::new(),
+            preview_history,
+            preview_view: Default::default(),
             canvas_state: {
                 #[cfg(feature = "pro")]
                 { initial_canvas_state }
@@ -593,6 +734,7 @@ impl App {
                 .join("profiles")
                 .join(profile)
                 .join("agent-runtime"),
+            agent_dirs,
             language: config.language.as_ref()
                 .map(|s| crate::i18n::Language::from_str(s))
                 .unwrap_or_default(),
@@ -600,19 +742,41 @@ impl App {
             search_query: String::new(),
             search_results: Vec::new(),
             search_selected: 0,
+            search_offset: 0,
             dialog: None,
             pending_attach: None,
+            pending_ops: super::CancelRegistry::default(),
             last_attach_source: None,
             keybindings,
             last_navigation_time: Instant::now(),
             is_navigating: false,
             pending_preview_id: None,
+            marked: HashSet::new(),
+            pinned_ids: Vec::new(),
+            pending_forward: None,
             last_status_refresh: Instant::now(),
             last_cache_refresh: Instant::now(),
+            last_live_preview: Instant::now(),
+            preview_shows_diff: false,
+            status_timing: config.status_timing(),
+            desktop_notifier: crate::notification::desktop::DesktopNotifier::new(
+                config.notification(),
+            ),
+            last_env_check: Instant::now(),
+            missing_paths: HashSet::new(),
+            last_reap: None,
+            pending_sort_changes: SortChanges::default(),
+            last_resort: Instant::now(),
+            perf: crate::ui::perf::Perf::new(config.perf_thresholds(), Instant::now()),
             previous_statuses: HashMap::new(),
+            stale_statuses,
+            tui_heartbeat: crate::session::heartbeat::HeartbeatWriter::new(profile_dir),
+            bulk_operations,
             last_tmux_activity: HashMap::new(),
             last_tmux_activity_change: HashMap::new(),
             last_status_probe: HashMap::new(),
+            pane_stalls: HashMap::new(),
+            attach_history: Default::default(),
             last_seen_detach_at: None,
             force_probe_tmux: None,
             event_receiver: crate::hooks::EventReceiver::new().ok(),
@@ -621,7 +785,11 @@ impl App {
             hook_broadcast_tx: hook_broadcast_tx.clone(),
             pending_hook_events: Vec::new(),
             info_bar_message: None,
+            info_bar_queue: VecDeque::new(),
+            message_log: Default::default(),
             info_bar_rx: None,
+            startup_sweep_rx: None,
+            wrapup_rx: None,
             tick_count: 0,
             attention_ttl,
             transition_engine: crate::ui::transition::TransitionEngine::new(
@@ -630,6 +798,8 @@ impl App {
             startup_phase: crate::ui::transition::StartupPhase::Logo,
             startup_started_at: None,
             activity: activity::ActivityTracker::default(),
+            undo: undo::UndoStack::default(),
+            profile: profile.to_string(),
             storage: Arc::new(Mutex::new(storage)),
             tmux: Arc::new(tmux),
             analytics,
@@ -640,12 +810,25 @@ impl App {
             cached_ptmx_max: system_ptmx_max,
             scan_state,
             _scan_task: scan_task,
+            discovery_state,
+            _discovery_task: discovery_task,
+            discovery_ignore_path,
+            discovery_ignored,
+            unmanaged_agents: Vec::new(),
+            repo_ids: HashMap::new(),
+            repo_ids_rx: None,
+            repo_badges: HashMap::new(),
+            ui_state,
+            capabilities,
+            dismissed_agent_pids: HashSet::new(),
             auth_token: crate::auth::AuthToken::load(),
             heartbeat_rx: None,
             last_heartbeat: Instant::now(),
             list_state: ratatui::widgets::ListState::default(),
             scroll_padding: config.scroll_padding(),
             mouse_captured: resolve_mouse_capture(&config),
+            hit_areas: Default::default(),
+            clicks: Default::default(),
             mouse_capture_changed: false,
             attached_session,
             sound_config,
@@ -668,14 +851,32 @@ impl App {
         app.ensure_groups_exist();
         app.rebuild_tree();
         app.rebuild_sessions_index();
-
-        // Prime tmux cache/status so initial render isn't stale
-        app.tmux.ensure_server().await;
-        let _ = app.tmux.refresh_cache().await;
-        app.last_cache_refresh = Instant::now();
-        let _ = app.refresh_statuses().await;
-        app.last_status_refresh = Instant::now();
-        let _ = app.update_preview().await;
+        app.refresh_missing_paths();
+
+        // Statuses and the preview are filled in once the event loop runs
+        // (see startup.rs), so nothing here captures a pane.
+        let autostart_report = app.autostart_sessions().await;
+        // Startup reports take turns in the info bar, the recovery first.
+        if let Some(generation) = recovered_from {
+            app.queue_info_bar(
+                format!(
+                    "sessions.json was unreadable — restored from backup {generation} \
+                     (see `agent-hand profile restore {profile} --list`)"
+                ),
+                ratatui::style::Color::Red,
+            );
+        }
+        if !tmux_unsupported.is_empty() {
+            let list: Vec<String> = tmux_unsupported.iter().map(|u| u.to_string()).collect();
+            app.queue_info_bar(list.join("; "), ratatui::style::Color::Yellow);
+        } else if !startup_summary.is_empty() {
+            let messages: Vec<String> = startup_summary.iter().map(|s| s.message()).collect();
+            app.queue_info_bar(messages.join("; "), ratatui::style::Color::DarkGray);
+        }
+        app.report_keybinding_conflicts();
+        if let Some((report, color)) = autostart_report {
+            app.queue_info_bar(report, color);
+        }
 
         // Check for orphaned relay rooms from a previous session
         #[cfg(feature = "pro")]
@@ -725,8 +926,10 @@ impl App {
 
     /// Run the TUI application
     pub async fn run(&mut self) -> Result<()> {
-        // Setup terminal
+        // Setup terminal; the guard puts it back on every way out,
+        // panics included.
         enable_raw_mode()?;
+        let guard = crate::ui::term::TermGuard::new();
         let mut stdout = io::stdout();
         if self.mouse_captured {
             execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
@@ -741,19 +944,7 @@ impl App {
         // Run event loop
         let result = self.event_loop(&mut terminal).await;
 
-        // Restore terminal
-        disable_raw_mode()?;
-        if self.mouse_captured {
-            execute!(
-                terminal.backend_mut(),
-                LeaveAlternateScreen,
-                DisableMouseCapture
-            )?;
-        } else {
-            execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-        }
-        terminal.show_cursor()?;
-
+        drop(guard);
         result
     }
 
@@ -769,6 +960,8 @@ impl App {
 
         // Initial preview/status
         self.on_navigation();
+        self.start_startup_sweep();
+        self.seed_attach_history().await;
 
         // Show orphaned rooms dialog if any were detected at startup
         #[cfg(feature = "pro")]
@@ -794,12 +987,14 @@ impl App {
             }
 
             // Draw UI
+            self.perf.begin_frame(Instant::now());
+            let draw_started = self.perf.start();
             terminal.draw(|f| {
                 self.width = f.area().width;
                 self.height = f.area().height;
                 let area = f.area();
 
-                super::render::draw(f, self);
+                self.hit_areas = super::render::draw(f, self);
 
                 // Canvas animation effects (Pro: tachyonfx post-processing)
                 #[cfg(feature = "pro")]
@@ -826,13 +1021,23 @@ impl App {
                     self.transition_engine.apply_frame(f.buffer_mut());
                 }
             })?;
+            if crate::ui::term::take_bell() {
+                use std::io::Write;
+                let backend = terminal.backend_mut();
+                backend.write_all(b"\x07")?;
+                backend.flush()?;
+            }
+            self.perf.finish(Phase::Draw, draw_started);
 
             // Multiplex: terminal events, canvas socket ops, tick timer
             tokio::select! {
                 maybe_event = event_stream.next() => {
                     match maybe_event {
                         Some(Ok(CrosstermEvent::Key(key))) => {
-                            self.handle_key(key.code, key.modifiers).await?;
+                            let started = self.perf.start();
+                            let result = self.handle_key(key.code, key.modifiers).await;
+                            self.absorb_error(result).await?;
+                            self.perf.finish(Phase::Event, started);
                         }
                         Some(Ok(CrosstermEvent::Resize(_, _))) => {
                             // Cancel any in-progress animation on resize
@@ -841,7 +1046,8 @@ impl App {
                         }
                         Some(Ok(CrosstermEvent::Mouse(mouse))) => {
                             if self.mouse_captured {
-                                self.handle_mouse_event(mouse);
+                                let result = self.handle_mouse_event(mouse).await;
+                                self.absorb_error(result).await?;
                             }
                         }
                         Some(Err(_)) | None => {
@@ -880,23 +1086,32 @@ impl App {
                     self.pending_hook_events.push(event);
                 }
                 _ = tokio::time::sleep_until(next_tick) => {
-                    self.tick().await?;
+                    let result = self.tick().await;
+                    self.absorb_error(result).await?;
                     next_tick = tokio::time::Instant::now() + self.tick_rate();
                 }
             }
 
             if let Some(name) = self.pending_attach.take() {
-                // Record analytics: session enter
-                if let Some(session) = self.find_session_by_tmux_name(&name) {
-                    let _ = self
-                        .analytics
-                        .record_enter(&session.id, &session.title)
-                        .await;
+                // Persisted before attaching so the reload afterwards sees it.
+                let _ = self.mark_attached(&name).await;
+                if let Some(s) = self.find_session_by_tmux_name(&name) {
+                    crate::session::launch::send_post_attach(&self.tmux, &s).await;
+                }
+                let attached = self.perform_attach(terminal, &name).await;
+                let is_compare = TmuxManager::is_compare_session(&name);
+                if is_compare {
+                    // Only the view goes; the compared sessions keep running.
+                    let _ = self.tmux.kill_session(&name).await;
                 }
-
-                self.perform_attach(terminal, &name).await?;
                 let _ = self.cache_preview_by_tmux_name(&name).await;
-                self.refresh_sessions().await?;
+                let result = self.refresh_sessions().await;
+                self.absorb_error(result).await?;
+                if is_compare {
+                    self.absorb_error(attached).await?;
+                } else {
+                    self.finish_attach(&name, attached).await?;
+                }
 
                 // Pro: restore panel focus based on where the user attached from
                 #[cfg(feature = "pro")]
@@ -928,6 +1143,8 @@ impl App {
             }
 
             if self.should_quit {
+                self.pending_ops.cancel_all();
+                self.tui_heartbeat.clear();
                 // Auto-save canvas state for current group before exit (Pro only)
                 #[cfg(feature = "pro")]
                 if let Some(ref dir) = self.pro.canvas_dir {
@@ -953,6 +1170,26 @@ impl App {
         // Advance transition engine
         self.transition_engine.tick();
 
+        self.tui_heartbeat.tick(Instant::now(), chrono::Utc::now());
+        if self.tick_count % 4 == 0 {
+            self.apply_tombstones();
+        }
+
+        // Pick up sessions.json changes saved by the CLI or another TUI.
+        if self.tick_count % 4 == 0 && self.state == AppState::Normal {
+            let changed = self.storage.lock().await.changed_on_disk();
+            if changed {
+                self.refresh_sessions().await?;
+            }
+        }
+
+        if self.pending_forward.is_some() && self.tick_count % 4 == 0 {
+            self.deliver_pending_forward().await;
+        }
+
+        self.poll_startup_sweep();
+        self.poll_wrapup().await;
+
         // Startup logo phase progression
         if self.state == AppState::Startup {
             // Initialize startup timer on first tick
@@ -978,6 +1215,25 @@ impl App {
         // Auto-expire stuck activity operations (safety net)
         self.activity.auto_expire();
 
+        if self.tick_count % 20 == 0
+            && self
+                .capabilities
+                .is_restricted(crate::ui::guard::Restriction::NoTmux)
+        {
+            let available = TmuxManager::is_available().await.unwrap_or(false);
+            self.capabilities.set_tmux_available(available);
+        }
+
+        // After the startup sweep, so the first pass sees probed statuses.
+        if self.config.reaper.enabled
+            && self.startup_sweep_rx.is_none()
+            && self
+                .last_reap
+                .is_none_or(|at| at.elapsed() >= reaper::REAP_INTERVAL)
+        {
+            self.reap_idle_sessions().await;
+        }
+
         if self.is_navigating && self.last_navigation_time.elapsed() > Self::NAVIGATION_SETTLE {
             self.is_navigating = false;
 
@@ -999,7 +1255,9 @@ impl App {
         }
         if let Some(ref mut rx) = self.info_bar_rx {
             if let Ok(result) = rx.try_recv() {
-                self.info_bar_message = result.map(|(msg, color)| (msg, color, Instant::now()));
+                if let Some((msg, color)) = result {
+                    self.queue_info_bar(msg, color);
+                }
                 self.info_bar_rx = None;
             }
         }
@@ -1149,10 +1407,13 @@ impl App {
                 self.pro.toast_notifications.drain(0..self.pro.toast_notifications.len() - 10);
             }
         }
-        // Auto-expire info bar messages after 8 seconds
+        // Auto-expire info bar messages after 8 seconds; the next queued one follows
         if let Some((_, _, created_at)) = &self.info_bar_message {
             if created_at.elapsed() > Duration::from_secs(8) {
-                self.info_bar_message = None;
+                self.info_bar_message = self
+                    .info_bar_queue
+                    .pop_front()
+                    .map(|(msg, color)| (msg, color, Instant::now()));
             }
         }
 
@@ -1200,16 +1461,30 @@ impl App {
             return self.update_preview().await;
         }
 
-        if !self.is_navigating {
-            if self.last_cache_refresh.elapsed() >= Self::CACHE_REFRESH {
+        // Until the startup sweep reports, its probes stand in for polling.
+        if !self.is_navigating && self.startup_sweep_rx.is_none() {
+            if self.last_cache_refresh.elapsed() >= self.status_timing.cache_refresh {
+                let started = self.perf.start();
                 self.tmux.refresh_cache().await?;
+                self.perf.finish(Phase::Cache, started);
                 self.last_cache_refresh = Instant::now();
+                self.refresh_missing_paths();
+
+                // Piggyback on the fresh cache to drop markers naming dead sessions.
+                if self.last_env_check.elapsed() >= Self::ENV_CHECK {
+                    let tmux = self.tmux.clone();
+                    let _ = crate::tmux::env::clear_stale_markers(tmux.as_ref(), |name| {
+                        tmux.session_exists(name)
+                    })
+                    .await;
+                    self.last_env_check = Instant::now();
+                }
             }
 
-            if self.last_status_refresh.elapsed() >= Self::STATUS_REFRESH {
+            if self.last_status_refresh.elapsed() >= self.status_timing.refresh {
                 if let Ok(Some(detach_at)) = self
                     .tmux
-                    .get_environment_global("AGENTHAND_LAST_DETACH_AT")
+                    .get_environment_global(crate::tmux::env::LAST_DETACH_AT)
                     .await
                 {
                     if self.last_seen_detach_at.as_deref() != Some(detach_at.as_str()) {
@@ -1217,28 +1492,41 @@ impl App {
                         // Use cached session name (written by Ctrl+Q binding).
                         if let Ok(Some(name)) = self
                             .tmux
-                            .get_environment_global("AGENTHAND_LAST_SESSION")
+                            .get_environment_global(crate::tmux::env::LAST_SESSION)
                             .await
                         {
-                            self.force_probe_tmux = Some(name.clone());
-
-                            // Record analytics: session exit (Ctrl+Q detach)
-                            if let Some(session) = self.find_session_by_tmux_name(&name) {
-                                let _ = self
-                                    .analytics
-                                    .record_exit(&session.id, &session.title)
-                                    .await;
-                            }
+                            self.record_visit(&name);
+                            self.force_probe_tmux = Some(name);
                         }
 
                     }
                 }
 
+                let before: Vec<Status> = self.sessions.iter().map(|s| s.status).collect();
+                let started = self.perf.start();
                 self.refresh_statuses().await?;
+                self.perf.finish(Phase::Status, started);
+                self.group_counts = crate::ui::tree::group_counts(&self.sessions);
                 self.last_status_refresh = Instant::now();
+                if self.sessions.iter().map(|s| s.status).ne(before) {
+                    self.pending_sort_changes.status = true;
+                }
             }
         }
 
+        let resort = should_resort(&ResortCheck {
+            sort: self.config.tree_sort(),
+            changes: self.pending_sort_changes,
+            since_input: self.last_navigation_time.elapsed(),
+            since_resort: self.last_resort.elapsed(),
+            min_interval: self.status_timing.refresh,
+            dialog_open: self.dialog.is_some(),
+        });
+        if resort {
+            // Selection follows the session, not the row.
+            self.rebuild_tree();
+        }
+
         // Poll AI summary results (non-blocking)
         #[cfg(feature = "pro")]
         if let Some(ref mut summarizer) = self.max.summarizer {
@@ -1315,7 +1603,7 @@ impl App {
         }
 
         // Update PTY counts from background task (non-blocking)
-        // The background task scans every 30 minutes, we just read the cached state
+        // The background task scans every `[ptmx] interval_minutes`, we just read the cached state
         {
             let state = self.ptmx_state.read().await;
             for session in &mut self.sessions {
@@ -1326,6 +1614,16 @@ impl App {
             self.cached_ptmx_max = state.system_max;
         }
 
+        // Shared-repo detection: resolve new project paths in the background
+        if self.tick_count % 8 == 4 {
+            self.poll_repo_ids();
+        }
+
+        // Unmanaged agent discovery: pick up background results every ~40 ticks (~10s)
+        if self.tick_count % 40 == 20 {
+            self.poll_unmanaged_agents().await;
+        }
+
         // Session ID scanner: consume results and write new targets every ~20 ticks (~5s)
         if self.tick_count % 20 == 10 {
             // Read scan results and apply to sessions
@@ -1365,8 +1663,13 @@ impl App {
             }
 
             if changed {
-                let storage = self.storage.lock().await;
-                let _ = storage.save(&self.sessions, &self.groups, &self.relationships).await;
+                let saved = {
+                    let storage = self.storage.lock().await;
+                    storage.save(&self.sessions, &self.groups, &self.relationships).await
+                };
+                if let Err(e) = saved {
+                    self.notify_save_error(&e);
+                }
             }
 
             // Write new targets for next scan:
@@ -1397,6 +1700,8 @@ impl App {
             self.update_preview().await?;
         }
 
+        self.refresh_live_preview().await?;
+
         // WebSocket: drain pending requests and broadcast changes (Max tier)
         #[cfg(feature = "pro")]
         {
@@ -1417,6 +1722,8 @@ impl App {
 
         // Collect session IDs that transition from Running to Idle/Waiting for auto-capture
         let mut running_to_done: Vec<String> = Vec::new();
+        // ...and the Running → Waiting subset, for desktop notifications
+        let mut running_to_waiting: Vec<String> = Vec::new();
 
         // --- Phase 1: Process hook events (event-driven, precise) ---
         // Track which sessions were updated by hooks and what status they got.
@@ -1447,7 +1754,6 @@ impl App {
                     continue;
                 };
 
-                let prev_status = session.status;
                 let now_utc = chrono::Utc::now();
 
                 use crate::hooks::HookEventKind;
@@ -1472,6 +1778,7 @@ impl App {
                         continue;
                     }
                 };
+                let prev_status = self.stale_statuses.confirm(&session.id, session.status);
 
                 // Record timestamps
                 if new_status == Status::Running
@@ -1490,6 +1797,9 @@ impl App {
                 {
                     running_to_done.push(session.id.clone());
                 }
+                if tracked_prev == Some(Status::Running) && new_status == Status::Waiting {
+                    running_to_waiting.push(session.id.clone());
+                }
 
                 // Capture CLI session_id if present in the hook event
                 session.set_cli_session_id(&event.session_id, now_utc);
@@ -1518,7 +1828,10 @@ impl App {
 
                 self.previous_statuses
                     .insert(session.id.clone(), new_status);
-                session.status = new_status;
+                session.set_status(new_status, now_utc);
+                if new_status != Status::Running {
+                    self.pane_stalls.remove(&session.id);
+                }
                 hook_updated.insert(session.id.clone(), new_status);
             }
         }
@@ -1568,15 +1881,24 @@ impl App {
                 // Hook reports active state → trust it, skip polling
                 Some(Status::Running | Status::Waiting | Status::Starting) => continue,
                 // Hook reports Idle → allow polling to verify (may detect activity)
-                Some(Status::Idle | Status::Error) | None => {}
+                Some(Status::Idle | Status::Error | Status::Stopped) | None => {}
             }
 
             let tmux_session = session.tmux_name();
             if !self.tmux.session_exists(&tmux_session).unwrap_or(false) {
-                session.status = Status::Idle;
+                let prev = self.stale_statuses.confirm(&session.id, session.status);
+                // Only stopped sessions are checked: a running one keeps working.
+                // Vanishing mid-run is a crash rather than a stop.
+                let exited = if self.missing_paths.contains(&session.id) {
+                    Status::Error
+                } else {
+                    prev.after_exit()
+                };
+                session.set_status(exited, chrono::Utc::now());
                 self.last_tmux_activity.remove(&session.id);
                 self.last_tmux_activity_change.remove(&session.id);
                 self.last_status_probe.remove(&session.id);
+                self.pane_stalls.remove(&session.id);
                 continue;
             }
 
@@ -1584,6 +1906,10 @@ impl App {
             let prev_activity = self.last_tmux_activity.get(&session.id).copied();
 
             let activity_changed = prev_activity.is_some_and(|a| activity > a);
+            let since_change = self
+                .last_tmux_activity_change
+                .get(&session.id)
+                .map(|t| now.duration_since(*t));
             if activity_changed || prev_activity.is_none() {
                 self.last_tmux_activity.insert(session.id.clone(), activity);
                 if activity_changed {
@@ -1592,44 +1918,52 @@ impl App {
                 }
             }
 
-            let need_fallback_probe = self
-                .last_status_probe
-                .get(&session.id)
-                .is_none_or(|t| now.duration_since(*t) >= Self::STATUS_FALLBACK);
-
-            let activity_settled = self
-                .last_tmux_activity_change
-                .get(&session.id)
-                .is_some_and(|t| now.duration_since(*t) >= Self::STATUS_COOLDOWN);
+            let plan = crate::session::status_engine::plan_probe(
+                &crate::session::status_engine::ProbeCheck {
+                    prev: session.status,
+                    activity_changed,
+                    since_change,
+                    since_probe: self
+                        .last_status_probe
+                        .get(&session.id)
+                        .map(|t| now.duration_since(*t)),
+                },
+                &self.status_timing,
+            );
 
+            let activity_settled = since_change
+                .is_some_and(|d| !activity_changed && d >= self.status_timing.cooldown);
             let is_selected = selected_id.as_deref() == Some(session.id.as_str());
 
             let force_probe = self.force_probe_tmux.as_deref() == Some(tmux_session.as_str());
-            let should_probe = force_probe
-                || need_fallback_probe
-                || (is_selected && activity_settled)
-                || activity_changed
-                || prev_activity.is_none();
-
-            if !should_probe {
-                continue;
-            }
-
-            let content = self
-                .tmux
-                .capture_pane(&tmux_session, 35)
-                .await
-                .unwrap_or_default();
-            let detector = crate::tmux::PromptDetector::new(session.tool);
-            let new_status = if detector.has_prompt(&content) {
-                Status::Waiting
-            } else if detector.is_busy(&content) {
-                Status::Running
+            let should_probe = force_probe || plan.probe || (is_selected && activity_settled);
+
+            let mut captured = None;
+            let new_status = if should_probe {
+                let probe = crate::session::status_engine::probe_pane(
+                    &self.tmux,
+                    &session.tmux_target(),
+                    session.tool,
+                    session.status,
+                    self.status_timing.capture_lines,
+                )
+                .await;
+                self.last_status_probe.insert(session.id.clone(), now);
+                if force_probe {
+                    self.force_probe_tmux = None;
+                }
+                if probe.status == Status::Waiting {
+                    session.waiting_reason = probe.waiting_reason;
+                }
+                captured = Some(probe.content);
+                probe.status
+            } else if plan.interim != session.status {
+                plan.interim
             } else {
-                Status::Idle
+                continue;
             };
 
-            let prev_status = session.status;
+            let prev_status = self.stale_statuses.confirm(&session.id, session.status);
             let now_utc = chrono::Utc::now();
 
             if new_status == Status::Running
@@ -1648,13 +1982,45 @@ impl App {
             {
                 running_to_done.push(session.id.clone());
             }
+            if tracked_prev == Some(Status::Running) && new_status == Status::Waiting {
+                running_to_waiting.push(session.id.clone());
+            }
 
             self.previous_statuses.insert(session.id.clone(), new_status);
 
-            session.status = new_status;
-            self.last_status_probe.insert(session.id.clone(), now);
-            if force_probe {
-                self.force_probe_tmux = None;
+            session.set_status(new_status, now_utc);
+
+            // Only captures move the stall clock; an interim Running keeps it.
+            let stall = self.pane_stalls.remove(&session.id);
+            let stall = match &captured {
+                Some(content) => {
+                    crate::session::stall::PaneStall::observe(stall, session, content, now_utc)
+                }
+                None => stall.filter(|_| new_status == Status::Running),
+            };
+            if let Some(stall) = stall {
+                self.pane_stalls.insert(session.id.clone(), stall);
+            }
+        }
+        // Hooks and exits change statuses too; drop reasons that no longer apply.
+        for session in &mut self.sessions {
+            if session.status != Status::Waiting {
+                session.waiting_reason = None;
+            }
+        }
+
+        if !running_to_waiting.is_empty() && self.config.notification().desktop {
+            let attached = self
+                .tmux
+                .get_environment_global(crate::tmux::env::LAST_SESSION)
+                .await
+                .ok()
+                .flatten();
+            for id in &running_to_waiting {
+                if let Some(inst) = self.sessions.iter().find(|s| &s.id == id) {
+                    self.desktop_notifier
+                        .notify_waiting(inst, attached.as_deref());
+                }
             }
         }
 
@@ -1736,19 +2102,61 @@ impl App {
     }
 
     async fn cache_preview_for_id(&mut self, id: &str) -> Result<()> {
-        let tmux_session = self.tmux_name_for_id(id);
-        if !self.tmux.session_exists(&tmux_session).unwrap_or(false) {
-            self.preview_cache.remove(id);
-            return Ok(());
+        if let Some(content) = self.capture_preview(id).await {
+            self.preview_history.record(id, content, chrono::Utc::now());
+        }
+        Ok(())
+    }
+
+    /// Capture a session's agent pane; None if it has no live tmux session
+    /// or the capture came back empty.
+    async fn capture_preview(&mut self, id: &str) -> Option<String> {
+        let session = self.session_by_id(id)?;
+        let target = session.tmux_target();
+        if !self.tmux.session_exists(&session.tmux_name()).unwrap_or(false) {
+            return None;
         }
 
+        let lines = self.main_layout().capture_lines(self.height);
+        let started = self.perf.start();
         let content = self
             .tmux
-            .capture_pane(&tmux_session, 120)
+            .capture_pane(&target, lines)
             .await
             .unwrap_or_default();
-        if !content.is_empty() {
-            self.preview_cache.insert(id.to_string(), content);
+        self.perf.finish(Phase::Preview, started);
+        (!content.is_empty()).then_some(content)
+    }
+
+    /// Keep the preview live: re-capture the selected session every
+    /// `preview.auto_refresh_secs` while it is running or waiting. Waits
+    /// for navigation to settle and pauses while a dialog is open or the
+    /// preview is hidden.
+    async fn refresh_live_preview(&mut self) -> Result<()> {
+        let Some(interval) = self.config.preview_auto_refresh() else {
+            return Ok(());
+        };
+        if self.is_navigating
+            || self.pending_preview_id.is_some()
+            || self.dialog.is_some()
+            || self.preview_view.focused
+            || self.preview_shows_diff
+            || !self.main_layout().shows_preview()
+            || self.last_live_preview.elapsed() < interval
+        {
+            return Ok(());
+        }
+        let Some(id) = self
+            .selected_session()
+            .filter(|s| matches!(s.status, Status::Running | Status::Waiting))
+            .map(|s| s.id.clone())
+        else {
+            return Ok(());
+        };
+        self.last_live_preview = Instant::now();
+        if let Some(content) = self.capture_preview(&id).await {
+            self.preview_history.record_live(&id, content, chrono::Utc::now());
+            self.update_preview().await?;
         }
         Ok(())
     }
@@ -1759,10 +2167,14 @@ impl App {
         };
 
         self.cache_preview_for_id(&id).await?;
+        if let Some(history) = self.preview_history.get_mut(&id) {
+            history.show_newest();
+        }
         self.update_preview().await
     }
 
     async fn update_preview(&mut self) -> Result<()> {
+        self.preview_shows_diff = false;
         if let Some(session) = self.selected_session() {
             #[cfg(feature = "pro")]
             let session_id = session.id.clone();
@@ -1770,7 +2182,7 @@ impl App {
 
             // Build resume hint if a CLI session ID has been captured
             let resume_hint = if let Some(sid) = session.cli_session_id() {
-                format!("\nSession ID: {}\n  u = resume previous conversation\n  R = rebuild pane + resume", sid)
+                format!("\nSession ID: {}\n  o = resume previous conversation\n  R = rebuild pane + resume", sid)
             } else if session.tool == crate::tmux::Tool::Shell {
                 "\n  (tool not detected yet — interact with the session first)".to_string()
             } else {
@@ -1778,15 +2190,19 @@ impl App {
             };
 
             if self.tmux.session_exists(&tmux_session).unwrap_or(false) {
-                if let Some(cached) = self.preview_cache.get(&session.id) {
-                    self.preview = cached.clone();
+                if let Some(cached) = self.preview_history.get(&session.id).and_then(|h| h.current()) {
+                    self.preview = cached.content.clone();
                 } else {
                     let ptmx_line = if session.ptmx_count > 0 {
                         format!("PTY FDs: {}\n", session.ptmx_count)
                     } else {
                         String::new()
                     };
-                    let status_str = format!("{:?}", session.status);
+                    let status_str = if self.stale_statuses.is_stale(&session.id) {
+                        format!("{:?} (stale)", session.status)
+                    } else {
+                        format!("{:?}", session.status)
+                    };
                     self.preview = format!(
                         "{}\n\nStatus: {}\nPath: {}\nLabel: {}\n{}{}",
                         session.title,
@@ -1803,14 +2219,18 @@ impl App {
                 } else {
                     String::new()
                 };
-                let action_hint = if session.cli_session_id().is_some() {
+                let path_missing = self.missing_paths.contains(&session.id);
+                let action_hint = if path_missing {
+                    "\n  Path missing — the directory was moved or deleted\n  s     = fix path or delete session"
+                } else if session.cli_session_id().is_some() {
                     "\n  Enter = start NEW conversation\n  s     = start session\n  a     = add to canvas"
                 } else {
                     "\n  Enter = start session\n  s     = start session\n  a     = add to canvas"
                 };
                 self.preview = format!(
-                    "{}\n\nStatus: Stopped\nPath: {}\nLabel: {}\n{}{}{}",
+                    "{}\n\nStatus: {}\nPath: {}\nLabel: {}\n{}{}{}",
                     session.title,
+                    if path_missing { "Path missing" } else { "Stopped" },
                     session.project_path.to_string_lossy(),
                     session.label,
                     ptmx_line,
@@ -1870,6 +2290,7 @@ impl App {
                 .iter()
                 .filter(|s| s.group_path == *path || s.group_path.starts_with(&prefix))
                 .count();
+            let notes = self.groups.get_group(path).map_or("", |g| g.notes.as_str());
 
             self.preview = format!(
                 "Group: {}\nPath: {}\nExpanded: {}\n\n{} sessions ({} direct)",
@@ -1879,6 +2300,16 @@ impl App {
                 total,
                 direct
             );
+            if !notes.is_empty() {
+                self.preview.push_str(&format!("\n\n─── Notes ───\n{}", notes));
+            } else if let Some(key) = self.keybindings.describe("group_notes") {
+                self.preview.push_str(&format!("\n\n  {} = add notes", key));
+            }
+            return Ok(());
+        }
+
+        if let Some(text) = self.unmanaged_preview() {
+            self.preview = text;
             return Ok(());
         }
 
@@ -1911,6 +2342,94 @@ impl App {
         &self.list_state
     }
 
+    pub fn dialog_nudge(&self, kind: &str) -> crate::ui::dialog_frame::SizeNudge {
+        self.ui_state.dialog_nudge(kind)
+    }
+
+    pub fn capabilities(&self) -> &crate::ui::guard::Capabilities {
+        &self.capabilities
+    }
+
+    pub fn is_simple_mode(&self) -> bool {
+        self.capabilities
+            .is_restricted(crate::ui::guard::Restriction::SimpleMode)
+    }
+
+    pub fn new_session_advanced(&self) -> bool {
+        !self.is_simple_mode() || self.ui_state.new_session_advanced
+    }
+
+    /// Switch between simple and full mode and remember the choice.
+    pub(super) fn toggle_ui_mode(&mut self) {
+        let mode = if self.is_simple_mode() {
+            crate::config::UiMode::Full
+        } else {
+            crate::config::UiMode::Simple
+        };
+        let no_tmux = self
+            .capabilities
+            .is_restricted(crate::ui::guard::Restriction::NoTmux);
+        self.capabilities = crate::ui::guard::Capabilities::new(mode);
+        self.capabilities.set_tmux_available(!no_tmux);
+        self.ui_state.ui_mode = Some(mode);
+        if let Err(e) = self.ui_state.save() {
+            tracing::warn!("failed to save UI state: {e}");
+        }
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        let msg = match (mode, is_zh) {
+            (crate::config::UiMode::Simple, false) => "Simple mode — F9 for all keys",
+            (crate::config::UiMode::Simple, true) => "简洁模式 — 按 F9 显示全部按键",
+            (crate::config::UiMode::Full, false) => "Full mode — F9 for simple mode",
+            (crate::config::UiMode::Full, true) => "完整模式 — 按 F9 切回简洁模式",
+        };
+        self.set_info_bar(msg.to_string(), ratatui::style::Color::Cyan);
+    }
+
+    pub fn main_layout(&self) -> MainLayout {
+        self.ui_state.main_layout
+    }
+
+    /// Switch to the next main-screen layout and remember it. Maximizing
+    /// the preview re-captures the selection with the deeper history.
+    pub(super) async fn cycle_layout(&mut self) -> Result<()> {
+        let layout = self.ui_state.main_layout.next();
+        self.ui_state.main_layout = layout;
+        if let Err(e) = self.ui_state.save() {
+            tracing::warn!("failed to save UI state: {e}");
+        }
+        if !layout.shows_preview() {
+            self.preview_view.reset();
+            self.canvas_focused = false;
+        }
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        let msg = match (layout, is_zh) {
+            (MainLayout::Split, false) => "Layout: list and preview",
+            (MainLayout::Split, true) => "布局：列表与预览",
+            (MainLayout::Preview, false) => "Layout: preview maximized",
+            (MainLayout::Preview, true) => "布局：预览最大化",
+            (MainLayout::List, false) => "Layout: list maximized",
+            (MainLayout::List, true) => "布局：列表最大化",
+        };
+        self.set_info_bar(msg.to_string(), ratatui::style::Color::Cyan);
+        if layout == MainLayout::Preview && self.preview_browse_label().is_none() {
+            self.refresh_preview_cache_selected().await?;
+        }
+        Ok(())
+    }
+
+    /// Whether `action` may run in the current mode; toasts why not if it
+    /// may not.
+    pub(super) fn guard(&mut self, action: &'static str) -> bool {
+        match self.capabilities.check(action) {
+            Ok(()) => true,
+            Err(denied) => {
+                let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+                self.set_info_bar(denied.message(is_zh), ratatui::style::Color::Yellow);
+                false
+            }
+        }
+    }
+
     pub fn session_by_id(&self, id: &str) -> Option<&Instance> {
         let &idx = self.sessions_by_id.get(id)?;
         self.sessions.get(idx)
@@ -1924,12 +2443,72 @@ impl App {
         self.groups.has_children(path)
     }
 
+    /// Statuses of the sessions in a group and its subgroups.
+    pub fn group_counts(&self, path: &str) -> crate::ui::tree::GroupCounts {
+        self.group_counts.get(path).copied().unwrap_or_default()
+    }
+
+    /// How long the session has been Running with an unchanged pane, once
+    /// that passes `[status] stall_secs` and until it is acknowledged.
+    pub fn stalled_for(&self, id: &str) -> Option<std::time::Duration> {
+        crate::session::stall::stalled_for(
+            self.session_by_id(id)?,
+            self.pane_stalls.get(id),
+            self.status_timing.stall,
+            chrono::Utc::now(),
+        )
+    }
+
+    /// Where a session's row sits among forks; default when it has none.
+    pub fn fork_row(&self, id: &str) -> crate::ui::tree::ForkRow {
+        self.fork_rows.get(id).copied().unwrap_or_default()
+    }
+
     pub fn help_visible(&self) -> bool {
         self.help_visible
     }
 
     pub fn preview(&self) -> &str {
-        &self.preview
+        self.preview_view.content.as_deref().unwrap_or(&self.preview)
+    }
+
+    pub fn preview_view(&self) -> &crate::ui::preview_view::PreviewView {
+        &self.preview_view
+    }
+
+    /// "snapshot 3/5 — 14:22, 18 min ago" while an older capture of the
+    /// selected session is on screen.
+    pub fn preview_browse_label(&self) -> Option<String> {
+        let history = self.preview_history.get(&self.selected_session()?.id)?;
+        let position = history.browsing()?;
+        Some(crate::ui::preview_history::browse_label(
+            position,
+            history.current()?.taken_at,
+            chrono::Utc::now(),
+            matches!(self.language(), crate::i18n::Language::Chinese),
+        ))
+    }
+
+    /// Show the selected session's previous (`older`) or next capture.
+    pub(super) async fn browse_preview(&mut self, older: bool) -> Result<()> {
+        let Some(id) = self.selected_session().map(|s| s.id.clone()) else {
+            return Ok(());
+        };
+        let moved = self.preview_history.get_mut(&id).is_some_and(|h| {
+            if older {
+                h.back()
+            } else {
+                h.forward()
+            }
+        });
+        if moved {
+            self.update_preview().await?;
+        } else if older {
+            let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+            let msg = if is_zh { "没有更早的快照" } else { "No older snapshot" };
+            self.set_info_bar(msg.to_string(), ratatui::style::Color::DarkGray);
+        }
+        Ok(())
     }
 
     /// Whether this session has a cached AI summary.
@@ -2634,6 +3213,20 @@ impl App {
         }
     }
 
+    pub fn group_notes_dialog(&self) -> Option<&GroupNotesDialog> {
+        match self.dialog.as_ref() {
+            Some(Dialog::GroupNotes(d)) => Some(d),
+            _ => None,
+        }
+    }
+
+    pub fn reparent_group_dialog(&self) -> Option<&ReparentGroupDialog> {
+        match self.dialog.as_ref() {
+            Some(Dialog::ReparentGroup(d)) => Some(d),
+            _ => None,
+        }
+    }
+
     pub fn rename_session_dialog(&self) -> Option<&RenameSessionDialog> {
         match self.dialog.as_ref() {
             Some(Dialog::RenameSession(d)) => Some(d),
@@ -2641,6 +3234,20 @@ impl App {
         }
     }
 
+    pub fn forward_output_dialog(&self) -> Option<&ForwardOutputDialog> {
+        match self.dialog.as_ref() {
+            Some(Dialog::ForwardOutput(d)) => Some(d),
+            _ => None,
+        }
+    }
+
+    pub fn repair_path_dialog(&self) -> Option<&RepairPathDialog> {
+        match self.dialog.as_ref() {
+            Some(Dialog::RepairPath(d)) => Some(d),
+            _ => None,
+        }
+    }
+
     pub fn tag_picker_dialog(&self) -> Option<&TagPickerDialog> {
         match self.dialog.as_ref() {
             Some(Dialog::TagPicker(d)) => Some(d),
@@ -2648,6 +3255,67 @@ impl App {
         }
     }
 
+    pub fn label_picker_dialog(&self) -> Option<&LabelPickerDialog> {
+        match self.dialog.as_ref() {
+            Some(Dialog::LabelPicker(d)) => Some(d),
+            _ => None,
+        }
+    }
+
+    pub fn lineage_dialog(&self) -> Option<&LineageDialog> {
+        match self.dialog.as_ref() {
+            Some(Dialog::Lineage(d)) => Some(d),
+            _ => None,
+        }
+    }
+
+    pub fn waiting_queue_dialog(&self) -> Option<&WaitingQueueDialog> {
+        match self.dialog.as_ref() {
+            Some(Dialog::WaitingQueue(d)) => Some(d),
+            _ => None,
+        }
+    }
+
+    pub fn wrapup_confirm_dialog(&self) -> Option<&WrapupConfirmDialog> {
+        match self.dialog.as_ref() {
+            Some(Dialog::WrapupConfirm(d)) => Some(d),
+            _ => None,
+        }
+    }
+
+    pub fn restart_exited_dialog(&self) -> Option<&RestartExitedDialog> {
+        match self.dialog.as_ref() {
+            Some(Dialog::RestartExited(d)) => Some(d),
+            _ => None,
+        }
+    }
+
+    /// The profile the TUI is showing.
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    pub fn message_log_dialog(&self) -> Option<&MessageLogDialog> {
+        match self.dialog.as_ref() {
+            Some(Dialog::MessageLog(d)) => Some(d),
+            _ => None,
+        }
+    }
+
+    pub fn profile_picker_dialog(&self) -> Option<&ProfilePickerDialog> {
+        match self.dialog.as_ref() {
+            Some(Dialog::ProfilePicker(d)) => Some(d),
+            _ => None,
+        }
+    }
+
+    pub fn rebind_dialog(&self) -> Option<&RebindDialog> {
+        match self.dialog.as_ref() {
+            Some(Dialog::Rebind(d)) => Some(d),
+            _ => None,
+        }
+    }
+
     pub fn settings_dialog(&self) -> Option<&SettingsDialog> {
         match self.dialog.as_ref() {
             Some(Dialog::Settings(d)) => Some(d),
@@ -2750,6 +3418,10 @@ impl App {
         }
     }
 
+    pub fn keybindings(&self) -> &crate::config::KeyBindings {
+        &self.keybindings
+    }
+
     pub fn language(&self) -> crate::i18n::Language {
         self.language
     }
@@ -2767,9 +3439,73 @@ impl App {
     }
 
     fn set_info_bar(&mut self, message: String, color: ratatui::style::Color) {
+        self.message_log
+            .push(messages::LoggedMessage::new(message.clone(), color));
         self.info_bar_message = Some((message, color, Instant::now()));
     }
 
+    /// Like [`set_info_bar`](Self::set_info_bar), but waits for the message
+    /// on screen to expire instead of replacing it. For reports nobody is
+    /// waiting on, such as those at startup.
+    fn queue_info_bar(&mut self, message: String, color: ratatui::style::Color) {
+        if self.info_bar_message.is_none() {
+            return self.set_info_bar(message, color);
+        }
+        self.message_log
+            .push(messages::LoggedMessage::new(message.clone(), color));
+        self.info_bar_queue.push_back((message, color));
+    }
+
+    /// Turn a failed tmux call into an info-bar error instead of leaving the
+    /// event loop, so a server killed from outside (`tmux kill-server`) does
+    /// not take the TUI down with it. A save that conflicts with another
+    /// process is reported the same way, then the sessions are reloaded from
+    /// disk. Other errors, I/O included, are passed through.
+    async fn absorb_error(&mut self, result: Result<()>) -> Result<()> {
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        match result {
+            Err(err @ crate::Error::Tmux(_)) => {
+                // A tmux that is gone altogether closes off the actions
+                // that need it; `tick` lifts that once it is back.
+                let available = TmuxManager::is_available().await.unwrap_or(false);
+                self.capabilities.set_tmux_available(available);
+                let msg = if is_zh {
+                    format!("tmux 出错: {err}（tmux 服务是否仍在运行？）")
+                } else {
+                    format!("tmux failed: {err} (is the tmux server still running?)")
+                };
+                self.notify_error(msg);
+                Ok(())
+            }
+            Err(err @ crate::Error::Conflict(_)) => {
+                let msg = if is_zh {
+                    format!("保存失败，已从磁盘重新加载: {err}")
+                } else {
+                    format!("Not saved, reloaded from disk: {err}")
+                };
+                self.notify_error(msg);
+                self.refresh_sessions().await
+            }
+            result => result,
+        }
+    }
+
+    /// Toast the keybinding conflicts resolved at load time, if any.
+    fn report_keybinding_conflicts(&mut self) {
+        let conflicts = self.keybindings.conflicts();
+        if conflicts.is_empty() {
+            return;
+        }
+        let list: Vec<String> = conflicts.iter().map(|c| c.describe()).collect();
+        self.queue_info_bar(
+            format!(
+                "Keybinding conflicts: {} (see `agent-hand config check`)",
+                list.join("; ")
+            ),
+            ratatui::style::Color::Yellow,
+        );
+    }
+
     pub fn dismiss_onboarding(&mut self) {
         self.show_onboarding = false;
     }
@@ -2790,6 +3526,47 @@ impl App {
         self.height
     }
 
+    /// The 1-based slot of a pinned session.
+    pub fn pin_slot(&self, session_id: &str) -> Option<usize> {
+        self.pinned_ids
+            .iter()
+            .position(|id| id == session_id)
+            .map(|i| i + 1)
+    }
+
+    /// Whether session rows have room for their `[label]` badge.
+    pub fn show_label_badges(&self) -> bool {
+        self.width >= self.config.label_min_width()
+    }
+
+    /// Right-aligned session row columns for a list `list_width` wide:
+    /// none when it is narrower than `[ui] columns_min_width`.
+    pub fn session_columns(&self, list_width: u16) -> Vec<crate::config::SessionColumn> {
+        if list_width < self.config.columns_min_width() {
+            return Vec::new();
+        }
+        self.config.session_columns()
+    }
+
+    /// Time since the session's tmux session last had activity, as of the
+    /// last cache refresh. None when it is not running.
+    pub fn last_activity_age(&self, session: &Instance) -> Option<chrono::Duration> {
+        let at = self
+            .tmux
+            .session_activity(&session.tmux_name())
+            .filter(|&at| at > 0)?;
+        Some(chrono::Duration::seconds(
+            chrono::Utc::now().timestamp().saturating_sub(at),
+        ))
+    }
+
+    /// The last minute of phase timings, while the F11 overlay is open.
+    pub fn perf_report(&self) -> Option<crate::ui::perf::PerfReport> {
+        self.perf
+            .overlay_open()
+            .then(|| self.perf.report(Instant::now()))
+    }
+
     pub fn is_attention_active(&self, id: &str) -> bool {
         // Ready (✓) if last_running_at is within ATTENTION_TTL
         self.session_by_id(id)
@@ -2834,6 +3611,24 @@ impl App {
         self.cached_ptmx_max
     }
 
+    /// Whether PTY usage is scanned (`[ptmx] enabled`).
+    pub fn ptmx_enabled(&self) -> bool {
+        self._ptmx_task.is_some()
+    }
+
+    /// The sessions holding the most PTYs, when usage is over `[ptmx]
+    /// warn_percent` of the system limit.
+    pub fn ptmx_warning(&self) -> Option<Vec<(&str, u32)>> {
+        let percent = self.config.ptmx_warn_percent();
+        if !crate::tmux::ptmx::over_limit(self.cached_ptmx_total, self.cached_ptmx_max, percent) {
+            return None;
+        }
+        Some(crate::tmux::ptmx::top_consumers(
+            self.sessions.iter().map(|s| (s.title.as_str(), s.ptmx_count)),
+            3,
+        ))
+    }
+
     pub fn auth_token(&self) -> Option<&crate::auth::AuthToken> {
         self.auth_token.as_ref()
     }
@@ -2861,7 +3656,7 @@ impl App {
         self.ordered_session_indices_by_group_baseline()
             .into_iter()
             .filter_map(|idx| {
-                let session = &self.sessions[idx];
+                let session = self.sessions.get(idx)?;
                 (!matches!(session.status, Status::Idle) || self.is_attention_active(&session.id))
                     .then_some(session)
             })
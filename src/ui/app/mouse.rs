@@ -0,0 +1,103 @@
+use super::*;
+
+use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+
+use crate::ui::mouse::DialogTarget;
+
+/// Preview lines one wheel notch scrolls.
+const WHEEL_LINES: isize = 3;
+
+impl App {
+    /// Mouse input, looked up in the areas the last frame drew: the wheel
+    /// moves the selection, or scrolls the preview when over it; a click
+    /// selects a tree row and a double click opens it; clicks work the
+    /// delete dialogs.
+    pub(super) async fn handle_mouse_event(&mut self, mouse: MouseEvent) -> Result<()> {
+        if self.help_visible {
+            return Ok(());
+        }
+
+        // ViewerMode: tmux handles scroll natively — ignore mouse events here
+        #[cfg(feature = "pro")]
+        if self.state == AppState::ViewerMode {
+            return Ok(());
+        }
+
+        let (col, row) = (mouse.column, mouse.row);
+        if self.state == AppState::Dialog {
+            if mouse.kind == MouseEventKind::Down(MouseButton::Left) {
+                if let Some(target) = self.hit_areas.dialog_target(col, row) {
+                    self.click_dialog(target).await?;
+                }
+            }
+            return Ok(());
+        }
+
+        match mouse.kind {
+            MouseEventKind::ScrollUp | MouseEventKind::ScrollDown => {
+                let up = mouse.kind == MouseEventKind::ScrollUp;
+                if self.hit_areas.over_preview(col, row) {
+                    self.scroll_preview(if up { -WHEEL_LINES } else { WHEEL_LINES });
+                    return Ok(());
+                }
+                if up {
+                    self.move_selection_up();
+                } else {
+                    self.move_selection_down();
+                }
+                self.on_navigation();
+                self.enforce_scrolloff();
+            }
+            MouseEventKind::Down(MouseButton::Left) => {
+                let Some(index) = self
+                    .hit_areas
+                    .tree_row(col, row)
+                    .filter(|&i| i < self.tree.len())
+                else {
+                    return Ok(());
+                };
+                let double = self.clicks.click(index, Instant::now());
+                if index != self.selected_index {
+                    self.selected_index = index;
+                    self.on_navigation();
+                    self.list_state.select(Some(index));
+                    self.enforce_scrolloff();
+                }
+                if double {
+                    self.activate_selected().await?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// A click on part of the open dialog. Clicking a delete-group choice
+    /// picks it; clicking the picked one again carries it out.
+    async fn click_dialog(&mut self, target: DialogTarget) -> Result<()> {
+        match (self.dialog.as_mut(), target) {
+            (Some(Dialog::DeleteConfirm(_)), DialogTarget::Confirm) => {
+                self.confirm_delete_sessions().await?;
+            }
+            (Some(Dialog::DeleteConfirm(d)), DialogTarget::ToggleKillTmux) => {
+                d.kill_tmux = !d.kill_tmux;
+            }
+            (Some(Dialog::DeleteConfirm(d)), DialogTarget::ToggleRemoveWorktree) => {
+                d.remove_worktree = !d.remove_worktree;
+            }
+            (Some(Dialog::DeleteGroup(d)), DialogTarget::GroupChoice(choice)) => {
+                if d.choice == choice {
+                    self.confirm_delete_group().await?;
+                } else {
+                    d.choice = choice;
+                }
+            }
+            (Some(_), DialogTarget::Cancel) => {
+                self.dialog = None;
+                self.state = AppState::Normal;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
@@ -6,6 +6,8 @@ impl App {
         self.last_navigation_time = Instant::now();
         self.is_navigating = true;
         self.pending_preview_id = self.selected_session().map(|s| s.id.clone());
+        self.preview_view.reset();
+        self.preview_shows_diff = false;
     }
 
     pub(super) async fn focus_session(&mut self, id: &str) -> Result<()> {
@@ -34,6 +36,7 @@ impl App {
             _ => false,
         }) {
             self.selected_index = idx;
+            self.enforce_scrolloff();
             self.preview.clear();
             self.update_preview().await?;
         }
@@ -49,6 +52,7 @@ impl App {
             _ => false,
         }) {
             self.selected_index = idx;
+            self.enforce_scrolloff();
             self.preview.clear();
             self.update_preview().await?;
         }
@@ -75,155 +79,73 @@ impl App {
         }
     }
 
-    /// Handle mouse events (scroll, click).
-    pub(super) fn handle_mouse_event(&mut self, mouse: crossterm::event::MouseEvent) {
-        use crossterm::event::{MouseEventKind, MouseButton};
-
-        // Ignore mouse events when a dialog or help overlay is open
-        if self.state == AppState::Dialog || self.help_visible {
-            return;
-        }
-
-        // ViewerMode: tmux handles scroll natively — ignore mouse events here
-        #[cfg(feature = "pro")]
-        if self.state == AppState::ViewerMode {
-            return;
-        }
+    /// Visible tree rows (total height minus header, info bar, status bar
+    /// and borders)
+    pub(super) fn visible_tree_height(&self) -> usize {
+        let info_bar = u16::from(self.info_bar_message().is_some());
+        self.height.saturating_sub(8 + info_bar) as usize
+    }
 
-        match mouse.kind {
-            MouseEventKind::ScrollUp => {
-                self.move_selection_up();
-                self.on_navigation();
-                self.enforce_scrolloff();
-            }
-            MouseEventKind::ScrollDown => {
-                self.move_selection_down();
-                self.on_navigation();
-                self.enforce_scrolloff();
-            }
-            MouseEventKind::Down(MouseButton::Left) => {
-                self.handle_mouse_click(mouse.column, mouse.row);
-            }
-            _ => {}
-        }
+    /// Selection after a page or Home/End key, or None for other keys.
+    pub(super) fn page_target(&self, key: &KeyCode, modifiers: KeyModifiers) -> Option<usize> {
+        let page = self.visible_tree_height();
+        let last = self.tree.len().saturating_sub(1);
+        let target = if self.keybindings.matches("page_down", key, modifiers) {
+            crate::ui::page_down(self.selected_index, self.tree.len(), page)
+        } else if self.keybindings.matches("page_up", key, modifiers) {
+            crate::ui::page_up(self.selected_index, page)
+        } else if self.keybindings.matches("top", key, modifiers) {
+            0
+        } else if self.keybindings.matches("bottom", key, modifiers) {
+            last
+        } else {
+            return None;
+        };
+        Some(target)
     }
 
-    /// Map a mouse click (column, row) to the corresponding tree item index.
-    ///
-    /// Layout (vertical): Title (3 rows) | Content | StatusBar (3 rows).
-    /// Content left panel is 45% width.
-    /// The session tree has a 1-row border top, items, 1-row border bottom.
-    pub(super) fn handle_mouse_click(&mut self, col: u16, row: u16) {
-        // Layout: title=3, content=middle, status_bar=3
-        let title_h: u16 = 3;
-        let status_h: u16 = 3;
-        let total = self.height;
-        if total <= title_h + status_h {
+    /// Pro: move focus from the tree to the active panel, or to the viewer
+    /// panel when no session is active.
+    #[cfg(feature = "pro")]
+    pub(super) fn focus_side_panel(&mut self) {
+        if !self.auth_token.as_ref().is_some_and(|t| t.is_pro()) {
             return;
         }
-        let content_h = total - title_h - status_h;
-        let content_top = title_h;
-
-        // Session list occupies left 45% of content area
-        let left_width = (self.width * 45) / 100;
-        if col >= left_width {
-            return; // Click is in preview pane
-        }
-        if row < content_top || row >= content_top + content_h {
-            return; // Click is outside content area
-        }
-
-        // Pro: account for the active panel above the tree
-        let tree_area_top;
-        #[cfg(feature = "pro")]
-        {
-            let is_pro = self.auth_token.as_ref().map_or(false, |t| t.is_pro());
-            let active_count = self.active_sessions().len();
-            if is_pro && active_count > 0 {
-                let max_panel_h = (content_h * 2 / 5).max(8);
-                let panel_h = (active_count as u16 + 2).min(max_panel_h);
-                tree_area_top = content_top + panel_h;
-            } else {
-                tree_area_top = content_top;
+        let active_count = self.active_sessions().len();
+        let viewer_count = self.pro.viewer_sessions.len();
+        if active_count > 0 {
+            self.active_panel_focused = true;
+            if self.active_panel_selected >= active_count {
+                self.active_panel_selected = active_count.saturating_sub(1);
+            }
+        } else if viewer_count > 0 {
+            self.pro.viewer_panel_focused = true;
+            if self.pro.viewer_panel_selected >= viewer_count {
+                self.pro.viewer_panel_selected = viewer_count.saturating_sub(1);
             }
-        }
-        #[cfg(not(feature = "pro"))]
-        {
-            tree_area_top = content_top;
-        }
-
-        if row < tree_area_top {
-            return; // Click is in the active panel, not the tree
-        }
-
-        // Inside tree area: 1 row border top, then items
-        let item_row = row.saturating_sub(tree_area_top + 1); // +1 for top border
-
-        // Determine viewport offset (scroll position)
-        let viewport_offset: usize;
-        #[cfg(feature = "pro")]
-        {
-            viewport_offset = self.list_state.offset();
-        }
-        #[cfg(not(feature = "pro"))]
-        {
-            // Non-pro: ratatui auto-scrolls around selected; approximate offset
-            let visible = self.height.saturating_sub(title_h + status_h + 2) as usize; // 2 for borders
-            viewport_offset = if self.selected_index >= visible {
-                self.selected_index.saturating_sub(visible / 2)
-            } else {
-                0
-            };
-        }
-
-        let target_index = viewport_offset + item_row as usize;
-        if target_index < self.tree.len() {
-            self.selected_index = target_index;
-            self.on_navigation();
-            self.list_state.select(Some(target_index));
-            self.enforce_scrolloff();
         }
     }
 
-    /// Visible tree rows (total height minus header, status bar, borders)
-    pub(super) fn visible_tree_height(&self) -> usize {
-        self.height.saturating_sub(5) as usize
-    }
-
-    /// Jump cursor down (Ctrl+D)
-    #[cfg(feature = "pro")]
-    pub(super) fn move_half_page_down(&mut self) {
-        let jump = self.pro.jump_lines.max(1);
-        let max = self.tree.len().saturating_sub(1);
-        self.selected_index = (self.selected_index + jump).min(max);
-    }
-
-    /// Jump cursor up (Ctrl+U)
-    #[cfg(feature = "pro")]
-    pub(super) fn move_half_page_up(&mut self) {
-        let jump = self.pro.jump_lines.max(1);
-        self.selected_index = self.selected_index.saturating_sub(jump);
-    }
-
-    /// Keep cursor ~SCROLLOFF lines from viewport edges (like vim `set scrolloff=5`)
+    /// Keep cursor `scroll_padding` lines from viewport edges (like vim
+    /// `set scrolloff=5`)
     pub(super) fn enforce_scrolloff(&mut self) {
-        const SCROLLOFF: usize = 10;
         let visible = self.visible_tree_height();
         if visible == 0 || self.tree.is_empty() {
             return;
         }
+        let scrolloff = self.scroll_padding.min(visible.saturating_sub(1) / 2);
 
         let selected = self.selected_index;
         let offset = self.list_state.offset();
 
         // Cursor too close to top edge — scroll up
-        if selected < offset + SCROLLOFF {
-            let new_offset = selected.saturating_sub(SCROLLOFF);
+        if selected < offset + scrolloff {
+            let new_offset = selected.saturating_sub(scrolloff);
             *self.list_state.offset_mut() = new_offset;
         }
         // Cursor too close to bottom edge — scroll down
-        else if selected + SCROLLOFF >= offset + visible {
-            let new_offset = (selected + SCROLLOFF + 1).saturating_sub(visible);
+        else if selected + scrolloff >= offset + visible {
+            let new_offset = (selected + scrolloff + 1).saturating_sub(visible);
             let max_offset = self.tree.len().saturating_sub(visible);
             *self.list_state.offset_mut() = new_offset.min(max_offset);
         }
@@ -235,6 +157,37 @@ impl App {
         self.tree.get(self.selected_index)
     }
 
+    /// Logical identity of the row under the cursor, for relocating it after a rebuild.
+    pub(super) fn selection_identity(&self) -> Option<SelectionIdentity> {
+        match self.selected_tree_item()? {
+            TreeItem::Group { path, .. } => Some(SelectionIdentity::Group { path: path.clone() }),
+            TreeItem::Session { id, .. } | TreeItem::Relationship { id, .. } => {
+                let group_path = self
+                    .session_by_id(id)
+                    .map(|s| s.group_path.clone())
+                    .unwrap_or_default();
+                Some(SelectionIdentity::Session {
+                    id: id.clone(),
+                    group_path,
+                })
+            }
+            TreeItem::UnmanagedHeader { .. } => Some(SelectionIdentity::UnmanagedHeader),
+            TreeItem::Unmanaged { pid } => Some(SelectionIdentity::Unmanaged { pid: *pid }),
+        }
+    }
+
+    /// Move the cursor back onto `identity` after the tree shape changed.
+    pub(super) fn restore_selection(&mut self, identity: Option<&SelectionIdentity>, old_index: usize) {
+        self.selected_index = crate::ui::relocate_selection(identity, old_index, &self.tree);
+        self.enforce_scrolloff();
+    }
+
+    /// Whether the cursor still sits on the row a confirm dialog was opened for.
+    pub(super) fn selection_matches(&self, identity: &SelectionIdentity) -> bool {
+        self.selected_tree_item()
+            .is_some_and(|item| identity.matches(item))
+    }
+
     /// Get selected session (if selection is a session or relationship row)
     pub fn selected_session(&self) -> Option<&Instance> {
         let id = match self.selected_tree_item()? {
@@ -257,13 +210,8 @@ impl App {
     }
 
     pub(super) fn priority_session_id(&self) -> Option<String> {
-        // Priority 1: Waiting (!) — needs user input, newest first.
-        if let Some(s) = self
-            .sessions
-            .iter()
-            .filter(|s| s.status == Status::Waiting)
-            .max_by_key(|s| s.last_waiting_at.unwrap_or(s.created_at))
-        {
+        // Priority 1: Waiting (!) — needs user input, longest wait first.
+        if let Some(s) = crate::session::waiting::waiting_queue(&self.sessions).first() {
             return Some(s.id.clone());
         }
 
@@ -285,6 +233,24 @@ impl App {
             .map(|s| s.id.clone())
     }
 
+    /// Select the session that has waited longest for input (`W`).
+    pub(super) async fn focus_longest_waiting(&mut self) -> Result<()> {
+        let Some(id) = crate::session::waiting::waiting_queue(&self.sessions)
+            .first()
+            .map(|s| s.id.clone())
+        else {
+            let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+            self.set_info_bar(
+                if is_zh { "没有等待输入的会话" } else { "No session is waiting for input" }.to_string(),
+                ratatui::style::Color::DarkGray,
+            );
+            return Ok(());
+        };
+        self.canvas_focused = false;
+        self.active_panel_focused = false;
+        self.focus_session(&id).await
+    }
+
     pub(super) async fn queue_attach_by_id(&mut self, id: &str) -> Result<()> {
         if let Some(pos) = self
             .tree
@@ -292,6 +258,7 @@ impl App {
             .position(|item| matches!(item, TreeItem::Session { id: sid, .. } | TreeItem::Relationship { id: sid, .. } if sid == id))
         {
             self.selected_index = pos;
+            self.enforce_scrolloff();
             self.on_navigation();
             self.preview.clear();
         }
@@ -305,7 +272,9 @@ impl App {
         } else {
             return Ok(());
         };
-        let session = self.sessions[idx].clone();
+        let Some(session) = self.sessions.get(idx).cloned() else {
+            return Ok(());
+        };
 
         let tmux_session = session.tmux_name();
         // Remember whether the session already existed before we try to create it.
@@ -325,16 +294,20 @@ impl App {
                 let c = session.command.as_str();
                 if c.trim().is_empty() { None } else { Some(c) }
             });
+            let cmd = session.launch_command(cmd);
 
-            let _ = self
+            let created = self
                 .tmux
                 .create_session(
                     &tmux_session,
                     &session.project_path.to_string_lossy(),
-                    cmd,
+                    cmd.as_deref(),
                     Some(&session.title),
                 )
                 .await;
+            if created.is_ok() {
+                crate::session::launch::open_windows(&self.tmux, &session).await;
+            }
         }
 
         // Proceed with attach if the session existed before OR exists now.
@@ -371,6 +344,70 @@ impl App {
             .cloned()
     }
 
+    /// Attach to the session visited before the one under the cursor.
+    pub(super) async fn jump_back(&mut self) -> Result<()> {
+        let current = self.selected_session().map(|s| s.id.clone());
+        let target = self.attach_history.previous(current.as_deref()).map(str::to_string);
+        if let Some(id) = &target {
+            self.focus_session(id).await?;
+        }
+        let selected = self.selected_session().map(|s| s.id.clone());
+        if target.is_none() || selected != target {
+            let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+            let msg = if is_zh {
+                "还没有可以返回的会话"
+            } else {
+                "No earlier session to jump back to"
+            };
+            self.set_info_bar(msg.to_string(), ratatui::style::Color::Yellow);
+            return Ok(());
+        }
+
+        self.last_attach_source = Some(super::AttachSource::TreePanel);
+        self.queue_attach_selected().await
+    }
+
+    /// Seed the attach history with the session tmux last switched to,
+    /// so jump-back works from the first attach of a run.
+    pub(super) async fn seed_attach_history(&mut self) {
+        if let Ok(Some(name)) = self
+            .tmux
+            .get_environment_global(crate::tmux::env::LAST_SESSION)
+            .await
+        {
+            self.record_visit(&name);
+        }
+    }
+
+    /// Note a visit to the session behind `tmux_name`, if it is one of ours.
+    pub(super) fn record_visit(&mut self, tmux_name: &str) {
+        if let Some(session) = self.sessions.iter().find(|s| s.tmux_name() == tmux_name) {
+            self.attach_history.visit(&session.id);
+        }
+    }
+
+    /// Titles of the last `n` sessions visited, most recent first.
+    pub fn recent_attach_titles(&self, n: usize) -> Vec<&str> {
+        self.attach_history
+            .recent()
+            .filter_map(|id| self.session_by_id(id))
+            .map(|s| s.title.as_str())
+            .take(n)
+            .collect()
+    }
+
+    /// Enter on the tree, or a double click: open or close a group, attach
+    /// to a session.
+    pub(super) async fn activate_selected(&mut self) -> Result<()> {
+        if self.toggle_selected_group(None).await? {
+            self.preview.clear();
+        } else {
+            self.last_attach_source = Some(super::AttachSource::TreePanel);
+            self.queue_attach_selected().await?;
+        }
+        Ok(())
+    }
+
     /// Queue attach to selected session (performed in event loop)
     pub(super) async fn queue_attach_selected(&mut self) -> Result<()> {
         if let Some(session) = self.selected_session() {
@@ -389,6 +426,64 @@ impl App {
         Ok(())
     }
 
+    /// Select and attach to the pinned session in `slot` (1-based).
+    pub(super) async fn attach_pinned(&mut self, slot: usize) -> Result<()> {
+        let id = self.pinned_ids.get(slot.wrapping_sub(1)).cloned();
+        let row = id.and_then(|id| {
+            self.tree.iter().position(|item| match item {
+                TreeItem::Session { id: sid, .. } | TreeItem::Relationship { id: sid, .. } => *sid == id,
+                _ => false,
+            })
+        });
+        let Some(row) = row else {
+            let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+            let msg = if is_zh {
+                format!("位置 {slot} 没有置顶会话（按 * 置顶）")
+            } else {
+                format!("No pinned session in slot {slot} (pin one with *)")
+            };
+            self.set_info_bar(msg, ratatui::style::Color::Yellow);
+            return Ok(());
+        };
+
+        self.selected_index = row;
+        self.enforce_scrolloff();
+        self.last_attach_source = Some(super::AttachSource::TreePanel);
+        self.queue_attach_selected().await
+    }
+
+    /// Settle an attach once it returns. When the session is gone, whether
+    /// the attach failed or the agent exited while attached, mark it exited
+    /// and offer a restart instead of dropping back into the list.
+    pub(super) async fn finish_attach(&mut self, name: &str, attached: Result<()>) -> Result<()> {
+        let _ = self.tmux.refresh_cache().await;
+        if self.tmux.session_exists(name).unwrap_or(false) {
+            return self.absorb_error(attached).await;
+        }
+        let Some(session) = self.sessions.iter_mut().find(|s| s.tmux_name() == name) else {
+            return self.absorb_error(attached).await;
+        };
+        let exited = session.status.after_exit();
+        session.set_status(exited, chrono::Utc::now());
+        let dialog = RestartExitedDialog {
+            session_id: session.id.clone(),
+            title: session.title.clone(),
+            attach_failed: attached.is_err(),
+        };
+
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        let msg = match &attached {
+            Err(e) if is_zh => format!("会话已退出: {}（{e}）", dialog.title),
+            Err(e) => format!("Session exited: {} ({e})", dialog.title),
+            Ok(()) if is_zh => format!("会话已退出: {}", dialog.title),
+            Ok(()) => format!("Session exited: {}", dialog.title),
+        };
+        self.notify_error(msg);
+        self.dialog = Some(Dialog::RestartExited(dialog));
+        self.state = AppState::Dialog;
+        Ok(())
+    }
+
     pub(super) async fn perform_attach(
         &mut self,
         terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
@@ -412,8 +507,21 @@ impl App {
             *g = Some(name.to_string());
         }
 
+        // Analytics: the enter/exit pair brackets exactly the attached time.
+        let session = self.find_session_by_tmux_name(name);
+        if let Some(s) = &session {
+            let _ = self.analytics.record_enter(&s.id, &s.title).await;
+            if s.agent_window().is_some() {
+                let _ = self.tmux.select_window(&s.tmux_target()).await;
+            }
+        }
+
         let attach_result = self.tmux.attach_session(name).await;
 
+        if let Some(s) = &session {
+            let _ = self.analytics.record_exit(&s.id, &s.title).await;
+        }
+
         // Clear the attached session — user is back on dashboard
         #[cfg(feature = "pro")]
         if let Ok(mut g) = self.attached_session.write() {
@@ -0,0 +1,141 @@
+use super::*;
+
+impl App {
+    /// Preview lines that fit on screen; the preview is as tall as the list,
+    /// less the selection summary when it is maximized.
+    fn preview_page(&self) -> usize {
+        let summary = match self.main_layout() {
+            MainLayout::Preview => usize::from(crate::ui::layout::SUMMARY_HEIGHT),
+            MainLayout::Split | MainLayout::List => 0,
+        };
+        self.visible_tree_height().saturating_sub(summary).max(1)
+    }
+
+    /// Scroll the preview by `delta` lines.
+    pub(super) fn scroll_preview(&mut self, delta: isize) {
+        let (lines, page) = (self.preview().lines().count(), self.preview_page());
+        self.preview_view.scroll_by(delta, lines, page);
+    }
+
+    /// Ctrl+U/Ctrl+D: scroll the preview by half a page, or by the Pro
+    /// `jump_lines` setting.
+    pub(super) fn scroll_preview_half_page(&mut self, down: bool) {
+        #[cfg(feature = "pro")]
+        let lines = self.pro.jump_lines.max(1) as isize;
+        #[cfg(not(feature = "pro"))]
+        let lines = (self.preview_page() / 2).max(1) as isize;
+        self.scroll_preview(if down { lines } else { -lines });
+    }
+
+    /// Tab: keys go to the preview until Esc or Tab. A live session is
+    /// re-captured with `[preview] focus_lines` of scrollback first. False
+    /// when there is no preview to focus.
+    pub(super) async fn enter_preview_focus(&mut self) -> Result<bool> {
+        if self.preview.trim().is_empty() || !self.main_layout().shows_preview() {
+            return Ok(false);
+        }
+        let browsing = self.preview_browse_label().is_some();
+        let live = self
+            .selected_session()
+            .map(|s| s.tmux_name())
+            .filter(|name| !browsing && self.tmux.session_exists(name).unwrap_or(false));
+
+        self.preview_view.reset();
+        self.preview_view.focused = true;
+        if let Some(tmux_name) = live {
+            let lines = self.config.preview_focus_lines();
+            match self.tmux.capture_history(&tmux_name, lines, false).await {
+                Ok(content) if !content.trim().is_empty() => {
+                    // Start at the newest output, as the pane shows it.
+                    let page = self.preview_page();
+                    self.preview_view
+                        .scroll_to_bottom(content.lines().count(), page);
+                    self.preview_view.content = Some(content);
+                }
+                Ok(_) => {}
+                Err(e) => self.set_info_bar(e.to_string(), ratatui::style::Color::Red),
+            }
+        }
+        Ok(true)
+    }
+
+    pub(super) async fn handle_preview_focus_key(
+        &mut self,
+        key: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> Result<()> {
+        if let Some(input) = self.preview_view.search.as_mut() {
+            match key {
+                KeyCode::Esc => self.preview_view.search = None,
+                KeyCode::Enter => {
+                    let query = input.text().to_string();
+                    self.preview_view.search = None;
+                    self.preview_view.query = query;
+                    self.preview_view.current_match = None;
+                    self.jump_to_preview_match(true);
+                }
+                KeyCode::Backspace => input.backspace(),
+                KeyCode::Delete => input.delete(),
+                KeyCode::Left => input.move_left(),
+                KeyCode::Right => input.move_right(),
+                KeyCode::Home => input.move_home(),
+                KeyCode::End => input.move_end(),
+                KeyCode::Char(c) if !modifiers.contains(KeyModifiers::CONTROL) => input.insert(c),
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        let page = self.preview_page() as isize;
+        let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+        match key {
+            KeyCode::Esc => self.preview_view.reset(),
+            KeyCode::Tab => {
+                self.preview_view.reset();
+                // Pro: Tab goes on around the panels.
+                #[cfg(feature = "pro")]
+                self.focus_side_panel();
+            }
+            KeyCode::Char('c') if ctrl => self.preview_view.reset(),
+            KeyCode::Char('d') if ctrl => self.scroll_preview_half_page(true),
+            KeyCode::Char('u') if ctrl => self.scroll_preview_half_page(false),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_preview(1),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_preview(-1),
+            KeyCode::PageDown | KeyCode::Char(' ') => self.scroll_preview(page),
+            KeyCode::PageUp => self.scroll_preview(-page),
+            KeyCode::Home | KeyCode::Char('g') => self.preview_view.scroll = 0,
+            KeyCode::End | KeyCode::Char('G') => {
+                let lines = self.preview().lines().count();
+                self.preview_view.scroll_to_bottom(lines, page as usize);
+            }
+            KeyCode::Char('/') => {
+                self.preview_view.search =
+                    Some(TextInput::with_text(self.preview_view.query.clone()));
+            }
+            KeyCode::Char('n') => self.jump_to_preview_match(true),
+            KeyCode::Char('N') => self.jump_to_preview_match(false),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn jump_to_preview_match(&mut self, forward: bool) {
+        if self.preview_view.query.is_empty() {
+            return;
+        }
+        let page = self.preview_page();
+        let text = self.preview().to_string();
+        if !self.preview_view.jump_to_match(&text, forward, page) {
+            let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+            let msg = if is_zh {
+                format!("预览中没有 \"{}\"", self.preview_view.query)
+            } else {
+                format!(
+                    "No match for \"{}\" in the preview",
+                    self.preview_view.query
+                )
+            };
+            self.set_info_bar(msg, ratatui::style::Color::Yellow);
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use super::*;
+
+use crate::session::reconcile::StaleStatuses;
+use crate::tmux::version::Unsupported;
+
+/// A profile's sessions and tmux server, reconciled with what is running.
+/// `App::new` and the profile picker both start from one.
+pub(super) struct LoadedProfile {
+    /// `~/.agent-hand/profiles/<profile>`, home of the heartbeat, journal
+    /// and the other per-profile state files.
+    pub(super) profile_dir: std::path::PathBuf,
+    pub(super) storage: Storage,
+    pub(super) sessions: Vec<Instance>,
+    pub(super) groups: GroupTree,
+    pub(super) relationships: Vec<Relationship>,
+    /// Backup generation `sessions.json` was restored from, if it was unreadable.
+    pub(super) recovered_from: Option<usize>,
+    pub(super) tmux: TmuxManager,
+    pub(super) tmux_unsupported: Vec<Unsupported>,
+    pub(super) stale_statuses: StaleStatuses,
+    /// Statuses as stored, before reconciling with tmux.
+    pub(super) persisted: Vec<Status>,
+}
+
+impl LoadedProfile {
+    pub(super) async fn load(profile: &str) -> Result<Self> {
+        let profile_dir = Storage::profile_dir_for(profile)?;
+        let storage = Storage::new(profile).await?;
+        let (mut sessions, groups, relationships) = storage.load().await?;
+        let recovered_from = storage.recovered_from_backup();
+        // Clear stale sharing state — relay rooms are ephemeral and won't survive TUI restart.
+        // Enforce default group: sessions with empty group_path get assigned to "default".
+        for s in &mut sessions {
+            if s.sharing.as_ref().is_some_and(|sh| sh.active) {
+                s.sharing = None;
+            }
+            if s.group_path.is_empty() {
+                s.group_path = "default".to_string();
+            }
+        }
+
+        let tmux = TmuxManager::new(profile);
+
+        // Clean up orphaned tmux sessions (exist in tmux but not in storage).
+        // This prevents PTY leaks from sessions that were deleted but whose tmux
+        // process was not properly killed.
+        {
+            let known_names: Vec<String> = sessions.iter().map(|s| s.tmux_name()).collect();
+            let known_refs: Vec<&str> = known_names.iter().map(|s| s.as_str()).collect();
+            let killed = tmux.cleanup_orphaned_sessions(&known_refs).await;
+            if killed > 0 {
+                tracing::info!("Cleaned up {} orphaned tmux session(s)", killed);
+            }
+        }
+
+        // The persisted status is only as fresh as the last save. Sessions without a
+        // tmux session are Stopped whatever was stored; the rest are shown as stale until
+        // their first probe.
+        let tmux_unsupported = tmux.ensure_server().await;
+        let _ = tmux.refresh_cache().await;
+        let persisted: Vec<Status> = sessions.iter().map(|s| s.status).collect();
+        let stale_statuses = crate::session::reconcile::reconcile(&mut sessions, |name| {
+            tmux.session_exists(name).unwrap_or(false)
+        });
+
+        Ok(Self {
+            profile_dir,
+            storage,
+            sessions,
+            groups,
+            relationships,
+            recovered_from,
+            tmux,
+            tmux_unsupported,
+            stale_statuses,
+            persisted,
+        })
+    }
+}
+
+impl App {
+    /// Open the profile picker on the current profile.
+    pub(super) async fn open_profile_picker(&mut self) {
+        let mut profiles = match Storage::list_profiles().await {
+            Ok(profiles) => profiles,
+            Err(e) => {
+                self.set_info_bar(
+                    format!("Failed to list profiles: {e}"),
+                    ratatui::style::Color::Red,
+                );
+                return;
+            }
+        };
+        if !profiles.contains(&self.profile) {
+            profiles.push(self.profile.clone());
+            profiles.sort();
+        }
+        let selected = profiles
+            .iter()
+            .position(|p| *p == self.profile)
+            .unwrap_or(0);
+        self.dialog = Some(Dialog::ProfilePicker(ProfilePickerDialog {
+            profiles,
+            current: self.profile.clone(),
+            selected,
+        }));
+        self.state = AppState::Dialog;
+    }
+
+    /// Show `profile` instead of the current one: load its sessions and tmux
+    /// server as `App::new` does, and drop everything tied to the old
+    /// profile. The old profile's tmux sessions keep running. Popups and
+    /// the statusline follow through the new server's `AGENTHAND_PROFILE`.
+    pub(super) async fn switch_profile(&mut self, profile: &str) {
+        if profile == self.profile {
+            return;
+        }
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+
+        let loaded = match LoadedProfile::load(profile).await {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                let msg = if is_zh {
+                    format!("无法切换到配置 {profile}: {e}")
+                } else {
+                    format!("Failed to switch to profile {profile}: {e}")
+                };
+                self.set_info_bar(msg, ratatui::style::Color::Red);
+                return;
+            }
+        };
+
+        self.tui_heartbeat.clear();
+        let profile_dir = loaded.profile_dir;
+        self.profile = profile.to_string();
+        self.storage = Arc::new(Mutex::new(loaded.storage));
+        self.tmux = Arc::new(loaded.tmux);
+        self.sessions = loaded.sessions;
+        self.groups = loaded.groups;
+        self.relationships = loaded.relationships;
+        self.stale_statuses = loaded.stale_statuses;
+        self.tui_heartbeat = crate::session::heartbeat::HeartbeatWriter::new(profile_dir.clone());
+        self.analytics = crate::analytics::ActivityTracker::new(profile).await;
+        self.runtime_dir = profile_dir.join("agent-runtime");
+        *self.agent_dirs.write().unwrap_or_else(|e| e.into_inner()) =
+            crate::agent::runner::ProfileDirs::for_profile(profile);
+        self.preview_history = crate::ui::preview_history::PreviewHistories::load(
+            profile_dir.join("previews"),
+            self.config.preview_history(),
+            crate::ui::preview_history::PREVIEW_BYTE_BUDGET,
+        );
+        self.preview_history
+            .retain_sessions(|id| self.sessions.iter().any(|s| s.id == id));
+        self.discovery_ignore_path = profile_dir.join("discovery_ignore.json");
+        self.discovery_ignored =
+            crate::tmux::discovery::load_ignore_list(&self.discovery_ignore_path);
+        crate::ui::theme::set_theme(self.config.theme(profile));
+
+        // Per-session state of the old profile.
+        self.selected_index = 0;
+        self.list_state = ratatui::widgets::ListState::default();
+        self.marked.clear();
+        self.search_query.clear();
+        self.search_results.clear();
+        self.search_selected = 0;
+        self.search_offset = 0;
+        self.pending_attach = None;
+        self.pending_preview_id = None;
+        self.pending_forward = None;
+        self.preview.clear();
+        self.preview_view = Default::default();
+        self.previous_statuses.clear();
+        self.last_tmux_activity.clear();
+        self.last_tmux_activity_change.clear();
+        self.last_status_probe.clear();
+        self.pane_stalls.clear();
+        self.attach_history.clear();
+        self.last_seen_detach_at = None;
+        self.force_probe_tmux = None;
+        self.relationship_snapshot_counts.clear();
+        self.selected_relationship_index = 0;
+        self.repo_ids.clear();
+        self.repo_ids_rx = None;
+        self.repo_badges.clear();
+        self.unmanaged_agents.clear();
+        self.undo.clear();
+
+        // The monitors watch one tmux server; the new profile has its own.
+        let server = self.tmux.server_name().to_string();
+        if let Some(task) = self._ptmx_task.take() {
+            task.abort();
+            self._ptmx_task = Some(spawn_ptmx_monitor(
+                self.cached_ptmx_max,
+                self.config.ptmx_interval(),
+                Arc::clone(&self.ptmx_state),
+                server.clone(),
+            ));
+        }
+        self._scan_task.abort();
+        self._scan_task = crate::tmux::session_id_scanner::spawn_session_id_scanner(
+            Arc::clone(&self.scan_state),
+            server.clone(),
+        );
+        if let Some(task) = self._discovery_task.take() {
+            task.abort();
+        }
+        if let Some(state) = &self.discovery_state {
+            self._discovery_task = Some(crate::tmux::discovery::spawn_discovery_monitor(
+                Duration::from_secs(self.config.discovery.interval_minutes.max(1) * 60),
+                Arc::clone(state),
+                server,
+            ));
+        }
+
+        self.ensure_groups_exist();
+        self.rebuild_tree();
+        self.rebuild_sessions_index();
+        let autostart_report = self.autostart_sessions().await;
+        self.on_navigation();
+        self.start_startup_sweep();
+        self.seed_attach_history().await;
+
+        let msg = if is_zh {
+            format!("已切换到配置 {profile}")
+        } else {
+            format!("Switched to profile {profile}")
+        };
+        // Reports still queued belong to the profile being left.
+        self.info_bar_queue.clear();
+        self.set_info_bar(msg, ratatui::style::Color::Cyan);
+        if !loaded.tmux_unsupported.is_empty() {
+            let list: Vec<String> = loaded
+                .tmux_unsupported
+                .iter()
+                .map(|u| u.to_string())
+                .collect();
+            self.queue_info_bar(list.join("; "), ratatui::style::Color::Yellow);
+        }
+        if let Some((report, color)) = autostart_report {
+            self.queue_info_bar(report, color);
+        }
+        if let Some(generation) = loaded.recovered_from {
+            self.queue_info_bar(
+                format!(
+                    "sessions.json was unreadable — restored from backup {generation} \
+                     (see `agent-hand profile restore {profile} --list`)"
+                ),
+                ratatui::style::Color::Red,
+            );
+        }
+    }
+}
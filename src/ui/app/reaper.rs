@@ -0,0 +1,75 @@
+use super::*;
+
+use crate::session::journal;
+use crate::session::reaper::{reap, stale_sessions};
+
+/// Least time between two passes of the idle reaper.
+pub(super) const REAP_INTERVAL: Duration = Duration::from_secs(3600);
+
+impl App {
+    /// Stop the tmux sessions of Idle sessions that have had no activity
+    /// for `[reaper] idle_hours`. Runs from `tick` when `[reaper] enabled`:
+    /// once at startup, then every [`REAP_INTERVAL`].
+    pub(super) async fn reap_idle_sessions(&mut self) {
+        self.last_reap = Some(Instant::now());
+        let stale = stale_sessions(
+            &self.sessions,
+            |name| self.tmux.session_activity(name),
+            chrono::Utc::now().timestamp(),
+            self.config.reaper_idle_after(),
+        );
+        // A status loaded from disk and not probed since may be out of date
+        // (the startup sweep can still be running); only a probed Idle counts.
+        let candidates: Vec<_> = stale
+            .into_iter()
+            .filter(|c| {
+                !self.stale_statuses.is_stale(&c.id)
+                    && self
+                        .session_by_id(&c.id)
+                        .is_some_and(|s| s.status == Status::Idle)
+            })
+            .collect();
+        if candidates.is_empty() {
+            return;
+        }
+
+        let results = reap(&self.tmux, candidates).await;
+        for (c, _) in results.iter().filter(|(_, error)| error.is_none()) {
+            self.mark_stopped(&c.id);
+        }
+        let entries: Vec<_> = results
+            .iter()
+            .map(|(c, error)| c.journal_entry(error.as_deref()))
+            .collect();
+        if let Err(e) = journal::append(self.tui_heartbeat.profile_dir(), &entries) {
+            tracing::warn!("failed to write reap journal: {e}");
+        }
+        let stopped: Vec<&str> = results
+            .iter()
+            .filter(|(_, error)| error.is_none())
+            .map(|(c, _)| c.title.as_str())
+            .collect();
+        if stopped.is_empty() {
+            return;
+        }
+
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        let msg = if is_zh {
+            format!(
+                "已停止 {} 个空闲会话: {}",
+                stopped.len(),
+                stopped.join(", ")
+            )
+        } else {
+            format!(
+                "Stopped {} idle session(s): {}",
+                stopped.len(),
+                stopped.join(", ")
+            )
+        };
+        self.set_info_bar(msg, ratatui::style::Color::Cyan);
+        if let Err(e) = self.refresh_sessions().await {
+            tracing::warn!("refresh after reaping failed: {e}");
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use super::*;
+
+use crate::session::repo;
+
+impl App {
+    /// Collect finished repo resolutions and start a new pass for project
+    /// paths not resolved yet. At most one pass runs at a time; each path is
+    /// resolved once per run.
+    pub(super) fn poll_repo_ids(&mut self) {
+        if let Some(ref mut rx) = self.repo_ids_rx {
+            match rx.try_recv() {
+                Ok(resolved) => {
+                    self.repo_ids.extend(resolved);
+                    self.repo_ids_rx = None;
+                    self.recompute_repo_badges();
+                }
+                Err(tokio::sync::oneshot::error::TryRecvError::Empty) => return,
+                Err(tokio::sync::oneshot::error::TryRecvError::Closed) => self.repo_ids_rx = None,
+            }
+        }
+
+        let mut pending: Vec<std::path::PathBuf> = self
+            .sessions
+            .iter()
+            .map(|s| repo::path_key(&s.project_path))
+            .filter(|key| !self.repo_ids.contains_key(key))
+            .collect();
+        if pending.is_empty() {
+            return;
+        }
+        pending.sort();
+        pending.dedup();
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            let _ = tx.send(repo::resolve(pending).await);
+        });
+        self.repo_ids_rx = Some(rx);
+    }
+
+    /// Rebuild shared-repo badges from the cached resolutions.
+    pub(super) fn recompute_repo_badges(&mut self) {
+        self.repo_badges = repo::repo_badges(
+            self.sessions
+                .iter()
+                .map(|s| (s.id.as_str(), repo::path_key(&s.project_path))),
+            &self.repo_ids,
+        );
+    }
+
+    pub fn repo_badge(&self, session_id: &str) -> Option<&repo::RepoBadge> {
+        self.repo_badges.get(session_id)
+    }
+
+    /// Send the configured sibling message to every other running session
+    /// in the selected session's repository.
+    pub(super) async fn notify_repo_siblings(&mut self) {
+        let Some(id) = self.selected_session().map(|s| s.id.clone()) else {
+            return;
+        };
+        let Some(badge) = self.repo_badges.get(&id) else {
+            self.set_info_bar(
+                "No other sessions share this repository".to_string(),
+                ratatui::style::Color::DarkGray,
+            );
+            return;
+        };
+        let repo_name = badge.name.clone();
+
+        let targets: Vec<String> = repo::siblings(&id, &self.repo_badges)
+            .iter()
+            .filter_map(|id| self.session_by_id(id))
+            .map(|s| s.tmux_name())
+            .filter(|name| self.tmux.session_exists(name).unwrap_or(false))
+            .collect();
+        if targets.is_empty() {
+            self.set_info_bar(
+                format!("No running siblings in {repo_name}"),
+                ratatui::style::Color::DarkGray,
+            );
+            return;
+        }
+
+        let message = self.config.repo_sibling_message().to_string();
+        let mut sent = 0;
+        for name in &targets {
+            match self.tmux.send_text(name, &message).await {
+                Ok(()) => sent += 1,
+                Err(e) => tracing::warn!("notify sibling {name}: {e}"),
+            }
+        }
+
+        let color = if sent == targets.len() {
+            ratatui::style::Color::Green
+        } else {
+            ratatui::style::Color::Yellow
+        };
+        self.set_info_bar(
+            format!("Notified {sent}/{} sibling(s) in {repo_name}", targets.len()),
+            color,
+        );
+    }
+}
@@ -1,77 +1,32 @@
 use super::*;
 
-impl App {
-    fn session_indices_grouped_by_path(
-        &self,
-    ) -> (
-        Vec<usize>,
-        std::collections::BTreeMap<String, Vec<usize>>,
-    ) {
-        use std::collections::BTreeMap;
-
-        let mut by_group: BTreeMap<String, Vec<usize>> = BTreeMap::new();
-        let mut ungrouped: Vec<usize> = Vec::new();
-
-        for (i, s) in self.sessions.iter().enumerate() {
-            if s.group_path.is_empty() {
-                ungrouped.push(i);
-            } else {
-                by_group.entry(s.group_path.clone()).or_default().push(i);
-            }
-        }
-
-        ungrouped.sort_by(|a, b| self.sessions[*a].title.cmp(&self.sessions[*b].title));
-        for v in by_group.values_mut() {
-            v.sort_by(|a, b| self.sessions[*a].title.cmp(&self.sessions[*b].title));
-        }
-
-        (ungrouped, by_group)
-    }
+use crate::session::groups_ops;
 
+impl App {
     pub(super) fn ordered_session_indices_by_group_baseline(&self) -> Vec<usize> {
-        let (ungrouped, by_group) = self.session_indices_grouped_by_path();
-        let mut ordered = ungrouped;
-
-        let mut roots: Vec<String> = self
-            .groups
-            .all_groups()
-            .into_iter()
-            .map(|g| g.path)
-            .filter(|p| !p.contains('/'))
-            .collect();
-        roots.sort();
-
-        fn visit(
-            app: &App,
-            ordered: &mut Vec<usize>,
-            by_group: &std::collections::BTreeMap<String, Vec<usize>>,
-            path: &str,
-        ) {
-            let mut children = app.groups.children(path);
-            children.sort();
-            for c in children {
-                visit(app, ordered, by_group, &c);
-            }
-
-            if let Some(sessions) = by_group.get(path) {
-                ordered.extend(sessions.iter().copied());
-            }
-        }
-
-        for r in roots {
-            visit(self, &mut ordered, &by_group, &r);
-        }
+        let sort = self.config.tree_sort();
+        let nodes = crate::ui::tree::build(
+            &self.sessions,
+            &self.groups,
+            sort,
+            crate::ui::tree::TreeScope::default(),
+        );
+        let nodes = crate::ui::tree::nest_forks(nodes, &self.sessions);
+        let mut ordered = crate::ui::tree::session_order(&nodes);
 
         // Fallback for sessions whose group_path exists in session data but is
         // missing from the current group tree for any reason.
-        let mut seen: std::collections::HashSet<usize> = ordered.iter().copied().collect();
-        for sessions in by_group.values() {
-            for &idx in sessions {
-                if seen.insert(idx) {
-                    ordered.push(idx);
-                }
-            }
-        }
+        let seen: std::collections::HashSet<usize> = ordered.iter().copied().collect();
+        let mut orphans: Vec<usize> = (0..self.sessions.len())
+            .filter(|i| !seen.contains(i))
+            .collect();
+        orphans.sort_by(|&a, &b| {
+            let (a, b) = (&self.sessions[a], &self.sessions[b]);
+            a.group_path
+                .cmp(&b.group_path)
+                .then_with(|| compare_sessions(sort, a, b))
+        });
+        ordered.extend(orphans);
 
         ordered
     }
@@ -81,36 +36,9 @@ impl App {
         session: &crate::session::Instance,
         cli_session_id: &str,
     ) -> Result<String> {
-        let original_command = session.command.clone();
-        let skip_perms = match session.tool {
-            crate::tmux::Tool::Claude => {
-                self.config.claude.dangerously_skip_permissions
-                    || original_command.contains("--dangerously-skip-permissions")
-            }
-            crate::tmux::Tool::Codex => {
-                self.config.codex.full_auto
-                    || original_command.contains("--full-auto")
-            }
-            crate::tmux::Tool::Gemini => {
-                self.config.gemini.yolo
-                    || original_command.contains("--yolo")
-                    || original_command.contains("-y")
-            }
-            _ => false,
-        };
-        let extra_flags = crate::tmux::resume_adapter::extract_preserve_flags(&original_command);
-
-        match crate::tmux::resume_adapter::build_resume_command(
-            session.tool,
-            cli_session_id,
-            skip_perms,
-            &extra_flags,
-        ) {
-            Some(spec) => Ok(spec.command),
-            None => Err(crate::Error::config(
-                "resume not supported for this tool type",
-            )),
-        }
+        session
+            .resume_command(cli_session_id, &self.config)
+            .ok_or_else(|| crate::Error::config("resume not supported for this tool type"))
     }
 
 
@@ -128,7 +56,9 @@ impl App {
                     .map(|s| s.group_path.clone())
                     .unwrap_or_else(|| "default".to_string())
             }
-            None => "default".to_string(),
+            Some(TreeItem::UnmanagedHeader { .. } | TreeItem::Unmanaged { .. }) | None => {
+                "default".to_string()
+            }
         }
     }
 
@@ -301,7 +231,11 @@ impl App {
         project_path: std::path::PathBuf,
         title: &str,
         group_path: &str,
+        context: Option<crate::claude::fork::ContextCopy>,
+        worktree_branch: Option<String>,
     ) -> Result<String> {
+        use crate::claude::fork::ContextCopy;
+
         let parent = self
             .session_by_id(parent_session_id)
             .cloned()
@@ -318,7 +252,12 @@ impl App {
         inst.group_path = group_path;
         inst.command = parent.command.clone();
         inst.tool = parent.tool;
+        inst.pre_command = parent.pre_command.clone();
+        inst.post_attach_command = parent.post_attach_command.clone();
         inst.parent_session_id = Some(parent_session_id.to_string());
+        // `project_path` is then the worktree created for this fork.
+        inst.fork_worktree = worktree_branch.is_some();
+        inst.fork_branch = worktree_branch;
 
         // Copy parent's CLI session IDs so fork can auto-resume
         inst.claude_session_id = parent.claude_session_id.clone();
@@ -329,6 +268,62 @@ impl App {
         inst.gemini_detected_at = parent.gemini_detected_at;
         inst.pending_cli_session_id = parent.pending_cli_session_id.clone();
 
+        // Claude, and Gemini, find conversations by working directory, so
+        // the parent's ids do not resolve from a new worktree.
+        let context = if inst.fork_worktree {
+            inst.claude_session_id = None;
+            inst.claude_detected_at = None;
+            inst.codex_session_id = None;
+            inst.codex_detected_at = None;
+            inst.gemini_session_id = None;
+            inst.gemini_detected_at = None;
+            inst.pending_cli_session_id = None;
+            // `--fork-session` would run from the worktree too; copy the file.
+            match context {
+                Some(ContextCopy::ForkFlag { parent_id }) => {
+                    let source = crate::claude::fork::claude_projects_dir()
+                        .and_then(|projects| {
+                            crate::claude::fork::find_conversation(
+                                &projects,
+                                &parent.project_path,
+                                &parent_id,
+                            )
+                        })
+                        .ok_or_else(|| {
+                            crate::Error::InvalidInput(
+                                "conversation file not found to copy into the worktree".to_string(),
+                            )
+                        })?;
+                    Some(ContextCopy::FileCopy { parent_id, source })
+                }
+                other => other,
+            }
+        } else {
+            context
+        };
+
+        // With context copy the fork gets its own conversation instead of
+        // resuming the parent's.
+        match context {
+            Some(ContextCopy::ForkFlag { parent_id }) => {
+                let resume = self.build_resume_command_for_session(&parent, &parent_id)?;
+                inst.command = format!("{resume} --fork-session");
+                inst.claude_session_id = None;
+                inst.claude_detected_at = None;
+            }
+            Some(ContextCopy::FileCopy { parent_id, source }) => {
+                let new_id = uuid::Uuid::new_v4().to_string();
+                let target_dir =
+                    crate::tmux::session_id_scanner::derive_claude_project_dir(&inst.project_path)
+                        .or_else(|| source.parent().map(std::path::Path::to_path_buf))
+                        .unwrap_or_default();
+                crate::claude::fork::copy_conversation(&source, &target_dir, &parent_id, &new_id)?;
+                inst.claude_session_id = Some(new_id);
+                inst.claude_detected_at = Some(chrono::Utc::now());
+            }
+            None => {}
+        }
+
         let storage = self.storage.lock().await;
         let (mut instances, tree, relationships) = storage.load().await?;
         instances.push(inst.clone());
@@ -337,6 +332,75 @@ impl App {
         Ok(inst.id)
     }
 
+    /// Show `git diff --stat parent...fork` for a forked session in the preview pane.
+    /// Best-effort: any missing piece (no parent, different repos, no branch) is
+    /// reported in the preview instead of failing.
+    pub(super) async fn show_fork_diff(&mut self, fork_id: &str) {
+        let Some(fork) = self.session_by_id(fork_id).cloned() else {
+            return;
+        };
+        let Some(parent) = fork
+            .parent_session_id
+            .as_deref()
+            .and_then(|id| self.session_by_id(id))
+            .cloned()
+        else {
+            self.preview = format!("{}\n\nNo parent session to diff against.", fork.title);
+            return;
+        };
+
+        self.preview = match Self::fork_diff_stat(&parent, &fork).await {
+            Ok(stat) if stat.trim().is_empty() => {
+                format!("{} vs {}\n\nNo differences.", fork.title, parent.title)
+            }
+            Ok(stat) => format!("{} vs {}\n\n{}", fork.title, parent.title, stat),
+            Err(msg) => format!("{} vs {}\n\nDiff unavailable: {}", fork.title, parent.title, msg),
+        };
+        self.preview_shows_diff = true;
+    }
+
+    async fn fork_diff_stat(
+        parent: &Instance,
+        fork: &Instance,
+    ) -> std::result::Result<String, String> {
+        let parent_repo = crate::git::common_dir(&parent.project_path)
+            .await
+            .map_err(|_| "parent path is not a git repository".to_string())?;
+        let fork_repo = crate::git::common_dir(&fork.project_path)
+            .await
+            .map_err(|_| "fork path is not a git repository".to_string())?;
+        if parent_repo != fork_repo {
+            return Err("parent and fork are in different repositories".to_string());
+        }
+
+        let parent_branch = match parent.fork_branch.clone() {
+            Some(b) => b,
+            None => crate::git::current_branch(&parent.project_path)
+                .await
+                .ok()
+                .flatten()
+                .ok_or_else(|| "parent branch unknown (detached HEAD?)".to_string())?,
+        };
+        let fork_branch = match fork.fork_branch.clone() {
+            Some(b) => b,
+            None => crate::git::current_branch(&fork.project_path)
+                .await
+                .ok()
+                .flatten()
+                .ok_or_else(|| "fork branch unknown (detached HEAD?)".to_string())?,
+        };
+        if parent_branch == fork_branch {
+            return Err(format!(
+                "parent and fork are both on '{}'; fork with a worktree to track divergence",
+                fork_branch
+            ));
+        }
+
+        crate::git::diff_stat(&fork.project_path, &parent_branch, &fork_branch)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
     pub(super) async fn apply_create_group(&mut self, group_path: &str) -> Result<()> {
         let group_path = group_path.trim();
         if group_path.is_empty() {
@@ -346,81 +410,83 @@ impl App {
         let storage = self.storage.lock().await;
         let (instances, mut tree, relationships) = storage.load().await?;
 
-        tree.create_group(group_path.to_string());
-
-        let parts: Vec<&str> = group_path.split('/').collect();
-        for i in 1..=parts.len() {
-            let p = parts[..i].join("/");
-            tree.set_expanded(&p, true);
-        }
+        groups_ops::create_group(&mut tree, group_path);
 
         storage.save(&instances, &tree, &relationships).await?;
         Ok(())
     }
 
-    pub(super) async fn apply_delete_group_prefix(&mut self, group_path: &str) -> Result<()> {
+    pub(super) async fn apply_delete_group_prefix(&mut self, group_path: &str) -> Result<undo::Deletion> {
         let group_path = group_path.trim();
+        let mut deletion = undo::Deletion::new(group_path);
         if group_path.is_empty() {
-            return Ok(());
+            return Ok(deletion);
         }
 
         let storage = self.storage.lock().await;
         let (instances, mut tree, relationships) = storage.load().await?;
 
-        tree.delete_group_prefix(group_path);
+        deletion.groups = tree.delete_group_prefix(group_path);
 
         storage.save(&instances, &tree, &relationships).await?;
-        Ok(())
+        Ok(deletion)
     }
 
-    pub(super) async fn apply_delete_group_keep_sessions(&mut self, group_path: &str) -> Result<()> {
+    pub(super) async fn apply_delete_group_keep_sessions(&mut self, group_path: &str) -> Result<undo::Deletion> {
         let group_path = group_path.trim();
+        let mut deletion = undo::Deletion::new(group_path);
         if group_path.is_empty() {
-            return Ok(());
+            return Ok(deletion);
         }
 
-        let prefix = format!("{}/", group_path);
-
         let storage = self.storage.lock().await;
         let (mut instances, mut tree, relationships) = storage.load().await?;
 
-        for inst in instances.iter_mut() {
-            if inst.group_path == group_path || inst.group_path.starts_with(&prefix) {
-                inst.group_path.clear();
-            }
-        }
-
-        tree.delete_group_prefix(group_path);
+        let deleted = groups_ops::delete_group_keep_sessions(&mut instances, &mut tree, group_path);
+        deletion.groups = deleted.groups;
+        deletion.regrouped = deleted.regrouped;
         storage.save(&instances, &tree, &relationships).await?;
-        Ok(())
+        Ok(deletion)
     }
 
-    pub(super) async fn apply_delete_group_and_sessions(&mut self, group_path: &str) -> Result<()> {
+    pub(super) async fn apply_delete_group_and_sessions(&mut self, group_path: &str) -> Result<undo::Deletion> {
         let group_path = group_path.trim();
+        let mut deletion = undo::Deletion::new(group_path);
         if group_path.is_empty() {
-            return Ok(());
+            return Ok(deletion);
         }
 
-        let prefix = format!("{}/", group_path);
-
         let storage = self.storage.lock().await;
         let (mut instances, mut tree, relationships) = storage.load().await?;
 
-        // Kill tmux sessions (best-effort) before removing from storage.
-        for inst in instances.iter() {
-            if inst.group_path == group_path || inst.group_path.starts_with(&prefix) {
-                let tmux_name = inst.tmux_name();
-                if self.tmux.session_exists(&tmux_name).unwrap_or(false) {
-                    let _ = self.tmux.kill_session(&tmux_name).await;
-                }
-            }
+        let deleted = groups_ops::delete_group_with_sessions(&mut instances, &mut tree, group_path);
+        let removed: Vec<String> = deleted.sessions.iter().map(|s| s.id.clone()).collect();
+        for id in &removed {
+            self.pending_ops.cancel(id);
+        }
+        if removed.len() > 1 {
+            self.bulk_operations.begin(Instant::now());
         }
 
-        instances.retain(|s| !(s.group_path == group_path || s.group_path.starts_with(&prefix)));
+        // Kill tmux sessions (best-effort) before saving.
+        for inst in &deleted.sessions {
+            let tmux_name = inst.tmux_name();
+            if self.tmux.session_exists(&tmux_name).unwrap_or(false)
+                && self.tmux.kill_session(&tmux_name).await.is_ok()
+            {
+                deletion.killed.push(inst.id.clone());
+            }
+        }
 
-        tree.delete_group_prefix(group_path);
         storage.save(&instances, &tree, &relationships).await?;
-        Ok(())
+        drop(storage);
+        for id in &removed {
+            self.purge_session_state(id);
+        }
+        deletion.groups = deleted.groups;
+        deletion.sessions = deleted.sessions;
+        deletion.take_mcp_configs();
+        Ok(deletion)
     }
 
     pub(super) async fn apply_move_group(&mut self, session_id: &str, group_path: &str) -> Result<()> {
@@ -457,12 +523,24 @@ impl App {
         label_color: crate::session::LabelColor,
         cli_session_id_override: Option<&str>,
     ) -> Result<()> {
-        let title = new_title.trim();
-        let title = if title.is_empty() { old_title } else { title };
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        let title = crate::session::titles::check(new_title)
+            .map_err(|e| crate::Error::InvalidInput(e.message(is_zh)))?;
         let label = label.trim();
 
         let storage = self.storage.lock().await;
         let (mut instances, tree, relationships) = storage.load().await?;
+        let group = instances
+            .iter()
+            .find(|s| s.id == session_id)
+            .map(|s| s.group_path.clone())
+            .unwrap_or_default();
+        if title != old_title
+            && crate::session::titles::taken(&instances, &group, title, Some(session_id))
+        {
+            let taken = crate::session::titles::TitleError::Taken;
+            return Err(crate::Error::InvalidInput(taken.message(is_zh)));
+        }
 
         if let Some(inst) = instances.iter_mut().find(|s| s.id == session_id) {
             let old_tmux_name = inst.tmux_name();
@@ -495,29 +573,74 @@ impl App {
         Ok(())
     }
 
+    /// Point a session at a new project directory.
+    pub(super) async fn apply_repair_path(&mut self, session_id: &str, path: std::path::PathBuf) -> Result<()> {
+        let storage = self.storage.lock().await;
+        let (mut instances, tree, relationships) = storage.load().await?;
+        if let Some(inst) = instances.iter_mut().find(|s| s.id == session_id) {
+            inst.project_path = path;
+        }
+        storage.save(&instances, &tree, &relationships).await?;
+        Ok(())
+    }
+
     pub(super) async fn apply_rename_group(&mut self, old_path: &str, new_path: &str) -> Result<()> {
         let old_path = old_path.trim();
         let new_path = new_path.trim();
-        if old_path.is_empty() || new_path.is_empty() || old_path == new_path {
-            return Ok(());
-        }
 
         let storage = self.storage.lock().await;
         let (mut instances, mut tree, relationships) = storage.load().await?;
 
-        let old_slash = format!("{}/", old_path);
-        for inst in instances.iter_mut() {
-            if inst.group_path == old_path || inst.group_path.starts_with(&old_slash) {
-                let suffix = &inst.group_path[old_path.len()..];
-                inst.group_path = format!("{new_path}{suffix}");
-            }
+        if !groups_ops::rename_group(&mut instances, &mut tree, old_path, new_path) {
+            return Ok(());
+        }
+        storage.save(&instances, &tree, &relationships).await?;
+        drop(storage);
+
+        #[cfg(feature = "pro")]
+        self.rename_group_canvases(old_path, new_path);
+
+        Ok(())
+    }
+
+    /// Save the notes typed into the group notes dialog.
+    pub(super) async fn apply_group_notes(&mut self, path: &str, text: &str) -> Result<()> {
+        let storage = self.storage.lock().await;
+        let (instances, mut tree, relationships) = storage.load().await?;
+        if tree.set_notes(path, crate::session::notes_from_input(text)) {
+            storage.save(&instances, &tree, &relationships).await?;
         }
+        Ok(())
+    }
 
-        tree.rename_prefix(old_path, new_path);
+    /// Move the group `path` under `new_parent` (empty for the top level).
+    /// Returns the group's new path.
+    pub(super) async fn apply_reparent_group(
+        &mut self,
+        path: &str,
+        new_parent: &str,
+    ) -> Result<String> {
+        let path = path.trim();
+        let storage = self.storage.lock().await;
+        let (mut instances, mut tree, relationships) = storage.load().await?;
+        let new_path = tree.move_group(path, new_parent)?;
+        if new_path == path {
+            return Ok(new_path);
+        }
+        crate::session::rename_sessions_group(&mut instances, path, &new_path);
         storage.save(&instances, &tree, &relationships).await?;
+        drop(storage);
 
-        // Rename corresponding canvas files (Pro only)
         #[cfg(feature = "pro")]
+        self.rename_group_canvases(path, &new_path);
+
+        Ok(new_path)
+    }
+
+    /// Rename the canvas files of group `old_path` and its subgroups.
+    #[cfg(feature = "pro")]
+    fn rename_group_canvases(&mut self, old_path: &str, new_path: &str) {
+        let old_slash = format!("{}/", old_path);
         if let Some(ref dir) = self.pro.canvas_dir {
             use crate::ui::canvas::canvas_filename_for_group;
             if let Ok(entries) = std::fs::read_dir(dir) {
@@ -547,59 +670,65 @@ impl App {
                 self.pro.canvas_group = format!("{new_path}{suffix}");
             }
         }
-
-        Ok(())
     }
 
     pub(super) async fn create_session_from_dialog(&mut self) -> Result<()> {
         let Some(Dialog::NewSession(d)) = self.dialog.as_ref() else {
             return Ok(());
         };
-
-        let project_path = d.validate()?;
-        let title = if d.title.text().trim().is_empty() {
-            project_path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("Untitled")
-                .to_string()
-        } else {
-            d.title.text().trim().to_string()
-        };
+        let mut instance = d.build_instance()?;
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        if let Err(e) = crate::session::titles::check(&instance.title) {
+            return Err(crate::Error::InvalidInput(e.message(is_zh)));
+        }
 
         let storage = self.storage.lock().await;
         let (mut instances, mut tree, relationships) = storage.load().await?;
-
-        let mut instance = Instance::new(title.clone(), project_path.clone());
-        let group_path = d.group_path.text().trim();
-        if !group_path.is_empty() {
-            instance.group_path = group_path.to_string();
+        let requested = instance.title.clone();
+        instance.title =
+            crate::session::titles::unique(&instances, &instance.group_path, &requested);
+        let renamed = (instance.title != requested).then(|| instance.title.clone());
+        if !instance.group_path.is_empty() {
             tree.create_group(instance.group_path.clone());
         }
-
-        instance.command.clear();
-        instance.tool = crate::tmux::Tool::Shell;
-
         instances.push(instance);
         storage.save(&instances, &tree, &relationships).await?;
+        drop(storage);
 
+        if let Some(title) = renamed {
+            self.notify_info(if is_zh {
+                format!("“{requested}”已存在，新会话命名为“{title}”")
+            } else {
+                format!("\"{requested}\" is taken in this group; created \"{title}\"")
+            });
+        }
         Ok(())
     }
 
-    pub(super) async fn delete_session(&mut self, session_id: &str, kill_tmux: bool) -> Result<()> {
+    /// Remove a session, and the relationships and workspace sessions tied
+    /// to it. Returns what was removed, for `undo_delete`.
+    pub(super) async fn delete_session(&mut self, session_id: &str, kill_tmux: bool) -> Result<undo::Deletion> {
+        // Stop queued work first so nothing re-creates the tmux session afterwards.
+        self.pending_ops.cancel(session_id);
         let tmux_name = self.tmux_name_for_id(session_id);
+        let title = self.session_by_id(session_id).map(|s| s.title.clone()).unwrap_or_default();
+        let mut deletion = undo::Deletion::new(title);
 
         if kill_tmux && self.tmux.session_exists(&tmux_name).unwrap_or(false) {
-            if let Err(e) = self.tmux.kill_session(&tmux_name).await {
-                tracing::warn!("Failed to kill tmux session {}: {}", tmux_name, e);
+            match self.tmux.kill_session(&tmux_name).await {
+                Ok(()) => deletion.killed.push(session_id.to_string()),
+                Err(e) => tracing::warn!("Failed to kill tmux session {}: {}", tmux_name, e),
             }
         }
+        let _ = crate::tmux::env::clear_markers_for(self.tmux.as_ref(), &tmux_name).await;
 
         let storage = self.storage.lock().await;
         let (mut instances, tree, mut relationships) = storage.load().await?;
-        let before = instances.len();
+        let before: Vec<String> = instances.iter().map(|s| s.id.clone()).collect();
+        let old_instances = instances.clone();
+        let old_relationships = relationships.clone();
         instances.retain(|s| s.id != session_id);
-        if instances.len() != before {
+        if instances.len() != before.len() {
             // Cascade: collect relationship IDs that reference the deleted session
             let orphaned_rel_ids: Vec<String> = relationships
                 .iter()
@@ -617,10 +746,87 @@ impl App {
                     r.session_a_id != session_id && r.session_b_id != session_id
                 });
             }
+            let removed = removed_ids(&before, &instances);
+            for id in &removed {
+                self.pending_ops.cancel(id);
+            }
             storage.save(&instances, &tree, &relationships).await?;
+            drop(storage);
+            for id in &removed {
+                self.purge_session_state(id);
+            }
+            deletion.sessions = old_instances.into_iter().filter(|s| removed.contains(&s.id)).collect();
+            deletion.take_mcp_configs();
+            deletion.relationships = old_relationships
+                .into_iter()
+                .filter(|r| !relationships.iter().any(|kept| kept.id == r.id))
+                .collect();
         }
 
-        Ok(())
+        Ok(deletion)
+    }
+
+    /// Drop sessions the CLI removed while this TUI had the profile open,
+    /// before the next save writes them back.
+    pub(super) fn apply_tombstones(&mut self) {
+        let tombstones = crate::session::heartbeat::take_tombstones(
+            self.tui_heartbeat.profile_dir(),
+            chrono::Utc::now(),
+        );
+        let ids: Vec<String> = tombstones
+            .iter()
+            .filter(|t| self.sessions_by_id.contains_key(&t.id))
+            .map(|t| t.id.clone())
+            .collect();
+        if ids.is_empty() {
+            return;
+        }
+
+        let selection = self.selection_identity();
+        let old_index = self.selected_index;
+        for id in &ids {
+            self.purge_session_state(id);
+        }
+        let dropped = crate::session::heartbeat::drop_tombstoned(&mut self.sessions, &tombstones);
+        self.rebuild_sessions_index();
+        self.rebuild_tree();
+        self.restore_selection(selection.as_ref(), old_index);
+
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        let msg = match dropped.as_slice() {
+            [only] if is_zh => format!("已通过命令行移除: {}", only.title),
+            [only] => format!("Removed from the CLI: {}", only.title),
+            _ if is_zh => format!("已通过命令行移除 {} 个会话", dropped.len()),
+            _ => format!("{} sessions removed from the CLI", dropped.len()),
+        };
+        self.set_info_bar(msg, ratatui::style::Color::Yellow);
+    }
+
+    /// Forget everything held in memory for a deleted session. New
+    /// per-session maps and queues belong here.
+    pub(super) fn purge_session_state(&mut self, id: &str) {
+        let tmux_name = self.tmux_name_for_id(id);
+        if self.pending_attach.as_deref() == Some(tmux_name.as_str()) {
+            self.pending_attach = None;
+        }
+        if self.force_probe_tmux.as_deref() == Some(tmux_name.as_str()) {
+            self.force_probe_tmux = None;
+        }
+        crate::ui::purge(
+            id,
+            &mut [
+                &mut self.pending_ops,
+                &mut self.preview_history,
+                &mut self.previous_statuses,
+                &mut self.stale_statuses,
+                &mut self.last_tmux_activity,
+                &mut self.last_tmux_activity_change,
+                &mut self.last_status_probe,
+                &mut self.pane_stalls,
+                &mut self.attach_history,
+                &mut self.repo_badges,
+            ],
+        );
     }
 
     /// Remove canvas nodes whose session no longer exists in `self.sessions`.
@@ -664,90 +870,235 @@ impl App {
     }
 
     pub(super) fn rebuild_tree(&mut self) {
-        let (ungrouped, by_group) = self.session_indices_grouped_by_path();
-
-        let mut items: Vec<TreeItem> = Vec::new();
-
-        // Root sessions
-        for si in ungrouped {
-            let session = &self.sessions[si];
-            if let Some(ref rel_id) = session.relationship_id {
-                items.push(TreeItem::Relationship {
-                    id: session.id.clone(),
-                    rel_id: rel_id.clone(),
-                    depth: 0,
-                });
+        let selection = self.selection_identity();
+        let old_index = self.selected_index;
+        self.pending_sort_changes = SortChanges::default();
+        self.last_resort = Instant::now();
+        let sort = self.config.tree_sort();
+        let pinned = crate::ui::tree::pinned(&self.sessions, sort);
+        let nodes = crate::ui::tree::build(
+            &self.sessions,
+            &self.groups,
+            sort,
+            crate::ui::tree::TreeScope::default(),
+        );
+        let nodes = crate::ui::tree::pin_to_top(nodes, &pinned);
+        let nodes = crate::ui::tree::nest_forks(nodes, &self.sessions);
+        self.tree = crate::ui::tree::flatten(&nodes, &self.sessions);
+        self.tree.extend(self.unmanaged_rows());
+        self.fork_rows = crate::ui::tree::fork_rows(&nodes, &self.sessions);
+        self.pinned_ids = pinned.iter().map(|&i| self.sessions[i].id.clone()).collect();
+        self.group_counts = crate::ui::tree::group_counts(&self.sessions);
+        self.restore_selection(selection.as_ref(), old_index);
+    }
+
+    /// Move the selected session one place up or down within its group.
+    /// Only meaningful under the manual tree sort; otherwise says so.
+    pub(super) async fn move_selected_in_group(&mut self, up: bool) -> Result<()> {
+        let Some(id) = self.selected_session().map(|s| s.id.clone()) else {
+            return Ok(());
+        };
+        if self.config.tree_sort() != crate::config::TreeSort::Manual {
+            let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+            let msg = if is_zh {
+                "手动排序需要在 config.toml 中设置 tree_sort = \"manual\""
             } else {
-                items.push(TreeItem::Session {
-                    id: session.id.clone(),
-                    depth: 0,
-                });
-            }
+                "Reordering needs tree_sort = \"manual\" in config.toml"
+            };
+            self.set_info_bar(msg.to_string(), ratatui::style::Color::Yellow);
+            return Ok(());
+        }
+        if !crate::ui::move_within_group(&mut self.sessions, &id, up) {
+            return Ok(());
         }
 
-        // Root groups
-        let mut roots: Vec<String> = self
-            .groups
-            .all_groups()
-            .into_iter()
-            .map(|g| g.path)
-            .filter(|p| !p.contains('/'))
-            .collect();
-        roots.sort();
-
-        fn visit(
-            app: &App,
-            items: &mut Vec<TreeItem>,
-            by_group: &std::collections::BTreeMap<String, Vec<usize>>,
-            path: &str,
-            depth: usize,
-        ) {
-            let name = app
-                .groups
-                .get_group(path)
-                .map(|g| g.name.clone())
-                .unwrap_or_else(|| path.split('/').last().unwrap_or(path).to_string());
-
-            items.push(TreeItem::Group {
-                path: path.to_string(),
-                name,
-                depth,
-            });
+        let storage = self.storage.lock().await;
+        storage.save(&self.sessions, &self.groups, &self.relationships).await?;
+        drop(storage);
 
-            if !app.groups.is_expanded(path) {
-                return;
-            }
+        self.rebuild_tree();
+        self.enforce_scrolloff();
+        Ok(())
+    }
 
-            let mut children = app.groups.children(path);
-            children.sort();
-            for c in children {
-                visit(app, items, by_group, &c, depth + 1);
-            }
+    /// Save the selected session's full scrollback under the profile's
+    /// `transcripts/` directory and show where it went.
+    pub(super) async fn save_selected_transcript(&mut self) -> Result<()> {
+        let Some((id, tmux_name)) = self
+            .selected_session()
+            .map(|s| (s.id.clone(), s.tmux_name()))
+        else {
+            return Ok(());
+        };
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        if !self.tmux.session_exists(&tmux_name).unwrap_or(false) {
+            let msg = if is_zh {
+                "会话未运行，没有可保存的输出"
+            } else {
+                "Session is not running; nothing to save"
+            };
+            self.set_info_bar(msg.to_string(), ratatui::style::Color::Yellow);
+            return Ok(());
+        }
 
-            if let Some(sessions) = by_group.get(path) {
-                for &si in sessions {
-                    let session = &app.sessions[si];
-                    if let Some(ref rel_id) = session.relationship_id {
-                        items.push(TreeItem::Relationship {
-                            id: session.id.clone(),
-                            rel_id: rel_id.clone(),
-                            depth: depth + 1,
-                        });
-                    } else {
-                        items.push(TreeItem::Session {
-                            id: session.id.clone(),
-                            depth: depth + 1,
-                        });
-                    }
-                }
-            }
+        let text = self.tmux.capture_history(&tmux_name, None, false).await?;
+        let profile = self.storage.lock().await.profile().to_string();
+        let path = Storage::transcript_path(&profile, &id)?;
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        tokio::fs::write(&path, crate::tmux::strip_ansi(&text)).await?;
+
+        let label = if is_zh { "已保存记录" } else { "Transcript saved" };
+        self.set_info_bar(
+            format!("{}: {}", label, path.display()),
+            ratatui::style::Color::Green,
+        );
+        Ok(())
+    }
+
+    /// Flip the selected session's autostart flag and save it.
+    pub(super) async fn toggle_selected_autostart(&mut self) -> Result<()> {
+        let Some(id) = self.selected_session().map(|s| s.id.clone()) else {
+            return Ok(());
+        };
+        let Some(session) = self.sessions.iter_mut().find(|s| s.id == id) else {
+            return Ok(());
+        };
+        session.autostart = !session.autostart;
+        let (on, title) = (session.autostart, session.title.clone());
+
+        let storage = self.storage.lock().await;
+        storage.save(&self.sessions, &self.groups, &self.relationships).await?;
+        drop(storage);
+
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        let msg = match (on, is_zh) {
+            (true, true) => format!("{title}：TUI 启动时自动运行"),
+            (true, false) => format!("{title}: starts with the TUI"),
+            (false, true) => format!("{title}：已关闭自动启动"),
+            (false, false) => format!("{title}: autostart off"),
+        };
+        self.set_info_bar(msg, ratatui::style::Color::Green);
+        Ok(())
+    }
+
+    /// Hide the selected session's stall marker until its pane changes.
+    pub(super) async fn acknowledge_selected_stall(&mut self) -> Result<()> {
+        let Some(id) = self.selected_session().map(|s| s.id.clone()) else {
+            return Ok(());
+        };
+        if self.stalled_for(&id).is_none() {
+            return Ok(());
         }
+        let Some(hash) = self.pane_stalls.get(&id).map(|s| s.hash) else {
+            return Ok(());
+        };
+        let Some(session) = self.sessions.iter_mut().find(|s| s.id == id) else {
+            return Ok(());
+        };
+        session.stall_acked = Some(hash);
+        let title = session.title.clone();
+
+        let storage = self.storage.lock().await;
+        storage.save(&self.sessions, &self.groups, &self.relationships).await?;
+        drop(storage);
+
+        let msg = if matches!(self.language(), crate::i18n::Language::Chinese) {
+            format!("{title}：已忽略卡住提示，输出变化后恢复检测")
+        } else {
+            format!("{title}: stall dismissed until its output changes")
+        };
+        self.set_info_bar(msg, ratatui::style::Color::Green);
+        Ok(())
+    }
+
+    /// Pin or unpin the selected session and save it.
+    pub(super) async fn toggle_selected_pin(&mut self) -> Result<()> {
+        let Some(id) = self.selected_session().map(|s| s.id.clone()) else {
+            return Ok(());
+        };
+        let Some(session) = self.sessions.iter_mut().find(|s| s.id == id) else {
+            return Ok(());
+        };
+        session.pinned = !session.pinned;
+        let (on, title) = (session.pinned, session.title.clone());
+
+        let storage = self.storage.lock().await;
+        storage.save(&self.sessions, &self.groups, &self.relationships).await?;
+        drop(storage);
 
-        for r in roots {
-            visit(self, &mut items, &by_group, &r, 0);
+        self.rebuild_tree();
+        self.enforce_scrolloff();
+
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        let msg = match (self.pin_slot(&id).filter(|_| on), is_zh) {
+            (Some(slot), true) => format!("{title}：已置顶，按 {slot} 直接进入"),
+            (Some(slot), false) => format!("{title}: pinned, press {slot} to attach"),
+            (None, true) => format!("{title}：已取消置顶"),
+            (None, false) => format!("{title}: unpinned"),
+        };
+        self.set_info_bar(msg, ratatui::style::Color::Green);
+        Ok(())
+    }
+
+    /// Create the tmux session of every autostart session that has none,
+    /// all at once, with its stored command. Returns a report for the info
+    /// bar; failures go there rather than stopping the TUI.
+    pub(super) async fn autostart_sessions(&self) -> Option<(String, ratatui::style::Color)> {
+        let pending: Vec<(&Instance, String, String, Option<String>)> = self
+            .sessions
+            .iter()
+            .filter(|s| s.autostart)
+            .filter(|s| !self.tmux.session_exists(&s.tmux_name()).unwrap_or(false))
+            .map(|s| {
+                let cmd = Some(s.command.trim()).filter(|c| !c.is_empty());
+                (
+                    s,
+                    s.tmux_name(),
+                    s.project_path.to_string_lossy().to_string(),
+                    s.launch_command(cmd),
+                )
+            })
+            .collect();
+        if pending.is_empty() {
+            return None;
         }
 
-        self.tree = items;
+        let results = futures::future::join_all(pending.iter().map(|(inst, name, path, cmd)| {
+            async move {
+                self.tmux
+                    .create_session(name, path, cmd.as_deref(), Some(inst.title.as_str()))
+                    .await?;
+                crate::session::launch::open_windows(&self.tmux, inst).await;
+                Ok::<_, crate::Error>(())
+            }
+        }))
+        .await;
+        let _ = self.tmux.refresh_cache().await;
+
+        let failed: Vec<String> = pending
+            .iter()
+            .zip(results)
+            .filter_map(|((inst, ..), r)| r.err().map(|e| format!("{} ({e})", inst.title)))
+            .collect();
+        let started = pending.len() - failed.len();
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        if failed.is_empty() {
+            let msg = if is_zh {
+                format!("已自动启动 {started} 个会话")
+            } else {
+                format!("Autostarted {started} session(s)")
+            };
+            Some((msg, ratatui::style::Color::DarkGray))
+        } else {
+            let msg = if is_zh {
+                format!("自动启动 {started} 个，失败：{}", failed.join("; "))
+            } else {
+                format!("Autostarted {started}, failed: {}", failed.join("; "))
+            };
+            Some((msg, ratatui::style::Color::Red))
+        }
     }
 
     pub(super) async fn toggle_selected_group(&mut self, desired: Option<bool>) -> Result<bool> {
@@ -772,61 +1123,168 @@ impl App {
         Ok(true)
     }
 
-    /// Start selected session
-    pub(super) async fn start_selected(&mut self) -> Result<()> {
-        if let Some(session) = self.selected_session() {
-            let tmux_session = session.tmux_name();
-
-            if !self.tmux.session_exists(&tmux_session).unwrap_or(false) {
-                // Prefer resume if session has a stored CLI session ID
-                let resume_cmd = session
-                    .cli_session_id()
-                    .and_then(|sid| {
-                        self.build_resume_command_for_session(session, sid).ok()
-                    });
+    /// Show or hide the forks of the selected session (`desired`, or flip
+    /// when None) and save it. Returns whether anything changed, false on
+    /// a session without forks.
+    pub(super) async fn toggle_selected_forks(&mut self, desired: Option<bool>) -> Result<bool> {
+        let Some(id) = self.selected_session().map(|s| s.id.clone()) else {
+            return Ok(false);
+        };
+        let row = self.fork_row(&id);
+        if row.forks == 0 {
+            return Ok(false);
+        }
+        let next = desired.unwrap_or(!row.expanded);
+        if next == row.expanded {
+            return Ok(false);
+        }
+        let Some(session) = self.sessions.iter_mut().find(|s| s.id == id) else {
+            return Ok(false);
+        };
+        session.forks_collapsed = !next;
 
-                let cmd = resume_cmd.as_deref().or_else(|| {
-                    let c = session.command.as_str();
-                    if c.trim().is_empty() { None } else { Some(c) }
-                });
+        let storage = self.storage.lock().await;
+        storage.save(&self.sessions, &self.groups, &self.relationships).await?;
+        drop(storage);
 
-                if let Err(e) = self
-                    .tmux
-                    .create_session(
-                        &tmux_session,
-                        &session.project_path.to_string_lossy(),
-                        cmd,
-                        Some(&session.title),
-                    )
-                    .await
-                {
-                    self.preview = format!(
-                        "{}\n\nPath: {}\nLabel: {}\n\nFailed to start tmux session:\n{}",
-                        session.title,
-                        session.project_path.to_string_lossy(),
-                        session.label,
-                        e
-                    );
-                    return Ok(());
-                }
+        self.rebuild_tree();
+        Ok(true)
+    }
 
-                self.refresh_sessions().await?;
-            }
+    /// Start selected session. Returns whether it started.
+    pub(super) async fn start_selected(&mut self) -> Result<bool> {
+        let Some(id) = self.selected_session().map(|s| s.id.clone()) else {
+            return Ok(false);
+        };
+        if self.needs_path_repair(&id) {
+            self.open_repair_path_dialog(&id);
+            return Ok(false);
         }
-        Ok(())
+        let started = self.start_session(&id).await?;
+        if started {
+            self.refresh_sessions().await?;
+        }
+        Ok(started)
     }
 
-    /// Stop selected session
-    pub(super) async fn stop_selected(&mut self) -> Result<()> {
-        if let Some(session) = self.selected_session() {
-            let tmux_session = session.tmux_name();
+    /// Re-check which project directories are gone. Runs with the tmux
+    /// cache refresh rather than on every status poll.
+    pub(super) fn refresh_missing_paths(&mut self) {
+        self.missing_paths = self
+            .sessions
+            .iter()
+            .filter(|s| s.path_missing())
+            .map(|s| s.id.clone())
+            .collect();
+    }
 
-            if self.tmux.session_exists(&tmux_session).unwrap_or(false) {
-                self.tmux.kill_session(&tmux_session).await?;
-                self.refresh_sessions().await?;
-            }
+    /// Whether `id` is stopped and its project directory is gone, so it
+    /// cannot start until the path is fixed.
+    fn needs_path_repair(&self, id: &str) -> bool {
+        self.session_by_id(id).is_some_and(|s| {
+            s.path_missing() && !self.tmux.session_exists(&s.tmux_name()).unwrap_or(false)
+        })
+    }
+
+    /// Create the tmux session for `id` unless it is already running.
+    /// Returns whether it started; a failure is shown in the preview and
+    /// the info bar.
+    pub(super) async fn start_session(&mut self, id: &str) -> Result<bool> {
+        let Some(session) = self.session_by_id(id) else {
+            return Ok(false);
+        };
+        let tmux_session = session.tmux_name();
+        if self.tmux.session_exists(&tmux_session).unwrap_or(false) {
+            return Ok(false);
+        }
+        if session.path_missing() {
+            self.preview = format!(
+                "{}\n\nPath missing: {}\n\nSelect the session and press s to fix the path or delete it.",
+                session.title,
+                session.project_path.to_string_lossy(),
+            );
+            return Ok(false);
+        }
+
+        // Prefer resume if session has a stored CLI session ID
+        let resume_cmd = session
+            .cli_session_id()
+            .and_then(|sid| {
+                self.build_resume_command_for_session(session, sid).ok()
+            });
+
+        let cmd = resume_cmd.as_deref().or_else(|| {
+            let c = session.command.as_str();
+            if c.trim().is_empty() { None } else { Some(c) }
+        });
+        let cmd = session.launch_command(cmd);
+
+        if let Err(e) = self
+            .tmux
+            .create_session(
+                &tmux_session,
+                &session.project_path.to_string_lossy(),
+                cmd.as_deref(),
+                Some(&session.title),
+            )
+            .await
+        {
+            let msg = if matches!(self.language(), crate::i18n::Language::Chinese) {
+                format!("无法启动 {}: {e}", session.title)
+            } else {
+                format!("Failed to start {}: {e}", session.title)
+            };
+            self.preview = format!(
+                "{}\n\nPath: {}\nLabel: {}\n\nFailed to start tmux session:\n{}",
+                session.title,
+                session.project_path.to_string_lossy(),
+                session.label,
+                e
+            );
+            self.notify_error(msg);
+            return Ok(false);
+        }
+        crate::session::launch::open_windows(&self.tmux, session).await;
+        Ok(true)
+    }
+
+    /// Stop selected session. Returns whether it was running.
+    pub(super) async fn stop_selected(&mut self) -> Result<bool> {
+        let Some(id) = self.selected_session().map(|s| s.id.clone()) else {
+            return Ok(false);
+        };
+        let stopped = self.stop_session(&id).await?;
+        if stopped {
+            self.refresh_sessions().await?;
+        }
+        Ok(stopped)
+    }
+
+    /// Kill the tmux session for `id` if it is running. Returns whether it
+    /// was.
+    pub(super) async fn stop_session(&mut self, id: &str) -> Result<bool> {
+        let tmux_session = self.tmux_name_for_id(id);
+        if !self.tmux.session_exists(&tmux_session).unwrap_or(false) {
+            return Ok(false);
+        }
+        self.tmux.kill_session(&tmux_session).await?;
+        self.mark_stopped(id);
+        Ok(true)
+    }
+
+    /// Create `id`'s extra windows after its tmux session was created.
+    pub(super) async fn open_windows_for(&self, id: &str) {
+        if let Some(session) = self.session_by_id(id) {
+            crate::session::launch::open_windows(&self.tmux, session).await;
+        }
+    }
+
+    /// Show `id` as Stopped right away, so the next poll does not take the
+    /// vanished tmux session for a crash.
+    pub(super) fn mark_stopped(&mut self, id: &str) {
+        if let Some(s) = self.sessions.iter_mut().find(|s| s.id == id) {
+            s.set_status(Status::Stopped, chrono::Utc::now());
         }
-        Ok(())
     }
 
     /// Resume selected session's CLI conversation.
@@ -842,20 +1300,30 @@ impl App {
         let tmux_name = session.tmux_name();
         let project_path = session.project_path.to_string_lossy().to_string();
         let title = session.title.clone();
+        let id = session.id.clone();
 
         let Some(sid) = cli_session_id else {
-            let msg = if session.tool == crate::tmux::Tool::Shell {
-                "Tool not detected yet — start the session and run a CLI tool first"
-            } else {
-                "No CLI session ID captured yet — interact with the session first"
-            };
-            self.preview = format!("{}\n\n{}", title, msg);
-            self.set_info_bar(msg.to_string(), ratatui::style::Color::Yellow);
+            if self.tmux.session_exists(&tmux_name).unwrap_or(false) {
+                let msg = if session.tool == crate::tmux::Tool::Shell {
+                    "Tool not detected yet — start the session and run a CLI tool first"
+                } else {
+                    "No CLI session ID captured yet — interact with the session first"
+                };
+                self.preview = format!("{}\n\n{}", title, msg);
+                self.set_info_bar(msg.to_string(), ratatui::style::Color::Yellow);
+                return Ok(());
+            }
+            // Nothing to resume into; a plain start is the closest thing.
+            if self.start_session(&id).await? {
+                self.refresh_sessions().await?;
+                let msg = self.no_session_id_note(false);
+                self.set_info_bar(msg, ratatui::style::Color::Yellow);
+            }
             return Ok(());
         };
 
         let resume_cmd = match self.build_resume_command_for_session(session, &sid) {
-            Ok(cmd) => cmd,
+            Ok(cmd) => session.launch_command(Some(&cmd)).unwrap_or(cmd),
             Err(_) => {
                 self.preview = format!("{}\n\nResume not supported for this tool type.", title);
                 self.set_info_bar(
@@ -885,6 +1353,7 @@ impl App {
                     Some(&title),
                 )
                 .await?;
+            self.open_windows_for(&id).await;
             self.refresh_sessions().await?;
             self.set_info_bar(
                 format!("Resumed session from stored CLI ID: {}", sid),
@@ -900,16 +1369,19 @@ impl App {
         if let Some(session) = self.selected_session() {
             let has_sid = session.cli_session_id().is_some();
             if has_sid {
+                let id = session.id.clone();
                 let tmux_name = session.tmux_name();
                 let title = session.title.clone();
                 let project_path = session.project_path.to_string_lossy().to_string();
                 let sid = session.cli_session_id().unwrap().to_string();
                 let resume_cmd = match self.build_resume_command_for_session(session, &sid) {
-                    Ok(cmd) => cmd,
+                    Ok(cmd) => session.launch_command(Some(&cmd)).unwrap_or(cmd),
                     Err(_) => {
                         self.stop_selected().await?;
                         tokio::time::sleep(Duration::from_millis(500)).await;
-                        self.start_selected().await?;
+                        if self.start_selected().await? {
+                            self.notify_restarted(&title);
+                        }
                         return Ok(());
                     }
                 };
@@ -921,36 +1393,110 @@ impl App {
                 self.tmux
                     .create_session(&tmux_name, &project_path, Some(&resume_cmd), Some(&title))
                     .await?;
+                self.open_windows_for(&id).await;
                 self.refresh_sessions().await?;
                 self.set_info_bar(
                     format!("Rebuilt pane and resumed CLI conversation: {}", sid),
                     ratatui::style::Color::Green,
                 );
             } else {
+                let tool = session.tool;
+                let title = session.title.clone();
                 self.stop_selected().await?;
                 tokio::time::sleep(Duration::from_millis(500)).await;
-                self.start_selected().await?;
+                if !self.start_selected().await? {
+                    return Ok(());
+                }
+                if tool != crate::tmux::Tool::Shell {
+                    let msg = self.no_session_id_note(true);
+                    self.set_info_bar(msg, ratatui::style::Color::Yellow);
+                } else {
+                    self.notify_restarted(&title);
+                }
             }
         }
         Ok(())
     }
 
+    fn notify_restarted(&mut self, title: &str) {
+        let msg = if matches!(self.language(), crate::i18n::Language::Chinese) {
+            format!("已重启 {title}")
+        } else {
+            format!("Restarted {title}")
+        };
+        self.notify_info(msg);
+    }
+
+    /// Info bar note for a resume that fell back to a plain start (or
+    /// restart) because no CLI session ID was detected.
+    fn no_session_id_note(&self, restarted: bool) -> String {
+        match (matches!(self.language(), crate::i18n::Language::Chinese), restarted) {
+            (true, false) => "未检测到 CLI 会话 ID，已直接启动（未恢复对话）".to_string(),
+            (true, true) => "未检测到 CLI 会话 ID，已直接重启（未恢复对话）".to_string(),
+            (false, false) => {
+                "No CLI session ID detected; started without resuming the conversation".to_string()
+            }
+            (false, true) => {
+                "No CLI session ID detected; restarted without resuming the conversation"
+                    .to_string()
+            }
+        }
+    }
+
+    /// Record that the session behind `tmux_name` is being attached to.
+    /// Only its access time is written, onto what is on disk.
+    pub(super) async fn mark_attached(&mut self, tmux_name: &str) -> Result<()> {
+        let Some(session) = self.sessions.iter_mut().find(|s| s.tmux_name() == tmux_name) else {
+            return Ok(());
+        };
+        session.mark_accessed();
+        let (id, accessed) = (session.id.clone(), session.last_accessed_at);
+        self.attach_history.visit(&id);
+        self.pending_sort_changes.last_accessed = true;
+
+        let storage = self.storage.lock().await;
+        let (mut instances, tree, relationships) = storage.load().await?;
+        if let Some(inst) = instances.iter_mut().find(|s| s.id == id) {
+            inst.last_accessed_at = accessed;
+        }
+        storage.save(&instances, &tree, &relationships).await
+    }
+
     /// Refresh sessions data
     pub(super) async fn refresh_sessions(&mut self) -> Result<()> {
+        // Captured before the reload so a removed session still knows its group.
+        let selection = self.selection_identity();
+        let old_index = self.selected_index;
+
         let storage = self.storage.lock().await;
-        let (sessions, groups, relationships) = storage.load().await?;
+        let (mut sessions, groups, relationships) = storage.load().await?;
         drop(storage);
 
+        // Storage only holds settled statuses; keep the probed ones.
+        let live: HashMap<&str, &Instance> =
+            self.sessions.iter().map(|s| (s.id.as_str(), s)).collect();
+        for s in &mut sessions {
+            if let Some(probed) = live.get(s.id.as_str()) {
+                s.status = probed.status;
+                s.status_since = probed.status_since;
+                s.waiting_reason = probed.waiting_reason.clone();
+            }
+        }
+
         self.sessions = sessions;
         self.groups = groups;
         self.relationships = relationships;
 
         self.ensure_groups_exist();
         self.rebuild_sessions_index();
+        self.refresh_missing_paths();
+        self.retain_marks();
+        self.recompute_repo_badges();
         self.rebuild_tree();
+        self.restore_selection(selection.as_ref(), old_index);
 
         // Refresh tmux cache (rate-limited). tmux can fail transiently; avoid crashing the TUI.
-        if self.last_cache_refresh.elapsed() >= Self::CACHE_REFRESH {
+        if self.last_cache_refresh.elapsed() >= self.status_timing.cache_refresh {
             let _ = self.tmux.refresh_cache().await;
             self.last_cache_refresh = Instant::now();
         }
@@ -963,11 +1509,6 @@ impl App {
         let _ = self.refresh_statuses().await;
         self.last_status_refresh = Instant::now();
 
-        // Clamp selected index
-        if self.selected_index >= self.tree.len() && !self.tree.is_empty() {
-            self.selected_index = self.tree.len() - 1;
-        }
-
         if self.state == AppState::Search {
             self.update_search_results();
         }
@@ -991,6 +1532,11 @@ impl App {
                 self.pro.share_task_rx = None;
                 self.activity.complete(super::activity::ActivityOp::StartingShare);
 
+                // Finished just before its session was deleted.
+                if !self.sessions_by_id.contains_key(&result.session_id) {
+                    return Ok(());
+                }
+
                 // Store relay client to keep background streaming alive
                 self.pro.relay_clients.insert(result.session_id.clone(), result.relay_client);
 
@@ -1076,3 +1622,13 @@ impl App {
         Ok(())
     }
 }
+
+/// Ids in `before` that are no longer in `after`.
+fn removed_ids(before: &[String], after: &[Instance]) -> Vec<String> {
+    let kept: HashSet<&str> = after.iter().map(|s| s.id.as_str()).collect();
+    before
+        .iter()
+        .filter(|id| !kept.contains(id.as_str()))
+        .cloned()
+        .collect()
+}
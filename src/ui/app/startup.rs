@@ -0,0 +1,135 @@
+use super::*;
+
+use std::future::Future;
+
+use tokio::sync::oneshot::error::TryRecvError;
+use tokio::sync::Semaphore;
+
+use crate::session::status_engine::probe_pane;
+
+/// Pane captures the startup sweep runs at once.
+const STARTUP_PROBES: usize = 8;
+
+/// Statuses (and waiting reasons) found by the startup sweep, by session id.
+pub(super) type SweepResult = Vec<(String, Status, Option<String>)>;
+
+impl App {
+    /// Refresh the tmux cache and probe every live session in the
+    /// background, so the first frame does not wait on one capture per
+    /// session. `tick` applies the result; until then sessions show their
+    /// reconciled (stale) statuses and regular polling holds off.
+    pub(super) fn start_startup_sweep(&mut self) {
+        let tmux = self.tmux.clone();
+        let lines = self.status_timing.capture_lines;
+        let targets: Vec<_> = self
+            .sessions
+            .iter()
+            .map(|s| {
+                (
+                    s.id.clone(),
+                    s.tmux_name(),
+                    s.tmux_target(),
+                    s.tool,
+                    s.status,
+                )
+            })
+            .collect();
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            let _ = tmux.refresh_cache().await;
+            let live: Vec<_> = targets
+                .into_iter()
+                .filter(|(_, name, ..)| tmux.session_exists(name).unwrap_or(false))
+                .collect();
+            let statuses = probe_bounded(live, STARTUP_PROBES, |(id, _, target, tool, shown)| {
+                let tmux = tmux.clone();
+                async move {
+                    let probe = probe_pane(&tmux, &target, tool, shown, lines).await;
+                    (id, probe.status, probe.waiting_reason)
+                }
+            })
+            .await;
+            let _ = tx.send(statuses);
+        });
+        self.startup_sweep_rx = Some(rx);
+    }
+
+    /// Apply the startup sweep's statuses once it has finished.
+    pub(super) fn poll_startup_sweep(&mut self) {
+        let Some(rx) = self.startup_sweep_rx.as_mut() else {
+            return;
+        };
+        let statuses = match rx.try_recv() {
+            Ok(statuses) => statuses,
+            Err(TryRecvError::Empty) => return,
+            // The task died; regular polling picks the sessions up.
+            Err(TryRecvError::Closed) => Vec::new(),
+        };
+        self.startup_sweep_rx = None;
+
+        let now = Instant::now();
+        let now_utc = chrono::Utc::now();
+        for (id, status, reason) in statuses {
+            let Some(session) = self.sessions.iter_mut().find(|s| s.id == id) else {
+                continue;
+            };
+            self.stale_statuses.confirm(&id, session.status);
+            if status == Status::Running {
+                session.last_running_at = Some(now_utc);
+            }
+            if status == Status::Waiting && session.status != Status::Waiting {
+                session.last_waiting_at = Some(now_utc);
+            }
+            session.set_status(status, now_utc);
+            session.waiting_reason = reason;
+            self.previous_statuses.insert(id.clone(), status);
+            self.last_status_probe.insert(id, now);
+        }
+        self.group_counts = crate::ui::tree::group_counts(&self.sessions);
+        self.pending_sort_changes.status = true;
+        self.last_cache_refresh = now;
+        self.last_status_refresh = now;
+    }
+}
+
+/// Run `probe` over `items` with at most `limit` in flight, keeping the
+/// results in order.
+async fn probe_bounded<T, R, F, Fut>(items: Vec<T>, limit: usize, probe: F) -> Vec<R>
+where
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = R>,
+{
+    let permits = Semaphore::new(limit);
+    let (permits, probe) = (&permits, &probe);
+    futures::future::join_all(items.into_iter().map(|item| async move {
+        let _permit = permits.acquire().await;
+        probe(item).await
+    }))
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn probes_run_at_most_limit_at_once() {
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let doubled = probe_bounded((0..20).collect(), 8, |i: usize| {
+            let (running, peak) = (&running, &peak);
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                i * 2
+            }
+        })
+        .await;
+        assert_eq!(doubled, (0..20).map(|i| i * 2).collect::<Vec<_>>());
+        assert_eq!(peak.load(Ordering::SeqCst), 8);
+    }
+}
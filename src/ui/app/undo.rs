@@ -0,0 +1,190 @@
+use super::*;
+
+use std::collections::VecDeque;
+
+use crate::session::GroupData;
+
+/// Deletions `u` can take back, oldest dropped first.
+const UNDO_LIMIT: usize = 10;
+
+/// What one delete removed, kept so `u` can put it back.
+#[derive(Default)]
+pub(super) struct Deletion {
+    /// Shown as "Restored <label>": the session title or group path.
+    pub(super) label: String,
+    pub(super) sessions: Vec<Instance>,
+    /// Relationships removed with the sessions.
+    pub(super) relationships: Vec<Relationship>,
+    pub(super) groups: Vec<GroupData>,
+    /// Sessions moved out of a deleted group, with the group they were in.
+    pub(super) regrouped: Vec<(String, String)>,
+    /// Ids of sessions whose tmux session was killed.
+    pub(super) killed: Vec<String>,
+    /// The removed sessions' own MCP configs: path and contents.
+    pub(super) mcp_configs: Vec<(std::path::PathBuf, String)>,
+}
+
+impl Deletion {
+    pub(super) fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Fold in another deletion made by the same action.
+    pub(super) fn absorb(&mut self, other: Deletion) {
+        self.sessions.extend(other.sessions);
+        self.relationships.extend(other.relationships);
+        self.groups.extend(other.groups);
+        self.regrouped.extend(other.regrouped);
+        self.killed.extend(other.killed);
+        self.mcp_configs.extend(other.mcp_configs);
+    }
+
+    /// Delete the removed sessions' own MCP configs, keeping what they
+    /// held for undo.
+    pub(super) fn take_mcp_configs(&mut self) {
+        for path in self.sessions.iter().filter_map(|s| s.mcp_config.as_ref()) {
+            if let Some(contents) = crate::claude::mcp::take_session_config(path) {
+                self.mcp_configs.push((path.clone(), contents));
+            }
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.sessions.is_empty() && self.groups.is_empty()
+    }
+}
+
+/// Recent deletions, newest last. Kept for this run only.
+#[derive(Default)]
+pub(super) struct UndoStack {
+    entries: VecDeque<Deletion>,
+}
+
+impl UndoStack {
+    pub(super) fn push(&mut self, deletion: Deletion) {
+        if deletion.is_empty() {
+            return;
+        }
+        if self.entries.len() == UNDO_LIMIT {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(deletion);
+    }
+
+    pub(super) fn pop(&mut self) -> Option<Deletion> {
+        self.entries.pop_back()
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+impl App {
+    /// Put back the most recent deletion. Sessions, groups and
+    /// relationships go back into storage; sessions whose tmux session was
+    /// killed start again in a fresh pane, without their old scrollback.
+    pub(super) async fn undo_delete(&mut self) -> Result<()> {
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        let Some(deletion) = self.undo.pop() else {
+            let msg = if is_zh {
+                "没有可撤销的操作"
+            } else {
+                "Nothing to undo"
+            };
+            self.set_info_bar(msg.to_string(), ratatui::style::Color::Yellow);
+            return Ok(());
+        };
+
+        {
+            let storage = self.storage.lock().await;
+            let (mut instances, mut tree, mut relationships) = storage.load().await?;
+            for group in deletion.groups {
+                tree.restore_group(group);
+            }
+            // Only sessions still where the delete left them go back.
+            for (id, group) in &deletion.regrouped {
+                if let Some(inst) = instances.iter_mut().find(|s| s.id == *id) {
+                    if inst.group_path.is_empty() || inst.group_path == "default" {
+                        inst.group_path = group.clone();
+                    }
+                }
+            }
+            for mut inst in deletion.sessions {
+                if instances.iter().any(|s| s.id == inst.id) {
+                    continue;
+                }
+                if deletion.killed.contains(&inst.id) {
+                    inst.status = Status::Stopped;
+                }
+                if !inst.group_path.is_empty() {
+                    tree.create_group(inst.group_path.clone());
+                }
+                instances.push(inst);
+            }
+            for rel in deletion.relationships {
+                if !relationships.iter().any(|r| r.id == rel.id) {
+                    relationships.push(rel);
+                }
+            }
+            storage.save(&instances, &tree, &relationships).await?;
+        }
+        for (path, contents) in &deletion.mcp_configs {
+            if let Err(e) = std::fs::write(path, contents) {
+                tracing::warn!("could not restore {}: {e}", path.display());
+            }
+        }
+        self.refresh_sessions().await?;
+
+        let mut restarted = 0;
+        for id in &deletion.killed {
+            if self.start_session(id).await? {
+                restarted += 1;
+            }
+        }
+        if restarted > 0 {
+            self.refresh_sessions().await?;
+        }
+
+        let label = &deletion.label;
+        let msg = match (restarted > 0, is_zh) {
+            (false, false) => format!("Restored {label}"),
+            (false, true) => format!("已恢复 {label}"),
+            (true, false) => {
+                format!("Restored {label} — started in a fresh pane, old scrollback is gone")
+            }
+            (true, true) => format!("已恢复 {label} — 已在新窗格中启动，原有输出已丢失"),
+        };
+        self.set_info_bar(msg, ratatui::style::Color::Cyan);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn deletion(title: &str) -> Deletion {
+        let mut d = Deletion::new(title);
+        d.sessions
+            .push(Instance::new(title.to_string(), "/tmp".into()));
+        d
+    }
+
+    #[test]
+    fn stack_keeps_the_newest_ten() {
+        let mut undo = UndoStack::default();
+        for i in 0..12 {
+            undo.push(deletion(&format!("s{i}")));
+        }
+        undo.push(Deletion::new("nothing removed"));
+
+        let labels: Vec<String> = std::iter::from_fn(|| undo.pop()).map(|d| d.label).collect();
+        assert_eq!(labels.len(), UNDO_LIMIT);
+        assert_eq!(labels.first().map(String::as_str), Some("s11"));
+        assert_eq!(labels.last().map(String::as_str), Some("s2"));
+    }
+}
@@ -0,0 +1,119 @@
+use super::*;
+
+use tokio::sync::oneshot::error::TryRecvError;
+
+use crate::session::journal;
+use crate::session::wrapup::{self, WrapupOptions, WrapupOutcome, WrapupResult};
+
+impl App {
+    /// Live sessions a wrap-up of `group` (or of everything) would touch.
+    fn wrapup_targets(&self, group: Option<&str>) -> Vec<Instance> {
+        wrapup::select_targets(&self.sessions, group)
+            .into_iter()
+            .filter(|s| self.tmux.session_exists(&s.tmux_name()).unwrap_or(false))
+            .cloned()
+            .collect()
+    }
+
+    /// Ask before wrapping up: the selected group when a group row is
+    /// selected, otherwise every session.
+    pub(super) fn open_wrapup_dialog(&mut self) {
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        if self.wrapup_rx.is_some() {
+            let msg = if is_zh { "收尾正在进行中" } else { "A wrap-up is already running" };
+            self.set_info_bar(msg.to_string(), ratatui::style::Color::Yellow);
+            return;
+        }
+        let group = match self.selected_tree_item() {
+            Some(TreeItem::Group { path, .. }) => Some(path.clone()),
+            _ => None,
+        };
+        let count = self.wrapup_targets(group.as_deref()).len();
+        if count == 0 {
+            let msg = if is_zh {
+                "没有需要收尾的运行中会话"
+            } else {
+                "No running sessions to wrap up"
+            };
+            self.set_info_bar(msg.to_string(), ratatui::style::Color::DarkGray);
+            return;
+        }
+        self.dialog = Some(Dialog::WrapupConfirm(WrapupConfirmDialog { group, count }));
+        self.state = AppState::Dialog;
+    }
+
+    /// Run `agent-hand wrapup` with its defaults in the background;
+    /// `poll_wrapup` reports the outcome.
+    pub(super) fn start_wrapup(&mut self, group: Option<&str>) {
+        let targets = self.wrapup_targets(group);
+        let opts = WrapupOptions {
+            message: wrapup::DEFAULT_WRAPUP_MESSAGE.to_string(),
+            grace: Duration::from_secs(wrapup::DEFAULT_GRACE_SECS),
+            kill: true,
+        };
+        let tmux = self.tmux.clone();
+        let profile_dir = self.tui_heartbeat.profile_dir().to_path_buf();
+        let count = targets.len();
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            let results = wrapup::run_wrapup(tmux, targets, &opts).await;
+            let entries: Vec<_> = results.iter().flat_map(|r| r.journal_entries()).collect();
+            if let Err(e) = journal::append(&profile_dir, &entries) {
+                tracing::warn!("failed to write wrapup journal: {e}");
+            }
+            let _ = tx.send(results);
+        });
+        self.wrapup_rx = Some(rx);
+
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        let msg = if is_zh {
+            format!("正在收尾 {count} 个会话（最多 {}s）…", wrapup::DEFAULT_GRACE_SECS)
+        } else {
+            format!("Wrapping up {count} session(s) (up to {}s)…", wrapup::DEFAULT_GRACE_SECS)
+        };
+        self.set_info_bar(msg, ratatui::style::Color::Cyan);
+    }
+
+    /// Report a finished wrap-up: how many sessions ended each way.
+    pub(super) async fn poll_wrapup(&mut self) {
+        let Some(rx) = self.wrapup_rx.as_mut() else {
+            return;
+        };
+        let results: Vec<WrapupResult> = match rx.try_recv() {
+            Ok(results) => results,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Closed) => Vec::new(),
+        };
+        self.wrapup_rx = None;
+
+        for r in results.iter().filter(|r| r.killed) {
+            self.mark_stopped(&r.id);
+        }
+        let count = |outcome: WrapupOutcome| {
+            results.iter().filter(|r| r.outcome == outcome).count()
+        };
+        let is_zh = matches!(self.language(), crate::i18n::Language::Chinese);
+        let parts: Vec<String> = [
+            (WrapupOutcome::Finished, "finished cleanly", "正常完成"),
+            (WrapupOutcome::TimedOut, "timed out", "超时"),
+            (WrapupOutcome::AlreadyIdle, "already idle", "原本已空闲"),
+            (WrapupOutcome::Vanished, "exited while waiting", "等待期间已退出"),
+            (WrapupOutcome::SendFailed, "could not send", "发送失败"),
+        ]
+        .into_iter()
+        .filter(|(outcome, ..)| count(*outcome) > 0)
+        .map(|(outcome, en, zh)| format!("{} {}", count(outcome), if is_zh { zh } else { en }))
+        .collect();
+        let killed = results.iter().filter(|r| r.killed).count();
+        let msg = if is_zh {
+            format!("收尾完成，已停止 {killed} 个: {}", parts.join(", "))
+        } else {
+            format!("Wrap-up done, {killed} stopped: {}", parts.join(", "))
+        };
+        self.set_info_bar(msg, ratatui::style::Color::Cyan);
+        if let Err(e) = self.refresh_sessions().await {
+            tracing::warn!("refresh after wrap-up failed: {e}");
+        }
+    }
+}
@@ -58,6 +58,11 @@ impl App {
                     .iter()
                     .filter(|s| matches!(s.status, Status::Idle))
                     .count();
+                let stopped = self
+                    .sessions
+                    .iter()
+                    .filter(|s| matches!(s.status, Status::Stopped))
+                    .count();
                 let error = self
                     .sessions
                     .iter()
@@ -68,6 +73,7 @@ impl App {
                     "running": running,
                     "waiting": waiting,
                     "idle": idle,
+                    "stopped": stopped,
                     "error": error,
                 })
             }
@@ -0,0 +1,89 @@
+//! Sessions attached to during this run, for the jump-back key and the
+//! status bar.
+//!
+//! Seeded at startup with `AGENTHAND_LAST_SESSION`, then fed by every
+//! attach from the dashboard and every detach the tmux binding reports,
+//! so a session switched to inside tmux counts too.
+
+/// Visits remembered; older ones drop off.
+const CAPACITY: usize = 20;
+
+/// Session ids, most recent last, each at most once.
+#[derive(Debug, Clone, Default)]
+pub struct AttachHistory {
+    ids: Vec<String>,
+}
+
+impl AttachHistory {
+    /// Record a visit to `id`, moving it to the top.
+    pub fn visit(&mut self, id: &str) {
+        self.ids.retain(|seen| seen != id);
+        self.ids.push(id.to_string());
+        if self.ids.len() > CAPACITY {
+            self.ids.remove(0);
+        }
+    }
+
+    /// Visited sessions, most recent first.
+    pub fn recent(&self) -> impl Iterator<Item = &str> {
+        self.ids.iter().rev().map(String::as_str)
+    }
+
+    /// Where jumping back goes: the most recent visit other than
+    /// `current`, so from the session just left it alternates between the
+    /// last two.
+    pub fn previous(&self, current: Option<&str>) -> Option<&str> {
+        self.recent().find(|&id| Some(id) != current)
+    }
+
+    pub fn clear(&mut self) {
+        self.ids.clear();
+    }
+}
+
+impl crate::ui::SessionKeyed for AttachHistory {
+    fn forget(&mut self, session_id: &str) {
+        self.ids.retain(|id| id != session_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::SessionKeyed;
+
+    fn history(ids: &[&str]) -> AttachHistory {
+        let mut h = AttachHistory::default();
+        for id in ids {
+            h.visit(id);
+        }
+        h
+    }
+
+    #[test]
+    fn revisits_move_to_the_top() {
+        let h = history(&["a", "b", "a", "c"]);
+        assert_eq!(h.recent().collect::<Vec<_>>(), ["c", "a", "b"]);
+    }
+
+    #[test]
+    fn jumping_back_skips_the_current_session() {
+        let h = history(&["a", "b"]);
+        assert_eq!(h.previous(None), Some("b"));
+        assert_eq!(h.previous(Some("b")), Some("a"));
+        assert_eq!(h.previous(Some("x")), Some("b"));
+        assert_eq!(history(&["a"]).previous(Some("a")), None);
+    }
+
+    #[test]
+    fn forgets_deleted_sessions_and_old_visits() {
+        let mut h = history(&["a", "b"]);
+        h.forget("b");
+        assert_eq!(h.previous(None), Some("a"));
+
+        let ids: Vec<String> = (0..=CAPACITY).map(|i| i.to_string()).collect();
+        let h = history(&ids.iter().map(String::as_str).collect::<Vec<_>>());
+        assert_eq!(h.recent().count(), CAPACITY);
+        assert_eq!(h.recent().last(), Some("1"));
+    }
+}
@@ -0,0 +1,234 @@
+//! In-flight work keyed by session id.
+//!
+//! Background operations register under the session they act on. Deleting
+//! a session cancels everything registered for it before storage changes,
+//! then [`purge`] drops the id from every per-session store, so a late
+//! result has nothing to write back into.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use tokio::task::AbortHandle;
+
+/// Shared flag a background operation checks before publishing its result.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug)]
+struct Pending {
+    token: CancelToken,
+    task: Option<AbortHandle>,
+}
+
+impl Pending {
+    fn cancel(&self) {
+        self.token.cancel();
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.token.is_cancelled() || self.task.as_ref().is_some_and(|t| t.is_finished())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct CancelRegistry {
+    pending: HashMap<String, Vec<Pending>>,
+}
+
+impl CancelRegistry {
+    /// Register work for `session_id` that is not a spawned task.
+    pub fn register(&mut self, session_id: &str) -> CancelToken {
+        self.insert(session_id, None)
+    }
+
+    /// Register a spawned task; cancelling also aborts it.
+    pub fn register_task(&mut self, session_id: &str, task: AbortHandle) -> CancelToken {
+        self.insert(session_id, Some(task))
+    }
+
+    fn insert(&mut self, session_id: &str, task: Option<AbortHandle>) -> CancelToken {
+        let token = CancelToken::default();
+        let entries = self.pending.entry(session_id.to_string()).or_default();
+        entries.retain(|p| !p.is_done());
+        entries.push(Pending {
+            token: token.clone(),
+            task,
+        });
+        token
+    }
+
+    /// Deregister finished work. Returns whether its result should be
+    /// applied: false once it was cancelled or its session purged.
+    pub fn finish(&mut self, session_id: &str, token: &CancelToken) -> bool {
+        let Some(entries) = self.pending.get_mut(session_id) else {
+            return false;
+        };
+        let before = entries.len();
+        entries.retain(|p| !Arc::ptr_eq(&p.token.0, &token.0));
+        let found = entries.len() != before;
+        if entries.is_empty() {
+            self.pending.remove(session_id);
+        }
+        found && !token.is_cancelled()
+    }
+
+    /// Cancel all work registered for `session_id`; returns how much there was.
+    pub fn cancel(&mut self, session_id: &str) -> usize {
+        let entries = self.pending.remove(session_id).unwrap_or_default();
+        for p in &entries {
+            p.cancel();
+        }
+        entries.len()
+    }
+
+    pub fn cancel_all(&mut self) -> usize {
+        let ids: Vec<String> = self.pending.keys().cloned().collect();
+        ids.iter().map(|id| self.cancel(id)).sum()
+    }
+
+    pub fn has_pending(&self, session_id: &str) -> bool {
+        self.pending
+            .get(session_id)
+            .is_some_and(|entries| entries.iter().any(|p| !p.is_done()))
+    }
+}
+
+/// Per-session state that must not outlive its session.
+pub trait SessionKeyed {
+    fn forget(&mut self, session_id: &str);
+}
+
+impl<V> SessionKeyed for HashMap<String, V> {
+    fn forget(&mut self, session_id: &str) {
+        self.remove(session_id);
+    }
+}
+
+impl SessionKeyed for HashSet<String> {
+    fn forget(&mut self, session_id: &str) {
+        self.remove(session_id);
+    }
+}
+
+impl SessionKeyed for CancelRegistry {
+    fn forget(&mut self, session_id: &str) {
+        self.cancel(session_id);
+    }
+}
+
+impl SessionKeyed for crate::session::reconcile::StaleStatuses {
+    fn forget(&mut self, session_id: &str) {
+        self.confirm(session_id, crate::session::Status::Idle);
+    }
+}
+
+/// Drop `session_id` from every store.
+pub fn purge(session_id: &str, stores: &mut [&mut dyn SessionKeyed]) {
+    for store in stores {
+        store.forget(session_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn cancel_by_id_only_touches_that_session() {
+        let mut reg = CancelRegistry::default();
+        let a1 = reg.register("a");
+        let a2 = reg.register("a");
+        let b = reg.register("b");
+
+        assert_eq!(reg.cancel("a"), 2);
+        assert!(a1.is_cancelled() && a2.is_cancelled());
+        assert!(!b.is_cancelled());
+        assert!(!reg.has_pending("a"));
+        assert!(reg.has_pending("b"));
+        assert_eq!(reg.cancel("a"), 0);
+
+        assert!(reg.finish("b", &b));
+        assert!(!reg.has_pending("b"));
+    }
+
+    #[test]
+    fn cancel_all_cancels_everything() {
+        let mut reg = CancelRegistry::default();
+        let tokens = [reg.register("a"), reg.register("b"), reg.register("b")];
+        assert_eq!(reg.cancel_all(), 3);
+        assert!(tokens.iter().all(CancelToken::is_cancelled));
+        assert_eq!(reg.cancel_all(), 0);
+    }
+
+    #[tokio::test]
+    async fn cancelling_aborts_registered_tasks() {
+        let mut reg = CancelRegistry::default();
+        let task = tokio::spawn(tokio::time::sleep(Duration::from_secs(60)));
+        reg.register_task("a", task.abort_handle());
+
+        reg.cancel("a");
+        assert!(task.await.unwrap_err().is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn result_landing_between_cancel_and_purge_is_dropped() {
+        let mut reg = CancelRegistry::default();
+        let mut preview_cache: HashMap<String, String> = HashMap::new();
+        preview_cache.insert("a".into(), "old pane".into());
+
+        // The task has already produced its result when the delete starts.
+        let (tx, mut rx) = tokio::sync::oneshot::channel();
+        let task = tokio::spawn(async move {
+            let _ = tx.send("a".to_string());
+        });
+        let token = reg.register_task("a", task.abort_handle());
+        task.await.unwrap();
+
+        reg.cancel("a");
+        purge("a", &mut [&mut reg, &mut preview_cache]);
+
+        let id = rx.try_recv().unwrap();
+        if reg.finish(&id, &token) {
+            preview_cache.insert(id, "new pane".into());
+        }
+        assert!(preview_cache.is_empty());
+        assert!(!reg.has_pending("a"));
+    }
+
+    #[test]
+    fn purge_clears_every_store() {
+        let mut reg = CancelRegistry::default();
+        let token = reg.register("a");
+        let keep = reg.register("b");
+        let mut cache: HashMap<String, String> = HashMap::new();
+        let mut probes: HashMap<String, u64> = HashMap::new();
+        let mut seen: HashSet<String> = HashSet::new();
+        for id in ["a", "b"] {
+            cache.insert(id.into(), String::new());
+            probes.insert(id.into(), 1);
+            seen.insert(id.into());
+        }
+
+        purge("a", &mut [&mut reg, &mut cache, &mut probes, &mut seen]);
+
+        assert!(token.is_cancelled() && !keep.is_cancelled());
+        assert_eq!(cache.keys().collect::<Vec<_>>(), ["b"]);
+        assert_eq!(probes.keys().collect::<Vec<_>>(), ["b"]);
+        assert!(!seen.contains("a") && seen.contains("b"));
+    }
+}
@@ -0,0 +1,197 @@
+//! Popup geometry for dialogs that size themselves to their content.
+//!
+//! A dialog declares its lines, which line has focus, and a percentage
+//! range; the frame grows the popup to fit within that range, scrolls the
+//! body when even the maximum is too small, and applies the user's
+//! per-dialog size nudge (Ctrl+arrows, persisted in the UI-state file).
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use ratatui::layout::Rect;
+use serde::{Deserialize, Serialize};
+
+/// `UiState::dialog_sizes` keys of dialogs rendered through the frame.
+pub const NEW_SESSION: &str = "new_session";
+pub const FORK: &str = "fork";
+
+/// Percentage points one Ctrl+arrow press adds or removes.
+pub const NUDGE_STEP: i16 = 5;
+const NUDGE_LIMIT: i16 = 40;
+/// Popups never shrink below this share of the terminal.
+const MIN_PERCENT: i16 = 20;
+
+/// Size range of a dialog as percentages of the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DialogGeometry {
+    pub min_pct: (u16, u16),
+    pub max_pct: (u16, u16),
+}
+
+/// User adjustment to a dialog's size range, in percentage points.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SizeNudge {
+    #[serde(default)]
+    pub width: i16,
+    #[serde(default)]
+    pub height: i16,
+}
+
+impl SizeNudge {
+    /// Grow (positive) or shrink (negative) by whole steps.
+    pub fn nudged(self, dw: i16, dh: i16) -> Self {
+        let clamp = |v: i16| v.clamp(-NUDGE_LIMIT, NUDGE_LIMIT);
+        Self {
+            width: clamp(self.width + dw * NUDGE_STEP),
+            height: clamp(self.height + dh * NUDGE_STEP),
+        }
+    }
+}
+
+fn scaled(total: u16, pct: u16, nudge: i16) -> u16 {
+    let pct = (pct as i16 + nudge).clamp(MIN_PERCENT, 100) as u32;
+    (total as u32 * pct / 100) as u16
+}
+
+/// Popup rectangle for content of `content_w` × `content_h` (excluding
+/// borders), centered in `area`.
+pub fn popup_rect(area: Rect, geometry: DialogGeometry, nudge: SizeNudge, content_w: u16, content_h: u16) -> Rect {
+    let fit = |total: u16, min_pct: u16, max_pct: u16, nudge: i16, content: u16| {
+        let lo = scaled(total, min_pct, nudge);
+        let hi = scaled(total, max_pct, nudge).max(lo);
+        content.saturating_add(2).clamp(lo, hi).min(total)
+    };
+    let width = fit(area.width, geometry.min_pct.0, geometry.max_pct.0, nudge.width, content_w);
+    let height = fit(area.height, geometry.min_pct.1, geometry.max_pct.1, nudge.height, content_h);
+
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Visible slice of a dialog body taller than its popup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyScroll {
+    pub offset: usize,
+    pub hidden_above: usize,
+    pub hidden_below: usize,
+}
+
+/// Scroll just far enough to show `focus`; bodies that fit never scroll.
+pub fn body_scroll(total: usize, visible: usize, focus: usize) -> BodyScroll {
+    let visible = visible.max(1);
+    let offset = if total <= visible {
+        0
+    } else {
+        (focus + 1).saturating_sub(visible).min(total - visible)
+    };
+    BodyScroll {
+        offset,
+        hidden_above: offset,
+        hidden_below: total.saturating_sub(offset + visible),
+    }
+}
+
+/// Persisted UI preferences that are not configuration: `ui_state.json`
+/// next to the profiles.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UiState {
+    /// Size nudges keyed by dialog kind (e.g. `new_session`).
+    #[serde(default)]
+    pub dialog_sizes: HashMap<String, SizeNudge>,
+    /// Mode chosen with F9; overrides `ui.mode` from the config once set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ui_mode: Option<crate::config::UiMode>,
+    /// Whether the New Session dialog shows its advanced fields in simple mode.
+    #[serde(default)]
+    pub new_session_advanced: bool,
+    /// Main-screen layout last chosen with the cycle key.
+    #[serde(default)]
+    pub main_layout: crate::ui::layout::MainLayout,
+}
+
+impl UiState {
+    fn path() -> Option<PathBuf> {
+        crate::session::Storage::get_agent_hand_dir()
+            .ok()
+            .map(|d| d.join("ui_state.json"))
+    }
+
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> crate::Result<()> {
+        let Some(path) = Self::path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn dialog_nudge(&self, kind: &str) -> SizeNudge {
+        self.dialog_sizes.get(kind).copied().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GEOM: DialogGeometry = DialogGeometry {
+        min_pct: (70, 40),
+        max_pct: (90, 90),
+    };
+
+    #[test]
+    fn popup_grows_to_fit_content_within_range() {
+        let area = Rect::new(0, 0, 100, 28);
+        let small = popup_rect(area, GEOM, SizeNudge::default(), 10, 5);
+        assert_eq!((small.width, small.height), (70, 11));
+
+        let tall = popup_rect(area, GEOM, SizeNudge::default(), 60, 18);
+        assert_eq!(tall.height, 20);
+
+        let huge = popup_rect(area, GEOM, SizeNudge::default(), 200, 200);
+        assert_eq!((huge.width, huge.height), (90, 25));
+        assert_eq!((huge.x, huge.y), (5, 1));
+    }
+
+    #[test]
+    fn nudges_shift_the_range_and_are_bounded() {
+        let area = Rect::new(0, 0, 100, 40);
+        let grown = popup_rect(area, GEOM, SizeNudge::default().nudged(1, 1), 10, 5);
+        assert_eq!((grown.width, grown.height), (75, 18));
+
+        let mut nudge = SizeNudge::default();
+        for _ in 0..20 {
+            nudge = nudge.nudged(-1, 1);
+        }
+        assert_eq!(nudge, SizeNudge { width: -NUDGE_LIMIT, height: NUDGE_LIMIT });
+        let r = popup_rect(area, GEOM, nudge, 200, 200);
+        assert_eq!((r.width, r.height), (50, 40));
+    }
+
+    #[test]
+    fn scroll_keeps_focus_visible() {
+        assert_eq!(body_scroll(10, 20, 9).offset, 0);
+
+        let s = body_scroll(30, 10, 5);
+        assert_eq!((s.offset, s.hidden_above, s.hidden_below), (0, 0, 20));
+
+        let s = body_scroll(30, 10, 15);
+        assert_eq!((s.offset, s.hidden_above, s.hidden_below), (6, 6, 14));
+
+        let s = body_scroll(30, 10, 29);
+        assert_eq!((s.offset, s.hidden_below), (20, 0));
+    }
+}
@@ -3,6 +3,7 @@ use std::path::PathBuf;
 use crate::error::Result;
 
 use super::input::TextInput;
+use super::selection::SelectionIdentity;
 
 // Pro/Max dialog types re-exported from pro module
 #[cfg(feature = "pro")]
@@ -14,13 +15,105 @@ pub use crate::pro::ui::dialogs_max::*;
 pub enum NewSessionField {
     Path,
     Title,
+    Tool,
+    Command,
+    Pre,
     Group,
 }
 
+impl NewSessionField {
+    /// Field after this one; `advanced` includes Tool, Command, Pre and
+    /// Group.
+    pub fn next(self, advanced: bool) -> Self {
+        match self {
+            Self::Path => Self::Title,
+            Self::Title if advanced => Self::Tool,
+            Self::Title => Self::Title,
+            Self::Tool => Self::Command,
+            Self::Command => Self::Pre,
+            Self::Pre | Self::Group => Self::Group,
+        }
+    }
+
+    pub fn prev(self) -> Self {
+        match self {
+            Self::Path | Self::Title => Self::Path,
+            Self::Tool => Self::Title,
+            Self::Command => Self::Tool,
+            Self::Pre => Self::Command,
+            Self::Group => Self::Pre,
+        }
+    }
+
+    /// Whether the field is hidden while the advanced section is collapsed.
+    pub fn is_advanced(self) -> bool {
+        matches!(self, Self::Tool | Self::Command | Self::Pre | Self::Group)
+    }
+}
+
+/// Agent picked in the New Session dialog's Tool field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NewSessionTool {
+    Shell,
+    Claude,
+    Codex,
+    Gemini,
+    OpenCode,
+}
+
+impl NewSessionTool {
+    pub const ALL: [NewSessionTool; 5] = [
+        Self::Shell,
+        Self::Claude,
+        Self::Codex,
+        Self::Gemini,
+        Self::OpenCode,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Shell => "shell",
+            Self::Claude => "claude",
+            Self::Codex => "codex",
+            Self::Gemini => "gemini",
+            Self::OpenCode => "opencode",
+        }
+    }
+
+    /// Command the Command field is filled with; empty for a plain shell.
+    pub fn default_command(self) -> &'static str {
+        match self {
+            Self::Shell => "",
+            other => other.name(),
+        }
+    }
+
+    pub fn from_tool(tool: crate::tmux::Tool) -> Self {
+        use crate::tmux::Tool;
+        match tool {
+            Tool::Shell => Self::Shell,
+            Tool::Claude => Self::Claude,
+            Tool::Codex => Self::Codex,
+            Tool::Gemini => Self::Gemini,
+            Tool::OpenCode => Self::OpenCode,
+        }
+    }
+
+    pub fn cycle(self, backwards: bool) -> Self {
+        let n = Self::ALL.len();
+        let i = Self::ALL.iter().position(|t| *t == self).unwrap_or(0);
+        Self::ALL[if backwards { (i + n - 1) % n } else { (i + 1) % n }]
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct NewSessionDialog {
     pub path: TextInput,
     pub title: TextInput,
+    pub tool: NewSessionTool,
+    pub command: TextInput,
+    /// Shell command run in the pane before the agent starts.
+    pub pre_command: TextInput,
     pub group_path: TextInput,
     pub field: NewSessionField,
 
@@ -35,6 +128,13 @@ pub struct NewSessionDialog {
     // Debounced auto-suggest for the Path field.
     pub path_dirty: bool,
     pub path_last_edit: std::time::Instant,
+
+    /// Saved templates, cycled with Ctrl+T; `template` is the one applied.
+    pub templates: Vec<crate::session::templates::SessionTemplate>,
+    pub template: Option<usize>,
+
+    /// Why the last submit failed; the dialog stays open.
+    pub error: Option<String>,
 }
 
 impl NewSessionDialog {
@@ -81,7 +181,31 @@ impl NewSessionDialog {
 pub struct DeleteConfirmDialog {
     pub session_id: String,
     pub title: String,
+    /// Marked sessions as (id, title) when deleting a multi-selection;
+    /// empty when the dialog is for `session_id` alone.
+    pub batch: Vec<(String, String)>,
     pub kill_tmux: bool,
+    /// Git worktree agent-hand created for this session, if any.
+    pub worktree: Option<PathBuf>,
+    /// Also remove `worktree`; off until explicitly toggled.
+    pub remove_worktree: bool,
+    /// Tree row the dialog was opened on; confirm aborts if it moved.
+    pub target: SelectionIdentity,
+}
+
+impl DeleteConfirmDialog {
+    /// Every session the delete applies to.
+    pub fn session_ids(&self) -> Vec<String> {
+        batch_ids(&self.session_id, &self.batch)
+    }
+}
+
+fn batch_ids(session_id: &str, batch: &[(String, String)]) -> Vec<String> {
+    if batch.is_empty() {
+        vec![session_id.to_string()]
+    } else {
+        batch.iter().map(|(id, _)| id.clone()).collect()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -96,12 +220,17 @@ pub struct DeleteGroupDialog {
     pub group_path: String,
     pub session_count: usize,
     pub choice: DeleteGroupChoice,
+    /// Tree row the dialog was opened on; confirm aborts if it moved.
+    pub target: SelectionIdentity,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ForkField {
     Title,
     Group,
+    Context,
+    Worktree,
+    Branch,
 }
 
 #[derive(Debug, Clone)]
@@ -111,6 +240,36 @@ pub struct ForkDialog {
     pub title: TextInput,
     pub group_path: TextInput,
     pub field: ForkField,
+    /// Carry the parent's Claude conversation into the fork.
+    pub copy_context: bool,
+    /// How context would be copied, or why it can't be.
+    pub context_copy: std::result::Result<crate::claude::fork::ContextCopy, String>,
+    /// Check the fork out in a new git worktree on `branch`.
+    pub worktree: bool,
+    pub branch: TextInput,
+    /// Directory the worktree is created in.
+    pub worktree_root: PathBuf,
+    /// Why the last submit failed; the dialog stays open.
+    pub error: Option<String>,
+}
+
+impl ForkDialog {
+    /// Tab order; the branch field is skipped while worktree is off.
+    pub fn next_field(&mut self) {
+        self.field = match self.field {
+            ForkField::Title => ForkField::Group,
+            ForkField::Group => ForkField::Context,
+            ForkField::Context => ForkField::Worktree,
+            ForkField::Worktree if self.worktree => ForkField::Branch,
+            ForkField::Worktree | ForkField::Branch => ForkField::Title,
+        };
+    }
+
+    /// Where the worktree would be created, when enabled.
+    pub fn worktree_dest(&self) -> Option<PathBuf> {
+        self.worktree
+            .then(|| crate::git::worktree_dir(&self.worktree_root, self.branch.text()))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -163,6 +322,9 @@ impl CreateGroupDialog {
 pub struct MoveGroupDialog {
     pub session_id: String,
     pub title: String,
+    /// Marked sessions as (id, title) when moving a multi-selection; empty
+    /// when the dialog is for `session_id` alone.
+    pub batch: Vec<(String, String)>,
     pub input: TextInput,
     pub all_groups: Vec<String>,
     pub matches: Vec<String>,
@@ -170,6 +332,11 @@ pub struct MoveGroupDialog {
 }
 
 impl MoveGroupDialog {
+    /// Every session the move applies to.
+    pub fn session_ids(&self) -> Vec<String> {
+        batch_ids(&self.session_id, &self.batch)
+    }
+
     fn fuzzy_match(query: &str, text: &str) -> bool {
         let q = query.trim().to_lowercase();
         if q.is_empty() {
@@ -213,6 +380,23 @@ pub struct RenameGroupDialog {
     pub new_path: TextInput,
 }
 
+/// Edit a group's notes. The input is one line; `\n` stands for a line
+/// break (see [`crate::session::notes_to_input`]).
+#[derive(Debug, Clone)]
+pub struct GroupNotesDialog {
+    pub path: String,
+    pub notes: TextInput,
+}
+
+/// Move a whole group under another parent.
+#[derive(Debug, Clone)]
+pub struct ReparentGroupDialog {
+    pub path: String,
+    /// Destination parent; empty for the top level.
+    pub new_parent: TextInput,
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SessionEditField {
     Title,
@@ -231,6 +415,124 @@ pub struct RenameSessionDialog {
     /// Editable CLI session ID (for manual correction)
     pub cli_session_id: TextInput,
     pub field: SessionEditField,
+    /// Why the last apply failed; the dialog stays open.
+    pub error: Option<String>,
+}
+
+/// Opened when starting a session whose project directory is gone: point
+/// it at a new directory, or delete the session.
+#[derive(Debug, Clone)]
+pub struct RepairPathDialog {
+    pub session_id: String,
+    pub title: String,
+    pub old_path: PathBuf,
+    pub path: TextInput,
+    pub suggestions: Vec<String>,
+    pub suggestions_idx: usize,
+    pub suggestions_visible: bool,
+}
+
+impl RepairPathDialog {
+    pub fn new(session_id: String, title: String, old_path: PathBuf) -> Self {
+        Self {
+            session_id,
+            title,
+            path: TextInput::with_text(old_path.to_string_lossy().to_string()),
+            old_path,
+            suggestions: Vec::new(),
+            suggestions_idx: 0,
+            suggestions_visible: false,
+        }
+    }
+
+    /// Tab: apply the highlighted suggestion, or list completions (applying
+    /// the only one directly).
+    pub fn complete(&mut self) {
+        if self.suggestions_visible {
+            if let Some(sel) = self.suggestions.get(self.suggestions_idx).cloned() {
+                self.path.set_text(sel);
+            }
+            self.clear_suggestions();
+            return;
+        }
+        self.suggestions = NewSessionDialog::suggest_paths(self.path.text());
+        self.suggestions_idx = 0;
+        if self.suggestions.len() == 1 {
+            self.path.set_text(self.suggestions[0].clone());
+            self.clear_suggestions();
+        } else {
+            self.suggestions_visible = !self.suggestions.is_empty();
+        }
+    }
+
+    pub fn cycle_suggestion(&mut self, backwards: bool) {
+        let n = self.suggestions.len();
+        if !self.suggestions_visible || n == 0 {
+            return;
+        }
+        self.suggestions_idx = if backwards {
+            (self.suggestions_idx + n - 1) % n
+        } else {
+            (self.suggestions_idx + 1) % n
+        };
+    }
+
+    pub fn clear_suggestions(&mut self) {
+        self.suggestions.clear();
+        self.suggestions_idx = 0;
+        self.suggestions_visible = false;
+    }
+
+    /// The typed path, when it is an existing directory.
+    pub fn validate(&self) -> Result<PathBuf> {
+        let path = NewSessionDialog::expand_home(self.path.text());
+        if !path.is_dir() {
+            return Err(crate::Error::InvalidInput(format!(
+                "Not a directory: {}",
+                path.display()
+            )));
+        }
+        Ok(path)
+    }
+}
+
+/// A session the forward-output picker offers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForwardTarget {
+    pub id: String,
+    pub title: String,
+    pub running: bool,
+}
+
+/// Picker for the "forward output" action (F): the source session's recent
+/// output is typed into the chosen target.
+#[derive(Debug, Clone)]
+pub struct ForwardOutputDialog {
+    pub source_id: String,
+    pub source_title: String,
+    pub targets: Vec<ForwardTarget>,
+    pub selected: usize,
+    /// Enter on a stopped target asks first; the next Enter starts it.
+    pub confirm_start: bool,
+}
+
+impl ForwardOutputDialog {
+    pub fn selected_target(&self) -> Option<&ForwardTarget> {
+        self.targets.get(self.selected)
+    }
+
+    pub fn move_selection(&mut self, down: bool) {
+        let n = self.targets.len();
+        if n == 0 {
+            return;
+        }
+        self.selected = if down {
+            (self.selected + 1) % n
+        } else {
+            (self.selected + n - 1) % n
+        };
+        self.confirm_start = false;
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -246,6 +548,133 @@ pub struct TagPickerDialog {
     pub selected: usize,
 }
 
+/// Label picker, opened with `c` on a session: a short free-text label and
+/// a color, previewed as the badge the row will show.
+#[derive(Debug, Clone)]
+pub struct LabelPickerDialog {
+    pub session_id: String,
+    pub label: TextInput,
+    /// Index into [`crate::session::LabelColor::ALL`]; one past the end is
+    /// the "clear label" choice.
+    pub choice: usize,
+}
+
+impl LabelPickerDialog {
+    /// The seven colors plus "clear label".
+    pub const CHOICES: usize = crate::session::LabelColor::ALL.len() + 1;
+
+    pub fn clears(&self) -> bool {
+        self.choice >= crate::session::LabelColor::ALL.len()
+    }
+
+    pub fn color(&self) -> crate::session::LabelColor {
+        crate::session::LabelColor::ALL
+            .get(self.choice)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The label and color to store; an empty label when clearing.
+    pub fn result(&self) -> (String, crate::session::LabelColor) {
+        if self.clears() {
+            (String::new(), crate::session::LabelColor::default())
+        } else {
+            (self.label.text().trim().to_string(), self.color())
+        }
+    }
+}
+
+/// Fork lineage overlay: the fork tree around one session.
+#[derive(Debug, Clone)]
+pub struct LineageDialog {
+    pub focus_id: String,
+    pub rows: Vec<crate::session::lineage::LineageRow>,
+    pub selected: usize,
+}
+
+/// Sessions waiting for input, longest wait first (`w`). The rows come
+/// from the live waiting queue on every draw; only the highlight is kept.
+#[derive(Debug, Clone, Default)]
+pub struct WaitingQueueDialog {
+    pub selected_id: Option<String>,
+}
+
+impl WaitingQueueDialog {
+    /// Row of the highlighted session in `queue`, or the first row once it
+    /// has left the queue.
+    pub fn selected_index(&self, queue: &[&crate::session::Instance]) -> usize {
+        self.selected_id
+            .as_deref()
+            .and_then(|id| queue.iter().position(|s| s.id == id))
+            .unwrap_or(0)
+    }
+}
+
+/// Offered when an attach returns and its session is gone, whether the
+/// attach failed or the agent exited while attached. Enter restarts it.
+#[derive(Debug, Clone)]
+pub struct RestartExitedDialog {
+    pub session_id: String,
+    pub title: String,
+    /// The attach itself failed, rather than the session ending during it.
+    pub attach_failed: bool,
+}
+
+/// Confirms a wrap-up (`E`): each running agent is asked to finish, then
+/// its session is stopped, as `agent-hand wrapup` does.
+#[derive(Debug, Clone)]
+pub struct WrapupConfirmDialog {
+    /// Only this group and its subgroups; everything when None.
+    pub group: Option<String>,
+    /// Running sessions it would touch.
+    pub count: usize,
+}
+
+/// Profile picker (`P`). Enter reloads the TUI on the highlighted profile.
+#[derive(Debug, Clone)]
+pub struct ProfilePickerDialog {
+    pub profiles: Vec<String>,
+    /// The profile the TUI is showing.
+    pub current: String,
+    pub selected: usize,
+}
+
+/// Recent info-bar messages (`!`), newest first.
+#[derive(Debug, Clone, Default)]
+pub struct MessageLogDialog {
+    pub selected: usize,
+}
+
+/// One rebindable key in the rebind dialog.
+#[derive(Debug, Clone)]
+pub struct RebindRow {
+    pub target: crate::config::RebindTarget,
+    /// The current binding as it is written in the config.
+    pub current: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RebindStage {
+    Browse,
+    /// Waiting for the new key.
+    Capture,
+    /// A captured key waiting for confirmation, with the conflicts it causes.
+    Confirm {
+        key: crate::config::KeySpec,
+        value: String,
+        conflicts: Vec<String>,
+    },
+}
+
+/// Interactive rebinding, opened with `e` from the help screen. Confirmed
+/// keys are written into the config file.
+#[derive(Debug, Clone)]
+pub struct RebindDialog {
+    pub rows: Vec<RebindRow>,
+    pub selected: usize,
+    pub stage: RebindStage,
+}
+
 #[derive(Debug, Clone)]
 pub enum Dialog {
     NewSession(NewSessionDialog),
@@ -255,8 +684,20 @@ pub enum Dialog {
     CreateGroup(CreateGroupDialog),
     MoveGroup(MoveGroupDialog),
     RenameGroup(RenameGroupDialog),
+    GroupNotes(GroupNotesDialog),
+    ReparentGroup(ReparentGroupDialog),
     RenameSession(RenameSessionDialog),
+    RepairPath(RepairPathDialog),
+    ForwardOutput(ForwardOutputDialog),
     TagPicker(TagPickerDialog),
+    LabelPicker(LabelPickerDialog),
+    Lineage(LineageDialog),
+    WaitingQueue(WaitingQueueDialog),
+    RestartExited(RestartExitedDialog),
+    WrapupConfirm(WrapupConfirmDialog),
+    ProfilePicker(ProfilePickerDialog),
+    MessageLog(MessageLogDialog),
+    Rebind(RebindDialog),
     QuitConfirm,
     Settings(SettingsDialog),
     #[cfg(feature = "pro")]
@@ -290,6 +731,17 @@ pub enum Dialog {
     BehaviorAnalysis(BehaviorAnalysisDialog),
 }
 
+impl Dialog {
+    /// Key for the persisted size nudge, for dialogs with fitted geometry.
+    pub fn size_key(&self) -> Option<&'static str> {
+        match self {
+            Dialog::NewSession(_) => Some(super::dialog_frame::NEW_SESSION),
+            Dialog::Fork(_) => Some(super::dialog_frame::FORK),
+            _ => None,
+        }
+    }
+}
+
 // ── Pro/Max dialog struct definitions in pro/src/ui/dialogs*.rs ──
 
 // ── Settings Dialog ──────────────────────────────────────────────────
@@ -937,6 +1389,9 @@ impl NewSessionDialog {
         let mut d = Self {
             path: TextInput::with_text(default_path.to_string_lossy().to_string()),
             title: TextInput::new(),
+            tool: NewSessionTool::Shell,
+            command: TextInput::new(),
+            pre_command: TextInput::new(),
             group_path: TextInput::with_text(default_group),
             field: NewSessionField::Path,
             group_all_groups: all_groups,
@@ -947,18 +1402,48 @@ impl NewSessionDialog {
             path_suggestions_visible: false,
             path_dirty: false,
             path_last_edit: std::time::Instant::now(),
+            templates: Vec::new(),
+            template: None,
+            error: None,
         };
         d.update_group_matches();
         d
     }
 
+    /// Apply the next saved template: title pattern, tool, command and
+    /// group. `{dirname}` stays in the title until the session is created.
+    pub fn cycle_template(&mut self) {
+        if self.templates.is_empty() {
+            return;
+        }
+        let next = self.template.map_or(0, |i| (i + 1) % self.templates.len());
+        let t = &self.templates[next];
+        self.title.set_text(t.title.clone());
+        self.tool = NewSessionTool::from_tool(t.tool);
+        self.command.set_text(t.command.clone());
+        self.group_path.set_text(t.group_path.clone());
+        self.template = Some(next);
+        self.update_group_matches();
+    }
+
+    /// Select the next (or previous) tool. The command follows the tool
+    /// unless it was edited away from the old tool's default.
+    pub fn cycle_tool(&mut self, backwards: bool) {
+        let command = self.command.text().trim();
+        let untouched = command.is_empty() || command == self.tool.default_command();
+        self.tool = self.tool.cycle(backwards);
+        if untouched {
+            self.command.set_text(self.tool.default_command());
+        }
+    }
+
     pub fn clear_path_suggestions(&mut self) {
         self.path_suggestions.clear();
         self.path_suggestions_idx = 0;
         self.path_suggestions_visible = false;
     }
 
-    fn expand_home(path: &str) -> PathBuf {
+    pub fn expand_home(path: &str) -> PathBuf {
         let trimmed = path.trim();
         if trimmed == "~" {
             return dirs::home_dir().unwrap_or_else(|| PathBuf::from(trimmed));
@@ -1037,22 +1522,30 @@ impl NewSessionDialog {
     }
 
     pub fn update_path_suggestions(&mut self) {
-        // Compute suggestions once
         self.clear_path_suggestions();
+        let suggestions = Self::suggest_paths(self.path.text());
+        if suggestions.is_empty() {
+            return;
+        }
+
+        // Show suggestion list (do not auto-apply arbitrary choice)
+        self.path_suggestions = suggestions;
+        self.path_suggestions_visible = true;
+    }
 
-        let expanded = Self::expand_home(self.path.text());
+    /// Completions for a partly typed path: entries of its directory that
+    /// fuzzy-match the last component, most recently modified first.
+    /// Directories end in `/`.
+    pub fn suggest_paths(input: &str) -> Vec<String> {
+        let expanded = Self::expand_home(input);
         let raw = expanded.to_string_lossy().to_string();
-        let (dir, prefix, base_has_slash) = match raw.rfind('/') {
-            Some(idx) => (
-                PathBuf::from(&raw[..=idx]),
-                raw[idx + 1..].to_string(),
-                true,
-            ),
-            None => (PathBuf::from("./"), raw.clone(), false),
+        let (dir, prefix) = match raw.rfind('/') {
+            Some(idx) => (PathBuf::from(&raw[..=idx]), raw[idx + 1..].to_string()),
+            None => (PathBuf::from("./"), raw.clone()),
         };
 
         let Ok(rd) = std::fs::read_dir(&dir) else {
-            return;
+            return Vec::new();
         };
 
         use std::time::UNIX_EPOCH;
@@ -1090,19 +1583,7 @@ impl NewSessionDialog {
         }
 
         matches.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)).then(a.2.cmp(&b.2)));
-        if matches.is_empty() {
-            return;
-        }
-
-        // Show suggestion list (do not auto-apply arbitrary choice)
-        self.path_suggestions = matches.into_iter().map(|(_, _, p)| p).collect();
-        self.path_suggestions_visible = true;
-        self.path_suggestions_idx = 0;
-
-        // If the user didn't type a slash and is completing in CWD, keep relative feeling.
-        if !base_has_slash && self.path.text().starts_with('~') {
-            // leave as-is
-        }
+        matches.into_iter().map(|(_, _, p)| p).collect()
     }
 
     pub fn apply_selected_path_suggestion(&mut self) {
@@ -1138,6 +1619,32 @@ impl NewSessionDialog {
         }
         Ok(project_path)
     }
+
+    /// The session the dialog describes, with the project directory
+    /// created if needed. Storing it (and creating its group) is left to
+    /// the caller.
+    pub fn build_instance(&self) -> Result<crate::session::Instance> {
+        let project_path = self.validate()?;
+        let title = if self.title.text().trim().is_empty() {
+            project_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("Untitled")
+                .to_string()
+        } else {
+            crate::session::templates::expand_title(self.title.text().trim(), &project_path)
+        };
+
+        let mut instance = crate::session::Instance::new(title, project_path);
+        let group_path = self.group_path.text().trim();
+        if !group_path.is_empty() {
+            instance.group_path = group_path.to_string();
+        }
+        instance.command = self.command.text().trim().to_string();
+        instance.tool = crate::tmux::Tool::from_command(&instance.command);
+        instance.pre_command = self.pre_command.text().trim().to_string();
+        Ok(instance)
+    }
 }
 
 /// Dialog for browsing and installing sound packs from the registry.
@@ -1184,3 +1691,151 @@ impl PackBrowserDialog {
 }
 
 // ── Skills Manager + AI dialogs — definitions in pro/src/ui/dialogs*.rs ──
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tool_cycling_fills_command_until_edited() {
+        let mut d = NewSessionDialog::new(PathBuf::from("/tmp"), String::new(), Vec::new());
+        d.cycle_tool(false);
+        assert_eq!(d.tool, NewSessionTool::Claude);
+        assert_eq!(d.command.text(), "claude");
+
+        d.cycle_tool(false);
+        assert_eq!(d.command.text(), "codex");
+
+        d.command.set_text("codex --full-auto");
+        d.cycle_tool(false);
+        assert_eq!(d.tool, NewSessionTool::Gemini);
+        assert_eq!(d.command.text(), "codex --full-auto");
+
+        d.command.set_text("");
+        d.cycle_tool(true);
+        assert_eq!(d.tool, NewSessionTool::Codex);
+        assert_eq!(d.command.text(), "codex");
+    }
+
+    #[test]
+    fn built_instance_defaults_title_and_detects_tool() {
+        let root = tempfile::tempdir().unwrap();
+        let project = root.path().join("api");
+        let mut d = NewSessionDialog::new(project.clone(), "work".into(), Vec::new());
+        d.cycle_tool(false);
+
+        let inst = d.build_instance().unwrap();
+        assert!(project.is_dir());
+        assert_eq!(inst.title, "api");
+        assert_eq!(inst.group_path, "work");
+        assert_eq!(inst.command, "claude");
+        assert_eq!(inst.tool, crate::tmux::Tool::Claude);
+
+        d.title.set_text("{dirname} review");
+        assert_eq!(d.build_instance().unwrap().title, "api review");
+    }
+
+    #[test]
+    fn repair_path_completes_and_validates() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir(root.path().join("moved-project")).unwrap();
+        std::fs::create_dir(root.path().join("other")).unwrap();
+        let gone = root.path().join("project");
+        let mut d = RepairPathDialog::new("id".into(), "api".into(), gone.clone());
+        assert!(d.validate().is_err());
+
+        // "project" fuzzy-matches only "moved-project", which is applied.
+        d.complete();
+        assert!(!d.suggestions_visible);
+        let fixed = format!("{}/", root.path().join("moved-project").display());
+        assert_eq!(d.path.text(), fixed);
+        assert_eq!(d.validate().unwrap(), PathBuf::from(&fixed));
+
+        d.path.set_text(format!("{}/", root.path().display()));
+        d.complete();
+        assert!(d.suggestions_visible);
+        assert_eq!(d.suggestions.len(), 2);
+        d.cycle_suggestion(true);
+        assert_eq!(d.suggestions_idx, 1);
+    }
+
+    #[test]
+    fn templates_cycle_and_fill_fields() {
+        use crate::session::templates::SessionTemplate;
+        let template = |name: &str, tool: crate::tmux::Tool, command: &str| SessionTemplate {
+            name: name.into(),
+            title: "{dirname} review".into(),
+            tool,
+            command: command.into(),
+            group_path: "work".into(),
+        };
+        let mut d = NewSessionDialog::new(PathBuf::from("/tmp"), String::new(), vec!["work".into()]);
+        d.cycle_template();
+        assert_eq!(d.template, None);
+
+        d.templates = vec![
+            template("a", crate::tmux::Tool::Claude, "claude --continue"),
+            template("b", crate::tmux::Tool::Shell, ""),
+        ];
+        d.cycle_template();
+        assert_eq!(d.template, Some(0));
+        assert_eq!(d.title.text(), "{dirname} review");
+        assert_eq!(d.tool, NewSessionTool::Claude);
+        assert_eq!(d.command.text(), "claude --continue");
+        assert_eq!(d.group_path.text(), "work");
+
+        d.cycle_template();
+        d.cycle_template();
+        assert_eq!(d.template, Some(0));
+    }
+
+    #[test]
+    fn fields_advance_through_tool_and_command() {
+        let order: Vec<_> = std::iter::successors(Some(NewSessionField::Path), |f| {
+            Some(f.next(true)).filter(|n| n != f)
+        })
+        .collect();
+        assert_eq!(
+            order,
+            [
+                NewSessionField::Path,
+                NewSessionField::Title,
+                NewSessionField::Tool,
+                NewSessionField::Command,
+                NewSessionField::Pre,
+                NewSessionField::Group,
+            ]
+        );
+        assert_eq!(NewSessionField::Title.next(false), NewSessionField::Title);
+    }
+
+    #[test]
+    fn fork_tab_order_includes_branch_only_with_worktree() {
+        let mut d = ForkDialog {
+            parent_session_id: "p".to_string(),
+            project_path: PathBuf::from("/w/api"),
+            title: TextInput::with_text("api (fork)".to_string()),
+            group_path: TextInput::new(),
+            field: ForkField::Context,
+            copy_context: false,
+            context_copy: Err("not claude".to_string()),
+            worktree: false,
+            branch: TextInput::with_text("feat/x".to_string()),
+            worktree_root: PathBuf::from("/w"),
+            error: None,
+        };
+        d.next_field();
+        assert_eq!(d.field, ForkField::Worktree);
+        d.next_field();
+        assert_eq!(d.field, ForkField::Title);
+        assert_eq!(d.worktree_dest(), None);
+
+        d.worktree = true;
+        d.field = ForkField::Worktree;
+        d.next_field();
+        assert_eq!(d.field, ForkField::Branch);
+        d.next_field();
+        assert_eq!(d.field, ForkField::Title);
+        assert_eq!(d.worktree_dest(), Some(PathBuf::from("/w/feat-x")));
+    }
+}
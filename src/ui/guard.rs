@@ -0,0 +1,214 @@
+//! Which normal-mode actions are available right now.
+//!
+//! Every mode that takes actions away (simple mode, and running without
+//! tmux) is a row in one matrix, so key handling, hints, help and the CLI's
+//! tmux check all ask the same question: [`Capabilities::check`] with the
+//! action's keybinding name.
+
+use crate::config::UiMode;
+
+/// Actions simple mode keeps: moving around, attaching, starting, creating,
+/// deleting, searching, help, settings, the layout, and the way back to
+/// full mode.
+const SIMPLE_ACTIONS: &[&str] = &[
+    "quit",
+    "settings",
+    "up",
+    "down",
+    "half_page_down",
+    "half_page_up",
+    "select",
+    "start",
+    "collapse",
+    "expand",
+    "toggle_group",
+    "refresh",
+    "new_session",
+    "delete",
+    "undo",
+    "search",
+    "help",
+    "cycle_layout",
+    "jump_back",
+    "toggle_ui_mode",
+];
+
+/// Actions that run tmux: attaching, and starting or stopping sessions.
+const TMUX_ACTIONS: &[&str] = &[
+    "select",
+    "start",
+    "stop",
+    "restart",
+    "resume",
+    "compare",
+    "forward_output",
+    "wrapup",
+];
+
+/// Why an action is unavailable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Restriction {
+    /// Hidden until the user switches to full mode.
+    SimpleMode,
+    /// tmux is not installed or not on PATH; everything that runs it is
+    /// refused until it is back.
+    NoTmux,
+}
+
+impl Restriction {
+    fn allows(self, action: &str) -> bool {
+        match self {
+            Restriction::SimpleMode => SIMPLE_ACTIONS.contains(&action),
+            Restriction::NoTmux => !TMUX_ACTIONS.contains(&action),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Denied {
+    pub action: &'static str,
+    pub restriction: Restriction,
+}
+
+impl Denied {
+    /// Toast text, e.g. "Fork is available in full mode — press F9".
+    pub fn message(&self, is_zh: bool) -> String {
+        let label = action_label(self.action);
+        match self.restriction {
+            Restriction::SimpleMode if is_zh => format!("{label} 仅在完整模式可用 — 按 F9"),
+            Restriction::SimpleMode => format!("{label} is available in full mode — press F9"),
+            Restriction::NoTmux if is_zh => format!("{label} 需要 tmux，但未安装或不在 PATH 中"),
+            Restriction::NoTmux => format!("{label} needs tmux, which is not on PATH"),
+        }
+    }
+}
+
+fn action_label(action: &str) -> String {
+    let words = action.replace('_', " ");
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// The restrictions currently in force.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    restrictions: Vec<Restriction>,
+}
+
+impl Capabilities {
+    pub fn new(mode: UiMode) -> Self {
+        let mut restrictions = Vec::new();
+        if mode == UiMode::Simple {
+            restrictions.push(Restriction::SimpleMode);
+        }
+        Self { restrictions }
+    }
+
+    /// Lift or impose [`Restriction::NoTmux`].
+    pub fn set_tmux_available(&mut self, available: bool) {
+        self.restrictions.retain(|&r| r != Restriction::NoTmux);
+        if !available {
+            self.restrictions.push(Restriction::NoTmux);
+        }
+    }
+
+    pub fn is_restricted(&self, restriction: Restriction) -> bool {
+        self.restrictions.contains(&restriction)
+    }
+
+    pub fn allows(&self, action: &str) -> bool {
+        self.restrictions.iter().all(|r| r.allows(action))
+    }
+
+    pub fn check(&self, action: &'static str) -> Result<(), Denied> {
+        match self.restrictions.iter().find(|r| !r.allows(action)) {
+            Some(&restriction) => Err(Denied { action, restriction }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_mode_allows_everything() {
+        let caps = Capabilities::new(UiMode::Full);
+        for &action in crate::config::NORMAL_ACTION_PRIORITY {
+            assert!(caps.check(action).is_ok(), "{action}");
+        }
+        assert!(caps.allows("relationships"));
+    }
+
+    #[test]
+    fn simple_mode_matrix() {
+        let caps = Capabilities::new(UiMode::Simple);
+        let cases = [
+            ("up", true),
+            ("select", true),
+            ("start", true),
+            ("new_session", true),
+            ("delete", true),
+            ("undo", true),
+            ("search", true),
+            ("help", true),
+            ("toggle_ui_mode", true),
+            ("fork", false),
+            ("create_group", false),
+            ("move", false),
+            ("canvas_toggle", false),
+            ("notify_siblings", false),
+            ("share", false),
+        ];
+        for (action, allowed) in cases {
+            assert_eq!(caps.allows(action), allowed, "{action}");
+        }
+    }
+
+    #[test]
+    fn simple_mode_actions_are_real_bindings() {
+        for action in SIMPLE_ACTIONS {
+            assert!(crate::config::NORMAL_ACTION_PRIORITY.contains(action), "{action}");
+        }
+    }
+
+    #[test]
+    fn without_tmux_only_tmux_actions_are_refused() {
+        let mut caps = Capabilities::new(UiMode::Full);
+        caps.set_tmux_available(false);
+        assert!(caps.allows("new_session"));
+        assert!(caps.allows("rename"));
+        let denied = caps.check("start").unwrap_err();
+        assert_eq!(denied.restriction, Restriction::NoTmux);
+        assert_eq!(
+            denied.message(false),
+            "Start needs tmux, which is not on PATH"
+        );
+
+        caps.set_tmux_available(true);
+        assert!(caps.check("start").is_ok());
+        assert_eq!(caps, Capabilities::new(UiMode::Full));
+    }
+
+    #[test]
+    fn tmux_actions_are_real_bindings() {
+        for action in TMUX_ACTIONS {
+            assert!(crate::config::NORMAL_ACTION_PRIORITY.contains(action), "{action}");
+        }
+    }
+
+    #[test]
+    fn denial_points_at_full_mode() {
+        let caps = Capabilities::new(UiMode::Simple);
+        let denied = caps.check("fork").unwrap_err();
+        assert_eq!(denied.restriction, Restriction::SimpleMode);
+        assert_eq!(denied.message(false), "Fork is available in full mode — press F9");
+
+        let denied = caps.check("create_group").unwrap_err();
+        assert!(denied.message(false).starts_with("Create group "));
+    }
+}
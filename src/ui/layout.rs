@@ -0,0 +1,157 @@
+//! Main-screen layouts: the session list beside the preview, or either one
+//! on its own.
+//!
+//! The choice is cycled from normal mode and kept in `ui_state.json`. With
+//! the preview maximized the list shrinks to a one-line summary of the
+//! selection, so moving the selection still has visible feedback.
+
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use serde::{Deserialize, Serialize};
+
+/// Lines of history captured for the preview in the split layout.
+pub const PREVIEW_CAPTURE_LINES: usize = 120;
+
+/// Height of the selection summary, borders included.
+pub const SUMMARY_HEIGHT: u16 = 3;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MainLayout {
+    /// List on the left, preview on the right.
+    #[default]
+    Split,
+    /// Preview across the whole width under a summary of the selection.
+    Preview,
+    /// List across the whole width; no preview.
+    List,
+}
+
+/// Where each part of the main screen goes; None when it is not shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MainAreas {
+    pub list: Option<Rect>,
+    pub summary: Option<Rect>,
+    pub preview: Option<Rect>,
+}
+
+impl MainLayout {
+    /// The layout the cycle key switches to.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Split => Self::Preview,
+            Self::Preview => Self::List,
+            Self::List => Self::Split,
+        }
+    }
+
+    pub fn shows_preview(self) -> bool {
+        self != Self::List
+    }
+
+    /// Split `area` into the parts this layout shows.
+    pub fn areas(self, area: Rect) -> MainAreas {
+        match self {
+            Self::Split => {
+                let cols = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
+                    .split(area);
+                MainAreas {
+                    list: Some(cols[0]),
+                    summary: None,
+                    preview: Some(cols[1]),
+                }
+            }
+            Self::Preview => {
+                let rows = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(SUMMARY_HEIGHT), Constraint::Min(0)])
+                    .split(area);
+                MainAreas {
+                    list: None,
+                    summary: Some(rows[0]),
+                    preview: Some(rows[1]),
+                }
+            }
+            Self::List => MainAreas {
+                list: Some(area),
+                summary: None,
+                preview: None,
+            },
+        }
+    }
+
+    /// Lines of history to capture for the preview on a terminal `rows`
+    /// tall. A maximized preview keeps at least two screens of it.
+    pub fn capture_lines(self, rows: u16) -> usize {
+        match self {
+            Self::Preview => PREVIEW_CAPTURE_LINES.max(usize::from(rows) * 2),
+            Self::Split | Self::List => PREVIEW_CAPTURE_LINES,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: Rect = Rect {
+        x: 0,
+        y: 3,
+        width: 100,
+        height: 40,
+    };
+
+    #[test]
+    fn cycle_visits_every_layout() {
+        let mut layout = MainLayout::default();
+        let mut seen = vec![layout];
+        for _ in 0..2 {
+            layout = layout.next();
+            seen.push(layout);
+        }
+        assert_eq!(
+            seen,
+            [MainLayout::Split, MainLayout::Preview, MainLayout::List]
+        );
+        assert_eq!(layout.next(), MainLayout::Split);
+    }
+
+    #[test]
+    fn areas_cover_the_main_screen() {
+        let split = MainLayout::Split.areas(AREA);
+        let (list, preview) = (split.list.unwrap(), split.preview.unwrap());
+        assert_eq!((list.width + preview.width, list.height), (100, 40));
+        assert_eq!(split.summary, None);
+
+        let zoomed = MainLayout::Preview.areas(AREA);
+        assert_eq!(zoomed.list, None);
+        assert_eq!(zoomed.summary, Some(Rect { height: 3, ..AREA }));
+        assert_eq!(
+            zoomed.preview,
+            Some(Rect {
+                y: 6,
+                height: 37,
+                ..AREA
+            })
+        );
+
+        let list_only = MainLayout::List.areas(AREA);
+        assert_eq!((list_only.list, list_only.preview), (Some(AREA), None));
+    }
+
+    #[test]
+    fn maximized_preview_captures_more() {
+        assert_eq!(MainLayout::Split.capture_lines(200), PREVIEW_CAPTURE_LINES);
+        assert_eq!(MainLayout::Preview.capture_lines(40), PREVIEW_CAPTURE_LINES);
+        assert_eq!(MainLayout::Preview.capture_lines(90), 180);
+    }
+
+    #[test]
+    fn layout_names_are_stable() {
+        assert_eq!(
+            serde_json::to_string(&MainLayout::Preview).unwrap(),
+            "\"preview\""
+        );
+    }
+}
@@ -1,21 +1,42 @@
 mod app;
+pub mod attach_history;
+mod cancel;
 pub mod canvas;
+pub mod dialog_frame;
 mod dialogs;
 mod events;
+pub mod guard;
 mod input;
+pub mod layout;
+pub mod mouse;
+pub mod perf;
+pub mod preview_history;
+pub mod preview_view;
+pub mod query;
 mod render;
+mod selection;
+mod sort;
 mod switcher;
+pub mod term;
+pub mod text;
 pub mod theme;
 pub mod transition;
+pub mod tree;
+mod window;
 
 pub use app::App;
+pub use cancel::{purge, CancelRegistry, CancelToken, SessionKeyed};
 pub use dialogs::{
     CreateGroupDialog,
     DeleteConfirmDialog, DeleteGroupChoice, DeleteGroupDialog, Dialog,
-    ForkDialog, ForkField, MoveGroupDialog, NewSessionDialog,
-    NewSessionField, RenameGroupDialog, RenameSessionDialog, SessionEditField,
+    ForkDialog, ForkField, ForwardOutputDialog, ForwardTarget, GroupNotesDialog, LabelPickerDialog,
+    LineageDialog,
+    MoveGroupDialog, NewSessionDialog,
+    MessageLogDialog, NewSessionField, ProfilePickerDialog, RebindDialog, RebindRow, RebindStage, RenameGroupDialog,
+    ReparentGroupDialog, RestartExitedDialog,
+    RenameSessionDialog, RepairPathDialog, SessionEditField,
     SettingsDialog, SettingsField, SettingsTab,
-    TagPickerDialog, TagSpec,
+    TagPickerDialog, TagSpec, WaitingQueueDialog, WrapupConfirmDialog,
 };
 
 #[cfg(feature = "pro")]
@@ -31,8 +52,11 @@ pub use dialogs::{
 pub use dialogs::{AiAnalysisDialog, AiAnalysisMode, BehaviorAnalysisDialog};
 
 pub use input::TextInput;
+pub use selection::{relocate_selection, SelectionIdentity};
+pub use sort::{compare_sessions, move_within_group, should_resort, ResortCheck, SortChanges};
 pub use switcher::run_switcher;
 pub use transition::StartupPhase;
+pub use window::{list_window, page_down, page_up, truncation_notice, ListWindow};
 
 use crossterm::event::{KeyCode, KeyModifiers};
 
@@ -80,4 +104,28 @@ pub enum TreeItem {
         rel_id: String,
         depth: usize,
     },
+    /// Header of the "unmanaged agents detected" section at the end of the
+    /// tree, shown while discovery has candidates.
+    UnmanagedHeader {
+        count: usize,
+    },
+    /// An agent process running outside agent-hand, under that header.
+    Unmanaged {
+        pid: u32,
+    },
+}
+
+/// Terminal color for a session label badge.
+pub(crate) fn label_fg(color: crate::session::LabelColor) -> ratatui::style::Color {
+    use crate::session::LabelColor;
+    use ratatui::style::Color;
+    match color {
+        LabelColor::Gray => Color::DarkGray,
+        LabelColor::Magenta => Color::Magenta,
+        LabelColor::Cyan => Color::Cyan,
+        LabelColor::Green => Color::Green,
+        LabelColor::Yellow => Color::Yellow,
+        LabelColor::Red => Color::Red,
+        LabelColor::Blue => Color::Blue,
+    }
 }
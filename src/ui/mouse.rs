@@ -0,0 +1,116 @@
+//! Mouse hit-testing: where the last frame put the session tree, the
+//! preview and the clickable parts of a dialog, and telling a double click
+//! from two single ones.
+//!
+//! `render::draw` fills a [`HitAreas`] on every frame; the app keeps the
+//! latest one and looks mouse events up in it, so clicks always land on
+//! what is on screen rather than on a guess at the layout.
+
+use std::time::{Duration, Instant};
+
+use ratatui::layout::{Margin, Position, Rect};
+
+use super::DeleteGroupChoice;
+
+/// Two clicks on the same row within this long are a double click.
+pub const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// What clicking part of a dialog does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DialogTarget {
+    Confirm,
+    Cancel,
+    ToggleKillTmux,
+    ToggleRemoveWorktree,
+    GroupChoice(DeleteGroupChoice),
+}
+
+/// Screen regions of the last frame that react to the mouse.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HitAreas {
+    /// The session tree, borders included.
+    pub tree: Option<Rect>,
+    /// Index of the first tree row on screen.
+    pub tree_offset: usize,
+    /// The preview pane, borders included. Unset in Pro builds, where the
+    /// canvas takes its place.
+    pub preview: Option<Rect>,
+    /// Clickable parts of the open dialog.
+    pub dialog: Vec<(Rect, DialogTarget)>,
+}
+
+impl HitAreas {
+    /// Index of the tree row at (`col`, `row`), if that is inside the tree's
+    /// borders. It may be past the last row.
+    pub fn tree_row(&self, col: u16, row: u16) -> Option<usize> {
+        let inner = self.tree?.inner(Margin::new(1, 1));
+        inner
+            .contains(Position::new(col, row))
+            .then(|| self.tree_offset + usize::from(row - inner.y))
+    }
+
+    pub fn over_preview(&self, col: u16, row: u16) -> bool {
+        self.preview
+            .is_some_and(|area| area.contains(Position::new(col, row)))
+    }
+
+    pub fn dialog_target(&self, col: u16, row: u16) -> Option<DialogTarget> {
+        self.dialog
+            .iter()
+            .find(|(area, _)| area.contains(Position::new(col, row)))
+            .map(|&(_, target)| target)
+    }
+}
+
+/// The last click on a tree row, to spot the second half of a double click.
+#[derive(Debug, Clone, Default)]
+pub struct ClickTracker {
+    last: Option<(usize, Instant)>,
+}
+
+impl ClickTracker {
+    /// Record a click on tree row `row`; true when it completes a double
+    /// click. A third click starts over rather than counting again.
+    pub fn click(&mut self, row: usize, now: Instant) -> bool {
+        match self.last.take() {
+            Some((last, at)) if last == row && now.duration_since(at) <= DOUBLE_CLICK => true,
+            _ => {
+                self.last = Some((row, now));
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tree_rows_skip_the_border_and_follow_the_scroll() {
+        let hits = HitAreas {
+            tree: Some(Rect::new(0, 3, 40, 10)),
+            tree_offset: 5,
+            ..HitAreas::default()
+        };
+        assert_eq!(hits.tree_row(1, 4), Some(5));
+        assert_eq!(hits.tree_row(10, 7), Some(8));
+        assert_eq!(hits.tree_row(10, 3), None);
+        assert_eq!(hits.tree_row(0, 7), None);
+        assert_eq!(hits.tree_row(10, 12), None);
+        assert!(!hits.over_preview(50, 5));
+    }
+
+    #[test]
+    fn double_clicks_need_the_same_row_soon_after() {
+        let start = Instant::now();
+        let mut clicks = ClickTracker::default();
+        assert!(!clicks.click(2, start));
+        assert!(clicks.click(2, start + Duration::from_millis(300)));
+        assert!(!clicks.click(2, start + Duration::from_millis(350)));
+
+        assert!(!clicks.click(3, start + Duration::from_millis(400)));
+        assert!(!clicks.click(4, start + Duration::from_millis(450)));
+        assert!(!clicks.click(4, start + Duration::from_millis(900)));
+    }
+}
@@ -0,0 +1,488 @@
+//! Frame-phase latency: fixed-bucket histograms over a rolling minute, the
+//! data behind the perf overlay (F11), and the slow-phase warnings.
+//!
+//! Timing is sampled, not continuous. A frame is sampled while the overlay
+//! is open, and otherwise during a short burst every few seconds; outside
+//! those frames [`Perf::start`] returns `None` and [`Perf::finish`] does
+//! nothing.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::tmux::calls::CallCounts;
+
+/// Upper bounds (inclusive) of the histogram buckets, in milliseconds. One
+/// more bucket catches everything slower.
+pub const BUCKET_BOUNDS_MS: [u64; 12] = [1, 2, 5, 10, 20, 50, 100, 200, 500, 1000, 2000, 5000];
+const BUCKETS: usize = BUCKET_BOUNDS_MS.len() + 1;
+
+/// How far back the overlay and the warnings look.
+pub const WINDOW: Duration = Duration::from_secs(60);
+const WINDOW_SECS: usize = 60;
+
+/// With the overlay closed, frames are timed for `SAMPLE_BURST` out of every
+/// `SAMPLE_PERIOD`. Long enough to catch the 1s status and 2s cache
+/// refreshes.
+const SAMPLE_PERIOD: Duration = Duration::from_secs(6);
+const SAMPLE_BURST: Duration = Duration::from_secs(2);
+/// Thresholds are checked at most this often.
+const EVAL_EVERY: Duration = Duration::from_secs(5);
+/// A phase with fewer samples in the window is never reported as slow.
+const MIN_SAMPLES: u32 = 5;
+/// tmux call counts are snapshotted this often for the per-minute figures.
+const CALLS_SNAPSHOT_EVERY: Duration = Duration::from_secs(10);
+
+/// A timed part of the main loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Handling one terminal event.
+    Event,
+    /// `refresh_statuses`: hook events and pane probes.
+    Status,
+    /// The tmux session cache refresh.
+    Cache,
+    /// Capturing the selected session's pane for the preview.
+    Preview,
+    /// `terminal.draw`.
+    Draw,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 5] = [
+        Phase::Event,
+        Phase::Status,
+        Phase::Cache,
+        Phase::Preview,
+        Phase::Draw,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Event => "event",
+            Phase::Status => "status",
+            Phase::Cache => "cache",
+            Phase::Preview => "preview",
+            Phase::Draw => "draw",
+        }
+    }
+
+    /// Phases that wait on tmux or the filesystem, held to the probe
+    /// threshold rather than the draw one.
+    pub fn is_probe(self) -> bool {
+        matches!(self, Phase::Status | Phase::Cache | Phase::Preview)
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// The bucket `d` falls into.
+pub fn bucket_index(d: Duration) -> usize {
+    let micros = d.as_micros();
+    BUCKET_BOUNDS_MS
+        .iter()
+        .position(|&ms| micros <= u128::from(ms) * 1000)
+        .unwrap_or(BUCKETS - 1)
+}
+
+/// Sample counts per bucket, plus the largest sample.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Histogram {
+    counts: [u32; BUCKETS],
+    total: u32,
+    max: Duration,
+}
+
+impl Histogram {
+    pub fn record(&mut self, d: Duration) {
+        self.counts[bucket_index(d)] += 1;
+        self.total += 1;
+        self.max = self.max.max(d);
+    }
+
+    pub fn merge(&mut self, other: &Histogram) {
+        for (mine, theirs) in self.counts.iter_mut().zip(other.counts) {
+            *mine += theirs;
+        }
+        self.total += other.total;
+        self.max = self.max.max(other.max);
+    }
+
+    pub fn count(&self) -> u32 {
+        self.total
+    }
+
+    pub fn max(&self) -> Option<Duration> {
+        (self.total > 0).then_some(self.max)
+    }
+
+    /// The `q` quantile (0.0..=1.0), as the upper bound of the bucket it
+    /// lands in. Never more than the largest sample, so the overflow bucket
+    /// reports that.
+    pub fn percentile(&self, q: f64) -> Option<Duration> {
+        if self.total == 0 {
+            return None;
+        }
+        let rank = ((q.clamp(0.0, 1.0) * f64::from(self.total)).ceil() as u32).max(1);
+        let mut seen = 0;
+        for (i, &count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                let bound = BUCKET_BOUNDS_MS
+                    .get(i)
+                    .map_or(self.max, |&ms| Duration::from_millis(ms));
+                return Some(bound.min(self.max));
+            }
+        }
+        Some(self.max)
+    }
+}
+
+/// One histogram per second over the last [`WINDOW`]. Slots are reused
+/// as time moves on, so memory stays fixed.
+#[derive(Debug, Clone)]
+pub struct RollingHistogram {
+    origin: Instant,
+    slots: [Histogram; WINDOW_SECS],
+    /// The second (since `origin`) each slot holds.
+    slot_secs: [u64; WINDOW_SECS],
+}
+
+impl RollingHistogram {
+    pub fn new(origin: Instant) -> Self {
+        Self {
+            origin,
+            slots: [Histogram::default(); WINDOW_SECS],
+            slot_secs: [0; WINDOW_SECS],
+        }
+    }
+
+    fn second(&self, now: Instant) -> u64 {
+        now.saturating_duration_since(self.origin).as_secs()
+    }
+
+    pub fn record(&mut self, now: Instant, d: Duration) {
+        let sec = self.second(now);
+        let i = (sec % WINDOW_SECS as u64) as usize;
+        if self.slot_secs[i] != sec {
+            self.slots[i] = Histogram::default();
+            self.slot_secs[i] = sec;
+        }
+        self.slots[i].record(d);
+    }
+
+    /// Everything recorded in the [`WINDOW`] up to `now`.
+    pub fn window(&self, now: Instant) -> Histogram {
+        let sec = self.second(now);
+        let mut merged = Histogram::default();
+        for (slot, &slot_sec) in self.slots.iter().zip(&self.slot_secs) {
+            if slot_sec <= sec && sec - slot_sec < WINDOW_SECS as u64 {
+                merged.merge(slot);
+            }
+        }
+        merged
+    }
+}
+
+/// p95 limits above which a phase is logged as slow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Thresholds {
+    /// Draw and event handling.
+    pub draw: Duration,
+    /// Status, cache and preview refreshes.
+    pub probe: Duration,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            draw: Duration::from_millis(50),
+            probe: Duration::from_millis(200),
+        }
+    }
+}
+
+impl Thresholds {
+    pub fn for_phase(&self, phase: Phase) -> Duration {
+        if phase.is_probe() {
+            self.probe
+        } else {
+            self.draw
+        }
+    }
+}
+
+/// One overlay row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhaseStats {
+    pub phase: Phase,
+    pub samples: u32,
+    pub p50: Option<Duration>,
+    pub p95: Option<Duration>,
+    pub max: Option<Duration>,
+    /// The p95 this phase is held to.
+    pub threshold: Duration,
+}
+
+impl PhaseStats {
+    pub fn is_slow(&self) -> bool {
+        self.p95.is_some_and(|p95| p95 > self.threshold)
+    }
+}
+
+/// What the overlay shows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PerfReport {
+    pub phases: Vec<PhaseStats>,
+    /// tmux subprocesses started over the window.
+    pub calls: CallCounts,
+}
+
+/// Per-phase timings for the TUI, owned by `App`.
+#[derive(Debug)]
+pub struct Perf {
+    phases: [RollingHistogram; Phase::ALL.len()],
+    thresholds: Thresholds,
+    origin: Instant,
+    overlay_open: bool,
+    sampling: bool,
+    last_eval: Option<Instant>,
+    last_warned: [Option<Instant>; Phase::ALL.len()],
+    calls: VecDeque<(Instant, CallCounts)>,
+}
+
+impl Perf {
+    pub fn new(thresholds: Thresholds, now: Instant) -> Self {
+        Self {
+            phases: std::array::from_fn(|_| RollingHistogram::new(now)),
+            thresholds,
+            origin: now,
+            overlay_open: false,
+            sampling: false,
+            last_eval: None,
+            last_warned: [None; Phase::ALL.len()],
+            calls: VecDeque::new(),
+        }
+    }
+
+    pub fn overlay_open(&self) -> bool {
+        self.overlay_open
+    }
+
+    pub fn toggle_overlay(&mut self) {
+        self.overlay_open = !self.overlay_open;
+    }
+
+    /// Decide whether this frame is timed, and on timed frames check the
+    /// thresholds and snapshot tmux call counts when they are due.
+    pub fn begin_frame(&mut self, now: Instant) {
+        let into_period = now.saturating_duration_since(self.origin).as_millis()
+            % SAMPLE_PERIOD.as_millis();
+        self.sampling = self.overlay_open || into_period < SAMPLE_BURST.as_millis();
+        if !self.sampling {
+            return;
+        }
+
+        if self
+            .calls
+            .back()
+            .is_none_or(|(at, _)| now.saturating_duration_since(*at) >= CALLS_SNAPSHOT_EVERY)
+        {
+            self.calls.push_back((now, CallCounts::snapshot()));
+            while self
+                .calls
+                .front()
+                .is_some_and(|(at, _)| now.saturating_duration_since(*at) > WINDOW)
+            {
+                self.calls.pop_front();
+            }
+        }
+
+        if self
+            .last_eval
+            .is_none_or(|at| now.saturating_duration_since(at) >= EVAL_EVERY)
+        {
+            self.last_eval = Some(now);
+            for (phase, p95) in self.slow_phases(now) {
+                tracing::warn!(
+                    "perf: {} p95 {}ms over the last minute (threshold {}ms)",
+                    phase.name(),
+                    p95.as_millis(),
+                    self.thresholds.for_phase(phase).as_millis()
+                );
+            }
+        }
+    }
+
+    /// `Some(now)` on timed frames; pass the result to [`Perf::finish`].
+    pub fn start(&self) -> Option<Instant> {
+        self.sampling.then(Instant::now)
+    }
+
+    pub fn finish(&mut self, phase: Phase, started: Option<Instant>) {
+        if let Some(started) = started {
+            let now = Instant::now();
+            self.record(phase, now, now.saturating_duration_since(started));
+        }
+    }
+
+    pub fn record(&mut self, phase: Phase, now: Instant, d: Duration) {
+        self.phases[phase.index()].record(now, d);
+    }
+
+    /// Phases whose p95 is over their threshold and that have not been
+    /// reported in the last minute, with that p95. Marks them reported.
+    pub fn slow_phases(&mut self, now: Instant) -> Vec<(Phase, Duration)> {
+        let mut slow = Vec::new();
+        for phase in Phase::ALL {
+            let window = self.phases[phase.index()].window(now);
+            let Some(p95) = window.percentile(0.95) else {
+                continue;
+            };
+            if window.count() < MIN_SAMPLES || p95 <= self.thresholds.for_phase(phase) {
+                continue;
+            }
+            let warned = &mut self.last_warned[phase.index()];
+            if warned.is_some_and(|at| now.saturating_duration_since(at) < WINDOW) {
+                continue;
+            }
+            *warned = Some(now);
+            slow.push((phase, p95));
+        }
+        slow
+    }
+
+    pub fn report(&self, now: Instant) -> PerfReport {
+        let phases = Phase::ALL
+            .iter()
+            .map(|&phase| {
+                let window = self.phases[phase.index()].window(now);
+                PhaseStats {
+                    phase,
+                    samples: window.count(),
+                    p50: window.percentile(0.5),
+                    p95: window.percentile(0.95),
+                    max: window.max(),
+                    threshold: self.thresholds.for_phase(phase),
+                }
+            })
+            .collect();
+        let calls = match self.calls.front() {
+            Some((_, oldest)) => CallCounts::snapshot().since(oldest),
+            None => CallCounts::default(),
+        };
+        PerfReport { phases, calls }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    fn secs(n: u64) -> Duration {
+        Duration::from_secs(n)
+    }
+
+    #[test]
+    fn bucket_boundaries() {
+        assert_eq!(bucket_index(Duration::ZERO), 0);
+        assert_eq!(bucket_index(ms(1)), 0);
+        assert_eq!(bucket_index(ms(1) + Duration::from_micros(1)), 1);
+        assert_eq!(bucket_index(ms(50)), 5);
+        assert_eq!(bucket_index(ms(51)), 6);
+        assert_eq!(bucket_index(ms(5000)), BUCKETS - 2);
+        assert_eq!(bucket_index(ms(5001)), BUCKETS - 1);
+        assert_eq!(bucket_index(secs(3600)), BUCKETS - 1);
+    }
+
+    #[test]
+    fn percentile_math() {
+        let mut h = Histogram::default();
+        assert_eq!(h.percentile(0.5), None);
+        assert_eq!(h.max(), None);
+
+        for _ in 0..90 {
+            h.record(ms(3));
+        }
+        for _ in 0..9 {
+            h.record(ms(150));
+        }
+        h.record(ms(400));
+        assert_eq!(h.count(), 100);
+        assert_eq!(h.percentile(0.5), Some(ms(5)));
+        assert_eq!(h.percentile(0.89), Some(ms(5)));
+        assert_eq!(h.percentile(0.92), Some(ms(200)));
+        assert_eq!(h.percentile(0.95), Some(ms(200)));
+        assert_eq!(h.percentile(1.0), Some(ms(400)));
+        assert_eq!(h.max(), Some(ms(400)));
+
+        // A bucket bound above every sample reports the largest sample.
+        let mut one = Histogram::default();
+        one.record(ms(30));
+        assert_eq!(one.percentile(0.5), Some(ms(30)));
+        // The overflow bucket has no bound of its own.
+        one.record(secs(9));
+        assert_eq!(one.percentile(0.95), Some(secs(9)));
+    }
+
+    #[test]
+    fn window_expiry() {
+        let t0 = Instant::now();
+        let mut r = RollingHistogram::new(t0);
+        r.record(t0, ms(10));
+        r.record(t0 + secs(30), ms(20));
+        assert_eq!(r.window(t0 + secs(59)).count(), 2);
+        assert_eq!(r.window(t0 + secs(60)).count(), 1);
+        assert_eq!(r.window(t0 + secs(89)).count(), 1);
+        assert_eq!(r.window(t0 + secs(90)).count(), 0);
+
+        // A slot reused a minute later starts empty.
+        r.record(t0 + secs(120), ms(1));
+        let w = r.window(t0 + secs(120));
+        assert_eq!(w.count(), 1);
+        assert_eq!(w.max(), Some(ms(1)));
+    }
+
+    #[test]
+    fn slow_phase_is_reported_once_per_minute() {
+        let t0 = Instant::now();
+        let mut perf = Perf::new(Thresholds::default(), t0);
+        for _ in 0..10 {
+            perf.record(Phase::Draw, t0, ms(80));
+            perf.record(Phase::Status, t0, ms(80));
+        }
+        // Draw is over 50ms; the status probe is under 200ms.
+        assert_eq!(perf.slow_phases(t0), [(Phase::Draw, ms(80))]);
+        assert!(perf.slow_phases(t0 + secs(30)).is_empty());
+
+        for _ in 0..10 {
+            perf.record(Phase::Draw, t0 + secs(61), ms(80));
+        }
+        assert_eq!(perf.slow_phases(t0 + secs(61)), [(Phase::Draw, ms(80))]);
+    }
+
+    #[test]
+    fn closed_overlay_samples_in_bursts() {
+        let t0 = Instant::now();
+        let mut perf = Perf::new(Thresholds::default(), t0);
+        perf.begin_frame(t0 + ms(500));
+        assert!(perf.start().is_some());
+        perf.begin_frame(t0 + secs(3));
+        assert!(perf.start().is_none());
+        perf.finish(Phase::Draw, None);
+        assert_eq!(perf.report(t0 + secs(3)).phases[Phase::Draw.index()].samples, 0);
+
+        perf.toggle_overlay();
+        perf.begin_frame(t0 + secs(3));
+        let started = perf.start();
+        assert!(started.is_some());
+        perf.finish(Phase::Draw, started);
+        let report = perf.report(Instant::now());
+        assert_eq!(report.phases[Phase::Draw.index()].samples, 1);
+    }
+}
@@ -0,0 +1,522 @@
+//! Recent pane captures per session, browsable from the preview.
+//!
+//! Every capture is kept as a numbered file under `profiles/<p>/previews/
+//! <session id>/`; the newest one doubles as the preview cache. Captures
+//! rotate out past `keep` per session, and oldest-first across sessions
+//! once all of them together exceed the byte budget.
+
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Captures kept per session unless configured otherwise.
+pub const DEFAULT_PREVIEW_HISTORY: usize = 5;
+/// Total size of all kept captures.
+pub const PREVIEW_BYTE_BUDGET: usize = 8 * 1024 * 1024;
+/// Live-preview captures closer together than this overwrite the newest
+/// snapshot instead of adding one, so following a busy session keeps
+/// about one capture a minute.
+pub const LIVE_SNAPSHOT_GAP_SECS: i64 = 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreviewSnapshot {
+    /// File number; increases with every capture of the session.
+    pub seq: u64,
+    pub taken_at: DateTime<Utc>,
+    pub content: String,
+}
+
+/// One session's captures, oldest first, plus which one is on screen.
+#[derive(Debug, Clone, Default)]
+pub struct PreviewHistory {
+    snapshots: VecDeque<PreviewSnapshot>,
+    /// Index of the capture being viewed; `None` follows the newest.
+    cursor: Option<usize>,
+    /// When live captures started overwriting the newest snapshot.
+    live_since: Option<DateTime<Utc>>,
+}
+
+impl PreviewHistory {
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    pub fn newest(&self) -> Option<&PreviewSnapshot> {
+        self.snapshots.back()
+    }
+
+    fn current_index(&self) -> Option<usize> {
+        match self.cursor {
+            Some(i) => Some(i),
+            None => self.snapshots.len().checked_sub(1),
+        }
+    }
+
+    /// The capture on screen.
+    pub fn current(&self) -> Option<&PreviewSnapshot> {
+        self.snapshots.get(self.current_index()?)
+    }
+
+    /// `(1-based position, total)` while browsing older captures.
+    pub fn browsing(&self) -> Option<(usize, usize)> {
+        self.cursor.map(|i| (i + 1, self.snapshots.len()))
+    }
+
+    /// The capture on screen and the one before it, for diffing.
+    pub fn adjacent(&self) -> Option<(&PreviewSnapshot, &PreviewSnapshot)> {
+        let i = self.current_index()?;
+        Some((self.snapshots.get(i.checked_sub(1)?)?, self.snapshots.get(i)?))
+    }
+
+    /// Step to an older capture; false if already at the oldest.
+    pub fn back(&mut self) -> bool {
+        match self.current_index() {
+            Some(i) if i > 0 => {
+                self.cursor = Some(i - 1);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Step to a newer capture; reaching the newest follows new captures again.
+    pub fn forward(&mut self) -> bool {
+        let Some(i) = self.cursor else {
+            return false;
+        };
+        self.cursor = (i + 2 < self.snapshots.len()).then_some(i + 1);
+        true
+    }
+
+    /// Stop browsing and follow the newest capture.
+    pub fn show_newest(&mut self) {
+        self.cursor = None;
+    }
+
+    /// Append a capture and return the ones rotated out. A capture being
+    /// browsed stays on screen until it rotates out itself.
+    fn push(&mut self, snapshot: PreviewSnapshot, keep: usize) -> Vec<PreviewSnapshot> {
+        self.snapshots.push_back(snapshot);
+        let excess = self.snapshots.len().saturating_sub(keep.max(1));
+        (0..excess).filter_map(|_| self.pop_oldest()).collect()
+    }
+
+    fn pop_oldest(&mut self) -> Option<PreviewSnapshot> {
+        let oldest = self.snapshots.pop_front()?;
+        self.cursor = self
+            .cursor
+            .map(|i| i.saturating_sub(1))
+            .filter(|&i| i + 1 < self.snapshots.len());
+        Some(oldest)
+    }
+}
+
+/// Histories of every session, optionally mirrored to disk.
+#[derive(Debug, Default)]
+pub struct PreviewHistories {
+    dir: Option<PathBuf>,
+    keep: usize,
+    byte_budget: usize,
+    by_session: HashMap<String, PreviewHistory>,
+}
+
+impl PreviewHistories {
+    pub fn new(dir: Option<PathBuf>, keep: usize, byte_budget: usize) -> Self {
+        Self {
+            dir,
+            keep: keep.max(1),
+            byte_budget,
+            by_session: HashMap::new(),
+        }
+    }
+
+    /// Load what earlier runs left in `dir`.
+    pub fn load(dir: PathBuf, keep: usize, byte_budget: usize) -> Self {
+        let mut this = Self::new(Some(dir.clone()), keep, byte_budget);
+        let Ok(entries) = fs::read_dir(&dir) else {
+            return this;
+        };
+        for entry in entries.flatten() {
+            let Some(id) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            let mut snapshots = read_snapshots(&entry.path());
+            snapshots.sort_by_key(|s| s.seq);
+            if !snapshots.is_empty() {
+                let history = this.by_session.entry(id.clone()).or_default();
+                let rotated = history.push_all(snapshots, this.keep);
+                this.remove_files(&id, &rotated);
+            }
+        }
+        this.enforce_budget();
+        this
+    }
+
+    pub fn get(&self, session_id: &str) -> Option<&PreviewHistory> {
+        self.by_session.get(session_id)
+    }
+
+    pub fn get_mut(&mut self, session_id: &str) -> Option<&mut PreviewHistory> {
+        self.by_session.get_mut(session_id)
+    }
+
+    /// Content of the newest capture: the cached preview.
+    pub fn newest_content(&self, session_id: &str) -> Option<&str> {
+        Some(self.by_session.get(session_id)?.newest()?.content.as_str())
+    }
+
+    /// Record a fresh capture as the session's newest, unless it is
+    /// identical to the current newest.
+    pub fn record(&mut self, session_id: &str, content: String, taken_at: DateTime<Utc>) {
+        if self.append(session_id, content, taken_at) {
+            if let Some(history) = self.by_session.get_mut(session_id) {
+                history.live_since = None;
+            }
+        }
+    }
+
+    /// Record a live-preview capture: within [`LIVE_SNAPSHOT_GAP_SECS`] of
+    /// the snapshot live captures last added, overwrite it in place.
+    pub fn record_live(&mut self, session_id: &str, content: String, taken_at: DateTime<Utc>) {
+        let history = self.by_session.get_mut(session_id);
+        let in_gap = history.as_ref().and_then(|h| h.live_since).is_some_and(|since| {
+            taken_at.signed_duration_since(since).num_seconds() < LIVE_SNAPSHOT_GAP_SECS
+        });
+        let newest = history.and_then(|h| h.snapshots.back_mut());
+        match newest {
+            Some(newest) if in_gap => {
+                if newest.content == content {
+                    return;
+                }
+                newest.content = content;
+                newest.taken_at = taken_at;
+                let snapshot = newest.clone();
+                self.write_file(session_id, &snapshot);
+                self.enforce_budget();
+            }
+            _ => {
+                if self.append(session_id, content, taken_at) {
+                    if let Some(history) = self.by_session.get_mut(session_id) {
+                        history.live_since = Some(taken_at);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Push a capture unless it matches the newest; true if it was added.
+    fn append(&mut self, session_id: &str, content: String, taken_at: DateTime<Utc>) -> bool {
+        let seq = match self.by_session.get(session_id).and_then(|h| h.newest()) {
+            Some(newest) if newest.content == content => return false,
+            Some(newest) => newest.seq + 1,
+            None => 1,
+        };
+        let snapshot = PreviewSnapshot {
+            seq,
+            taken_at,
+            content,
+        };
+        self.write_file(session_id, &snapshot);
+        let rotated = self
+            .by_session
+            .entry(session_id.to_string())
+            .or_default()
+            .push(snapshot, self.keep);
+        self.remove_files(session_id, &rotated);
+        self.enforce_budget();
+        true
+    }
+
+    pub fn total_bytes(&self) -> usize {
+        self.by_session
+            .values()
+            .flat_map(|h| h.snapshots.iter())
+            .map(|s| s.content.len())
+            .sum()
+    }
+
+    /// Drop the oldest captures across all sessions until within budget.
+    /// A session's newest capture goes last.
+    fn enforce_budget(&mut self) {
+        let mut total = self.total_bytes();
+        while total > self.byte_budget {
+            let oldest = self
+                .by_session
+                .iter()
+                .filter_map(|(id, h)| Some((id, h.snapshots.front()?, h.len())))
+                .min_by_key(|(_, s, len)| (*len == 1, s.taken_at))
+                .map(|(id, _, _)| id.clone());
+            let Some(id) = oldest else {
+                break;
+            };
+            let history = self.by_session.get_mut(&id).expect("id from map");
+            let Some(removed) = history.pop_oldest() else {
+                break;
+            };
+            total -= removed.content.len();
+            if history.is_empty() {
+                self.by_session.remove(&id);
+            }
+            self.remove_files(&id, std::slice::from_ref(&removed));
+        }
+    }
+
+    /// Drop histories of sessions that no longer exist.
+    pub fn retain_sessions(&mut self, exists: impl Fn(&str) -> bool) {
+        let gone: Vec<String> = self
+            .by_session
+            .keys()
+            .filter(|id| !exists(id))
+            .cloned()
+            .collect();
+        for id in gone {
+            self.remove(&id);
+        }
+    }
+
+    /// Forget a session and delete its files.
+    pub fn remove(&mut self, session_id: &str) {
+        self.by_session.remove(session_id);
+        if let Some(dir) = &self.dir {
+            let _ = fs::remove_dir_all(dir.join(session_id));
+        }
+    }
+
+    fn write_file(&self, session_id: &str, snapshot: &PreviewSnapshot) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+        let dir = dir.join(session_id);
+        let written = fs::create_dir_all(&dir).and_then(|_| {
+            let json = serde_json::to_vec(snapshot).map_err(std::io::Error::other)?;
+            fs::write(dir.join(file_name(snapshot.seq)), json)
+        });
+        if let Err(e) = written {
+            tracing::warn!("failed to save preview snapshot for {session_id}: {e}");
+        }
+    }
+
+    fn remove_files(&self, session_id: &str, snapshots: &[PreviewSnapshot]) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+        for s in snapshots {
+            let _ = fs::remove_file(dir.join(session_id).join(file_name(s.seq)));
+        }
+    }
+}
+
+impl PreviewHistory {
+    fn push_all(&mut self, snapshots: Vec<PreviewSnapshot>, keep: usize) -> Vec<PreviewSnapshot> {
+        let mut rotated = Vec::new();
+        for s in snapshots {
+            rotated.extend(self.push(s, keep));
+        }
+        rotated
+    }
+}
+
+impl crate::ui::SessionKeyed for PreviewHistories {
+    fn forget(&mut self, session_id: &str) {
+        self.remove(session_id);
+    }
+}
+
+fn file_name(seq: u64) -> String {
+    format!("{seq:06}.json")
+}
+
+fn read_snapshots(dir: &Path) -> Vec<PreviewSnapshot> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|e| e.path().extension().is_some_and(|x| x == "json"))
+        .filter_map(|e| fs::read(e.path()).ok())
+        .filter_map(|bytes| serde_json::from_slice(&bytes).ok())
+        .collect()
+}
+
+/// "snapshot 3/5 — 14:22, 18 min ago"
+pub fn browse_label(
+    position: (usize, usize),
+    taken_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+    is_zh: bool,
+) -> String {
+    let (index, total) = position;
+    let clock = taken_at.with_timezone(&chrono::Local).format("%H:%M");
+    let mins = now.signed_duration_since(taken_at).num_minutes().max(0);
+    let ago = match (mins, is_zh) {
+        (0, false) => "just now".to_string(),
+        (0, true) => "刚刚".to_string(),
+        (1..=59, false) => format!("{mins} min ago"),
+        (1..=59, true) => format!("{mins} 分钟前"),
+        (_, false) => format!("{} hr ago", mins / 60),
+        (_, true) => format!("{} 小时前", mins / 60),
+    };
+    if is_zh {
+        format!("快照 {index}/{total} — {clock}，{ago}")
+    } else {
+        format!("snapshot {index}/{total} — {clock}, {ago}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(minute: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + minute * 60, 0).unwrap()
+    }
+
+    fn histories(keep: usize, budget: usize) -> PreviewHistories {
+        PreviewHistories::new(None, keep, budget)
+    }
+
+    fn contents(h: &PreviewHistory) -> Vec<&str> {
+        h.snapshots.iter().map(|s| s.content.as_str()).collect()
+    }
+
+    #[test]
+    fn rotates_by_count_and_numbers_captures() {
+        let mut all = histories(3, usize::MAX);
+        for (i, text) in ["a", "b", "c", "d"].iter().enumerate() {
+            all.record("s", text.to_string(), at(i as i64));
+        }
+        let h = all.get("s").unwrap();
+        assert_eq!(contents(h), ["b", "c", "d"]);
+        assert_eq!(h.newest().unwrap().seq, 4);
+        assert_eq!(all.newest_content("s"), Some("d"));
+
+        all.record("s", "d".into(), at(9));
+        assert_eq!(all.get("s").unwrap().newest().unwrap().seq, 4);
+    }
+
+    #[test]
+    fn budget_drops_oldest_across_sessions_newest_last() {
+        let mut all = histories(5, 10);
+        all.record("a", "aaaa".into(), at(0));
+        all.record("b", "bbbb".into(), at(1));
+        all.record("a", "AAAA".into(), at(2));
+        // 12 bytes: a's first capture is the oldest.
+        assert_eq!(contents(all.get("a").unwrap()), ["AAAA"]);
+        assert_eq!(contents(all.get("b").unwrap()), ["bbbb"]);
+
+        all.record("b", "BBBB".into(), at(3));
+        // b's older capture goes before anyone's newest.
+        assert_eq!(contents(all.get("b").unwrap()), ["BBBB"]);
+        assert_eq!(all.total_bytes(), 8);
+    }
+
+    #[test]
+    fn navigation_state_machine() {
+        let mut all = histories(5, usize::MAX);
+        for (i, text) in ["1", "2", "3"].iter().enumerate() {
+            all.record("s", text.to_string(), at(i as i64));
+        }
+        let h = all.get_mut("s").unwrap();
+        assert_eq!(h.browsing(), None);
+        assert!(!h.forward());
+
+        assert!(h.back());
+        assert_eq!((h.current().unwrap().content.as_str(), h.browsing()), ("2", Some((2, 3))));
+        assert!(h.back());
+        assert!(!h.back());
+        assert_eq!(h.browsing(), Some((1, 3)));
+        assert!(h.adjacent().is_none());
+
+        assert!(h.forward());
+        let (older, newer) = h.adjacent().unwrap();
+        assert_eq!((older.content.as_str(), newer.content.as_str()), ("1", "2"));
+        assert!(h.forward());
+        assert_eq!((h.current().unwrap().content.as_str(), h.browsing()), ("3", None));
+    }
+
+    #[test]
+    fn background_captures_keep_the_browsed_snapshot() {
+        let mut all = histories(3, usize::MAX);
+        for (i, text) in ["1", "2", "3"].iter().enumerate() {
+            all.record("s", text.to_string(), at(i as i64));
+        }
+        all.get_mut("s").unwrap().back();
+        all.record("s", "4".into(), at(3));
+        // "1" rotated out; "2" is still on screen, now at 1/3.
+        let h = all.get_mut("s").unwrap();
+        assert_eq!((h.current().unwrap().content.as_str(), h.browsing()), ("2", Some((1, 3))));
+
+        h.show_newest();
+        assert_eq!((h.current().unwrap().content.as_str(), h.browsing()), ("4", None));
+
+        // The browsed snapshot itself rotating out moves to the next oldest.
+        h.back();
+        h.back();
+        all.record("s", "5".into(), at(4));
+        let h = all.get("s").unwrap();
+        assert_eq!((h.current().unwrap().content.as_str(), h.browsing()), ("3", Some((1, 3))));
+    }
+
+    #[test]
+    fn live_captures_overwrite_newest_within_gap() {
+        let mut all = histories(5, usize::MAX);
+        all.record("s", "manual".into(), at(0));
+        let secs = |s: i64| at(1) + chrono::Duration::seconds(s);
+        all.record_live("s", "live 1".into(), secs(0));
+        all.record_live("s", "live 2".into(), secs(2));
+        all.record_live("s", "live 3".into(), secs(4));
+        let h = all.get("s").unwrap();
+        assert_eq!(contents(h), ["manual", "live 3"]);
+        assert_eq!(h.newest().unwrap().taken_at, secs(4));
+
+        // A minute after the last added live capture, a new one is added.
+        all.record_live("s", "live 4".into(), secs(LIVE_SNAPSHOT_GAP_SECS));
+        assert_eq!(contents(all.get("s").unwrap()), ["manual", "live 3", "live 4"]);
+
+        // `p` always adds, and the next live capture adds again.
+        all.record("s", "manual 2".into(), secs(62));
+        all.record_live("s", "live 5".into(), secs(64));
+        assert_eq!(
+            contents(all.get("s").unwrap()),
+            ["manual", "live 3", "live 4", "manual 2", "live 5"]
+        );
+    }
+
+    #[test]
+    fn persists_numbered_files_and_reloads() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut all = PreviewHistories::new(Some(dir.path().to_path_buf()), 2, usize::MAX);
+        for (i, text) in ["x", "y", "z"].iter().enumerate() {
+            all.record("s", text.to_string(), at(i as i64));
+        }
+        let mut files: Vec<String> = fs::read_dir(dir.path().join("s"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        assert_eq!(files, ["000002.json", "000003.json"]);
+
+        let reloaded = PreviewHistories::load(dir.path().to_path_buf(), 2, usize::MAX);
+        assert_eq!(contents(reloaded.get("s").unwrap()), ["y", "z"]);
+
+        let mut reloaded = reloaded;
+        reloaded.remove("s");
+        assert!(!dir.path().join("s").exists());
+    }
+
+    #[test]
+    fn label_shows_position_and_age() {
+        let label = browse_label((3, 5), at(0), at(18), false);
+        assert!(label.starts_with("snapshot 3/5 — "), "{label}");
+        assert!(label.ends_with(", 18 min ago"), "{label}");
+        assert!(browse_label((1, 2), at(0), at(0), false).ends_with("just now"));
+        assert!(browse_label((1, 2), at(0), at(125), false).ends_with("2 hr ago"));
+    }
+}
@@ -0,0 +1,202 @@
+//! Scrolling and searching within the preview pane.
+//!
+//! The scroll offset counts content lines from the top; the renderer turns
+//! it into wrapped rows. Focus mode swaps in a longer capture and sends
+//! keys to the preview: scrolling, `/` to search, `n`/`N` between matches.
+//! Searches are smart-case: case-insensitive unless the query has an
+//! uppercase letter.
+
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use unicode_width::UnicodeWidthStr;
+
+use super::TextInput;
+
+/// Lines kept above a match jumped to.
+const MATCH_CONTEXT: usize = 2;
+
+#[derive(Debug, Default)]
+pub struct PreviewView {
+    /// First content line on screen.
+    pub scroll: usize,
+    pub focused: bool,
+    /// The longer capture taken when focus mode began; shown instead of
+    /// the regular preview.
+    pub content: Option<String>,
+    /// The query being typed after `/`.
+    pub search: Option<TextInput>,
+    /// The highlighted query; empty when not searching.
+    pub query: String,
+    /// Line of the match last jumped to.
+    pub current_match: Option<usize>,
+}
+
+impl PreviewView {
+    /// Back to the top and out of focus mode, e.g. for a new selection.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Move `delta` lines through `lines` lines of content, `page` of
+    /// which fit on screen.
+    pub fn scroll_by(&mut self, delta: isize, lines: usize, page: usize) {
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(delta)
+            .min(max_scroll(lines, page));
+    }
+
+    pub fn scroll_to_bottom(&mut self, lines: usize, page: usize) {
+        self.scroll = max_scroll(lines, page);
+    }
+
+    /// Scroll to the next (or previous) match of the query in `text`,
+    /// wrapping around. False when nothing matches.
+    pub fn jump_to_match(&mut self, text: &str, forward: bool, page: usize) -> bool {
+        let matches = matching_lines(text, &self.query);
+        let anchor = self.current_match;
+        let next = if forward {
+            matches
+                .iter()
+                .find(|&&l| anchor.map_or(l >= self.scroll, |a| l > a))
+                .or(matches.first())
+        } else {
+            matches
+                .iter()
+                .rev()
+                .find(|&&l| anchor.map_or(l < self.scroll, |a| l < a))
+                .or(matches.last())
+        };
+        let Some(&line) = next else {
+            self.current_match = None;
+            return false;
+        };
+        self.current_match = Some(line);
+        self.scroll = line
+            .saturating_sub(MATCH_CONTEXT)
+            .min(max_scroll(text.lines().count(), page));
+        true
+    }
+}
+
+/// Largest scroll offset that still fills the screen.
+pub fn max_scroll(lines: usize, page: usize) -> usize {
+    lines.saturating_sub(page.max(1))
+}
+
+/// Byte ranges of the non-overlapping matches of `query` in `line`.
+fn match_ranges(line: &str, query: &str) -> Vec<(usize, usize)> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    // ASCII lowercasing keeps byte offsets valid for the original line.
+    let (haystack, needle) = if query.chars().any(char::is_uppercase) {
+        (line.to_string(), query.to_string())
+    } else {
+        (line.to_ascii_lowercase(), query.to_ascii_lowercase())
+    };
+    haystack
+        .match_indices(&needle)
+        .map(|(start, m)| (start, start + m.len()))
+        .collect()
+}
+
+/// Indices of the lines of `text` that contain `query`.
+pub fn matching_lines(text: &str, query: &str) -> Vec<usize> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !match_ranges(line, query).is_empty())
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// `text` as lines with the matches of `query` highlighted, those on line
+/// `current` brighter.
+pub fn highlighted_lines(text: &str, query: &str, current: Option<usize>) -> Vec<Line<'static>> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let ranges = match_ranges(line, query);
+            if ranges.is_empty() {
+                return Line::from(line.to_string());
+            }
+            let t = crate::ui::theme::theme();
+            let style = if current == Some(i) {
+                t.selection_style().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(t.selection_fg).bg(t.highlight)
+            };
+            let mut spans = Vec::new();
+            let mut pos = 0;
+            for (start, end) in ranges {
+                if start > pos {
+                    spans.push(Span::raw(line[pos..start].to_string()));
+                }
+                spans.push(Span::styled(line[start..end].to_string(), style));
+                pos = end;
+            }
+            if pos < line.len() {
+                spans.push(Span::raw(line[pos..].to_string()));
+            }
+            Line::from(spans)
+        })
+        .collect()
+}
+
+/// Rows `line` takes when wrapped to `width` columns.
+pub fn wrapped_rows(line: &str, width: u16) -> usize {
+    UnicodeWidthStr::width(line)
+        .div_ceil(usize::from(width.max(1)))
+        .max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT: &str =
+        "build started\nerror: missing semicolon\nwarning: unused\nError: link failed\ndone";
+
+    #[test]
+    fn search_is_smart_case() {
+        assert_eq!(matching_lines(TEXT, "error"), [1, 3]);
+        assert_eq!(matching_lines(TEXT, "Error"), [3]);
+        assert!(matching_lines(TEXT, "").is_empty());
+
+        let line = &highlighted_lines(TEXT, "error", None)[3];
+        let parts: Vec<&str> = line.spans.iter().map(|s| s.content.as_ref()).collect();
+        assert_eq!(parts, ["Error", ": link failed"]);
+    }
+
+    #[test]
+    fn matches_cycle_and_wrap_around() {
+        let mut view = PreviewView {
+            query: "error".into(),
+            ..Default::default()
+        };
+        assert!(view.jump_to_match(TEXT, true, 2));
+        assert_eq!(view.current_match, Some(1));
+        assert!(view.jump_to_match(TEXT, true, 2));
+        assert_eq!(view.current_match, Some(3));
+        // Context lines stay above the match.
+        assert_eq!(view.scroll, 1);
+        assert!(view.jump_to_match(TEXT, true, 2));
+        assert_eq!(view.current_match, Some(1));
+        assert!(view.jump_to_match(TEXT, false, 2));
+        assert_eq!(view.current_match, Some(3));
+
+        view.query = "nope".into();
+        assert!(!view.jump_to_match(TEXT, true, 2));
+    }
+
+    #[test]
+    fn scrolling_stays_within_the_content() {
+        let mut view = PreviewView::default();
+        view.scroll_by(-3, 10, 4);
+        assert_eq!(view.scroll, 0);
+        view.scroll_by(100, 10, 4);
+        assert_eq!(view.scroll, 6);
+        assert_eq!(wrapped_rows("", 10), 1);
+        assert_eq!(wrapped_rows(&"x".repeat(25), 10), 3);
+    }
+}
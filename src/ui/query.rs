@@ -0,0 +1,305 @@
+//! Search query syntax shared by the search popup and the switcher.
+//!
+//! Space-separated terms, all of which must match:
+//! - `foo`: fuzzy over title, label, group and path
+//! - `t:foo`: tool name starts with the pattern, or fuzzy over title
+//! - `g:foo`: group path starts with the pattern
+//! - `p:foo`: fuzzy over project path
+//! - `s:wait`: status name starts with the pattern
+//! - `#foo`: fuzzy over tags
+//!
+//! Unknown prefixes (`x:foo`) are plain terms, and a prefix with no pattern
+//! (`t:`) is ignored so the list does not empty while typing.
+
+use crate::session::{Instance, Status};
+
+/// Prefix hint shown under search inputs.
+pub const SYNTAX_HINT_EN: &str = "t:tool/title  g:group  p:path  s:status  #tag";
+pub const SYNTAX_HINT_ZH: &str = "t:工具/标题  g:分组  p:路径  s:状态  #标签";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Any,
+    Title,
+    Group,
+    Path,
+    Status,
+    Tag,
+}
+
+impl Field {
+    /// Name shown for an active filter; None for bare terms.
+    pub fn label(self, is_zh: bool) -> Option<&'static str> {
+        let (en, zh) = match self {
+            Self::Any => return None,
+            Self::Title => ("tool/title", "工具/标题"),
+            Self::Group => ("group", "分组"),
+            Self::Path => ("path", "路径"),
+            Self::Status => ("status", "状态"),
+            Self::Tag => ("tag", "标签"),
+        };
+        Some(if is_zh { zh } else { en })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Term {
+    pub field: Field,
+    pub pattern: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    pub terms: Vec<Term>,
+}
+
+impl SearchQuery {
+    pub fn parse(input: &str) -> Self {
+        let terms = input.split_whitespace().filter_map(parse_term).collect();
+        Self { terms }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Prefixed terms as `field=pattern`, for showing the active filters.
+    pub fn filter_labels(&self, is_zh: bool) -> Vec<String> {
+        self.terms
+            .iter()
+            .filter_map(|t| Some(format!("{}={}", t.field.label(is_zh)?, t.pattern)))
+            .collect()
+    }
+
+    /// Combined score of `inst` against every term, or `None` if any term
+    /// fails. `extra` is appended to the haystack of bare terms (the
+    /// switcher matches session ids too).
+    pub fn score(&self, inst: &Instance, extra: &str) -> Option<i32> {
+        let mut total = 0;
+        for term in &self.terms {
+            total += score_term(term, inst, extra)?;
+        }
+        Some(total)
+    }
+}
+
+fn parse_term(token: &str) -> Option<Term> {
+    let term = |field, pattern: &str| Term {
+        field,
+        pattern: pattern.to_string(),
+    };
+
+    if let Some(tag) = token.strip_prefix('#') {
+        return (!tag.is_empty()).then(|| term(Field::Tag, tag));
+    }
+    if let Some((prefix, pattern)) = token.split_once(':') {
+        let field = match prefix {
+            "t" => Some(Field::Title),
+            "g" => Some(Field::Group),
+            "p" => Some(Field::Path),
+            "s" => Some(Field::Status),
+            _ => None,
+        };
+        if let Some(field) = field {
+            return (!pattern.is_empty()).then(|| term(field, pattern));
+        }
+    }
+    Some(term(Field::Any, token))
+}
+
+fn status_name(status: Status) -> &'static str {
+    match status {
+        Status::Running => "running",
+        Status::Waiting => "waiting",
+        Status::Idle => "idle",
+        Status::Error => "error",
+        Status::Starting => "starting",
+        Status::Stopped => "stopped",
+    }
+}
+
+fn score_term(term: &Term, inst: &Instance, extra: &str) -> Option<i32> {
+    let pattern = term.pattern.as_str();
+    match term.field {
+        Field::Any => {
+            let hay = format!(
+                "{} {} {} {} {}",
+                inst.title,
+                inst.label,
+                inst.group_path,
+                inst.project_path.to_string_lossy(),
+                extra
+            );
+            fuzzy_score(pattern, hay.trim_end())
+        }
+        Field::Title => {
+            let tool = inst
+                .tool
+                .to_string()
+                .starts_with(&pattern.to_lowercase())
+                .then_some(0);
+            tool.max(fuzzy_score(pattern, &inst.title))
+        }
+        Field::Group => inst
+            .group_path
+            .to_lowercase()
+            .starts_with(&pattern.to_lowercase())
+            .then_some(0),
+        Field::Path => fuzzy_score(pattern, &inst.project_path.to_string_lossy()),
+        Field::Status => status_name(inst.status)
+            .starts_with(&pattern.to_lowercase())
+            .then_some(0),
+        Field::Tag => inst.tags.iter().filter_map(|t| fuzzy_score(pattern, t)).max(),
+    }
+}
+
+/// Subsequence match with bonuses for contiguous and early matches.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    let q = query.to_lowercase();
+    let t = text.to_lowercase();
+
+    let mut score: i32 = 0;
+    let mut last_match: Option<usize> = None;
+    let mut pos = 0usize;
+
+    for ch in q.chars() {
+        if let Some(found) = t[pos..].find(ch) {
+            let idx = pos + found;
+            score += 10;
+            if let Some(prev) = last_match {
+                if idx == prev + 1 {
+                    score += 15; // contiguous bonus
+                } else {
+                    score -= (idx.saturating_sub(prev) as i32).min(10);
+                }
+            } else {
+                score -= idx.min(15) as i32; // earlier is better
+            }
+            last_match = Some(idx);
+            pos = idx + ch.len_utf8();
+        } else {
+            return None;
+        }
+    }
+
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn session(title: &str, group: &str, path: &str, status: Status, tags: &[&str]) -> Instance {
+        let mut inst = Instance::with_group(title.into(), PathBuf::from(path), group.into());
+        inst.status = status;
+        inst.tags = tags.iter().map(|t| t.to_string()).collect();
+        inst
+    }
+
+    fn fields(q: &str) -> Vec<(Field, String)> {
+        SearchQuery::parse(q)
+            .terms
+            .into_iter()
+            .map(|t| (t.field, t.pattern))
+            .collect()
+    }
+
+    #[test]
+    fn parse_table() {
+        let cases: &[(&str, Vec<(Field, &str)>)] = &[
+            ("api", vec![(Field::Any, "api")]),
+            ("t:api g:work", vec![(Field::Title, "api"), (Field::Group, "work")]),
+            ("p:src/backend s:wait", vec![(Field::Path, "src/backend"), (Field::Status, "wait")]),
+            ("#urgent fix", vec![(Field::Tag, "urgent"), (Field::Any, "fix")]),
+            ("x:foo", vec![(Field::Any, "x:foo")]),
+            ("t: # g:", vec![]),
+            ("  ", vec![]),
+            ("t:a:b", vec![(Field::Title, "a:b")]),
+            ("t:数据 接口", vec![(Field::Title, "数据"), (Field::Any, "接口")]),
+        ];
+        for (input, expected) in cases {
+            let expected: Vec<(Field, String)> =
+                expected.iter().map(|(f, p)| (*f, p.to_string())).collect();
+            assert_eq!(fields(input), expected, "query {input:?}");
+        }
+    }
+
+    #[test]
+    fn field_terms_only_look_at_their_field() {
+        let inst = session("frontend", "work", "/src/api/web", Status::Idle, &[]);
+        assert!(SearchQuery::parse("api").score(&inst, "").is_some());
+        assert!(SearchQuery::parse("t:api").score(&inst, "").is_none());
+        assert!(SearchQuery::parse("p:api").score(&inst, "").is_some());
+        assert!(SearchQuery::parse("g:work t:front").score(&inst, "").is_some());
+        assert!(SearchQuery::parse("g:home t:front").score(&inst, "").is_none());
+    }
+
+    #[test]
+    fn status_and_tag_terms() {
+        let inst = session("api", "", "/tmp", Status::Waiting, &["urgent", "backend"]);
+        assert!(SearchQuery::parse("s:wait").score(&inst, "").is_some());
+        assert!(SearchQuery::parse("s:WAITING").score(&inst, "").is_some());
+        assert!(SearchQuery::parse("s:run").score(&inst, "").is_none());
+        assert!(SearchQuery::parse("s:sleeping").score(&inst, "").is_none());
+        assert!(SearchQuery::parse("#urg").score(&inst, "").is_some());
+        assert!(SearchQuery::parse("#frontend").score(&inst, "").is_none());
+
+        let untagged = session("api", "", "/tmp", Status::Idle, &[]);
+        assert!(SearchQuery::parse("#urg").score(&untagged, "").is_none());
+    }
+
+    #[test]
+    fn scores_add_up_and_extra_hay_is_searched() {
+        let inst = session("api", "", "/tmp", Status::Idle, &[]);
+        let one = SearchQuery::parse("t:api").score(&inst, "").unwrap();
+        let two = SearchQuery::parse("t:api api").score(&inst, "").unwrap();
+        assert!(two > one);
+
+        assert!(SearchQuery::parse("abc123").score(&inst, "").is_none());
+        assert!(SearchQuery::parse("abc123").score(&inst, "abc123").is_some());
+        assert_eq!(SearchQuery::parse("").score(&inst, ""), Some(0));
+    }
+
+    #[test]
+    fn bare_terms_search_the_label() {
+        let mut inst = session("api", "", "/tmp", Status::Idle, &[]);
+        assert!(SearchQuery::parse("blocked").score(&inst, "").is_none());
+        inst.label = "blocked".into();
+        assert!(SearchQuery::parse("blocked").score(&inst, "").is_some());
+        assert!(SearchQuery::parse("t:blocked").score(&inst, "").is_none());
+    }
+
+    #[test]
+    fn tool_and_group_prefix_terms() {
+        let mut inst = session("api", "work/backend", "/tmp", Status::Waiting, &[]);
+        inst.tool = crate::tmux::Tool::Claude;
+        assert!(SearchQuery::parse("t:claude").score(&inst, "").is_some());
+        assert!(SearchQuery::parse("t:cl").score(&inst, "").is_some());
+        assert!(SearchQuery::parse("t:codex").score(&inst, "").is_none());
+        assert!(SearchQuery::parse("s:waiting t:claude api").score(&inst, "").is_some());
+        assert!(SearchQuery::parse("s:running t:claude api").score(&inst, "").is_none());
+
+        assert!(SearchQuery::parse("g:work").score(&inst, "").is_some());
+        assert!(SearchQuery::parse("g:Work/back").score(&inst, "").is_some());
+        assert!(SearchQuery::parse("g:backend").score(&inst, "").is_none());
+    }
+
+    #[test]
+    fn filter_labels_skip_bare_terms() {
+        let query = SearchQuery::parse("s:waiting api g:work x:y");
+        assert_eq!(query.filter_labels(false), ["status=waiting", "group=work"]);
+        assert_eq!(query.filter_labels(true), ["状态=waiting", "分组=work"]);
+    }
+
+    #[test]
+    fn unicode_titles_match() {
+        let inst = session("数据接口", "", "/tmp", Status::Idle, &[]);
+        assert!(SearchQuery::parse("t:接口").score(&inst, "").is_some());
+        assert!(SearchQuery::parse("t:接数").score(&inst, "").is_none());
+    }
+}
@@ -1,5 +1,8 @@
 use super::*;
 
+use crate::ui::dialog_frame::{DialogGeometry, SizeNudge};
+use crate::ui::mouse::DialogTarget;
+
 pub(super) fn render_dialog(f: &mut Frame, area: Rect, app: &App) {
     let lang = app.language();
     let is_zh = matches!(lang, crate::i18n::Language::Chinese);
@@ -10,7 +13,14 @@ pub(super) fn render_dialog(f: &mut Frame, area: Rect, app: &App) {
     }
 
     if let Some(d) = app.new_session_dialog() {
-        render_new_session_dialog(f, area, d, is_zh);
+        render_new_session_dialog(
+            f,
+            area,
+            d,
+            app.dialog_nudge(crate::ui::dialog_frame::NEW_SESSION),
+            app.new_session_advanced(),
+            is_zh,
+        );
         return;
     }
 
@@ -25,7 +35,7 @@ pub(super) fn render_dialog(f: &mut Frame, area: Rect, app: &App) {
     }
 
     if let Some(d) = app.fork_dialog() {
-        render_fork_dialog(f, area, d, is_zh);
+        render_fork_dialog(f, area, d, app.dialog_nudge(crate::ui::dialog_frame::FORK), is_zh);
         return;
     }
 
@@ -44,16 +54,77 @@ pub(super) fn render_dialog(f: &mut Frame, area: Rect, app: &App) {
         return;
     }
 
+    if let Some(d) = app.repair_path_dialog() {
+        render_repair_path_dialog(f, area, d, is_zh);
+        return;
+    }
+
     if let Some(d) = app.tag_picker_dialog() {
         render_tag_picker_dialog(f, area, d, is_zh);
         return;
     }
 
+    if let Some(d) = app.label_picker_dialog() {
+        render_label_picker_dialog(f, area, d, is_zh);
+        return;
+    }
+
     if let Some(d) = app.rename_group_dialog() {
         render_rename_group_dialog(f, area, d, is_zh);
         return;
     }
 
+    if let Some(d) = app.group_notes_dialog() {
+        render_group_notes_dialog(f, area, d, is_zh);
+        return;
+    }
+
+    if let Some(d) = app.reparent_group_dialog() {
+        render_reparent_group_dialog(f, area, d, is_zh);
+        return;
+    }
+
+    if let Some(d) = app.lineage_dialog() {
+        render_lineage_dialog(f, area, d, app, is_zh);
+        return;
+    }
+
+    if let Some(d) = app.waiting_queue_dialog() {
+        let queue = crate::session::waiting::waiting_queue(app.sessions());
+        render_waiting_queue_dialog(f, area, d, &queue, is_zh);
+        return;
+    }
+
+    if let Some(d) = app.wrapup_confirm_dialog() {
+        render_wrapup_confirm_dialog(f, area, d, is_zh);
+        return;
+    }
+
+    if let Some(d) = app.restart_exited_dialog() {
+        render_restart_exited_dialog(f, area, d, is_zh);
+        return;
+    }
+
+    if let Some(d) = app.profile_picker_dialog() {
+        render_profile_picker_dialog(f, area, d, is_zh);
+        return;
+    }
+
+    if let Some(d) = app.message_log_dialog() {
+        render_message_log_dialog(f, area, d, &app.message_log(), is_zh);
+        return;
+    }
+
+    if let Some(d) = app.forward_output_dialog() {
+        render_forward_output_dialog(f, area, d, is_zh);
+        return;
+    }
+
+    if let Some(d) = app.rebind_dialog() {
+        render_rebind_dialog(f, area, d, is_zh);
+        return;
+    }
+
     if let Some(d) = app.settings_dialog() {
         render_settings_dialog(f, area, d, is_zh);
         return;
@@ -148,13 +219,30 @@ pub(super) fn render_dialog(f: &mut Frame, area: Rect, app: &App) {
     }
 }
 
-pub(super) fn render_new_session_dialog(f: &mut Frame, area: Rect, d: &crate::ui::NewSessionDialog, is_zh: bool) {
-    let popup_area = centered_rect(75, 60, area);
-    f.render_widget(Clear, popup_area);
+const NEW_SESSION_GEOMETRY: DialogGeometry = DialogGeometry {
+    min_pct: (75, 40),
+    max_pct: (90, 90),
+};
+
+const FORK_GEOMETRY: DialogGeometry = DialogGeometry {
+    min_pct: (70, 30),
+    max_pct: (90, 80),
+};
 
+pub(super) fn render_new_session_dialog(
+    f: &mut Frame,
+    area: Rect,
+    d: &crate::ui::NewSessionDialog,
+    nudge: SizeNudge,
+    advanced: bool,
+    is_zh: bool,
+) {
     let base_style = Style::default();
     let is_path_active = d.field == crate::ui::NewSessionField::Path;
     let is_title_active = d.field == crate::ui::NewSessionField::Title;
+    let is_tool_active = d.field == crate::ui::NewSessionField::Tool;
+    let is_command_active = d.field == crate::ui::NewSessionField::Command;
+    let is_pre_active = d.field == crate::ui::NewSessionField::Pre;
     let is_group_active = d.field == crate::ui::NewSessionField::Group;
 
     let mut path_spans = vec![Span::raw(if is_zh { "路径:   " } else { "Path:   " })];
@@ -163,6 +251,22 @@ pub(super) fn render_new_session_dialog(f: &mut Frame, area: Rect, d: &crate::ui
     let mut title_spans = vec![Span::raw(if is_zh { "标题:   " } else { "Title:  " })];
     title_spans.extend(render_text_input(&d.title, is_title_active, base_style));
 
+    let tool_style = if is_tool_active {
+        crate::ui::theme::theme().selection_style()
+    } else {
+        base_style
+    };
+    let tool_spans = vec![
+        Span::raw(if is_zh { "工具:   " } else { "Tool:   " }),
+        Span::styled(format!("◂ {} ▸", d.tool.name()), tool_style),
+    ];
+
+    let mut command_spans = vec![Span::raw(if is_zh { "命令:   " } else { "Command:" })];
+    command_spans.extend(render_text_input(&d.command, is_command_active, base_style));
+
+    let mut pre_spans = vec![Span::raw(if is_zh { "前置:   " } else { "Pre:    " })];
+    pre_spans.extend(render_text_input(&d.pre_command, is_pre_active, base_style));
+
     let mut group_spans = vec![Span::raw(if is_zh { "分组:   " } else { "Group:  " })];
     group_spans.extend(render_text_input(
         &d.group_path,
@@ -178,8 +282,25 @@ pub(super) fn render_new_session_dialog(f: &mut Frame, area: Rect, d: &crate::ui
                 .add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
-        Line::from(path_spans),
     ];
+    if !d.templates.is_empty() {
+        let name = d
+            .template
+            .and_then(|i| d.templates.get(i))
+            .map_or(if is_zh { "(无)" } else { "(none)" }, |t| t.name.as_str());
+        lines.push(Line::from(Span::styled(
+            if is_zh {
+                format!("模板: {} • Ctrl+T 切换", name)
+            } else {
+                format!("Template: {} • Ctrl+T next", name)
+            },
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    lines.push(Line::from(path_spans));
+    let path_line = lines.len() - 1;
+    let mut focus_suggestion = None;
+    let mut focus_group = None;
 
     if d.path_will_be_created() {
         lines.push(Line::from(vec![
@@ -215,7 +336,8 @@ pub(super) fn render_new_session_dialog(f: &mut Frame, area: Rect, d: &crate::ui
             .take(max_show)
         {
             let style = if i == d.path_suggestions_idx {
-                Style::default().fg(Color::Black).bg(Color::Cyan)
+                focus_suggestion = Some(lines.len());
+                crate::ui::theme::theme().selection_style()
             } else {
                 Style::default().fg(Color::DarkGray)
             };
@@ -226,9 +348,21 @@ pub(super) fn render_new_session_dialog(f: &mut Frame, area: Rect, d: &crate::ui
         }
     }
 
-    lines.extend([Line::from(title_spans), Line::from(group_spans)]);
+    let title_line = lines.len();
+    lines.push(Line::from(title_spans));
+    if advanced {
+        lines.push(Line::from(tool_spans));
+        lines.push(Line::from(command_spans));
+        lines.push(Line::from(pre_spans));
+        lines.push(Line::from(group_spans));
+    } else {
+        lines.push(Line::from(Span::styled(
+            if is_zh { "▸ 高级… (Ctrl+O)" } else { "▸ advanced… (Ctrl+O)" },
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
 
-    if d.field == crate::ui::NewSessionField::Group {
+    if advanced && d.field == crate::ui::NewSessionField::Group {
         lines.push(Line::from(""));
         lines.push(Line::from(Span::styled(
             if is_zh { "分组 (↑/↓ 选择):" } else { "Groups (↑/↓ to select):" },
@@ -261,7 +395,8 @@ pub(super) fn render_new_session_dialog(f: &mut Frame, area: Rect, d: &crate::ui
             {
                 let label = if g.is_empty() { if is_zh { "(无)" } else { "(none)" } } else { g.as_str() };
                 let style = if i == d.group_selected {
-                    Style::default().fg(Color::Black).bg(Color::Cyan)
+                    focus_group = Some(lines.len());
+                    crate::ui::theme::theme().selection_style()
                 } else {
                     Style::default().fg(Color::DarkGray)
                 };
@@ -273,23 +408,43 @@ pub(super) fn render_new_session_dialog(f: &mut Frame, area: Rect, d: &crate::ui
         }
     }
 
+    if let Some(error) = &d.error {
+        let t = crate::ui::theme::theme();
+        lines.push(Line::from(Span::styled(error.clone(), Style::default().fg(t.error))));
+    }
+
     lines.push(Line::from(""));
     lines.push(Line::from(Span::styled(
-        if is_zh { "Tab: 补全路径 • ↑↓: 选择 • 回车: 下一个/提交 • Esc/Ctrl+C: 取消" } else { "Tab: complete path • ↑↓: pick • Enter: next/submit • Esc/Ctrl+C: cancel" },
+        if is_zh { "Tab: 补全路径/下一项 • ←→: 工具 • ↑↓: 选择 • 回车: 下一个/提交 • Esc/Ctrl+C: 取消" } else { "Tab: complete path/next • ←→: tool • ↑↓: pick • Enter: next/submit • Esc/Ctrl+C: cancel" },
         Style::default().fg(Color::DarkGray),
     )));
 
-    let p = Paragraph::new(lines)
-        .wrap(Wrap { trim: false })
-        .block(dialog_block("新建", "New", is_zh));
-
-    f.render_widget(p, popup_area);
+    let focus = match d.field {
+        crate::ui::NewSessionField::Path => focus_suggestion.unwrap_or(path_line),
+        crate::ui::NewSessionField::Title => title_line,
+        crate::ui::NewSessionField::Tool => title_line + 1,
+        crate::ui::NewSessionField::Command => title_line + 2,
+        crate::ui::NewSessionField::Pre => title_line + 3,
+        crate::ui::NewSessionField::Group => focus_group.unwrap_or(title_line + 4),
+    };
+    render_fitted_dialog(
+        f,
+        area,
+        NEW_SESSION_GEOMETRY,
+        nudge,
+        dialog_block("新建", "New", is_zh),
+        lines,
+        focus,
+    );
 }
 
-pub(super) fn render_fork_dialog(f: &mut Frame, area: Rect, d: &crate::ui::ForkDialog, is_zh: bool) {
-    let popup_area = centered_rect(70, 40, area);
-    f.render_widget(Clear, popup_area);
-
+pub(super) fn render_fork_dialog(
+    f: &mut Frame,
+    area: Rect,
+    d: &crate::ui::ForkDialog,
+    nudge: SizeNudge,
+    is_zh: bool,
+) {
     let base_style = Style::default();
     let is_title_active = d.field == crate::ui::ForkField::Title;
     let is_group_active = d.field == crate::ui::ForkField::Group;
@@ -304,7 +459,50 @@ pub(super) fn render_fork_dialog(f: &mut Frame, area: Rect, d: &crate::ui::ForkD
         base_style,
     ));
 
-    let lines = vec![
+    let is_context_active = d.field == crate::ui::ForkField::Context;
+    let context_label = if is_zh { "复制对话上下文" } else { "Copy conversation context" };
+    let context_line = match &d.context_copy {
+        Ok(_) => {
+            let style = if is_context_active {
+                crate::ui::theme::theme().selection_style()
+            } else {
+                Style::default()
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("[{}] {}", if d.copy_context { "x" } else { " " }, context_label),
+                    style,
+                ),
+            ])
+        }
+        Err(reason) => Line::from(vec![
+            Span::styled(
+                format!("[-] {}", context_label),
+                if is_context_active {
+                    Style::default().fg(Color::DarkGray).bg(Color::Indexed(236))
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                },
+            ),
+            Span::styled(format!("  ({reason})"), Style::default().fg(Color::DarkGray)),
+        ]),
+    };
+
+    let worktree_style = if d.field == crate::ui::ForkField::Worktree {
+        crate::ui::theme::theme().selection_style()
+    } else {
+        Style::default()
+    };
+    let worktree_line = Line::from(Span::styled(
+        format!(
+            "[{}] {}",
+            if d.worktree { "x" } else { " " },
+            if is_zh { "新建 git worktree" } else { "Create git worktree" }
+        ),
+        worktree_style,
+    ));
+
+    let mut lines = vec![
         Line::from(Span::styled(
             if is_zh { "复制会话" } else { "Fork Session" },
             Style::default()
@@ -314,18 +512,52 @@ pub(super) fn render_fork_dialog(f: &mut Frame, area: Rect, d: &crate::ui::ForkD
         Line::from(""),
         Line::from(title_spans),
         Line::from(group_spans),
+        context_line,
+        worktree_line,
+    ];
+    if let Some(dest) = d.worktree_dest() {
+        let mut branch_spans = vec![Span::raw(if is_zh { "分支: " } else { "Branch: " })];
+        branch_spans.extend(render_text_input(
+            &d.branch,
+            d.field == crate::ui::ForkField::Branch,
+            base_style,
+        ));
+        lines.push(Line::from(branch_spans));
+        lines.push(Line::from(Span::styled(
+            format!("  → {}", dest.display()),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    if let Some(err) = &d.error {
+        lines.push(Line::from(Span::styled(
+            err.clone(),
+            Style::default().fg(Color::Red),
+        )));
+    }
+    lines.extend([
         Line::from(""),
         Line::from(Span::styled(
-            if is_zh { "Tab: 切换字段 • 回车: 下一个/提交 • Esc/Ctrl+C: 取消" } else { "Tab: switch field • Enter: next/submit • Esc/Ctrl+C: cancel" },
+            if is_zh { "Tab: 切换字段 • 空格: 切换选项 • 回车: 下一个/提交 • Esc/Ctrl+C: 取消" } else { "Tab: switch field • Space: toggle • Enter: next/submit • Esc/Ctrl+C: cancel" },
             Style::default().fg(Color::DarkGray),
         )),
-    ];
-
-    let p = Paragraph::new(lines)
-        .wrap(Wrap { trim: false })
-        .block(dialog_block("复制", "Fork", is_zh));
-
-    f.render_widget(p, popup_area);
+    ]);
+
+    let focus = match d.field {
+        crate::ui::ForkField::Title => 2,
+        crate::ui::ForkField::Group => 3,
+        crate::ui::ForkField::Context => 4,
+        crate::ui::ForkField::Worktree => 5,
+        crate::ui::ForkField::Branch => 6,
+    };
+    render_fitted_dialog(
+        f,
+        area,
+        FORK_GEOMETRY,
+        nudge,
+        dialog_block("复制", "Fork", is_zh),
+        lines,
+        focus,
+    );
 }
 
 pub(super) fn render_create_group_dialog(f: &mut Frame, area: Rect, d: &crate::ui::CreateGroupDialog, is_zh: bool) {
@@ -371,7 +603,7 @@ pub(super) fn render_create_group_dialog(f: &mut Frame, area: Rect, d: &crate::u
 
         for (i, g) in d.matches.iter().enumerate().skip(start).take(max_show) {
             let style = if i == d.selected {
-                Style::default().fg(Color::Black).bg(Color::Cyan)
+                crate::ui::theme::theme().selection_style()
             } else {
                 Style::default().fg(Color::DarkGray)
             };
@@ -403,9 +635,18 @@ pub(super) fn render_move_group_dialog(f: &mut Frame, area: Rect, d: &crate::ui:
     let mut input_spans = vec![Span::raw(if is_zh { "过滤: " } else { "Filter: " })];
     input_spans.extend(render_text_input(&d.input, true, base_style));
 
+    let many = d.batch.len() > 1;
+    let heading = match (many, is_zh) {
+        (true, true) => format!("移动 {} 个会话到分组", d.batch.len()),
+        (true, false) => format!("Move {} Sessions to Group", d.batch.len()),
+        (false, true) => "移动会话到分组".to_string(),
+        (false, false) => "Move Session to Group".to_string(),
+    };
+    let subject = if many { batch_titles(&d.batch, 4) } else { d.title.clone() };
+
     let mut lines = vec![
         Line::from(Span::styled(
-            if is_zh { "移动会话到分组" } else { "Move Session to Group" },
+            heading,
             Style::default()
                 .fg(Color::Cyan)
                 .add_modifier(Modifier::BOLD),
@@ -414,7 +655,7 @@ pub(super) fn render_move_group_dialog(f: &mut Frame, area: Rect, d: &crate::ui:
         Line::from(vec![
             Span::raw(if is_zh { "标题:  " } else { "Title:  " }),
             Span::styled(
-                d.title.clone(),
+                subject,
                 Style::default().add_modifier(Modifier::BOLD),
             ),
         ]),
@@ -446,7 +687,7 @@ pub(super) fn render_move_group_dialog(f: &mut Frame, area: Rect, d: &crate::ui:
         for (i, g) in d.matches.iter().enumerate().skip(start).take(max_show) {
             let label = if g.is_empty() { if is_zh { "(无)" } else { "(none)" } } else { g.as_str() };
             let style = if i == d.selected {
-                Style::default().fg(Color::Black).bg(Color::Cyan)
+                crate::ui::theme::theme().selection_style()
             } else {
                 Style::default().fg(Color::DarkGray)
             };
@@ -470,6 +711,398 @@ pub(super) fn render_move_group_dialog(f: &mut Frame, area: Rect, d: &crate::ui:
     f.render_widget(p, popup_area);
 }
 
+pub(super) fn render_lineage_dialog(
+    f: &mut Frame,
+    area: Rect,
+    d: &crate::ui::LineageDialog,
+    app: &App,
+    is_zh: bool,
+) {
+    let popup_area = centered_rect(60, 50, area);
+    f.render_widget(Clear, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .split(popup_area);
+
+    let items: Vec<ListItem> = d
+        .rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let indent = "  ".repeat(row.depth);
+            let branch = if row.depth == 0 { "● " } else { "└ " };
+            let Some(s) = app.session_by_id(&row.id) else {
+                return ListItem::new(Line::from(format!("{indent}{branch}{}", row.id)));
+            };
+
+            let (status, status_color) = match s.status {
+                Status::Running => ("running", Color::Yellow),
+                Status::Waiting => ("waiting", Color::Blue),
+                Status::Idle => ("idle", Color::DarkGray),
+                Status::Error => ("error", Color::Red),
+                Status::Starting => ("starting", Color::Cyan),
+                Status::Stopped => ("stopped", Color::DarkGray),
+            };
+
+            let mut title_style = Style::default();
+            if row.is_focus {
+                title_style = title_style.fg(Color::Cyan).add_modifier(Modifier::BOLD);
+            }
+            if i == d.selected {
+                title_style = title_style.patch(crate::ui::theme::theme().selection_style());
+            }
+
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{indent}{branch}"), Style::default().fg(Color::DarkGray)),
+                Span::styled(s.title.clone(), title_style),
+                Span::raw("  "),
+                Span::styled(status, Style::default().fg(status_color)),
+                Span::styled(
+                    format!("  {}", format_elapsed_short(s.created_at)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items).block(dialog_block("分支谱系", "Fork Lineage", is_zh));
+    let mut state = ListState::default().with_selected(Some(d.selected));
+    f.render_stateful_widget(list, chunks[0], &mut state);
+
+    let hint = Paragraph::new(if is_zh {
+        "↑/↓: 选择 • 回车: 跳转 • D: 与父会话比较 • Esc: 关闭"
+    } else {
+        "↑/↓: select • Enter: jump • D: diff vs parent • Esc: close"
+    })
+    .style(Style::default().fg(Color::DarkGray))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(hint, chunks[1]);
+}
+
+pub(super) fn render_waiting_queue_dialog(
+    f: &mut Frame,
+    area: Rect,
+    d: &crate::ui::WaitingQueueDialog,
+    queue: &[&crate::session::Instance],
+    is_zh: bool,
+) {
+    let popup_area = centered_rect(60, 50, area);
+    f.render_widget(Clear, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .split(popup_area);
+    let block = dialog_block("等待输入", "Waiting for Input", is_zh);
+
+    if queue.is_empty() {
+        let empty = Paragraph::new(if is_zh { "没有等待输入的会话" } else { "Nothing is waiting for input" })
+            .style(Style::default().fg(Color::DarkGray))
+            .alignment(Alignment::Center)
+            .block(block);
+        f.render_widget(empty, chunks[0]);
+    } else {
+        let selected = d.selected_index(queue);
+        let items: Vec<ListItem> = queue
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                let pos = if i < 9 { format!("{}.", i + 1) } else { "  ".to_string() };
+                let mut title_style = Style::default();
+                if i == selected {
+                    title_style = title_style.patch(crate::ui::theme::theme().selection_style());
+                }
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{pos} "), Style::default().fg(Color::Blue)),
+                    Span::styled(s.title.clone(), title_style),
+                    Span::styled(format!("  {}", s.group_path), Style::default().fg(Color::DarkGray)),
+                    Span::styled(
+                        format!("  {}", format_elapsed_short(crate::session::waiting::waiting_since(s))),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                ]))
+            })
+            .collect();
+
+        let list = List::new(items).block(block);
+        let mut state = ListState::default().with_selected(Some(selected));
+        f.render_stateful_widget(list, chunks[0], &mut state);
+    }
+
+    let hint = Paragraph::new(if is_zh {
+        "↑/↓: 选择 • 回车: 进入 • 1-9: 直接进入 • Esc: 关闭"
+    } else {
+        "↑/↓: select • Enter: attach • 1-9: attach directly • Esc: close"
+    })
+    .style(Style::default().fg(Color::DarkGray))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(hint, chunks[1]);
+}
+
+pub(super) fn render_profile_picker_dialog(
+    f: &mut Frame,
+    area: Rect,
+    d: &crate::ui::ProfilePickerDialog,
+    is_zh: bool,
+) {
+    let popup_area = centered_rect(50, 40, area);
+    f.render_widget(Clear, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .split(popup_area);
+    let block = dialog_block("切换配置", "Switch Profile", is_zh);
+
+    let items: Vec<ListItem> = d
+        .profiles
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let style = if i == d.selected {
+                crate::ui::theme::theme().selection_style()
+            } else {
+                Style::default()
+            };
+            let mut spans = vec![Span::styled(name.clone(), style)];
+            if *name == d.current {
+                spans.push(Span::styled(
+                    if is_zh { "  (当前)" } else { "  (current)" },
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let list = List::new(items).block(block);
+    let mut state = ListState::default().with_selected(Some(d.selected));
+    f.render_stateful_widget(list, chunks[0], &mut state);
+
+    let hint = Paragraph::new(if is_zh {
+        "↑/↓: 选择 • 回车: 切换 • Esc: 关闭"
+    } else {
+        "↑/↓: select • Enter: switch • Esc: close"
+    })
+    .style(Style::default().fg(Color::DarkGray))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(hint, chunks[1]);
+}
+
+pub(super) fn render_message_log_dialog(
+    f: &mut Frame,
+    area: Rect,
+    d: &crate::ui::MessageLogDialog,
+    log: &[crate::ui::app::messages::LoggedMessage],
+    is_zh: bool,
+) {
+    let popup_area = centered_rect(70, 60, area);
+    f.render_widget(Clear, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .split(popup_area);
+    let block = dialog_block("最近消息", "Recent Messages", is_zh);
+
+    if log.is_empty() {
+        let empty = Paragraph::new(if is_zh { "暂无消息" } else { "No messages yet" })
+            .style(Style::default().fg(Color::DarkGray))
+            .block(block);
+        f.render_widget(empty, chunks[0]);
+    } else {
+        let items: Vec<ListItem> = log
+            .iter()
+            .enumerate()
+            .map(|(i, m)| {
+                let mut text_style = Style::default().fg(m.color);
+                if i == d.selected {
+                    text_style = text_style.patch(crate::ui::theme::theme().selection_style());
+                }
+                let mut spans = vec![
+                    Span::styled(
+                        format!("{} ", m.at.format("%H:%M:%S")),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled(m.text.clone(), text_style),
+                ];
+                if m.repeats > 1 {
+                    spans.push(Span::styled(
+                        format!("  ×{}", m.repeats),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let list = List::new(items).block(block);
+        let mut state = ListState::default().with_selected(Some(d.selected));
+        f.render_stateful_widget(list, chunks[0], &mut state);
+    }
+
+    let hint = Paragraph::new(if is_zh {
+        "↑/↓: 滚动 • Esc: 关闭"
+    } else {
+        "↑/↓: scroll • Esc: close"
+    })
+    .style(Style::default().fg(Color::DarkGray))
+    .alignment(Alignment::Center)
+    .block(Block::default().borders(Borders::ALL));
+    f.render_widget(hint, chunks[1]);
+}
+
+pub(super) fn render_forward_output_dialog(
+    f: &mut Frame,
+    area: Rect,
+    d: &crate::ui::ForwardOutputDialog,
+    is_zh: bool,
+) {
+    let popup_area = centered_rect(60, 50, area);
+    f.render_widget(Clear, popup_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(3)])
+        .split(popup_area);
+    let title = if is_zh {
+        format!("转发 {} 的输出到…", d.source_title)
+    } else {
+        format!("Forward output of {} to…", d.source_title)
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(crate::ui::theme::theme().dialog_border_style())
+        .title(title);
+
+    let items: Vec<ListItem> = d
+        .targets
+        .iter()
+        .enumerate()
+        .map(|(i, t)| {
+            let mut title_style = Style::default();
+            if i == d.selected {
+                title_style = title_style.patch(crate::ui::theme::theme().selection_style());
+            }
+            let (state, state_style) = match (t.running, is_zh) {
+                (true, _) => ("", Style::default()),
+                (false, true) => ("  未运行", Style::default().fg(Color::DarkGray)),
+                (false, false) => ("  stopped", Style::default().fg(Color::DarkGray)),
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(t.title.clone(), title_style),
+                Span::styled(state, state_style),
+            ]))
+        })
+        .collect();
+    let list = List::new(items).block(block);
+    let mut state = ListState::default().with_selected(Some(d.selected));
+    f.render_stateful_widget(list, chunks[0], &mut state);
+
+    let (hint, hint_style) = match d.selected_target() {
+        Some(t) if d.confirm_start => (
+            if is_zh {
+                format!("{} 未运行 • 回车: 启动并转发 • Esc: 取消", t.title)
+            } else {
+                format!("{} is not running • Enter: start it and forward • Esc: cancel", t.title)
+            },
+            Style::default().fg(Color::Yellow),
+        ),
+        _ => (
+            if is_zh {
+                "↑/↓: 选择 • 回车: 转发（不提交）• Esc: 关闭".to_string()
+            } else {
+                "↑/↓: select • Enter: forward (not submitted) • Esc: close".to_string()
+            },
+            Style::default().fg(Color::DarkGray),
+        ),
+    };
+    let hint = Paragraph::new(hint)
+        .style(hint_style)
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(hint, chunks[1]);
+}
+
+pub(super) fn render_rebind_dialog(f: &mut Frame, area: Rect, d: &crate::ui::RebindDialog, is_zh: bool) {
+    use crate::ui::RebindStage;
+
+    let popup_area = centered_rect(60, 70, area);
+    f.render_widget(Clear, popup_area);
+
+    let action = d.rows.get(d.selected).map(|r| r.target.name()).unwrap_or("");
+    let footer: Vec<Line> = match &d.stage {
+        RebindStage::Browse => vec![Line::from(Span::styled(
+            if is_zh {
+                "↑/↓: 选择 • e/回车: 重新绑定 • Esc: 返回"
+            } else {
+                "↑/↓: select • e/Enter: rebind • Esc: back"
+            },
+            Style::default().fg(Color::DarkGray),
+        ))],
+        RebindStage::Capture => vec![Line::from(Span::styled(
+            if is_zh {
+                format!("按下 '{action}' 的新按键，Esc 取消")
+            } else {
+                format!("Press the new key for '{action}', Esc to cancel")
+            },
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        ))],
+        RebindStage::Confirm { value, conflicts, .. } => {
+            let current = d.rows.get(d.selected).map(|r| r.current.as_str()).unwrap_or("");
+            let mut lines = vec![Line::from(vec![
+                Span::raw(format!("{action}: {current} → ")),
+                Span::styled(value.clone(), Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+            ])];
+            lines.extend(conflicts.iter().map(|c| {
+                Line::from(Span::styled(format!("⚠ {c}"), Style::default().fg(Color::Yellow)))
+            }));
+            lines.push(Line::from(Span::styled(
+                if is_zh { "回车: 写入配置 • Esc: 取消" } else { "Enter: save to config • Esc: cancel" },
+                Style::default().fg(Color::DarkGray),
+            )));
+            lines
+        }
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(footer.len() as u16 + 2)])
+        .split(popup_area);
+
+    let items: Vec<ListItem> = d
+        .rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut name_style = Style::default();
+            if i == d.selected {
+                name_style = name_style.patch(crate::ui::theme::theme().selection_style());
+            }
+            let scope = match row.target {
+                crate::config::RebindTarget::Action(_) => "",
+                crate::config::RebindTarget::Tmux(_) => "tmux ",
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{:<24}", format!("{scope}{}", row.target.name())), name_style),
+                Span::styled(row.current.clone(), Style::default().fg(Color::Yellow)),
+            ]))
+        })
+        .collect();
+
+    let list = List::new(items).block(dialog_block("重新绑定按键", "Rebind Keys", is_zh));
+    let mut state = ListState::default().with_selected(Some(d.selected));
+    f.render_stateful_widget(list, chunks[0], &mut state);
+
+    let footer = Paragraph::new(footer)
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(footer, chunks[1]);
+}
+
 pub(super) fn render_tag_picker_dialog(f: &mut Frame, area: Rect, d: &crate::ui::TagPickerDialog, is_zh: bool) {
     let popup_area = centered_rect(60, 50, area);
     f.render_widget(Clear, popup_area);
@@ -506,7 +1139,7 @@ pub(super) fn render_tag_picker_dialog(f: &mut Frame, area: Rect, d: &crate::ui:
                 let style = if i == d.selected {
                     Style::default()
                         .fg(fg)
-                        .bg(Color::Cyan)
+                        .bg(crate::ui::theme::theme().selection_bg)
                         .add_modifier(Modifier::BOLD)
                 } else {
                     Style::default().fg(fg)
@@ -531,6 +1164,61 @@ pub(super) fn render_tag_picker_dialog(f: &mut Frame, area: Rect, d: &crate::ui:
     f.render_widget(hint, chunks[1]);
 }
 
+pub(super) fn render_label_picker_dialog(f: &mut Frame, area: Rect, d: &crate::ui::LabelPickerDialog, is_zh: bool) {
+    let popup_area = centered_rect(50, 60, area);
+    f.render_widget(Clear, popup_area);
+
+    let mut label_spans = vec![Span::raw(if is_zh { "标签:  " } else { "Label:  " })];
+    label_spans.extend(render_text_input(&d.label, true, Style::default()));
+
+    // Live preview: the badge exactly as the tree row will show it.
+    let (label, color) = d.result();
+    let preview_span = match crate::session::label_badge(&label) {
+        Some(badge) => Span::styled(badge, Style::default().fg(crate::ui::label_fg(color))),
+        None => Span::styled(if is_zh { "(无标签)" } else { "(no label)" }, Style::default().fg(Color::DarkGray)),
+    };
+
+    let mut lines = vec![
+        Line::from(label_spans),
+        Line::from(vec![Span::raw(if is_zh { "预览:  " } else { "Preview: " }), preview_span]),
+        Line::from(""),
+    ];
+
+    for (i, color) in crate::session::LabelColor::ALL.into_iter().enumerate() {
+        let selected = i == d.choice;
+        let mut style = Style::default().fg(crate::ui::label_fg(color));
+        if selected {
+            style = style
+                .bg(crate::ui::theme::theme().selection_bg)
+                .add_modifier(Modifier::BOLD);
+        }
+        lines.push(Line::from(vec![
+            Span::raw(if selected { "▸ " } else { "  " }),
+            Span::styled(format!("■ {}", color.name()), style),
+        ]));
+    }
+    let clear_style = if d.clears() {
+        crate::ui::theme::theme().selection_style().add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+    lines.push(Line::from(vec![
+        Span::raw(if d.clears() { "▸ " } else { "  " }),
+        Span::styled(if is_zh { "✕ 清除标签" } else { "✕ clear label" }, clear_style),
+    ]));
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        if is_zh { "输入: 标签 • ↑/↓: 颜色 • 回车: 应用 • Esc: 取消" } else { "Type: label • ↑/↓: color • Enter: apply • Esc: cancel" },
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let p = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(dialog_block("标签", "Label", is_zh));
+    f.render_widget(p, popup_area);
+}
+
 pub(super) fn render_rename_session_dialog(f: &mut Frame, area: Rect, d: &crate::ui::RenameSessionDialog, is_zh: bool) {
     let popup_area = centered_rect(70, 45, area);
     f.render_widget(Clear, popup_area);
@@ -560,79 +1248,219 @@ pub(super) fn render_rename_session_dialog(f: &mut Frame, area: Rect, d: &crate:
     };
 
     let color_style = if d.field == crate::ui::SessionEditField::Color {
-        Style::default()
-            .fg(Color::Black)
-            .bg(Color::Cyan)
-            .add_modifier(Modifier::BOLD)
+        crate::ui::theme::theme().selection_style().add_modifier(Modifier::BOLD)
     } else {
         Style::default().fg(Color::DarkGray)
     };
 
+    let mut lines = vec![
+        Line::from(Span::styled(
+            if is_zh { "编辑会话" } else { "Edit Session" },
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(title_spans),
+        Line::from(label_spans),
+        Line::from(vec![
+            Span::raw(if is_zh { "颜色:      " } else { "Color:      " }),
+            Span::styled(
+                format!("{color_name}"),
+                color_style.fg(color_fg).add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(sid_spans),
+    ];
+    if let Some(error) = &d.error {
+        let t = crate::ui::theme::theme();
+        lines.push(Line::from(Span::styled(error.clone(), Style::default().fg(t.error))));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("Tab", Style::default().fg(Color::Yellow)),
+        Span::raw(if is_zh { ":下一字段  " } else { ":next field  " }),
+        Span::styled("Enter", Style::default().fg(Color::Green)),
+        Span::raw(if is_zh { ":下一个/应用  " } else { ":next/apply  " }),
+        Span::styled("←/→", Style::default().fg(Color::Yellow)),
+        Span::raw(if is_zh { ":颜色  " } else { ":color  " }),
+        Span::styled("Esc", Style::default().fg(Color::DarkGray)),
+        Span::raw(if is_zh { ":取消" } else { ":cancel" }),
+    ]));
+
+    let p = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(dialog_block("会话", "Session", is_zh));
+
+    f.render_widget(p, popup_area);
+}
+
+pub(super) fn render_rename_group_dialog(f: &mut Frame, area: Rect, d: &crate::ui::RenameGroupDialog, is_zh: bool) {
+    let popup_area = centered_rect(70, 35, area);
+    f.render_widget(Clear, popup_area);
+
+    let base_style = Style::default();
+    let mut new_path_spans = vec![Span::raw(if is_zh { "到:    " } else { "To:    " })];
+    new_path_spans.extend(render_text_input(&d.new_path, true, base_style));
+
+    let lines = vec![
+        Line::from(Span::styled(
+            if is_zh { "重命名分组" } else { "Rename Group" },
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::raw(if is_zh { "从:    " } else { "From:  " }),
+            Span::styled(d.old_path.clone(), Style::default().fg(Color::DarkGray)),
+        ]),
+        Line::from(new_path_spans),
+        Line::from(""),
+        Line::from(Span::styled(
+            if is_zh { "回车: 应用 • Esc/Ctrl+C: 取消" } else { "Enter: apply • Esc/Ctrl+C: cancel" },
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+
+    let p = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(dialog_block("分组", "Group", is_zh));
+
+    f.render_widget(p, popup_area);
+}
+
+pub(super) fn render_group_notes_dialog(f: &mut Frame, area: Rect, d: &crate::ui::GroupNotesDialog, is_zh: bool) {
+    let popup_area = centered_rect(70, 35, area);
+    f.render_widget(Clear, popup_area);
+
+    let mut notes_spans = vec![Span::raw(if is_zh { "备注:  " } else { "Notes: " })];
+    notes_spans.extend(render_text_input(&d.notes, true, Style::default()));
+
     let lines = vec![
         Line::from(Span::styled(
-            if is_zh { "编辑会话" } else { "Edit Session" },
+            if is_zh { "分组备注" } else { "Group Notes" },
             Style::default()
                 .fg(Color::Cyan)
                 .add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
-        Line::from(title_spans),
-        Line::from(label_spans),
         Line::from(vec![
-            Span::raw(if is_zh { "颜色:      " } else { "Color:      " }),
-            Span::styled(
-                format!("{color_name}"),
-                color_style.fg(color_fg).add_modifier(Modifier::BOLD),
-            ),
+            Span::raw(if is_zh { "分组:  " } else { "Group: " }),
+            Span::styled(d.path.clone(), Style::default().fg(Color::DarkGray)),
         ]),
-        Line::from(sid_spans),
+        Line::from(notes_spans),
+        Line::from(""),
+        Line::from(Span::styled(
+            if is_zh { "输入 \\n 换行；留空则清除" } else { "Type \\n for a line break; leave empty to clear" },
+            Style::default().fg(Color::DarkGray),
+        )),
+        Line::from(Span::styled(
+            if is_zh { "回车: 保存 • Esc/Ctrl+C: 取消" } else { "Enter: save • Esc/Ctrl+C: cancel" },
+            Style::default().fg(Color::DarkGray),
+        )),
+    ];
+
+    let p = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(dialog_block("分组", "Group", is_zh));
+
+    f.render_widget(p, popup_area);
+}
+
+pub(super) fn render_repair_path_dialog(f: &mut Frame, area: Rect, d: &crate::ui::RepairPathDialog, is_zh: bool) {
+    let popup_area = centered_rect(70, 50, area);
+    f.render_widget(Clear, popup_area);
+
+    let mut path_spans = vec![Span::raw(if is_zh { "新路径: " } else { "New path: " })];
+    path_spans.extend(render_text_input(&d.path, true, Style::default()));
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            if is_zh { "项目目录不存在" } else { "Project directory missing" },
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )),
         Line::from(""),
         Line::from(vec![
-            Span::styled("Tab", Style::default().fg(Color::Yellow)),
-            Span::raw(if is_zh { ":下一字段  " } else { ":next field  " }),
-            Span::styled("Enter", Style::default().fg(Color::Green)),
-            Span::raw(if is_zh { ":下一个/应用  " } else { ":next/apply  " }),
-            Span::styled("←/→", Style::default().fg(Color::Yellow)),
-            Span::raw(if is_zh { ":颜色  " } else { ":color  " }),
-            Span::styled("Esc", Style::default().fg(Color::DarkGray)),
-            Span::raw(if is_zh { ":取消" } else { ":cancel" }),
+            Span::raw(if is_zh { "会话:   " } else { "Session:  " }),
+            Span::styled(d.title.clone(), Style::default().add_modifier(Modifier::BOLD)),
+        ]),
+        Line::from(vec![
+            Span::raw(if is_zh { "原路径: " } else { "Old path: " }),
+            Span::styled(
+                d.old_path.to_string_lossy().to_string(),
+                Style::default().fg(Color::DarkGray),
+            ),
         ]),
+        Line::from(path_spans),
     ];
 
+    if d.suggestions_visible {
+        let max_show = 6usize;
+        let start = (d.suggestions_idx + 1).saturating_sub(max_show);
+        for (i, s) in d.suggestions.iter().enumerate().skip(start).take(max_show) {
+            let style = if i == d.suggestions_idx {
+                crate::ui::theme::theme().selection_style()
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            lines.push(Line::from(vec![
+                Span::raw("          "),
+                Span::styled(s.clone(), style),
+            ]));
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("Tab", Style::default().fg(Color::Yellow)),
+        Span::raw(if is_zh { ":补全  " } else { ":complete  " }),
+        Span::styled("Enter", Style::default().fg(Color::Green)),
+        Span::raw(if is_zh { ":保存并启动  " } else { ":save & start  " }),
+        Span::styled("Ctrl+D", Style::default().fg(Color::Red)),
+        Span::raw(if is_zh { ":删除会话  " } else { ":delete session  " }),
+        Span::styled("Esc", Style::default().fg(Color::DarkGray)),
+        Span::raw(if is_zh { ":取消" } else { ":cancel" }),
+    ]));
+
     let p = Paragraph::new(lines)
         .wrap(Wrap { trim: false })
-        .block(dialog_block("会话", "Session", is_zh));
-
+        .block(dialog_block("修复路径", "Fix Path", is_zh));
     f.render_widget(p, popup_area);
 }
 
-pub(super) fn render_rename_group_dialog(f: &mut Frame, area: Rect, d: &crate::ui::RenameGroupDialog, is_zh: bool) {
+pub(super) fn render_reparent_group_dialog(f: &mut Frame, area: Rect, d: &crate::ui::ReparentGroupDialog, is_zh: bool) {
+    let t = crate::ui::theme::theme();
     let popup_area = centered_rect(70, 35, area);
     f.render_widget(Clear, popup_area);
 
-    let base_style = Style::default();
-    let mut new_path_spans = vec![Span::raw(if is_zh { "到:    " } else { "To:    " })];
-    new_path_spans.extend(render_text_input(&d.new_path, true, base_style));
+    let mut parent_spans = vec![Span::raw(if is_zh { "移到:  " } else { "Under: " })];
+    parent_spans.extend(render_text_input(&d.new_parent, true, Style::default()));
 
-    let lines = vec![
+    let mut lines = vec![
         Line::from(Span::styled(
-            if is_zh { "重命名分组" } else { "Rename Group" },
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
+            if is_zh { "移动分组" } else { "Move Group" },
+            Style::default().fg(t.accent).add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
         Line::from(vec![
-            Span::raw(if is_zh { "从:    " } else { "From:  " }),
-            Span::styled(d.old_path.clone(), Style::default().fg(Color::DarkGray)),
+            Span::raw(if is_zh { "分组:  " } else { "Group: " }),
+            Span::styled(d.path.clone(), t.dim_style()),
         ]),
-        Line::from(new_path_spans),
-        Line::from(""),
+        Line::from(parent_spans),
         Line::from(Span::styled(
-            if is_zh { "回车: 应用 • Esc/Ctrl+C: 取消" } else { "Enter: apply • Esc/Ctrl+C: cancel" },
-            Style::default().fg(Color::DarkGray),
+            if is_zh { "留空表示移到顶层；子分组和会话一起移动" } else { "Empty moves it to the top level; subgroups and sessions move along" },
+            t.dim_style(),
         )),
     ];
+    if let Some(error) = &d.error {
+        lines.push(Line::from(Span::styled(error.clone(), Style::default().fg(t.error))));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        if is_zh { "回车: 移动 • Esc/Ctrl+C: 取消" } else { "Enter: move • Esc/Ctrl+C: cancel" },
+        t.dim_style(),
+    )));
 
     let p = Paragraph::new(lines)
         .wrap(Wrap { trim: false })
@@ -664,28 +1492,156 @@ pub(super) fn render_quit_confirm_dialog(f: &mut Frame, area: Rect, is_zh: bool)
     f.render_widget(p, popup_area);
 }
 
-pub(super) fn render_delete_confirm_dialog(f: &mut Frame, area: Rect, d: &crate::ui::DeleteConfirmDialog, is_zh: bool) {
+pub(super) fn render_wrapup_confirm_dialog(
+    f: &mut Frame,
+    area: Rect,
+    d: &crate::ui::WrapupConfirmDialog,
+    is_zh: bool,
+) {
     let popup_area = centered_rect(60, 30, area);
     f.render_widget(Clear, popup_area);
 
+    let t = crate::ui::theme::theme();
+    let scope = match (&d.group, is_zh) {
+        (Some(group), true) => format!("分组 {group} 中 {} 个运行中的会话", d.count),
+        (Some(group), false) => format!("{} running session(s) in {group}", d.count),
+        (None, true) => format!("全部 {} 个运行中的会话", d.count),
+        (None, false) => format!("All {} running session(s)", d.count),
+    };
+    let grace = crate::session::wrapup::DEFAULT_GRACE_SECS;
     let lines = vec![
         Line::from(Span::styled(
-            if is_zh { "删除会话？" } else { "Delete session?" },
-            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            scope,
+            Style::default().fg(t.highlight).add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
-        Line::from(vec![
-            Span::raw(if is_zh { "标题: " } else { "Title: " }),
-            Span::styled(
-                d.title.clone(),
-                Style::default().add_modifier(Modifier::BOLD),
-            ),
-        ]),
-        Line::from(vec![
-            Span::raw("ID:    "),
-            Span::styled(d.session_id.clone(), Style::default().fg(Color::DarkGray)),
-        ]),
+        Line::from(if is_zh {
+            format!("让每个 Agent 收尾，最多等待 {grace}s，然后停止会话。")
+        } else {
+            format!("Ask each agent to wrap up, wait up to {grace}s, then stop the sessions.")
+        }),
+        Line::from(""),
+        Line::from(if is_zh {
+            "Enter/y 开始，其他键取消。"
+        } else {
+            "Enter/y: start, any other key: cancel."
+        }),
+    ];
+
+    let p = Paragraph::new(lines)
+        .alignment(ratatui::layout::Alignment::Center)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(t.dialog_border_style())
+                .title(if is_zh { "收尾" } else { "Wrap up" }),
+        );
+
+    f.render_widget(p, popup_area);
+}
+
+pub(super) fn render_restart_exited_dialog(
+    f: &mut Frame,
+    area: Rect,
+    d: &crate::ui::RestartExitedDialog,
+    is_zh: bool,
+) {
+    let popup_area = centered_rect(60, 30, area);
+    f.render_widget(Clear, popup_area);
+
+    let reason = match (d.attach_failed, is_zh) {
+        (true, true) => "连接完成前会话就已退出。",
+        (true, false) => "It was gone before the attach finished.",
+        (false, true) => "连接期间会话已结束。",
+        (false, false) => "It ended while you were attached.",
+    };
+    let t = crate::ui::theme::theme();
+    let lines = vec![
+        Line::from(Span::styled(
+            d.title.clone(),
+            Style::default().fg(t.highlight).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(reason),
         Line::from(""),
+        Line::from(if is_zh {
+            "Enter/y 重启，其他键返回列表。"
+        } else {
+            "Enter/y: restart, any other key: back to the list."
+        }),
+    ];
+
+    let p = Paragraph::new(lines)
+        .alignment(ratatui::layout::Alignment::Center)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(t.dialog_border_style())
+                .title(if is_zh { "会话已退出" } else { "Session exited" }),
+        );
+
+    f.render_widget(p, popup_area);
+}
+
+pub(super) fn render_delete_confirm_dialog(f: &mut Frame, area: Rect, d: &crate::ui::DeleteConfirmDialog, is_zh: bool) {
+    let popup_area = centered_rect(60, 30, area);
+    f.render_widget(Clear, popup_area);
+
+    let (lines, _) = delete_confirm_lines(d, is_zh);
+    let p = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(dialog_block("确认", "Confirm", is_zh));
+
+    f.render_widget(p, popup_area);
+}
+
+/// The delete-confirm dialog's lines, and which of them take clicks.
+fn delete_confirm_lines(
+    d: &crate::ui::DeleteConfirmDialog,
+    is_zh: bool,
+) -> (Vec<Line<'static>>, Vec<LineTarget>) {
+    let mut lines = if d.batch.len() > 1 {
+        vec![
+            Line::from(Span::styled(
+                if is_zh {
+                    format!("删除 {} 个会话？", d.batch.len())
+                } else {
+                    format!("Delete {} sessions?", d.batch.len())
+                },
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                batch_titles(&d.batch, 6),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+        ]
+    } else {
+        vec![
+            Line::from(Span::styled(
+                if is_zh { "删除会话？" } else { "Delete session?" },
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            Line::from(vec![
+                Span::raw(if is_zh { "标题: " } else { "Title: " }),
+                Span::styled(
+                    d.title.clone(),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            Line::from(vec![
+                Span::raw("ID:    "),
+                Span::styled(d.session_id.clone(), Style::default().fg(Color::DarkGray)),
+            ]),
+        ]
+    };
+    let mut targets = Vec::new();
+    lines.push(Line::from(""));
+    targets.push(LineTarget::line(lines.len(), DialogTarget::ToggleKillTmux));
+    lines.extend([
         Line::from(vec![
             Span::raw(if is_zh { "终止 tmux 会话: " } else { "Kill tmux session: " }),
             Span::styled(
@@ -702,13 +1658,50 @@ pub(super) fn render_delete_confirm_dialog(f: &mut Frame, area: Rect, d: &crate:
             ),
             Span::raw(if is_zh { "  (按 't' 切换)" } else { "  (press 't' to toggle)" }),
         ]),
-        Line::from(""),
-        Line::from(Span::styled(
-            if is_zh { "y/回车: 确认 • n/Esc/Ctrl+C: 取消" } else { "y/Enter: confirm • n/Esc/Ctrl+C: cancel" },
+    ]);
+    if let Some(path) = &d.worktree {
+        targets.push(LineTarget::line(lines.len(), DialogTarget::ToggleRemoveWorktree));
+        lines.push(Line::from(vec![
+            Span::raw(if is_zh { "删除 worktree: " } else { "Remove worktree: " }),
+            Span::styled(
+                if is_zh { if d.remove_worktree { "是" } else { "否" } } else { if d.remove_worktree { "YES" } else { "NO" } },
+                if d.remove_worktree {
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
+                },
+            ),
+            Span::raw(if is_zh { "  (按 'w' 切换)" } else { "  (press 'w' to toggle)" }),
+        ]));
+        lines.push(Line::from(Span::styled(
+            path.display().to_string(),
             Style::default().fg(Color::DarkGray),
-        )),
-    ];
+        )));
+    }
+    lines.push(Line::from(""));
+    targets.extend([
+        LineTarget::span(lines.len(), 0, DialogTarget::Confirm),
+        LineTarget::span(lines.len(), 2, DialogTarget::Cancel),
+    ]);
+    lines.push(Line::from(vec![
+        Span::styled(
+            if is_zh { "[ y/回车: 确认 ]" } else { "[ y/Enter: confirm ]" },
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ),
+        Span::raw("  "),
+        Span::styled(
+            if is_zh { "[ n/Esc/Ctrl+C: 取消 ]" } else { "[ n/Esc/Ctrl+C: cancel ]" },
+            Style::default().fg(Color::DarkGray),
+        ),
+    ]));
+    (lines, targets)
+}
+
+pub(super) fn render_delete_group_dialog(f: &mut Frame, area: Rect, d: &crate::ui::DeleteGroupDialog, is_zh: bool) {
+    let popup_area = centered_rect(70, 35, area);
+    f.render_widget(Clear, popup_area);
 
+    let (lines, _) = delete_group_lines(d, is_zh);
     let p = Paragraph::new(lines)
         .wrap(Wrap { trim: false })
         .block(dialog_block("确认", "Confirm", is_zh));
@@ -716,29 +1709,17 @@ pub(super) fn render_delete_confirm_dialog(f: &mut Frame, area: Rect, d: &crate:
     f.render_widget(p, popup_area);
 }
 
-pub(super) fn render_delete_group_dialog(f: &mut Frame, area: Rect, d: &crate::ui::DeleteGroupDialog, is_zh: bool) {
-    let popup_area = centered_rect(70, 35, area);
-    f.render_widget(Clear, popup_area);
-
-    let active = Style::default().fg(Color::Black).bg(Color::Cyan);
+/// The delete-group dialog's lines, and which of them take clicks: the
+/// three choices.
+fn delete_group_lines(
+    d: &crate::ui::DeleteGroupDialog,
+    is_zh: bool,
+) -> (Vec<Line<'static>>, Vec<LineTarget>) {
+    use crate::ui::DeleteGroupChoice;
 
-    let opt1_style = if d.choice == crate::ui::DeleteGroupChoice::DeleteGroupKeepSessions {
-        active
-    } else {
-        Style::default().fg(Color::DarkGray)
-    };
-    let opt2_style = if d.choice == crate::ui::DeleteGroupChoice::Cancel {
-        active
-    } else {
-        Style::default().fg(Color::DarkGray)
-    };
-    let opt3_style = if d.choice == crate::ui::DeleteGroupChoice::DeleteGroupAndSessions {
-        active
-    } else {
-        Style::default().fg(Color::DarkGray)
-    };
+    let active = crate::ui::theme::theme().selection_style();
 
-    let lines = vec![
+    let mut lines = vec![
         Line::from(Span::styled(
             if is_zh { "删除分组？" } else { "Delete group?" },
             Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
@@ -761,33 +1742,134 @@ pub(super) fn render_delete_group_dialog(f: &mut Frame, area: Rect, d: &crate::u
             ),
         ]),
         Line::from(""),
-        Line::from(vec![
-            Span::styled("1", Style::default().fg(Color::Yellow)),
-            Span::raw(" "),
-            Span::styled(if is_zh { "仅删除分组 (保留会话)" } else { "Delete group only (keep sessions)" }, opt1_style),
-        ]),
-        Line::from(vec![
-            Span::styled("2", Style::default().fg(Color::Yellow)),
-            Span::raw(" "),
-            Span::styled(if is_zh { "取消" } else { "Cancel" }, opt2_style),
-        ]),
-        Line::from(vec![
-            Span::styled("3", Style::default().fg(Color::Yellow)),
-            Span::raw(" "),
-            Span::styled(if is_zh { "删除分组和会话" } else { "Delete group + sessions" }, opt3_style),
-        ]),
-        Line::from(""),
-        Line::from(Span::styled(
-            if is_zh { "1/2/3 或 ↑/↓ • 回车: 确认 • Esc/Ctrl+C: 取消" } else { "1/2/3 or ↑/↓ • Enter: confirm • Esc/Ctrl+C: cancel" },
-            Style::default().fg(Color::DarkGray),
-        )),
     ];
 
-    let p = Paragraph::new(lines)
-        .wrap(Wrap { trim: false })
-        .block(dialog_block("确认", "Confirm", is_zh));
+    let choices = [
+        (
+            "1",
+            DeleteGroupChoice::DeleteGroupKeepSessions,
+            if is_zh { "仅删除分组 (保留会话)" } else { "Delete group only (keep sessions)" },
+        ),
+        ("2", DeleteGroupChoice::Cancel, if is_zh { "取消" } else { "Cancel" }),
+        (
+            "3",
+            DeleteGroupChoice::DeleteGroupAndSessions,
+            if is_zh { "删除分组和会话" } else { "Delete group + sessions" },
+        ),
+    ];
+    let mut targets = Vec::new();
+    for (key, choice, label) in choices {
+        let style = if d.choice == choice {
+            active
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        targets.push(LineTarget::line(lines.len(), DialogTarget::GroupChoice(choice)));
+        lines.push(Line::from(vec![
+            Span::styled(key, Style::default().fg(Color::Yellow)),
+            Span::raw(" "),
+            Span::styled(label, style),
+        ]));
+    }
 
-    f.render_widget(p, popup_area);
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        if is_zh { "1/2/3 或 ↑/↓ • 回车: 确认 • Esc/Ctrl+C: 取消" } else { "1/2/3 or ↑/↓ • Enter: confirm • Esc/Ctrl+C: cancel" },
+        Style::default().fg(Color::DarkGray),
+    )));
+    (lines, targets)
+}
+
+/// A part of a dialog line that takes clicks: the whole line, or one of
+/// its spans.
+struct LineTarget {
+    line: usize,
+    span: Option<usize>,
+    target: DialogTarget,
+}
+
+impl LineTarget {
+    fn line(line: usize, target: DialogTarget) -> Self {
+        Self { line, span: None, target }
+    }
+
+    fn span(line: usize, span: usize, target: DialogTarget) -> Self {
+        Self { line, span: Some(span), target }
+    }
+}
+
+/// Where the clickable parts of the open dialog are on screen; empty for
+/// dialogs that only take keys. Mirrors the placement in `render_dialog`.
+pub(super) fn dialog_targets(area: Rect, app: &App) -> Vec<(Rect, DialogTarget)> {
+    let is_zh = matches!(app.language(), crate::i18n::Language::Chinese);
+    if let Some(d) = app.delete_confirm_dialog() {
+        return delete_confirm_targets(area, d, is_zh);
+    }
+    if let Some(d) = app.delete_group_dialog() {
+        return delete_group_targets(area, d, is_zh);
+    }
+    Vec::new()
+}
+
+pub(super) fn delete_confirm_targets(
+    area: Rect,
+    d: &crate::ui::DeleteConfirmDialog,
+    is_zh: bool,
+) -> Vec<(Rect, DialogTarget)> {
+    let (lines, targets) = delete_confirm_lines(d, is_zh);
+    target_rects(centered_rect(60, 30, area), &lines, &targets)
+}
+
+pub(super) fn delete_group_targets(
+    area: Rect,
+    d: &crate::ui::DeleteGroupDialog,
+    is_zh: bool,
+) -> Vec<(Rect, DialogTarget)> {
+    let (lines, targets) = delete_group_lines(d, is_zh);
+    target_rects(centered_rect(70, 35, area), &lines, &targets)
+}
+
+/// Screen rects of `targets` in a bordered popup that wraps `lines`. Rows
+/// are counted by character wrapping, which matches word wrapping for lines
+/// this short; parts past the bottom border are left out.
+fn target_rects(
+    popup: Rect,
+    lines: &[Line],
+    targets: &[LineTarget],
+) -> Vec<(Rect, DialogTarget)> {
+    let inner = popup.inner(ratatui::layout::Margin::new(1, 1));
+    if inner.width == 0 {
+        return Vec::new();
+    }
+    // Top row and height of each line once wrapped.
+    let mut y = inner.y;
+    let rows: Vec<(u16, u16)> = lines
+        .iter()
+        .map(|line| {
+            let height = (line.width() as u16).div_ceil(inner.width).max(1);
+            let top = y;
+            y = y.saturating_add(height);
+            (top, height)
+        })
+        .collect();
+
+    targets
+        .iter()
+        .filter_map(|t| {
+            let (top, height) = *rows.get(t.line)?;
+            let rect = match t.span {
+                None => Rect::new(inner.x, top, inner.width, height),
+                Some(i) => {
+                    let spans = &lines[t.line].spans;
+                    let before: usize = spans.get(..i)?.iter().map(|s| s.width()).sum();
+                    let x = inner.x.saturating_add(before as u16);
+                    Rect::new(x, top, spans.get(i)?.width() as u16, 1)
+                }
+            };
+            let rect = rect.intersection(inner);
+            (!rect.is_empty()).then_some((rect, t.target))
+        })
+        .collect()
 }
 
 pub(super) fn render_search_popup(f: &mut Frame, area: Rect, app: &App) {
@@ -802,14 +1884,26 @@ pub(super) fn render_search_popup(f: &mut Frame, area: Rect, app: &App) {
             .fg(Color::Cyan)
             .add_modifier(Modifier::BOLD),
     )));
-    lines.push(Line::from(Span::raw(format!(
+    let mut query_spans = vec![Span::raw(format!(
         "{}: {}",
         if is_zh { "查询" } else { "Query" },
         app.search_query()
-    ))));
+    ))];
+    // Kept on the query line: the header stays 3 lines for `search_viewport`.
+    let filters = crate::ui::query::SearchQuery::parse(app.search_query()).filter_labels(is_zh);
+    if !filters.is_empty() {
+        query_spans.push(Span::styled(
+            format!("   {} {}", if is_zh { "筛选:" } else { "Filters:" }, filters.join("  ")),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    lines.push(Line::from(query_spans));
     lines.push(Line::from(""));
 
-    for (i, id) in app.search_results().iter().enumerate() {
+    // One row per result: windowing assumes lines do not wrap.
+    let results = app.search_results();
+    let window = app.search_window();
+    for (i, id) in results[window.range()].iter().enumerate() {
         let s = app.session_by_id(id);
         let title = s.map(|x| x.title.as_str()).unwrap_or("<missing>");
         let group = s.map(|x| x.group_path.as_str()).unwrap_or("");
@@ -817,11 +1911,8 @@ pub(super) fn render_search_popup(f: &mut Frame, area: Rect, app: &App) {
             .map(|x| x.project_path.to_string_lossy().to_string())
             .unwrap_or_default();
 
-        let style = if i == app.search_selected() {
-            Style::default()
-                .fg(Color::Black)
-                .bg(Color::Cyan)
-                .add_modifier(Modifier::BOLD)
+        let style = if Some(i) == window.selected {
+            crate::ui::theme::theme().selection_style().add_modifier(Modifier::BOLD)
         } else {
             Style::default()
         };
@@ -835,7 +1926,7 @@ pub(super) fn render_search_popup(f: &mut Frame, area: Rect, app: &App) {
         ]));
     }
 
-    if app.search_results().is_empty() {
+    if results.is_empty() {
         lines.push(Line::from(Span::styled(
             "(no matches)",
             Style::default().fg(Color::DarkGray),
@@ -843,14 +1934,30 @@ pub(super) fn render_search_popup(f: &mut Frame, area: Rect, app: &App) {
     }
 
     lines.push(Line::from(""));
+    if window.is_truncated(results.len()) {
+        lines.push(Line::from(Span::styled(
+            crate::ui::truncation_notice(window, results.len(), is_zh),
+            Style::default().fg(Color::Yellow),
+        )));
+    }
+    lines.push(Line::from(Span::styled(
+        if is_zh {
+            "输入以筛选 • ↑/↓ 选择 • PgUp/PgDn 翻页 • 回车跳转 • Esc 关闭"
+        } else {
+            "Type to filter • ↑/↓ to select • PgUp/PgDn to page • Enter to jump • Esc to close"
+        },
+        Style::default().fg(Color::DarkGray),
+    )));
     lines.push(Line::from(Span::styled(
-        "Type to filter • ↑/↓ to select • Enter to jump • Esc to close",
+        if is_zh {
+            crate::ui::query::SYNTAX_HINT_ZH
+        } else {
+            crate::ui::query::SYNTAX_HINT_EN
+        },
         Style::default().fg(Color::DarkGray),
     )));
 
-    let p = Paragraph::new(lines)
-        .wrap(Wrap { trim: false })
-        .block(dialog_block("搜索", "Search", is_zh));
+    let p = Paragraph::new(lines).block(dialog_block("搜索", "Search", is_zh));
 
     f.render_widget(p, popup_area);
 }
@@ -904,10 +2011,7 @@ pub(super) fn render_settings_dialog(
         if *tab == d.tab {
             tab_spans.push(Span::styled(
                 label,
-                Style::default()
-                    .fg(Color::Black)
-                    .bg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD),
+                crate::ui::theme::theme().selection_style().add_modifier(Modifier::BOLD),
             ));
         } else {
             tab_spans.push(Span::styled(label, dim_style));
@@ -1006,10 +2110,7 @@ pub(super) fn render_settings_dialog(
                         }
 
                         let style = if i == d.ai_provider_idx {
-                            Style::default()
-                                .fg(Color::Black)
-                                .bg(Color::Cyan)
-                                .add_modifier(Modifier::BOLD)
+                            crate::ui::theme::theme().selection_style().add_modifier(Modifier::BOLD)
                         } else {
                             Style::default().fg(Color::DarkGray)
                         };
@@ -1107,7 +2208,7 @@ pub(super) fn render_settings_dialog(
                 let is_editing_this = d.editing && is_active;
                 let is_ro = d.default_permission != "rw";
                 if is_editing_this {
-                    let sel = Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD);
+                    let sel = crate::ui::theme::theme().selection_style().add_modifier(Modifier::BOLD);
                     let unsel = Style::default().fg(Color::DarkGray);
                     spans.push(Span::styled(" Read Only ", if is_ro { sel } else { unsel }));
                     spans.push(Span::raw(" "));
@@ -1123,7 +2224,7 @@ pub(super) fn render_settings_dialog(
             SettingsField::PromptCollection => {
                 let is_editing_this = d.editing && is_active;
                 if is_editing_this {
-                    let sel = Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD);
+                    let sel = crate::ui::theme::theme().selection_style().add_modifier(Modifier::BOLD);
                     let unsel = Style::default().fg(Color::DarkGray);
                     spans.push(Span::styled(" Off ", if !d.prompt_collection { sel } else { unsel }));
                     spans.push(Span::raw(" "));
@@ -1139,7 +2240,7 @@ pub(super) fn render_settings_dialog(
             SettingsField::AnimationsEnabled => {
                 let is_editing_this = d.editing && is_active;
                 if is_editing_this {
-                    let sel = Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD);
+                    let sel = crate::ui::theme::theme().selection_style().add_modifier(Modifier::BOLD);
                     let unsel = Style::default().fg(Color::DarkGray);
                     spans.push(Span::styled(" Off ", if !d.animations_enabled { sel } else { unsel }));
                     spans.push(Span::raw(" "));
@@ -1155,7 +2256,7 @@ pub(super) fn render_settings_dialog(
             SettingsField::AnalyticsEnabled => {
                 let is_editing_this = d.editing && is_active;
                 if is_editing_this {
-                    let sel = Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD);
+                    let sel = crate::ui::theme::theme().selection_style().add_modifier(Modifier::BOLD);
                     let unsel = Style::default().fg(Color::DarkGray);
                     spans.push(Span::styled(" Off ", if !d.analytics_enabled { sel } else { unsel }));
                     spans.push(Span::raw(" "));
@@ -1172,7 +2273,7 @@ pub(super) fn render_settings_dialog(
                 let is_editing_this = d.editing && is_active;
                 let labels = ["Auto", "On", "Off"];
                 if is_editing_this {
-                    let sel = Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD);
+                    let sel = crate::ui::theme::theme().selection_style().add_modifier(Modifier::BOLD);
                     let unsel = Style::default().fg(Color::DarkGray);
                     for (i, label) in labels.iter().enumerate() {
                         if i > 0 {
@@ -1202,7 +2303,7 @@ pub(super) fn render_settings_dialog(
                         agent_hooks::ToolStatus::NotInstalled => ("\u{2717}", Color::DarkGray),
                     };
                     let name_style = if is_sel {
-                        Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+                        crate::ui::theme::theme().selection_style().add_modifier(Modifier::BOLD)
                     } else if info.status == agent_hooks::ToolStatus::NotInstalled {
                         dim_style
                     } else {
@@ -1232,7 +2333,7 @@ pub(super) fn render_settings_dialog(
             SettingsField::NotifAutoRegister => {
                 let is_editing_this = d.editing && is_active;
                 if is_editing_this {
-                    let sel = Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD);
+                    let sel = crate::ui::theme::theme().selection_style().add_modifier(Modifier::BOLD);
                     let unsel = Style::default().fg(Color::DarkGray);
                     spans.push(Span::styled(" Off ", if !d.hook_auto_register { sel } else { unsel }));
                     spans.push(Span::raw(" "));
@@ -1249,7 +2350,7 @@ pub(super) fn render_settings_dialog(
             SettingsField::NotifEnabled => {
                 let is_editing_this = d.editing && is_active;
                 if is_editing_this {
-                    let sel = Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD);
+                    let sel = crate::ui::theme::theme().selection_style().add_modifier(Modifier::BOLD);
                     let unsel = Style::default().fg(Color::DarkGray);
                     spans.push(Span::styled(" Off ", if !d.notif_enabled { sel } else { unsel }));
                     spans.push(Span::raw(" "));
@@ -1288,10 +2389,7 @@ pub(super) fn render_settings_dialog(
                             }
 
                             let style = if i == d.notif_pack_idx {
-                                Style::default()
-                                    .fg(Color::Black)
-                                    .bg(Color::Cyan)
-                                    .add_modifier(Modifier::BOLD)
+                                crate::ui::theme::theme().selection_style().add_modifier(Modifier::BOLD)
                             } else {
                                 Style::default().fg(Color::DarkGray)
                             };
@@ -1330,7 +2428,7 @@ pub(super) fn render_settings_dialog(
             SettingsField::NotifOnComplete => {
                 let is_editing_this = d.editing && is_active;
                 if is_editing_this {
-                    let sel = Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD);
+                    let sel = crate::ui::theme::theme().selection_style().add_modifier(Modifier::BOLD);
                     let unsel = Style::default().fg(Color::DarkGray);
                     spans.push(Span::styled(" Off ", if !d.notif_on_complete { sel } else { unsel }));
                     spans.push(Span::raw(" "));
@@ -1346,7 +2444,7 @@ pub(super) fn render_settings_dialog(
             SettingsField::NotifOnInput => {
                 let is_editing_this = d.editing && is_active;
                 if is_editing_this {
-                    let sel = Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD);
+                    let sel = crate::ui::theme::theme().selection_style().add_modifier(Modifier::BOLD);
                     let unsel = Style::default().fg(Color::DarkGray);
                     spans.push(Span::styled(" Off ", if !d.notif_on_input { sel } else { unsel }));
                     spans.push(Span::raw(" "));
@@ -1362,7 +2460,7 @@ pub(super) fn render_settings_dialog(
             SettingsField::NotifOnError => {
                 let is_editing_this = d.editing && is_active;
                 if is_editing_this {
-                    let sel = Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD);
+                    let sel = crate::ui::theme::theme().selection_style().add_modifier(Modifier::BOLD);
                     let unsel = Style::default().fg(Color::DarkGray);
                     spans.push(Span::styled(" Off ", if !d.notif_on_error { sel } else { unsel }));
                     spans.push(Span::raw(" "));
@@ -1423,7 +2521,7 @@ pub(super) fn render_settings_dialog(
                 let is_editing_this = d.editing && is_active;
                 let labels = ["English", "中文"];
                 if is_editing_this {
-                    let sel = Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD);
+                    let sel = crate::ui::theme::theme().selection_style().add_modifier(Modifier::BOLD);
                     let unsel = Style::default().fg(Color::DarkGray);
                     for (i, label) in labels.iter().enumerate() {
                         if i > 0 {
@@ -1636,10 +2734,7 @@ pub(super) fn render_pack_browser_dialog(
                             .bg(Color::DarkGray)
                             .add_modifier(Modifier::BOLD)
                     } else {
-                        Style::default()
-                            .fg(Color::Black)
-                            .bg(Color::Cyan)
-                            .add_modifier(Modifier::BOLD)
+                        crate::ui::theme::theme().selection_style().add_modifier(Modifier::BOLD)
                     }
                 } else if pack.installed {
                     Style::default().fg(Color::DarkGray)
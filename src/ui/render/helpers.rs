@@ -12,6 +12,9 @@ pub(super) fn running_anim(tick: u64) -> &'static str {
     FRAMES[(tick as usize) % FRAMES.len()]
 }
 
+/// Running, but the pane has not changed for `[status] stall_secs`.
+pub(super) const STALLED_ICON: &str = "⊘";
+
 pub(super) fn waiting_anim(tick: u64) -> &'static str {
     // Blink to draw attention: ~1s on, ~0.3s off (tick is 250ms).
     const FRAMES: [&str; 5] = ["!", "!", "!", "!", " "];
@@ -43,7 +46,7 @@ pub(super) const INPUT_MIN_WIDTH: usize = 30;
 
 /// Render a TextInput with cursor visible when active.
 /// A subtle background strip marks the editable area so users can see where to type.
-pub(super) fn render_text_input(input: &TextInput, active: bool, _base_style: Style) -> Vec<Span<'static>> {
+pub(crate) fn render_text_input(input: &TextInput, active: bool, _base_style: Style) -> Vec<Span<'static>> {
     let text = input.text();
     let cursor_pos = input.cursor();
 
@@ -91,25 +94,8 @@ pub(super) fn render_text_input(input: &TextInput, active: bool, _base_style: St
     ]
 }
 
-#[cfg(feature = "pro")]
 pub(super) fn truncate_name(name: &str, max_width: usize) -> String {
-    use unicode_width::UnicodeWidthStr;
-    if UnicodeWidthStr::width(name) <= max_width {
-        name.to_string()
-    } else {
-        // Truncate respecting display width (CJK chars = 2 columns)
-        let mut w = 0;
-        let mut end = 0;
-        for (i, ch) in name.char_indices() {
-            let cw = unicode_width::UnicodeWidthChar::width(ch).unwrap_or(0);
-            if w + cw + 2 > max_width { // +2 for ".."
-                break;
-            }
-            w += cw;
-            end = i + ch.len_utf8();
-        }
-        format!("{}..", &name[..end])
-    }
+    crate::ui::text::truncate(name, max_width)
 }
 
 pub(super) fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
@@ -147,6 +133,39 @@ pub(super) fn dialog_block<'a>(title_zh: &'a str, title_en: &'a str, is_zh: bool
         .title(if is_zh { title_zh } else { title_en })
 }
 
+/// Render a content-sized dialog: pick the popup size from `lines`, and
+/// when they do not fit, scroll to keep line `focus` visible with a count of
+/// hidden lines on the bottom border. Lines are not wrapped.
+pub(super) fn render_fitted_dialog(
+    f: &mut Frame,
+    area: Rect,
+    geometry: crate::ui::dialog_frame::DialogGeometry,
+    nudge: crate::ui::dialog_frame::SizeNudge,
+    block: Block,
+    lines: Vec<Line>,
+    focus: usize,
+) {
+    let content_w = lines.iter().map(|l| l.width()).max().unwrap_or(0) as u16;
+    let popup_area =
+        crate::ui::dialog_frame::popup_rect(area, geometry, nudge, content_w, lines.len() as u16);
+    f.render_widget(Clear, popup_area);
+
+    let visible = popup_area.height.saturating_sub(2) as usize;
+    let scroll = crate::ui::dialog_frame::body_scroll(lines.len(), visible, focus);
+    let mut block = block;
+    if scroll.hidden_above > 0 || scroll.hidden_below > 0 {
+        block = block.title_bottom(
+            Line::from(format!(" ↑{} ↓{} ", scroll.hidden_above, scroll.hidden_below))
+                .right_aligned(),
+        );
+    }
+
+    let p = Paragraph::new(lines)
+        .scroll((scroll.offset as u16, 0))
+        .block(block);
+    f.render_widget(p, popup_area);
+}
+
 /// Build a bordered block with a single title (no i18n).
 pub(super) fn titled_block(title: &str) -> Block<'_> {
     let t = crate::ui::theme::theme();
@@ -161,11 +180,66 @@ pub(super) fn plain_block() -> Block<'static> {
     Block::default().borders(Borders::ALL)
 }
 
+/// Titles of a multi-selection for a dialog line: the first `max`, then
+/// "+N more".
+pub(super) fn batch_titles(batch: &[(String, String)], max: usize) -> String {
+    let mut text = batch
+        .iter()
+        .take(max)
+        .map(|(_, title)| title.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    if batch.len() > max {
+        text.push_str(&format!(" +{} more", batch.len() - max));
+    }
+    text
+}
+
 /// Compute a unicode-aware display width for terminal text.
 pub(super) fn display_width(s: &str) -> u16 {
     unicode_width::UnicodeWidthStr::width(s) as u16
 }
 
+/// Compact elapsed time since `since` (e.g. "45s", "12m", "3h", "2d").
+pub(super) fn format_elapsed_short(since: chrono::DateTime<chrono::Utc>) -> String {
+    let secs = chrono::Utc::now().signed_duration_since(since).num_seconds().max(0);
+    match secs {
+        0..=59 => format!("{secs}s"),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+/// Waiting sessions named in the status bar.
+pub(super) const STATUS_QUEUE_MAX: usize = 3;
+const STATUS_QUEUE_TITLE_WIDTH: usize = 14;
+
+/// Status bar queue entries (`1.api`, `2.web`, …) for titles in queue
+/// order, dropping entries from the back until a two-space lead plus the
+/// entries, space-separated, fit in `budget` columns.
+pub(super) fn waiting_queue_entries(titles: &[&str], budget: usize) -> Vec<String> {
+    use unicode_width::UnicodeWidthStr;
+
+    let mut entries: Vec<String> = titles
+        .iter()
+        .take(STATUS_QUEUE_MAX)
+        .enumerate()
+        .map(|(i, title)| format!("{}.{}", i + 1, truncate_name(title, STATUS_QUEUE_TITLE_WIDTH)))
+        .collect();
+    while !entries.is_empty() {
+        let width = 2
+            + entries.iter().map(|e| UnicodeWidthStr::width(e.as_str())).sum::<usize>()
+            + entries.len()
+            - 1;
+        if width <= budget {
+            break;
+        }
+        entries.pop();
+    }
+    entries
+}
+
 /// Format room age as a human-readable string (e.g. "5 min ago").
 #[cfg(feature = "pro")]
 pub(super) fn format_room_age(created_at: &str, is_zh: bool) -> String {
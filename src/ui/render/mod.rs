@@ -10,6 +10,7 @@ use crate::session::Status;
 use crate::ui::TextInput;
 
 use super::app::App;
+use super::mouse::HitAreas;
 use super::TreeItem;
 
 mod helpers;
@@ -30,6 +31,8 @@ mod dialogs_pro;
 mod dialogs_max;
 
 use helpers::*;
+// The Ctrl+G switcher draws its new-session form with the same inputs.
+pub(crate) use helpers::render_text_input;
 use sessions::*;
 use dialogs::*;
 #[cfg(feature = "pro")]
@@ -39,12 +42,15 @@ use dialogs_max::*;
 #[cfg(feature = "pro")]
 use viewer::*;
 
-/// Main render function
-pub fn draw(f: &mut Frame, app: &App) {
+/// Main render function. Returns where the parts that take mouse clicks
+/// ended up.
+pub fn draw(f: &mut Frame, app: &App) -> HitAreas {
+    let mut hits = HitAreas::default();
+
     // Startup splash screen
     if app.state() == crate::ui::AppState::Startup {
         render_startup(f, f.area(), app.startup_phase(), app.startup_elapsed_ms());
-        return;
+        return hits;
     }
 
     // In ViewerMode, use full screen for PTY content (no title/status bars)
@@ -55,40 +61,43 @@ pub fn draw(f: &mut Frame, app: &App) {
 
         // Still render overlays if needed
         if app.help_visible() {
-            render_help_modal(f, f.area(), app.language());
+            render_help_modal(f, f.area(), app.language(), app.is_simple_mode(), app.keybindings());
         }
         if app.state() == crate::ui::AppState::Dialog {
             render_dialog(f, f.area(), app);
+            hits.dialog = dialog_targets(f.area(), app);
         }
         render_toast_notifications(f, f.area(), app);
-        return;
+        return hits;
     }
 
     // Normal layout for other modes (with title and status bars)
     let has_info = app.info_bar_message().is_some();
+    // The status bar grows a line for the PTY warning.
+    let status_height = if app.ptmx_warning().is_some() { 4 } else { 3 };
     let chunks = if has_info {
         Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(3), // Title
-                Constraint::Length(1), // Info bar
-                Constraint::Min(0),    // Content
-                Constraint::Length(3), // Status bar
+                Constraint::Length(3),             // Title
+                Constraint::Length(1),             // Info bar
+                Constraint::Min(0),                // Content
+                Constraint::Length(status_height), // Status bar
             ])
             .split(f.area())
     } else {
         Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(3), // Title
-                Constraint::Min(0),    // Content
-                Constraint::Length(3), // Status bar
+                Constraint::Length(3),             // Title
+                Constraint::Min(0),                // Content
+                Constraint::Length(status_height), // Status bar
             ])
             .split(f.area())
     };
 
     // Render title
-    render_title(f, chunks[0], app.language());
+    render_title(f, chunks[0], app.language(), app.profile());
 
     let (content_idx, status_idx) = if has_info {
         render_info_bar(f, chunks[1], app);
@@ -111,11 +120,11 @@ pub fn draw(f: &mut Frame, app: &App) {
             if app.state() == crate::ui::AppState::Relationships {
                 render_relationships(f, chat_split[0], app);
             } else {
-                render_main(f, chat_split[0], app);
+                render_main(f, chat_split[0], app, &mut hits);
             }
         }
         #[cfg(not(feature = "pro"))]
-        render_main(f, chat_split[0], app);
+        render_main(f, chat_split[0], app, &mut hits);
 
         render_chat_panel(f, chat_split[1], app);
     } else {
@@ -124,11 +133,11 @@ pub fn draw(f: &mut Frame, app: &App) {
             if app.state() == crate::ui::AppState::Relationships {
                 render_relationships(f, content_area, app);
             } else {
-                render_main(f, content_area, app);
+                render_main(f, content_area, app, &mut hits);
             }
         }
         #[cfg(not(feature = "pro"))]
-        render_main(f, content_area, app);
+        render_main(f, content_area, app, &mut hits);
     }
 
     // Render status bar
@@ -139,11 +148,12 @@ pub fn draw(f: &mut Frame, app: &App) {
 
     // Help modal overlays on top when visible
     if app.help_visible() {
-        render_help_modal(f, f.area(), app.language());
+        render_help_modal(f, f.area(), app.language(), app.is_simple_mode(), app.keybindings());
     }
 
     if app.state() == crate::ui::AppState::Dialog {
         render_dialog(f, f.area(), app);
+        hits.dialog = dialog_targets(f.area(), app);
     }
 
     if app.state() == crate::ui::AppState::Search {
@@ -176,6 +186,61 @@ pub fn draw(f: &mut Frame, app: &App) {
     if app.show_onboarding() {
         render_onboarding_welcome(f, f.area(), app.language());
     }
+
+    // Perf overlay (F11), above everything else
+    if let Some(report) = app.perf_report() {
+        render_perf_overlay(f, f.area(), &report);
+    }
+
+    hits
+}
+
+/// Render the perf overlay (F11) in the top-right corner: p50/p95/max per
+/// phase over the last minute, and tmux subprocesses started in that time.
+pub(super) fn render_perf_overlay(f: &mut Frame, area: Rect, report: &crate::ui::perf::PerfReport) {
+    fn ms(d: Option<std::time::Duration>) -> String {
+        match d {
+            None => "-".to_string(),
+            Some(d) if d < std::time::Duration::from_millis(1) => "<1ms".to_string(),
+            Some(d) => format!("{}ms", d.as_millis()),
+        }
+    }
+
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut lines = vec![Line::from(Span::styled(
+        format!("{:<8}{:>7}{:>7}{:>8}{:>6}", "phase", "p50", "p95", "max", "n"),
+        dim,
+    ))];
+    for stats in &report.phases {
+        let p95_style = if stats.is_slow() {
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(vec![
+            Span::raw(format!("{:<8}{:>7}", stats.phase.name(), ms(stats.p50))),
+            Span::styled(format!("{:>7}", ms(stats.p95)), p95_style),
+            Span::raw(format!("{:>8}{:>6}", ms(stats.max), stats.samples)),
+        ]));
+    }
+    let calls = &report.calls;
+    lines.push(Line::from(Span::styled(
+        format!("tmux calls, last minute: {}", calls.total()),
+        dim,
+    )));
+    lines.push(Line::from(format!(
+        " list {}  capture {}  env {}  send {}  other {}",
+        calls.list, calls.capture, calls.env, calls.send, calls.other
+    )));
+
+    let width = 50.min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let rect = Rect::new(area.right().saturating_sub(width), area.y, width, height);
+    f.render_widget(Clear, rect);
+    f.render_widget(
+        Paragraph::new(lines).block(titled_block(" Perf · last 60s ")),
+        rect,
+    );
 }
 
 /// Render the info bar (version update or tier mismatch hint)
@@ -195,7 +260,7 @@ fn render_info_bar(f: &mut Frame, area: Rect, app: &App) {
 }
 
 /// Render title bar
-fn render_title(f: &mut Frame, area: Rect, lang: crate::i18n::Language) {
+fn render_title(f: &mut Frame, area: Rect, lang: crate::i18n::Language, profile: &str) {
     use crate::i18n::{Translate, Language};
 
     let title_text = match lang {
@@ -211,6 +276,10 @@ fn render_title(f: &mut Frame, area: Rect, lang: crate::i18n::Language) {
                 .fg(Color::Cyan)
                 .add_modifier(Modifier::BOLD),
         ),
+        Span::styled(
+            format!("  [{profile}]"),
+            Style::default().fg(Color::Cyan),
+        ),
         Span::styled(
             format!("  {help_hint}"),
             Style::default()
@@ -226,15 +295,22 @@ fn render_title(f: &mut Frame, area: Rect, lang: crate::i18n::Language) {
     f.render_widget(title, area);
 }
 
-fn render_main(f: &mut Frame, area: Rect, app: &App) {
-    let cols = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(45), Constraint::Percentage(55)])
-        .split(area);
-
-    render_session_list(f, cols[0], app);
+fn render_main(f: &mut Frame, area: Rect, app: &App, hits: &mut HitAreas) {
+    let areas = app.main_layout().areas(area);
+    if let Some(list) = areas.list {
+        let (tree, offset) = render_session_list(f, list, app);
+        hits.tree = Some(tree);
+        hits.tree_offset = offset;
+    }
+    if let Some(summary) = areas.summary {
+        crate::ui::render::sessions::render_selection_summary(f, summary, app);
+    }
+    let Some(right) = areas.preview else {
+        return;
+    };
 
-    // Pro: canvas fills the right side (no preview panel)
+    // Pro: canvas fills the right side (no preview panel), so
+    // `hits.preview` stays unset and the wheel moves the selection there too
     // Free: preview only
     #[cfg(feature = "pro")]
     {
@@ -251,23 +327,24 @@ fn render_main(f: &mut Frame, area: Rect, app: &App) {
             let right_split = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([Constraint::Min(6), Constraint::Length(12)])
-                .split(cols[1]);
+                .split(right);
             crate::ui::canvas::render::render_canvas(f, right_split[0], app.canvas_state(), app.canvas_focused(), is_zh);
             crate::ui::render::sessions::render_relationship_detail(f, right_split[1], app);
         } else if show_projection_detail {
             let right_split = Layout::default()
                 .direction(Direction::Vertical)
                 .constraints([Constraint::Min(6), Constraint::Length(12)])
-                .split(cols[1]);
+                .split(right);
             crate::ui::canvas::render::render_canvas(f, right_split[0], app.canvas_state(), app.canvas_focused(), is_zh);
             crate::ui::render::sessions::render_canvas_projection_detail(f, right_split[1], app);
         } else {
-            crate::ui::canvas::render::render_canvas(f, cols[1], app.canvas_state(), app.canvas_focused(), is_zh);
+            crate::ui::canvas::render::render_canvas(f, right, app.canvas_state(), app.canvas_focused(), is_zh);
         }
     }
     #[cfg(not(feature = "pro"))]
     {
-        crate::ui::render::sessions::render_preview(f, cols[1], app);
+        crate::ui::render::sessions::render_preview(f, right, app);
+        hits.preview = Some(right);
     }
 }
 
@@ -408,6 +485,14 @@ fn render_status_bar(f: &mut Frame, area: Rect, app: &App) {
         .iter()
         .filter(|s| s.status == Status::Idle && !app.is_attention_active(&s.id))
         .count();
+    let stopped = sessions
+        .iter()
+        .filter(|s| s.status == Status::Stopped)
+        .count();
+    let failed = sessions
+        .iter()
+        .filter(|s| s.status == Status::Error)
+        .count();
 
     let mut spans: Vec<Span> = Vec::new();
 
@@ -428,6 +513,16 @@ fn render_status_bar(f: &mut Frame, area: Rect, app: &App) {
             Style::default().fg(Color::Blue),
         ),
         Span::raw(format!("{}", waiting)),
+    ]);
+    // How long the head of the queue has been stuck.
+    let queue = crate::session::waiting::waiting_queue(sessions);
+    if let Some(oldest) = queue.first() {
+        spans.push(Span::styled(
+            format!(" ({})", format_elapsed_short(crate::session::waiting::waiting_since(oldest))),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    spans.extend([
         Span::raw("  "),
         Span::styled("✓", Style::default().fg(Color::Cyan)),
         Span::raw(format!("{}", attention)),
@@ -440,26 +535,53 @@ fn render_status_bar(f: &mut Frame, area: Rect, app: &App) {
         Span::raw("  "),
         Span::styled("○", Style::default().fg(Color::DarkGray)),
         Span::raw(format!("{}", idle)),
-        Span::raw("  |  "),
     ]);
+    // Stopped and failed sessions only take room when there are any.
+    if stopped > 0 {
+        spans.extend([
+            Span::raw("  "),
+            Span::styled("■", Style::default().fg(Color::DarkGray)),
+            Span::raw(format!("{}", stopped)),
+        ]);
+    }
+    if failed > 0 {
+        spans.extend([
+            Span::raw("  "),
+            Span::styled("✕", Style::default().fg(Color::Red)),
+            Span::raw(format!("{}", failed)),
+        ]);
+    }
+    // The waiting queue goes here once the rest of the bar is known, so it
+    // can take whatever width is left.
+    let queue_at = spans.len();
+    spans.push(Span::raw("  |  "));
 
     // PTY gauge: green < 50%, yellow 50-80%, red > 80%
-    let pty_pct = if app.system_ptmx_max() > 0 {
-        app.system_ptmx_total() as f32 / app.system_ptmx_max() as f32
-    } else {
-        0.0
-    };
-    let pty_color = if pty_pct < 0.5 {
-        Color::Green
-    } else if pty_pct < 0.8 {
-        Color::Yellow
-    } else {
-        Color::Red
-    };
-    spans.push(Span::styled(
-        format!("PTY: {}/{}", app.system_ptmx_total(), app.system_ptmx_max()),
-        Style::default().fg(pty_color),
-    ));
+    if app.ptmx_enabled() {
+        let pty_pct = if app.system_ptmx_max() > 0 {
+            app.system_ptmx_total() as f32 / app.system_ptmx_max() as f32
+        } else {
+            0.0
+        };
+        let pty_color = if pty_pct < 0.5 {
+            Color::Green
+        } else if pty_pct < 0.8 {
+            Color::Yellow
+        } else {
+            Color::Red
+        };
+        spans.push(Span::styled(
+            format!("PTY: {}/{}", app.system_ptmx_total(), app.system_ptmx_max()),
+            Style::default().fg(pty_color),
+        ));
+    }
+
+    if crate::network::is_offline() {
+        spans.push(Span::styled(
+            if is_zh { "  离线" } else { "  offline" },
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
 
     if app.canvas_focused() {
         spans.push(Span::raw("  |  "));
@@ -468,7 +590,7 @@ fn render_status_bar(f: &mut Frame, area: Rect, app: &App) {
     } else if let Some(session) = app.selected_session() {
         if session.cli_session_id().is_some() {
             spans.push(Span::raw("  |  "));
-            spans.push(Span::styled("u", Style::default().fg(Color::Cyan)));
+            spans.push(Span::styled("o", Style::default().fg(Color::Cyan)));
             spans.push(Span::raw(if is_zh {
                 ":恢复已停止会话"
             } else {
@@ -477,6 +599,18 @@ fn render_status_bar(f: &mut Frame, area: Rect, app: &App) {
         }
     }
 
+    // Where jump-back leads: the last two sessions attached to, newest first.
+    let recent = app.recent_attach_titles(2);
+    if !recent.is_empty() {
+        spans.push(Span::raw("  |  "));
+        if let Some(key) = app.keybindings().describe("jump_back") {
+            spans.push(Span::styled(key, Style::default().fg(Color::Cyan)));
+            spans.push(Span::raw(":"));
+        }
+        let titles: Vec<String> = recent.iter().map(|t| truncate_name(t, 16)).collect();
+        spans.push(Span::raw(titles.join(" ← ")));
+    }
+
     if app.state() == crate::ui::AppState::Search {
         spans.push(Span::styled(
             if is_zh { "搜索: " } else { "Search: " },
@@ -518,13 +652,51 @@ fn render_status_bar(f: &mut Frame, area: Rect, app: &App) {
         ));
     }
 
-    let status_line = Line::from(spans);
+    if !queue.is_empty() {
+        let titles: Vec<&str> = queue.iter().map(|s| s.title.as_str()).collect();
+        let used: usize = spans.iter().map(|s| s.width()).sum();
+        let budget = usize::from(area.width.saturating_sub(2)).saturating_sub(used);
+        let mut queue_spans = Vec::new();
+        for entry in waiting_queue_entries(&titles, budget) {
+            let (pos, title) = entry.split_once('.').unwrap_or(("", entry.as_str()));
+            queue_spans.push(Span::raw(if queue_spans.is_empty() { "  " } else { " " }));
+            queue_spans.push(Span::styled(format!("{pos}."), Style::default().fg(Color::Blue)));
+            queue_spans.push(Span::raw(title.to_string()));
+        }
+        spans.splice(queue_at..queue_at, queue_spans);
+    }
+
+    let mut lines = vec![Line::from(spans)];
+    if let Some(top) = app.ptmx_warning() {
+        lines.push(pty_warning_line(
+            app.system_ptmx_total(),
+            app.system_ptmx_max(),
+            &top,
+            is_zh,
+        ));
+    }
 
-    let status = Paragraph::new(status_line).block(Block::default().borders(Borders::ALL));
+    let status = Paragraph::new(lines).block(Block::default().borders(Borders::ALL));
 
     f.render_widget(status, area);
 }
 
+/// Status bar line for PTY usage over `[ptmx] warn_percent`, naming the
+/// sessions that hold the most.
+fn pty_warning_line(total: u32, max: u32, top: &[(&str, u32)], is_zh: bool) -> Line<'static> {
+    let mut text = if is_zh {
+        format!("  ⚠ PTY 已用 {total}/{max}")
+    } else {
+        format!("  ⚠ PTY usage {total}/{max}")
+    };
+    if !top.is_empty() {
+        let names: Vec<String> = top.iter().map(|(title, n)| format!("{title} ({n})")).collect();
+        text.push_str(if is_zh { " — 占用最多: " } else { " — most held by: " });
+        text.push_str(&names.join(", "));
+    }
+    Line::from(Span::styled(text, Style::default().fg(Color::Red)))
+}
+
 /// Collect keyboard hints as structured (key, label, color) tuples for the overlay.
 /// Context-aware: shows different hints based on current focus (canvas, active panel, tree, etc.)
 fn collect_overlay_hints(app: &App) -> Vec<(&'static str, &'static str, Color)> {
@@ -569,6 +741,19 @@ fn collect_overlay_hints(app: &App) -> Vec<(&'static str, &'static str, Color)>
         return hints;
     }
 
+    if app.is_simple_mode() {
+        hints.extend([
+            ("j/k", if is_zh { "上下" } else { "nav" }, Color::Cyan),
+            ("Enter", if is_zh { "附加" } else { "attach" }, Color::Green),
+            ("n", if is_zh { "新建" } else { "new" }, Color::Cyan),
+            ("d", if is_zh { "删除" } else { "del" }, Color::Red),
+            ("/", if is_zh { "搜索" } else { "search" }, Color::Cyan),
+            ("?", if is_zh { "帮助" } else { "help" }, Color::Magenta),
+            ("F9", if is_zh { "完整模式" } else { "full mode" }, Color::DarkGray),
+        ]);
+        return hints;
+    }
+
     // Context-sensitive item hints (tree / relationships)
     #[cfg(feature = "pro")]
     {
@@ -692,6 +877,16 @@ fn collect_item_selection_hints(hints: &mut Vec<(&'static str, &'static str, Col
             #[cfg(feature = "pro")]
             hints.push(("A", "AI", Color::Magenta));
         }
+        Some(TreeItem::UnmanagedHeader { .. }) => {
+            hints.push(("d", if is_zh { "全部忽略" } else { "dismiss all" }, Color::Cyan));
+        }
+        Some(TreeItem::Unmanaged { .. }) => {
+            hints.extend([
+                ("Enter", if is_zh { "接管" } else { "adopt" }, Color::Cyan),
+                ("d", if is_zh { "暂时忽略" } else { "dismiss" }, Color::Cyan),
+                ("U", if is_zh { "忽略此目录" } else { "ignore dir" }, Color::Yellow),
+            ]);
+        }
         _ => {
             hints.extend([
                 ("n", if is_zh { "新建" } else { "new" }, Color::Cyan),
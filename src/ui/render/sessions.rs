@@ -10,6 +10,8 @@ use std::fs;
 use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::config::SessionColumn;
+
 #[derive(Debug, Clone, Deserialize)]
 struct DerivedHookRecord {
     #[allow(dead_code)]
@@ -51,8 +53,9 @@ fn session_has_recent_token_burst(
     })
 }
 
-/// Render session list (splits off active panel at top when premium + active sessions exist)
-pub(super) fn render_session_list(f: &mut Frame, area: Rect, app: &App) {
+/// Render session list (splits off active panel at top when premium + active sessions exist).
+/// Returns the tree's area and the index of its first visible row.
+pub(super) fn render_session_list(f: &mut Frame, area: Rect, app: &App) -> (Rect, usize) {
     #[cfg(feature = "pro")]
     {
         let is_pro = app.auth_token().map_or(false, |t| t.is_pro());
@@ -96,18 +99,19 @@ pub(super) fn render_session_list(f: &mut Frame, area: Rect, app: &App) {
                 render_viewer_sessions_panel(f, rows[row_idx], app);
                 row_idx += 1;
             }
-            render_session_tree(f, rows[row_idx], app);
+            (rows[row_idx], render_session_tree(f, rows[row_idx], app))
         } else {
-            render_session_tree(f, area, app);
+            (area, render_session_tree(f, area, app))
         }
     }
     #[cfg(not(feature = "pro"))]
-    render_session_tree(f, area, app);
+    (area, render_session_tree(f, area, app))
 }
 
 /// Render the active sessions panel (premium feature pinned above the session tree)
 #[cfg(feature = "pro")]
 pub(super) fn render_active_panel(f: &mut Frame, area: Rect, app: &App, active: &[&crate::session::Instance]) {
+    let t = crate::ui::theme::theme();
     let focused = app.active_panel_focused();
     let selected = app.active_panel_selected();
     let token_bursts = load_recent_token_bursts(app.runtime_dir());
@@ -118,16 +122,19 @@ pub(super) fn render_active_panel(f: &mut Frame, area: Rect, app: &App, active:
         .map(|(i, s)| {
             let is_selected = focused && i == selected;
             let base = if is_selected {
-                Style::default().fg(Color::Black).bg(Color::Yellow)
+                Style::default().fg(t.selection_fg).bg(t.highlight)
             } else {
                 Style::default()
             };
 
+            let stalled = app.stalled_for(&s.id).is_some();
             let status_icon = match s.status {
                 Status::Waiting => waiting_anim(app.tick_count()),
+                Status::Running if stalled => STALLED_ICON,
                 Status::Running => running_anim(app.tick_count()),
                 Status::Error => "✕",
                 Status::Starting => "⋯",
+                Status::Stopped => "■",
                 Status::Idle => {
                     if app.is_attention_active(&s.id) {
                         "✓"
@@ -137,17 +144,9 @@ pub(super) fn render_active_panel(f: &mut Frame, area: Rect, app: &App, active:
                 }
             };
             let status_color = match s.status {
-                Status::Waiting => Color::Blue,
-                Status::Running => Color::Yellow,
-                Status::Error => Color::Red,
-                Status::Starting => Color::Cyan,
-                Status::Idle => {
-                    if app.is_attention_active(&s.id) {
-                        Color::Cyan
-                    } else {
-                        Color::DarkGray
-                    }
-                }
+                Status::Idle if app.is_attention_active(&s.id) => t.accent,
+                Status::Running if stalled => t.stalled,
+                status => t.status_color(status),
             };
 
             let mut spans = vec![
@@ -257,6 +256,7 @@ pub(super) fn render_active_panel(f: &mut Frame, area: Rect, app: &App, active:
 /// Render the viewer sessions panel (premium feature)
 #[cfg(feature = "pro")]
 pub(super) fn render_viewer_sessions_panel(f: &mut Frame, area: Rect, app: &App) {
+    let t = crate::ui::theme::theme();
     let sessions = &app.pro.viewer_sessions;
     let focused = app.pro.viewer_panel_focused;
     let selected = app.pro.viewer_panel_selected;
@@ -271,7 +271,7 @@ pub(super) fn render_viewer_sessions_panel(f: &mut Frame, area: Rect, app: &App)
         .map(|(i, (room_id, info))| {
             let is_selected = focused && i == selected;
             let base = if is_selected {
-                Style::default().fg(Color::Black).bg(Color::Yellow)
+                Style::default().fg(t.selection_fg).bg(t.highlight)
             } else {
                 Style::default()
             };
@@ -347,8 +347,124 @@ pub(super) fn render_viewer_sessions_panel(f: &mut Frame, area: Rect, app: &App)
     f.render_stateful_widget(list, area, &mut state);
 }
 
-/// Render the full session tree (groups + sessions)
-pub(super) fn render_session_tree(f: &mut Frame, area: Rect, app: &App) {
+/// Leading spans of a session row: indent, a `◆` when the row is marked,
+/// status icon, the `[label]` badge when there is one, then the title.
+pub(super) fn session_row_head<'a>(
+    indent: String,
+    marked: bool,
+    status_icon: &'a str,
+    status_color: Color,
+    badge: Option<(String, Color)>,
+    title: &'a str,
+    title_style: Style,
+) -> Vec<Span<'a>> {
+    let mut spans = vec![Span::styled(indent, Style::default())];
+    if marked {
+        spans.push(Span::styled(
+            "◆ ",
+            Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+        ));
+    }
+    spans.extend([
+        Span::styled(status_icon, Style::default().fg(status_color)),
+        Span::raw(" "),
+    ]);
+    if let Some((badge, color)) = badge {
+        spans.push(Span::styled(badge, Style::default().fg(color)));
+        spans.push(Span::raw(" "));
+    }
+    spans.push(Span::styled(title, title_style));
+    spans
+}
+
+/// Titles keep at least this many columns before the row's right-hand
+/// columns give way.
+const MIN_TITLE_WIDTH: usize = 12;
+
+/// The right-hand cells of a session row, each padded to a fixed width so
+/// they line up down the list.
+fn session_column_spans(
+    app: &App,
+    session: &crate::session::Instance,
+    columns: &[SessionColumn],
+    style: Style,
+) -> Vec<Span<'static>> {
+    columns
+        .iter()
+        .map(|column| {
+            let cell = match column {
+                SessionColumn::Tool => {
+                    let tool = session.tool.to_string();
+                    format!("{tool:>8}")
+                }
+                SessionColumn::Age => format!(
+                    "{:>4}",
+                    app.last_activity_age(session)
+                        .map(crate::ui::text::format_age)
+                        .unwrap_or_default()
+                ),
+            };
+            Span::styled(cell, style)
+        })
+        .collect()
+}
+
+/// Lay a session row out in `width` columns: `spans` is the row up to its
+/// badges, with the title at `title_idx`; `reason` is what a waiting session
+/// asks; `columns` go on the right. The title is cut with `…` to make room,
+/// down to [`MIN_TITLE_WIDTH`]; when even that is not enough the columns
+/// are dropped. The reason only gets what space is left.
+pub(super) fn fit_session_row<'a>(
+    mut spans: Vec<Span<'a>>,
+    title_idx: usize,
+    reason: Option<Span<'a>>,
+    mut columns: Vec<Span<'a>>,
+    width: usize,
+) -> Line<'a> {
+    let mut used: usize = spans.iter().map(Span::width).sum();
+    let title_width = spans[title_idx].width();
+    let title_give = title_width.saturating_sub(MIN_TITLE_WIDTH);
+    let mut columns_width: usize = columns.iter().map(|c| c.width() + 2).sum();
+    if used.saturating_sub(title_give) + columns_width > width {
+        columns.clear();
+        columns_width = 0;
+    }
+
+    let overflow = (used + columns_width).saturating_sub(width);
+    if overflow > 0 {
+        let style = spans[title_idx].style;
+        let cut = crate::ui::text::truncate(
+            &spans[title_idx].content,
+            title_width - overflow.min(title_give),
+        );
+        used = used - title_width + crate::ui::text::width(&cut);
+        spans[title_idx] = Span::styled(cut, style);
+    }
+
+    if let Some(reason) = reason {
+        let room = width.saturating_sub(used + columns_width + 2);
+        if room >= 2 {
+            let cut = crate::ui::text::truncate(&reason.content, room);
+            used += 2 + crate::ui::text::width(&cut);
+            spans.push(Span::raw("  "));
+            spans.push(Span::styled(cut, reason.style));
+        }
+    }
+
+    if !columns.is_empty() {
+        spans.push(Span::raw(" ".repeat(width.saturating_sub(used + columns_width))));
+        for column in columns {
+            spans.push(Span::raw("  "));
+            spans.push(column);
+        }
+    }
+    Line::from(spans)
+}
+
+/// Render the full session tree (groups + sessions). Returns the index of
+/// the first visible row.
+pub(super) fn render_session_tree(f: &mut Frame, area: Rect, app: &App) -> usize {
+    let t = crate::ui::theme::theme();
     let tree = app.tree();
     let token_bursts = load_recent_token_bursts(app.runtime_dir());
 
@@ -361,16 +477,20 @@ pub(super) fn render_session_tree(f: &mut Frame, area: Rect, app: &App) {
             "No sessions found.\n\nUse: agent-hand add ...\nPress 'n' to create.\nPress '?' for help."
         };
         let empty = Paragraph::new(empty_msg)
-            .style(Style::default().fg(Color::DarkGray))
+            .style(t.dim_style())
             .alignment(Alignment::Center)
             .block(Block::default().borders(Borders::ALL).title(
                 if is_zh { "会话" } else { "Sessions" }
             ));
 
         f.render_widget(empty, area);
-        return;
+        return 0;
     }
 
+    let show_labels = app.show_label_badges();
+    let columns = app.session_columns(area.width);
+    let row_width = area.width.saturating_sub(2) as usize;
+    let now = chrono::Utc::now();
     let tree_focused = {
         #[cfg(feature = "pro")]
         { !app.active_panel_focused() && !app.pro.viewer_panel_focused }
@@ -384,7 +504,7 @@ pub(super) fn render_session_tree(f: &mut Frame, area: Rect, app: &App) {
         .map(|(i, item)| {
             let is_selected = tree_focused && i == app.selected_index();
             let base = if is_selected {
-                Style::default().fg(Color::Black).bg(Color::Cyan)
+                t.selection_style()
             } else {
                 Style::default()
             };
@@ -402,24 +522,45 @@ pub(super) fn render_session_tree(f: &mut Frame, area: Rect, app: &App) {
                         " "
                     };
 
-                    let line = Line::from(vec![
+                    // Something below needs input: flag the group even when collapsed.
+                    let counts = app.group_counts(path);
+                    let name_style = if counts.waiting > 0 && !is_selected {
+                        base.fg(t.waiting)
+                    } else {
+                        base
+                    };
+
+                    let mut spans = vec![
                         Span::styled(indent, Style::default()),
-                        Span::styled(icon, Style::default().fg(Color::Magenta)),
+                        Span::styled(icon, Style::default().fg(t.group)),
                         Span::raw(" "),
-                        Span::styled(name, base.add_modifier(Modifier::BOLD)),
+                        Span::styled(name, name_style.add_modifier(Modifier::BOLD)),
                         Span::raw(" "),
-                        Span::styled(format!("({})", path), Style::default().fg(Color::DarkGray)),
-                    ]);
-                    ListItem::new(line)
+                        Span::styled(format!("({})", path), t.dim_style()),
+                    ];
+                    // Expanded groups show their sessions; only collapsed ones need the tally.
+                    if let Some(label) = counts.label().filter(|_| !app.is_group_expanded(path)) {
+                        spans.push(Span::raw(" "));
+                        spans.push(Span::styled(label, t.dim_style()));
+                    }
+                    ListItem::new(Line::from(spans))
                 }
                 TreeItem::Session { id, depth } => {
-                    let indent = "  ".repeat(*depth);
+                    let fork = app.fork_row(id);
+                    // A fork hangs off its parent's row, one level in.
+                    let indent = if fork.nested {
+                        format!("{}└─", "  ".repeat(depth.saturating_sub(1)))
+                    } else {
+                        "  ".repeat(*depth)
+                    };
                     let s = app.session_by_id(id);
 
-                    let (status_icon, status_color, title, label, label_color) =
+                    let stalled = s.and_then(|session| app.stalled_for(&session.id));
+                    let (status_icon, status_color, title, badge) =
                         if let Some(session) = s {
                             let status_icon = match session.status {
                                 Status::Waiting => waiting_anim(app.tick_count()),
+                                Status::Running if stalled.is_some() => STALLED_ICON,
                                 Status::Running => running_anim(app.tick_count()),
                                 Status::Idle => {
                                     if app.is_attention_active(&session.id) {
@@ -430,63 +571,69 @@ pub(super) fn render_session_tree(f: &mut Frame, area: Rect, app: &App) {
                                 }
                                 Status::Error => "✕",
                                 Status::Starting => "⋯",
+                                Status::Stopped => "■",
                             };
 
                             let status_color = match session.status {
-                                Status::Waiting => Color::Blue,
-                                Status::Running => Color::Yellow,
-                                Status::Idle => {
-                                    if app.is_attention_active(&session.id) {
-                                        Color::Cyan
-                                    } else {
-                                        Color::DarkGray
-                                    }
-                                }
-                                Status::Error => Color::Red,
-                                Status::Starting => Color::Cyan,
+                                Status::Idle if app.is_attention_active(&session.id) => t.accent,
+                                Status::Running if stalled.is_some() => t.stalled,
+                                status => t.status_color(status),
                             };
 
-                            (
-                                status_icon,
-                                status_color,
-                                session.title.as_str(),
-                                session.label.as_str(),
-                                session.label_color,
-                            )
+                            let badge = session
+                                .label_badge()
+                                .filter(|_| show_labels)
+                                .map(|b| (b, crate::ui::label_fg(session.label_color)));
+
+                            (status_icon, status_color, session.title.as_str(), badge)
                         } else {
-                            (
-                                "?",
-                                Color::Red,
-                                "<missing>",
-                                "",
-                                crate::session::LabelColor::Gray,
-                            )
+                            ("?", t.error, "<missing>", None)
                         };
 
-                    let label_color = match label_color {
-                        crate::session::LabelColor::Gray => Color::DarkGray,
-                        crate::session::LabelColor::Magenta => Color::Magenta,
-                        crate::session::LabelColor::Cyan => Color::Cyan,
-                        crate::session::LabelColor::Green => Color::Green,
-                        crate::session::LabelColor::Yellow => Color::Yellow,
-                        crate::session::LabelColor::Red => Color::Red,
-                        crate::session::LabelColor::Blue => Color::Blue,
-                    };
+                    let has_badge = badge.is_some();
+                    let mut spans = session_row_head(
+                        indent,
+                        app.is_marked(id),
+                        status_icon,
+                        status_color,
+                        badge,
+                        title,
+                        base.add_modifier(Modifier::BOLD),
+                    );
+                    let mut title_idx = spans.len() - 1;
+                    // Time in status goes right after the icon.
+                    if let Some(timer) = s.and_then(|session| crate::ui::text::status_timer(session, now)) {
+                        let at = if has_badge { title_idx - 2 } else { title_idx };
+                        spans.insert(at, Span::styled(timer, Style::default().fg(status_color)));
+                        spans.insert(at + 1, Span::raw(" "));
+                        title_idx += 2;
+                    }
+                    if let Some(slot) = app.pin_slot(id) {
+                        let pin = if slot <= 9 { format!("★{slot} ") } else { "★ ".to_string() };
+                        spans.insert(1, Span::styled(pin, Style::default().fg(t.highlight)));
+                        title_idx += 1;
+                    }
 
-                    let mut spans = vec![
-                        Span::styled(indent, Style::default()),
-                        Span::styled(status_icon, Style::default().fg(status_color)),
-                        Span::raw(" "),
-                        Span::styled(title, base.add_modifier(Modifier::BOLD)),
-                    ];
+                    // Hidden forks are counted on their parent.
+                    if fork.forks > 0 && !fork.expanded {
+                        let n = fork.forks;
+                        let label = match (is_zh, n) {
+                            (true, _) => format!("▸ {n} 个分支"),
+                            (false, 1) => "▸ 1 fork".to_string(),
+                            (false, _) => format!("▸ {n} forks"),
+                        };
+                        spans.push(Span::raw("  "));
+                        spans.push(Span::styled(label, t.dim_style()));
+                    }
 
-                    let label = label.trim();
-                    if !label.is_empty() {
+                    if let Some(badge) = app.repo_badge(id) {
                         spans.push(Span::raw("  "));
-                        spans.push(Span::styled(
-                            format!("[{label}]"),
-                            Style::default().fg(label_color),
-                        ));
+                        spans.push(Span::styled(badge.label(), t.dim_style()));
+                    }
+
+                    if s.is_some_and(|session| session.autostart) {
+                        spans.push(Span::raw(" "));
+                        spans.push(Span::styled("↻", Style::default().fg(Color::Green)));
                     }
 
                     if let Some(session) = s {
@@ -663,8 +810,30 @@ pub(super) fn render_session_tree(f: &mut Frame, area: Rect, app: &App) {
                         }
                     }
 
-                    let line = Line::from(spans);
-                    ListItem::new(line)
+                    // What a waiting session asks, or how long a stalled one has
+                    // been quiet; last, so a narrow list drops it first.
+                    let reason = match stalled {
+                        Some(d) => {
+                            let age = crate::ui::text::format_age(chrono::Duration::seconds(
+                                d.as_secs() as i64,
+                            ));
+                            let text = if is_zh {
+                                format!("卡住？{age} 无输出")
+                            } else {
+                                format!("stalled? no output for {age}")
+                            };
+                            Some(Span::styled(text, Style::default().fg(t.stalled)))
+                        }
+                        None => s
+                            .filter(|session| session.status == Status::Waiting)
+                            .and_then(|session| session.waiting_reason.as_deref())
+                            .map(|reason| Span::styled(reason, t.dim_style())),
+                    };
+                    let column_spans = s
+                        .map(|session| session_column_spans(app, session, &columns, t.dim_style()))
+                        .unwrap_or_default();
+
+                    ListItem::new(fit_session_row(spans, title_idx, reason, column_spans, row_width))
                 }
                 TreeItem::Relationship { id, rel_id, depth } => {
                     let indent = "  ".repeat(*depth);
@@ -677,17 +846,11 @@ pub(super) fn render_session_tree(f: &mut Frame, area: Rect, app: &App) {
                             Status::Idle => "○",
                             Status::Error => "✕",
                             Status::Starting => "⋯",
+                            Status::Stopped => "■",
                         };
-                        let color = match session.status {
-                            Status::Waiting => Color::Blue,
-                            Status::Running => Color::Yellow,
-                            Status::Idle => Color::DarkGray,
-                            Status::Error => Color::Red,
-                            Status::Starting => Color::Cyan,
-                        };
-                        (icon, color, session.title.as_str())
+                        (icon, t.status_color(session.status), session.title.as_str())
                     } else {
-                        ("?", Color::Red, "<missing>")
+                        ("?", t.error, "<missing>")
                     };
 
                     // Determine badge color from relationship type
@@ -726,14 +889,48 @@ pub(super) fn render_session_tree(f: &mut Frame, area: Rect, app: &App) {
                     let line = Line::from(spans);
                     ListItem::new(line)
                 }
+                TreeItem::UnmanagedHeader { count } => {
+                    let name = if is_zh { "检测到未托管的 Agent" } else { "Unmanaged agents detected" };
+                    ListItem::new(Line::from(vec![
+                        Span::styled("⚠", Style::default().fg(Color::Yellow)),
+                        Span::raw(" "),
+                        Span::styled(name, base.add_modifier(Modifier::BOLD)),
+                        Span::raw(" "),
+                        Span::styled(format!("({count})"), t.dim_style()),
+                    ]))
+                }
+                TreeItem::Unmanaged { pid } => {
+                    let Some(agent) = app.unmanaged_agent(*pid) else {
+                        return ListItem::new(Line::from(Span::styled(
+                            format!("  pid {pid}"),
+                            t.dim_style(),
+                        )));
+                    };
+                    let cwd = agent
+                        .cwd
+                        .as_ref()
+                        .map_or_else(|| "?".to_string(), |p| p.display().to_string());
+                    ListItem::new(Line::from(vec![
+                        Span::raw("  "),
+                        Span::styled(
+                            format!("{:<7}", agent.tool.to_string()),
+                            Style::default().fg(Color::Yellow),
+                        ),
+                        Span::styled(cwd, base),
+                        Span::styled(
+                            format!("  pid {}  {}", agent.pid, format_elapsed_short(agent.started_at)),
+                            t.dim_style(),
+                        ),
+                    ]))
+                }
             }
         })
         .collect();
 
     let border_style = if tree_focused {
-        Style::default().fg(Color::Yellow)
+        Style::default().fg(t.highlight)
     } else {
-        Style::default().fg(Color::DarkGray)
+        t.dim_style()
     };
 
     let list = List::new(items)
@@ -742,26 +939,106 @@ pub(super) fn render_session_tree(f: &mut Frame, area: Rect, app: &App) {
             Block::default()
                 .borders(Borders::ALL)
                 .title(Span::styled(
-                    format!(
-                        "Tree ({}/{})",
-                        app.selected_index() + 1,
-                        tree.len()
-                    ),
+                    match app.marked_count() {
+                        0 => format!("Tree ({}/{})", app.selected_index() + 1, tree.len()),
+                        marked => format!(
+                            "Tree ({}/{}) · {} marked",
+                            app.selected_index() + 1,
+                            tree.len(),
+                            marked
+                        ),
+                    },
                     if tree_focused {
-                        Style::default().fg(Color::Cyan)
+                        Style::default().fg(t.accent)
                     } else {
-                        Style::default().fg(Color::DarkGray)
+                        t.dim_style()
                     },
                 ))
                 .border_style(border_style),
         );
 
-    let mut state = if tree_focused {
-        app.list_state().clone()
-    } else {
-        ListState::default()
-    };
+    // Start from the remembered offset so refreshes don't scroll; the
+    // widget still scrolls the selected row into view if it has to.
+    let mut state = app.list_state().clone();
+    state.select(tree_focused.then_some(app.selected_index()));
     f.render_stateful_widget(list, area, &mut state);
+    state.offset()
+}
+
+/// The list's stand-in while the preview is maximized: the selected row,
+/// its place in the tree, and the key that brings the list back.
+pub(super) fn render_selection_summary(f: &mut Frame, area: Rect, app: &App) {
+    let t = crate::ui::theme::theme();
+    let is_zh = matches!(app.language(), crate::i18n::Language::Chinese);
+
+    let mut spans = match app.selected_item() {
+        Some(TreeItem::Session { id, .. } | TreeItem::Relationship { id, .. }) => {
+            match app.session_by_id(id) {
+                Some(s) => {
+                    let stalled = app.stalled_for(&s.id).is_some();
+                    let attention = app.is_attention_active(&s.id);
+                    let icon = match s.status {
+                        Status::Waiting => waiting_anim(app.tick_count()),
+                        Status::Running if stalled => STALLED_ICON,
+                        Status::Running => running_anim(app.tick_count()),
+                        Status::Idle if attention => "✓",
+                        Status::Idle => "○",
+                        Status::Error => "✕",
+                        Status::Starting => "⋯",
+                        Status::Stopped => "■",
+                    };
+                    let color = match s.status {
+                        Status::Idle if attention => t.accent,
+                        Status::Running if stalled => t.stalled,
+                        status => t.status_color(status),
+                    };
+                    let mut spans = vec![
+                        Span::styled(icon, Style::default().fg(color)),
+                        Span::raw(" "),
+                    ];
+                    if let Some(timer) = crate::ui::text::status_timer(s, chrono::Utc::now()) {
+                        spans.push(Span::styled(timer, Style::default().fg(color)));
+                        spans.push(Span::raw(" "));
+                    }
+                    spans.push(Span::styled(
+                        s.title.clone(),
+                        Style::default().add_modifier(Modifier::BOLD),
+                    ));
+                    if !s.group_path.is_empty() {
+                        spans.push(Span::raw("  "));
+                        spans.push(Span::styled(format!("({})", s.group_path), t.dim_style()));
+                    }
+                    spans
+                }
+                None => vec![Span::styled("<missing>", Style::default().fg(t.error))],
+            }
+        }
+        Some(TreeItem::Group { name, path, .. }) => vec![
+            Span::styled("▸ ", Style::default().fg(t.group)),
+            Span::styled(name.clone(), Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(" "),
+            Span::styled(format!("({})", path), t.dim_style()),
+        ],
+        _ => vec![Span::styled(
+            if is_zh { "没有选中的会话" } else { "Nothing selected" },
+            t.dim_style(),
+        )],
+    };
+    if !app.tree().is_empty() {
+        spans.push(Span::raw("  "));
+        spans.push(Span::styled(
+            format!("{}/{}", app.selected_index() + 1, app.tree().len()),
+            t.dim_style(),
+        ));
+    }
+
+    let title = if is_zh { " 会话 " } else { " Sessions " };
+    let mut block = Block::default().borders(Borders::ALL).title(title);
+    if let Some(key) = app.keybindings().describe("cycle_layout") {
+        let hint = if is_zh { format!(" {key} 切换布局 ") } else { format!(" {key} layout ") };
+        block = block.title(Line::from(Span::styled(hint, t.dim_style())).right_aligned());
+    }
+    f.render_widget(Paragraph::new(Line::from(spans)).block(block), area);
 }
 
 pub(super) fn render_preview(f: &mut Frame, area: Rect, app: &App) {
@@ -772,15 +1049,54 @@ pub(super) fn render_preview(f: &mut Frame, area: Rect, app: &App) {
     let title = match app.selected_item() {
         Some(TreeItem::Session { id, .. } | TreeItem::Relationship { id, .. }) => app
             .session_by_id(id)
-            .map(|s| format!("{preview_label} • {}", s.title))
+            .map(|s| {
+                let mut title = format!("{preview_label} • {}", s.title);
+                if s.ptmx_count > 0 {
+                    title.push_str(&format!(" • {} pty", s.ptmx_count));
+                }
+                if let Some(label) = app.preview_browse_label() {
+                    title.push_str(&format!(" • {label}"));
+                }
+                title
+            })
             .unwrap_or_else(|| preview_label.to_string()),
         Some(TreeItem::Group { name, .. }) => format!("{preview_label} • {}", name),
         _ => preview_label.to_string(),
     };
 
-    let p = Paragraph::new(app.preview())
+    use crate::ui::preview_view::{highlighted_lines, wrapped_rows};
+
+    let view = app.preview_view();
+    let is_zh = matches!(app.language(), crate::i18n::Language::Chinese);
+    let mut block = Block::default().borders(Borders::ALL).title(title);
+    if view.focused {
+        let t = crate::ui::theme::theme();
+        block = block.border_style(t.dialog_border_style());
+        let hint = match &view.search {
+            Some(input) => format!(" /{}▏ ", input.text()),
+            None if is_zh => " j/k Ctrl+U/D 滚动  / 搜索  n/N 下/上一个  Esc 退出 ".to_string(),
+            None => " j/k Ctrl+U/D scroll  / search  n/N next/prev  Esc leave ".to_string(),
+        };
+        block = block.title_bottom(Line::from(Span::styled(hint, Style::default().fg(t.accent))));
+    }
+
+    // The offset counts content lines; the paragraph scrolls wrapped rows.
+    let inner = block.inner(area);
+    let text = app.preview();
+    let rows: Vec<usize> = text.lines().map(|l| wrapped_rows(l, inner.width)).collect();
+    let max_offset = rows
+        .iter()
+        .sum::<usize>()
+        .saturating_sub(usize::from(inner.height));
+    let offset = rows[..view.scroll.min(rows.len())]
+        .iter()
+        .sum::<usize>()
+        .min(max_offset);
+
+    let p = Paragraph::new(highlighted_lines(text, &view.query, view.current_match))
         .wrap(Wrap { trim: false })
-        .block(Block::default().borders(Borders::ALL).title(title));
+        .scroll((offset.min(usize::from(u16::MAX)) as u16, 0))
+        .block(block);
 
     f.render_widget(p, area);
 }
@@ -1166,9 +1482,16 @@ pub(super) fn render_onboarding_welcome(f: &mut Frame, area: Rect, lang: crate::
     f.render_widget(paragraph, modal_area);
 }
 
-pub(super) fn render_help_modal(f: &mut Frame, area: Rect, lang: crate::i18n::Language) {
+pub(super) fn render_help_modal(
+    f: &mut Frame,
+    area: Rect,
+    lang: crate::i18n::Language,
+    simple: bool,
+    bindings: &crate::config::KeyBindings,
+) {
     use crate::i18n::Language;
 
+    let t = crate::ui::theme::theme();
     let modal_area = centered_rect(80, 85, area);
     f.render_widget(Clear, modal_area);
 
@@ -1177,12 +1500,12 @@ pub(super) fn render_help_modal(f: &mut Frame, area: Rect, lang: crate::i18n::La
             Span::styled(
                 format!("── {label} "),
                 Style::default()
-                    .fg(Color::Cyan)
+                    .fg(t.accent)
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(
                 "─".repeat(40usize.saturating_sub(label.len() + 4)),
-                Style::default().fg(Color::DarkGray),
+                t.dim_style(),
             ),
         ])
     };
@@ -1191,65 +1514,107 @@ pub(super) fn render_help_modal(f: &mut Frame, area: Rect, lang: crate::i18n::La
         Line::from(vec![
             Span::styled(
                 format!("  {:<12}", k),
-                Style::default().fg(Color::Yellow),
+                Style::default().fg(t.highlight),
             ),
             Span::raw(desc.to_string()),
         ])
     };
 
+    // Labels come from the loaded bindings so rebound keys show up here.
+    let bound = |actions: &[&str]| -> String {
+        let keys: Vec<String> = actions.iter().filter_map(|a| bindings.describe(a)).collect();
+        if keys.is_empty() {
+            "—".to_string()
+        } else {
+            keys.join("/")
+        }
+    };
+
     let hint = |text: &str| -> Line<'static> {
         Line::from(Span::styled(
             format!("  {text}"),
-            Style::default().fg(Color::DarkGray),
+            t.dim_style(),
         ))
     };
 
     let is_zh = matches!(lang, Language::Chinese);
 
-    let help_text: Vec<Line<'static>> = vec![
+    let mut help_text: Vec<Line<'static>> = vec![
         Line::from(""),
         section(if is_zh { "导航" } else { "Navigation" }),
         hint(if is_zh { "在树形视图中浏览会话和分组" } else { "Browse sessions and groups in the tree view" }),
-        key("↑/k", if is_zh { "向上移动" } else { "Move up" }),
-        key("↓/j", if is_zh { "向下移动" } else { "Move down" }),
-        key("←/→", if is_zh { "展开/折叠分组" } else { "Expand/collapse group" }),
-        key("/", if is_zh { "按名称搜索会话" } else { "Search sessions by name" }),
+        key(&bound(&["up"]), if is_zh { "向上移动" } else { "Move up" }),
+        key(&bound(&["down"]), if is_zh { "向下移动" } else { "Move down" }),
+        key(&bound(&["page_up", "page_down"]), if is_zh { "上下翻一页" } else { "Move up / down a page" }),
+        key(&bound(&["top", "bottom"]), if is_zh { "跳到第一项/最后一项" } else { "Jump to the first / last row" }),
+        key(&bound(&["collapse", "expand"]), if is_zh { "展开/折叠分组或会话的分支" } else { "Expand/collapse group, or a session's forks" }),
+        key(&bound(&["search"]), if is_zh { "按名称搜索会话" } else { "Search sessions by name" }),
         key("Tab", if is_zh { "切换面板焦点：活跃→观察→树 (Pro)" } else { "Cycle focus: Active → Viewer → Tree (Pro)" }),
         Line::from(""),
         section(if is_zh { "会话操作" } else { "Session Actions" }),
         hint(if is_zh { "在树形视图中管理单个会话" } else { "Manage individual sessions from the tree view" }),
-        key("Enter", if is_zh { "连接到所选会话的终端" } else { "Attach to the selected session's terminal" }),
-        key("s", if is_zh { "启动已停止的会话" } else { "Start a stopped session" }),
-        key("x", if is_zh { "停止正在运行的会话" } else { "Stop a running session" }),
-        key("r", if is_zh { "编辑会话名称或配置" } else { "Edit session name or configuration" }),
-        key("R", if is_zh { "重启：先停止再启动会话" } else { "Restart: stop then start a session" }),
-        key("m", if is_zh { "将会话移动到其他分组" } else { "Move session to a different group" }),
-        key("f", if is_zh { "复制：创建会话副本" } else { "Fork: create a copy of the session" }),
-        key("d", if is_zh { "永久删除会话" } else { "Delete session permanently" }),
-        key("b", if is_zh { "提升：将会话置顶到活跃面板" } else { "Boost: bring session to active panel" }),
-        key("u", if is_zh { "恢复：继续 AI CLI 对话" } else { "Resume: continue AI CLI conversation" }),
+        key(&bound(&["select"]), if is_zh { "连接到所选会话的终端" } else { "Attach to the selected session's terminal" }),
+        key(&bound(&["start"]), if is_zh { "启动已停止的会话" } else { "Start a stopped session" }),
+        key(&bound(&["stop"]), if is_zh { "停止正在运行的会话" } else { "Stop a running session" }),
+        key(&bound(&["rename"]), if is_zh { "编辑会话名称或配置" } else { "Edit session name or configuration" }),
+        key(&bound(&["restart"]), if is_zh { "重启：先停止再启动会话" } else { "Restart: stop then start a session" }),
+        key(&bound(&["move"]), if is_zh { "将会话移动到其他分组" } else { "Move session to a different group" }),
+        key(&bound(&["move_session_up", "move_session_down"]), if is_zh { "在分组内上移/下移会话（手动排序）" } else { "Move session up/down within its group (manual sort)" }),
+        key(&bound(&["label"]), if is_zh { "设置会话标签和颜色" } else { "Set the session's label and color" }),
+        key(&bound(&["fork"]), if is_zh { "复制：创建会话副本" } else { "Fork: create a copy of the session" }),
+        key(&bound(&["lineage"]), if is_zh { "查看分支谱系（D 与父会话比较）" } else { "Fork lineage (D: diff vs parent)" }),
+        key(&bound(&["unmanaged_agents"]), if is_zh { "跳到未托管的 Agent 进程（需开启 discovery）；在其行上忽略该目录" } else { "Jump to unmanaged agents (needs discovery.enabled); on one, ignore its dir" }),
+        key(&bound(&["notify_siblings"]), if is_zh { "通知同仓库的其他会话：有新提交" } else { "Notify sessions in the same repo of new commits" }),
+        key(&bound(&["wrapup"]), if is_zh { "收尾：让运行中的 Agent 完成工作后停止（选中分组时仅该分组）" } else { "Wrap up: ask running agents to finish, then stop them (selected group only)" }),
+        key(&bound(&["forward_output"]), if is_zh { "把当前会话最近的输出转发给另一个会话（不提交）" } else { "Forward recent output to another session (typed, not submitted)" }),
+        key(&bound(&["delete"]), if is_zh { "删除会话" } else { "Delete session" }),
+        key(&bound(&["undo"]), if is_zh { "撤销最近一次删除会话或分组（本次运行内最多 10 次）" } else { "Undo the last session or group delete (up to 10 per run)" }),
+        key(&bound(&["boost"]), if is_zh { "提升：将会话置顶到活跃面板" } else { "Boost: bring session to active panel" }),
+        key(&bound(&["resume"]), if is_zh { "恢复：继续 AI CLI 对话" } else { "Resume: continue AI CLI conversation" }),
+        key(&bound(&["mark", "toggle_group"]), if is_zh { "标记会话；有标记时 s/x/d/m 作用于全部标记（Esc 清除）" } else { "Mark a session; s/x/d/m then act on all marked (Esc clears)" }),
+        key(&bound(&["compare"]), if is_zh { "并排对比两个标记的会话（或标记的与选中的）" } else { "Compare two marked sessions (or marked + selected) side by side" }),
+        key(&bound(&["pin"]), if is_zh { "置顶/取消置顶：置顶会话显示在列表最上方 (★)" } else { "Pin/unpin: pinned sessions stay at the top of the list (★)" }),
+        key("1-9", if is_zh { "直接进入第 N 个置顶会话" } else { "Attach to the Nth pinned session" }),
+        key(&bound(&["acknowledge_stall"]), if is_zh { "忽略卡住提示 (⊘)，直到会话输出变化" } else { "Dismiss a stall marker (⊘) until the session's output changes" }),
+        key(&bound(&["toggle_autostart"]), if is_zh { "切换自动启动：TUI 启动时自动运行该会话 (↻)" } else { "Toggle autostart: run the session when the TUI starts (↻)" }),
+        key(&bound(&["transcript"]), if is_zh { "保存完整滚动记录到 transcripts/" } else { "Save full scrollback to transcripts/" }),
         #[cfg(feature = "pro")]
-        key("A", if is_zh { "AI 总结会话输出 (Max)" } else { "AI summary of session output (Max)" }),
+        key(&bound(&["summarize"]), if is_zh { "AI 总结会话输出 (Max)" } else { "AI summary of session output (Max)" }),
         Line::from(""),
         section(if is_zh { "分组操作" } else { "Group Actions" }),
         hint(if is_zh { "将会话整理到可折叠的分组中" } else { "Organize sessions into collapsible groups" }),
-        key("Enter", if is_zh { "展开/折叠分组" } else { "Toggle group expand/collapse" }),
-        key("r", if is_zh { "重命名分组" } else { "Rename group" }),
-        key("d", if is_zh { "删除分组（会话保留）" } else { "Delete group (sessions are unlinked)" }),
+        key(&bound(&["toggle_group"]), if is_zh { "展开/折叠分组" } else { "Toggle group expand/collapse" }),
+        key(&bound(&["rename"]), if is_zh { "重命名分组" } else { "Rename group" }),
+        key(&bound(&["move_group"]), if is_zh { "移动分组（含子分组和会话）到其他父分组" } else { "Move group (with subgroups and sessions) under another parent" }),
+        key(&bound(&["group_notes"]), if is_zh { "编辑分组备注（显示在预览中）" } else { "Edit group notes (shown in the preview)" }),
+        key(&bound(&["delete"]), if is_zh { "删除分组（会话保留）" } else { "Delete group (sessions are unlinked)" }),
         Line::from(""),
         section(if is_zh { "全局" } else { "Global" }),
         hint(if is_zh { "在任何界面均可使用" } else { "Available from any screen" }),
-        key("n", if is_zh { "创建新会话" } else { "Create a new session" }),
-        key("g", if is_zh { "创建新分组" } else { "Create a new group" }),
-        key("p", if is_zh { "预览最近的会话快照" } else { "Preview latest session snapshot" }),
-        key("Ctrl+r", if is_zh { "强制刷新所有会话状态" } else { "Force refresh all session statuses" }),
+        key(&bound(&["new_session"]), if is_zh { "创建新会话" } else { "Create a new session" }),
+        key(&bound(&["create_group"]), if is_zh { "创建新分组" } else { "Create a new group" }),
+        key(&bound(&["canvas_toggle"]), if is_zh { "预览最近的会话快照" } else { "Preview latest session snapshot" }),
+        key(&bound(&["preview_older", "preview_newer"]), if is_zh { "在预览中浏览较早/较新的快照" } else { "Browse older/newer snapshots in the preview" }),
+        key(&bound(&["half_page_up", "half_page_down"]), if is_zh { "滚动预览半页" } else { "Scroll the preview half a page" }),
+        key(&bound(&["preview_page_up", "preview_page_down"]), if is_zh { "滚动预览一页" } else { "Scroll the preview a page" }),
+        key(&bound(&["preview_focus"]), if is_zh { "聚焦预览：载入更多回滚输出，/ 搜索，n/N 跳转，Esc 退出" } else { "Focus the preview: more scrollback, / search, n/N jump, Esc leaves" }),
+        key(&bound(&["cycle_layout"]), if is_zh { "切换布局：列表与预览 → 预览最大化 → 列表最大化" } else { "Cycle the layout: list and preview → preview maximized → list maximized" }),
+        key(&bound(&["jump_back"]), if is_zh { "返回上一个连接过的会话" } else { "Jump back to the previously attached session" }),
+        key(&bound(&["jump_waiting"]), if is_zh { "选中等待最久的会话" } else { "Select the session waiting longest" }),
+        key(&bound(&["waiting_queue"]), if is_zh { "等待队列：按等待时长列出等待输入的会话" } else { "Waiting queue: sessions waiting for input, longest first" }),
+        key(&bound(&["profile_picker"]), if is_zh { "切换配置（profile），无需重启" } else { "Switch profile without restarting" }),
+        key(&bound(&["message_log"]), if is_zh { "查看最近 20 条提示与错误" } else { "Show the last 20 messages and errors" }),
+        key(&bound(&["refresh"]), if is_zh { "强制刷新所有会话状态" } else { "Force refresh all session statuses" }),
         key("Ctrl+e", if is_zh { "查看会话关系图" } else { "View session relationships graph" }),
-        key("K", if is_zh { "打开 Skills 浏览器 (Pro)" } else { "Open skills browser (Pro)" }),
+        key(&bound(&["skills_browser"]), if is_zh { "打开 Skills 浏览器 (Pro)" } else { "Open skills browser (Pro)" }),
         key("Shift+S", if is_zh { "通过中继分享会话 (Pro)" } else { "Share session via relay (Pro)" }),
         key("Shift+J", if is_zh { "通过 URL 加入共享会话 (Pro)" } else { "Join a shared session by URL (Pro)" }),
-        key(",", if is_zh { "打开设置" } else { "Open settings" }),
-        key("?", if is_zh { "切换帮助界面" } else { "Toggle this help screen" }),
-        key("q", if is_zh { "退出 Agent Hand" } else { "Quit Agent Hand" }),
+        key(&bound(&["settings"]), if is_zh { "打开设置" } else { "Open settings" }),
+        key(&bound(&["toggle_ui_mode"]), if is_zh { "切换到简洁模式" } else { "Switch to simple mode" }),
+        key("F11", if is_zh { "显示/隐藏性能面板（各阶段耗时与 tmux 调用数）" } else { "Toggle the perf overlay (phase timings, tmux calls)" }),
+        key("e", if is_zh { "在此界面重新绑定按键（写入配置文件）" } else { "Rebind keys from here (written to the config file)" }),
+        key(&bound(&["help"]), if is_zh { "切换帮助界面" } else { "Toggle this help screen" }),
+        key(&bound(&["quit"]), if is_zh { "退出 Agent Hand" } else { "Quit Agent Hand" }),
         Line::from(""),
         section(if is_zh { "观察者会话面板 (Pro)" } else { "Viewer Sessions Panel (Pro)" }),
         hint(if is_zh { "管理已连接的共享会话" } else { "Manage shared sessions you've connected to" }),
@@ -1273,28 +1638,58 @@ pub(super) fn render_help_modal(f: &mut Frame, area: Rect, lang: crate::i18n::La
         section(if is_zh { "状态指示器" } else { "Status Indicators" }),
         hint(if is_zh { "树形视图中的会话状态图标" } else { "Session status icons in the tree view" }),
         Line::from(vec![
-            Span::styled("  !  ", Style::default().fg(Color::Blue)),
+            Span::styled("  !  ", Style::default().fg(t.waiting)),
             Span::raw(if is_zh { "等待中" } else { "WAITING" }),
             Span::raw("    "),
-            Span::styled("✓  ", Style::default().fg(Color::Cyan)),
+            Span::styled("✓  ", Style::default().fg(t.accent)),
             Span::raw(if is_zh { "就绪" } else { "READY" }),
             Span::raw("     "),
-            Span::styled("●  ", Style::default().fg(Color::Yellow)),
+            Span::styled("●  ", Style::default().fg(t.running)),
             Span::raw(if is_zh { "运行中" } else { "RUNNING" }),
         ]),
         Line::from(vec![
-            Span::styled("  ○  ", Style::default().fg(Color::DarkGray)),
+            Span::styled("  ○  ", Style::default().fg(t.idle)),
             Span::raw(if is_zh { "空闲" } else { "IDLE" }),
             Span::raw("       "),
-            Span::styled("✕  ", Style::default().fg(Color::Red)),
+            Span::styled("✕  ", Style::default().fg(t.error)),
             Span::raw(if is_zh { "错误" } else { "ERROR" }),
         ]),
         Line::from(""),
         Line::from(Span::styled(
             if is_zh { "          按 ? 或 Esc 关闭" } else { "          Press ? or Esc to close" },
-            Style::default().fg(Color::DarkGray),
+            t.dim_style(),
         )),
     ];
+    // Simple mode lists only the actions it keeps.
+    if simple {
+        help_text = vec![
+            Line::from(""),
+            section(if is_zh { "简洁模式" } else { "Simple Mode" }),
+            hint(if is_zh { "只显示最常用的操作" } else { "Only the everyday actions are shown" }),
+            key(&bound(&["up", "down"]), if is_zh { "上下移动" } else { "Move up / down" }),
+            key(&bound(&["collapse", "expand"]), if is_zh { "展开/折叠分组" } else { "Expand/collapse group" }),
+            key(&bound(&["select"]), if is_zh { "连接到所选会话的终端" } else { "Attach to the selected session's terminal" }),
+            key(&bound(&["start"]), if is_zh { "启动已停止的会话" } else { "Start a stopped session" }),
+            key(&bound(&["new_session"]), if is_zh { "创建新会话" } else { "Create a new session" }),
+            key(&bound(&["delete"]), if is_zh { "删除会话" } else { "Delete session" }),
+            key(&bound(&["undo"]), if is_zh { "撤销最近一次删除" } else { "Undo the last delete" }),
+            key(&bound(&["search"]), if is_zh { "按名称搜索会话" } else { "Search sessions by name" }),
+            key(&bound(&["settings"]), if is_zh { "打开设置" } else { "Open settings" }),
+            key(&bound(&["help"]), if is_zh { "切换帮助界面" } else { "Toggle this help screen" }),
+            key(&bound(&["quit"]), if is_zh { "退出 Agent Hand" } else { "Quit Agent Hand" }),
+            Line::from(""),
+            hint(&if is_zh {
+                format!("分组、复制、画布等更多操作在完整模式中 — 按 {} 切换", bound(&["toggle_ui_mode"]))
+            } else {
+                format!("Groups, fork, canvas and more are in full mode — press {} to switch", bound(&["toggle_ui_mode"]))
+            }),
+            Line::from(""),
+            Line::from(Span::styled(
+                if is_zh { "          按 ? 或 Esc 关闭" } else { "          Press ? or Esc to close" },
+                t.dim_style(),
+            )),
+        ];
+    }
 
     let help_title = if is_zh { " ⌨ 命令与快捷键 " } else { " ⌨ Commands & Shortcuts " };
     let help = Paragraph::new(help_text)
@@ -1306,10 +1701,10 @@ pub(super) fn render_help_modal(f: &mut Frame, area: Rect, lang: crate::i18n::La
                 .title(Span::styled(
                     help_title,
                     Style::default()
-                        .fg(Color::Cyan)
+                        .fg(t.accent)
                         .add_modifier(Modifier::BOLD),
                 ))
-                .border_style(Style::default().fg(Color::Cyan)),
+                .border_style(t.dialog_border_style()),
         );
 
     f.render_widget(help, modal_area);
@@ -315,4 +315,636 @@ mod render_tests {
         assert!(unfocused.contains("Canvas"), "unfocused should have title");
         assert!(focused.contains("Canvas"), "focused should have title");
     }
+
+    // ── Fitted dialogs on small terminals ───────────────────────
+
+    fn new_session_with_suggestions(n: usize) -> crate::ui::NewSessionDialog {
+        let mut d = crate::ui::NewSessionDialog::new(PathBuf::from("/tmp"), String::new(), Vec::new());
+        d.path_suggestions = (0..n).map(|i| format!("/tmp/suggestion-{i}")).collect();
+        d.path_suggestions_idx = n - 1;
+        d.path_suggestions_visible = true;
+        d
+    }
+
+    #[test]
+    fn new_session_dialog_fits_eight_suggestions_at_100x28() {
+        let mut terminal = Terminal::new(TestBackend::new(100, 28)).unwrap();
+        let dialog = new_session_with_suggestions(8);
+
+        terminal.draw(|f| {
+            super::super::render_new_session_dialog(
+                f,
+                Rect::new(0, 0, 100, 28),
+                &dialog,
+                Default::default(),
+                true,
+                false,
+            );
+        }).unwrap();
+        let output = buffer_to_string(&terminal);
+
+        for needle in ["/tmp/suggestion-0", "/tmp/suggestion-7", "Title:", "Group:", "Tab: complete path"] {
+            assert!(output.contains(needle), "missing {needle:?}. Output:\n{output}");
+        }
+        assert!(!output.contains(" ↓"), "nothing should be scrolled. Output:\n{output}");
+    }
+
+    #[test]
+    fn new_session_dialog_scrolls_to_selection_when_too_short() {
+        let mut terminal = Terminal::new(TestBackend::new(100, 12)).unwrap();
+        let dialog = new_session_with_suggestions(8);
+
+        terminal.draw(|f| {
+            super::super::render_new_session_dialog(
+                f,
+                Rect::new(0, 0, 100, 12),
+                &dialog,
+                Default::default(),
+                true,
+                false,
+            );
+        }).unwrap();
+        let output = buffer_to_string(&terminal);
+
+        assert!(output.contains("/tmp/suggestion-7"), "selection must stay visible. Output:\n{output}");
+        assert!(output.contains("↑4 ↓7"), "should show hidden line counts. Output:\n{output}");
+    }
+
+    #[test]
+    fn simple_mode_collapses_new_session_group() {
+        let mut terminal = Terminal::new(TestBackend::new(100, 28)).unwrap();
+        let dialog = crate::ui::NewSessionDialog::new(PathBuf::from("/tmp"), "work".into(), Vec::new());
+
+        terminal.draw(|f| {
+            super::super::render_new_session_dialog(
+                f,
+                Rect::new(0, 0, 100, 28),
+                &dialog,
+                Default::default(),
+                false,
+                false,
+            );
+        }).unwrap();
+        let output = buffer_to_string(&terminal);
+
+        assert!(output.contains("Title:"), "Output:\n{output}");
+        assert!(output.contains("advanced… (Ctrl+O)"), "Output:\n{output}");
+        assert!(!output.contains("Group:"), "Output:\n{output}");
+    }
+
+    #[test]
+    fn new_session_dialog_shows_tool_and_command() {
+        let mut terminal = Terminal::new(TestBackend::new(100, 28)).unwrap();
+        let mut dialog = crate::ui::NewSessionDialog::new(PathBuf::from("/tmp"), String::new(), Vec::new());
+        dialog.field = crate::ui::NewSessionField::Tool;
+        dialog.cycle_tool(false);
+
+        terminal.draw(|f| {
+            super::super::render_new_session_dialog(
+                f,
+                Rect::new(0, 0, 100, 28),
+                &dialog,
+                Default::default(),
+                true,
+                false,
+            );
+        }).unwrap();
+        let output = buffer_to_string(&terminal);
+
+        assert!(output.contains("Tool:   ◂ claude ▸"), "Output:\n{output}");
+        assert!(output.contains("Command:claude"), "Output:\n{output}");
+    }
+
+    #[test]
+    fn waiting_queue_dialog_numbers_sessions_and_shows_wait() {
+        use crate::session::{Instance, Status};
+
+        let mut sessions: Vec<Instance> = ["api", "web"]
+            .iter()
+            .map(|t| Instance::with_group(t.to_string(), PathBuf::from("/tmp"), "work".into()))
+            .collect();
+        for (s, mins) in sessions.iter_mut().zip([5, 25]) {
+            s.status = Status::Waiting;
+            s.last_waiting_at = Some(chrono::Utc::now() - chrono::Duration::minutes(mins));
+        }
+        let queue = crate::session::waiting::waiting_queue(&sessions);
+        let dialog = crate::ui::WaitingQueueDialog::default();
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|f| {
+            super::super::render_waiting_queue_dialog(f, f.area(), &dialog, &queue, false);
+        }).unwrap();
+        let output = buffer_to_string(&terminal);
+
+        assert!(output.contains("1. web  work  25m"), "Output:\n{output}");
+        assert!(output.contains("2. api  work  5m"), "Output:\n{output}");
+    }
+
+    #[test]
+    fn restart_exited_dialog_says_how_the_session_ended() {
+        let mut dialog = crate::ui::RestartExitedDialog {
+            session_id: "id".into(),
+            title: "api".into(),
+            attach_failed: false,
+        };
+
+        for (attach_failed, reason) in
+            [(false, "It ended while you were attached."), (true, "It was gone before")]
+        {
+            dialog.attach_failed = attach_failed;
+            let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+            terminal.draw(|f| {
+                super::super::render_restart_exited_dialog(f, f.area(), &dialog, false);
+            }).unwrap();
+            let output = buffer_to_string(&terminal);
+
+            assert!(output.contains("Session exited"), "Output:\n{output}");
+            assert!(output.contains(reason), "Output:\n{output}");
+            assert!(output.contains("Enter/y: restart"), "Output:\n{output}");
+        }
+    }
+
+    #[test]
+    fn rename_session_dialog_shows_why_the_title_was_refused() {
+        let dialog = crate::ui::RenameSessionDialog {
+            session_id: "abc".into(),
+            old_title: "api".into(),
+            new_title: crate::ui::TextInput::with_text("web".to_string()),
+            label: crate::ui::TextInput::new(),
+            label_color: crate::session::LabelColor::Gray,
+            cli_session_id: crate::ui::TextInput::new(),
+            field: crate::ui::SessionEditField::SessionId,
+            error: Some(crate::session::titles::TitleError::Taken.message(false)),
+        };
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|f| {
+            super::super::render_rename_session_dialog(f, f.area(), &dialog, false);
+        }).unwrap();
+        let output = buffer_to_string(&terminal);
+
+        assert!(output.contains("Another session in this group"), "Output:\n{output}");
+        assert!(output.contains("Tab:next field"), "Output:\n{output}");
+    }
+
+    #[test]
+    fn profile_picker_marks_the_current_profile() {
+        let dialog = crate::ui::ProfilePickerDialog {
+            profiles: vec!["default".into(), "work".into()],
+            current: "work".into(),
+            selected: 0,
+        };
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|f| {
+            super::super::render_profile_picker_dialog(f, f.area(), &dialog, false);
+        }).unwrap();
+        let output = buffer_to_string(&terminal);
+
+        assert!(output.contains("Switch Profile"), "Output:\n{output}");
+        assert!(output.contains("work  (current)"), "Output:\n{output}");
+        assert!(!output.contains("default  (current)"), "Output:\n{output}");
+    }
+
+    #[test]
+    fn message_log_lists_newest_first_with_repeats() {
+        use crate::ui::app::messages::LoggedMessage;
+        use ratatui::style::Color;
+
+        let mut error = LoggedMessage::new("tmux failed".into(), Color::Red);
+        error.repeats = 3;
+        let log = vec![error, LoggedMessage::new("Started api".into(), Color::Green)];
+        let dialog = crate::ui::MessageLogDialog::default();
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|f| {
+            super::super::render_message_log_dialog(f, f.area(), &dialog, &log, false);
+        }).unwrap();
+        let output = buffer_to_string(&terminal);
+
+        assert!(output.contains("Recent Messages"), "Output:\n{output}");
+        assert!(output.contains("tmux failed  ×3"), "Output:\n{output}");
+        let failed = output.find("tmux failed").unwrap();
+        let started = output.find("Started api").unwrap();
+        assert!(failed < started, "Output:\n{output}");
+    }
+
+    #[test]
+    fn simple_mode_help_lists_only_simple_actions() {
+        let mut terminal = Terminal::new(TestBackend::new(100, 40)).unwrap();
+        terminal.draw(|f| {
+            super::super::render_help_modal(
+                f,
+                f.area(),
+                crate::i18n::Language::English,
+                true,
+                &crate::config::KeyBindings::default(),
+            );
+        }).unwrap();
+        let output = buffer_to_string(&terminal);
+
+        assert!(output.contains("Create a new session"), "Output:\n{output}");
+        assert!(output.contains("press F9"), "Output:\n{output}");
+        assert!(!output.contains("Fork:"), "Output:\n{output}");
+    }
+
+    #[test]
+    fn help_shows_rebound_keys() {
+        use crate::config::KeySpec;
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        let mut bindings = crate::config::KeyBindings::default();
+        bindings.set_binding(
+            "new_session",
+            vec![KeySpec { code: KeyCode::Char('N'), modifiers: KeyModifiers::CONTROL }],
+        );
+        bindings.set_binding("toggle_ui_mode", Vec::new());
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 40)).unwrap();
+        terminal.draw(|f| {
+            super::super::render_help_modal(f, f.area(), crate::i18n::Language::English, true, &bindings);
+        }).unwrap();
+        let output = buffer_to_string(&terminal);
+
+        assert!(output.contains("Ctrl+N      Create a new session"), "Output:\n{output}");
+        assert!(output.contains("press — to switch"), "Output:\n{output}");
+    }
+
+    #[test]
+    fn rebind_dialog_previews_key_and_conflicts() {
+        use crate::config::{KeySpec, RebindTarget};
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        let dialog = crate::ui::RebindDialog {
+            rows: vec![
+                crate::ui::RebindRow {
+                    target: RebindTarget::Action("delete"),
+                    current: "d".to_string(),
+                },
+                crate::ui::RebindRow {
+                    target: RebindTarget::Tmux("switcher"),
+                    current: "C-g".to_string(),
+                },
+            ],
+            selected: 0,
+            stage: crate::ui::RebindStage::Confirm {
+                key: KeySpec {
+                    code: KeyCode::Char('x'),
+                    modifiers: KeyModifiers::NONE,
+                },
+                value: "x".to_string(),
+                conflicts: vec!["[normal] x: stop shadows delete".to_string()],
+            },
+        };
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        terminal.draw(|f| {
+            super::super::render_rebind_dialog(f, f.area(), &dialog, false);
+        }).unwrap();
+        let output = buffer_to_string(&terminal);
+
+        assert!(output.contains("tmux switcher"), "Output:\n{output}");
+        assert!(output.contains("delete: d → x"), "Output:\n{output}");
+        assert!(output.contains("stop shadows delete"), "Output:\n{output}");
+        assert!(output.contains("Enter: save to config"), "Output:\n{output}");
+    }
+
+    // ── Session row label badge ──────────────────────────────────
+
+    fn render_row(marked: bool, badge: Option<(String, ratatui::style::Color)>) -> Terminal<TestBackend> {
+        use ratatui::style::{Color, Style};
+        use ratatui::text::Line;
+        use ratatui::widgets::Paragraph;
+
+        let mut terminal = Terminal::new(TestBackend::new(40, 1)).unwrap();
+        terminal.draw(|f| {
+            let spans = super::super::session_row_head(
+                "  ".to_string(),
+                marked,
+                "○",
+                Color::DarkGray,
+                badge,
+                "api",
+                Style::default(),
+            );
+            f.render_widget(Paragraph::new(Line::from(spans)), f.area());
+        }).unwrap();
+        terminal
+    }
+
+    #[test]
+    fn session_row_without_label_has_no_badge() {
+        let terminal = render_row(false, None);
+        assert_eq!(buffer_to_string(&terminal), "  ○ api");
+    }
+
+    #[test]
+    fn marked_session_row_has_indicator_before_status() {
+        use ratatui::style::Color;
+
+        let terminal = render_row(true, None);
+        assert_eq!(buffer_to_string(&terminal), "  ◆ ○ api");
+        assert_eq!(terminal.backend().buffer()[(2, 0)].fg, Color::Magenta);
+    }
+
+    #[test]
+    fn session_row_shows_colored_badge_before_title() {
+        use ratatui::style::Color;
+
+        let badge = crate::session::label_badge("waiting on review").unwrap();
+        let terminal = render_row(false, Some((badge, Color::Red)));
+        assert_eq!(buffer_to_string(&terminal), "  ○ [waiting o…] api");
+        let buf = terminal.backend().buffer();
+        assert_eq!(buf[(4, 0)].fg, Color::Red);
+        assert_eq!(buf[(17, 0)].fg, Color::Reset);
+    }
+
+    // ── Session row columns ──────────────────────────────────────
+
+    fn fit_row(title: &str, reason: Option<&str>, width: u16) -> String {
+        use ratatui::style::Style;
+        use ratatui::text::Span;
+        use ratatui::widgets::Paragraph;
+
+        let mut terminal = Terminal::new(TestBackend::new(width, 1)).unwrap();
+        terminal.draw(|f| {
+            let spans = super::super::session_row_head(
+                "  ".to_string(),
+                false,
+                "○",
+                ratatui::style::Color::DarkGray,
+                None,
+                title,
+                Style::default(),
+            );
+            let title_idx = spans.len() - 1;
+            let columns = vec![Span::raw("  claude"), Span::raw("  3m")];
+            let line = super::super::fit_session_row(
+                spans,
+                title_idx,
+                reason.map(Span::raw),
+                columns,
+                width as usize,
+            );
+            f.render_widget(Paragraph::new(line), f.area());
+        }).unwrap();
+        buffer_to_string(&terminal)
+    }
+
+    #[test]
+    fn long_title_is_cut_so_columns_stay_right_aligned() {
+        assert_eq!(
+            fit_row("refactor-the-authentication-layer", None, 40),
+            "  ○ refactor-the-authen…    claude    3m"
+        );
+        assert_eq!(
+            fit_row("api", None, 40),
+            "  ○ api                     claude    3m"
+        );
+    }
+
+    #[test]
+    fn narrow_row_drops_columns_before_the_title_gets_too_short() {
+        assert_eq!(
+            fit_row("refactor-the-authentication-layer", None, 24),
+            "  ○ refactor-the-authen…"
+        );
+    }
+
+    #[test]
+    fn waiting_reason_gets_the_space_left() {
+        assert_eq!(
+            fit_row("api", Some("Allow Bash(rm -rf build)?"), 40),
+            "  ○ api  Allow Bash(rm …    claude    3m"
+        );
+    }
+
+    #[test]
+    fn label_picker_previews_badge_and_clear_choice() {
+        let mut dialog = crate::ui::LabelPickerDialog {
+            session_id: "abc".to_string(),
+            label: crate::ui::TextInput::with_text("blocked"),
+            choice: 5,
+        };
+        let mut terminal = Terminal::new(TestBackend::new(100, 40)).unwrap();
+        terminal.draw(|f| {
+            super::super::render_label_picker_dialog(f, f.area(), &dialog, false);
+        }).unwrap();
+        let output = buffer_to_string(&terminal);
+        assert!(output.contains("Preview: [blocked]"), "Output:\n{output}");
+        assert!(output.contains("▸ ■ red"), "Output:\n{output}");
+
+        dialog.choice = crate::ui::LabelPickerDialog::CHOICES - 1;
+        terminal.draw(|f| {
+            super::super::render_label_picker_dialog(f, f.area(), &dialog, false);
+        }).unwrap();
+        let output = buffer_to_string(&terminal);
+        assert!(output.contains("Preview: (no label)"), "Output:\n{output}");
+        assert!(output.contains("▸ ✕ clear label"), "Output:\n{output}");
+    }
+
+    // ── Status bar waiting queue ─────────────────────────────────
+
+    #[test]
+    fn waiting_queue_entries_drop_titles_to_fit() {
+        use super::super::waiting_queue_entries;
+
+        let titles = ["api", "frontend-refactor-branch", "docs", "extra"];
+        assert_eq!(
+            waiting_queue_entries(&titles, 80),
+            ["1.api", "2.frontend-refa…", "3.docs"]
+        );
+        // "  1.api 2.frontend-refa…" is 24 columns.
+        assert_eq!(waiting_queue_entries(&titles, 24), ["1.api", "2.frontend-refa…"]);
+        assert_eq!(waiting_queue_entries(&titles, 23), ["1.api"]);
+        assert!(waiting_queue_entries(&titles, 6).is_empty());
+        assert!(waiting_queue_entries(&[], 80).is_empty());
+    }
+
+    #[test]
+    fn pty_warning_names_the_top_sessions() {
+        use super::super::pty_warning_line;
+
+        let line = pty_warning_line(230, 256, &[("api", 40), ("docs", 12)], false);
+        assert_eq!(line.to_string(), "  ⚠ PTY usage 230/256 — most held by: api (40), docs (12)");
+        let line = pty_warning_line(230, 256, &[], false);
+        assert_eq!(line.to_string(), "  ⚠ PTY usage 230/256");
+    }
+
+    // ── Perf overlay ─────────────────────────────────────────────
+
+    #[test]
+    fn perf_overlay_lists_phases_and_tmux_calls() {
+        use crate::ui::perf::{Phase, PerfReport, PhaseStats, Thresholds};
+        use std::time::Duration;
+
+        let thresholds = Thresholds::default();
+        let phases = Phase::ALL
+            .iter()
+            .map(|&phase| {
+                let timed = phase == Phase::Draw;
+                PhaseStats {
+                    phase,
+                    samples: if timed { 40 } else { 0 },
+                    p50: timed.then(|| Duration::from_millis(20)),
+                    p95: timed.then(|| Duration::from_millis(100)),
+                    max: timed.then(|| Duration::from_millis(130)),
+                    threshold: thresholds.for_phase(phase),
+                }
+            })
+            .collect();
+        let report = PerfReport {
+            phases,
+            calls: crate::tmux::calls::CallCounts {
+                list: 30,
+                capture: 12,
+                env: 24,
+                send: 1,
+                other: 2,
+            },
+        };
+
+        let backend = TestBackend::new(80, 20);
+        let mut terminal = Terminal::new(backend).unwrap();
+        terminal
+            .draw(|f| super::super::render_perf_overlay(f, f.area(), &report))
+            .unwrap();
+        let text = buffer_to_string(&terminal);
+
+        assert!(text.contains("Perf"), "{text}");
+        assert!(text.contains("draw       20ms  100ms   130ms    40"), "{text}");
+        assert!(text.contains("event         -      -       -     0"), "{text}");
+        assert!(text.contains("tmux calls, last minute: 69"), "{text}");
+        assert!(text.contains("list 30  capture 12  env 24  send 1  other 2"), "{text}");
+        assert!(report.phases.iter().find(|s| s.phase == Phase::Draw).unwrap().is_slow());
+    }
+
+    // ── Multi-select delete ──────────────────────────────────────
+
+    #[test]
+    fn delete_confirm_shows_count_for_marked_sessions() {
+        let batch: Vec<(String, String)> = ["api", "web", "docs", "infra"]
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (format!("id{i}"), t.to_string()))
+            .collect();
+        let dialog = crate::ui::DeleteConfirmDialog {
+            session_id: "id0".to_string(),
+            title: "api".to_string(),
+            batch,
+            kill_tmux: true,
+            worktree: None,
+            remove_worktree: false,
+            target: crate::ui::SelectionIdentity::Session {
+                id: "id0".to_string(),
+                group_path: String::new(),
+            },
+        };
+        assert_eq!(dialog.session_ids(), ["id0", "id1", "id2", "id3"]);
+
+        let mut terminal = Terminal::new(TestBackend::new(100, 40)).unwrap();
+        terminal.draw(|f| {
+            super::super::render_delete_confirm_dialog(f, f.area(), &dialog, false);
+        }).unwrap();
+        let output = buffer_to_string(&terminal);
+        assert!(output.contains("Delete 4 sessions?"), "Output:\n{output}");
+        assert!(output.contains("api, web, docs, infra"), "Output:\n{output}");
+        assert!(output.contains("Kill tmux session: YES"), "Output:\n{output}");
+    }
+
+    #[test]
+    fn delete_confirm_offers_worktree_removal_off_by_default() {
+        let mut dialog = crate::ui::DeleteConfirmDialog {
+            session_id: "id0".to_string(),
+            title: "api (fork)".to_string(),
+            batch: Vec::new(),
+            kill_tmux: true,
+            worktree: Some(std::path::PathBuf::from("/w/api-fork")),
+            remove_worktree: false,
+            target: crate::ui::SelectionIdentity::Session {
+                id: "id0".to_string(),
+                group_path: String::new(),
+            },
+        };
+        let draw = |dialog: &crate::ui::DeleteConfirmDialog| {
+            let mut terminal = Terminal::new(TestBackend::new(100, 40)).unwrap();
+            terminal.draw(|f| {
+                super::super::render_delete_confirm_dialog(f, f.area(), dialog, false);
+            }).unwrap();
+            buffer_to_string(&terminal)
+        };
+
+        let output = draw(&dialog);
+        assert!(output.contains("Remove worktree: NO"), "Output:\n{output}");
+        assert!(output.contains("/w/api-fork"), "Output:\n{output}");
+
+        dialog.remove_worktree = true;
+        let output = draw(&dialog);
+        assert!(output.contains("Remove worktree: YES"), "Output:\n{output}");
+    }
+
+    // ── Mouse targets ────────────────────────────────────────────
+
+    /// The text drawn in `rect` on the buffer's first row of it.
+    fn text_at(terminal: &Terminal<TestBackend>, rect: Rect) -> String {
+        let buf = terminal.backend().buffer();
+        (rect.x..rect.right())
+            .map(|x| buf[(x, rect.y)].symbol())
+            .collect::<String>()
+            .trim()
+            .to_string()
+    }
+
+    #[test]
+    fn delete_dialog_click_targets_sit_on_their_rows() {
+        use crate::ui::mouse::DialogTarget;
+        use crate::ui::DeleteGroupChoice;
+
+        let dialog = crate::ui::DeleteGroupDialog {
+            group_path: "work/api".to_string(),
+            session_count: 3,
+            choice: DeleteGroupChoice::DeleteGroupKeepSessions,
+            target: crate::ui::SelectionIdentity::Group {
+                path: "work/api".to_string(),
+            },
+        };
+        let mut terminal = Terminal::new(TestBackend::new(100, 40)).unwrap();
+        terminal.draw(|f| {
+            super::super::render_delete_group_dialog(f, f.area(), &dialog, false);
+        }).unwrap();
+        let targets =
+            super::super::delete_group_targets(Rect::new(0, 0, 100, 40), &dialog, false);
+        assert_eq!(targets.len(), 3);
+        let (rect, target) = targets[2];
+        assert_eq!(
+            target,
+            DialogTarget::GroupChoice(DeleteGroupChoice::DeleteGroupAndSessions)
+        );
+        assert_eq!(text_at(&terminal, rect), "3 Delete group + sessions");
+
+        let dialog = crate::ui::DeleteConfirmDialog {
+            session_id: "id0".to_string(),
+            title: "api".to_string(),
+            batch: Vec::new(),
+            kill_tmux: true,
+            worktree: None,
+            remove_worktree: false,
+            target: crate::ui::SelectionIdentity::Session {
+                id: "id0".to_string(),
+                group_path: String::new(),
+            },
+        };
+        let mut terminal = Terminal::new(TestBackend::new(100, 40)).unwrap();
+        terminal.draw(|f| {
+            super::super::render_delete_confirm_dialog(f, f.area(), &dialog, false);
+        }).unwrap();
+        let targets =
+            super::super::delete_confirm_targets(Rect::new(0, 0, 100, 40), &dialog, false);
+        let text = |want: DialogTarget| {
+            let (rect, _) = targets.iter().find(|(_, t)| *t == want).unwrap();
+            text_at(&terminal, *rect)
+        };
+        assert!(text(DialogTarget::ToggleKillTmux).starts_with("Kill tmux session: YES"));
+        assert_eq!(text(DialogTarget::Confirm), "[ y/Enter: confirm ]");
+        assert_eq!(text(DialogTarget::Cancel), "[ n/Esc/Ctrl+C: cancel ]");
+    }
 }
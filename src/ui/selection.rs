@@ -0,0 +1,215 @@
+//! Identity-based selection tracking for the session tree.
+//!
+//! The tree is rebuilt from storage on every refresh, so a raw row index is
+//! not a stable handle: a session added above the cursor, a collapsed group,
+//! or a re-sort shifts every row below it. Callers capture the identity of
+//! the selected row before a rebuild and relocate it afterwards.
+
+use super::TreeItem;
+
+/// Logical identity of a tree row, independent of its position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectionIdentity {
+    /// A session (or relationship workspace) row. `group_path` is the group
+    /// it lived in when captured, used to fall back onto the group header
+    /// when the session is hidden or removed.
+    Session { id: String, group_path: String },
+    Group { path: String },
+    /// The header of the unmanaged-agents section.
+    UnmanagedHeader,
+    /// An unmanaged agent row, by process id.
+    Unmanaged { pid: u32 },
+}
+
+impl SelectionIdentity {
+    /// Whether `item` is the same logical row as this identity.
+    pub fn matches(&self, item: &TreeItem) -> bool {
+        match (self, item) {
+            (
+                SelectionIdentity::Session { id, .. },
+                TreeItem::Session { id: other, .. } | TreeItem::Relationship { id: other, .. },
+            ) => id == other,
+            (SelectionIdentity::Group { path }, TreeItem::Group { path: other, .. }) => {
+                path == other
+            }
+            (SelectionIdentity::UnmanagedHeader, TreeItem::UnmanagedHeader { .. }) => true,
+            (SelectionIdentity::Unmanaged { pid }, TreeItem::Unmanaged { pid: other }) => {
+                pid == other
+            }
+            _ => false,
+        }
+    }
+
+    /// Group path whose header is the closest fallback for this identity.
+    fn fallback_group(&self) -> &str {
+        match self {
+            SelectionIdentity::Session { group_path, .. } => group_path,
+            SelectionIdentity::Group { path } => path
+                .rsplit_once('/')
+                .map(|(parent, _)| parent)
+                .unwrap_or(""),
+            SelectionIdentity::UnmanagedHeader | SelectionIdentity::Unmanaged { .. } => "",
+        }
+    }
+}
+
+/// Find the row in `tree` that best matches `identity`.
+///
+/// Resolution order: the exact item, then the header of its group (or the
+/// nearest visible ancestor group), then the row just before `old_index`.
+/// Returns 0 for an empty tree.
+pub fn relocate_selection(
+    identity: Option<&SelectionIdentity>,
+    old_index: usize,
+    tree: &[TreeItem],
+) -> usize {
+    if tree.is_empty() {
+        return 0;
+    }
+
+    if let Some(identity) = identity {
+        if let Some(pos) = tree.iter().position(|item| identity.matches(item)) {
+            return pos;
+        }
+
+        let mut group = identity.fallback_group();
+        while !group.is_empty() {
+            let header = tree
+                .iter()
+                .position(|item| matches!(item, TreeItem::Group { path, .. } if path == group));
+            if let Some(pos) = header {
+                return pos;
+            }
+            group = group.rsplit_once('/').map(|(parent, _)| parent).unwrap_or("");
+        }
+
+        // The item is gone: stay on the row that preceded it.
+        return old_index.min(tree.len()).saturating_sub(1);
+    }
+
+    old_index.min(tree.len() - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(path: &str, depth: usize) -> TreeItem {
+        TreeItem::Group {
+            path: path.to_string(),
+            name: path.rsplit('/').next().unwrap_or(path).to_string(),
+            depth,
+        }
+    }
+
+    fn session(id: &str, depth: usize) -> TreeItem {
+        TreeItem::Session {
+            id: id.to_string(),
+            depth,
+        }
+    }
+
+    fn session_identity(id: &str, group_path: &str) -> SelectionIdentity {
+        SelectionIdentity::Session {
+            id: id.to_string(),
+            group_path: group_path.to_string(),
+        }
+    }
+
+    #[test]
+    fn follows_item_when_rows_shift() {
+        let tree = vec![
+            session("new", 0),
+            group("work", 0),
+            session("a", 1),
+            session("b", 1),
+        ];
+        let id = session_identity("b", "work");
+        assert_eq!(relocate_selection(Some(&id), 2, &tree), 3);
+    }
+
+    #[test]
+    fn removed_item_falls_back_to_group_header() {
+        let tree = vec![session("x", 0), group("work", 0), session("a", 1)];
+        let id = session_identity("gone", "work");
+        assert_eq!(relocate_selection(Some(&id), 3, &tree), 1);
+    }
+
+    #[test]
+    fn removed_ungrouped_item_falls_back_to_prior_row() {
+        let tree = vec![session("x", 0), session("y", 0), session("z", 0)];
+        let id = session_identity("gone", "");
+        assert_eq!(relocate_selection(Some(&id), 2, &tree), 1);
+        assert_eq!(relocate_selection(Some(&id), 0, &tree), 0);
+        assert_eq!(relocate_selection(Some(&id), 9, &tree), 2);
+    }
+
+    #[test]
+    fn moved_item_is_found_in_new_group() {
+        let tree = vec![
+            group("a", 0),
+            session("s1", 1),
+            group("b", 0),
+            session("s2", 1),
+            session("moved", 1),
+        ];
+        let id = session_identity("moved", "a");
+        assert_eq!(relocate_selection(Some(&id), 2, &tree), 4);
+    }
+
+    #[test]
+    fn collapsed_group_selects_nearest_visible_ancestor() {
+        // "work/api" is hidden because "work" is collapsed.
+        let tree = vec![session("x", 0), group("work", 0), group("other", 0)];
+        let id = session_identity("s", "work/api");
+        assert_eq!(relocate_selection(Some(&id), 4, &tree), 1);
+
+        let hidden_group = SelectionIdentity::Group {
+            path: "work/api".to_string(),
+        };
+        assert_eq!(relocate_selection(Some(&hidden_group), 4, &tree), 1);
+    }
+
+    #[test]
+    fn emptied_tree_returns_zero() {
+        let id = session_identity("s", "work");
+        assert_eq!(relocate_selection(Some(&id), 5, &[]), 0);
+        assert_eq!(relocate_selection(None, 5, &[]), 0);
+    }
+
+    #[test]
+    fn without_identity_index_is_clamped() {
+        let tree = vec![session("a", 0), session("b", 0)];
+        assert_eq!(relocate_selection(None, 1, &tree), 1);
+        assert_eq!(relocate_selection(None, 7, &tree), 1);
+    }
+
+    #[test]
+    fn relationship_rows_match_session_identity() {
+        let tree = vec![TreeItem::Relationship {
+            id: "ws".to_string(),
+            rel_id: "r1".to_string(),
+            depth: 0,
+        }];
+        let id = session_identity("ws", "");
+        assert!(id.matches(&tree[0]));
+        assert_eq!(relocate_selection(Some(&id), 3, &tree), 0);
+    }
+
+    #[test]
+    fn unmanaged_agent_rows_follow_their_pid() {
+        let tree = vec![
+            session("a", 0),
+            session("new", 0),
+            TreeItem::UnmanagedHeader { count: 2 },
+            TreeItem::Unmanaged { pid: 41 },
+            TreeItem::Unmanaged { pid: 42 },
+        ];
+        let id = SelectionIdentity::Unmanaged { pid: 42 };
+        assert_eq!(relocate_selection(Some(&id), 3, &tree), 4);
+
+        // Adopted or dismissed: the cursor stays on the row above.
+        let gone = SelectionIdentity::Unmanaged { pid: 40 };
+        assert_eq!(relocate_selection(Some(&gone), 3, &tree), 2);
+    }
+}
@@ -0,0 +1,223 @@
+//! Tree sort order and when to re-apply it.
+//!
+//! Status and recency orders go stale as sessions change, but re-sorting
+//! moves rows, so it is held back while the user is mid-interaction.
+
+use std::cmp::Ordering;
+use std::time::Duration;
+
+use crate::config::TreeSort;
+use crate::session::{Instance, Status};
+
+/// Input recency under which rows must not move.
+pub const INPUT_QUIET: Duration = Duration::from_secs(1);
+
+/// Order two sessions within a group.
+pub fn compare_sessions(sort: TreeSort, a: &Instance, b: &Instance) -> Ordering {
+    let primary = match sort {
+        TreeSort::Manual => a.order.cmp(&b.order),
+        TreeSort::Title => Ordering::Equal,
+        TreeSort::Status => status_rank(a.status).cmp(&status_rank(b.status)),
+        TreeSort::Recency => b.last_accessed_at.cmp(&a.last_accessed_at),
+    };
+    primary.then_with(|| a.title.cmp(&b.title))
+}
+
+fn status_rank(status: Status) -> u8 {
+    match status {
+        Status::Waiting => 0,
+        Status::Running => 1,
+        Status::Starting => 2,
+        Status::Error => 3,
+        Status::Idle => 4,
+        Status::Stopped => 5,
+    }
+}
+
+/// Move session `id` one place up or down among the sessions of its group,
+/// in manual order. The group is renumbered from zero so orders stay
+/// distinct. Returns whether anything moved.
+pub fn move_within_group(sessions: &mut [Instance], id: &str, up: bool) -> bool {
+    let Some(group) = sessions
+        .iter()
+        .find(|s| s.id == id)
+        .map(|s| s.group_path.clone())
+    else {
+        return false;
+    };
+    let mut members: Vec<usize> = (0..sessions.len())
+        .filter(|&i| sessions[i].group_path == group)
+        .collect();
+    members.sort_by(|&a, &b| compare_sessions(TreeSort::Manual, &sessions[a], &sessions[b]));
+
+    let Some(pos) = members.iter().position(|&i| sessions[i].id == id) else {
+        return false;
+    };
+    let target = if up {
+        pos.checked_sub(1)
+    } else {
+        Some(pos + 1).filter(|&t| t < members.len())
+    };
+    let Some(target) = target else {
+        return false;
+    };
+    members.swap(pos, target);
+    for (order, &i) in members.iter().enumerate() {
+        sessions[i].order = order as i32;
+    }
+    true
+}
+
+/// Sort inputs that changed since the tree was last ordered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SortChanges {
+    pub status: bool,
+    pub last_accessed: bool,
+}
+
+impl SortChanges {
+    /// Whether these changes can affect the order under `sort`.
+    pub fn affects(self, sort: TreeSort) -> bool {
+        match sort {
+            TreeSort::Manual | TreeSort::Title => false,
+            TreeSort::Status => self.status,
+            TreeSort::Recency => self.last_accessed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ResortCheck {
+    pub sort: TreeSort,
+    pub changes: SortChanges,
+    /// Time since the user last moved the selection.
+    pub since_input: Duration,
+    /// Time since the tree was last re-sorted.
+    pub since_resort: Duration,
+    /// Minimum spacing between re-sorts.
+    pub min_interval: Duration,
+    pub dialog_open: bool,
+}
+
+/// Re-sort only when relevant inputs changed, the user is not mid-action,
+/// and the last re-sort was at least `min_interval` ago.
+pub fn should_resort(check: &ResortCheck) -> bool {
+    check.changes.affects(check.sort)
+        && !check.dialog_open
+        && check.since_input >= INPUT_QUIET
+        && check.since_resort >= check.min_interval
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn idle_check(sort: TreeSort, changes: SortChanges) -> ResortCheck {
+        ResortCheck {
+            sort,
+            changes,
+            since_input: Duration::from_secs(5),
+            since_resort: Duration::from_secs(5),
+            min_interval: Duration::from_secs(1),
+            dialog_open: false,
+        }
+    }
+
+    const STATUS: SortChanges = SortChanges { status: true, last_accessed: false };
+    const ACCESSED: SortChanges = SortChanges { status: false, last_accessed: true };
+
+    #[test]
+    fn only_changes_to_the_active_key_trigger() {
+        assert!(should_resort(&idle_check(TreeSort::Status, STATUS)));
+        assert!(!should_resort(&idle_check(TreeSort::Status, ACCESSED)));
+        assert!(should_resort(&idle_check(TreeSort::Recency, ACCESSED)));
+        assert!(!should_resort(&idle_check(TreeSort::Recency, STATUS)));
+        assert!(!should_resort(&idle_check(TreeSort::Title, STATUS)));
+        assert!(!should_resort(&idle_check(TreeSort::Status, SortChanges::default())));
+    }
+
+    #[test]
+    fn held_back_during_interaction() {
+        let mut check = idle_check(TreeSort::Status, STATUS);
+        check.dialog_open = true;
+        assert!(!should_resort(&check));
+
+        let mut check = idle_check(TreeSort::Status, STATUS);
+        check.since_input = Duration::from_millis(400);
+        assert!(!should_resort(&check));
+    }
+
+    #[test]
+    fn rate_limited_by_interval() {
+        let mut check = idle_check(TreeSort::Status, STATUS);
+        check.since_resort = Duration::from_millis(500);
+        assert!(!should_resort(&check));
+        check.since_resort = Duration::from_secs(1);
+        assert!(should_resort(&check));
+    }
+
+    #[test]
+    fn status_order_puts_waiting_first_then_title() {
+        let mk = |title: &str, status: Status| {
+            let mut inst = Instance::new(title.into(), PathBuf::from("/tmp"));
+            inst.status = status;
+            inst
+        };
+        let mut list = vec![
+            mk("b", Status::Idle),
+            mk("c", Status::Waiting),
+            mk("a", Status::Idle),
+            mk("d", Status::Running),
+        ];
+        list.sort_by(|a, b| compare_sessions(TreeSort::Status, a, b));
+        let titles: Vec<&str> = list.iter().map(|i| i.title.as_str()).collect();
+        assert_eq!(titles, vec!["c", "d", "a", "b"]);
+    }
+
+    #[test]
+    fn recency_order_puts_latest_first_and_never_accessed_last() {
+        let mut older = Instance::new("older".into(), PathBuf::from("/tmp"));
+        older.last_accessed_at = Some(chrono::Utc::now() - chrono::Duration::hours(1));
+        let mut newer = Instance::new("newer".into(), PathBuf::from("/tmp"));
+        newer.mark_accessed();
+        let never = Instance::new("never".into(), PathBuf::from("/tmp"));
+
+        let mut list = vec![never, older, newer];
+        list.sort_by(|a, b| compare_sessions(TreeSort::Recency, a, b));
+        let titles: Vec<&str> = list.iter().map(|i| i.title.as_str()).collect();
+        assert_eq!(titles, vec!["newer", "older", "never"]);
+    }
+
+    #[test]
+    fn moves_stay_within_the_group_and_renumber_it() {
+        let mk = |title: &str, group: &str| {
+            Instance::with_group(title.into(), PathBuf::from("/tmp"), group.into())
+        };
+        let mut list = vec![
+            mk("b", "work"),
+            mk("a", "work"),
+            mk("c", "work"),
+            mk("z", "misc"),
+        ];
+        let titles = |list: &[Instance]| {
+            let mut sorted: Vec<&Instance> =
+                list.iter().filter(|s| s.group_path == "work").collect();
+            sorted.sort_by(|a, b| compare_sessions(TreeSort::Manual, a, b));
+            sorted.iter().map(|s| s.title.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(titles(&list), vec!["a", "b", "c"]);
+
+        let c = list[2].id.clone();
+        assert!(move_within_group(&mut list, &c, true));
+        assert_eq!(titles(&list), vec!["a", "c", "b"]);
+        assert!(move_within_group(&mut list, &c, true));
+        assert_eq!(titles(&list), vec!["c", "a", "b"]);
+        assert!(!move_within_group(&mut list, &c, true));
+
+        let z = list[3].id.clone();
+        assert!(!move_within_group(&mut list, &z, false));
+        assert_eq!(list[3].order, 0);
+        assert!(!move_within_group(&mut list, "missing", false));
+    }
+}
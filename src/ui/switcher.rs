@@ -6,11 +6,11 @@ use std::time::{Duration, Instant};
 use crossterm::{
     event::{self, Event as CrosstermEvent, KeyCode, KeyModifiers},
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{enable_raw_mode, EnterAlternateScreen},
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Alignment, Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
@@ -18,192 +18,379 @@ use ratatui::{
 };
 
 use crate::error::Result;
-use crate::session::{GroupTree, Status, Storage};
-use crate::tmux::{PromptDetector, TmuxManager};
-
-struct TermGuard;
-
-impl Drop for TermGuard {
-    fn drop(&mut self) {
-        let _ = disable_raw_mode();
-        let mut stdout = io::stdout();
-        let _ = execute!(stdout, LeaveAlternateScreen, crossterm::cursor::Show);
-    }
-}
+use crate::session::{GroupTree, Instance, Status, Storage};
+use crate::session::status_engine::{plan_probe, probe_pane, ProbeCheck};
+use crate::tmux::TmuxManager;
+use crate::ui::query::{SearchQuery, SYNTAX_HINT_EN};
+use crate::ui::term::TermGuard;
+use crate::ui::{
+    label_fg, list_window, page_down, page_up, truncation_notice, ListWindow, NewSessionDialog,
+    NewSessionField,
+};
 
 /// Tree item for switcher display
 #[derive(Debug, Clone)]
 enum SwitcherItem {
-    Group { name: String, depth: usize },
+    Group {
+        path: String,
+        name: String,
+        depth: usize,
+        expanded: bool,
+    },
     Session { idx: usize, depth: usize },
 }
 
+/// Group-organized rows. Collapsed groups show their header only.
+fn build_tree(groups: &GroupTree, instances: &[crate::session::Instance]) -> Vec<SwitcherItem> {
+    use std::collections::BTreeMap;
+
+    let mut by_group: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    let mut ungrouped: Vec<usize> = Vec::new();
+
+    for (i, inst) in instances.iter().enumerate() {
+        if inst.group_path.is_empty() {
+            ungrouped.push(i);
+        } else {
+            by_group.entry(inst.group_path.clone()).or_default().push(i);
+        }
+    }
+
+    // Sort ungrouped by last_accessed_at desc
+    ungrouped.sort_by(|&a, &b| {
+        instances[b]
+            .last_accessed_at
+            .cmp(&instances[a].last_accessed_at)
+    });
+
+    let mut items: Vec<SwitcherItem> = Vec::new();
+
+    // Root groups first
+    let mut roots: Vec<String> = groups
+        .all_groups()
+        .into_iter()
+        .map(|g| g.path)
+        .filter(|p| !p.contains('/'))
+        .collect();
+    super::tree::sort_groups(groups, &mut roots);
+
+    fn visit(
+        items: &mut Vec<SwitcherItem>,
+        groups: &GroupTree,
+        instances: &[crate::session::Instance],
+        by_group: &BTreeMap<String, Vec<usize>>,
+        path: &str,
+        depth: usize,
+    ) {
+        let name = groups
+            .get_group(path)
+            .map(|g| g.name.clone())
+            .unwrap_or_else(|| path.split('/').next_back().unwrap_or(path).to_string());
+        let expanded = groups.is_expanded(path);
+
+        items.push(SwitcherItem::Group {
+            path: path.to_string(),
+            name,
+            depth,
+            expanded,
+        });
+        if !expanded {
+            return;
+        }
+
+        // Child groups
+        let mut children = groups.children(path);
+        super::tree::sort_groups(groups, &mut children);
+        for c in children {
+            visit(items, groups, instances, by_group, &c, depth + 1);
+        }
+
+        // Sessions in this group
+        if let Some(sessions) = by_group.get(path) {
+            let mut sorted = sessions.clone();
+            sorted.sort_by(|&a, &b| {
+                instances[b]
+                    .last_accessed_at
+                    .cmp(&instances[a].last_accessed_at)
+            });
+            for idx in sorted {
+                items.push(SwitcherItem::Session {
+                    idx,
+                    depth: depth + 1,
+                });
+            }
+        }
+    }
+
+    for r in roots {
+        visit(&mut items, groups, instances, &by_group, &r, 0);
+    }
+
+    // Ungrouped sessions at bottom
+    for idx in ungrouped {
+        items.push(SwitcherItem::Session { idx, depth: 0 });
+    }
+
+    items
+}
+
+/// Row of the group header at `path`, if shown.
+fn group_row(items: &[SwitcherItem], path: &str) -> Option<usize> {
+    items
+        .iter()
+        .position(|item| matches!(item, SwitcherItem::Group { path: p, .. } if p == path))
+}
+
+/// Ctrl+N: the popup asks for a path, a title and a tool in turn, then
+/// creates the session and switches to it. The group is the one under the
+/// cursor when the form opened.
+struct CreateForm {
+    dialog: NewSessionDialog,
+    error: Option<String>,
+}
+
+enum FormAction {
+    Edit,
+    Cancel,
+    Submit,
+}
+
+impl CreateForm {
+    fn new(default_path: std::path::PathBuf, group: String) -> Self {
+        Self {
+            dialog: NewSessionDialog::new(default_path, group, Vec::new()),
+            error: None,
+        }
+    }
+
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> FormAction {
+        let d = &mut self.dialog;
+        match (d.field, code) {
+            (_, KeyCode::Esc) => return FormAction::Cancel,
+            (_, KeyCode::Char('c')) if modifiers.contains(KeyModifiers::CONTROL) => {
+                return FormAction::Cancel
+            }
+            (NewSessionField::Path, KeyCode::Enter) if d.path_suggestions_visible => {
+                d.apply_selected_path_suggestion();
+            }
+            (NewSessionField::Path, KeyCode::Tab) => {
+                if d.path_suggestions_visible {
+                    d.apply_selected_path_suggestion();
+                } else {
+                    d.complete_path_or_cycle(false);
+                }
+            }
+            (NewSessionField::Path, KeyCode::Up | KeyCode::BackTab)
+                if d.path_suggestions_visible =>
+            {
+                d.complete_path_or_cycle(true);
+            }
+            (NewSessionField::Path, KeyCode::Down) if d.path_suggestions_visible => {
+                d.complete_path_or_cycle(false);
+            }
+            (NewSessionField::Path, KeyCode::Enter) => d.field = NewSessionField::Title,
+            (NewSessionField::Title, KeyCode::Enter) => d.field = NewSessionField::Tool,
+            (NewSessionField::Tool, KeyCode::Enter) => return FormAction::Submit,
+            (NewSessionField::Title | NewSessionField::Tool, KeyCode::BackTab) => {
+                d.field = d.field.prev();
+            }
+            (NewSessionField::Tool, KeyCode::Left | KeyCode::Up | KeyCode::Char('k')) => {
+                d.cycle_tool(true);
+            }
+            (
+                NewSessionField::Tool,
+                KeyCode::Right | KeyCode::Down | KeyCode::Tab | KeyCode::Char(' ' | 'j'),
+            ) => d.cycle_tool(false),
+            (field @ (NewSessionField::Path | NewSessionField::Title), KeyCode::Backspace) => {
+                let input = if field == NewSessionField::Path { &mut d.path } else { &mut d.title };
+                input.backspace();
+                d.clear_path_suggestions();
+            }
+            (field @ (NewSessionField::Path | NewSessionField::Title), KeyCode::Char(ch))
+                if !modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                let input = if field == NewSessionField::Path { &mut d.path } else { &mut d.title };
+                input.insert(ch);
+                d.clear_path_suggestions();
+            }
+            _ => {}
+        }
+        self.error = None;
+        FormAction::Edit
+    }
+}
+
+/// Save the session the form describes, then start its tmux session. Saved
+/// first so a tmux session never runs without an entry; a failed start
+/// takes the entry back out.
+async fn create_session(
+    storage: &Storage,
+    manager: &Arc<TmuxManager>,
+    dialog: &NewSessionDialog,
+) -> Result<Instance> {
+    let mut instance = dialog.build_instance()?;
+    instance.mark_accessed();
+    let (mut instances, mut groups, relationships) = storage.load().await?;
+    if !instance.group_path.is_empty() {
+        groups.create_group(instance.group_path.clone());
+    }
+    instances.push(instance.clone());
+    storage.save(&instances, &groups, &relationships).await?;
+
+    instance.init_tmux(manager.clone());
+    if let Err(e) = instance.start().await {
+        // Reloaded: the dashboard may have saved since.
+        let (mut instances, groups, relationships) = storage.load().await?;
+        instances.retain(|inst| inst.id != instance.id);
+        storage.save(&instances, &groups, &relationships).await?;
+        return Err(e);
+    }
+    Ok(instance)
+}
+
+/// Stamp the session being switched to so both session lists rank it by
+/// recency. Reloads first: the dashboard may have saved since we started.
+async fn mark_switched(storage: &Storage, id: &str) -> Result<()> {
+    let (mut instances, groups, relationships) = storage.load().await?;
+    let Some(inst) = instances.iter_mut().find(|inst| inst.id == id) else {
+        return Ok(());
+    };
+    inst.mark_accessed();
+    storage.save(&instances, &groups, &relationships).await
+}
+
+/// Search score added to forks of the session the switcher was opened
+/// over: about one more matched character.
+const ACTIVE_FORK_BOOST: i32 = 10;
+
 pub async fn run_switcher(profile: &str) -> Result<()> {
     let storage = Storage::new(profile).await?;
-    let (instances, groups, _) = storage.load().await?;
+    let (mut instances, mut groups, relationships) = storage.load().await?;
 
     let manager = Arc::new(TmuxManager::new(profile));
+    let config = crate::config::ConfigFile::load().await.ok().flatten();
+    let result_cap = config.as_ref().map(|c| c.search_result_cap()).unwrap_or(50);
+    let label_min_width = config.as_ref().map(|c| c.label_min_width()).unwrap_or(80);
+    if let Some(config) = &config {
+        crate::ui::theme::set_theme(config.theme(profile));
+    }
+    let timing = config
+        .as_ref()
+        .map(|c| c.status_timing())
+        .unwrap_or_default();
+    let mut desktop_notifier = config
+        .as_ref()
+        .filter(|c| c.notification().desktop)
+        .map(|c| crate::notification::desktop::DesktopNotifier::new(c.notification()));
     let mut analytics = crate::analytics::ActivityTracker::new(profile).await;
 
     enable_raw_mode()?;
+    let _guard = TermGuard::new();
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
-    let _guard = TermGuard;
     terminal.clear()?;
 
     let mut query = String::new();
     let mut tree_items: Vec<SwitcherItem>;
     let mut flat_matches: Vec<usize>;
     let mut selected: usize = 0;
-    let mut list_state = ListState::default();
+    let mut offset: usize = 0;
+    let mut create: Option<CreateForm> = None;
 
     let mut tick_count: u64 = 0;
     let mut last_cache_refresh = Instant::now();
 
     // Status probing state
     let mut status_by_id: HashMap<String, Status> = HashMap::new();
+    let mut waiting_reasons: HashMap<String, String> = HashMap::new();
     let mut last_tmux_activity: HashMap<String, i64> = HashMap::new();
     let mut last_tmux_activity_change: HashMap<String, Instant> = HashMap::new();
     let mut last_status_probe: HashMap<String, Instant> = HashMap::new();
 
-    // Build tree view (group-organized)
-    let build_tree =
-        |groups: &GroupTree, instances: &[crate::session::Instance]| -> Vec<SwitcherItem> {
-            use std::collections::BTreeMap;
-
-            let mut by_group: BTreeMap<String, Vec<usize>> = BTreeMap::new();
-            let mut ungrouped: Vec<usize> = Vec::new();
-
-            for (i, inst) in instances.iter().enumerate() {
-                if inst.group_path.is_empty() {
-                    ungrouped.push(i);
-                } else {
-                    by_group.entry(inst.group_path.clone()).or_default().push(i);
-                }
-            }
-
-            // Sort ungrouped by last_accessed_at desc
-            ungrouped.sort_by(|&a, &b| {
-                instances[b]
-                    .last_accessed_at
-                    .cmp(&instances[a].last_accessed_at)
-            });
-
-            let mut items: Vec<SwitcherItem> = Vec::new();
-
-            // Root groups first
-            let mut roots: Vec<String> = groups
-                .all_groups()
-                .into_iter()
-                .map(|g| g.path)
-                .filter(|p| !p.contains('/'))
-                .collect();
-            roots.sort();
-
-            fn visit(
-                items: &mut Vec<SwitcherItem>,
-                groups: &GroupTree,
-                instances: &[crate::session::Instance],
-                by_group: &BTreeMap<String, Vec<usize>>,
-                path: &str,
-                depth: usize,
-            ) {
-                let name = groups
-                    .get_group(path)
-                    .map(|g| g.name.clone())
-                    .unwrap_or_else(|| path.split('/').last().unwrap_or(path).to_string());
-
-                items.push(SwitcherItem::Group { name, depth });
-
-                // Child groups
-                let mut children = groups.children(path);
-                children.sort();
-                for c in children {
-                    visit(items, groups, instances, by_group, &c, depth + 1);
-                }
-
-                // Sessions in this group
-                if let Some(sessions) = by_group.get(path) {
-                    let mut sorted = sessions.clone();
-                    sorted.sort_by(|&a, &b| {
-                        instances[b]
-                            .last_accessed_at
-                            .cmp(&instances[a].last_accessed_at)
-                    });
-                    for idx in sorted {
-                        items.push(SwitcherItem::Session {
-                            idx,
-                            depth: depth + 1,
-                        });
-                    }
-                }
-            }
-
-            for r in roots {
-                visit(&mut items, groups, instances, &by_group, &r, 0);
-            }
-
-            // Ungrouped sessions at bottom
-            for idx in ungrouped {
-                items.push(SwitcherItem::Session { idx, depth: 0 });
-            }
-
-            items
-        };
+    // Forks of the session the switcher was opened over get a small boost
+    // in search results.
+    let active_id = manager.current_session().await.and_then(|name| {
+        instances
+            .iter()
+            .find(|inst| inst.tmux_name() == name)
+            .map(|inst| inst.id.clone())
+    });
 
     // Build flat matches (fuzzy search)
+    // Pinned sessions come first in both orders.
     let build_flat = |query: &str, instances: &[crate::session::Instance]| -> Vec<usize> {
         let q = query.trim();
         if q.is_empty() {
             let mut all: Vec<usize> = (0..instances.len()).collect();
             all.sort_by(|&a, &b| {
-                instances[b]
-                    .last_accessed_at
-                    .cmp(&instances[a].last_accessed_at)
+                instances[b].pinned.cmp(&instances[a].pinned).then(
+                    instances[b]
+                        .last_accessed_at
+                        .cmp(&instances[a].last_accessed_at),
+                )
             });
-            return all.into_iter().take(50).collect();
+            return all;
         }
 
+        let query = SearchQuery::parse(q);
         let mut scored: Vec<(i32, usize)> = Vec::new();
         for (idx, inst) in instances.iter().enumerate() {
-            let hay = format!(
-                "{} {} {} {}",
-                inst.title,
-                inst.group_path,
-                inst.project_path.to_string_lossy(),
-                inst.id
-            );
-            if let Some(score) = fuzzy_score(q, &hay) {
-                scored.push((score, idx));
+            if let Some(score) = query.score(inst, &inst.id) {
+                let fork_of_active = active_id.is_some()
+                    && inst.parent_session_id.as_deref() == active_id.as_deref();
+                let boost = if fork_of_active { ACTIVE_FORK_BOOST } else { 0 };
+                scored.push((score + boost, idx));
             }
         }
 
-        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
-        scored.into_iter().map(|(_, idx)| idx).take(50).collect()
+        scored.sort_by(|a, b| {
+            let pinned = |i: usize| instances[i].pinned;
+            pinned(b.1)
+                .cmp(&pinned(a.1))
+                .then(b.0.cmp(&a.0))
+                .then(a.1.cmp(&b.1))
+        });
+        scored.into_iter().map(|(_, idx)| idx).collect()
     };
 
     // Initial build
     tree_items = build_tree(&groups, &instances);
     flat_matches = build_flat(&query, &instances);
-    list_state.select(Some(0));
 
     let tick_rate = Duration::from_millis(250);
     let result = loop {
         tick_count = tick_count.wrapping_add(1);
 
         // Keep tmux cache fresh.
-        if last_cache_refresh.elapsed() >= Duration::from_secs(1) {
+        if last_cache_refresh.elapsed() >= timing.cache_refresh {
             let _ = manager.refresh_cache().await;
             last_cache_refresh = Instant::now();
         }
 
-        // Probe statuses for visible sessions
+        // Determine display mode and the visible window. The list area is
+        // the terminal minus the title and footer boxes and its own border.
+        let is_tree_mode = query.trim().is_empty();
+        let item_count = if is_tree_mode {
+            tree_items.len()
+        } else {
+            flat_matches.len()
+        };
+        let viewport = (terminal.size()?.height as usize)
+            .saturating_sub(9)
+            .max(1)
+            .min(result_cap);
+        let window = list_window(item_count, selected, viewport, offset);
+        if item_count > 0 {
+            selected = window.start + window.selected.unwrap_or(0);
+        }
+        offset = window.start;
+
+        // Probe statuses for the sessions on screen; collapsed groups
+        // contribute none.
         let now = Instant::now();
-        let visible_sessions: Vec<usize> = if query.trim().is_empty() {
-            // Tree mode - collect session indices from tree items
-            tree_items
+        let visible_sessions: Vec<usize> = if is_tree_mode {
+            tree_items[window.range()]
                 .iter()
                 .filter_map(|item| {
                     if let SwitcherItem::Session { idx, .. } = item {
@@ -212,11 +399,9 @@ pub async fn run_switcher(profile: &str) -> Result<()> {
                         None
                     }
                 })
-                .take(20)
                 .collect()
         } else {
-            // Flat mode
-            flat_matches.iter().copied().take(20).collect()
+            flat_matches[window.range()].to_vec()
         };
 
         for idx in visible_sessions {
@@ -225,18 +410,23 @@ pub async fn run_switcher(profile: &str) -> Result<()> {
             let tmux_session = inst.tmux_name();
 
             if !manager.session_exists(&tmux_session).unwrap_or(false) {
-                status_by_id.insert(inst.id.clone(), Status::Idle);
+                let shown = status_by_id.get(id).copied().unwrap_or(Status::Idle);
+                status_by_id.insert(inst.id.clone(), shown.after_exit());
+                waiting_reasons.remove(id);
                 last_tmux_activity.remove(id);
                 last_tmux_activity_change.remove(id);
                 last_status_probe.remove(id);
+                instances[idx].set_status(shown.after_exit(), chrono::Utc::now());
                 continue;
             }
 
             let activity = manager.session_activity(&tmux_session).unwrap_or(0);
             let prev_activity = last_tmux_activity.get(id).copied();
 
-            // Track activity changes (but don't infer Running from it)
             let activity_changed = prev_activity.is_some_and(|a| activity > a);
+            let since_change = last_tmux_activity_change
+                .get(id)
+                .map(|t| now.duration_since(*t));
             if activity_changed || prev_activity.is_none() {
                 last_tmux_activity.insert(inst.id.clone(), activity);
                 if activity_changed {
@@ -244,51 +434,51 @@ pub async fn run_switcher(profile: &str) -> Result<()> {
                 }
             }
 
-            // Decide whether to probe
-            let settled = last_tmux_activity_change
-                .get(id)
-                .is_some_and(|t| now.duration_since(*t) >= Duration::from_secs(2));
-            let need_probe = last_status_probe
-                .get(id)
-                .is_none_or(|t| now.duration_since(*t) >= Duration::from_secs(2));
-
-            let should_probe =
-                (settled && need_probe) || activity_changed || prev_activity.is_none();
+            let shown = status_by_id.get(id).copied().unwrap_or(Status::Idle);
+            let plan = plan_probe(
+                &ProbeCheck {
+                    prev: shown,
+                    activity_changed,
+                    since_change,
+                    since_probe: last_status_probe.get(id).map(|t| now.duration_since(*t)),
+                },
+                &timing,
+            );
 
-            if !should_probe {
+            if !plan.probe {
+                status_by_id.insert(inst.id.clone(), plan.interim);
+                if plan.interim != Status::Waiting {
+                    waiting_reasons.remove(id);
+                }
+                instances[idx].set_status(plan.interim, chrono::Utc::now());
                 continue;
             }
 
-            let content = manager
-                .capture_pane(&tmux_session, 15)
-                .await
-                .unwrap_or_default();
-            let detector = PromptDetector::new(inst.tool);
-            let new_status = if detector.has_prompt(&content) {
-                Status::Waiting
-            } else if detector.is_busy(&content) {
-                Status::Running
-            } else {
-                Status::Idle
-            };
+            let target = inst.tmux_target();
+            let probe = probe_pane(&manager, &target, inst.tool, shown, timing.capture_lines).await;
+            waiting_reasons.remove(id);
+            if let Some(reason) = probe.waiting_reason {
+                waiting_reasons.insert(inst.id.clone(), reason);
+            }
+            let new_status = probe.status;
 
-            status_by_id.insert(inst.id.clone(), new_status);
+            let prev_status = status_by_id.insert(inst.id.clone(), new_status);
             last_status_probe.insert(inst.id.clone(), now);
-        }
 
-        // Determine display mode and item count
-        let is_tree_mode = query.trim().is_empty();
-        let item_count = if is_tree_mode {
-            tree_items.len()
-        } else {
-            flat_matches.len()
-        };
-
-        // Clamp selection
-        if selected >= item_count && item_count > 0 {
-            selected = item_count - 1;
+            if prev_status == Some(Status::Running) && new_status == Status::Waiting {
+                if let Some(notifier) = desktop_notifier.as_mut() {
+                    let attached = manager
+                        .get_environment_global(crate::tmux::env::LAST_SESSION)
+                        .await
+                        .ok()
+                        .flatten();
+                    notifier.notify_waiting(inst, attached.as_deref());
+                }
+            }
+            // Kept on the instance for the row timers, and saved with the
+            // next fold.
+            instances[idx].set_status(new_status, chrono::Utc::now());
         }
-        list_state.select(if item_count > 0 { Some(selected) } else { None });
 
         terminal.draw(|f| {
             draw_switcher(
@@ -297,19 +487,70 @@ pub async fn run_switcher(profile: &str) -> Result<()> {
                 &query,
                 &tree_items,
                 &flat_matches,
-                &mut list_state,
+                window,
                 &status_by_id,
+                &waiting_reasons,
                 tick_count,
+                label_min_width,
+                create.as_ref(),
             )
         })?;
 
+        // Enter, Space and Left/Right fold a group row instead.
+        let selected_group = match tree_items.get(selected) {
+            Some(SwitcherItem::Group { path, expanded, .. }) if is_tree_mode => {
+                Some((path.clone(), *expanded))
+            }
+            _ => None,
+        };
+
         if event::poll(tick_rate)? {
             match event::read()? {
+                CrosstermEvent::Key(key) if create.is_some() => {
+                    let Some(form) = create.as_mut() else { continue };
+                    match form.handle_key(key.code, key.modifiers) {
+                        FormAction::Edit => {}
+                        FormAction::Cancel => create = None,
+                        FormAction::Submit => {
+                            match create_session(&storage, &manager, &form.dialog).await {
+                                Ok(inst) => {
+                                    let tmux_name = inst.tmux_name();
+                                    let _ = analytics.record_switch(&inst.id, &inst.title).await;
+                                    let _ = manager
+                                        .set_environment_global(
+                                            crate::tmux::env::LAST_SESSION,
+                                            &tmux_name,
+                                        )
+                                        .await;
+                                    manager.switch_client(&tmux_name).await?;
+                                    break Ok(());
+                                }
+                                Err(e) => form.error = Some(e.to_string()),
+                            }
+                        }
+                    }
+                }
                 CrosstermEvent::Key(key) => match key.code {
                     KeyCode::Esc => break Ok(()),
                     KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                         break Ok(())
                     }
+                    KeyCode::Enter | KeyCode::Left | KeyCode::Right | KeyCode::Char(' ')
+                        if selected_group.is_some() =>
+                    {
+                        let (path, expanded) = selected_group.unwrap_or_default();
+                        let next = match key.code {
+                            KeyCode::Left => false,
+                            KeyCode::Right => true,
+                            _ => !expanded,
+                        };
+                        if next != expanded {
+                            groups.set_expanded(&path, next);
+                            storage.save(&instances, &groups, &relationships).await?;
+                            tree_items = build_tree(&groups, &instances);
+                            selected = group_row(&tree_items, &path).unwrap_or(0);
+                        }
+                    }
                     KeyCode::Enter => {
                         // Find selected session
                         let session_idx = if is_tree_mode {
@@ -330,10 +571,15 @@ pub async fn run_switcher(profile: &str) -> Result<()> {
 
                             // Record analytics: switcher usage
                             let _ = analytics.record_switch(&inst.id, &inst.title).await;
+                            let _ = mark_switched(&storage, &inst.id).await;
 
                             let _ = manager
-                                .set_environment_global("AGENTHAND_LAST_SESSION", &tmux_name)
+                                .set_environment_global(crate::tmux::env::LAST_SESSION, &tmux_name)
                                 .await;
+                            crate::session::launch::send_post_attach(&manager, inst).await;
+                            if inst.agent_window().is_some() {
+                                let _ = manager.select_window(&inst.tmux_target()).await;
+                            }
                             manager.switch_client(&tmux_name).await?;
                         }
                         break Ok(());
@@ -345,6 +591,7 @@ pub async fn run_switcher(profile: &str) -> Result<()> {
                         }
                         flat_matches = build_flat(&query, &instances);
                         selected = 0;
+                        offset = 0;
                     }
                     KeyCode::Up | KeyCode::Char('k') => {
                         if item_count > 0 {
@@ -360,11 +607,33 @@ pub async fn run_switcher(profile: &str) -> Result<()> {
                             selected = (selected + 1) % item_count;
                         }
                     }
+                    KeyCode::PageDown => {
+                        selected = page_down(selected, item_count, viewport);
+                    }
+                    KeyCode::PageUp => {
+                        selected = page_up(selected, viewport);
+                    }
+                    KeyCode::Char('n') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        // New sessions go in the group under the cursor.
+                        let group = match tree_items.get(selected) {
+                            Some(SwitcherItem::Group { path, .. }) if is_tree_mode => path.clone(),
+                            Some(SwitcherItem::Session { idx, .. }) if is_tree_mode => {
+                                instances[*idx].group_path.clone()
+                            }
+                            _ => flat_matches
+                                .get(selected)
+                                .filter(|_| !is_tree_mode)
+                                .map(|&idx| instances[idx].group_path.clone())
+                                .unwrap_or_default(),
+                        };
+                        create = Some(CreateForm::new(std::env::current_dir()?, group));
+                    }
                     KeyCode::Char(ch) => {
                         if !key.modifiers.contains(KeyModifiers::CONTROL) {
                             query.push(ch);
                             flat_matches = build_flat(&query, &instances);
                             selected = 0;
+                            offset = 0;
                         }
                     }
                     _ => {}
@@ -387,63 +656,111 @@ fn waiting_anim(tick: u64) -> &'static str {
     FRAMES[(tick as usize) % FRAMES.len()]
 }
 
+fn status_icon(status: Status, tick: u64) -> &'static str {
+    match status {
+        Status::Waiting => waiting_anim(tick),
+        Status::Running => running_anim(tick),
+        Status::Idle => "○",
+        Status::Error => "✕",
+        Status::Starting => "⋯",
+        Status::Stopped => "■",
+    }
+}
+
+/// Append how long the session has been waiting or running, once a probe
+/// has confirmed the status shown.
+fn push_status_timer(spans: &mut Vec<Span<'_>>, inst: &Instance, shown: Status, color: Color) {
+    if inst.status != shown {
+        return;
+    }
+    if let Some(timer) = crate::ui::text::status_timer(inst, chrono::Utc::now()) {
+        spans.push(Span::styled(timer, Style::default().fg(color)));
+        spans.push(Span::raw(" "));
+    }
+}
+
+/// Append what a waiting session asks, when the last probe found it.
+fn push_waiting_reason(
+    spans: &mut Vec<Span<'_>>,
+    waiting_reasons: &HashMap<String, String>,
+    id: &str,
+    status: Status,
+    style: Style,
+) {
+    if let Some(reason) = waiting_reasons.get(id).filter(|_| status == Status::Waiting) {
+        spans.push(Span::raw("  "));
+        spans.push(Span::styled(format!("— {reason}"), style));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn draw_switcher(
     f: &mut Frame,
     instances: &[crate::session::Instance],
     query: &str,
     tree_items: &[SwitcherItem],
     flat_matches: &[usize],
-    list_state: &mut ListState,
+    window: ListWindow,
     status_by_id: &HashMap<String, Status>,
+    waiting_reasons: &HashMap<String, String>,
     tick: u64,
+    label_min_width: u16,
+    create: Option<&CreateForm>,
 ) {
+    let t = crate::ui::theme::theme();
     let area = f.area();
+    let show_labels = area.width >= label_min_width;
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),
             Constraint::Min(0),
-            Constraint::Length(3),
+            Constraint::Length(4),
         ])
         .split(area);
 
-    let title = Paragraph::new("Switch Session")
-        .style(
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        )
+    let title = Paragraph::new(if create.is_some() { "New Session" } else { "Switch Session" })
+        .style(Style::default().fg(t.accent).add_modifier(Modifier::BOLD))
         .alignment(Alignment::Center)
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(title, chunks[0]);
 
+    if let Some(form) = create {
+        draw_create_form(f, chunks[1], chunks[2], form);
+        return;
+    }
+
     let list_area = chunks[1];
     f.render_widget(Clear, list_area);
 
     let is_tree_mode = query.trim().is_empty();
-    let selected = list_state.selected().unwrap_or(0);
+    // Rows below are absolute indices; only the window is built and drawn.
+    let selected = window.start + window.selected.unwrap_or(0);
 
     let mut items: Vec<ListItem> = Vec::new();
 
     if is_tree_mode {
         // Tree view mode
-        for (row, item) in tree_items.iter().enumerate() {
+        for (row, item) in tree_items.iter().enumerate().take(window.end).skip(window.start) {
             match item {
-                SwitcherItem::Group { name, depth } => {
+                SwitcherItem::Group {
+                    name,
+                    depth,
+                    expanded,
+                    ..
+                } => {
                     let indent = "  ".repeat(*depth);
                     let style = if row == selected {
                         Style::default()
-                            .fg(Color::Black)
-                            .bg(Color::Magenta)
+                            .fg(t.selection_fg)
+                            .bg(t.group)
                             .add_modifier(Modifier::BOLD)
                     } else {
-                        Style::default()
-                            .fg(Color::Magenta)
-                            .add_modifier(Modifier::BOLD)
+                        Style::default().fg(t.group).add_modifier(Modifier::BOLD)
                     };
                     let line = Line::from(vec![
                         Span::raw(indent),
-                        Span::styled("▸ ", style),
+                        Span::styled(if *expanded { "▾ " } else { "▸ " }, style),
                         Span::styled(name.clone(), style),
                     ]);
                     items.push(ListItem::new(line));
@@ -453,49 +770,49 @@ fn draw_switcher(
                     let indent = "  ".repeat(*depth);
 
                     let status = status_by_id.get(&inst.id).copied().unwrap_or(Status::Idle);
-                    let (icon, color) = match status {
-                        Status::Waiting => (waiting_anim(tick), Color::Blue),
-                        Status::Running => (running_anim(tick), Color::Yellow),
-                        Status::Idle => ("○", Color::DarkGray),
-                        Status::Error => ("✕", Color::Red),
-                        Status::Starting => ("⋯", Color::Cyan),
-                    };
+                    let icon = status_icon(status, tick);
+                    let color = t.status_color(status);
 
                     let is_selected = row == selected;
                     let icon_style = if is_selected {
-                        Style::default().fg(color).bg(Color::Cyan)
+                        Style::default().fg(color).bg(t.selection_bg)
                     } else {
                         Style::default().fg(color)
                     };
                     let text_style = if is_selected {
-                        Style::default()
-                            .fg(Color::Black)
-                            .bg(Color::Cyan)
-                            .add_modifier(Modifier::BOLD)
+                        t.selection_style().add_modifier(Modifier::BOLD)
                     } else {
                         Style::default()
                     };
                     let path_style = if is_selected {
-                        Style::default().fg(Color::Black).bg(Color::Cyan)
+                        t.selection_style()
                     } else {
-                        Style::default().fg(Color::DarkGray)
+                        t.dim_style()
                     };
 
-                    let line = Line::from(vec![
+                    let mut spans = vec![
                         Span::raw(indent),
                         Span::styled(icon, icon_style),
                         Span::raw(" "),
+                    ];
+                    push_status_timer(&mut spans, inst, status, color);
+                    if let Some(badge) = inst.label_badge().filter(|_| show_labels) {
+                        spans.push(Span::styled(badge, Style::default().fg(label_fg(inst.label_color))));
+                        spans.push(Span::raw(" "));
+                    }
+                    spans.extend([
                         Span::styled(inst.title.clone(), text_style),
                         Span::raw("  "),
                         Span::styled(inst.project_path.to_string_lossy().to_string(), path_style),
                     ]);
-                    items.push(ListItem::new(line));
+                    push_waiting_reason(&mut spans, waiting_reasons, &inst.id, status, path_style);
+                    items.push(ListItem::new(Line::from(spans)));
                 }
             }
         }
     } else {
         // Flat fuzzy search mode
-        for (row, &idx) in flat_matches.iter().enumerate() {
+        for (row, &idx) in flat_matches.iter().enumerate().take(window.end).skip(window.start) {
             let inst = &instances[idx];
 
             let rank_style = if row == 0 {
@@ -503,14 +820,11 @@ fn draw_switcher(
                     .fg(Color::Green)
                     .add_modifier(Modifier::BOLD)
             } else {
-                Style::default().fg(Color::Yellow)
+                Style::default().fg(t.highlight)
             };
 
             let style = if row == selected {
-                Style::default()
-                    .fg(Color::Black)
-                    .bg(Color::Cyan)
-                    .add_modifier(Modifier::BOLD)
+                t.selection_style().add_modifier(Modifier::BOLD)
             } else {
                 rank_style
             };
@@ -522,105 +836,261 @@ fn draw_switcher(
             };
 
             let status = status_by_id.get(&inst.id).copied().unwrap_or(Status::Idle);
-            let (icon, color) = match status {
-                Status::Waiting => (waiting_anim(tick), Color::Blue),
-                Status::Running => (running_anim(tick), Color::Yellow),
-                Status::Idle => ("○", Color::DarkGray),
-                Status::Error => ("✕", Color::Red),
-                Status::Starting => ("⋯", Color::Cyan),
-            };
+            let icon = status_icon(status, tick);
+            let color = t.status_color(status);
             let icon_style = if row == selected {
-                Style::default().fg(color).bg(Color::Cyan)
+                Style::default().fg(color).bg(t.selection_bg)
             } else {
                 Style::default().fg(color)
             };
+            let path_style = if row == selected {
+                t.selection_style()
+            } else {
+                t.dim_style()
+            };
 
-            let line = Line::from(vec![
-                Span::styled(icon, icon_style),
-                Span::raw(" "),
+            let mut spans = vec![Span::styled(icon, icon_style), Span::raw(" ")];
+            push_status_timer(&mut spans, inst, status, color);
+            if inst.pinned {
+                spans.push(Span::styled("★ ", Style::default().fg(t.highlight)));
+            }
+            if let Some(badge) = inst.label_badge().filter(|_| show_labels) {
+                spans.push(Span::styled(badge, Style::default().fg(label_fg(inst.label_color))));
+                spans.push(Span::raw(" "));
+            }
+            spans.extend([
                 Span::styled(inst.title.clone(), style),
                 Span::raw("  "),
-                Span::styled(format!("[{group}]"), Style::default().fg(Color::Magenta)),
+                Span::styled(format!("[{group}]"), Style::default().fg(t.group)),
                 Span::raw("  "),
-                Span::styled(
-                    inst.project_path.to_string_lossy().to_string(),
-                    if row == selected {
-                        Style::default().fg(Color::Black).bg(Color::Cyan)
-                    } else {
-                        Style::default().fg(Color::DarkGray)
-                    },
-                ),
+                Span::styled(inst.project_path.to_string_lossy().to_string(), path_style),
             ]);
+            push_waiting_reason(&mut spans, waiting_reasons, &inst.id, status, path_style);
 
-            items.push(ListItem::new(line));
+            items.push(ListItem::new(Line::from(spans)));
         }
     }
 
     if items.is_empty() {
         items.push(ListItem::new(Span::styled(
             "(no sessions)",
-            Style::default().fg(Color::DarkGray),
+            t.dim_style(),
         )));
     }
 
-    let title_str = if is_tree_mode {
+    let total = if is_tree_mode {
+        tree_items.len()
+    } else {
+        flat_matches.len()
+    };
+    let mut title_str = if is_tree_mode {
         "Sessions (type to search)".to_string()
     } else {
-        format!("Search: {query}")
+        let filters = SearchQuery::parse(query).filter_labels(false);
+        if filters.is_empty() {
+            format!("Search: {query}")
+        } else {
+            format!("Search: {query} — filters: {}", filters.join("  "))
+        }
     };
+    if window.is_truncated(total) {
+        title_str.push_str(" — ");
+        title_str.push_str(&truncation_notice(window, total, false));
+    }
 
+    let mut list_state = ListState::default();
+    list_state.select(window.selected);
     let list = List::new(items)
         .block(Block::default().borders(Borders::ALL).title(title_str))
         .highlight_symbol("");
-    f.render_stateful_widget(list, list_area, list_state);
-
-    let footer = Paragraph::new(Line::from(vec![
-        Span::styled("Type", Style::default().fg(Color::Cyan)),
-        Span::raw(": filter  "),
-        Span::styled("↑/↓", Style::default().fg(Color::Cyan)),
-        Span::raw(": select  "),
-        Span::styled("Enter", Style::default().fg(Color::Cyan)),
-        Span::raw(": switch  "),
-        Span::styled("Esc", Style::default().fg(Color::Cyan)),
-        Span::raw(": close"),
-    ]))
+    f.render_stateful_widget(list, list_area, &mut list_state);
+
+    let footer = Paragraph::new(vec![
+        Line::from(vec![
+            Span::styled("Type", Style::default().fg(t.accent)),
+            Span::raw(": filter  "),
+            Span::styled("↑/↓", Style::default().fg(t.accent)),
+            Span::raw(": select  "),
+            Span::styled("PgUp/PgDn", Style::default().fg(t.accent)),
+            Span::raw(": page  "),
+            Span::styled("Enter", Style::default().fg(t.accent)),
+            Span::raw(": switch  "),
+            Span::styled("←/→", Style::default().fg(t.accent)),
+            Span::raw(": fold group  "),
+            Span::styled("Ctrl+N", Style::default().fg(t.accent)),
+            Span::raw(": new  "),
+            Span::styled("Esc", Style::default().fg(t.accent)),
+            Span::raw(": close"),
+        ]),
+        Line::from(Span::styled(SYNTAX_HINT_EN, t.dim_style())),
+    ])
     .wrap(Wrap { trim: true })
     .alignment(Alignment::Center)
     .block(Block::default().borders(Borders::ALL));
     f.render_widget(footer, chunks[2]);
 }
 
-fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
-    if query.is_empty() {
-        return Some(0);
+fn draw_create_form(f: &mut Frame, area: Rect, footer_area: Rect, form: &CreateForm) {
+    let t = crate::ui::theme::theme();
+    let d = &form.dialog;
+    let label = |text: &'static str, field: NewSessionField| {
+        let style = if d.field == field {
+            Style::default().fg(t.accent).add_modifier(Modifier::BOLD)
+        } else {
+            t.dim_style()
+        };
+        Span::styled(text, style)
+    };
+
+    let mut path = vec![label("Path:  ", NewSessionField::Path)];
+    path.extend(crate::ui::render::render_text_input(
+        &d.path,
+        d.field == NewSessionField::Path,
+        Style::default(),
+    ));
+    let mut lines = vec![Line::from(path)];
+    if d.path_suggestions_visible {
+        // Keep the title and tool rows on screen below the list.
+        let room = (area.height as usize).saturating_sub(8).max(1);
+        let skip = d.path_suggestions_idx.saturating_sub(room - 1);
+        for (i, suggestion) in d.path_suggestions.iter().enumerate().skip(skip).take(room) {
+            let style = if i == d.path_suggestions_idx {
+                t.selection_style()
+            } else {
+                t.dim_style()
+            };
+            lines.push(Line::from(Span::styled(format!("       {suggestion}"), style)));
+        }
     }
 
-    let q = query.to_lowercase();
-    let t = text.to_lowercase();
+    let mut title = vec![label("Title: ", NewSessionField::Title)];
+    title.extend(crate::ui::render::render_text_input(
+        &d.title,
+        d.field == NewSessionField::Title,
+        Style::default(),
+    ));
+    lines.push(Line::from(title));
 
-    let mut score: i32 = 0;
-    let mut last_match: Option<usize> = None;
-    let mut pos = 0usize;
+    let tool_style = if d.field == NewSessionField::Tool {
+        t.selection_style()
+    } else {
+        Style::default()
+    };
+    lines.push(Line::from(vec![
+        label("Tool:  ", NewSessionField::Tool),
+        Span::styled(format!("◂ {} ▸", d.tool.name()), tool_style),
+    ]));
+
+    let group = d.group_path.text();
+    lines.push(Line::from(Span::styled(
+        format!("Group: {}", if group.is_empty() { "(none)" } else { group }),
+        t.dim_style(),
+    )));
+    if d.title.text().trim().is_empty() {
+        lines.push(Line::from(Span::styled(
+            "An empty title uses the directory name.",
+            t.dim_style(),
+        )));
+    }
+    if let Some(error) = &form.error {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(error.clone(), Style::default().fg(t.error))));
+    }
 
-    for ch in q.chars() {
-        if let Some(found) = t[pos..].find(ch) {
-            let idx = pos + found;
-            score += 10;
-            if let Some(prev) = last_match {
-                if idx == prev + 1 {
-                    score += 15;
-                } else {
-                    score -= (idx.saturating_sub(prev) as i32).min(10);
-                }
-            } else {
-                score -= idx.min(15) as i32;
-            }
-            last_match = Some(idx);
-            pos = idx + ch.len_utf8();
-        } else {
-            return None;
-        }
+    f.render_widget(Clear, area);
+    let body = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(body, area);
+
+    let hint = match d.field {
+        NewSessionField::Path => "Tab: complete  ↑/↓: pick  Enter: next  Esc: back",
+        NewSessionField::Title => "Enter: next  Shift+Tab: back  Esc: back",
+        _ => "←/→: tool  Enter: create and switch  Shift+Tab: back  Esc: back",
+    };
+    let footer = Paragraph::new(Line::from(Span::styled(hint, Style::default().fg(t.accent))))
+        .wrap(Wrap { trim: true })
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+    f.render_widget(footer, footer_area);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::Instance;
+    use std::path::PathBuf;
+
+    fn rows(items: &[SwitcherItem], instances: &[Instance]) -> Vec<String> {
+        items
+            .iter()
+            .map(|item| match item {
+                SwitcherItem::Group { name, .. } => format!("[{name}]"),
+                SwitcherItem::Session { idx, .. } => instances[*idx].title.clone(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn collapsed_groups_hide_subgroups_and_sessions() {
+        let instances = vec![
+            Instance::with_group("api".into(), PathBuf::from("/w/api"), "work".into()),
+            Instance::with_group("web".into(), PathBuf::from("/w/web"), "work/front".into()),
+            Instance::with_group("notes".into(), PathBuf::from("/notes"), String::new()),
+        ];
+        let mut groups = GroupTree::new();
+        groups.create_group("work/front".into());
+
+        let items = build_tree(&groups, &instances);
+        assert_eq!(
+            rows(&items, &instances),
+            ["[work]", "[front]", "web", "api", "notes"]
+        );
+
+        groups.set_expanded("work", false);
+        let items = build_tree(&groups, &instances);
+        assert_eq!(rows(&items, &instances), ["[work]", "notes"]);
+        assert_eq!(group_row(&items, "work"), Some(0));
+        assert_eq!(group_row(&items, "work/front"), None);
+    }
+
+    #[test]
+    fn groups_follow_their_stored_order() {
+        let order = [("b", 0), ("a", 1), ("a/y", 0), ("a/x", 1)];
+        let groups = GroupTree::from_groups(
+            order
+                .iter()
+                .map(|&(path, order)| crate::session::GroupData {
+                    order,
+                    ..crate::session::GroupData::new(path.into())
+                })
+                .collect(),
+        );
+        let items = build_tree(&groups, &[]);
+        assert_eq!(rows(&items, &[]), ["[b]", "[a]", "[y]", "[x]"]);
     }
 
-    Some(score)
+    #[test]
+    fn create_form_asks_path_title_then_tool() {
+        let mut form = CreateForm::new(PathBuf::from("/tmp/api"), "work".into());
+        let none = KeyModifiers::NONE;
+        assert!(matches!(form.handle_key(KeyCode::Enter, none), FormAction::Edit));
+        assert_eq!(form.dialog.field, NewSessionField::Title);
+
+        for ch in "web".chars() {
+            form.handle_key(KeyCode::Char(ch), none);
+        }
+        form.handle_key(KeyCode::Enter, none);
+        assert_eq!(form.dialog.title.text(), "web");
+        assert_eq!(form.dialog.field, NewSessionField::Tool);
+
+        // Typing on the tool row picks tools instead of editing the title.
+        form.handle_key(KeyCode::Char('j'), none);
+        form.handle_key(KeyCode::Backspace, none);
+        assert_eq!(form.dialog.tool, crate::ui::dialogs::NewSessionTool::Claude);
+        assert_eq!(form.dialog.title.text(), "web");
+
+        assert!(matches!(form.handle_key(KeyCode::Enter, none), FormAction::Submit));
+        assert!(matches!(form.handle_key(KeyCode::Esc, none), FormAction::Cancel));
+    }
 }
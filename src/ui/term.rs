@@ -0,0 +1,82 @@
+//! Putting the terminal back the way the shell expects it, however the TUI
+//! exits: raw mode off, main screen, no mouse reporting, cursor shown.
+
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crossterm::{
+    cursor::Show,
+    event::DisableMouseCapture,
+    execute,
+    terminal::{disable_raw_mode, LeaveAlternateScreen},
+};
+
+/// Whether a [`TermGuard`] is alive, i.e. the terminal is in raw mode.
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// A bell rung while the TUI owns the terminal, waiting for the draw loop.
+static BELL: AtomicBool = AtomicBool::new(false);
+
+/// Whether the TUI owns the terminal, so only its draw loop may write to it.
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::SeqCst)
+}
+
+/// Ask the draw loop to ring the bell after its next frame.
+pub fn queue_bell() {
+    BELL.store(true, Ordering::SeqCst);
+}
+
+/// Whether a bell was queued since the last call.
+pub fn take_bell() -> bool {
+    BELL.swap(false, Ordering::SeqCst)
+}
+
+/// Restore the terminal. Safe to call more than once.
+pub fn restore() {
+    let _ = disable_raw_mode();
+    let mut stdout = io::stdout();
+    let _ = execute!(stdout, LeaveAlternateScreen, DisableMouseCapture, Show);
+}
+
+/// Restores the terminal when dropped, including while unwinding from a
+/// panic. Create it right after entering raw mode.
+pub struct TermGuard(());
+
+impl TermGuard {
+    pub fn new() -> Self {
+        ACTIVE.store(true, Ordering::SeqCst);
+        Self(())
+    }
+}
+
+impl Default for TermGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TermGuard {
+    fn drop(&mut self) {
+        restore();
+        ACTIVE.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Restore the terminal before a panic message is printed, so it is not
+/// lost on the alternate screen. Unwinding runs the guard only after the
+/// message.
+///
+/// Only panics on the main thread, where the TUI's event loop and drawing
+/// run, count: a spawned task panicking leaves the TUI running, and
+/// restoring the terminal under it would wreck the screen.
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let on_main = std::thread::current().name() == Some("main");
+        if on_main && ACTIVE.load(Ordering::SeqCst) {
+            restore();
+        }
+        previous(info);
+    }));
+}
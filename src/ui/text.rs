@@ -0,0 +1,116 @@
+//! Fitting text into terminal columns, shared by the TUI and the CLI tables.
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+const ELLIPSIS: char = '…';
+
+/// Columns `s` takes on screen (CJK and most emoji take two).
+pub fn width(s: &str) -> usize {
+    UnicodeWidthStr::width(s)
+}
+
+/// `s` cut to at most `max_width` columns, ending in `…` when anything was
+/// cut. Never splits a character.
+pub fn truncate(s: &str, max_width: usize) -> String {
+    if width(s) <= max_width {
+        return s.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+    let budget = max_width - 1;
+    let mut used = 0;
+    let mut end = 0;
+    for (i, ch) in s.char_indices() {
+        let w = ch.width().unwrap_or(0);
+        if used + w > budget {
+            break;
+        }
+        used += w;
+        end = i + ch.len_utf8();
+    }
+    format!("{}{ELLIPSIS}", &s[..end])
+}
+
+/// `s` truncated or padded with spaces to exactly `columns` wide, for
+/// lining up tables that hold CJK text (`{:<n}` pads by chars, not columns).
+pub fn fit(s: &str, columns: usize) -> String {
+    let mut out = truncate(s, columns);
+    let pad = columns.saturating_sub(width(&out));
+    out.push_str(&" ".repeat(pad));
+    out
+}
+
+/// A short age: `45s`, `3m`, `2h`, `5d`.
+pub fn format_age(d: chrono::Duration) -> String {
+    let secs = d.num_seconds().max(0);
+    match secs {
+        0..=59 => format!("{secs}s"),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+/// How long `inst` has been waiting or running, shown next to its status
+/// icon: `⏸ 12m`, `▶ 45s`. None in any other status or when not known.
+pub fn status_timer(
+    inst: &crate::session::Instance,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Option<String> {
+    use crate::session::Status;
+
+    let (symbol, elapsed) = match inst.status {
+        Status::Waiting => ("⏸", inst.waiting_duration(now)?),
+        Status::Running => ("▶", inst.running_duration(now)?),
+        _ => return None,
+    };
+    Some(format!("{symbol} {}", format_age(elapsed)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_counts_columns_not_bytes() {
+        assert_eq!(truncate("api-server", 20), "api-server");
+        assert_eq!(truncate("api-server", 6), "api-s…");
+        assert_eq!(truncate("数据库迁移任务", 8), "数据库…");
+        assert_eq!(truncate("数据库迁移任务", 7), "数据库…");
+        assert_eq!(truncate("🚀 deploy", 4), "🚀 …");
+        assert_eq!(truncate("abc", 0), "");
+        assert!(width(&truncate("ééééé", 3)) <= 3);
+    }
+
+    #[test]
+    fn fit_pads_to_columns() {
+        assert_eq!(fit("api", 5), "api  ");
+        assert_eq!(fit("数据", 5), "数据 ");
+        assert_eq!(fit("数据库迁移", 5), "数据…");
+        assert_eq!(width(&fit("数据库迁移", 5)), 5);
+    }
+
+    #[test]
+    fn status_timer_covers_waiting_and_running() {
+        use crate::session::{Instance, Status};
+
+        let now = chrono::Utc::now();
+        let mut inst = Instance::new("api".into(), "/tmp".into());
+        inst.set_status(Status::Waiting, now - chrono::Duration::minutes(12));
+        assert_eq!(status_timer(&inst, now).as_deref(), Some("⏸ 12m"));
+        inst.set_status(Status::Running, now - chrono::Duration::seconds(45));
+        assert_eq!(status_timer(&inst, now).as_deref(), Some("▶ 45s"));
+        inst.set_status(Status::Idle, now);
+        assert_eq!(status_timer(&inst, now), None);
+    }
+
+    #[test]
+    fn ages_use_the_largest_whole_unit() {
+        assert_eq!(format_age(chrono::Duration::seconds(-5)), "0s");
+        assert_eq!(format_age(chrono::Duration::seconds(59)), "59s");
+        assert_eq!(format_age(chrono::Duration::seconds(180)), "3m");
+        assert_eq!(format_age(chrono::Duration::hours(2)), "2h");
+        assert_eq!(format_age(chrono::Duration::days(3)), "3d");
+    }
+}
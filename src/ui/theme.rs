@@ -0,0 +1,292 @@
+//! Colors of the TUI and the switcher.
+//!
+//! A theme starts from a preset ("default" for dark terminals, "light")
+//! and takes per-slot overrides from the `[theme]` config table, then from
+//! `[theme.profiles.<profile>]`. Renderers read the active theme with
+//! [`theme()`]; it is set once at startup.
+
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+use ratatui::style::{Color, Style};
+
+use crate::config::ThemeConfig;
+use crate::session::Status;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Background of the selected row.
+    pub selection_bg: Color,
+    /// Text of the selected row.
+    pub selection_fg: Color,
+    /// Group names and icons.
+    pub group: Color,
+    pub waiting: Color,
+    pub running: Color,
+    /// Running with a pane that has not changed for a while.
+    pub stalled: Color,
+    pub idle: Color,
+    pub error: Color,
+    /// Titles, focused borders and key hints.
+    pub accent: Color,
+    /// Secondary text: paths, counts, hints.
+    pub dim: Color,
+    /// Key names in the help screen and other highlighted text.
+    pub highlight: Color,
+}
+
+impl Theme {
+    /// For dark terminals.
+    pub const DEFAULT: Theme = Theme {
+        selection_bg: Color::Cyan,
+        selection_fg: Color::Black,
+        group: Color::Magenta,
+        waiting: Color::Blue,
+        running: Color::Yellow,
+        stalled: Color::Indexed(208),
+        idle: Color::DarkGray,
+        error: Color::Red,
+        accent: Color::Cyan,
+        dim: Color::DarkGray,
+        highlight: Color::Yellow,
+    };
+
+    /// For light terminals, where yellow and cyan text wash out.
+    pub const LIGHT: Theme = Theme {
+        selection_bg: Color::Blue,
+        selection_fg: Color::White,
+        group: Color::Magenta,
+        waiting: Color::Blue,
+        running: Color::Rgb(175, 95, 0),
+        stalled: Color::Rgb(215, 95, 0),
+        idle: Color::Rgb(110, 110, 110),
+        error: Color::Red,
+        accent: Color::Rgb(0, 95, 135),
+        dim: Color::Rgb(110, 110, 110),
+        highlight: Color::Rgb(175, 95, 0),
+    };
+
+    pub const PRESETS: [&'static str; 2] = ["default", "light"];
+
+    pub fn preset(name: &str) -> Option<Theme> {
+        match name.to_ascii_lowercase().as_str() {
+            "default" | "dark" => Some(Self::DEFAULT),
+            "light" => Some(Self::LIGHT),
+            _ => None,
+        }
+    }
+
+    /// The theme for `profile`. Unknown presets, slots and colors are
+    /// logged and skipped.
+    pub fn from_config(cfg: &ThemeConfig, profile: &str) -> Theme {
+        let profile_table = cfg.profiles.get(profile);
+        let preset = profile_table
+            .and_then(|t| t.preset.as_deref())
+            .or(cfg.preset.as_deref());
+        let mut theme = match preset {
+            Some(name) => Self::preset(name).unwrap_or_else(|| {
+                tracing::warn!(
+                    "unknown theme preset '{name}' (expected one of: {})",
+                    Self::PRESETS.join(", ")
+                );
+                Self::DEFAULT
+            }),
+            None => Self::DEFAULT,
+        };
+        theme.apply(&cfg.colors);
+        if let Some(table) = profile_table {
+            theme.apply(&table.colors);
+        }
+        theme
+    }
+
+    fn apply(&mut self, colors: &BTreeMap<String, String>) {
+        for (slot, value) in colors {
+            let Some(target) = self.slot_mut(slot) else {
+                tracing::warn!("unknown theme slot '{slot}'");
+                continue;
+            };
+            match parse_color(value) {
+                Some(color) => *target = color,
+                None => {
+                    tracing::warn!("theme.{slot}: unknown color '{value}', keeping the preset's")
+                }
+            }
+        }
+    }
+
+    fn slot_mut(&mut self, slot: &str) -> Option<&mut Color> {
+        Some(match slot {
+            "selection_bg" => &mut self.selection_bg,
+            "selection_fg" => &mut self.selection_fg,
+            "group" => &mut self.group,
+            "waiting" => &mut self.waiting,
+            "running" => &mut self.running,
+            "stalled" => &mut self.stalled,
+            "idle" => &mut self.idle,
+            "error" => &mut self.error,
+            "accent" => &mut self.accent,
+            "dim" => &mut self.dim,
+            "highlight" => &mut self.highlight,
+            _ => return None,
+        })
+    }
+
+    /// Style of the selected row.
+    pub fn selection_style(&self) -> Style {
+        Style::default().fg(self.selection_fg).bg(self.selection_bg)
+    }
+
+    pub fn dialog_border_style(&self) -> Style {
+        Style::default().fg(self.accent)
+    }
+
+    pub fn dim_style(&self) -> Style {
+        Style::default().fg(self.dim)
+    }
+
+    /// Color of a session's status icon.
+    pub fn status_color(&self, status: Status) -> Color {
+        match status {
+            Status::Waiting => self.waiting,
+            Status::Running => self.running,
+            Status::Idle => self.idle,
+            Status::Error => self.error,
+            Status::Starting => self.accent,
+            Status::Stopped => self.dim,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+static THEME: RwLock<Theme> = RwLock::new(Theme::DEFAULT);
+
+/// The active theme.
+pub fn theme() -> Theme {
+    *THEME.read().unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn set_theme(theme: Theme) {
+    *THEME.write().unwrap_or_else(|e| e.into_inner()) = theme;
+}
+
+/// Parse a ratatui color name ("cyan", "dark_gray", "lightred"), a hex
+/// value ("#0087af" or "#08a") or a 256-color index ("244").
+pub fn parse_color(s: &str) -> Option<Color> {
+    let s = s.trim();
+    if let Some(hex) = s.strip_prefix('#') {
+        return parse_hex(hex);
+    }
+    if let Ok(index) = s.parse::<u8>() {
+        return Some(Color::Indexed(index));
+    }
+    let name: String = s
+        .chars()
+        .filter(|c| !matches!(c, '_' | '-' | ' '))
+        .collect::<String>()
+        .to_ascii_lowercase();
+    Some(match name.as_str() {
+        "reset" => Color::Reset,
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "gray" | "grey" => Color::Gray,
+        "darkgray" | "darkgrey" => Color::DarkGray,
+        "lightred" => Color::LightRed,
+        "lightgreen" => Color::LightGreen,
+        "lightyellow" => Color::LightYellow,
+        "lightblue" => Color::LightBlue,
+        "lightmagenta" => Color::LightMagenta,
+        "lightcyan" => Color::LightCyan,
+        "white" => Color::White,
+        _ => return None,
+    })
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+    if !hex.is_ascii() {
+        return None;
+    }
+    let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+    match hex.len() {
+        6 => Some(Color::Rgb(
+            channel(&hex[0..2])?,
+            channel(&hex[2..4])?,
+            channel(&hex[4..6])?,
+        )),
+        // "#08a" is "#0088aa".
+        3 => Some(Color::Rgb(
+            channel(&hex[0..1])? * 17,
+            channel(&hex[1..2])? * 17,
+            channel(&hex[2..3])? * 17,
+        )),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_names_hex_and_indexes() {
+        assert_eq!(parse_color("cyan"), Some(Color::Cyan));
+        assert_eq!(parse_color("Dark_Gray"), Some(Color::DarkGray));
+        assert_eq!(parse_color("light-red"), Some(Color::LightRed));
+        assert_eq!(parse_color("#0087af"), Some(Color::Rgb(0, 135, 175)));
+        assert_eq!(parse_color("#08a"), Some(Color::Rgb(0, 136, 170)));
+        assert_eq!(parse_color("244"), Some(Color::Indexed(244)));
+        assert_eq!(parse_color("#12345"), None);
+        assert_eq!(parse_color("#ééé"), None);
+        assert_eq!(parse_color("chartreuse"), None);
+    }
+
+    #[test]
+    fn config_picks_preset_then_overrides_then_profile() {
+        let cfg: crate::config::ConfigFile = toml::from_str(
+            r##"
+            [theme]
+            preset = "light"
+            accent = "#ff0000"
+            running = "not-a-color"
+            sparkle = "red"
+
+            [theme.profiles.work]
+            preset = "default"
+            dim = "white"
+            "##,
+        )
+        .unwrap();
+
+        let t = Theme::from_config(&cfg.theme, "default");
+        assert_eq!(t.selection_bg, Theme::LIGHT.selection_bg);
+        assert_eq!(t.accent, Color::Rgb(255, 0, 0));
+        // Bad colors keep the preset's.
+        assert_eq!(t.running, Theme::LIGHT.running);
+
+        let t = Theme::from_config(&cfg.theme, "work");
+        assert_eq!(t.selection_bg, Theme::DEFAULT.selection_bg);
+        assert_eq!(t.accent, Color::Rgb(255, 0, 0));
+        assert_eq!(t.dim, Color::White);
+    }
+
+    #[test]
+    fn missing_table_and_unknown_preset_use_the_default() {
+        let cfg = ThemeConfig::default();
+        assert_eq!(Theme::from_config(&cfg, "default"), Theme::DEFAULT);
+        let cfg = ThemeConfig {
+            preset: Some("solarized".into()),
+            ..Default::default()
+        };
+        assert_eq!(Theme::from_config(&cfg, "default"), Theme::DEFAULT);
+    }
+}
@@ -0,0 +1,656 @@
+//! The group/session hierarchy, independent of how it is shown.
+//!
+//! [`build`] orders sessions and groups exactly as the session list does;
+//! [`nest_forks`] then moves forks under the session they were forked
+//! from. The TUI flattens the result into rows with [`flatten`], and
+//! `agent-hand tree` renders it for export.
+
+use std::collections::HashMap;
+
+use crate::config::TreeSort;
+use crate::session::{GroupTree, Instance, Status};
+
+use super::{compare_sessions, TreeItem};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TreeNode {
+    Group {
+        path: String,
+        name: String,
+        expanded: bool,
+        /// Subgroups first (by order, then path), then sessions in sort order.
+        children: Vec<TreeNode>,
+    },
+    /// Index into the session slice the tree was built from.
+    Session(usize),
+    /// A session with the sessions forked from it; see [`nest_forks`].
+    Forked {
+        session: usize,
+        expanded: bool,
+        forks: Vec<TreeNode>,
+    },
+}
+
+/// Which part of the hierarchy to build.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TreeScope<'a> {
+    /// Only this group and what is below it.
+    pub group: Option<&'a str>,
+}
+
+/// Build the tree: ungrouped sessions, then root groups. Sessions
+/// whose group is not in `groups` are left out, as in the session list.
+pub fn build(
+    sessions: &[Instance],
+    groups: &GroupTree,
+    sort: TreeSort,
+    scope: TreeScope<'_>,
+) -> Vec<TreeNode> {
+    let by_session_order = |mut indices: Vec<usize>| {
+        indices.sort_by(|&a, &b| compare_sessions(sort, &sessions[a], &sessions[b]));
+        indices.into_iter().map(TreeNode::Session).collect::<Vec<_>>()
+    };
+
+    if let Some(path) = scope.group {
+        if groups.get_group(path).is_none() {
+            return Vec::new();
+        }
+        return vec![group_node(sessions, groups, path, &by_session_order)];
+    }
+
+    let ungrouped = (0..sessions.len())
+        .filter(|&i| sessions[i].group_path.is_empty())
+        .collect();
+    let mut nodes = by_session_order(ungrouped);
+
+    let mut roots: Vec<String> = groups
+        .all_groups()
+        .into_iter()
+        .map(|g| g.path)
+        .filter(|p| !p.contains('/'))
+        .collect();
+    sort_groups(groups, &mut roots);
+    nodes.extend(
+        roots
+            .iter()
+            .map(|r| group_node(sessions, groups, r, &by_session_order)),
+    );
+    nodes
+}
+
+fn group_node(
+    sessions: &[Instance],
+    groups: &GroupTree,
+    path: &str,
+    by_session_order: &dyn Fn(Vec<usize>) -> Vec<TreeNode>,
+) -> TreeNode {
+    let name = groups
+        .get_group(path)
+        .map(|g| g.name.clone())
+        .unwrap_or_else(|| path.split('/').next_back().unwrap_or(path).to_string());
+
+    let mut subgroups = groups.children(path);
+    sort_groups(groups, &mut subgroups);
+    let mut children: Vec<TreeNode> = subgroups
+        .iter()
+        .map(|c| group_node(sessions, groups, c, by_session_order))
+        .collect();
+    let members = (0..sessions.len())
+        .filter(|&i| sessions[i].group_path == path)
+        .collect();
+    children.extend(by_session_order(members));
+
+    TreeNode::Group {
+        path: path.to_string(),
+        name,
+        expanded: groups.is_expanded(path),
+        children,
+    }
+}
+
+/// Pinned sessions in slot order (`1`-`9` attach to them), sorted like the
+/// session list.
+pub fn pinned(sessions: &[Instance], sort: TreeSort) -> Vec<usize> {
+    let mut pinned: Vec<usize> = (0..sessions.len()).filter(|&i| sessions[i].pinned).collect();
+    pinned.sort_by(|&a, &b| compare_sessions(sort, &sessions[a], &sessions[b]));
+    pinned
+}
+
+/// `nodes` with the `pinned` sessions taken out of their groups and put
+/// first, in the given order.
+pub fn pin_to_top(nodes: Vec<TreeNode>, pinned: &[usize]) -> Vec<TreeNode> {
+    fn strip(nodes: Vec<TreeNode>, pinned: &[usize]) -> Vec<TreeNode> {
+        nodes
+            .into_iter()
+            .filter_map(|node| match node {
+                TreeNode::Session(i) if pinned.contains(&i) => None,
+                TreeNode::Group {
+                    path,
+                    name,
+                    expanded,
+                    children,
+                } => Some(TreeNode::Group {
+                    path,
+                    name,
+                    expanded,
+                    children: strip(children, pinned),
+                }),
+                session => Some(session),
+            })
+            .collect()
+    }
+
+    if pinned.is_empty() {
+        return nodes;
+    }
+    pinned
+        .iter()
+        .map(|&i| TreeNode::Session(i))
+        .chain(strip(nodes, pinned))
+        .collect()
+}
+
+/// `nodes` with each fork moved under its parent session, when the parent
+/// is a sibling in the same group. Forks keep their sort order below the
+/// parent. A fork whose parent is elsewhere or gone stays where it was, as
+/// do sessions whose parent links form a cycle.
+pub fn nest_forks(nodes: Vec<TreeNode>, sessions: &[Instance]) -> Vec<TreeNode> {
+    let index: Vec<Option<usize>> = nodes
+        .iter()
+        .map(|node| match node {
+            TreeNode::Session(i) => Some(*i),
+            _ => None,
+        })
+        .collect();
+    let by_id: HashMap<&str, usize> = index
+        .iter()
+        .flatten()
+        .map(|&i| (sessions[i].id.as_str(), i))
+        .collect();
+    let parent_of = |i: usize| -> Option<usize> {
+        let session = &sessions[i];
+        let parent = *by_id.get(session.parent_session_id.as_deref()?)?;
+        (parent != i && sessions[parent].group_path == session.group_path).then_some(parent)
+    };
+    // Each session has at most one parent, so a walk up either ends or
+    // loops; `i` is on a cycle if it comes back to `i`.
+    let on_cycle = |i: usize| {
+        let mut at = i;
+        for _ in 0..by_id.len() {
+            match parent_of(at) {
+                Some(parent) if parent == i => return true,
+                Some(parent) => at = parent,
+                None => return false,
+            }
+        }
+        false
+    };
+
+    let mut forks: HashMap<usize, Vec<usize>> = HashMap::new();
+    let mut nested: Vec<bool> = vec![false; nodes.len()];
+    for (pos, i) in index.iter().enumerate() {
+        let Some(i) = *i else { continue };
+        if let Some(parent) = parent_of(i).filter(|_| !on_cycle(i)) {
+            forks.entry(parent).or_default().push(i);
+            nested[pos] = true;
+        }
+    }
+
+    fn with_forks(i: usize, forks: &HashMap<usize, Vec<usize>>, sessions: &[Instance]) -> TreeNode {
+        match forks.get(&i) {
+            Some(children) => TreeNode::Forked {
+                session: i,
+                expanded: !sessions[i].forks_collapsed,
+                forks: children
+                    .iter()
+                    .map(|&c| with_forks(c, forks, sessions))
+                    .collect(),
+            },
+            None => TreeNode::Session(i),
+        }
+    }
+
+    nodes
+        .into_iter()
+        .zip(nested)
+        .filter(|(_, nested)| !nested)
+        .map(|(node, _)| match node {
+            TreeNode::Group {
+                path,
+                name,
+                expanded,
+                children,
+            } => TreeNode::Group {
+                path,
+                name,
+                expanded,
+                children: nest_forks(children, sessions),
+            },
+            TreeNode::Session(i) => with_forks(i, &forks, sessions),
+            forked => forked,
+        })
+        .collect()
+}
+
+/// Where a session sits among forks, for drawing its row.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ForkRow {
+    /// Drawn under the session it was forked from.
+    pub nested: bool,
+    /// Forks below it, at any depth.
+    pub forks: usize,
+    /// Whether its forks are shown.
+    pub expanded: bool,
+}
+
+/// [`ForkRow`]s by session id, for sessions that are nested or have forks.
+pub fn fork_rows(nodes: &[TreeNode], sessions: &[Instance]) -> HashMap<String, ForkRow> {
+    /// Records `node` and what is below it; returns how many sessions that is.
+    fn visit(
+        node: &TreeNode,
+        nested: bool,
+        sessions: &[Instance],
+        rows: &mut HashMap<String, ForkRow>,
+    ) -> usize {
+        match node {
+            TreeNode::Group { children, .. } => {
+                for child in children {
+                    visit(child, false, sessions, rows);
+                }
+                0
+            }
+            TreeNode::Session(i) => {
+                if nested {
+                    let row = ForkRow {
+                        nested: true,
+                        ..Default::default()
+                    };
+                    rows.insert(sessions[*i].id.clone(), row);
+                }
+                1
+            }
+            TreeNode::Forked {
+                session,
+                expanded,
+                forks,
+            } => {
+                let count: usize = forks.iter().map(|f| visit(f, true, sessions, rows)).sum();
+                let row = ForkRow {
+                    nested,
+                    forks: count,
+                    expanded: *expanded,
+                };
+                rows.insert(sessions[*session].id.clone(), row);
+                count + 1
+            }
+        }
+    }
+
+    let mut rows = HashMap::new();
+    for node in nodes {
+        visit(node, false, sessions, &mut rows);
+    }
+    rows
+}
+
+/// Sibling groups by their persisted `order`, then path.
+pub fn sort_groups(groups: &GroupTree, paths: &mut [String]) {
+    let order = |p: &str| groups.get_group(p).map_or(0, |g| g.order);
+    paths.sort_by(|a, b| order(a).cmp(&order(b)).then_with(|| a.cmp(b)));
+}
+
+/// Session indices in tree order, including those in collapsed groups.
+pub fn session_order(nodes: &[TreeNode]) -> Vec<usize> {
+    fn visit(node: &TreeNode, order: &mut Vec<usize>) {
+        match node {
+            TreeNode::Group { children, .. } => {
+                for child in children {
+                    visit(child, order);
+                }
+            }
+            TreeNode::Session(i) => order.push(*i),
+            TreeNode::Forked { session, forks, .. } => {
+                order.push(*session);
+                for fork in forks {
+                    visit(fork, order);
+                }
+            }
+        }
+    }
+
+    let mut order = Vec::new();
+    for node in nodes {
+        visit(node, &mut order);
+    }
+    order
+}
+
+/// Rows of the session list: depth-first, skipping the insides of
+/// collapsed groups and the forks of collapsed sessions. Forks are one
+/// level deeper than their parent.
+pub fn flatten(nodes: &[TreeNode], sessions: &[Instance]) -> Vec<TreeItem> {
+    fn session_item(session: &Instance, depth: usize) -> TreeItem {
+        match &session.relationship_id {
+            Some(rel_id) => TreeItem::Relationship {
+                id: session.id.clone(),
+                rel_id: rel_id.clone(),
+                depth,
+            },
+            None => TreeItem::Session {
+                id: session.id.clone(),
+                depth,
+            },
+        }
+    }
+
+    fn visit(node: &TreeNode, sessions: &[Instance], depth: usize, items: &mut Vec<TreeItem>) {
+        match node {
+            TreeNode::Group {
+                path,
+                name,
+                expanded,
+                children,
+            } => {
+                items.push(TreeItem::Group {
+                    path: path.clone(),
+                    name: name.clone(),
+                    depth,
+                });
+                if *expanded {
+                    for child in children {
+                        visit(child, sessions, depth + 1, items);
+                    }
+                }
+            }
+            TreeNode::Session(i) => items.push(session_item(&sessions[*i], depth)),
+            TreeNode::Forked {
+                session,
+                expanded,
+                forks,
+            } => {
+                items.push(session_item(&sessions[*session], depth));
+                if *expanded {
+                    for fork in forks {
+                        visit(fork, sessions, depth + 1, items);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut items = Vec::new();
+    for node in nodes {
+        visit(node, sessions, 0, &mut items);
+    }
+    items
+}
+
+/// Statuses of every session in a group and its subgroups.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GroupCounts {
+    pub waiting: usize,
+    pub running: usize,
+    pub idle: usize,
+}
+
+impl GroupCounts {
+    /// "2⏸ 1▶ 3○", without the zero counts; None when all are zero.
+    pub fn label(&self) -> Option<String> {
+        let parts: Vec<String> = [(self.waiting, "⏸"), (self.running, "▶"), (self.idle, "○")]
+            .into_iter()
+            .filter(|(n, _)| *n > 0)
+            .map(|(n, glyph)| format!("{n}{glyph}"))
+            .collect();
+        (!parts.is_empty()).then(|| parts.join(" "))
+    }
+}
+
+/// Counts per group path. A session counts toward its own group and every
+/// group above it.
+pub fn group_counts(sessions: &[Instance]) -> HashMap<String, GroupCounts> {
+    let mut counts: HashMap<String, GroupCounts> = HashMap::new();
+    for session in sessions.iter().filter(|s| !s.group_path.is_empty()) {
+        let path = session.group_path.as_str();
+        let ancestors = path
+            .match_indices('/')
+            .map(|(i, _)| &path[..i])
+            .chain(std::iter::once(path));
+        for group in ancestors {
+            let entry = counts.entry(group.to_string()).or_default();
+            match session.status {
+                Status::Waiting => entry.waiting += 1,
+                Status::Running => entry.running += 1,
+                Status::Idle => entry.idle += 1,
+                Status::Error | Status::Starting | Status::Stopped => {}
+            }
+        }
+    }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn session(title: &str, group: &str) -> Instance {
+        let mut inst = Instance::new(title.into(), PathBuf::from("/tmp"));
+        inst.group_path = group.into();
+        inst
+    }
+
+    fn fixture() -> (Vec<Instance>, GroupTree) {
+        let sessions = vec![
+            session("zeta", "work"),
+            session("loose", ""),
+            session("alpha", "work"),
+            session("api", "work/backend"),
+            session("notes", "home"),
+            session("orphan", "gone"),
+        ];
+        let mut groups = GroupTree::new();
+        for path in ["work", "work/backend", "home"] {
+            groups.create_group(path.into());
+        }
+        (sessions, groups)
+    }
+
+    fn outline(nodes: &[TreeNode], sessions: &[Instance]) -> Vec<String> {
+        flatten(nodes, sessions)
+            .iter()
+            .map(|item| match item {
+                TreeItem::Group { path, depth, .. } => format!("{}{path}/", "  ".repeat(*depth)),
+                TreeItem::Session { id, depth } | TreeItem::Relationship { id, depth, .. } => {
+                    let s = sessions.iter().find(|s| &s.id == id).unwrap();
+                    format!("{}{}", "  ".repeat(*depth), s.title)
+                }
+                TreeItem::UnmanagedHeader { .. } | TreeItem::Unmanaged { .. } => {
+                    unreachable!("flatten never yields unmanaged rows")
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn nests_groups_and_orders_like_the_session_list() {
+        let (sessions, groups) = fixture();
+        let tree = build(&sessions, &groups, TreeSort::Title, TreeScope::default());
+        assert_eq!(
+            outline(&tree, &sessions),
+            [
+                "loose",
+                "home/",
+                "  notes",
+                "work/",
+                "  work/backend/",
+                "    api",
+                "  alpha",
+                "  zeta",
+            ]
+        );
+    }
+
+    #[test]
+    fn sort_order_applies_within_groups() {
+        let (mut sessions, groups) = fixture();
+        sessions[0].status = Status::Waiting;
+        let tree = build(&sessions, &groups, TreeSort::Status, TreeScope { group: Some("work") });
+        assert_eq!(
+            outline(&tree, &sessions),
+            ["work/", "  work/backend/", "    api", "  zeta", "  alpha"]
+        );
+    }
+
+    #[test]
+    fn manual_order_and_group_order_win_over_names() {
+        let (mut sessions, groups) = fixture();
+        sessions[0].order = -1;
+        let mut data = groups.all_groups();
+        data.iter_mut().find(|g| g.path == "home").unwrap().order = 1;
+        let groups = GroupTree::from_groups(data);
+
+        let tree = build(&sessions, &groups, TreeSort::Manual, TreeScope::default());
+        assert_eq!(
+            outline(&tree, &sessions),
+            [
+                "loose",
+                "work/",
+                "  work/backend/",
+                "    api",
+                "  zeta",
+                "  alpha",
+                "home/",
+                "  notes",
+            ]
+        );
+        let tree = build(&sessions, &groups, TreeSort::Title, TreeScope { group: Some("work") });
+        assert_eq!(
+            outline(&tree, &sessions),
+            ["work/", "  work/backend/", "    api", "  alpha", "  zeta"]
+        );
+    }
+
+    #[test]
+    fn collapsed_groups_hide_rows_but_not_nodes() {
+        let (sessions, mut groups) = fixture();
+        groups.set_expanded("work", false);
+        let tree = build(&sessions, &groups, TreeSort::Title, TreeScope::default());
+        assert_eq!(outline(&tree, &sessions), ["loose", "home/", "  notes", "work/"]);
+
+        let TreeNode::Group { children, .. } = &tree[2] else {
+            panic!("expected the work group");
+        };
+        assert_eq!(children.len(), 3);
+        let titles: Vec<&str> = session_order(&tree)
+            .into_iter()
+            .map(|i| sessions[i].title.as_str())
+            .collect();
+        assert_eq!(titles, ["loose", "notes", "api", "alpha", "zeta"]);
+    }
+
+    #[test]
+    fn pinned_sessions_lead_the_tree_even_from_collapsed_groups() {
+        let (mut sessions, mut groups) = fixture();
+        groups.set_expanded("work", false);
+        sessions[3].pinned = true; // api, in work/backend
+        sessions[4].pinned = true; // notes, in home
+
+        let pinned = pinned(&sessions, TreeSort::Title);
+        let titles: Vec<&str> = pinned.iter().map(|&i| sessions[i].title.as_str()).collect();
+        assert_eq!(titles, ["api", "notes"]);
+
+        let tree = build(&sessions, &groups, TreeSort::Title, TreeScope::default());
+        let tree = pin_to_top(tree, &pinned);
+        assert_eq!(outline(&tree, &sessions), ["api", "notes", "loose", "home/", "work/"]);
+    }
+
+    #[test]
+    fn forks_nest_under_their_parent_in_the_same_group() {
+        let (mut sessions, groups) = fixture();
+        let fork = |title: &str, group: &str, parent: &str| {
+            let mut inst = session(title, group);
+            inst.parent_session_id = Some(parent.into());
+            inst
+        };
+        let alpha = sessions[2].id.clone();
+        let zeta = sessions[0].id.clone();
+        sessions.push(fork("beta", "work", &alpha));
+        let beta = sessions[6].id.clone();
+        sessions.push(fork("beta2", "work", &beta));
+        sessions.push(fork("elsewhere", "home", &alpha));
+        sessions.push(fork("gone", "work", "no-such-id"));
+        // zeta and yank fork each other: both stay flat.
+        sessions.push(fork("yank", "work", &zeta));
+        let yank = sessions[10].id.clone();
+        sessions[0].parent_session_id = Some(yank);
+
+        let tree = nest_forks(
+            build(&sessions, &groups, TreeSort::Title, TreeScope::default()),
+            &sessions,
+        );
+        assert_eq!(
+            outline(&tree, &sessions),
+            [
+                "loose",
+                "home/",
+                "  elsewhere",
+                "  notes",
+                "work/",
+                "  work/backend/",
+                "    api",
+                "  alpha",
+                "    beta",
+                "      beta2",
+                "  gone",
+                "  yank",
+                "  zeta",
+            ]
+        );
+        let rows = fork_rows(&tree, &sessions);
+        assert_eq!(
+            rows[&alpha],
+            ForkRow {
+                nested: false,
+                forks: 2,
+                expanded: true
+            }
+        );
+        assert!(rows[&beta].nested);
+        assert!(!rows.contains_key(&zeta));
+
+        sessions[2].forks_collapsed = true;
+        let tree = nest_forks(
+            build(&sessions, &groups, TreeSort::Title, TreeScope { group: Some("work") }),
+            &sessions,
+        );
+        assert_eq!(
+            outline(&tree, &sessions),
+            ["work/", "  work/backend/", "    api", "  alpha", "  gone", "  yank", "  zeta"]
+        );
+        assert!(!fork_rows(&tree, &sessions)[&alpha].expanded);
+        // Hidden forks keep their place in the session order.
+        assert_eq!(session_order(&tree).len(), 7);
+    }
+
+    #[test]
+    fn group_counts_roll_up_to_ancestors() {
+        let (mut sessions, _) = fixture();
+        sessions[0].status = Status::Waiting;
+        sessions[3].status = Status::Running;
+        let counts = group_counts(&sessions);
+        let work = counts["work"];
+        assert_eq!((work.waiting, work.running, work.idle), (1, 1, 1));
+        assert_eq!(work.label().as_deref(), Some("1⏸ 1▶ 1○"));
+        assert_eq!(counts["work/backend"].label().as_deref(), Some("1▶"));
+        assert_eq!(counts["home"].label().as_deref(), Some("1○"));
+        assert!(!counts.contains_key(""));
+        assert_eq!(GroupCounts::default().label(), None);
+    }
+
+    #[test]
+    fn unknown_scope_group_is_empty() {
+        let (sessions, groups) = fixture();
+        let scope = TreeScope { group: Some("nope") };
+        assert!(build(&sessions, &groups, TreeSort::Title, scope).is_empty());
+    }
+}
@@ -0,0 +1,166 @@
+//! Viewport windowing for long lists.
+//!
+//! Callers keep the full list and an absolute selected index; this maps
+//! them to the rows to draw. Keeping the math in one place means the search
+//! popup, the switcher and the tree agree on edge cases.
+
+use std::ops::Range;
+
+/// The visible part of a list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListWindow {
+    /// First visible index (also the offset to remember for the next call).
+    pub start: usize,
+    /// One past the last visible index.
+    pub end: usize,
+    /// Selected row relative to `start`, if the list is non-empty.
+    pub selected: Option<usize>,
+}
+
+impl ListWindow {
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Whether some items fall outside the window.
+    pub fn is_truncated(&self, len: usize) -> bool {
+        self.start > 0 || self.end < len
+    }
+}
+
+/// Window of at most `viewport` rows over `len` items that contains
+/// `selected`, scrolling as little as possible from the previous `offset`.
+///
+/// `selected` is clamped to the list; an empty list or zero viewport yields
+/// an empty window with no selection.
+pub fn list_window(len: usize, selected: usize, viewport: usize, offset: usize) -> ListWindow {
+    if len == 0 || viewport == 0 {
+        return ListWindow {
+            start: 0,
+            end: 0,
+            selected: None,
+        };
+    }
+
+    let selected = selected.min(len - 1);
+    let mut start = offset.min(len.saturating_sub(viewport));
+    if selected < start {
+        start = selected;
+    } else if selected >= start + viewport {
+        start = selected + 1 - viewport;
+    }
+
+    ListWindow {
+        start,
+        end: (start + viewport).min(len),
+        selected: Some(selected - start),
+    }
+}
+
+/// "showing 51–100 of 137 matches — refine your query"
+pub fn truncation_notice(window: ListWindow, len: usize, is_zh: bool) -> String {
+    let first = if window.end > window.start { window.start + 1 } else { 0 };
+    if is_zh {
+        format!("显示第 {}–{} 条，共 {} 条匹配 — 请细化查询", first, window.end, len)
+    } else {
+        format!(
+            "showing {}–{} of {} matches — refine your query",
+            first, window.end, len
+        )
+    }
+}
+
+/// Selected index after PageDown: one page further, stopping at the end.
+pub fn page_down(selected: usize, len: usize, page: usize) -> usize {
+    selected.saturating_add(page.max(1)).min(len.saturating_sub(1))
+}
+
+/// Selected index after PageUp: one page back, stopping at the top.
+pub fn page_up(selected: usize, page: usize) -> usize {
+    selected.saturating_sub(page.max(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_inputs_have_no_selection() {
+        assert_eq!(list_window(0, 3, 10, 0).selected, None);
+        assert_eq!(list_window(5, 0, 0, 0).range(), 0..0);
+    }
+
+    #[test]
+    fn short_list_fits_entirely() {
+        let w = list_window(3, 2, 10, 7);
+        assert_eq!(w.range(), 0..3);
+        assert_eq!(w.selected, Some(2));
+        assert!(!w.is_truncated(3));
+    }
+
+    #[test]
+    fn scrolls_minimally_to_follow_selection() {
+        // Moving down past the bottom edge shifts by one row.
+        let w = list_window(100, 10, 10, 0);
+        assert_eq!(w.range(), 1..11);
+        assert_eq!(w.selected, Some(9));
+
+        // Moving within the window keeps the offset.
+        let w = list_window(100, 5, 10, 1);
+        assert_eq!(w.range(), 1..11);
+        assert_eq!(w.selected, Some(4));
+
+        // Moving above the top edge pins the selection to the first row.
+        let w = list_window(100, 0, 10, 1);
+        assert_eq!(w.range(), 0..10);
+        assert_eq!(w.selected, Some(0));
+    }
+
+    #[test]
+    fn last_row_is_reachable_and_window_stays_full() {
+        let w = list_window(137, 136, 50, 0);
+        assert_eq!(w.range(), 87..137);
+        assert_eq!(w.selected, Some(49));
+        assert!(w.is_truncated(137));
+
+        // A stale offset past the end is pulled back so the window stays full.
+        let w = list_window(60, 59, 50, 55);
+        assert_eq!(w.range(), 10..60);
+    }
+
+    #[test]
+    fn selection_beyond_list_is_clamped() {
+        let w = list_window(5, 99, 3, 0);
+        assert_eq!(w.range(), 2..5);
+        assert_eq!(w.selected, Some(2));
+    }
+
+    #[test]
+    fn absolute_index_round_trips() {
+        for offset in [0, 13, 40] {
+            for sel in 0..137 {
+                let w = list_window(137, sel, 50, offset);
+                assert_eq!(w.start + w.selected.unwrap(), sel);
+                assert!(w.end - w.start <= 50);
+            }
+        }
+    }
+
+    #[test]
+    fn notice_uses_one_based_range() {
+        let w = list_window(137, 0, 50, 0);
+        assert_eq!(
+            truncation_notice(w, 137, false),
+            "showing 1–50 of 137 matches — refine your query"
+        );
+    }
+
+    #[test]
+    fn paging_stops_at_edges() {
+        assert_eq!(page_down(0, 137, 50), 50);
+        assert_eq!(page_down(120, 137, 50), 136);
+        assert_eq!(page_down(0, 0, 50), 0);
+        assert_eq!(page_up(60, 50), 10);
+        assert_eq!(page_up(10, 50), 0);
+    }
+}
@@ -1,8 +1,8 @@
-use std::time::Duration;
+use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 
-use crate::error::Result;
+use crate::network::{FetchError, HttpFetch, ReqwestFetch};
 use crate::session::Storage;
 
 const REPO_API_LATEST: &str = "https://api.github.com/repos/weykon/agent-hand/releases/latest";
@@ -44,87 +44,106 @@ fn has_newer_version(current: &str, latest: &str) -> bool {
     lat > cur
 }
 
-async fn cache_path() -> Result<std::path::PathBuf> {
-    let dir = Storage::get_agent_hand_dir()?.join("cache");
-    tokio::fs::create_dir_all(&dir).await?;
-    Ok(dir.join("update.json"))
+/// Where the update check caches its result: `~/.agent-hand/cache`.
+fn cache_dir() -> Option<std::path::PathBuf> {
+    Storage::get_agent_hand_dir().ok().map(|dir| dir.join("cache"))
 }
 
-async fn load_cache() -> Option<UpdateCache> {
-    let path = cache_path().await.ok()?;
-    let content = tokio::fs::read_to_string(&path).await.ok()?;
+async fn load_cache(dir: &Path) -> Option<UpdateCache> {
+    let content = tokio::fs::read_to_string(dir.join("update.json")).await.ok()?;
     serde_json::from_str(&content).ok()
 }
 
-async fn save_cache(cache: &UpdateCache) {
-    let path = match cache_path().await {
-        Ok(p) => p,
-        Err(_) => return,
-    };
+async fn save_cache(dir: &Path, cache: &UpdateCache) {
     let Ok(json) = serde_json::to_string(cache) else {
         return;
     };
-    let _ = tokio::fs::write(path, json).await;
+    if tokio::fs::create_dir_all(dir).await.is_err() {
+        return;
+    }
+    // Several statusline runs may save at once; each renames its own file.
+    let tmp = dir.join(format!("update.json.{}.tmp", std::process::id()));
+    if tokio::fs::write(&tmp, json).await.is_ok()
+        && tokio::fs::rename(&tmp, dir.join("update.json")).await.is_err()
+    {
+        let _ = tokio::fs::remove_file(&tmp).await;
+    }
 }
 
-async fn fetch_latest_tag() -> Result<String> {
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(2))
-        .build()
-        .map_err(|e| crate::Error::Other(e.to_string()))?;
-
-    let resp = client
-        .get(REPO_API_LATEST)
-        .header("User-Agent", "agent-hand")
-        .header("Accept", "application/vnd.github+json")
-        .send()
-        .await
-        .map_err(|e| crate::Error::Other(e.to_string()))?;
-
-    let release: LatestRelease = resp
-        .json()
-        .await
-        .map_err(|e| crate::Error::Other(e.to_string()))?;
+async fn fetch_latest_tag(http: &dyn HttpFetch) -> std::result::Result<String, FetchError> {
+    let value = http
+        .get_json(
+            REPO_API_LATEST,
+            &[
+                ("User-Agent", "agent-hand"),
+                ("Accept", "application/vnd.github+json"),
+            ],
+        )
+        .await?;
+    let release: LatestRelease =
+        serde_json::from_value(value).map_err(|e| FetchError::Failed(e.to_string()))?;
     Ok(release.tag_name)
 }
 
+/// The version in a cached tag when it is newer than the running binary —
+/// the cache may have been written by an older binary before an upgrade.
+fn newer_version(tag: Option<&str>) -> Option<String> {
+    let tag = tag?;
+    has_newer_version(crate::VERSION, tag).then(|| tag.trim_start_matches('v').to_string())
+}
+
 /// Returns a short statusline suffix when an update is available, e.g. "↑0.2.9 upgrade".
 ///
 /// To avoid hammering the network (statusline runs every few seconds), we cache results for 24h.
 pub async fn statusline_update_hint() -> Option<String> {
+    statusline_update_hint_with(&ReqwestFetch::default(), &cache_dir()?).await
+}
+
+/// [`statusline_update_hint`] over a given transport, caching in
+/// `cache_dir`. Offline, only the cache is consulted, however old it is.
+pub async fn statusline_update_hint_with(
+    http: &dyn HttpFetch,
+    cache_dir: &Path,
+) -> Option<String> {
+    available_update_with(http, cache_dir)
+        .await
+        .map(|version| format!("↑{version} upgrade"))
+}
+
+/// The newer release's version (e.g. "0.3.0"), from the same 24h cache as
+/// [`statusline_update_hint`].
+pub async fn available_update() -> Option<String> {
+    available_update_with(&ReqwestFetch::default(), &cache_dir()?).await
+}
+
+pub async fn available_update_with(http: &dyn HttpFetch, cache_dir: &Path) -> Option<String> {
     let now = chrono::Utc::now().timestamp();
+    let cached = load_cache(cache_dir).await;
 
-    if let Some(cache) = load_cache().await {
-        if now.saturating_sub(cache.last_checked_at) < CACHE_TTL_SECS {
-            // Re-validate against the current binary version — the cache may have
-            // been written by an older binary before an upgrade.
-            if let Some(ref tag) = cache.latest_tag {
-                if has_newer_version(crate::VERSION, tag) {
-                    return Some(format!("↑{} upgrade", tag.trim_start_matches('v')));
-                }
-            }
-            return None;
+    if let Some(ref cache) = cached {
+        let fresh = now.saturating_sub(cache.last_checked_at) < CACHE_TTL_SECS;
+        if fresh || crate::network::is_offline() {
+            return newer_version(cache.latest_tag.as_deref());
         }
+    } else if crate::network::is_offline() {
+        return None;
     }
 
-    let latest_tag = fetch_latest_tag().await.ok();
-    let has_update = latest_tag
-        .as_deref()
-        .is_some_and(|t| has_newer_version(crate::VERSION, t));
+    // A failed check keeps the last known tag and waits out the TTL again.
+    let latest_tag = match fetch_latest_tag(http).await {
+        Ok(tag) => Some(tag),
+        Err(_) => cached.and_then(|c| c.latest_tag),
+    };
+    let version = newer_version(latest_tag.as_deref());
 
     let cache = UpdateCache {
         last_checked_at: now,
-        latest_tag: latest_tag.clone(),
-        has_update,
+        latest_tag,
+        has_update: version.is_some(),
     };
-    save_cache(&cache).await;
+    save_cache(cache_dir, &cache).await;
 
-    if has_update {
-        let tag = latest_tag?;
-        return Some(format!("↑{} upgrade", tag.trim_start_matches('v')));
-    }
-
-    None
+    version
 }
 
 /// Returns a statusline hint when the user's plan exceeds their binary tier.
@@ -148,9 +167,16 @@ pub fn tier_upgrade_hint() -> Option<String> {
 /// Returns a TUI info bar message when a version update or tier mismatch is detected.
 /// Priority: version update (yellow) > tier mismatch (green).
 pub async fn tui_update_hint() -> Option<(String, ratatui::style::Color)> {
+    tui_update_hint_with(&ReqwestFetch::default(), &cache_dir()?).await
+}
+
+pub async fn tui_update_hint_with(
+    http: &dyn HttpFetch,
+    cache_dir: &Path,
+) -> Option<(String, ratatui::style::Color)> {
     use ratatui::style::Color;
     // Priority 1: version update available
-    if let Some(hint) = statusline_update_hint().await {
+    if let Some(hint) = statusline_update_hint_with(http, cache_dir).await {
         let tag = hint.trim_start_matches('↑').trim_end_matches(" upgrade");
         return Some((
             format!(" ↑ {} available — run: agent-hand upgrade ", tag),
@@ -0,0 +1,95 @@
+//! `agent-hand add --existing` when the path already has a session, and
+//! `add --window`. None of these start anything, so no tmux is needed.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn add(home: &Path, project: &Path, extra: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_agent-hand"))
+        .arg("add")
+        .arg(project)
+        .args(extra)
+        .env("HOME", home)
+        .env("LC_ALL", "C")
+        .env_remove("AGENTHAND_PROFILE")
+        .output()
+        .unwrap()
+}
+
+fn session_count(home: &Path) -> usize {
+    let output = Command::new(env!("CARGO_BIN_EXE_agent-hand"))
+        .args(["list", "--json"])
+        .env("HOME", home)
+        .env_remove("AGENTHAND_PROFILE")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let sessions: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    sessions.as_array().map_or(0, Vec::len)
+}
+
+#[test]
+fn existing_session_is_reused_refused_or_duplicated() {
+    let home = tempfile::tempdir().unwrap();
+    let project = home.path().join("api");
+    std::fs::create_dir_all(&project).unwrap();
+
+    let first = add(home.path(), &project, &[]);
+    assert!(
+        first.status.success(),
+        "{}",
+        String::from_utf8_lossy(&first.stderr)
+    );
+    assert_eq!(session_count(home.path()), 1);
+
+    let reused = add(home.path(), &project, &[]);
+    assert!(reused.status.success());
+    assert!(String::from_utf8_lossy(&reused.stdout).contains("already exists"));
+    assert_eq!(session_count(home.path()), 1);
+
+    let refused = add(home.path(), &project, &["--existing", "error"]);
+    assert!(!refused.status.success());
+    assert!(String::from_utf8_lossy(&refused.stderr).contains("already exists"));
+    assert_eq!(session_count(home.path()), 1);
+
+    let duplicated = add(home.path(), &project, &["--existing", "new"]);
+    assert!(duplicated.status.success());
+    assert_eq!(session_count(home.path()), 2);
+}
+
+#[test]
+fn windows_are_saved_with_the_agent_tool() {
+    let home = tempfile::tempdir().unwrap();
+    let project = home.path().join("web");
+    std::fs::create_dir_all(&project).unwrap();
+
+    let bad = add(home.path(), &project, &["--window", "dev"]);
+    assert!(!bad.status.success());
+
+    let added = add(
+        home.path(),
+        &project,
+        &["--window", "dev:npm run dev", "--window", "agent:claude"],
+    );
+    assert!(
+        added.status.success(),
+        "{}",
+        String::from_utf8_lossy(&added.stderr)
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_agent-hand"))
+        .args(["list", "--json"])
+        .env("HOME", home.path())
+        .env_remove("AGENTHAND_PROFILE")
+        .output()
+        .unwrap();
+    let sessions: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let session = &sessions[0];
+    assert_eq!(session["tool"], "claude");
+    assert_eq!(session["windows"][0]["name"], "dev");
+    assert_eq!(session["windows"][1]["command"], "claude");
+}
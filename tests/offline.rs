@@ -0,0 +1,171 @@
+//! Startup and update checks with the network unavailable.
+//!
+//! The offline switch is process-global, so the update checks run as one
+//! test whose steps depend on the order it flips in, and the headless
+//! `App::new` check runs in a child copy of this binary with its own
+//! `$HOME` and tmux server (as in `startup.rs`).
+
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+
+use agent_hand::network::{self, FetchError, HttpFetch, ReqwestFetch};
+use agent_hand::session::{GroupTree, Instance, Storage};
+
+/// A transport that never answers.
+struct Hang;
+
+#[async_trait]
+impl HttpFetch for Hang {
+    async fn get_json(
+        &self,
+        _url: &str,
+        _headers: &[(&str, &str)],
+    ) -> Result<serde_json::Value, FetchError> {
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        Err(FetchError::Unreachable("hung".into()))
+    }
+}
+
+/// TEST-NET-1 address: nothing routes there.
+const UNROUTABLE: &str = "http://192.0.2.1/releases/latest";
+
+#[tokio::test]
+async fn nothing_blocks_on_the_network_when_offline() {
+    let home = tempfile::tempdir().unwrap();
+    let cache_dir = home.path().join("cache");
+
+    // A request that cannot connect holds off further requests for a while.
+    assert!(!network::is_offline());
+    let fetch = ReqwestFetch {
+        timeout: Duration::from_millis(300),
+    };
+    let started = Instant::now();
+    let err = fetch.get_json(UNROUTABLE, &[]).await.unwrap_err();
+    assert!(matches!(err, FetchError::Unreachable(_)), "{err:?}");
+    assert!(started.elapsed() < Duration::from_secs(2));
+    assert!(network::is_offline());
+
+    // Meanwhile nothing is sent: a hanging transport must not be awaited.
+    let started = Instant::now();
+    assert_eq!(fetch.get_json(UNROUTABLE, &[]).await, Err(FetchError::Offline));
+    assert!(agent_hand::update::statusline_update_hint_with(&Hang, &cache_dir)
+        .await
+        .is_none());
+    assert!(agent_hand::update::available_update_with(&Hang, &cache_dir)
+        .await
+        .is_none());
+    assert!(started.elapsed() < Duration::from_millis(500));
+
+    // User-initiated network actions still try; with offline mode on they
+    // fail fast with a readable error.
+    assert!(network::ensure_online().is_ok());
+    network::set_offline(true);
+    let msg = network::ensure_online().unwrap_err().to_string();
+    assert!(msg.contains("offline"), "{msg}");
+
+    // The CLI starts and reports normally with `offline = true` configured.
+    let config_dir = home.path().join(".agent-hand");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(config_dir.join("config.toml"), "offline = true\n").unwrap();
+
+    let started = Instant::now();
+    let output = Command::new(env!("CARGO_BIN_EXE_agent-hand"))
+        .args(["status", "--json"])
+        .env("HOME", home.path())
+        .output()
+        .unwrap();
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(r#""total": 0"#), "{stdout}");
+    assert!(!String::from_utf8_lossy(&output.stderr).to_lowercase().contains("error"));
+}
+
+const PROFILE: &str = "offline-test";
+
+/// Names the child's tmux server; set only in the child.
+const SERVER_ENV: &str = "AGENT_HAND_OFFLINE_TEST_SERVER";
+
+/// Kills the child's tmux server, however the test ends.
+struct KillServer(String);
+
+impl Drop for KillServer {
+    fn drop(&mut self) {
+        let _ = Command::new("tmux")
+            .args(["-L", &self.0, "kill-server"])
+            .output();
+    }
+}
+
+#[test]
+fn app_new_starts_headless_when_offline() {
+    let home = tempfile::tempdir().unwrap();
+    let config_dir = home.path().join(".agent-hand");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(config_dir.join("config.toml"), "offline = true\n").unwrap();
+
+    let server = KillServer(format!("ah_offline_test_{}", std::process::id()));
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "probe_app_new_offline", "--ignored", "--nocapture"])
+        .env("HOME", home.path())
+        .env(SERVER_ENV, &server.0)
+        // Anything that still reached for the network would hang here.
+        .env("HTTPS_PROXY", "http://192.0.2.1:9")
+        .env("HTTP_PROXY", "http://192.0.2.1:9")
+        .env_remove("AGENTHAND_PROFILE")
+        .env_remove("TMUX")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// The check itself; run by [`app_new_starts_headless_when_offline`].
+#[tokio::test]
+#[ignore = "run by app_new_starts_headless_when_offline with its own HOME"]
+async fn probe_app_new_offline() {
+    use agent_hand::ui::{App, AppState, TreeItem};
+
+    let server = std::env::var(SERVER_ENV).expect("run by app_new_starts_headless_when_offline");
+    assert!(agent_hand::tmux::set_server_template(&server));
+    let home = std::env::var_os("HOME").map(std::path::PathBuf::from).unwrap();
+    let project = home.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+
+    let instances: Vec<Instance> = ["api", "web"]
+        .into_iter()
+        .map(|title| Instance::new(title.to_string(), project.clone()))
+        .collect();
+    let storage = Storage::new(PROFILE).await.unwrap();
+    storage
+        .save(&instances, &GroupTree::new(), &[])
+        .await
+        .unwrap();
+    drop(storage);
+
+    let started = Instant::now();
+    let app = App::new(PROFILE).await.expect("App::new");
+    assert!(started.elapsed() < Duration::from_secs(5), "{:?}", started.elapsed());
+
+    assert!(network::is_offline(), "config `offline = true` was not applied");
+    assert!(matches!(app.state(), AppState::Startup | AppState::Normal));
+    let mut titles: Vec<&str> = app.sessions().iter().map(|s| s.title.as_str()).collect();
+    titles.sort();
+    assert_eq!(titles, ["api", "web"]);
+    let rows = app
+        .tree()
+        .iter()
+        .filter(|item| matches!(item, TreeItem::Session { .. }))
+        .count();
+    assert_eq!(rows, 2, "{:?}", app.tree());
+    assert!(app.selected_item().is_some());
+    if let Some((msg, color)) = app.info_bar_message() {
+        assert_ne!(*color, ratatui::style::Color::Red, "error at startup: {msg}");
+    }
+}
@@ -0,0 +1,64 @@
+//! Snapshot round-trip: create → mutate → restore.
+//!
+//! Verifies the restored files are byte-identical to the snapshotted ones and
+//! that the replaced state was kept as an automatic safety snapshot.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use agent_hand::session::snapshot::{create_snapshot, list_snapshots, restore_snapshot};
+use agent_hand::session::{Instance, StorageData};
+
+fn write_sessions(profile_dir: &Path, titles: &[&str]) {
+    let data = StorageData {
+        instances: titles
+            .iter()
+            .map(|t| Instance::new(t.to_string(), PathBuf::from("/tmp")))
+            .collect(),
+        groups: Vec::new(),
+        relationships: Vec::new(),
+        updated_at: chrono::Utc::now(),
+        revision: 0,
+    };
+    fs::write(
+        profile_dir.join("sessions.json"),
+        serde_json::to_string_pretty(&data).unwrap(),
+    )
+    .unwrap();
+}
+
+#[test]
+fn restore_round_trip_is_byte_exact() {
+    let dir = tempfile::tempdir().unwrap();
+    let profile_dir = dir.path();
+    let sessions = profile_dir.join("sessions.json");
+
+    write_sessions(profile_dir, &["api", "web"]);
+    let original = fs::read(&sessions).unwrap();
+    let snap = create_snapshot(profile_dir, Some("before regroup"), false).unwrap();
+
+    write_sessions(profile_dir, &["api", "web", "docs"]);
+    let mutated = fs::read(&sessions).unwrap();
+    assert_ne!(original, mutated);
+
+    let report = restore_snapshot(profile_dir, &snap.id, false).unwrap();
+    assert_eq!(fs::read(&sessions).unwrap(), original);
+    assert_eq!(report.diff.added.len(), 2);
+    assert_eq!(report.diff.removed.len(), 3);
+
+    let safety = report.safety.expect("restore should take a safety snapshot");
+    assert!(safety.automatic);
+    let safety_copy = profile_dir
+        .join("snapshots")
+        .join(&safety.id)
+        .join("sessions.json");
+    assert_eq!(fs::read(safety_copy).unwrap(), mutated);
+
+    let ids: Vec<String> = list_snapshots(profile_dir)
+        .unwrap()
+        .into_iter()
+        .map(|s| s.id)
+        .collect();
+    assert!(ids.contains(&snap.id));
+    assert!(ids.contains(&safety.id));
+}
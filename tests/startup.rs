@@ -0,0 +1,85 @@
+//! `App::new` must not capture panes: with many sessions, one probe each
+//! kept the terminal blank for over a second before the first frame.
+//!
+//! Sessions are started in tmux when it is installed, so there are panes
+//! that an eager status probe would capture.
+//!
+//! `App::new` finds its files through `$HOME`, so the check runs in a child
+//! copy of this test binary given its own home and tmux server, rather than
+//! changing the environment of the whole test process.
+
+use std::process::Command;
+
+use agent_hand::session::{GroupTree, Instance, Storage};
+use agent_hand::tmux::calls::CallCounts;
+use agent_hand::tmux::TmuxManager;
+
+const PROFILE: &str = "startup-test";
+
+/// Names the child's tmux server; set only in the child.
+const SERVER_ENV: &str = "AGENT_HAND_STARTUP_TEST_SERVER";
+
+/// Kills the child's tmux server, however the test ends.
+struct KillServer(String);
+
+impl Drop for KillServer {
+    fn drop(&mut self) {
+        let _ = Command::new("tmux")
+            .args(["-L", &self.0, "kill-server"])
+            .output();
+    }
+}
+
+#[test]
+fn app_new_captures_no_panes() {
+    let home = tempfile::tempdir().unwrap();
+    let server = KillServer(format!("ah_startup_test_{}", std::process::id()));
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "probe_app_new", "--ignored", "--nocapture"])
+        .env("HOME", home.path())
+        .env(SERVER_ENV, &server.0)
+        .env_remove("AGENTHAND_PROFILE")
+        .env_remove("TMUX")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// The check itself; run by [`app_new_captures_no_panes`].
+#[tokio::test]
+#[ignore = "run by app_new_captures_no_panes with its own HOME"]
+async fn probe_app_new() {
+    let server = std::env::var(SERVER_ENV).expect("run by app_new_captures_no_panes");
+    assert!(agent_hand::tmux::set_server_template(&server));
+    let home = std::env::var_os("HOME").map(std::path::PathBuf::from).unwrap();
+    let project = home.join("project");
+    std::fs::create_dir_all(&project).unwrap();
+
+    let instances: Vec<Instance> = (0..12)
+        .map(|i| Instance::new(format!("s{i}"), project.clone()))
+        .collect();
+    let storage = Storage::new(PROFILE).await.unwrap();
+    storage
+        .save(&instances, &GroupTree::new(), &[])
+        .await
+        .unwrap();
+    drop(storage);
+
+    let tmux = TmuxManager::new(PROFILE);
+    let dir = project.to_string_lossy();
+    for inst in &instances {
+        let _ = tmux.create_session(&inst.tmux_name(), &dir, None, None).await;
+    }
+
+    let before = CallCounts::snapshot();
+    let app = agent_hand::ui::App::new(PROFILE).await.map(drop);
+    let calls = CallCounts::snapshot().since(&before);
+
+    app.expect("App::new");
+    assert_eq!(calls.capture, 0, "App::new captured panes: {calls:?}");
+}